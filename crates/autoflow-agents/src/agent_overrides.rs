@@ -0,0 +1,56 @@
+/// Per-project agent prompt overlays
+///
+/// `.autoflow/agent-overrides/<agent>.md` holds project rules for one agent.
+/// Its content is appended to the agent's base system prompt when the agent is
+/// loaded, so a project can tighten a standard agent without copying and then
+/// maintaining the whole `.agent.md`.
+use std::path::{Path, PathBuf};
+
+/// Overlay directory, relative to the project root
+pub const AGENT_OVERRIDES_DIR: &str = ".autoflow/agent-overrides";
+
+/// Path of an agent's overlay in the project at `project_root`
+pub fn agent_override_path(project_root: &Path, agent_name: &str) -> PathBuf {
+    project_root.join(AGENT_OVERRIDES_DIR).join(format!("{}.md", agent_name))
+}
+
+/// The agent's overlay, if the project has a non-empty one
+pub fn load_agent_override(project_root: &Path, agent_name: &str) -> Option<String> {
+    let content = std::fs::read_to_string(agent_override_path(project_root, agent_name)).ok()?;
+    let content = content.trim();
+    (!content.is_empty()).then(|| content.to_string())
+}
+
+/// Base prompt with the overlay appended under its own heading
+pub fn compose_agent_prompt(base: &str, overlay: Option<&str>) -> String {
+    match overlay {
+        Some(overlay) => format!("{}\n\n# Project-Specific Rules\n\n{}", base.trim_end(), overlay),
+        None => base.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agent_override() {
+        let dir = std::env::temp_dir().join(format!("autoflow-agent-overrides-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join(AGENT_OVERRIDES_DIR)).unwrap();
+        std::fs::write(agent_override_path(&dir, "reviewer"), "\nNever approve raw SQL.\n").unwrap();
+        std::fs::write(agent_override_path(&dir, "unit-fixer"), "  \n").unwrap();
+
+        let overlay = load_agent_override(&dir, "reviewer");
+        assert_eq!(overlay.as_deref(), Some("Never approve raw SQL."));
+        assert_eq!(load_agent_override(&dir, "unit-fixer"), None);
+        assert_eq!(load_agent_override(&dir, "code-implementer"), None);
+
+        assert_eq!(
+            compose_agent_prompt("You review code.\n", overlay.as_deref()),
+            "You review code.\n\n# Project-Specific Rules\n\nNever approve raw SQL."
+        );
+        assert_eq!(compose_agent_prompt("You review code.", None), "You review code.");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
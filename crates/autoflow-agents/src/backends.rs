@@ -0,0 +1,504 @@
+/// Chat API backends: agents run on another vendor's model
+///
+/// Agents mapped to a provider in `[backends]` skip the claude CLI. Their
+/// conversation runs here as a tool loop: each model turn's tool calls are
+/// carried out by `local_tools` and the results sent back, until the model
+/// answers without calling a tool. OpenAI-compatible chat completions (which
+/// covers local Ollama and llama.cpp servers) and Gemini are supported.
+///
+/// Providers that can't call tools only take one-shot text tasks through
+/// [`complete_text`].
+use crate::cost::TokenUsage;
+use crate::local_tools::{ToolSet, ToolSpec};
+use crate::http::post_json;
+use anyhow::{bail, Context, Result};
+use autoflow_data::{ProviderConfig, ProviderKind, SamplingParams};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::time::Duration;
+
+/// Model turns before an agent is stopped; the claude CLI has no turn limit either, so this only catches loops
+const MAX_TOOL_TURNS: u32 = 150;
+
+/// Seconds one model turn may take
+const REQUEST_TIMEOUT_SECS: u64 = 600;
+
+/// Where a local provider is served when it sets no `url`: Ollama's default port
+const LOCAL_URL: &str = "http://localhost:11434/v1";
+
+/// One message of a provider-neutral conversation
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChatMessage {
+    User(String),
+    Assistant { text: String, tool_calls: Vec<ToolCall> },
+    ToolResult { call_id: String, name: String, content: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub input: Value,
+}
+
+/// What the model answered in one turn
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelTurn {
+    pub text: String,
+    pub tool_calls: Vec<ToolCall>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// A chat API that can take one turn of a tool-using conversation
+pub trait ChatBackend: Send + Sync {
+    /// Provider name for logs
+    fn name(&self) -> &'static str;
+
+    fn complete(&self, system: &str, messages: &[ChatMessage], tools: &[ToolSpec]) -> Result<ModelTurn>;
+}
+
+/// Backend for a configured provider, sampling as `sampling` says
+pub fn chat_backend(provider: &ProviderConfig, sampling: &SamplingParams) -> Box<dyn ChatBackend> {
+    let (config, sampling) = (provider.clone(), sampling.clone());
+    match provider.kind {
+        ProviderKind::Openai | ProviderKind::Local => Box::new(OpenAiBackend { config, sampling }),
+        ProviderKind::Gemini => Box::new(GeminiBackend { config, sampling }),
+    }
+}
+
+/// `/chat/completions`, as served by OpenAI and compatible servers, local ones included
+pub struct OpenAiBackend {
+    pub config: ProviderConfig,
+    pub sampling: SamplingParams,
+}
+
+impl OpenAiBackend {
+    pub fn request_body(&self, system: &str, messages: &[ChatMessage], tools: &[ToolSpec]) -> Value {
+        let mut wire = vec![json!({"role": "system", "content": system})];
+        for message in messages {
+            wire.push(match message {
+                ChatMessage::User(text) => json!({"role": "user", "content": text}),
+                ChatMessage::Assistant { text, tool_calls } if tool_calls.is_empty() => json!({"role": "assistant", "content": text}),
+                ChatMessage::Assistant { text, tool_calls } => json!({
+                    "role": "assistant",
+                    "content": if text.is_empty() { Value::Null } else { json!(text) },
+                    "tool_calls": tool_calls.iter().map(|call| json!({
+                        "id": call.id,
+                        "type": "function",
+                        "function": {"name": call.name, "arguments": call.input.to_string()},
+                    })).collect::<Vec<_>>(),
+                }),
+                ChatMessage::ToolResult { call_id, content, .. } => json!({"role": "tool", "tool_call_id": call_id, "content": content}),
+            });
+        }
+        let mut body = json!({"model": self.config.model, "messages": wire, "max_tokens": self.config.max_tokens});
+        // top_k isn't part of the OpenAI API; local servers that take it read it from here too
+        for (key, value) in [
+            ("temperature", self.sampling.temperature.map(|v| json!(v))),
+            ("top_p", self.sampling.top_p.map(|v| json!(v))),
+            ("top_k", self.sampling.top_k.filter(|_| self.config.kind == ProviderKind::Local).map(|v| json!(v))),
+            ("seed", self.sampling.seed.map(|v| json!(v))),
+        ] {
+            if let Some(value) = value {
+                body[key] = value;
+            }
+        }
+        if !tools.is_empty() {
+            body["tools"] = tools
+                .iter()
+                .map(|tool| json!({"type": "function", "function": {"name": tool.name, "description": tool.description, "parameters": tool.parameters}}))
+                .collect();
+        }
+        body
+    }
+
+    pub fn parse_response(response: &Value) -> Result<ModelTurn> {
+        let message = response.pointer("/choices/0/message").context("Response has no choices")?;
+        let tool_calls = message
+            .get("tool_calls")
+            .and_then(|c| c.as_array())
+            .map(|calls| {
+                calls
+                    .iter()
+                    .map(|call| ToolCall {
+                        id: call.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                        name: call.pointer("/function/name").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                        // Arguments arrive as a JSON string; a malformed one is passed on as is for the tool to reject
+                        input: call
+                            .pointer("/function/arguments")
+                            .and_then(|v| v.as_str())
+                            .map(|args| serde_json::from_str(args).unwrap_or_else(|_| json!({ "raw": args })))
+                            .unwrap_or(Value::Null),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(ModelTurn {
+            text: message.get("content").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+            tool_calls,
+            input_tokens: response.pointer("/usage/prompt_tokens").and_then(|v| v.as_u64()).unwrap_or(0),
+            output_tokens: response.pointer("/usage/completion_tokens").and_then(|v| v.as_u64()).unwrap_or(0),
+        })
+    }
+}
+
+impl ChatBackend for OpenAiBackend {
+    fn name(&self) -> &'static str {
+        match self.config.kind {
+            ProviderKind::Local => "local",
+            _ => "openai",
+        }
+    }
+
+    fn complete(&self, system: &str, messages: &[ChatMessage], tools: &[ToolSpec]) -> Result<ModelTurn> {
+        let (default_url, default_key_env) = match self.config.kind {
+            // Ollama's OpenAI-compatible endpoint; a local server needs no key unless api_key_env names one
+            ProviderKind::Local => (LOCAL_URL, None),
+            _ => ("https://api.openai.com/v1", Some("OPENAI_API_KEY")),
+        };
+        let base = self.config.url.as_deref().unwrap_or(default_url);
+        let url = format!("{}/chat/completions", base.trim_end_matches('/'));
+        let key = match default_key_env {
+            Some(env) => api_key(&self.config, env),
+            None => self.config.api_key_env.as_ref().and_then(|env| std::env::var(env).ok()).filter(|key| !key.is_empty()),
+        };
+        let headers = key.map(|key| ("Authorization", format!("Bearer {}", key))).into_iter().collect::<Vec<_>>();
+        let response = post_json(&url, &headers, &self.request_body(system, messages, tools), Duration::from_secs(REQUEST_TIMEOUT_SECS))?;
+        Self::parse_response(&response)
+    }
+}
+
+/// Google's Gemini `generateContent` API
+pub struct GeminiBackend {
+    pub config: ProviderConfig,
+    pub sampling: SamplingParams,
+}
+
+impl GeminiBackend {
+    pub fn request_body(&self, system: &str, messages: &[ChatMessage], tools: &[ToolSpec]) -> Value {
+        let mut contents: Vec<Value> = Vec::new();
+        for message in messages {
+            let (role, part) = match message {
+                ChatMessage::User(text) => ("user", vec![json!({"text": text})]),
+                ChatMessage::Assistant { text, tool_calls } => {
+                    let mut parts: Vec<Value> = Vec::new();
+                    if !text.is_empty() {
+                        parts.push(json!({"text": text}));
+                    }
+                    parts.extend(tool_calls.iter().map(|call| json!({"functionCall": {"name": call.name, "args": call.input}})));
+                    ("model", parts)
+                }
+                ChatMessage::ToolResult { name, content, .. } => {
+                    ("user", vec![json!({"functionResponse": {"name": name, "response": {"content": content}}})])
+                }
+            };
+            // Results of one turn's calls go back together, in one user message
+            match contents.last_mut() {
+                Some(last) if last["role"] == role && matches!(message, ChatMessage::ToolResult { .. }) => {
+                    if let Some(parts) = last["parts"].as_array_mut() {
+                        parts.extend(part);
+                    }
+                }
+                _ => contents.push(json!({"role": role, "parts": part})),
+            }
+        }
+        let mut body = json!({
+            "systemInstruction": {"parts": [{"text": system}]},
+            "contents": contents,
+            "generationConfig": {"maxOutputTokens": self.config.max_tokens},
+        });
+        for (key, value) in [
+            ("temperature", self.sampling.temperature.map(|v| json!(v))),
+            ("topP", self.sampling.top_p.map(|v| json!(v))),
+            ("topK", self.sampling.top_k.map(|v| json!(v))),
+            ("seed", self.sampling.seed.map(|v| json!(v))),
+        ] {
+            if let Some(value) = value {
+                body["generationConfig"][key] = value;
+            }
+        }
+        if !tools.is_empty() {
+            let declarations: Vec<Value> = tools
+                .iter()
+                .map(|tool| json!({"name": tool.name, "description": tool.description, "parameters": tool.parameters}))
+                .collect();
+            body["tools"] = json!([{ "functionDeclarations": declarations }]);
+        }
+        body
+    }
+
+    /// Gemini calls have no ids; they are numbered so results can be matched up
+    pub fn parse_response(response: &Value) -> Result<ModelTurn> {
+        let parts = response.pointer("/candidates/0/content/parts").and_then(|p| p.as_array());
+        let Some(parts) = parts else {
+            let reason = response.pointer("/candidates/0/finishReason").or(response.pointer("/promptFeedback/blockReason"));
+            bail!("Response has no content{}", reason.map(|r| format!(" ({})", r)).unwrap_or_default());
+        };
+        let mut turn = ModelTurn::default();
+        for part in parts {
+            if let Some(text) = part.get("text").and_then(|t| t.as_str()) {
+                turn.text.push_str(text);
+            }
+            if let Some(call) = part.get("functionCall") {
+                turn.tool_calls.push(ToolCall {
+                    id: format!("call-{}", turn.tool_calls.len() + 1),
+                    name: call.get("name").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                    input: call.get("args").cloned().unwrap_or_else(|| json!({})),
+                });
+            }
+        }
+        turn.input_tokens = response.pointer("/usageMetadata/promptTokenCount").and_then(|v| v.as_u64()).unwrap_or(0);
+        turn.output_tokens = response.pointer("/usageMetadata/candidatesTokenCount").and_then(|v| v.as_u64()).unwrap_or(0);
+        Ok(turn)
+    }
+}
+
+impl ChatBackend for GeminiBackend {
+    fn name(&self) -> &'static str {
+        "gemini"
+    }
+
+    fn complete(&self, system: &str, messages: &[ChatMessage], tools: &[ToolSpec]) -> Result<ModelTurn> {
+        let base = self.config.url.as_deref().unwrap_or("https://generativelanguage.googleapis.com/v1beta");
+        let url = format!("{}/models/{}:generateContent", base.trim_end_matches('/'), self.config.model);
+        let key = api_key(&self.config, "GEMINI_API_KEY");
+        let headers = key.map(|key| ("x-goog-api-key", key)).into_iter().collect::<Vec<_>>();
+        let response = post_json(&url, &headers, &self.request_body(system, messages, tools), Duration::from_secs(REQUEST_TIMEOUT_SECS))?;
+        Self::parse_response(&response)
+    }
+}
+
+fn api_key(config: &ProviderConfig, default_env: &str) -> Option<String> {
+    std::env::var(config.api_key_env.as_deref().unwrap_or(default_env)).ok().filter(|key| !key.is_empty())
+}
+
+/// Answer a one-shot text task (e.g. `failure-triage`) on the provider mapped to it in `[backends]`
+///
+/// No tools are offered, so any provider can take it, local models without
+/// tool calling included. Sampling comes from `[sampling]` under the task's
+/// name. None when no provider is mapped to the task; the tokens used are
+/// recorded under the task's name.
+pub async fn complete_text(task: &str, sprint_id: Option<u32>, system: &str, prompt: &str) -> Result<Option<String>> {
+    let config = autoflow_data::Config::global().ok();
+    let backends = config.as_ref().map(|c| c.backends.clone()).unwrap_or_default();
+    let sampling = config.map(|c| c.sampling.for_agent(task)).unwrap_or_default();
+    let Some((provider_name, provider)) = backends.provider_for_task(task)? else {
+        return Ok(None);
+    };
+    tracing::info!("Running {} on {} ({}) {}", task, provider_name, provider.model, sampling.describe());
+    let provider = provider.clone();
+    let (system, messages) = (system.to_string(), vec![ChatMessage::User(prompt.to_string())]);
+    let turn = tokio::task::spawn_blocking(move || {
+        chat_backend(&provider, &sampling).complete(&system, &messages, &[]).map(|turn| {
+            let cost = (turn.input_tokens as f64 * provider.input_price_per_mtok + turn.output_tokens as f64 * provider.output_price_per_mtok) / 1_000_000.0;
+            (turn, cost)
+        })
+    })
+    .await??;
+
+    let (turn, cost_usd) = turn;
+    let usage = TokenUsage { calls: 1, input_tokens: turn.input_tokens, output_tokens: turn.output_tokens, cost_usd, ..Default::default() };
+    crate::cost::record_agent_cost(task, sprint_id, &usage);
+    Ok(Some(turn.text.trim().to_string()).filter(|text| !text.is_empty()))
+}
+
+/// Outcome of an agent run on a chat API
+#[derive(Debug, Clone, Default)]
+pub struct ChatRun {
+    /// The model's text across all turns
+    pub output: String,
+    /// Bash commands the agent ran
+    pub commands: Vec<String>,
+    pub usage: TokenUsage,
+    /// False when the turn limit stopped the agent
+    pub finished: bool,
+}
+
+/// System prompt for an agent on a chat API
+pub fn chat_system_prompt(agent_name: &str, workdir: &Path, tools: &ToolSet) -> String {
+    let mut system = format!(
+        "You are the AutoFlow agent '{}', working autonomously in the project at {}. \
+         Use the tools to inspect and change the project; relative paths are relative to the project root. \
+         Do the work yourself rather than describing it, then reply with your final report.",
+        agent_name,
+        workdir.display()
+    );
+    if !tools.unsupported.is_empty() {
+        system.push_str(&format!(
+            "\n\nThese tools from your instructions are not available here: {}. Do without them.",
+            tools.unsupported.join(", ")
+        ));
+    }
+    system
+}
+
+/// Run one agent's conversation to completion
+///
+/// `on_tool_call` sees each call before it runs, for logging.
+pub fn run_chat_agent(
+    backend: &dyn ChatBackend,
+    tools: &ToolSet,
+    workdir: &Path,
+    system: &str,
+    prompt: &str,
+    mut on_tool_call: impl FnMut(&ToolCall),
+) -> Result<ChatRun> {
+    let specs = tools.specs();
+    let mut messages = vec![ChatMessage::User(prompt.to_string())];
+    let mut run = ChatRun::default();
+
+    for _ in 0..MAX_TOOL_TURNS {
+        let turn = backend.complete(system, &messages, &specs)?;
+        run.usage.calls = 1;
+        run.usage.input_tokens += turn.input_tokens;
+        run.usage.output_tokens += turn.output_tokens;
+        if !turn.text.is_empty() {
+            if !run.output.is_empty() {
+                run.output.push('\n');
+            }
+            run.output.push_str(&turn.text);
+        }
+        if turn.tool_calls.is_empty() {
+            run.finished = true;
+            return Ok(run);
+        }
+
+        let results: Vec<ChatMessage> = turn
+            .tool_calls
+            .iter()
+            .map(|call| {
+                on_tool_call(call);
+                if call.name == "Bash" {
+                    if let Some(command) = call.input.get("command").and_then(|c| c.as_str()) {
+                        run.commands.push(command.to_string());
+                    }
+                }
+                ChatMessage::ToolResult { call_id: call.id.clone(), name: call.name.clone(), content: tools.call(workdir, &call.name, &call.input) }
+            })
+            .collect();
+        messages.push(ChatMessage::Assistant { text: turn.text, tool_calls: turn.tool_calls });
+        messages.extend(results);
+    }
+
+    tracing::warn!("Agent on {} stopped after {} turns", backend.name(), MAX_TOOL_TURNS);
+    Ok(run)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn provider(kind: ProviderKind) -> ProviderConfig {
+        ProviderConfig {
+            kind,
+            model: "test-model".to_string(),
+            url: None,
+            api_key_env: None,
+            max_tokens: 1024,
+            tools: None,
+            input_price_per_mtok: 0.0,
+            output_price_per_mtok: 0.0,
+        }
+    }
+
+    fn conversation() -> Vec<ChatMessage> {
+        vec![
+            ChatMessage::User("List src".to_string()),
+            ChatMessage::Assistant {
+                text: String::new(),
+                tool_calls: vec![
+                    ToolCall { id: "c1".to_string(), name: "LS".to_string(), input: json!({"path": "src"}) },
+                    ToolCall { id: "c2".to_string(), name: "Read".to_string(), input: json!({"file_path": "a"}) },
+                ],
+            },
+            ChatMessage::ToolResult { call_id: "c1".to_string(), name: "LS".to_string(), content: "lib.rs".to_string() },
+            ChatMessage::ToolResult { call_id: "c2".to_string(), name: "Read".to_string(), content: "x".to_string() },
+        ]
+    }
+
+    #[test]
+    fn test_openai_wire_format() {
+        let sampling = SamplingParams { temperature: Some(0.0), top_k: Some(40), seed: Some(7), ..Default::default() };
+        let backend = OpenAiBackend { config: provider(ProviderKind::Openai), sampling };
+        let specs = ToolSet::new(&["LS".to_string()], &[]).specs();
+        let body = backend.request_body("sys", &conversation(), &specs);
+        assert_eq!(body["messages"][0], json!({"role": "system", "content": "sys"}));
+        assert_eq!(body["messages"][2]["tool_calls"][0]["function"]["arguments"], r#"{"path":"src"}"#);
+        assert_eq!(body["messages"][3], json!({"role": "tool", "tool_call_id": "c1", "content": "lib.rs"}));
+        assert_eq!(body["tools"][0]["function"]["name"], "LS");
+        assert_eq!((body["temperature"].as_f64(), body["seed"].as_u64(), body.get("top_k")), (Some(0.0), Some(7), None));
+
+        let response = json!({
+            "choices": [{"message": {"content": null, "tool_calls": [
+                {"id": "call_9", "type": "function", "function": {"name": "Bash", "arguments": "{\"command\":\"ls\"}"}}
+            ]}}],
+            "usage": {"prompt_tokens": 120, "completion_tokens": 8}
+        });
+        let turn = OpenAiBackend::parse_response(&response).unwrap();
+        assert_eq!(turn.tool_calls[0], ToolCall { id: "call_9".to_string(), name: "Bash".to_string(), input: json!({"command": "ls"}) });
+        assert_eq!((turn.input_tokens, turn.output_tokens), (120, 8));
+    }
+
+    #[test]
+    fn test_gemini_wire_format() {
+        let sampling = SamplingParams { temperature: Some(0.2), top_k: Some(40), ..Default::default() };
+        let backend = GeminiBackend { config: provider(ProviderKind::Gemini), sampling };
+        let body = backend.request_body("sys", &conversation(), &[]);
+        assert_eq!(body["generationConfig"], json!({"maxOutputTokens": 1024, "temperature": 0.2, "topK": 40}));
+        assert_eq!(body["systemInstruction"]["parts"][0]["text"], "sys");
+        assert_eq!(body["contents"][1]["role"], "model");
+        assert_eq!(body["contents"][1]["parts"][0]["functionCall"]["name"], "LS");
+        // Both results share one user message
+        assert_eq!(body["contents"].as_array().unwrap().len(), 3);
+        assert_eq!(body["contents"][2]["parts"][1]["functionResponse"]["name"], "Read");
+
+        let response = json!({
+            "candidates": [{"content": {"role": "model", "parts": [{"text": "Done. "}, {"functionCall": {"name": "Glob", "args": {"pattern": "*.rs"}}}]}}],
+            "usageMetadata": {"promptTokenCount": 50, "candidatesTokenCount": 5}
+        });
+        let turn = GeminiBackend::parse_response(&response).unwrap();
+        assert_eq!((turn.text.as_str(), turn.tool_calls[0].id.as_str(), turn.input_tokens), ("Done. ", "call-1", 50));
+        assert!(GeminiBackend::parse_response(&json!({"promptFeedback": {"blockReason": "SAFETY"}})).is_err());
+    }
+
+    /// Plays back canned turns
+    struct Scripted(Mutex<Vec<ModelTurn>>);
+
+    impl ChatBackend for Scripted {
+        fn name(&self) -> &'static str {
+            "scripted"
+        }
+
+        fn complete(&self, _system: &str, messages: &[ChatMessage], _tools: &[ToolSpec]) -> Result<ModelTurn> {
+            if let Some(ChatMessage::ToolResult { content, .. }) = messages.last() {
+                assert_eq!(content, "ok\n[exit code 0]");
+            }
+            Ok(self.0.lock().unwrap().remove(0))
+        }
+    }
+
+    #[test]
+    fn test_tool_loop() {
+        let backend = Scripted(Mutex::new(vec![
+            ModelTurn {
+                text: "Checking.".to_string(),
+                tool_calls: vec![ToolCall { id: "1".to_string(), name: "Bash".to_string(), input: json!({"command": "echo ok"}) }],
+                input_tokens: 100,
+                output_tokens: 10,
+            },
+            ModelTurn { text: "All good.".to_string(), input_tokens: 130, output_tokens: 4, ..Default::default() },
+        ]));
+        let tools = ToolSet::new(&["Bash".to_string(), "WebSearch".to_string()], &[]);
+        assert!(chat_system_prompt("reviewer", Path::new("/p"), &tools).contains("not available here: WebSearch"));
+
+        let mut seen = Vec::new();
+        let run = run_chat_agent(&backend, &tools, &std::env::temp_dir(), "sys", "Review", |call| seen.push(call.name.clone())).unwrap();
+        assert!(run.finished);
+        assert_eq!(run.output, "Checking.\nAll good.");
+        assert_eq!(run.commands, ["echo ok"]);
+        assert_eq!(seen, ["Bash"]);
+        assert_eq!((run.usage.calls, run.usage.input_tokens, run.usage.output_tokens), (1, 230, 14));
+    }
+}
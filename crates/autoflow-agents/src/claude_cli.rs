@@ -0,0 +1,197 @@
+/// Claude CLI version detection and flag compatibility
+///
+/// The executor relies on CLI flags that appeared in different releases.
+/// Rather than failing later with an unparseable stream, detect the
+/// installed version once per process and only pass flags it understands.
+use anyhow::Result;
+use autoflow_data::AutoFlowError;
+use semver::Version;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Oldest CLI release AutoFlow can drive at all
+pub const MIN_CLAUDE_VERSION: &str = "1.0.0";
+
+/// Command users should run to upgrade
+pub const CLAUDE_UPGRADE_HINT: &str = "npm install -g @anthropic-ai/claude-code@latest";
+
+/// Optional CLI flags the executor uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CliFlag {
+    /// `--allowedTools`
+    AllowedTools,
+    /// `--disallowedTools`
+    DisallowedTools,
+    /// `--output-format stream-json` (requires `--verbose` with `--print`)
+    StreamJson,
+    /// `--include-partial-messages`, used for live streaming
+    IncludePartialMessages,
+    /// `--add-dir`, grants access to directories outside the working directory
+    AddDir,
+}
+
+/// First CLI release that accepts each flag
+const COMPATIBILITY_MATRIX: &[(CliFlag, &str)] = &[
+    (CliFlag::AllowedTools, "0.2.0"),
+    (CliFlag::DisallowedTools, "0.2.0"),
+    (CliFlag::StreamJson, "0.2.66"),
+    (CliFlag::IncludePartialMessages, "1.0.86"),
+    (CliFlag::AddDir, "1.0.18"),
+];
+
+/// Built-in tools an agent's `tools:` list can name
+pub const KNOWN_TOOLS: &[&str] = &[
+    "Agent", "Bash", "BashOutput", "Edit", "ExitPlanMode", "Glob", "Grep", "KillShell", "LS", "MultiEdit",
+    "NotebookEdit", "NotebookRead", "Read", "Skill", "SlashCommand", "Task", "TodoWrite", "WebFetch",
+    "WebSearch", "Write",
+];
+
+/// Whether the CLI recognizes a `tools:` entry
+///
+/// Accepts built-in tools, permission rules such as `Bash(git:*)` and MCP tools (`mcp__server__tool`).
+pub fn is_known_tool(tool: &str) -> bool {
+    let name = tool.split('(').next().unwrap_or(tool).trim();
+    KNOWN_TOOLS.contains(&name) || name.starts_with("mcp__")
+}
+
+/// Built-in tool a misspelled entry probably meant, e.g. `bash` or `WebSerach`
+pub fn suggest_tool(tool: &str) -> Option<&'static str> {
+    let name = tool.split('(').next().unwrap_or(tool).trim().to_lowercase();
+    KNOWN_TOOLS.iter().copied().find(|known| {
+        let known = known.to_lowercase();
+        known == name || (known.len() == name.len() && {
+            let mut a: Vec<char> = known.chars().collect();
+            let mut b: Vec<char> = name.chars().collect();
+            a.sort_unstable();
+            b.sort_unstable();
+            a == b
+        })
+    })
+}
+
+/// What the installed claude CLI supports
+#[derive(Debug, Clone)]
+pub struct ClaudeCapabilities {
+    /// Detected version, None when `claude --version` could not be parsed
+    pub version: Option<Version>,
+}
+
+impl ClaudeCapabilities {
+    pub fn for_version(version: Option<Version>) -> Self {
+        Self { version }
+    }
+
+    /// Whether `flag` can be passed; unknown versions are assumed current
+    pub fn supports(&self, flag: CliFlag) -> bool {
+        let Some(version) = &self.version else {
+            return true;
+        };
+        COMPATIBILITY_MATRIX
+            .iter()
+            .find(|(f, _)| *f == flag)
+            .map(|(_, min)| version >= &Version::parse(min).expect("valid matrix version"))
+            .unwrap_or(true)
+    }
+
+    /// Fail with an actionable message when the CLI is older than we support
+    pub fn ensure_supported(&self) -> Result<()> {
+        let min = Version::parse(MIN_CLAUDE_VERSION).expect("valid minimum version");
+        if let Some(version) = &self.version {
+            if version < &min {
+                return Err(AutoFlowError::ClaudeCliUnavailable(format!(
+                    "Claude CLI {} is too old; AutoFlow requires {} or newer.\nUpgrade with: {}",
+                    version,
+                    MIN_CLAUDE_VERSION,
+                    CLAUDE_UPGRADE_HINT
+                ))
+                .into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse the output of `claude --version`, e.g. "1.0.120 (Claude Code)"
+pub fn parse_claude_version(output: &str) -> Option<Version> {
+    output
+        .split_whitespace()
+        .map(|token| token.trim_start_matches('v'))
+        .find_map(|token| Version::parse(token).ok())
+}
+
+/// Run `claude --version`; errors when the CLI is missing
+pub fn detect_claude_version() -> Result<Option<Version>> {
+    let output = match Command::new("claude").arg("--version").output() {
+        Ok(output) => output,
+        Err(_) => {
+            return Err(AutoFlowError::ClaudeCliUnavailable(format!(
+                "Claude CLI not found on PATH.\nInstall it with: {}",
+                CLAUDE_UPGRADE_HINT
+            ))
+            .into())
+        }
+    };
+    let version = parse_claude_version(&String::from_utf8_lossy(&output.stdout));
+    if version.is_none() {
+        tracing::warn!("Could not parse `claude --version` output; assuming a current CLI");
+    }
+    Ok(version)
+}
+
+/// Detect capabilities once per process and reject unsupported CLIs
+pub fn claude_capabilities() -> Result<ClaudeCapabilities> {
+    static CAPABILITIES: OnceLock<ClaudeCapabilities> = OnceLock::new();
+    if let Some(caps) = CAPABILITIES.get() {
+        return Ok(caps.clone());
+    }
+
+    let caps = ClaudeCapabilities::for_version(detect_claude_version()?);
+    caps.ensure_supported()?;
+    if !caps.supports(CliFlag::IncludePartialMessages) {
+        tracing::info!("Claude CLI predates --include-partial-messages; live logs will show whole messages");
+    }
+    Ok(CAPABILITIES.get_or_init(|| caps).clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_claude_version() {
+        assert_eq!(parse_claude_version("1.0.120 (Claude Code)"), Some(Version::new(1, 0, 120)));
+        assert_eq!(parse_claude_version("claude v2.1.3\n"), Some(Version::new(2, 1, 3)));
+        assert_eq!(parse_claude_version("unknown"), None);
+    }
+
+    #[test]
+    fn test_flags_follow_compatibility_matrix() {
+        let old = ClaudeCapabilities::for_version(Some(Version::new(1, 0, 50)));
+        assert!(old.supports(CliFlag::StreamJson));
+        assert!(!old.supports(CliFlag::IncludePartialMessages));
+        assert!(old.ensure_supported().is_ok());
+
+        let unknown = ClaudeCapabilities::for_version(None);
+        assert!(unknown.supports(CliFlag::IncludePartialMessages));
+    }
+
+    #[test]
+    fn test_known_tools() {
+        assert!(is_known_tool("Read"));
+        assert!(is_known_tool("Bash(git:*)"));
+        assert!(is_known_tool("mcp__playwright__browser_click"));
+        assert!(!is_known_tool("bash"));
+        assert!(!is_known_tool("WebSerach"));
+        assert_eq!(suggest_tool("bash"), Some("Bash"));
+        assert_eq!(suggest_tool("WebSerach"), Some("WebSearch"));
+        assert_eq!(suggest_tool("Deploy"), None);
+    }
+
+    #[test]
+    fn test_too_old_cli_is_rejected_with_upgrade_hint() {
+        let ancient = ClaudeCapabilities::for_version(Some(Version::new(0, 2, 9)));
+        let err = ancient.ensure_supported().unwrap_err().to_string();
+        assert!(err.contains("too old"));
+        assert!(err.contains(CLAUDE_UPGRADE_HINT));
+    }
+}
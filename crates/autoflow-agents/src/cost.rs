@@ -0,0 +1,280 @@
+/// Token and cost accounting for agent calls
+///
+/// Each call's usage is read from the claude CLI's stream-json events: input,
+/// output and prompt-cache tokens, plus the cost the CLI reports. Calls are
+/// rolled up per run, per sprint and per agent in `.autoflow/.metrics/costs.json`,
+/// which `autoflow cost` displays.
+use autoflow_data::Result;
+use autoflow_utils::{current_run_id, INPUT_PRICE_PER_MTOK, OUTPUT_PRICE_PER_MTOK};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Cost ledger, relative to the project root
+pub const COSTS_PATH: &str = ".autoflow/.metrics/costs.json";
+
+/// Prompt cache reads and writes, as a fraction of the input price
+const CACHE_READ_PRICE_FACTOR: f64 = 0.1;
+const CACHE_WRITE_PRICE_FACTOR: f64 = 1.25;
+
+/// Tokens and cost of one or more agent calls
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct TokenUsage {
+    #[serde(default)]
+    pub calls: u32,
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+    #[serde(default)]
+    pub cache_read_tokens: u64,
+    #[serde(default)]
+    pub cache_write_tokens: u64,
+    /// As reported by the CLI, else estimated from list prices
+    #[serde(default)]
+    pub cost_usd: f64,
+}
+
+impl TokenUsage {
+    fn from_json(usage: &serde_json::Value) -> Self {
+        let tokens = |key: &str| usage.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+        Self {
+            calls: 0,
+            input_tokens: tokens("input_tokens"),
+            output_tokens: tokens("output_tokens"),
+            cache_read_tokens: tokens("cache_read_input_tokens"),
+            cache_write_tokens: tokens("cache_creation_input_tokens"),
+            cost_usd: 0.0,
+        }
+    }
+
+    /// Cost at list prices (Sonnet-class models)
+    pub fn estimated_cost_usd(&self) -> f64 {
+        let input = self.input_tokens as f64
+            + self.cache_read_tokens as f64 * CACHE_READ_PRICE_FACTOR
+            + self.cache_write_tokens as f64 * CACHE_WRITE_PRICE_FACTOR;
+        (input * INPUT_PRICE_PER_MTOK + self.output_tokens as f64 * OUTPUT_PRICE_PER_MTOK) / 1_000_000.0
+    }
+
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.calls += other.calls;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cache_write_tokens += other.cache_write_tokens;
+        self.cost_usd += other.cost_usd;
+    }
+}
+
+/// Usage of one agent call, accumulated from its stream-json events
+///
+/// Each message reports its input tokens when it starts and its running output
+/// count as it streams; the CLI's closing `result` event, when it arrives,
+/// carries the call's totals and cost and replaces the running sum.
+#[derive(Debug, Clone, Default)]
+pub struct UsageMeter {
+    finished: TokenUsage,
+    message: Option<TokenUsage>,
+    reported: Option<TokenUsage>,
+}
+
+impl UsageMeter {
+    /// Take in one line of stream-json output, wrapped or not
+    pub fn observe(&mut self, line: &serde_json::Value) {
+        if line.get("type").and_then(|t| t.as_str()) == Some("result") {
+            if let Some(usage) = line.get("usage") {
+                let mut reported = TokenUsage::from_json(usage);
+                reported.cost_usd = line.get("total_cost_usd").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                self.reported = Some(reported);
+            }
+            return;
+        }
+        let event = match line.get("type").and_then(|t| t.as_str()) {
+            Some("stream_event") => match line.get("event") {
+                Some(event) => event,
+                None => return,
+            },
+            _ => line,
+        };
+        match event.get("type").and_then(|t| t.as_str()) {
+            Some("message_start") => {
+                if let Some(message) = self.message.take() {
+                    self.finished.add(&message);
+                }
+                self.message = event.pointer("/message/usage").map(TokenUsage::from_json);
+            }
+            Some("message_delta") => {
+                if let (Some(message), Some(output)) = (self.message.as_mut(), event.pointer("/usage/output_tokens").and_then(|v| v.as_u64())) {
+                    message.output_tokens = output;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The call's usage so far, counted as one call
+    pub fn usage(&self) -> TokenUsage {
+        let mut usage = self.reported.unwrap_or_else(|| {
+            let mut usage = self.finished;
+            if let Some(ref message) = self.message {
+                usage.add(message);
+            }
+            usage
+        });
+        usage.calls = 1;
+        if usage.cost_usd == 0.0 {
+            usage.cost_usd = usage.estimated_cost_usd();
+        }
+        usage
+    }
+}
+
+/// One run's spend
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RunCost {
+    pub started_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub total: TokenUsage,
+    #[serde(default)]
+    pub sprints: BTreeMap<u32, TokenUsage>,
+    #[serde(default)]
+    pub agents: BTreeMap<String, TokenUsage>,
+}
+
+/// Contents of `.autoflow/.metrics/costs.json`: spend per run, keyed by run id
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CostTracker {
+    #[serde(default)]
+    pub runs: BTreeMap<String, RunCost>,
+}
+
+impl CostTracker {
+    pub fn load(project_path: &Path) -> Self {
+        autoflow_data::read_state_to_string(project_path.join(COSTS_PATH))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, project_path: &Path) -> Result<()> {
+        let path = project_path.join(COSTS_PATH);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        autoflow_data::write_state(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// Add one call's usage to its run, sprint and agent
+    pub fn add(&mut self, run_id: &str, agent: &str, sprint: Option<u32>, usage: &TokenUsage) {
+        let now = Utc::now();
+        let run = self.runs.entry(run_id.to_string()).or_default();
+        run.started_at.get_or_insert(now);
+        run.updated_at = Some(now);
+        run.total.add(usage);
+        run.agents.entry(agent.to_string()).or_default().add(usage);
+        if let Some(sprint) = sprint {
+            run.sprints.entry(sprint).or_default().add(usage);
+        }
+    }
+
+    /// Each sprint's spend across every run
+    pub fn sprint_totals(&self) -> BTreeMap<u32, TokenUsage> {
+        let mut totals: BTreeMap<u32, TokenUsage> = BTreeMap::new();
+        for (sprint, usage) in self.runs.values().flat_map(|run| &run.sprints) {
+            totals.entry(*sprint).or_default().add(usage);
+        }
+        totals
+    }
+
+    /// Spend across every run
+    pub fn total(&self) -> TokenUsage {
+        let mut total = TokenUsage::default();
+        for run in self.runs.values() {
+            total.add(&run.total);
+        }
+        total
+    }
+
+    /// Runs oldest first
+    pub fn runs_by_start(&self) -> Vec<(&String, &RunCost)> {
+        let mut runs: Vec<_> = self.runs.iter().collect();
+        runs.sort_by_key(|(_, run)| run.started_at);
+        runs
+    }
+
+    /// Run whose id is or starts with `prefix`; an ambiguous prefix matches nothing
+    pub fn find_run(&self, prefix: &str) -> Option<(&String, &RunCost)> {
+        let mut matching = self.runs.iter().filter(|(id, _)| id.starts_with(prefix));
+        match (matching.next(), matching.next()) {
+            (Some(run), None) => Some(run),
+            _ => self.runs.get_key_value(prefix),
+        }
+    }
+}
+
+/// Record a finished agent call against the current run in the project's ledger (best effort)
+///
+/// Parallel sprints finish calls concurrently, so each update reads, adds and
+/// writes the ledger under one lock.
+pub fn record_agent_cost(agent: &str, sprint: Option<u32>, usage: &TokenUsage) {
+    static LEDGER: Mutex<()> = Mutex::new(());
+    let Some(run_id) = current_run_id() else {
+        return;
+    };
+    if !Path::new(".autoflow").exists() || autoflow_data::is_read_only() {
+        return;
+    }
+    let _guard = LEDGER.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let project_path = Path::new(".");
+    let mut tracker = CostTracker::load(project_path);
+    tracker.add(&run_id, agent, sprint, usage);
+    if let Err(e) = tracker.save(project_path) {
+        tracing::warn!("Failed to record agent cost: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_usage_meter_and_rollups() {
+        let mut meter = UsageMeter::default();
+        let events = [
+            json!({"type": "stream_event", "event": {"type": "message_start", "message": {"usage": {"input_tokens": 1200, "cache_read_input_tokens": 8000, "output_tokens": 1}}}}),
+            json!({"type": "stream_event", "event": {"type": "message_delta", "usage": {"output_tokens": 300}}}),
+            json!({"type": "assistant", "message": {"content": []}}),
+            json!({"type": "stream_event", "event": {"type": "message_start", "message": {"usage": {"input_tokens": 400, "output_tokens": 1}}}}),
+            json!({"type": "stream_event", "event": {"type": "message_delta", "usage": {"output_tokens": 150}}}),
+        ];
+        for event in &events {
+            meter.observe(event);
+        }
+        let usage = meter.usage();
+        assert_eq!((usage.calls, usage.input_tokens, usage.output_tokens, usage.cache_read_tokens), (1, 1600, 450, 8000));
+        assert!((usage.cost_usd - usage.estimated_cost_usd()).abs() < 1e-9);
+
+        // The CLI's closing totals win over the running sum
+        meter.observe(&json!({"type": "result", "total_cost_usd": 0.25, "usage": {"input_tokens": 1700, "output_tokens": 460}}));
+        let usage = meter.usage();
+        assert_eq!((usage.input_tokens, usage.output_tokens, usage.cost_usd), (1700, 460, 0.25));
+
+        let mut tracker = CostTracker::default();
+        tracker.add("01J9Z", "code-implementer", Some(3), &usage);
+        tracker.add("01J9Z", "reviewer", Some(3), &usage);
+        tracker.add("01J9Z", "sprint-generator", None, &usage);
+        tracker.add("01JA0", "unit-fixer", Some(3), &usage);
+        let run = &tracker.runs["01J9Z"];
+        assert_eq!(run.total.calls, 3);
+        assert_eq!(run.sprints[&3].output_tokens, 920);
+        assert_eq!(run.agents["reviewer"].calls, 1);
+        assert_eq!(tracker.sprint_totals()[&3].calls, 3);
+        assert!((tracker.total().cost_usd - 1.0).abs() < 1e-9);
+        assert_eq!(tracker.find_run("01JA").map(|(id, _)| id.as_str()), Some("01JA0"));
+        assert!(tracker.find_run("01J").is_none());
+    }
+}
@@ -0,0 +1,240 @@
+/// Embedding-based section retrieval for doc references that miss
+///
+/// Heading names drift as docs are revised, so `API_SPEC.md#UserEndpoints`
+/// may no longer match any heading. With `[embeddings]` configured, the doc's
+/// sections and the referencing tasks are embedded and the most similar
+/// sections are inlined in place of a table of contents. Any local model
+/// served by Ollama or any OpenAI-compatible API will do.
+use crate::doc_index::{DocHeading, DocIndex};
+use anyhow::{bail, Context, Result};
+use autoflow_data::{EmbeddingProvider, EmbeddingsConfig};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Characters of a section that are embedded; enough to capture its topic
+const MAX_SECTION_CHARS: usize = 2000;
+
+/// Request timeout per embedding call
+const EMBED_TIMEOUT_SECS: u64 = 30;
+
+/// Sections of `index` closest in meaning to `query`, best first
+pub fn closest_sections<'a>(
+    config: &EmbeddingsConfig,
+    index: &'a DocIndex,
+    query: &str,
+) -> Result<Vec<(&'a DocHeading, f32)>> {
+    if index.headings.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut texts: Vec<String> = index
+        .headings
+        .iter()
+        .map(|h| index.section_text(h).chars().take(MAX_SECTION_CHARS).collect())
+        .collect();
+    texts.push(query.to_string());
+
+    let mut vectors = embed_cached(config, &texts)?;
+    let query_vector = vectors.pop().unwrap_or_default();
+    Ok(rank_sections(
+        &index.headings,
+        &query_vector,
+        &vectors,
+        config.max_sections,
+        config.min_similarity,
+    ))
+}
+
+/// Up to `max` headings scoring at least `min_similarity`, best first
+///
+/// A section overlapping one already picked (its parent or child) is
+/// skipped, so the same text is never inlined twice.
+pub fn rank_sections<'a>(
+    headings: &'a [DocHeading],
+    query: &[f32],
+    sections: &[Vec<f32>],
+    max: usize,
+    min_similarity: f32,
+) -> Vec<(&'a DocHeading, f32)> {
+    let mut scored: Vec<(&DocHeading, f32)> = headings
+        .iter()
+        .zip(sections)
+        .map(|(heading, vector)| (heading, cosine_similarity(query, vector)))
+        .filter(|(_, score)| *score >= min_similarity)
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let mut picked: Vec<(&DocHeading, f32)> = Vec::new();
+    for (heading, score) in scored {
+        if picked.len() >= max {
+            break;
+        }
+        if picked.iter().all(|(p, _)| heading.end <= p.start || p.end <= heading.start) {
+            picked.push((heading, score));
+        }
+    }
+    picked
+}
+
+/// Cosine similarity; 0 for empty or mismatched vectors
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 {
+        0.0
+    } else {
+        dot / denominator
+    }
+}
+
+type EmbeddingCache = Mutex<HashMap<u64, Vec<f32>>>;
+
+fn cache() -> &'static EmbeddingCache {
+    static CACHE: OnceLock<EmbeddingCache> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Embed `texts`, requesting only those not embedded earlier in this process
+fn embed_cached(config: &EmbeddingsConfig, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+    let model = model(config)?;
+    let keys: Vec<u64> = texts
+        .iter()
+        .map(|text| {
+            let mut hasher = DefaultHasher::new();
+            (&model, text).hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect();
+
+    let missing: Vec<usize> = {
+        let cache = cache().lock().map_err(|_| anyhow::anyhow!("embedding cache poisoned"))?;
+        (0..texts.len()).filter(|&i| !cache.contains_key(&keys[i])).collect()
+    };
+    if !missing.is_empty() {
+        let batch: Vec<String> = missing.iter().map(|&i| texts[i].clone()).collect();
+        let vectors = embed(config, &batch)?;
+        let mut cache = cache().lock().map_err(|_| anyhow::anyhow!("embedding cache poisoned"))?;
+        for (i, vector) in missing.into_iter().zip(vectors) {
+            cache.insert(keys[i], vector);
+        }
+    }
+
+    let cache = cache().lock().map_err(|_| anyhow::anyhow!("embedding cache poisoned"))?;
+    Ok(keys.iter().map(|k| cache.get(k).cloned().unwrap_or_default()).collect())
+}
+
+fn model(config: &EmbeddingsConfig) -> Result<String> {
+    let provider = config.provider.context("No [embeddings] provider configured")?;
+    Ok(config.model.clone().unwrap_or_else(|| {
+        match provider {
+            EmbeddingProvider::Ollama => "nomic-embed-text",
+            EmbeddingProvider::Openai => "text-embedding-3-small",
+        }
+        .to_string()
+    }))
+}
+
+/// One vector per text, from the configured provider
+pub fn embed(config: &EmbeddingsConfig, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+    let provider = config.provider.context("No [embeddings] provider configured")?;
+    let base = config.url.clone().unwrap_or_else(|| {
+        match provider {
+            EmbeddingProvider::Ollama => "http://localhost:11434",
+            EmbeddingProvider::Openai => "https://api.openai.com/v1",
+        }
+        .to_string()
+    });
+    let url = match provider {
+        EmbeddingProvider::Ollama => format!("{}/api/embed", base.trim_end_matches('/')),
+        EmbeddingProvider::Openai => format!("{}/embeddings", base.trim_end_matches('/')),
+    };
+    let body = serde_json::json!({ "model": model(config)?, "input": texts });
+
+    let headers = std::env::var(&config.api_key_env)
+        .ok()
+        .filter(|key| !key.is_empty())
+        .map(|key| ("Authorization", format!("Bearer {}", key)))
+        .into_iter()
+        .collect::<Vec<_>>();
+    let response = crate::http::post_json(&url, &headers, &body, Duration::from_secs(EMBED_TIMEOUT_SECS))
+        .context("Embedding request failed")?;
+    let vectors = parse_embeddings(provider, &response)?;
+    if vectors.len() != texts.len() {
+        bail!("Embedding response has {} vectors for {} inputs", vectors.len(), texts.len());
+    }
+    Ok(vectors)
+}
+
+/// Vectors from an Ollama (`embeddings`) or OpenAI (`data[].embedding`) response, in input order
+pub fn parse_embeddings(provider: EmbeddingProvider, response: &serde_json::Value) -> Result<Vec<Vec<f32>>> {
+    let vector = |value: &serde_json::Value| -> Option<Vec<f32>> {
+        value.as_array()?.iter().map(|x| x.as_f64().map(|x| x as f32)).collect()
+    };
+    let vectors = match provider {
+        EmbeddingProvider::Ollama => response["embeddings"]
+            .as_array()
+            .context("Embedding response has no `embeddings`")?
+            .iter()
+            .map(vector)
+            .collect::<Option<Vec<_>>>(),
+        EmbeddingProvider::Openai => {
+            let mut data: Vec<&serde_json::Value> = response["data"]
+                .as_array()
+                .context("Embedding response has no `data`")?
+                .iter()
+                .collect();
+            data.sort_by_key(|item| item["index"].as_u64().unwrap_or(0));
+            data.into_iter().map(|item| vector(&item["embedding"])).collect::<Option<Vec<_>>>()
+        }
+    };
+    vectors.context("Embedding response has a malformed vector")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rank_sections() {
+        let index = DocIndex::build(
+            "# API\n\n## Accounts\n\n### Sign Up\n\nPOST /users\n\n## Billing\n\nInvoices\n\n## Health\n\nGET /health\n",
+        );
+        let titles: Vec<&str> = index.headings.iter().map(|h| h.title.as_str()).collect();
+        assert_eq!(titles, ["API", "Accounts", "Sign Up", "Billing", "Health"]);
+
+        // API covers everything, Sign Up sits inside Accounts
+        let vectors = vec![
+            vec![0.6, 0.6, 0.5],
+            vec![1.0, 0.1, 0.0],
+            vec![0.9, 0.0, 0.1],
+            vec![0.0, 1.0, 0.0],
+            vec![0.0, 0.0, 1.0],
+        ];
+        let query = [1.0, 0.0, 0.0];
+        let ranked = rank_sections(&index.headings, &query, &vectors, 2, 0.3);
+        let picked: Vec<&str> = ranked.iter().map(|(h, _)| h.title.as_str()).collect();
+        assert_eq!(picked, ["Accounts"]);
+
+        let ranked = rank_sections(&index.headings, &[0.5, 0.5, 0.0], &vectors, 2, 0.3);
+        let picked: Vec<&str> = ranked.iter().map(|(h, _)| h.title.as_str()).collect();
+        assert_eq!(picked, ["API"]);
+
+        assert!(rank_sections(&index.headings, &[0.0, 0.0, 0.0], &vectors, 2, 0.3).is_empty());
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[1.0]), 0.0);
+
+        let ollama = serde_json::json!({"embeddings": [[0.1, 0.2], [0.3, 0.4]]});
+        assert_eq!(parse_embeddings(EmbeddingProvider::Ollama, &ollama).unwrap(), vec![vec![0.1, 0.2], vec![0.3, 0.4]]);
+        let openai = serde_json::json!({"data": [
+            {"index": 1, "embedding": [0.3, 0.4]},
+            {"index": 0, "embedding": [0.1, 0.2]}
+        ]});
+        assert_eq!(parse_embeddings(EmbeddingProvider::Openai, &openai).unwrap(), vec![vec![0.1, 0.2], vec![0.3, 0.4]]);
+        assert!(parse_embeddings(EmbeddingProvider::Openai, &ollama).is_err());
+    }
+}
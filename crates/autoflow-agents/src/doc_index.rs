@@ -0,0 +1,204 @@
+/// Heading index over the project docs used to build compact agent context
+///
+/// Sprint tasks reference docs as `FILE.md` or `FILE.md#Section`. Instead of
+/// inlining whole documents, resolve the section through a per-doc heading
+/// index and, when no section matches, give the agent the table of contents
+/// and let it Read only what it needs.
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+
+/// Directory task doc references are resolved against
+pub const DOCS_DIR: &str = ".autoflow/docs";
+
+/// A markdown heading and the line range of its section
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocHeading {
+    pub level: usize,
+    pub title: String,
+    /// Normalized title used for matching (see `normalize_heading`)
+    pub key: String,
+    /// Line of the heading itself
+    pub start: usize,
+    /// One past the last line of the section (next same-or-higher heading)
+    pub end: usize,
+}
+
+/// Heading index for one document
+#[derive(Debug, Clone)]
+pub struct DocIndex {
+    lines: Vec<String>,
+    pub headings: Vec<DocHeading>,
+}
+
+impl DocIndex {
+    pub fn build(content: &str) -> Self {
+        let lines: Vec<String> = content.lines().map(str::to_string).collect();
+        let mut headings: Vec<DocHeading> = Vec::new();
+        let mut in_code_block = false;
+
+        for (i, line) in lines.iter().enumerate() {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+                continue;
+            }
+            if in_code_block || !line.starts_with('#') {
+                continue;
+            }
+            let level = line.chars().take_while(|c| *c == '#').count();
+            let title = line[level..].trim();
+            if title.is_empty() || level > 6 {
+                continue;
+            }
+            headings.push(DocHeading {
+                level,
+                title: title.to_string(),
+                key: normalize_heading(title),
+                start: i,
+                end: lines.len(),
+            });
+        }
+
+        // Close each section at the next heading of the same or higher level
+        for i in 0..headings.len() {
+            if let Some(next) = headings[i + 1..].iter().find(|h| h.level <= headings[i].level) {
+                headings[i].end = next.start;
+            }
+        }
+
+        Self { lines, headings }
+    }
+
+    /// Resolve a section reference case/space-insensitively
+    ///
+    /// "Tech Stack", "tech-stack" and "TechStack" all match `## Tech Stack`.
+    /// Exact matches win, then a heading starting with the reference, then
+    /// the shortest heading containing it.
+    pub fn find_section(&self, reference: &str) -> Option<&DocHeading> {
+        let wanted = normalize_heading(reference);
+        if wanted.is_empty() {
+            return None;
+        }
+        self.headings
+            .iter()
+            .find(|h| h.key == wanted)
+            .or_else(|| self.headings.iter().find(|h| h.key.starts_with(&wanted)))
+            .or_else(|| {
+                self.headings
+                    .iter()
+                    .filter(|h| h.key.contains(&wanted))
+                    .min_by_key(|h| h.key.len())
+            })
+    }
+
+    /// Text of a section, including its heading and subsections
+    pub fn section_text(&self, heading: &DocHeading) -> String {
+        let mut text = self.lines[heading.start..heading.end].join("\n");
+        text.push('\n');
+        text
+    }
+
+    /// Indented list of headings
+    pub fn table_of_contents(&self) -> String {
+        let min_level = self.headings.iter().map(|h| h.level).min().unwrap_or(1);
+        self.headings
+            .iter()
+            .map(|h| format!("{}- {}\n", "  ".repeat(h.level - min_level), h.title))
+            .collect()
+    }
+
+    pub fn full_text(&self) -> String {
+        let mut text = self.lines.join("\n");
+        text.push('\n');
+        text
+    }
+}
+
+/// Lowercase and drop everything but letters and digits
+///
+/// Also strips a leading "1." / "2.3" style section number so references
+/// don't have to repeat numbering.
+pub fn normalize_heading(text: &str) -> String {
+    let text = text
+        .trim()
+        .trim_start_matches(|c: char| c.is_ascii_digit() || c == '.')
+        .trim();
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+type IndexCache = Mutex<HashMap<PathBuf, (Option<SystemTime>, Arc<DocIndex>)>>;
+
+fn cache() -> &'static IndexCache {
+    static CACHE: OnceLock<IndexCache> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Index for a doc, rebuilt only when the file changed since it was cached
+pub fn doc_index<P: AsRef<Path>>(path: P) -> Option<Arc<DocIndex>> {
+    let path = path.as_ref();
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+
+    let mut cache = cache().lock().ok()?;
+    if let Some((cached_at, index)) = cache.get(path) {
+        if *cached_at == modified && modified.is_some() {
+            return Some(index.clone());
+        }
+    }
+
+    let content = std::fs::read_to_string(path).ok()?;
+    let index = Arc::new(DocIndex::build(&content));
+    cache.insert(path.to_path_buf(), (modified, index.clone()));
+    Some(index)
+}
+
+/// Index every markdown doc up front (called at run start)
+pub fn warm_doc_index<P: AsRef<Path>>(dir: P) -> usize {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+                .filter(|p| doc_index(p).is_some())
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "# Build Spec\n\nIntro\n\n## 1. Tech Stack\n\nRust\n\n### Database\n\nPostgres\n\n```\n# not a heading\n```\n\n## Deployment\n\nDocker\n";
+
+    #[test]
+    fn test_sections_end_at_same_level_heading() {
+        let index = DocIndex::build(DOC);
+        let heading = index.find_section("Tech Stack").unwrap();
+        let text = index.section_text(heading);
+        assert!(text.contains("Postgres"));
+        assert!(text.contains("# not a heading"));
+        assert!(!text.contains("Docker"));
+        assert_eq!(index.headings.len(), 4);
+    }
+
+    #[test]
+    fn test_fuzzy_section_matching() {
+        let index = DocIndex::build(DOC);
+        for reference in ["TechStack", "tech-stack", "tech_stack", " TECH STACK "] {
+            assert_eq!(index.find_section(reference).unwrap().title, "1. Tech Stack");
+        }
+        assert_eq!(index.find_section("data").unwrap().title, "Database");
+        assert!(index.find_section("Security").is_none());
+    }
+
+    #[test]
+    fn test_table_of_contents() {
+        let toc = DocIndex::build(DOC).table_of_contents();
+        assert_eq!(toc, "- Build Spec\n  - 1. Tech Stack\n    - Database\n  - Deployment\n");
+    }
+}
@@ -1,6 +1,15 @@
 use anyhow::{bail, Context, Result};
-use autoflow_utils::get_debug_logger;
-use std::path::PathBuf;
+use crate::agent_overrides::{compose_agent_prompt, load_agent_override};
+use crate::backends::{chat_backend, chat_system_prompt, run_chat_agent};
+use crate::claude_cli::CliFlag;
+use crate::cost::{record_agent_cost, UsageMeter};
+use crate::doc_index::{doc_index, DOCS_DIR};
+use crate::local_tools::ToolSet;
+use crate::remote::RemoteExecutor;
+use crate::watchdog::{AgentStalled, StallWatchdog, WatchdogVerdict};
+use autoflow_data::AutoFlowError;
+use autoflow_utils::{get_debug_logger, sanitize_output};
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::Command;
@@ -14,43 +23,74 @@ pub struct AgentResult {
     pub error: Option<String>,
     pub log_path: Option<PathBuf>,
     pub json_log_path: Option<PathBuf>,
+    /// Bash commands the agent ran (only captured with stream-json output)
+    pub commands: Vec<String>,
 }
 
 /// Agent definition loaded from .agent.md file
 #[derive(Debug)]
-struct AgentDef {
-    model: String,
-    tools: Vec<String>,
-    system_prompt: String,
+pub struct AgentDef {
+    /// File the definition was read from
+    pub path: PathBuf,
+    /// Model after the `AUTOFLOW_MODEL` override
+    pub model: String,
+    pub tools: Vec<String>,
+    pub system_prompt: String,
+    /// Completion protocol version from the `protocol:` frontmatter line
+    pub protocol: Option<u32>,
+}
+
+/// Rough token count of a prompt (about four characters per token)
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Load agent definition, with the project's prompt overlay applied
+async fn load_agent_def(agent_name: &str) -> Result<AgentDef> {
+    let mut def = load_base_agent_def(agent_name).await?;
+    if let Some(overlay) = load_agent_override(Path::new("."), agent_name) {
+        tracing::debug!("Applying prompt overlay for agent '{}'", agent_name);
+        def.system_prompt = compose_agent_prompt(&def.system_prompt, Some(&overlay));
+    }
+    Ok(def)
+}
+
+/// An agent's definition; with `resolved`, its prompt is as sent (including the project's overlay)
+pub async fn load_agent(agent_name: &str, resolved: bool) -> Result<AgentDef> {
+    if resolved {
+        load_agent_def(agent_name).await
+    } else {
+        load_base_agent_def(agent_name).await
+    }
 }
 
 /// Load agent definition from agents directory
 /// Looks in order:
 /// 1. ./agents/ (project-local override)
 /// 2. ~/.claude/agents/ (auto-synced on startup)
-async fn load_agent_def(agent_name: &str) -> Result<AgentDef> {
-    let home = std::env::var("HOME").unwrap_or_else(|_| "/root".to_string());
-
-    let possible_paths = vec![
+async fn load_base_agent_def(agent_name: &str) -> Result<AgentDef> {
+    let mut possible_paths = vec![
         // Project-local agents directory (for development/testing)
         PathBuf::from("./agents").join(format!("{}.md", agent_name)),
-        // ~/.claude/agents/ (auto-synced from source on every run)
-        PathBuf::from(home)
-            .join(".claude")
-            .join("agents")
-            .join(format!("{}.agent.md", agent_name)),
     ];
+    // ~/.claude/agents/ (auto-synced from source on every run)
+    if let Some(home) = autoflow_utils::home_dir() {
+        possible_paths.push(home.join(".claude").join("agents").join(format!("{}.agent.md", agent_name)));
+    }
 
-    let agent_path = possible_paths
-        .iter()
-        .find(|p| p.exists())
-        .ok_or_else(|| anyhow::anyhow!("Agent file not found for '{}'. Tried: {:?}", agent_name, possible_paths))?
-        .clone();
+    let Some(agent_path) = possible_paths.iter().find(|p| p.exists()).cloned() else {
+        tracing::warn!("Agent file not found for '{}'. Tried: {:?}", agent_name, possible_paths);
+        return Err(AutoFlowError::AgentNotFound(agent_name.to_string()).into());
+    };
 
     let content = tokio::fs::read_to_string(&agent_path)
         .await
         .context(format!("Failed to read agent file: {:?}", agent_path))?;
 
+    // A template written for another completion protocol would be misread
+    let protocol = crate::protocol::declared_protocol(&content);
+    crate::protocol::check_protocol(agent_name, protocol)?;
+
     // Parse frontmatter
     let mut lines = content.lines();
 
@@ -88,18 +128,20 @@ async fn load_agent_def(agent_name: &str) -> Result<AgentDef> {
         }
     }
 
-    // Check for model override via environment variable
-    let final_model = if let Ok(env_model) = std::env::var("AUTOFLOW_MODEL") {
-        tracing::info!("Using model override from AUTOFLOW_MODEL env: {}", env_model);
-        env_model
+    // Check for model override via AUTOFLOW_MODEL or the project's config.yml
+    let final_model = if let Some(project_model) = autoflow_data::ProjectConfig::current().model() {
+        tracing::info!("Using model override from AUTOFLOW_MODEL / config.yml: {}", project_model);
+        project_model
     } else {
         model
     };
 
     Ok(AgentDef {
+        path: agent_path,
         model: final_model,
         tools,
         system_prompt: system_prompt.trim().to_string(),
+        protocol,
     })
 }
 
@@ -114,14 +156,48 @@ pub async fn execute_agent_with_retry(
     context: &str,
     max_turns: u32,
     sprint_id: Option<u32>,
+) -> Result<AgentResult> {
+    execute_agent_with_model(agent_name, context, max_turns, sprint_id, None, None).await
+}
+
+/// Execute agent on a specific model (None = the agent's configured model)
+///
+/// Same retries as execute_agent_with_retry; the Opus to Sonnet fallback only
+/// applies when no model is given. With `workdir` the agent runs in that
+/// directory (another repository) and keeps access to the current one.
+pub async fn execute_agent_with_model(
+    agent_name: &str,
+    context: &str,
+    max_turns: u32,
+    sprint_id: Option<u32>,
+    model: Option<&str>,
+    workdir: Option<&Path>,
 ) -> Result<AgentResult> {
     const MAX_RETRIES: u32 = 3;
     const RETRY_DELAYS: [u64; 3] = [5, 15, 30]; // seconds
 
-    let mut current_model: Option<String> = None;
+    let mut current_model: Option<String> = model.map(String::from);
+    let network = autoflow_data::Config::global().map(|c| c.network).unwrap_or_default();
 
     for attempt in 1..=MAX_RETRIES {
-        match execute_agent_internal(agent_name, context, max_turns, sprint_id, current_model.as_deref()).await {
+        // A failure while the network is down is waited out and rerun, not counted
+        let outcome = loop {
+            let outcome = execute_agent_watched(agent_name, context, max_turns, sprint_id, current_model.as_deref(), workdir).await;
+            let failure = match &outcome {
+                Ok(result) if result.success => break outcome,
+                Ok(result) => result.error.clone().unwrap_or_default(),
+                Err(e) => e.to_string(),
+            };
+            if !autoflow_utils::network_lost(&network).await {
+                if autoflow_utils::looks_like_network_error(&failure) {
+                    tracing::warn!("Agent '{}' reported a connection error but {} is reachable", agent_name, network.probe);
+                }
+                break outcome;
+            }
+            autoflow_utils::wait_for_network(&network, agent_name, sprint_id).await?;
+        };
+
+        match outcome {
             Ok(result) => {
                 // Success - return immediately
                 return Ok(result);
@@ -168,7 +244,7 @@ pub async fn execute_agent_with_retry(
     }
 
     // Should never reach here, but satisfy compiler
-    bail!("Agent execution failed after {} retries", MAX_RETRIES)
+    Err(AutoFlowError::AgentExecutionFailed(agent_name.to_string(), format!("gave up after {} retries", MAX_RETRIES)).into())
 }
 
 /// Backwards-compatible alias for execute_agent_with_retry
@@ -181,6 +257,32 @@ pub async fn execute_agent(
     execute_agent_with_retry(agent_name, context, max_turns, sprint_id).await
 }
 
+/// Run the agent, killing and restarting it when the stall watchdog fires
+async fn execute_agent_watched(
+    agent_name: &str,
+    context: &str,
+    max_turns: u32,
+    sprint_id: Option<u32>,
+    model_override: Option<&str>,
+    workdir: Option<&Path>,
+) -> Result<AgentResult> {
+    let max_stall_retries = autoflow_data::Config::global()
+        .map(|c| c.watchdog.max_stall_retries)
+        .unwrap_or_else(|_| autoflow_data::WatchdogConfig::default().max_stall_retries);
+
+    let mut stalls = 0;
+    loop {
+        match execute_agent_internal(agent_name, context, max_turns, sprint_id, model_override, workdir).await {
+            Err(e) if e.is::<AgentStalled>() && stalls < max_stall_retries => {
+                stalls += 1;
+                tracing::warn!("{} - restarting (stall retry {}/{})", e, stalls, max_stall_retries);
+                println!("  {} - restarting ({}/{})", e, stalls, max_stall_retries);
+            }
+            result => return result,
+        }
+    }
+}
+
 /// Internal execute agent function (called by retry wrapper)
 async fn execute_agent_internal(
     agent_name: &str,
@@ -188,8 +290,10 @@ async fn execute_agent_internal(
     _max_turns: u32,
     sprint_id: Option<u32>,
     model_override: Option<&str>,
+    workdir: Option<&Path>,
 ) -> Result<AgentResult> {
     tracing::info!("Executing agent: {}", agent_name);
+    let started = std::time::Instant::now();
 
     // Initialize debug logger
     let debug_logger = get_debug_logger();
@@ -198,7 +302,7 @@ async fn execute_agent_internal(
     }
 
     // Initialize live logger if enabled
-    let live_enabled = std::env::var("AUTOFLOW_LIVE_LOGGING").unwrap_or_default() == "1";
+    let live_enabled = autoflow_data::ProjectConfig::current().live_logging().unwrap_or(false);
     let live_logger = if live_enabled {
         match crate::live_logger::LiveLogger::new(agent_name, sprint_id) {
             Ok(logger) => {
@@ -247,7 +351,11 @@ async fn execute_agent_internal(
     // Sprint-specific logs are no longer created to avoid duplication
 
     // Build the full prompt combining system prompt and context
-    let full_prompt = format!("{}\n\n# Context\n\n{}", agent_def.system_prompt, context);
+    let mut full_prompt = format!("{}\n\n# Context\n\n{}", agent_def.system_prompt, context);
+    if let Some(protocol) = crate::protocol::agent_protocol(agent_name) {
+        full_prompt.push_str("\n\n");
+        full_prompt.push_str(&protocol.instructions(sprint_id));
+    }
 
     // Log the FULL combined prompt that will be sent to Claude
     if let Some(ref logger) = debug_logger {
@@ -262,13 +370,106 @@ async fn execute_agent_internal(
     let debug_mode = std::env::var("AUTOFLOW_DEBUG").unwrap_or_default() == "1"
         || std::env::var("RUST_LOG").unwrap_or_default().contains("debug");
 
+    // Agents mapped to another vendor in [backends] don't go through the claude CLI
+    let config = autoflow_data::Config::global().ok();
+    let backends = config.as_ref().map(|c| c.backends.clone()).unwrap_or_default();
+    let sampling = config.map(|c| c.sampling.for_agent(agent_name)).unwrap_or_default();
+    let disallowed_tools = restrict_tools(&mut agent_def);
+
+    // A phase that needs tools never goes to a model that can't call them
+    let provider = backends.provider_for(agent_name)?.filter(|(provider_name, provider)| {
+        let usable = provider.can_use_tools() || agent_def.tools.is_empty();
+        if !usable {
+            tracing::warn!("Agent '{}' needs tools, which {} can't call; running it on the claude CLI", agent_name, provider_name);
+        }
+        usable
+    });
+
     // Log agent start to live logger
     if let Some(ref logger) = live_logger {
-        let _ = logger.log_agent_start(agent_name, &agent_def.model);
+        let model = provider.map(|(_, p)| p.model.as_str()).unwrap_or(&agent_def.model);
+        let _ = logger.log_agent_start(agent_name, model);
+    }
+
+    if let Some((provider_name, provider)) = provider {
+        let local_dir = match workdir {
+            Some(dir) => dir.to_path_buf(),
+            None => std::env::current_dir()?,
+        };
+        // Tool calls are carried out here, so a configured remote host isn't used
+        if RemoteExecutor::for_dir(&local_dir).is_some() {
+            tracing::warn!("Agent '{}' runs on {}, locally rather than on the remote host", agent_name, provider_name);
+        }
+        println!("  🔌 Running '{}' on {} ({})", agent_name, provider_name, provider.model);
+        if !sampling.is_empty() {
+            println!("     Sampling: {}", sampling.describe());
+        }
+        let provider = provider.clone();
+        let tools = ToolSet::new(&agent_def.tools, &disallowed_tools);
+        if !tools.unsupported.is_empty() {
+            tracing::warn!("Agent '{}' runs on {} without: {}", agent_name, provider_name, tools.unsupported.join(", "));
+        }
+        let system = chat_system_prompt(agent_name, &local_dir, &tools);
+        autoflow_utils::record_agent_started(agent_name, sprint_id);
+
+        let logger = live_logger.clone();
+        let params = sampling.clone();
+        let outcome = tokio::task::spawn_blocking(move || {
+            let backend = chat_backend(&provider, &params);
+            let run = run_chat_agent(backend.as_ref(), &tools, &local_dir, &system, &full_prompt, |call| {
+                if let Some(ref logger) = logger {
+                    let _ = logger.log_event(&crate::live_logger::StreamEvent::ContentBlockStart {
+                        index: 0,
+                        content_block: crate::live_logger::ContentBlock::ToolUse { id: call.id.clone(), name: call.name.clone() },
+                    });
+                }
+            });
+            run.map(|mut run| {
+                run.usage.cost_usd = (run.usage.input_tokens as f64 * provider.input_price_per_mtok
+                    + run.usage.output_tokens as f64 * provider.output_price_per_mtok)
+                    / 1_000_000.0;
+                run
+            })
+        })
+        .await?;
+
+        let (success, output, error, usage, commands) = match outcome {
+            Ok(run) if run.finished => (true, run.output, None, run.usage, run.commands),
+            Ok(run) => (false, run.output, Some("Agent stopped at the turn limit without finishing".to_string()), run.usage, run.commands),
+            Err(e) => (false, String::new(), Some(e.to_string()), Default::default(), Vec::new()),
+        };
+        if let Some(ref logger) = live_logger {
+            let _ = logger.log_text(&output);
+            let _ = logger.log_agent_complete(if success { "end_turn" } else { "error" }, usage.output_tokens as usize);
+        }
+        if let Some(ref logger) = debug_logger {
+            let _ = logger.log_raw_output(agent_name, &output, error.as_deref().unwrap_or_default());
+            let _ = logger.log_agent_end(agent_name, success, error.as_deref());
+        }
+        let applied = Some(sampling.describe()).filter(|s| !s.is_empty());
+        autoflow_utils::record_agent_run(agent_name, sprint_id, success, usage.output_tokens, started.elapsed().as_secs(), applied.as_deref());
+        if usage.calls > 0 {
+            record_agent_cost(agent_name, sprint_id, &usage);
+        }
+        return Ok(AgentResult {
+            success,
+            output: sanitize_output(&output),
+            error,
+            log_path: None,
+            json_log_path: None,
+            commands,
+        });
     }
 
+    if !sampling.is_empty() {
+        tracing::warn!("The claude CLI takes no sampling options; '{}' runs without {}", agent_name, sampling.describe());
+    }
+
+    // Only pass flags the installed CLI understands
+    let caps = crate::claude_cli::claude_capabilities()?;
+
     // Use stream-json format when live logging is enabled for event capture
-    let output_format = if live_logger.is_some() {
+    let output_format = if live_logger.is_some() && caps.supports(CliFlag::StreamJson) {
         "stream-json"
     } else {
         "text"
@@ -284,15 +485,36 @@ async fn execute_agent_internal(
         .arg("--dangerously-skip-permissions"); // For automated execution
 
     // Pass tools to claude CLI
-    if !agent_def.tools.is_empty() {
+    if !agent_def.tools.is_empty() && caps.supports(CliFlag::AllowedTools) {
         cmd.arg("--allowedTools");
         cmd.arg(agent_def.tools.join(" "));
     }
+    if !disallowed_tools.is_empty() && caps.supports(CliFlag::DisallowedTools) {
+        cmd.arg("--disallowedTools");
+        cmd.arg(disallowed_tools.join(" "));
+    }
+
+    // Projects with a configured remote host run the CLI there over SSH
+    let local_dir = match workdir {
+        Some(dir) => dir.to_path_buf(),
+        None => std::env::current_dir()?,
+    };
+    let remote = RemoteExecutor::for_dir(&local_dir);
+
+    // Meta-project sprints run in their repository; plan and docs stay reachable
+    if let Some(dir) = workdir {
+        cmd.current_dir(dir);
+        if caps.supports(CliFlag::AddDir) && remote.is_none() {
+            cmd.arg("--add-dir").arg(std::env::current_dir()?);
+        }
+    }
 
     // stream-json requires --verbose flag with --print
-    if live_logger.is_some() {
+    if output_format == "stream-json" {
         cmd.arg("--verbose");
-        cmd.arg("--include-partial-messages"); // Include partial message chunks for live streaming
+        if caps.supports(CliFlag::IncludePartialMessages) {
+            cmd.arg("--include-partial-messages"); // Include partial message chunks for live streaming
+        }
     }
 
     // Set Docker user to current user to avoid root-owned files
@@ -316,12 +538,26 @@ async fn execute_agent_internal(
         }
     }
 
+    // Package manager stores and the cargo target dir are shared across worktrees
+    if remote.is_none() {
+        if let Some(cache) = autoflow_utils::EnvCache::load(&local_dir) {
+            cmd.envs(cache.shared_env());
+        }
+    }
+
+    if let Some(ref remote) = remote {
+        println!("  🌐 Running '{}' on {}", agent_name, remote.host());
+        remote.push().context("Failed to sync the working copy to the remote host")?;
+        cmd = remote.command(&cmd);
+    }
+
     let mut child = cmd
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()
         .context("Failed to spawn claude CLI")?;
+    autoflow_utils::record_agent_started(agent_name, sprint_id);
 
     // Write full prompt to stdin
     if let Some(mut stdin) = child.stdin.take() {
@@ -341,13 +577,62 @@ async fn execute_agent_internal(
 
     let mut output = String::new();
     let mut output_tokens = 0;
+    let mut meter = UsageMeter::default();
+    let mut commands = Vec::new();
+
+    // Silence is only observable when the CLI streams events; text mode prints at the end
+    let mut watchdog = (output_format == "stream-json").then(StallWatchdog::from_global_config);
+
+    loop {
+        let line = match watchdog {
+            Some(ref mut watchdog) => {
+                match tokio::time::timeout(watchdog.poll_interval(), stdout_reader.next_line()).await {
+                    Ok(line) => {
+                        watchdog.event();
+                        line?
+                    }
+                    Err(_) => match watchdog.check() {
+                        WatchdogVerdict::Healthy => continue,
+                        WatchdogVerdict::Warn(silent_for) => {
+                            tracing::warn!("Agent '{}' has produced no events for {}s", agent_name, silent_for.as_secs());
+                            println!(
+                                "  ⚠ Agent '{}' has produced no events for {}s (possibly stuck in a tool call)",
+                                agent_name,
+                                silent_for.as_secs()
+                            );
+                            continue;
+                        }
+                        WatchdogVerdict::Stalled(silent_for) => {
+                            let _ = child.kill().await;
+                            let stalled = AgentStalled { agent: agent_name.to_string(), silent_for };
+                            record_agent_cost(agent_name, sprint_id, &meter.usage());
+                            if let Some(ref logger) = live_logger {
+                                let _ = logger.log_agent_complete("stalled", output_tokens);
+                            }
+                            if let Some(ref logger) = debug_logger {
+                                let _ = logger.log_agent_end(agent_name, false, Some(&stalled.to_string()));
+                            }
+                            autoflow_utils::record_run_event("agent_stalled", sprint_id, &stalled.to_string());
+                            return Err(stalled.into());
+                        }
+                    },
+                }
+            }
+            None => stdout_reader.next_line().await?,
+        };
+        let Some(line) = line else {
+            break;
+        };
 
-    while let Some(line) = stdout_reader.next_line().await? {
-        tracing::debug!("Agent output: {}", line);
+        tracing::debug!("Agent output: {}", sanitize_output(&line));
 
         // If live logging is enabled and we're using stream-json, parse events
         if live_logger.is_some() && output_format == "stream-json" {
             if let Ok(wrapper_json) = serde_json::from_str::<serde_json::Value>(&line) {
+                commands.extend(bash_commands(&wrapper_json));
+                meter.observe(&wrapper_json);
+                output_tokens = meter.usage().output_tokens as usize;
+
                 // With --verbose, events are wrapped in {"type":"stream_event","event":{...}}
                 let event_json = if wrapper_json.get("type").and_then(|v| v.as_str()) == Some("stream_event") {
                     wrapper_json.get("event").cloned()
@@ -361,13 +646,6 @@ async fn execute_agent_internal(
                         // Parse into StreamEvent
                         if let Ok(event) = serde_json::from_value::<crate::live_logger::StreamEvent>(event_json.clone()) {
                             let _ = logger.log_event(&event);
-
-                            // Track output tokens
-                            if let Some(usage) = event_json.get("usage") {
-                                if let Some(tokens) = usage.get("output_tokens").and_then(|v| v.as_u64()) {
-                                    output_tokens = tokens as usize;
-                                }
-                            }
                         }
                     }
 
@@ -420,7 +698,7 @@ async fn execute_agent_internal(
 
     let mut error_output = String::new();
     while let Some(line) = stderr_reader.next_line().await? {
-        tracing::warn!("Agent stderr: {}", line);
+        tracing::warn!("Agent stderr: {}", sanitize_output(&line));
         error_output.push_str(&line);
         error_output.push('\n');
 
@@ -431,6 +709,17 @@ async fn execute_agent_internal(
     // Wait for completion
     let status = child.wait().await.context("Failed to wait for agent")?;
 
+    // Escape codes and stray carriage returns stop here: only the raw log keeps them
+    if let Some(ref logger) = debug_logger {
+        let _ = logger.log_raw_output(agent_name, &output, &error_output);
+    }
+    let output = sanitize_output(&output);
+    let error_output = sanitize_output(&error_output);
+
+    if let Some(ref remote) = remote {
+        remote.pull().context("Failed to bring the agent's changes back from the remote host")?;
+    }
+
     // Log completion to live logger
     if let Some(ref logger) = live_logger {
         let stop_reason = if status.success() {
@@ -455,6 +744,19 @@ async fn execute_agent_internal(
         }
     }
 
+    autoflow_utils::record_agent_run(
+        agent_name,
+        sprint_id,
+        status.success(),
+        output_tokens as u64,
+        started.elapsed().as_secs(),
+        None,
+    );
+    // Text output carries no usage to account for
+    if output_format == "stream-json" {
+        record_agent_cost(agent_name, sprint_id, &meter.usage());
+    }
+
     Ok(AgentResult {
         success: status.success(),
         output,
@@ -468,9 +770,52 @@ async fn execute_agent_internal(
         },
         log_path: None,
         json_log_path: None,
+        commands,
     })
 }
 
+/// Apply the profile's tool policy and read-only mode to the agent's tools
+///
+/// Returns the tools the agent must be refused, for `--disallowedTools`.
+fn restrict_tools(agent_def: &mut AgentDef) -> Vec<String> {
+    let mut disallowed_tools = autoflow_utils::active_policy()
+        .map(|active| active.policy.disallowed_tools)
+        .unwrap_or_default();
+    // Read-only mode: nothing that can change files
+    if autoflow_data::is_read_only() {
+        for tool in autoflow_data::WRITE_TOOLS {
+            if !disallowed_tools.iter().any(|t| t == tool) {
+                disallowed_tools.push(tool.to_string());
+            }
+        }
+        agent_def.tools.retain(|tool| !autoflow_data::WRITE_TOOLS.iter().any(|w| tool.split('(').next() == Some(w)));
+    }
+    if !disallowed_tools.is_empty() {
+        agent_def.tools.retain(|tool| !disallowed_tools.contains(tool));
+    }
+    disallowed_tools
+}
+
+/// Bash tool calls in a complete assistant message event
+fn bash_commands(event: &serde_json::Value) -> Vec<String> {
+    if event.get("type").and_then(|t| t.as_str()) != Some("assistant") {
+        return Vec::new();
+    }
+    event
+        .pointer("/message/content")
+        .and_then(|c| c.as_array())
+        .map(|blocks| {
+            blocks
+                .iter()
+                .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("tool_use"))
+                .filter(|b| b.get("name").and_then(|n| n.as_str()) == Some("Bash"))
+                .filter_map(|b| b.pointer("/input/command").and_then(|c| c.as_str()))
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Map sprint status to agent name
 pub fn get_agent_for_status(status: &autoflow_data::SprintStatus) -> &'static str {
     use autoflow_data::SprintStatus;
@@ -486,56 +831,24 @@ pub fn get_agent_for_status(status: &autoflow_data::SprintStatus) -> &'static st
         SprintStatus::WriteE2eTests => "e2e-writer",
         SprintStatus::RunE2eTests => "e2e-test-runner",
         SprintStatus::E2eFix => "e2e-fixer",
+        SprintStatus::Deploy => "deployer",
+        SprintStatus::SmokeTest => "none",
+        SprintStatus::Rollback => "none",
         SprintStatus::Complete => "health-check",
         SprintStatus::Done => "done",
         SprintStatus::Blocked => "debug-blocker",
+        SprintStatus::Paused | SprintStatus::Skipped | SprintStatus::Cancelled => "none",
     }
 }
 
-/// Extract a section from markdown content by heading
-fn extract_markdown_section(content: &str, section_name: &str) -> Option<String> {
-    let lines: Vec<&str> = content.lines().collect();
-    let mut in_section = false;
-    let mut section_content = String::new();
-    let mut section_level = 0;
-
-    for line in lines {
-        // Check if this is a heading
-        if line.starts_with('#') {
-            let heading_level = line.chars().take_while(|c| *c == '#').count();
-            let heading_text = line.trim_start_matches('#').trim();
-
-            // Check if this is our target section
-            if heading_text.eq_ignore_ascii_case(section_name) {
-                in_section = true;
-                section_level = heading_level;
-                section_content.push_str(line);
-                section_content.push('\n');
-                continue;
-            }
-
-            // If we're in a section and hit a same-or-higher level heading, we're done
-            if in_section && heading_level <= section_level {
-                break;
-            }
-        }
-
-        // Add line if we're in the target section
-        if in_section {
-            section_content.push_str(line);
-            section_content.push('\n');
-        }
-    }
-
-    if section_content.is_empty() {
-        None
-    } else {
-        Some(section_content)
-    }
-}
+/// Failure reports, relative to the directory an agent works in
+const FAILURES_DIR: &str = ".autoflow/.failures";
 
 /// Get context for agent execution with full task details and referenced documentation
-pub fn build_agent_context(sprint: &autoflow_data::Sprint) -> String {
+///
+/// `workdir` is where the agent runs (the project, or the sprint's repo checkout);
+/// failure reports are looked up there.
+pub fn build_agent_context(sprint: &autoflow_data::Sprint, workdir: &Path) -> String {
     // Build detailed task information
     let tasks_detail = sprint
         .tasks
@@ -577,14 +890,28 @@ pub fn build_agent_context(sprint: &autoflow_data::Sprint) -> String {
                 }
             }
 
+            if !task.files.is_empty() {
+                task_str.push_str("\n**Files:**\n");
+                for file in &task.files {
+                    task_str.push_str(&format!("- {}\n", file));
+                }
+            }
+
+            if !task.review_findings.is_empty() {
+                task_str.push_str("\n**Open Review Findings:**\n");
+                for finding in &task.review_findings {
+                    task_str.push_str(&format!("- {}\n", finding));
+                }
+            }
+
             task_str
         })
         .collect::<Vec<_>>()
         .join("\n");
 
-    // Load and include referenced documentation sections
+    // Load referenced documentation: matched sections inline, otherwise only a table of contents
     let mut doc_sections = String::new();
-    let unique_docs: std::collections::HashSet<String> = sprint
+    let unique_docs: std::collections::BTreeSet<String> = sprint
         .tasks
         .iter()
         .flat_map(|t| t.docs.iter().cloned())
@@ -592,29 +919,56 @@ pub fn build_agent_context(sprint: &autoflow_data::Sprint) -> String {
 
     if !unique_docs.is_empty() {
         doc_sections.push_str("\n\n# Referenced Documentation\n");
+        let embeddings = autoflow_data::Config::global().map(|c| c.embeddings).unwrap_or_default();
 
         for doc_ref in unique_docs {
             // Parse reference: "BUILD_SPEC.md#TechStack" -> ("BUILD_SPEC.md", Some("TechStack"))
-            let parts: Vec<&str> = doc_ref.split('#').collect();
-            let filename = parts[0];
-            let section = parts.get(1);
-
-            let doc_path = format!(".autoflow/docs/{}", filename);
-
-            if let Ok(content) = std::fs::read_to_string(&doc_path) {
-                if let Some(section_name) = section {
-                    // Extract specific section
-                    if let Some(section_content) = extract_markdown_section(&content, section_name) {
-                        doc_sections.push_str(&format!("\n## {} (from {})\n\n{}\n", section_name, filename, section_content));
-                    } else {
-                        doc_sections.push_str(&format!("\n## {} (section not found in {})\n\n", section_name, filename));
-                    }
-                } else {
-                    // Include entire file if no section specified
-                    doc_sections.push_str(&format!("\n## {}\n\n{}\n", filename, content));
+            let (filename, section) = match doc_ref.split_once('#') {
+                Some((file, section)) => (file, Some(section)),
+                None => (doc_ref.as_str(), None),
+            };
+            let doc_path = format!("{}/{}", DOCS_DIR, filename);
+
+            let Some(index) = doc_index(&doc_path) else {
+                doc_sections.push_str(&format!("\n## {} (file not found at {})\n\n", doc_ref, doc_path));
+                continue;
+            };
+
+            if let Some(heading) = section.and_then(|name| index.find_section(name)) {
+                doc_sections.push_str(&format!(
+                    "\n## {} (from {})\n\n{}\n",
+                    heading.title,
+                    filename,
+                    index.section_text(heading)
+                ));
+            } else if index.headings.is_empty() {
+                // Nothing to index - the doc is small enough to inline
+                doc_sections.push_str(&format!("\n## {}\n\n{}\n", filename, index.full_text()));
+            } else if let Some(matches) = section
+                .filter(|_| embeddings.provider.is_some())
+                .and_then(|name| closest_sections_for(&embeddings, &index, name, &doc_ref, sprint))
+            {
+                // The anchor drifted: inline the sections closest to what the tasks need
+                for (heading, score) in matches {
+                    doc_sections.push_str(&format!(
+                        "\n## {} (from {}, closest match for '{}', similarity {:.2})\n\n{}\n",
+                        heading.title,
+                        filename,
+                        section.unwrap_or_default(),
+                        score,
+                        index.section_text(heading)
+                    ));
                 }
             } else {
-                doc_sections.push_str(&format!("\n## {} (file not found at {})\n\n", doc_ref, doc_path));
+                match section {
+                    Some(name) => doc_sections.push_str(&format!("\n## {} (section '{}' not found)\n\n", filename, name)),
+                    None => doc_sections.push_str(&format!("\n## {} (contents)\n\n", filename)),
+                }
+                doc_sections.push_str(&index.table_of_contents());
+                doc_sections.push_str(&format!(
+                    "\nRead only the sections of `{}` relevant to your tasks.\n",
+                    doc_path
+                ));
             }
         }
     }
@@ -625,9 +979,9 @@ pub fn build_agent_context(sprint: &autoflow_data::Sprint) -> String {
     // First check sprint.failure_reports field (persisted in SPRINTS.yml)
     if !sprint.failure_reports.is_empty() {
         for report_path in &sprint.failure_reports {
-            let path = std::path::Path::new(report_path);
+            let path = workdir.join(report_path);
             if path.exists() {
-                failure_reports.push_str(&format!("\n## Failure Report: {}\n\n", path.file_name().unwrap().to_str().unwrap()));
+                failure_reports.push_str(&format!("\n## Failure Report: {}\n\n", path.file_name().unwrap_or_default().to_string_lossy()));
                 failure_reports.push_str(&format!("**Path**: `{}`\n\n", report_path));
                 failure_reports.push_str("This file contains detailed failure information from the previous test/review run.\n");
                 failure_reports.push_str("**READ THIS FILE FIRST** to understand what failed and what needs to be fixed.\n\n");
@@ -635,7 +989,7 @@ pub fn build_agent_context(sprint: &autoflow_data::Sprint) -> String {
         }
     } else {
         // Fallback: scan .autoflow/.failures/ directory
-        let failure_dir = std::path::PathBuf::from(".autoflow/.failures");
+        let failure_dir = workdir.join(FAILURES_DIR);
         if failure_dir.exists() {
             let possible_reports = vec![
                 format!("sprint-{}-unit-tests.md", sprint.id),
@@ -730,11 +1084,6 @@ Run tests for the code implemented in this sprint.
 
 1. Identify and run the appropriate tests for this sprint
 2. Report all test results clearly
-3. **REQUIRED**: End your response with one of these markers:
-   - `TEST_RESULT: PASSED` if all tests pass
-   - `TEST_RESULT: FAILED` if any tests fail
-
-The orchestrator uses this marker to determine workflow progression.
 "#,
         sprint.id,
         sprint.goal,
@@ -742,18 +1091,50 @@ The orchestrator uses this marker to determine workflow progression.
     )
 }
 
+/// Sections of a doc closest to an unmatched `#section` and the tasks citing it
+///
+/// None when nothing is similar enough or the embedding call fails, so the
+/// caller falls back to the table of contents.
+fn closest_sections_for<'a>(
+    config: &autoflow_data::EmbeddingsConfig,
+    index: &'a crate::doc_index::DocIndex,
+    section: &str,
+    doc_ref: &str,
+    sprint: &autoflow_data::Sprint,
+) -> Option<Vec<(&'a crate::doc_index::DocHeading, f32)>> {
+    let mut query = section.to_string();
+    for task in sprint.tasks.iter().filter(|t| t.docs.iter().any(|d| d == doc_ref)) {
+        query.push('\n');
+        query.push_str(&task.title);
+        if let Some(ref description) = task.description {
+            query.push('\n');
+            query.push_str(description);
+        }
+    }
+    match crate::doc_embeddings::closest_sections(config, index, &query) {
+        Ok(matches) if !matches.is_empty() => Some(matches),
+        Ok(_) => None,
+        Err(e) => {
+            tracing::warn!("Embedding lookup for {} failed: {}", doc_ref, e);
+            None
+        }
+    }
+}
+
 /// Build lightweight context for fixer agents (review-fixer, unit-fixer, e2e-fixer, integration-fixer)
 /// Fixer agents only need the sprint goal and failure reports, not full task details
-pub fn build_fixer_context(sprint: &autoflow_data::Sprint) -> String {
+///
+/// Reports are looked up in `workdir`, where the fixer runs.
+pub fn build_fixer_context(sprint: &autoflow_data::Sprint, workdir: &Path) -> String {
     // Check for failure reports
     let mut failure_reports = String::new();
 
     // Check sprint.failure_reports field (persisted in SPRINTS.yml)
     if !sprint.failure_reports.is_empty() {
         for report_path in &sprint.failure_reports {
-            let path = std::path::Path::new(report_path);
+            let path = workdir.join(report_path);
             if path.exists() {
-                failure_reports.push_str(&format!("\n## Failure Report: {}\n\n", path.file_name().unwrap().to_str().unwrap()));
+                failure_reports.push_str(&format!("\n## Failure Report: {}\n\n", path.file_name().unwrap_or_default().to_string_lossy()));
                 failure_reports.push_str(&format!("**Path**: `{}`\n\n", report_path));
                 failure_reports.push_str("This file contains detailed failure information from the previous test/review run.\n");
                 failure_reports.push_str("**READ THIS FILE FIRST** to understand what failed and what needs to be fixed.\n\n");
@@ -766,10 +1147,14 @@ pub fn build_fixer_context(sprint: &autoflow_data::Sprint) -> String {
             format!("sprint-{}-unit-tests.md", sprint.id),
             format!("sprint-{}-integration-tests.md", sprint.id),
             format!("sprint-{}-e2e-tests.md", sprint.id),
+            format!("sprint-{}-migrations.md", sprint.id),
+            format!("sprint-{}-fixtures.md", sprint.id),
+            format!("sprint-{}-integration-points.md", sprint.id),
+            format!("sprint-{}-health.md", sprint.id),
         ];
 
         for pattern in &patterns {
-            let report_path = std::path::Path::new(".autoflow/.failures").join(pattern);
+            let report_path = workdir.join(FAILURES_DIR).join(pattern);
             if report_path.exists() {
                 let report_name = pattern;
                 failure_reports.push_str(&format!("\n## Failure Report: {}\n\n", report_name));
@@ -784,6 +1169,18 @@ pub fn build_fixer_context(sprint: &autoflow_data::Sprint) -> String {
         failure_reports = "\n**No failure reports found.**\n\nCheck `.autoflow/.failures/` directory for any reports.\n".to_string();
     }
 
+    // Review findings, with the acceptance criteria they leave unmet
+    let flagged: Vec<_> = sprint.tasks.iter().filter(|t| !t.review_findings.is_empty()).collect();
+    if !flagged.is_empty() {
+        failure_reports.push_str("\n## Review Findings by Task\n");
+        for task in flagged {
+            failure_reports.push_str(&format!("\n### {}: {}\n", task.id, task.title));
+            for finding in &task.review_findings {
+                failure_reports.push_str(&format!("- {}\n", finding));
+            }
+        }
+    }
+
     format!(
         r#"Sprint #{}: {}
 
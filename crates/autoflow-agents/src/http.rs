@@ -0,0 +1,96 @@
+/// JSON requests to model APIs, shared by the chat backends and embeddings
+///
+/// Each request runs on reqwest's blocking client in a thread of its own, so
+/// callers can be plain sync code whether or not an async runtime is driving
+/// the thread they are on.
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::Value;
+use std::time::Duration;
+
+/// POST `body` to `url` with extra `headers` and parse the JSON answer
+///
+/// Non-2xx answers are errors carrying the response body, which is where the
+/// APIs explain what was wrong with the request.
+pub(crate) fn post_json(url: &str, headers: &[(&str, String)], body: &Value, timeout: Duration) -> Result<Value> {
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                let client = reqwest::blocking::Client::builder().timeout(timeout).build().context("Failed to build HTTP client")?;
+                let mut request = client.post(url).json(body);
+                for (name, value) in headers {
+                    request = request.header(*name, value);
+                }
+                let response = request.send().with_context(|| format!("Request to {} failed", url))?;
+                let status = response.status();
+                let text = response.text().with_context(|| format!("Failed to read the response from {}", url))?;
+                if !status.is_success() {
+                    bail!("Request to {} failed: {} {}", url, status, text.trim());
+                }
+                serde_json::from_str(&text).with_context(|| format!("Unreadable response from {}", url))
+            })
+            .join()
+            .map_err(|_| anyhow!("Request to {} panicked", url))?
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Serve one request with `status` and `body`, returning the request as received
+    fn serve_once(status: &str, body: &str) -> (String, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/v1/chat", listener.local_addr().unwrap());
+        let response = format!("HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body);
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            // Headers, then as much body as Content-Length says
+            loop {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some(end) = text.find("\r\n\r\n") {
+                    let length = text[..end]
+                        .lines()
+                        .find_map(|line| line.to_ascii_lowercase().strip_prefix("content-length: ").map(|n| n.trim().parse::<usize>().unwrap()))
+                        .unwrap_or(0);
+                    if request.len() >= end + 4 + length {
+                        break;
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_post_json_sends_headers_and_body() {
+        let (url, server) = serve_once("200 OK", r#"{"ok": true}"#);
+        let headers = [("x-goog-api-key", "secret".to_string())];
+        let answer = post_json(&url, &headers, &serde_json::json!({"model": "m"}), Duration::from_secs(10)).unwrap();
+        assert_eq!(answer, serde_json::json!({"ok": true}));
+
+        let request = server.join().unwrap().to_ascii_lowercase();
+        assert!(request.starts_with("post /v1/chat "));
+        assert!(request.contains("x-goog-api-key: secret\r\n"));
+        assert!(request.contains("content-type: application/json\r\n"));
+        assert!(request.ends_with(r#"{"model":"m"}"#));
+    }
+
+    #[tokio::test]
+    async fn test_post_json_reports_api_errors_inside_a_runtime() {
+        let (url, server) = serve_once("400 Bad Request", r#"{"error": "bad model"}"#);
+        let err = post_json(&url, &[], &serde_json::json!({}), Duration::from_secs(10)).unwrap_err();
+        assert!(err.to_string().contains("400 Bad Request") && err.to_string().contains("bad model"));
+        server.join().unwrap();
+    }
+}
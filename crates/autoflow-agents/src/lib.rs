@@ -1,5 +1,26 @@
+pub mod agent_overrides;
+pub mod backends;
+pub mod claude_cli;
+pub mod cost;
+pub mod doc_embeddings;
+pub mod doc_index;
 pub mod executor;
+pub mod http;
 pub mod live_logger;
+pub mod local_tools;
+pub mod protocol;
+pub mod remote;
+pub mod watchdog;
 
-pub use executor::{execute_agent, execute_agent_with_retry, get_agent_for_status, build_agent_context, build_test_runner_context, build_fixer_context, AgentResult};
-pub use live_logger::{LiveLogger, StreamEvent};
+pub use agent_overrides::{agent_override_path, compose_agent_prompt, load_agent_override, AGENT_OVERRIDES_DIR};
+pub use executor::{estimate_tokens, load_agent, AgentDef, execute_agent, execute_agent_with_model, execute_agent_with_retry, get_agent_for_status, build_agent_context, build_test_runner_context, build_fixer_context, AgentResult};
+pub use backends::{chat_backend, complete_text, run_chat_agent, ChatBackend, ChatMessage, ChatRun, GeminiBackend, ModelTurn, OpenAiBackend, ToolCall};
+pub use claude_cli::{is_known_tool, suggest_tool, KNOWN_TOOLS, claude_capabilities, detect_claude_version, parse_claude_version, ClaudeCapabilities, CliFlag, MIN_CLAUDE_VERSION};
+pub use cost::{record_agent_cost, CostTracker, RunCost, TokenUsage, UsageMeter, COSTS_PATH};
+pub use doc_embeddings::closest_sections;
+pub use doc_index::{doc_index, warm_doc_index, DocIndex};
+pub use local_tools::{ToolSet, ToolSpec, LOCAL_TOOLS};
+pub use live_logger::{is_segment, log_segments, segment_path, LiveLogger, StreamEvent};
+pub use protocol::{agent_protocol, check_protocol, declared_protocol, incompatible_agents, AgentProtocol, Marker, FAILURE_REPORTS_DIR, PROTOCOL_VERSION};
+pub use remote::RemoteExecutor;
+pub use watchdog::{AgentStalled, StallWatchdog, WatchdogVerdict};
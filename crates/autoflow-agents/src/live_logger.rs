@@ -1,8 +1,10 @@
 use anyhow::Result;
+use autoflow_data::LiveLogConfig;
 use serde::{Deserialize, Serialize};
 use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 use std::sync::{Arc, Mutex};
 
 /// Claude API streaming event types
@@ -76,15 +78,79 @@ pub struct ErrorInfo {
 }
 
 /// Live logger that writes streaming events to JSONL file
+///
+/// With a size cap the file is rolled into numbered segments (`<log>.1.jsonl`,
+/// `<log>.2.jsonl`, ...) and the path being written always holds the newest events.
+#[derive(Clone)]
 pub struct LiveLogger {
-    file: Arc<Mutex<File>>,
+    file: Arc<Mutex<LogFile>>,
     log_path: PathBuf,
+    max_bytes: u64,
+    compress: bool,
+}
+
+/// The open tail of the log and how far it has grown
+struct LogFile {
+    file: File,
+    written: u64,
+    segments: u32,
+}
+
+/// Path of a rolled segment of the log at `path`
+pub fn segment_path(path: &Path, segment: u32) -> PathBuf {
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    path.with_file_name(format!("{}.{}.jsonl", stem, segment))
+}
+
+/// True for rolled segments (`<log>.<n>.jsonl`, possibly `.zst`), as opposed to a log's tail
+pub fn is_segment(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let name = name.strip_suffix(".zst").unwrap_or(&name);
+    name.strip_suffix(".jsonl")
+        .and_then(|stem| stem.rsplit_once('.'))
+        .is_some_and(|(_, n)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Rolled segments of the log at `path`, oldest first
+pub fn log_segments(path: &Path) -> Vec<PathBuf> {
+    let mut segments = Vec::new();
+    for n in 1.. {
+        let plain = segment_path(path, n);
+        let compressed = PathBuf::from(format!("{}.zst", plain.display()));
+        if plain.exists() {
+            segments.push(plain);
+        } else if compressed.exists() {
+            segments.push(compressed);
+        } else {
+            break;
+        }
+    }
+    segments
+}
+
+/// Compress a finished segment in the background; left as is without `zstd`
+fn compress_segment(path: PathBuf) {
+    std::thread::spawn(move || {
+        let status = Command::new("zstd")
+            .args(["-q", "--rm", "-f"])
+            .arg(&path)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        if !status.is_ok_and(|s| s.success()) {
+            tracing::debug!("Could not compress {} (is zstd installed?)", path.display());
+        }
+    });
 }
 
 impl LiveLogger {
     /// Create a new live logger for an agent execution
     pub fn new(agent_name: &str, sprint_id: Option<u32>) -> Result<Self> {
-        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+        // Prefix with the run id so `autoflow logs --run-id` can find a run's logs
+        let timestamp = match autoflow_utils::current_run_id() {
+            Some(run_id) => format!("{}_{}", chrono::Local::now().format("%Y%m%d_%H%M%S"), run_id),
+            None => chrono::Local::now().format("%Y%m%d_%H%M%S").to_string(),
+        };
 
         // Organize by sprint folder, then agent name with timestamp
         let log_path = if let Some(id) = sprint_id {
@@ -94,6 +160,11 @@ impl LiveLogger {
             PathBuf::from(".autoflow/.debug/live").join(format!("{}_{}.jsonl", timestamp, agent_name))
         };
 
+        let config = autoflow_data::Config::global().map(|c| c.live_logs).unwrap_or_default();
+        Self::open(log_path, &config)
+    }
+
+    fn open(log_path: PathBuf, config: &LiveLogConfig) -> Result<Self> {
         // Ensure directory exists
         if let Some(parent) = log_path.parent() {
             std::fs::create_dir_all(parent)?;
@@ -101,22 +172,41 @@ impl LiveLogger {
 
         let file = OpenOptions::new()
             .create(true)
-            .write(true)
             .append(true)
             .open(&log_path)?;
+        let written = file.metadata().map(|m| m.len()).unwrap_or(0);
 
         Ok(Self {
-            file: Arc::new(Mutex::new(file)),
+            file: Arc::new(Mutex::new(LogFile { file, written, segments: 0 })),
             log_path,
+            max_bytes: config.max_file_mb * 1024 * 1024,
+            compress: config.compress,
         })
     }
 
     /// Log a streaming event
     pub fn log_event(&self, event: &StreamEvent) -> Result<()> {
-        let mut file = self.file.lock().unwrap();
-        let json = serde_json::to_string(event)?;
-        writeln!(file, "{}", json)?;
-        file.flush()?;
+        let mut log = self.file.lock().unwrap();
+        let line = format!("{}\n", serde_json::to_string(event)?);
+        if self.max_bytes > 0 && log.written > 0 && log.written + line.len() as u64 > self.max_bytes {
+            self.roll(&mut log)?;
+        }
+        log.file.write_all(line.as_bytes())?;
+        log.file.flush()?;
+        log.written += line.len() as u64;
+        Ok(())
+    }
+
+    /// Move the full log into the next segment and start an empty one in its place
+    fn roll(&self, log: &mut LogFile) -> Result<()> {
+        log.segments += 1;
+        let segment = segment_path(&self.log_path, log.segments);
+        std::fs::rename(&self.log_path, &segment)?;
+        log.file = OpenOptions::new().create(true).append(true).open(&self.log_path)?;
+        log.written = 0;
+        if self.compress {
+            compress_segment(segment);
+        }
         Ok(())
     }
 
@@ -132,7 +222,7 @@ impl LiveLogger {
     }
 
     /// Log agent start
-    pub fn log_agent_start(&self, agent_name: &str, model: &str) -> Result<()> {
+    pub fn log_agent_start(&self, _agent_name: &str, model: &str) -> Result<()> {
         let event = StreamEvent::MessageStart {
             message: MessageInfo {
                 id: format!("msg_{}", chrono::Utc::now().timestamp()),
@@ -175,6 +265,35 @@ mod tests {
         assert!(logger.is_ok());
     }
 
+    #[test]
+    fn test_log_rolls_into_segments() {
+        let dir = std::env::temp_dir().join(format!("autoflow-live-roll-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("20250101_000000_code-implementer.jsonl");
+        let mut logger = LiveLogger::open(path.clone(), &LiveLogConfig::default()).unwrap();
+        logger.max_bytes = 200;
+
+        for i in 0..12 {
+            logger.log_text(&format!("chunk {}", i)).unwrap();
+        }
+
+        let segments = log_segments(&path);
+        assert!(segments.len() >= 2);
+        assert!(segments.iter().all(|s| is_segment(s)));
+        assert!(!is_segment(&path));
+        assert!(std::fs::metadata(&path).unwrap().len() <= 200);
+
+        // Nothing is lost: segments then the tail hold every event in order
+        let mut lines = Vec::new();
+        for file in segments.iter().chain(std::iter::once(&path)) {
+            lines.extend(std::fs::read_to_string(file).unwrap().lines().map(String::from));
+        }
+        assert_eq!(lines.len(), 12);
+        assert!(lines[0].contains("chunk 0") && lines[11].contains("chunk 11"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
     #[test]
     fn test_log_event() {
         let logger = LiveLogger::new("test-agent", Some(1)).unwrap();
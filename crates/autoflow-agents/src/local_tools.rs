@@ -0,0 +1,427 @@
+/// Agent tools carried out locally, for agents on a chat API backend
+///
+/// Chat APIs only describe tool calls. These run them in the agent's working
+/// directory under the names the claude CLI uses, so agent definitions work
+/// unchanged: Read, Write, Edit, Bash, Glob, Grep and LS. Bash permission
+/// rules such as `Bash(git:*)` are honoured; any other tool an agent lists
+/// is left out and reported, and the agent runs without it.
+use serde_json::{json, Value};
+use std::io::Read as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Tools available on API backends
+pub const LOCAL_TOOLS: &[&str] = &["Read", "Write", "Edit", "Bash", "Glob", "Grep", "LS"];
+
+/// Characters of a tool result sent back to the model
+const MAX_RESULT_CHARS: usize = 30_000;
+
+/// Lines Read returns when no limit is given
+const DEFAULT_READ_LINES: usize = 2000;
+
+/// Paths Glob returns
+const MAX_GLOB_MATCHES: usize = 200;
+
+/// Seconds a Bash command may run when the call doesn't say
+const DEFAULT_BASH_TIMEOUT_SECS: u64 = 120;
+
+/// Directories Glob never descends into
+const SKIP_DIRS: &[&str] = &[".git", "node_modules", "target", "vendor"];
+
+/// A tool as offered to the model
+#[derive(Debug, Clone)]
+pub struct ToolSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// JSON schema of the input
+    pub parameters: Value,
+}
+
+/// Tools one agent may call, with the Bash commands it is limited to
+#[derive(Debug, Clone, Default)]
+pub struct ToolSet {
+    tools: Vec<&'static str>,
+    /// From `Bash(...)` rules; empty allows any command
+    bash_allowed: Vec<String>,
+    /// From disallowed `Bash(...)` rules
+    bash_denied: Vec<String>,
+    /// Listed tools there is no local version of
+    pub unsupported: Vec<String>,
+}
+
+impl ToolSet {
+    /// From the agent's `tools:` list (empty means every local tool) less `disallowed`
+    pub fn new(tools: &[String], disallowed: &[String]) -> Self {
+        let mut set = ToolSet::default();
+        if tools.is_empty() {
+            set.tools = LOCAL_TOOLS.to_vec();
+        }
+        for tool in tools {
+            let (name, rule) = split_rule(tool);
+            match (LOCAL_TOOLS.iter().find(|t| **t == name), rule) {
+                (Some(&name), None) => set.add(name),
+                (Some(&"Bash"), Some(rule)) => {
+                    set.add("Bash");
+                    set.bash_allowed.push(rule.to_string());
+                }
+                _ => set.unsupported.push(tool.clone()),
+            }
+        }
+        for tool in disallowed {
+            match split_rule(tool) {
+                ("Bash", Some(rule)) => set.bash_denied.push(rule.to_string()),
+                (name, None) => set.tools.retain(|t| *t != name),
+                _ => {}
+            }
+        }
+        set
+    }
+
+    fn add(&mut self, name: &'static str) {
+        if !self.tools.contains(&name) {
+            self.tools.push(name);
+        }
+    }
+
+    pub fn names(&self) -> &[&'static str] {
+        &self.tools
+    }
+
+    pub fn specs(&self) -> Vec<ToolSpec> {
+        self.tools.iter().filter_map(|name| tool_spec(name)).collect()
+    }
+
+    /// Carry out one call; failures come back as text for the model to read
+    pub fn call(&self, workdir: &Path, name: &str, input: &Value) -> String {
+        if !self.tools.contains(&name) {
+            return format!("Error: tool {} is not available to this agent", name);
+        }
+        let result = match name {
+            "Read" => read(workdir, input),
+            "Write" => write(workdir, input),
+            "Edit" => edit(workdir, input),
+            "Bash" => self.bash(workdir, input),
+            "Glob" => glob(workdir, input),
+            "Grep" => grep(workdir, input),
+            "LS" => ls(workdir, input),
+            _ => Err(format!("unknown tool {}", name)),
+        };
+        let text = result.unwrap_or_else(|e| format!("Error: {}", e));
+        truncate(text)
+    }
+
+    /// Whether the Bash rules let `command` run
+    pub fn allows_command(&self, command: &str) -> bool {
+        let command = command.trim();
+        (self.bash_allowed.is_empty() || self.bash_allowed.iter().any(|rule| rule_matches(rule, command)))
+            && !self.bash_denied.iter().any(|rule| rule_matches(rule, command))
+    }
+
+    fn bash(&self, workdir: &Path, input: &Value) -> Result<String, String> {
+        let command = str_arg(input, "command")?;
+        if !self.allows_command(command) {
+            return Err(format!("command not permitted for this agent: {}", command));
+        }
+        let timeout = input.get("timeout").and_then(|v| v.as_u64()).map(|ms| ms / 1000).unwrap_or(DEFAULT_BASH_TIMEOUT_SECS);
+        run_shell(workdir, command, Duration::from_secs(timeout.max(1)))
+    }
+}
+
+/// `Bash(git:*)` -> ("Bash", Some("git:*"))
+fn split_rule(tool: &str) -> (&str, Option<&str>) {
+    match tool.split_once('(') {
+        Some((name, rest)) => (name.trim(), Some(rest.trim_end_matches(')'))),
+        None => (tool.trim(), None),
+    }
+}
+
+/// `prefix:*` matches commands starting with the prefix; anything else the exact command
+fn rule_matches(rule: &str, command: &str) -> bool {
+    match rule.strip_suffix(":*") {
+        Some(prefix) => command == prefix || command.starts_with(&format!("{} ", prefix)),
+        None => command == rule,
+    }
+}
+
+fn tool_spec(name: &str) -> Option<ToolSpec> {
+    let (description, parameters) = match name {
+        "Read" => (
+            "Read a text file. Returns its lines numbered from 1.",
+            json!({"type": "object", "properties": {
+                "file_path": {"type": "string", "description": "Path to the file"},
+                "offset": {"type": "integer", "description": "Line to start at (1-based)"},
+                "limit": {"type": "integer", "description": "Number of lines to read"}
+            }, "required": ["file_path"]}),
+        ),
+        "Write" => (
+            "Create or overwrite a file with the given content.",
+            json!({"type": "object", "properties": {
+                "file_path": {"type": "string"},
+                "content": {"type": "string"}
+            }, "required": ["file_path", "content"]}),
+        ),
+        "Edit" => (
+            "Replace old_string with new_string in a file. old_string must occur exactly once unless replace_all is true.",
+            json!({"type": "object", "properties": {
+                "file_path": {"type": "string"},
+                "old_string": {"type": "string"},
+                "new_string": {"type": "string"},
+                "replace_all": {"type": "boolean"}
+            }, "required": ["file_path", "old_string", "new_string"]}),
+        ),
+        "Bash" => (
+            "Run a shell command in the project directory. Returns its output and exit code.",
+            json!({"type": "object", "properties": {
+                "command": {"type": "string"},
+                "timeout": {"type": "integer", "description": "Timeout in milliseconds"}
+            }, "required": ["command"]}),
+        ),
+        "Glob" => (
+            "Find files whose path matches a glob such as src/**/*.rs.",
+            json!({"type": "object", "properties": {
+                "pattern": {"type": "string"},
+                "path": {"type": "string", "description": "Directory to search (default: project root)"}
+            }, "required": ["pattern"]}),
+        ),
+        "Grep" => (
+            "Search file contents for an extended regular expression. Returns matching lines with file and line number.",
+            json!({"type": "object", "properties": {
+                "pattern": {"type": "string"},
+                "path": {"type": "string", "description": "File or directory to search (default: project root)"},
+                "glob": {"type": "string", "description": "Only files whose name matches, e.g. *.ts"}
+            }, "required": ["pattern"]}),
+        ),
+        "LS" => (
+            "List a directory. Subdirectories end in /.",
+            json!({"type": "object", "properties": {
+                "path": {"type": "string"}
+            }, "required": ["path"]}),
+        ),
+        _ => return None,
+    };
+    LOCAL_TOOLS.iter().find(|t| **t == name).map(|&name| ToolSpec { name, description, parameters })
+}
+
+fn str_arg<'a>(input: &'a Value, key: &str) -> Result<&'a str, String> {
+    input.get(key).and_then(|v| v.as_str()).ok_or_else(|| format!("missing {}", key))
+}
+
+fn resolve(workdir: &Path, path: &str) -> PathBuf {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        workdir.join(path)
+    }
+}
+
+fn read(workdir: &Path, input: &Value) -> Result<String, String> {
+    let path = resolve(workdir, str_arg(input, "file_path")?);
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let offset = input.get("offset").and_then(|v| v.as_u64()).unwrap_or(1).max(1) as usize;
+    let limit = input.get("limit").and_then(|v| v.as_u64()).map(|l| l as usize).unwrap_or(DEFAULT_READ_LINES);
+    let lines: Vec<String> = content
+        .lines()
+        .enumerate()
+        .skip(offset - 1)
+        .take(limit)
+        .map(|(i, line)| format!("{:>6}\t{}", i + 1, line))
+        .collect();
+    Ok(if lines.is_empty() { "(empty)".to_string() } else { lines.join("\n") })
+}
+
+fn write(workdir: &Path, input: &Value) -> Result<String, String> {
+    let path = resolve(workdir, str_arg(input, "file_path")?);
+    let content = str_arg(input, "content")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(&path, content).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(format!("Wrote {} ({} bytes)", path.display(), content.len()))
+}
+
+fn edit(workdir: &Path, input: &Value) -> Result<String, String> {
+    let path = resolve(workdir, str_arg(input, "file_path")?);
+    let old = str_arg(input, "old_string")?;
+    let new = str_arg(input, "new_string")?;
+    let replace_all = input.get("replace_all").and_then(|v| v.as_bool()).unwrap_or(false);
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let count = if old.is_empty() { 0 } else { content.matches(old).count() };
+    let updated = match count {
+        0 => return Err(format!("old_string not found in {}", path.display())),
+        1 => content.replacen(old, new, 1),
+        _ if replace_all => content.replace(old, new),
+        n => return Err(format!("old_string occurs {} times in {}; add context or set replace_all", n, path.display())),
+    };
+    std::fs::write(&path, updated).map_err(|e| e.to_string())?;
+    Ok(format!("Edited {} ({} replacement{})", path.display(), count, if count == 1 { "" } else { "s" }))
+}
+
+/// Run `command` with `sh -c`, killing it at `timeout`
+fn run_shell(workdir: &Path, command: &str, timeout: Duration) -> Result<String, String> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(workdir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to run sh: {}", e))?;
+
+    // Drain both pipes while waiting, so a chatty command can't block on a full pipe
+    let drain = |pipe: Option<Box<dyn std::io::Read + Send>>| {
+        std::thread::spawn(move || {
+            let mut text = String::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_string(&mut text);
+            }
+            text
+        })
+    };
+    let stdout = drain(child.stdout.take().map(|p| Box::new(p) as Box<dyn std::io::Read + Send>));
+    let stderr = drain(child.stderr.take().map(|p| Box::new(p) as Box<dyn std::io::Read + Send>));
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) => break Some(status),
+            None if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            None => std::thread::sleep(Duration::from_millis(50)),
+        }
+    };
+
+    let mut output = stdout.join().unwrap_or_default();
+    let errors = stderr.join().unwrap_or_default();
+    if !errors.is_empty() {
+        if !output.is_empty() && !output.ends_with('\n') {
+            output.push('\n');
+        }
+        output.push_str(&errors);
+    }
+    match status {
+        Some(status) => Ok(format!("{}\n[exit code {}]", output.trim_end(), status.code().unwrap_or(-1))),
+        None => Ok(format!("{}\n[killed after {}s]", output.trim_end(), timeout.as_secs())),
+    }
+}
+
+fn glob(workdir: &Path, input: &Value) -> Result<String, String> {
+    let pattern: Vec<char> = str_arg(input, "pattern")?.trim_start_matches("./").chars().collect();
+    let root = input.get("path").and_then(|v| v.as_str()).map(|p| resolve(workdir, p)).unwrap_or_else(|| workdir.to_path_buf());
+    let mut matches = Vec::new();
+    let mut pending = vec![root.clone()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if path.is_dir() {
+                if !SKIP_DIRS.contains(&name.as_str()) {
+                    pending.push(path);
+                }
+                continue;
+            }
+            let relative: Vec<char> = path.strip_prefix(&root).unwrap_or(&path).to_string_lossy().chars().collect();
+            if autoflow_data::glob_match(&pattern, &relative) {
+                matches.push(path.strip_prefix(workdir).unwrap_or(&path).display().to_string());
+            }
+        }
+    }
+    matches.sort();
+    let total = matches.len();
+    matches.truncate(MAX_GLOB_MATCHES);
+    if total > MAX_GLOB_MATCHES {
+        matches.push(format!("... {} more", total - MAX_GLOB_MATCHES));
+    }
+    Ok(if matches.is_empty() { "No files found".to_string() } else { matches.join("\n") })
+}
+
+fn grep(workdir: &Path, input: &Value) -> Result<String, String> {
+    let pattern = str_arg(input, "pattern")?;
+    let path = input.get("path").and_then(|v| v.as_str()).unwrap_or(".");
+    let mut cmd = Command::new("grep");
+    cmd.args(["-rnIE", "--color=never"]);
+    for dir in SKIP_DIRS {
+        cmd.arg(format!("--exclude-dir={}", dir));
+    }
+    if let Some(glob) = input.get("glob").and_then(|v| v.as_str()) {
+        cmd.arg(format!("--include={}", glob));
+    }
+    let output = cmd.arg("-e").arg(pattern).arg("--").arg(path).current_dir(workdir).output().map_err(|e| format!("failed to run grep: {}", e))?;
+    match output.status.code() {
+        Some(0) => Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string()),
+        Some(1) => Ok("No matches".to_string()),
+        _ => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+    }
+}
+
+fn ls(workdir: &Path, input: &Value) -> Result<String, String> {
+    let dir = resolve(workdir, str_arg(input, "path")?);
+    let mut names: Vec<String> = std::fs::read_dir(&dir)
+        .map_err(|e| format!("{}: {}", dir.display(), e))?
+        .flatten()
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if entry.path().is_dir() {
+                format!("{}/", name)
+            } else {
+                name
+            }
+        })
+        .collect();
+    names.sort();
+    Ok(names.join("\n"))
+}
+
+fn truncate(mut text: String) -> String {
+    if text.len() > MAX_RESULT_CHARS {
+        let mut end = MAX_RESULT_CHARS;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.push_str("\n... (output truncated)");
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_set_and_local_calls() {
+        let tools = ["Read", "Edit", "Bash(git:*)", "WebFetch", "mcp__memory__search"].map(String::from);
+        let set = ToolSet::new(&tools, &["Bash(git push:*)".to_string()]);
+        assert_eq!(set.names(), ["Read", "Edit", "Bash"]);
+        assert_eq!(set.unsupported, ["WebFetch", "mcp__memory__search"]);
+        assert!(set.allows_command("git status"));
+        assert!(!set.allows_command("git push origin main"));
+        assert!(!set.allows_command("rm -rf /"));
+        assert_eq!(ToolSet::new(&[], &["Bash".to_string()]).names(), ["Read", "Write", "Edit", "Glob", "Grep", "LS"]);
+
+        let dir = std::env::temp_dir().join(format!("autoflow-local-tools-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+
+        let all = ToolSet::new(&[], &[]);
+        assert_eq!(all.call(&dir, "Read", &json!({"file_path": "src/lib.rs", "offset": 2})), "     2\tfn b() {}");
+        assert!(all.call(&dir, "Edit", &json!({"file_path": "src/lib.rs", "old_string": "fn", "new_string": "pub fn"})).contains("occurs 2 times"));
+        assert!(all.call(&dir, "Edit", &json!({"file_path": "src/lib.rs", "old_string": "fn b", "new_string": "fn c"})).starts_with("Edited"));
+        all.call(&dir, "Write", &json!({"file_path": "src/nested/mod.rs", "content": "// c"}));
+        assert_eq!(all.call(&dir, "Glob", &json!({"pattern": "**/*.rs"})), "src/lib.rs\nsrc/nested/mod.rs");
+        assert!(all.call(&dir, "Grep", &json!({"pattern": "fn c"})).contains("src/lib.rs:2:fn c() {}"));
+        assert_eq!(all.call(&dir, "LS", &json!({"path": "src"})), "lib.rs\nnested/");
+        assert_eq!(all.call(&dir, "Bash", &json!({"command": "echo hi; exit 3"})), "hi\n[exit code 3]");
+        assert!(set.call(&dir, "Write", &json!({})).contains("not available"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
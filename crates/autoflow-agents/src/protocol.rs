@@ -0,0 +1,200 @@
+/// Completion protocol between the orchestrator and its agents
+///
+/// Phase agents report their verdict with a marker line (`TEST_RESULT: PASSED`,
+/// `REVIEW_STATUS: FAILED`...) and write failure details to a report the
+/// fixers read. Those expectations live here, under a version number: the
+/// instructions are appended to every prompt that needs them, the
+/// orchestrator parses output with the same definitions, and an agent template
+/// declaring `protocol: N` in its frontmatter for another version is refused
+/// before it runs.
+use autoflow_data::AutoFlowError;
+use std::path::Path;
+
+/// Version of the marker and report contract this build speaks
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Where agents write failure reports, relative to the project root
+pub const FAILURE_REPORTS_DIR: &str = ".autoflow/.failures";
+
+/// A verdict line an agent ends its output with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Marker {
+    TestResult,
+    ReviewStatus,
+    HealthStatus,
+}
+
+impl Marker {
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::TestResult => "TEST_RESULT",
+            Self::ReviewStatus => "REVIEW_STATUS",
+            Self::HealthStatus => "HEALTH_STATUS",
+        }
+    }
+
+    /// The exact line for a verdict, e.g. `TEST_RESULT: PASSED`
+    pub fn line(self, passed: bool) -> String {
+        format!("{}: {}", self.key(), if passed { "PASSED" } else { "FAILED" })
+    }
+
+    /// The verdict in `output`; the last marker wins, None when there is none
+    pub fn parse(self, output: &str) -> Option<bool> {
+        let passed = output.rfind(&self.line(true));
+        let failed = output.rfind(&self.line(false));
+        match (passed, failed) {
+            (Some(p), Some(f)) => Some(p > f),
+            (Some(_), None) => Some(true),
+            (None, Some(_)) => Some(false),
+            (None, None) => None,
+        }
+    }
+}
+
+/// What one agent owes the orchestrator when it finishes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgentProtocol {
+    pub marker: Marker,
+    /// Failure report suffix: `sprint-<id>-<suffix>.md` under [`FAILURE_REPORTS_DIR`]
+    pub failure_report: Option<&'static str>,
+}
+
+/// The protocol an agent follows; None for agents with no verdict to report
+pub fn agent_protocol(agent_name: &str) -> Option<AgentProtocol> {
+    let (marker, failure_report) = match agent_name {
+        "unit-test-runner" => (Marker::TestResult, Some("unit-tests")),
+        "integration-test-runner" => (Marker::TestResult, Some("integration-tests")),
+        "e2e-test-runner" => (Marker::TestResult, Some("e2e-tests")),
+        "reviewer" => (Marker::ReviewStatus, Some("review")),
+        "health-check" => (Marker::HealthStatus, None),
+        _ => return None,
+    };
+    Some(AgentProtocol { marker, failure_report })
+}
+
+impl AgentProtocol {
+    /// File name of the sprint's failure report, when the agent writes one
+    pub fn failure_report_name(&self, sprint_id: u32) -> Option<String> {
+        self.failure_report.map(|suffix| format!("sprint-{}-{}.md", sprint_id, suffix))
+    }
+
+    /// Prompt section spelling out the contract for this run
+    pub fn instructions(&self, sprint_id: Option<u32>) -> String {
+        let mut text = format!(
+            "## Completion Protocol (v{})\n\n\
+             End your response with exactly one of these lines:\n\
+             - `{}` when everything passed\n\
+             - `{}` when anything failed\n",
+            PROTOCOL_VERSION,
+            self.marker.line(true),
+            self.marker.line(false),
+        );
+        if let Some(report) = sprint_id.and_then(|id| self.failure_report_name(id)) {
+            text.push_str(&format!(
+                "\nBefore reporting FAILED, use the Write tool to create `{}/{}` with the details the fixer needs.\n",
+                FAILURE_REPORTS_DIR, report
+            ));
+        }
+        text.push_str("\nThe orchestrator reads only this line to decide what happens next.\n");
+        text
+    }
+
+    /// Ways `output` breaks the contract; empty when it holds
+    pub fn violations(&self, output: &str, workdir: &Path, sprint_id: Option<u32>) -> Vec<String> {
+        let mut violations = Vec::new();
+        match self.marker.parse(output) {
+            None => violations.push(format!("no `{}: PASSED/FAILED` line", self.marker.key())),
+            Some(false) => {
+                if let Some(report) = sprint_id.and_then(|id| self.failure_report_name(id)) {
+                    if !workdir.join(FAILURE_REPORTS_DIR).join(&report).exists() {
+                        violations.push(format!("reported FAILED without writing {}", report));
+                    }
+                }
+            }
+            Some(true) => {}
+        }
+        violations
+    }
+}
+
+/// The `protocol:` version an agent template's frontmatter declares
+pub fn declared_protocol(content: &str) -> Option<u32> {
+    let mut lines = content.lines();
+    if lines.next() != Some("---") {
+        return None;
+    }
+    lines
+        .take_while(|line| *line != "---")
+        .find_map(|line| line.strip_prefix("protocol:"))
+        .and_then(|version| version.trim().parse().ok())
+}
+
+/// Refuse a template written for another protocol version; undeclared means current
+pub fn check_protocol(agent_name: &str, declared: Option<u32>) -> Result<(), AutoFlowError> {
+    match declared {
+        Some(version) if version != PROTOCOL_VERSION => Err(AutoFlowError::AgentProtocolMismatch {
+            agent: agent_name.to_string(),
+            declared: version,
+            supported: PROTOCOL_VERSION,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Installed templates in `agents_dir` declaring an unsupported version, with that version
+pub fn incompatible_agents(agents_dir: &Path) -> Vec<(String, u32)> {
+    let Ok(entries) = std::fs::read_dir(agents_dir) else {
+        return Vec::new();
+    };
+    let mut incompatible: Vec<(String, u32)> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let name = file_name.strip_suffix(".agent.md")?.to_string();
+            let version = declared_protocol(&std::fs::read_to_string(entry.path()).ok()?)?;
+            (version != PROTOCOL_VERSION).then_some((name, version))
+        })
+        .collect();
+    incompatible.sort();
+    incompatible
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_protocol() {
+        let marker = Marker::TestResult;
+        assert_eq!(marker.parse("ran 12 tests\nTEST_RESULT: PASSED\n"), Some(true));
+        // Quoting the instructions before the verdict doesn't count as passing
+        assert_eq!(marker.parse("either `TEST_RESULT: PASSED` or not\n...\nTEST_RESULT: FAILED"), Some(false));
+        assert_eq!(marker.parse("all good"), None);
+
+        let reviewer = agent_protocol("reviewer").unwrap();
+        let instructions = reviewer.instructions(Some(4));
+        assert!(instructions.contains("(v1)"));
+        assert!(instructions.contains("`REVIEW_STATUS: FAILED`"));
+        assert!(instructions.contains("`.autoflow/.failures/sprint-4-review.md`"));
+        assert!(!agent_protocol("health-check").unwrap().instructions(Some(4)).contains("Write tool"));
+        assert!(agent_protocol("code-implementer").is_none());
+
+        let workdir = std::env::temp_dir().join(format!("autoflow-protocol-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&workdir);
+        assert_eq!(reviewer.violations("LGTM", &workdir, Some(4)), ["no `REVIEW_STATUS: PASSED/FAILED` line"]);
+        assert_eq!(
+            reviewer.violations("REVIEW_STATUS: FAILED", &workdir, Some(4)),
+            ["reported FAILED without writing sprint-4-review.md"]
+        );
+        assert!(reviewer.violations("REVIEW_STATUS: PASSED", &workdir, Some(4)).is_empty());
+
+        std::fs::create_dir_all(&workdir).unwrap();
+        std::fs::write(workdir.join("reviewer.agent.md"), "---\nmodel: x\nprotocol: 2\n---\nprotocol: 1\n").unwrap();
+        std::fs::write(workdir.join("unit-test-runner.agent.md"), "---\nprotocol: 1\n---\n").unwrap();
+        std::fs::write(workdir.join("deployer.agent.md"), "---\nmodel: x\n---\n").unwrap();
+        assert_eq!(incompatible_agents(&workdir), [("reviewer".to_string(), 2)]);
+        assert!(check_protocol("reviewer", Some(2)).unwrap_err().to_string().contains("v2"));
+        assert!(check_protocol("deployer", None).is_ok());
+        let _ = std::fs::remove_dir_all(workdir);
+    }
+}
@@ -0,0 +1,272 @@
+use anyhow::{bail, Context, Result};
+use autoflow_data::{Config, RemoteConfig, RemoteSync};
+use autoflow_utils::{RUNTIME_STATUS_PATH, RUN_EVENTS_PATH, RUN_LOCK_PATH};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command as StdCommand, Stdio};
+use tokio::process::Command;
+
+/// Local-only state rsync never copies in either direction
+const LOCAL_ONLY: &[&str] =
+    &[".autoflow/.debug/", RUN_EVENTS_PATH, ".autoflow/runs/", RUN_LOCK_PATH, RUNTIME_STATUS_PATH];
+
+/// Ref the git backend pushes the snapshot of the local working tree to
+const REMOTE_REF: &str = "refs/autoflow/remote";
+
+/// Scratch index the snapshot is built in, under the local `.git`
+const SNAPSHOT_INDEX: &str = "autoflow-remote.index";
+
+/// Snapshots never reach the local history, so any identity will do
+const SNAPSHOT_IDENTITY: &[(&str, &str)] = &[
+    ("GIT_AUTHOR_NAME", "AutoFlow"),
+    ("GIT_AUTHOR_EMAIL", "autoflow@localhost"),
+    ("GIT_COMMITTER_NAME", "AutoFlow"),
+    ("GIT_COMMITTER_EMAIL", "autoflow@localhost"),
+];
+
+/// Runs agents for one project on the host from its `[remote."<project>"]` config
+///
+/// Before each agent the local working copy is synced to the remote one; the
+/// claude CLI then runs there over SSH with its stream-json events coming back
+/// on stdout, and afterwards the agent's changes are brought back as
+/// uncommitted changes, so phase commits still happen locally.
+#[derive(Debug, Clone)]
+pub struct RemoteExecutor {
+    config: RemoteConfig,
+    local_dir: PathBuf,
+}
+
+impl RemoteExecutor {
+    /// Remote configured for the project in `local_dir`, if any
+    pub fn for_dir(local_dir: &Path) -> Option<Self> {
+        let local_dir = local_dir.canonicalize().ok()?;
+        let config = Config::global().ok()?.remote_for(&local_dir)?.clone();
+        Some(Self { config, local_dir })
+    }
+
+    pub fn host(&self) -> &str {
+        &self.config.host
+    }
+
+    /// Send the local working copy to the remote host
+    pub fn push(&self) -> Result<()> {
+        let dir = remote_path(&self.config.dir);
+        match self.config.sync {
+            RemoteSync::Rsync => {
+                self.ssh_output(&format!("mkdir -p {}", dir))?;
+                let destination = format!("{}:{}/", self.config.host, self.config.dir);
+                self.rsync(&format!("{}/", self.local_dir.display()), &destination, &["--delete"], &[])
+            }
+            RemoteSync::Git => {
+                self.ssh_output(&format!(
+                    "mkdir -p {dir} && cd {dir} && (git rev-parse --git-dir >/dev/null 2>&1 || git init -q)"
+                ))?;
+                let url = format!("{}:{}", self.config.host, self.config.dir);
+                let refspec = format!("{}:{}", self.snapshot()?, REMOTE_REF);
+                self.local_git(&["push", "--force", "--quiet", &url, &refspec], None, &[])?;
+                self.ssh_output(&format!(
+                    "cd {} && git reset -q --hard {} && git clean -fdq",
+                    dir, REMOTE_REF
+                ))?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Bring the agent's changes back into the local working copy
+    ///
+    /// With rsync, `.autoflow/` comes back separately: only files the agent
+    /// wrote after the push are copied and nothing there is deleted, so plan
+    /// updates, hints and reports written locally during the run are kept.
+    pub fn pull(&self) -> Result<()> {
+        match self.config.sync {
+            RemoteSync::Rsync => {
+                let source = format!("{}:{}/", self.config.host, self.config.dir);
+                let local = format!("{}/", self.local_dir.display());
+                self.rsync(&source, &local, &["--delete"], &["/.autoflow/"])?;
+                self.rsync(&source, &local, &["--update", "--include=/.autoflow/***", "--exclude=*"], &[])
+            }
+            RemoteSync::Git => {
+                let diff = self.ssh_output(&format!(
+                    "cd {} && git add -A && git diff --cached --binary {}",
+                    remote_path(&self.config.dir),
+                    REMOTE_REF
+                ))?;
+                if diff.is_empty() {
+                    return Ok(());
+                }
+                self.local_git(&["apply", "--whitespace=nowarn"], Some(&diff), &[])?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Commit the local working tree, uncommitted changes included, on top of HEAD
+    ///
+    /// Built in a scratch index, so HEAD, the index and the working tree are
+    /// left as they were. Local-only state is left out. Returns the commit id.
+    fn snapshot(&self) -> Result<String> {
+        let index = self.local_git(&["rev-parse", "--git-path", SNAPSHOT_INDEX], None, &[])?;
+        let index = self.local_dir.join(String::from_utf8_lossy(&index).trim());
+        let index_env = index.to_string_lossy().to_string();
+        let env = [("GIT_INDEX_FILE", index_env.as_str())];
+        let excludes: Vec<String> = LOCAL_ONLY
+            .iter()
+            .map(|path| format!(":(exclude){}", path.trim_end_matches('/')))
+            .collect();
+        let commit = (|| {
+            self.local_git(&["read-tree", "HEAD"], None, &env)?;
+            let mut add = vec!["add", "-A", "--", "."];
+            add.extend(excludes.iter().map(String::as_str));
+            self.local_git(&add, None, &env)?;
+            let tree = String::from_utf8_lossy(&self.local_git(&["write-tree"], None, &env)?).trim().to_string();
+            self.local_git(&["commit-tree", &tree, "-p", "HEAD", "-m", "AutoFlow remote sync"], None, SNAPSHOT_IDENTITY)
+        })();
+        let _ = std::fs::remove_file(&index);
+        Ok(String::from_utf8_lossy(&commit?).trim().to_string())
+    }
+
+    /// The given local claude invocation, run in the remote working copy over SSH
+    ///
+    /// Only the arguments carry over; the CLI runs with the remote login environment.
+    pub fn command(&self, local: &Command) -> Command {
+        let args: Vec<String> = local.as_std().get_args().map(|a| a.to_string_lossy().to_string()).collect();
+
+        let mut cmd = Command::new("ssh");
+        cmd.args(&self.config.ssh_args)
+            .arg("-T")
+            .arg(&self.config.host)
+            .arg(remote_script(&self.config.dir, &self.config.claude, &args));
+        cmd
+    }
+
+    fn ssh_output(&self, script: &str) -> Result<Vec<u8>> {
+        let output = StdCommand::new("ssh")
+            .args(&self.config.ssh_args)
+            .arg("-T")
+            .arg(&self.config.host)
+            .arg(script)
+            .output()
+            .context("Failed to run ssh")?;
+        if !output.status.success() {
+            bail!(
+                "Remote command on {} failed: {}",
+                self.config.host,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(output.stdout)
+    }
+
+    /// rsync `source` to `destination`, skipping `excludes` besides the usual ones
+    ///
+    /// `options` come after the excludes, so filter rules in them apply only
+    /// to what the excludes let through.
+    fn rsync(&self, source: &str, destination: &str, options: &[&str], excludes: &[&str]) -> Result<()> {
+        let mut cmd = StdCommand::new("rsync");
+        cmd.args(["-az", "-e", &self.ssh_command()]);
+        for path in LOCAL_ONLY.iter().chain(excludes).copied().chain(self.config.exclude.iter().map(String::as_str)) {
+            cmd.arg("--exclude").arg(path);
+        }
+        cmd.args(options);
+        let output = cmd.arg(source).arg(destination).output().context("Failed to run rsync")?;
+        if !output.status.success() {
+            bail!("rsync {} -> {} failed: {}", source, destination, String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(())
+    }
+
+    /// `ssh` with the configured arguments, for rsync `-e` and `GIT_SSH_COMMAND`
+    fn ssh_command(&self) -> String {
+        std::iter::once("ssh".to_string())
+            .chain(self.config.ssh_args.iter().map(|a| shell_quote(a)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Run git in the local working copy with extra `env`; returns its stdout
+    fn local_git(&self, args: &[&str], stdin: Option<&[u8]>, env: &[(&str, &str)]) -> Result<Vec<u8>> {
+        let mut child = StdCommand::new("git")
+            .args(args)
+            .current_dir(&self.local_dir)
+            .env("GIT_SSH_COMMAND", self.ssh_command())
+            .envs(env.iter().copied())
+            .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to run git")?;
+        if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+            pipe.write_all(input)?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            bail!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(output.stdout)
+    }
+}
+
+/// Shell script run on the remote host: cd into the working copy, then exec claude
+fn remote_script(dir: &str, claude: &str, args: &[String]) -> String {
+    let mut script = format!("cd {} && exec {}", remote_path(dir), shell_quote(claude));
+    for arg in args {
+        script.push(' ');
+        script.push_str(&shell_quote(arg));
+    }
+    script
+}
+
+/// Quote a remote path, leaving a leading `~/` for the remote shell to expand
+fn remote_path(dir: &str) -> String {
+    match dir.strip_prefix("~/") {
+        Some(rest) => format!("~/{}", shell_quote(rest)),
+        None => shell_quote(dir),
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_script_quotes_arguments() {
+        let args = vec!["--print".to_string(), "--allowedTools".to_string(), "Read Bash(git log:*)".to_string()];
+        assert_eq!(
+            remote_script("~/work/it's", "claude", &args),
+            r#"cd ~/'work/it'\''s' && exec 'claude' '--print' '--allowedTools' 'Read Bash(git log:*)'"#
+        );
+        assert_eq!(remote_script("/srv/app", "/opt/bin/claude", &[]), "cd '/srv/app' && exec '/opt/bin/claude'");
+    }
+
+    #[test]
+    fn test_snapshot_includes_uncommitted_work() {
+        let dir = std::env::temp_dir().join(format!("autoflow-remote-snapshot-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join(".autoflow/runs")).unwrap();
+        let git = |args: &[&str]| {
+            let output = StdCommand::new("git").args(args).current_dir(&dir).output().unwrap();
+            assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+            String::from_utf8(output.stdout).unwrap()
+        };
+        git(&["init", "-q"]);
+        git(&["-c", "user.name=T", "-c", "user.email=t@example.com", "commit", "-q", "--allow-empty", "-m", "Initial"]);
+        std::fs::write(dir.join("fix.rs"), "fn fixed() {}").unwrap();
+        std::fs::write(dir.join(".autoflow/runs/log.txt"), "local only").unwrap();
+
+        let executor = RemoteExecutor {
+            config: serde_json::from_value(serde_json::json!({"host": "box", "dir": "~/work"})).unwrap(),
+            local_dir: dir.clone(),
+        };
+        let commit = executor.snapshot().unwrap();
+        assert_eq!(git(&["ls-tree", "-r", "--name-only", &commit]), "fix.rs\n");
+        assert_eq!(git(&["rev-parse", &format!("{}^", commit)]), git(&["rev-parse", "HEAD"]));
+        // HEAD and the index are untouched
+        assert_eq!(git(&["status", "--porcelain", "--untracked-files=all"]), "?? .autoflow/runs/log.txt\n?? fix.rs\n");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
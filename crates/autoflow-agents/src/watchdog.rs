@@ -0,0 +1,125 @@
+use autoflow_data::{Config, WatchdogConfig};
+use std::fmt;
+use std::time::Instant;
+use tokio::time::Duration;
+
+/// Error returned when the watchdog kills a silent agent
+#[derive(Debug)]
+pub struct AgentStalled {
+    pub agent: String,
+    pub silent_for: Duration,
+}
+
+impl fmt::Display for AgentStalled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Agent '{}' stalled: no stream events for {}s, killed by watchdog",
+            self.agent,
+            self.silent_for.as_secs()
+        )
+    }
+}
+
+impl std::error::Error for AgentStalled {}
+
+/// What the watchdog wants done after a check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchdogVerdict {
+    Healthy,
+    /// Silent past the warning threshold (reported once per silence)
+    Warn(Duration),
+    /// Silent past the kill threshold
+    Stalled(Duration),
+}
+
+/// Tracks time since an agent's last stream event
+#[derive(Debug)]
+pub struct StallWatchdog {
+    warn_after: Duration,
+    kill_after: Option<Duration>,
+    last_event: Instant,
+    warned: bool,
+}
+
+impl StallWatchdog {
+    pub fn new(config: &WatchdogConfig) -> Self {
+        Self {
+            warn_after: Duration::from_secs(config.warn_after_secs),
+            kill_after: (config.kill_after_secs > 0).then(|| Duration::from_secs(config.kill_after_secs)),
+            last_event: Instant::now(),
+            warned: false,
+        }
+    }
+
+    /// Watchdog using the global config, or defaults when there is none
+    pub fn from_global_config() -> Self {
+        Self::new(&Config::global().map(|c| c.watchdog).unwrap_or_default())
+    }
+
+    /// Record stream activity
+    pub fn event(&mut self) {
+        self.last_event = Instant::now();
+        self.warned = false;
+    }
+
+    /// How often the executor should call `check` while waiting for output
+    pub fn poll_interval(&self) -> Duration {
+        let shortest = self.kill_after.map_or(self.warn_after, |kill| kill.min(self.warn_after));
+        (shortest / 4).clamp(Duration::from_millis(10), Duration::from_secs(30))
+    }
+
+    pub fn check(&mut self) -> WatchdogVerdict {
+        self.check_at(Instant::now())
+    }
+
+    fn check_at(&mut self, now: Instant) -> WatchdogVerdict {
+        let silent_for = now.saturating_duration_since(self.last_event);
+        if self.kill_after.is_some_and(|kill| silent_for >= kill) {
+            WatchdogVerdict::Stalled(silent_for)
+        } else if silent_for >= self.warn_after && !self.warned {
+            self.warned = true;
+            WatchdogVerdict::Warn(silent_for)
+        } else {
+            WatchdogVerdict::Healthy
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warns_once_then_kills() {
+        let config = WatchdogConfig { warn_after_secs: 60, kill_after_secs: 300, max_stall_retries: 1 };
+        let mut watchdog = StallWatchdog::new(&config);
+        let start = watchdog.last_event;
+
+        assert_eq!(watchdog.check_at(start + Duration::from_secs(30)), WatchdogVerdict::Healthy);
+        assert_eq!(
+            watchdog.check_at(start + Duration::from_secs(61)),
+            WatchdogVerdict::Warn(Duration::from_secs(61))
+        );
+        assert_eq!(watchdog.check_at(start + Duration::from_secs(120)), WatchdogVerdict::Healthy);
+        assert_eq!(
+            watchdog.check_at(start + Duration::from_secs(301)),
+            WatchdogVerdict::Stalled(Duration::from_secs(301))
+        );
+
+        watchdog.event();
+        let restart = watchdog.last_event;
+        assert_eq!(watchdog.check_at(restart + Duration::from_secs(10)), WatchdogVerdict::Healthy);
+    }
+
+    #[test]
+    fn test_kill_disabled() {
+        let config = WatchdogConfig { warn_after_secs: 60, kill_after_secs: 0, max_stall_retries: 1 };
+        let mut watchdog = StallWatchdog::new(&config);
+        let start = watchdog.last_event;
+        assert_eq!(
+            watchdog.check_at(start + Duration::from_secs(86_400)),
+            WatchdogVerdict::Warn(Duration::from_secs(86_400))
+        );
+    }
+}
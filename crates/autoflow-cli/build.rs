@@ -0,0 +1,29 @@
+// Generates man pages for `autoflow` and every subcommand into
+// $OUT_DIR/man at build time. Set AUTOFLOW_MAN_DIR to also copy them
+// somewhere stable (e.g. for packaging).
+
+use std::path::PathBuf;
+
+#[allow(dead_code)]
+mod cli {
+    include!("src/cli.rs");
+}
+
+fn main() -> std::io::Result<()> {
+    println!("cargo:rerun-if-changed=src/cli.rs");
+    println!("cargo:rerun-if-env-changed=AUTOFLOW_MAN_DIR");
+
+    let out_dir = PathBuf::from(std::env::var_os("OUT_DIR").expect("OUT_DIR set by cargo"));
+    let mut targets = vec![out_dir.join("man")];
+    if let Some(dir) = std::env::var_os("AUTOFLOW_MAN_DIR") {
+        targets.push(PathBuf::from(dir));
+    }
+
+    let cmd = <cli::Cli as clap::CommandFactory>::command();
+    for dir in targets {
+        std::fs::create_dir_all(&dir)?;
+        clap_mangen::generate_to(cmd.clone(), &dir)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,873 @@
+// Command-line interface definition.
+// Kept free of crate-internal imports so `build.rs` can `include!` it to
+// generate man pages with clap_mangen.
+
+use clap::{Parser, Subcommand, ValueHint};
+pub use clap_complete::Shell;
+
+/// Value parser for path arguments: a relative path is resolved against the
+/// directory autoflow was run from
+///
+/// Arguments are parsed before `main` moves to the project root, so every
+/// path argument uses this (and a path `value_hint`) to keep meaning what the
+/// user typed.
+pub fn user_path<T: From<String>>(arg: &str) -> Result<T, String> {
+    let path = std::path::Path::new(arg);
+    if path.is_absolute() {
+        return Ok(T::from(arg.to_string()));
+    }
+    let cwd = std::env::current_dir().map_err(|e| format!("can't resolve {}: {}", arg, e))?;
+    Ok(T::from(cwd.join(path).display().to_string()))
+}
+
+#[derive(Parser)]
+#[command(name = "autoflow")]
+#[command(version = env!("CARGO_PKG_VERSION"))]
+#[command(about = "Best-in-class autonomous coding agent", long_about = None)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Commands,
+
+    /// Enable verbose logging
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+
+    /// Guardrail profile from config.toml (overrides AUTOFLOW_PROFILE)
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
+    /// Inspect without changing anything: no file writes, commits or writing agent tools
+    #[arg(long, global = true)]
+    pub read_only: bool,
+
+    /// Project directory (default: found by walking up to .autoflow/ or .git)
+    #[arg(long, global = true, value_name = "PATH", value_hint = ValueHint::DirPath, value_parser = user_path::<std::path::PathBuf>)]
+    pub project: Option<std::path::PathBuf>,
+
+    /// Home directory for AutoFlow's config and agents (overrides HOME, AUTOFLOW_HOME)
+    #[arg(long, global = true, value_hint = ValueHint::DirPath, value_parser = user_path::<std::path::PathBuf>)]
+    pub home: Option<std::path::PathBuf>,
+
+    /// How errors are printed: text or json (overrides AUTOFLOW_ERROR_FORMAT)
+    #[arg(long, global = true)]
+    pub error_format: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Guided first-run setup (prerequisites, agents, MCP servers, config)
+    Setup {
+        /// Accept all defaults without prompting
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Check that AutoFlow and its prerequisites are installed correctly
+    Doctor,
+
+    /// Install AutoFlow's agents, skills, templates, schemas and completions
+    Install {
+        /// Overwrite files that differ from the built-in copies
+        #[arg(long)]
+        force: bool,
+
+        /// Components to install, comma separated (default: all)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        components: Vec<InstallComponent>,
+
+        /// Check what is installed without writing anything
+        #[arg(long, conflicts_with = "force")]
+        verify: bool,
+
+        /// Print the install report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Create new project from IDEA.md (full autonomous setup)
+    Create {
+        /// Project name (optional, uses current directory if IDEA.md exists)
+        #[arg(value_name = "NAME", required = false)]
+        name: Option<String>,
+
+        /// Path to IDEA.md file (optional, uses ./IDEA.md or creates template if not provided)
+        #[arg(short, long, value_hint = ValueHint::FilePath, value_parser = user_path::<String>)]
+        idea: Option<String>,
+
+        /// Seed API_SPEC.md and DATA_MODEL.md from an OpenAPI/Swagger file
+        #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath, value_parser = user_path::<String>)]
+        openapi: Option<String>,
+
+        /// Doc sets to generate: build, api, ui (comma-separated; default: what the idea needs)
+        #[arg(long, value_name = "LIST")]
+        docs: Option<String>,
+
+        /// Skip UI_SPEC.md and TESTING_STRATEGY.md
+        #[arg(long)]
+        no_ui_docs: bool,
+    },
+
+    /// Initialize new project with AutoFlow
+    Init {
+        /// Use specific template (e.g., "react-node", "laravel-react")
+        #[arg(short, long)]
+        template: Option<String>,
+    },
+
+    /// Start autonomous development (execute sprints)
+    Start {
+        /// Execute sprints in parallel
+        #[arg(short, long)]
+        parallel: bool,
+
+        /// Run at most N sprints at once, starting dependents as their
+        /// prerequisites finish (implies --parallel; no limit by default)
+        #[arg(short, long, value_name = "N")]
+        jobs: Option<usize>,
+
+        /// Run specific sprint by ID
+        #[arg(short, long)]
+        sprint: Option<u32>,
+
+        /// Disable live streaming logs (enabled by default)
+        #[arg(long)]
+        no_live: bool,
+
+        /// Take over the run lock even if another run appears to hold it
+        #[arg(long)]
+        steal_lock: bool,
+
+        /// Update .autoflow/docs to match the built code when the run ends
+        #[arg(long)]
+        sync_docs: bool,
+
+        /// Sprint selection in continuous mode: sequential or weighted
+        /// (defaults to [defaults] scheduling in config)
+        #[arg(long)]
+        schedule: Option<String>,
+
+        /// Show the sprints that would run and their token forecast, then exit
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Run sprints even if SPRINTS.yml sends them back to phases that already completed
+        #[arg(long)]
+        force_redo: bool,
+    },
+
+    /// Continue a killed `start` run from its checkpoint, retry counters included
+    Resume {
+        /// Execute sprints in parallel
+        #[arg(short, long)]
+        parallel: bool,
+
+        /// Run at most N sprints at once (implies --parallel)
+        #[arg(short, long, value_name = "N")]
+        jobs: Option<usize>,
+
+        /// Resume a specific sprint by ID
+        #[arg(short, long)]
+        sprint: Option<u32>,
+
+        /// Disable live streaming logs (enabled by default)
+        #[arg(long)]
+        no_live: bool,
+
+        /// Take over the run lock even if another run appears to hold it
+        #[arg(long)]
+        steal_lock: bool,
+    },
+
+    /// Show sprint progress and status
+    Status {
+        /// Output as JSON
+        #[arg(short, long)]
+        json: bool,
+
+        /// Show the event timeline of one run (id or prefix)
+        #[arg(long)]
+        run_id: Option<String>,
+
+        /// Show the summary of the most recent run
+        #[arg(long, conflicts_with = "run_id")]
+        last_run: bool,
+    },
+
+    /// Inspect and compare stored runs
+    #[command(subcommand)]
+    Runs(RunsCommands),
+
+    /// Show token usage and spend per run and per sprint
+    Cost {
+        /// Break one run down by sprint and agent (id or prefix)
+        #[arg(long)]
+        run: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Pack SPRINTS.yml, config, recent runs and redacted logs into a .tar.gz for a bug report
+    ExportBundle {
+        /// Where to write the bundle (default: autoflow-bundle-<timestamp>.tar.gz, in the temp directory with --read-only)
+        #[arg(short, long, value_hint = ValueHint::FilePath, value_parser = user_path::<std::path::PathBuf>)]
+        output: Option<std::path::PathBuf>,
+
+        /// How many of the most recent runs to include
+        #[arg(long, default_value = "3")]
+        runs: usize,
+    },
+
+    /// Reconstruct the code and sprint state a phase ran with
+    #[command(subcommand)]
+    Debug(DebugCommands),
+
+    /// Analyze existing codebase
+    Analyze,
+
+    /// Render sprints as a markdown kanban board (.autoflow/BOARD.md)
+    Board {
+        /// Print to stdout instead of writing BOARD.md
+        #[arg(long)]
+        stdout: bool,
+    },
+
+    /// Add new feature to existing codebase
+    Add {
+        /// Feature description
+        description: String,
+
+        /// Additional requirements
+        #[arg(short, long)]
+        requirements: Option<String>,
+
+        /// Place the new sprint after this sprint and make it depend on it
+        #[arg(long, value_name = "ID")]
+        after: Option<u32>,
+
+        /// Sprint(s) the new sprint depends on (repeatable)
+        #[arg(long = "depends-on", value_name = "ID")]
+        depends_on: Vec<u32>,
+    },
+
+    /// Add maintenance sprints from dependency, TODO/FIXME and flaky test scans
+    Maintain {
+        /// Show the sprints without adding them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Only run when `[maintenance] interval_days` have passed since the last run
+        #[arg(long)]
+        if_due: bool,
+    },
+
+    /// Fix a bug
+    Fix {
+        /// Bug description
+        description: String,
+
+        /// Auto-implement fix after investigation
+        #[arg(long)]
+        auto_fix: bool,
+
+        /// Launch Playwright in headed mode
+        #[arg(long)]
+        playwright_headed: bool,
+
+        /// Take over the run lock even if another run appears to hold it
+        #[arg(long)]
+        steal_lock: bool,
+
+        /// Reproduce the bug with Playwright before fixing (automatic for UI bugs with --auto-fix)
+        #[arg(long)]
+        reproduce: bool,
+    },
+
+    /// Make a small change on a new branch: implement, test, review (no sprint plan)
+    Quick {
+        /// What to change
+        instruction: String,
+
+        /// Also record the change as a sprint in SPRINTS.yml
+        #[arg(long)]
+        save: bool,
+
+        /// Take over the run lock even if another run appears to hold it
+        #[arg(long)]
+        steal_lock: bool,
+    },
+
+    /// Update documentation and regenerate sprints based on feedback
+    Pivot {
+        /// Feedback/instruction for updating documentation
+        instruction: String,
+
+        /// Take over the run lock even if another run appears to hold it
+        #[arg(long)]
+        steal_lock: bool,
+    },
+
+    /// Rollback sprint
+    Rollback {
+        /// Sprint ID to rollback (default: last sprint)
+        #[arg(short, long)]
+        sprint: Option<u32>,
+    },
+
+    /// Work through a blocked sprint interactively: triage, re-run, hints, edit or skip
+    Resolve {
+        /// Blocked sprint ID
+        sprint: u32,
+    },
+
+    /// Give the next agent run on a sprint a hint; with no text, list pending hints
+    Hint {
+        /// Sprint ID
+        sprint: u32,
+
+        /// Guidance for the agent, e.g. "use the existing db pool from src/db.ts"
+        text: Option<String>,
+
+        /// Drop the sprint's pending hints
+        #[arg(long, conflicts_with = "text")]
+        clear: bool,
+    },
+
+    /// Manage git worktrees
+    #[command(subcommand)]
+    Worktree(WorktreeCommands),
+
+    /// Validate project (quality gates, infrastructure, etc.)
+    Validate {
+        /// Validate infrastructure
+        #[arg(long)]
+        infrastructure: bool,
+
+        /// Validate integration
+        #[arg(long)]
+        integration: bool,
+
+        /// Auto-fix issues where possible
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Manage sprints
+    #[command(subcommand)]
+    Sprints(SprintsCommands),
+
+    /// List available agents
+    Agents {
+        /// Show detailed information
+        #[arg(short, long)]
+        detailed: bool,
+
+        #[command(subcommand)]
+        command: Option<AgentsCommands>,
+    },
+
+    /// List available skills
+    Skills,
+
+    /// Inspect sprint workflows: phases, agents, fix loops and retries
+    #[command(subcommand)]
+    Workflows(WorkflowsCommands),
+
+    /// Review, apply and undo the patch bundles queued in patch mode
+    #[command(subcommand)]
+    Patch(PatchCommands),
+
+    /// Manage development environment
+    #[command(subcommand)]
+    Env(EnvCommands),
+
+    /// Manage MCP servers
+    #[command(subcommand)]
+    Mcp(McpCommands),
+
+    /// Live dashboard of sprint progress and running agents
+    Watch {
+        /// Refresh interval in milliseconds
+        #[arg(long, default_value = "500")]
+        interval: u64,
+    },
+
+    /// View agent execution logs
+    Logs {
+        /// Follow log output (tail -f style)
+        #[arg(short, long)]
+        follow: bool,
+
+        /// View live streaming logs (.jsonl format)
+        #[arg(short, long)]
+        live: bool,
+
+        /// Only logs from this run (id or prefix)
+        #[arg(long)]
+        run_id: Option<String>,
+    },
+
+    /// Check for and install updates
+    Update {
+        /// Force update check (ignore 24h interval)
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Manage git hooks that keep SPRINTS.yml valid
+    #[command(subcommand)]
+    Hooks(HooksCommands),
+
+    /// Manage anonymous usage telemetry (opt-in)
+    #[command(subcommand)]
+    Telemetry(TelemetryCommands),
+
+    /// Read and change project settings in .autoflow/config.yml
+    #[command(subcommand)]
+    Config(ConfigCommands),
+
+    /// Generate code from project docs
+    #[command(subcommand)]
+    Generate(GenerateCommands),
+
+    /// Generate shell completion script (bash, zsh, fish, powershell, elvish)
+    Completions {
+        /// Target shell
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum WorktreeCommands {
+    /// List all worktrees
+    List {
+        /// Filter by type (sprint, bugfix, other)
+        #[arg(long)]
+        r#type: Option<String>,
+    },
+
+    /// Create new worktree
+    Create {
+        /// Branch name (sprint-<id>)
+        #[arg(required_unless_present = "bugfix")]
+        branch: Option<String>,
+
+        /// Create a bugfix worktree named bugfix-<slug> instead
+        #[arg(long, value_name = "SLUG", conflicts_with = "branch")]
+        bugfix: Option<String>,
+
+        /// Branch to start from (default: main, or defaults.worktree_base_branch)
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Don't fetch the base branch from the remote first
+        #[arg(long)]
+        no_fetch: bool,
+
+        /// Don't configure the new branch to push to the remote
+        #[arg(long)]
+        no_track: bool,
+    },
+
+    /// Merge worktree to main
+    Merge {
+        /// Branch name
+        branch: String,
+    },
+
+    /// Push a sprint worktree's branch and open a pull request for the sprint
+    Pr {
+        /// Branch name (sprint-<id>)
+        branch: String,
+
+        /// Open the pull request as a draft
+        #[arg(long)]
+        draft: bool,
+    },
+
+    /// Delete worktree
+    Delete {
+        /// Branch name
+        branch: String,
+
+        /// Force delete even if not merged
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Clean up stale worktree records; with --merged, remove merged AutoFlow branches
+    Prune {
+        /// Remove AutoFlow branches merged into the base branch, with their worktrees
+        #[arg(long)]
+        merged: bool,
+
+        /// Only branches whose last commit is older than this (e.g. 14d, 36h, 2w;
+        /// defaults to [defaults] worktree_retention_days)
+        #[arg(long, value_name = "AGE", requires = "merged")]
+        older_than: Option<String>,
+
+        /// Show what would be removed without removing it
+        #[arg(long, requires = "merged")]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SprintsCommands {
+    /// List all sprints
+    List,
+
+    /// Show sprint details
+    Show {
+        /// Sprint ID
+        id: u32,
+
+        /// Show integration points
+        #[arg(long)]
+        integration: bool,
+    },
+
+    /// Create a sprint from a template in ~/.autoflow/sprint-templates (no LLM)
+    Create {
+        /// Template name, e.g. crud (lists templates when omitted)
+        #[arg(short, long)]
+        template: Option<String>,
+
+        /// Template parameter as key=value (repeatable)
+        #[arg(short, long = "param", value_name = "KEY=VALUE")]
+        params: Vec<String>,
+
+        /// Print the rendered sprint without writing SPRINTS.yml
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Export the sprint plan (json, csv or md)
+    Export {
+        /// Output format: json, csv or md
+        #[arg(short, long, default_value = "json")]
+        format: String,
+
+        /// Write to a file instead of stdout
+        #[arg(short, long, value_hint = ValueHint::FilePath, value_parser = user_path::<String>)]
+        output: Option<String>,
+    },
+
+    /// Import sprints from a json, yaml or csv file
+    Import {
+        /// File to import
+        #[arg(value_hint = ValueHint::FilePath, value_parser = user_path::<String>)]
+        file: String,
+
+        /// Input format (defaults to the file extension)
+        #[arg(short, long)]
+        format: Option<String>,
+
+        /// What to do when a sprint ID already exists: fail, replace, skip, renumber
+        #[arg(long, default_value = "fail")]
+        on_conflict: String,
+
+        /// Show what would change without writing SPRINTS.yml
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Hold a sprint where it is; runs pass over it until it is unpaused
+    Pause {
+        /// Sprint ID
+        id: u32,
+    },
+
+    /// Return a paused sprint to the phase it was paused at
+    Unpause {
+        /// Sprint ID
+        id: u32,
+    },
+
+    /// Close a sprint without its work; sprints depending on it can run
+    Skip {
+        /// Sprint ID
+        id: u32,
+
+        /// Why the sprint is skipped, recorded in SPRINTS.yml
+        #[arg(short, long)]
+        reason: String,
+    },
+
+    /// Abandon a sprint; sprints depending on it keep waiting
+    Cancel {
+        /// Sprint ID
+        id: u32,
+
+        /// Why the sprint is cancelled, recorded in SPRINTS.yml
+        #[arg(short, long)]
+        reason: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AgentsCommands {
+    /// Show an agent's system prompt
+    Show {
+        /// Agent name, e.g. reviewer
+        name: String,
+
+        /// Include the project's overlay from .autoflow/agent-overrides/<name>.md
+        #[arg(long)]
+        resolved: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RunsCommands {
+    /// Compare two runs' metrics: phase success rates, retries, tokens, durations
+    Compare {
+        /// Baseline run (id or prefix)
+        run_a: String,
+
+        /// Run to compare against it (id or prefix)
+        run_b: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DebugCommands {
+    /// Check out the code and sprint state as they were when a phase started, in a temp worktree
+    At {
+        /// Sprint ID
+        #[arg(long)]
+        sprint: u32,
+
+        /// Phase, e.g. RUN_UNIT_TESTS
+        #[arg(long)]
+        phase: String,
+
+        /// Only look in this run (id or prefix; default: the latest run of the phase)
+        #[arg(long)]
+        run: Option<String>,
+
+        /// Which time the phase ran, counting from 1 (default: the last)
+        #[arg(long)]
+        attempt: Option<usize>,
+
+        /// Where to create the worktree (default: a new temp directory)
+        #[arg(long, value_hint = ValueHint::DirPath, value_parser = user_path::<std::path::PathBuf>)]
+        path: Option<std::path::PathBuf>,
+    },
+
+    /// List recorded phase snapshots
+    List {
+        /// Only this sprint's
+        #[arg(long)]
+        sprint: Option<u32>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum WorkflowsCommands {
+    /// List workflows and their phases
+    List,
+
+    /// Show a workflow's phase graph
+    Show {
+        /// Workflow: a type (implementation, documentation, test, infrastructure, refactor, quick, deployment) or a custom workflow name
+        workflow: String,
+
+        /// Also print the graph as a Mermaid flowchart
+        #[arg(long)]
+        mermaid: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum EnvCommands {
+    /// Start development environment
+    Start,
+
+    /// Stop development environment
+    Stop,
+
+    /// Restart development environment
+    Restart,
+
+    /// View environment logs
+    Logs {
+        /// Follow logs
+        #[arg(short, long)]
+        follow: bool,
+    },
+
+    /// Check environment health
+    Health,
+
+    /// Show the command each env action runs and the task runner targets found
+    Targets,
+
+    /// Link or build the shared dependency and container caches for this checkout
+    Bootstrap,
+
+    /// Show the size of this project's environment caches
+    Cache {
+        /// Delete the caches
+        #[arg(long)]
+        clear: bool,
+    },
+}
+
+/// What `autoflow install` puts in place
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InstallComponent {
+    /// Agent definitions in ~/.claude/agents
+    Agents,
+    /// Skills in ~/.claude/skills
+    Skills,
+    /// Project and sprint templates in ~/.autoflow
+    Templates,
+    /// JSON schemas in ~/.autoflow/schemas
+    Schemas,
+    /// Completion script for the shell in $SHELL
+    Completions,
+}
+
+impl InstallComponent {
+    pub const ALL: &'static [InstallComponent] = &[Self::Agents, Self::Skills, Self::Templates, Self::Schemas, Self::Completions];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Agents => "agents",
+            Self::Skills => "skills",
+            Self::Templates => "templates",
+            Self::Schemas => "schemas",
+            Self::Completions => "completions",
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum McpCommands {
+    /// Install MCP servers (installs all recommended if none specified)
+    Install {
+        /// Specific servers to install (memory, playwright, github, postgres, etc.)
+        servers: Vec<String>,
+
+        /// Install to project level (.autoflow/settings.json) instead of global
+        #[arg(long)]
+        project: bool,
+    },
+
+    /// List installed MCP servers
+    List,
+
+    /// Show information about available servers
+    Info {
+        /// Server name (optional, shows all if not specified)
+        server: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TelemetryCommands {
+    /// Opt in to anonymous usage telemetry
+    On {
+        /// Collector URL to send events to
+        #[arg(long)]
+        endpoint: Option<String>,
+    },
+
+    /// Opt out and discard queued events
+    Off,
+
+    /// Show telemetry settings and what is collected
+    Status,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    /// Show a setting's effective value and where it comes from (all settings when no key is given)
+    Get {
+        /// max_iterations, auto_commit, model, update_check or live_logging
+        key: Option<String>,
+    },
+
+    /// Save a setting to .autoflow/config.yml
+    Set {
+        /// max_iterations, auto_commit, model, update_check or live_logging
+        key: String,
+
+        value: String,
+    },
+
+    /// Remove a setting from .autoflow/config.yml, back to the default
+    Unset {
+        key: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HooksCommands {
+    /// Install pre-commit and post-merge hooks
+    Install {
+        /// Replace existing hooks not installed by AutoFlow
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Remove hooks installed by AutoFlow
+    Uninstall,
+
+    /// Run a hook (called from the installed hook scripts)
+    Run {
+        /// Hook name: pre-commit or post-merge
+        hook: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum GenerateCommands {
+    /// Generate typed API client/types from an OpenAPI file or API_SPEC.md
+    ApiClient {
+        /// Spec file (default: openapi.yaml/json if present, else .autoflow/docs/API_SPEC.md)
+        #[arg(short, long, value_hint = ValueHint::FilePath, value_parser = user_path::<String>)]
+        spec: Option<String>,
+
+        /// Target language: typescript, rust, python (default: detected)
+        #[arg(short, long)]
+        lang: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PatchCommands {
+    /// List queued and applied patch bundles
+    List,
+
+    /// Print a patch bundle
+    Show {
+        /// Bundle file name, e.g. sprint-3-write_code.patch
+        #[arg(value_name = "BUNDLE")]
+        file: String,
+    },
+
+    /// Apply pending bundles to the working tree and index, in order
+    Apply {
+        /// Only this sprint's bundles
+        #[arg(long)]
+        sprint: Option<u32>,
+    },
+
+    /// Reverse applied bundles, newest first
+    Undo {
+        /// Only this sprint's bundles
+        #[arg(long)]
+        sprint: Option<u32>,
+    },
+
+    /// Drop every pending bundle and the shadow checkout holding their work
+    Discard,
+}
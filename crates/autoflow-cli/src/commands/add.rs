@@ -1,10 +1,15 @@
 use anyhow::{bail, Context};
-use autoflow_data::{Sprint, SprintsYaml};
+use autoflow_data::{AutoFlowError, SprintsYaml};
 use colored::*;
 use std::fs;
 use std::path::Path;
 
-pub async fn run(description: String, requirements: Option<String>) -> anyhow::Result<()> {
+pub async fn run(
+    description: String,
+    requirements: Option<String>,
+    after: Option<u32>,
+    depends_on: Vec<u32>,
+) -> anyhow::Result<()> {
     println!("{}", "➕ Adding new feature...".bright_cyan().bold());
     println!("\nFeature: {}", description.bright_blue());
 
@@ -15,11 +20,7 @@ pub async fn run(description: String, requirements: Option<String>) -> anyhow::R
     // Check if project is initialized
     let sprints_path = ".autoflow/SPRINTS.yml";
     if !Path::new(sprints_path).exists() {
-        bail!(
-            "{}\nRun {} first",
-            "Project not initialized.".red(),
-            "autoflow init".bright_blue()
-        );
+        return Err(AutoFlowError::NotInitialized.into());
     }
 
     // Load existing sprints
@@ -29,6 +30,12 @@ pub async fn run(description: String, requirements: Option<String>) -> anyhow::R
 
     println!("Current sprints: {}", sprints_data.sprints.len().to_string().bright_blue());
 
+    for id in after.iter().chain(depends_on.iter()) {
+        if !sprints_data.sprints.iter().any(|s| s.id == *id) {
+            bail!("Sprint {} not found in {}", id, sprints_path);
+        }
+    }
+
     // Check if INTEGRATION_GUIDE.md exists
     let integration_guide_path = ".autoflow/INTEGRATION_GUIDE.md";
     let has_integration_guide = Path::new(integration_guide_path).exists();
@@ -64,6 +71,12 @@ Feature: {}
     }
 
     // Add existing project info
+    let next_id = sprints_data.sprints.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+    let existing_summary: String = sprints_data
+        .sprints
+        .iter()
+        .map(|s| format!("- Sprint {} ({:?}): {}\n", s.id, s.status, s.goal))
+        .collect();
     context.push_str(&format!(
         r#"
 
@@ -71,54 +84,78 @@ Feature: {}
 - Total Sprints: {}
 - Next Sprint ID: {}
 
-Generate new sprints that integrate with the existing codebase.
+## Existing Sprints
+{}
+Generate ONE new sprint (id {}) that integrates with the existing codebase.
 Focus on incremental, testable changes.
+Fill in integration_points.modifies / integration_points.creates with the files it touches.
+Do NOT write or modify .autoflow/SPRINTS.yml - AutoFlow merges the sprint itself.
 Return ONLY valid YAML (no markdown code blocks).
 "#,
         sprints_data.project.total_sprints,
-        sprints_data.sprints.len() + 1
+        next_id,
+        existing_summary,
+        next_id
     ));
 
-    // Execute make-sprints agent
+    // Execute make-sprints agent; the planner validates the patched plan and
+    // falls back to a schema-valid template sprint if the agent can't produce one
     println!("  Spawning make-sprints agent...");
+    let (mut new_sprint, from_agent) = autoflow_core::generate_validated_sprint(
+        &sprints_data,
+        &context,
+        next_id,
+        &description,
+        3,
+    )
+    .await
+    .context("Failed to generate a valid sprint")?;
+
+    if from_agent {
+        println!("  {} Agent generated sprint", "✓".green());
+    } else {
+        println!("  {} Agent output was unusable, created template sprint instead", "⚠".yellow());
+    }
 
-    use autoflow_agents::execute_agent;
-
-    let next_id = sprints_data.sprints.len() as u32 + 1;
-
-    // Try to execute the agent, fall back to template if it fails
-    let new_sprint = match execute_agent("make-sprints", &context, 10, None).await {
-        Ok(result) => {
-            if result.success {
-                // Parse the agent output to extract sprint
-                match parse_sprint_from_output(&result.output, next_id, &description) {
-                    Ok(sprint) => {
-                        println!("  {} Agent generated sprint", "✓".green());
-                        sprint
-                    }
-                    Err(e) => {
-                        println!("  {} Failed to parse agent output: {}", "⚠".yellow(), e);
-                        println!("  Creating template sprint instead...");
-                        create_template_sprint(next_id, &description)
-                    }
-                }
-            } else {
-                println!("  {} Agent execution failed", "⚠".yellow());
-                println!("  Creating template sprint instead...");
-                create_template_sprint(next_id, &description)
-            }
+    // Sprints left on the default workflow get the one their content calls for
+    for change in autoflow_core::select_workflows(std::slice::from_mut(&mut new_sprint)) {
+        println!("  {} Using the {} workflow ({})", "✓".green(), change.recommended, change.reason);
+    }
+    for mismatch in autoflow_core::workflow_mismatches(std::slice::from_ref(&new_sprint)) {
+        println!("  {} {}", "⚠".yellow(), mismatch);
+    }
+
+    // Work out dependencies: explicit flags first, then inferred from integration points
+    let mut dependencies: Vec<String> = Vec::new();
+    for id in after.iter().chain(depends_on.iter()) {
+        let dep = id.to_string();
+        if !dependencies.contains(&dep) {
+            dependencies.push(dep);
+        }
+    }
+
+    let analysis = autoflow_core::CodebaseAnalyzer::new(".").analyze().ok();
+    let analysis_points = analysis.map(|a| a.integration_points).unwrap_or_default();
+    let inferred = autoflow_core::infer_dependencies(&new_sprint, &sprints_data.sprints, &analysis_points);
+    for dep in &inferred {
+        if !dependencies.contains(dep) {
+            dependencies.push(dep.clone());
         }
-        Err(e) => {
-            println!("  {} Agent spawn failed: {}", "⚠".yellow(), e);
-            println!("  Creating template sprint instead...");
-            create_template_sprint(next_id, &description)
+    }
+    for dep in &new_sprint.dependencies {
+        let known = sprints_data.sprints.iter().any(|s| s.id.to_string() == *dep);
+        if known && !dependencies.contains(dep) {
+            dependencies.push(dep.clone());
         }
-    };
+    }
+    new_sprint.dependencies = dependencies;
 
-    // Add to sprints
-    sprints_data.sprints.push(new_sprint.clone());
-    sprints_data.project.total_sprints = sprints_data.sprints.len() as u32;
-    sprints_data.project.last_updated = chrono::Utc::now();
+    // Insert right after --after's sprint (ids are never renumbered so existing
+    // references stay valid), otherwise append
+    let after = after.filter(|id| sprints_data.sprint(*id).is_some());
+    if let Err(e) = sprints_data.add_sprint(new_sprint.clone(), after) {
+        bail!("Refusing to save SPRINTS.yml, patched plan is invalid:\n{}", e);
+    }
 
     // Save updated sprints
     println!("\n{}", "Saving updated sprints...".bright_cyan());
@@ -134,6 +171,14 @@ Return ONLY valid YAML (no markdown code blocks).
     println!("  Status: {}", format!("{:?}", new_sprint.status).bright_yellow());
     println!("  Effort: {}", new_sprint.total_effort.bright_blue());
     println!("  Tasks: {}", new_sprint.tasks.len().to_string().bright_blue());
+    if new_sprint.dependencies.is_empty() {
+        println!("  Depends on: {}", "none".bright_black());
+    } else {
+        println!("  Depends on: {}", new_sprint.dependencies.join(", ").bright_blue());
+        if !inferred.is_empty() {
+            println!("    {} inferred from integration points: {}", "ℹ".blue(), inferred.join(", "));
+        }
+    }
 
     println!("\n{}", "Next steps:".bright_cyan());
     println!("  1. Review sprint in {}", sprints_path.bright_blue());
@@ -141,106 +186,3 @@ Return ONLY valid YAML (no markdown code blocks).
 
     Ok(())
 }
-
-/// Parse sprint from agent output
-fn parse_sprint_from_output(output: &str, next_id: u32, _description: &str) -> anyhow::Result<Sprint> {
-    // Extract YAML from markdown if present
-    let yaml_content = if output.contains("```yaml") {
-        output
-            .split("```yaml")
-            .nth(1)
-            .and_then(|s| s.split("```").next())
-            .unwrap_or(output)
-            .trim()
-    } else {
-        output.trim()
-    };
-
-    // Write to temp file and parse with SprintsYaml
-    let temp_path = format!("/tmp/sprint-{}.yml", next_id);
-    fs::write(&temp_path, yaml_content)
-        .context("Failed to write temp sprint file")?;
-
-    // Try to load as full SprintsYaml document
-    match SprintsYaml::load(&temp_path) {
-        Ok(sprints_yaml) => {
-            // Extract first sprint
-            if let Some(mut sprint) = sprints_yaml.sprints.into_iter().next() {
-                sprint.id = next_id;
-                let _ = fs::remove_file(&temp_path); // Clean up
-                Ok(sprint)
-            } else {
-                let _ = fs::remove_file(&temp_path);
-                anyhow::bail!("No sprints found in agent output")
-            }
-        }
-        Err(e) => {
-            let _ = fs::remove_file(&temp_path);
-            anyhow::bail!("Failed to parse sprint YAML: {}", e)
-        }
-    }
-}
-
-/// Create a template sprint (used when agent is not available)
-fn create_template_sprint(id: u32, description: &str) -> Sprint {
-    use autoflow_data::{SprintStatus, WorkflowType, Task, TaskType, Priority, TestingRequirements, TestRequirement};
-    use chrono::Utc;
-
-    Sprint {
-        id,
-        goal: description.to_string(),
-        status: SprintStatus::Pending,
-        workflow_type: WorkflowType::Implementation,
-        duration: Some("Week 1".to_string()),
-        total_effort: "8h".to_string(),
-        max_effort: "15h".to_string(),
-        started: None,
-        last_updated: Utc::now(),
-        completed_at: None,
-        deliverables: vec![
-            format!("{} implementation", description),
-            "Unit tests".to_string(),
-            "E2E tests".to_string(),
-        ],
-        tasks: vec![
-            Task {
-                id: format!("task-{:03}", id),
-                title: format!("Implement {}", description),
-                description: Some(format!("Implement {} feature", description)),
-                r#type: TaskType::Implementation,
-                doc_reference: None,
-                acceptance_criteria: vec!["Feature works as specified".to_string()],
-                test_specification: Some("Unit tests pass".to_string()),
-                effort: "6h".to_string(),
-                priority: Priority::High,
-                feature: description.to_string(),
-                docs: vec![],
-                business_rules: vec![
-                    "Follow existing code patterns".to_string(),
-                    "Maintain backward compatibility".to_string(),
-                ],
-                integration_notes: None,
-                testing: TestingRequirements {
-                    unit_tests: Some(TestRequirement {
-                        required: true,
-                        reason: "Core functionality validation".to_string(),
-                    }),
-                    integration_tests: None,
-                    e2e_tests: None,
-                },
-                status: autoflow_data::TaskStatus::Pending,
-                committed_at: None,
-                reviewed_at: None,
-                tested_at: None,
-                done_at: None,
-                git_commit: None,
-            },
-        ],
-        dependencies: vec![],
-        integration_points: None,
-        blocked_count: None,
-        must_complete_first: false,
-        failure_reports: vec![],
-        uses_blocker_resolver: false,
-    }
-}
@@ -1,13 +1,19 @@
 use colored::*;
 use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
+
+use crate::AgentsCommands;
+
+pub async fn run(detailed: bool, command: Option<AgentsCommands>) -> anyhow::Result<()> {
+    if let Some(AgentsCommands::Show { name, resolved }) = command {
+        return show(&name, resolved).await;
+    }
 
-pub async fn run(detailed: bool) -> anyhow::Result<()> {
     println!("{}", "🤖 Available Agents".bright_cyan().bold());
 
     // Load agents from ~/.claude/agents/
-    let home = std::env::var("HOME")?;
-    let agents_dir = PathBuf::from(home).join(".claude/agents");
+    let home = autoflow_utils::require_home()?;
+    let agents_dir = home.join(".claude/agents");
 
     if !agents_dir.exists() {
         println!("\n{}", "No agents directory found".yellow());
@@ -53,11 +59,17 @@ pub async fn run(detailed: bool) -> anyhow::Result<()> {
     println!("\nFound {} agents\n", agents.len().to_string().bright_blue());
 
     for (name, description, model) in &agents {
+        let overlay = autoflow_agents::agent_override_path(Path::new("."), name).exists();
         if detailed {
             println!("{}", format!("━━━━ {} ━━━━", name).bright_cyan());
             println!("  {}: {}", "Description".bold(), description);
             println!("  {}: {}", "Model".bold(), model.bright_blue());
+            if overlay {
+                println!("  {}: {}", "Overlay".bold(), autoflow_agents::agent_override_path(Path::new(""), name).display());
+            }
             println!();
+        } else if overlay {
+            println!("  {} - {} {}", name.bright_blue(), description, "(+ project overlay)".yellow());
         } else {
             println!("  {} - {}", name.bright_blue(), description);
         }
@@ -70,6 +82,83 @@ pub async fn run(detailed: bool) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// System prompts above this many tokens get a warning
+const LARGE_PROMPT_TOKENS: usize = 6_000;
+
+/// Print an agent's frontmatter, resolved model, checked tools, prompt size and prompt
+async fn show(name: &str, resolved: bool) -> anyhow::Result<()> {
+    let agent = autoflow_agents::load_agent(name, resolved).await?;
+    let has_overlay = autoflow_agents::load_agent_override(Path::new("."), name).is_some();
+    let overlay = autoflow_agents::agent_override_path(Path::new(""), name);
+
+    println!("{}", format!("🤖 {}", name).bright_cyan().bold());
+    println!("  {}: {}", "File".bold(), agent.path.display());
+
+    let content = fs::read_to_string(&agent.path)?;
+    let frontmatter: Vec<&str> = content.lines().skip(1).take_while(|l| l.trim() != "---").collect();
+    println!("\n{}", "Frontmatter".bold());
+    for line in &frontmatter {
+        println!("  {}", line);
+    }
+
+    println!("\n{}", "Model".bold());
+    println!("  {}", agent.model.bright_blue());
+    if let Ok(Some((_, source))) = autoflow_data::ProjectConfig::current().resolve("model") {
+        println!("  {}", format!("(from {})", source).bright_black());
+    }
+    let routing = autoflow_data::Config::global().map(|c| c.routing).unwrap_or_default();
+    if let Some(cheap) = autoflow_core::RoutingStats::load(Path::new(".")).cheap_model_for(&routing, name) {
+        println!("  {}", format!("tries {} first (model routing)", cheap).bright_black());
+    }
+
+    println!("\n{}", "Tools".bold());
+    if agent.tools.is_empty() {
+        println!("  {}", "(none listed: the CLI's defaults apply)".bright_black());
+    }
+    let mut unknown = 0;
+    for tool in &agent.tools {
+        if autoflow_agents::is_known_tool(tool) {
+            println!("  {} {}", "✓".green(), tool);
+        } else {
+            unknown += 1;
+            match autoflow_agents::suggest_tool(tool) {
+                Some(suggestion) => println!("  {} {} (did you mean {}?)", "✗".red(), tool, suggestion.bright_blue()),
+                None => println!("  {} {} (not a known claude tool)", "✗".red(), tool),
+            }
+        }
+    }
+
+    let tokens = autoflow_agents::estimate_tokens(&agent.system_prompt);
+    println!("\n{}", "System prompt".bold());
+    println!("  ~{} tokens ({} characters)", tokens, agent.system_prompt.chars().count());
+    if tokens > LARGE_PROMPT_TOKENS {
+        println!(
+            "  {} Larger than {} tokens; every turn of this agent pays for it",
+            "⚠".yellow(),
+            LARGE_PROMPT_TOKENS
+        );
+    }
+    if resolved {
+        if has_overlay {
+            println!("  {}", format!("Base prompt + overlay from {}", overlay.display()).bright_black());
+        } else {
+            println!("  {}", format!("No overlay ({} not found)", overlay.display()).bright_black());
+        }
+    } else if has_overlay {
+        println!(
+            "  {}",
+            format!("Base prompt only; {} also applies (see --resolved)", overlay.display()).bright_black()
+        );
+    }
+    println!("\n{}", "─".repeat(80).dimmed());
+    println!("{}", agent.system_prompt);
+
+    if unknown > 0 {
+        anyhow::bail!("{} unknown tool(s) in {}", unknown, agent.path.display());
+    }
+    Ok(())
+}
+
 fn extract_frontmatter_field(content: &str, field: &str) -> Option<String> {
     let lines: Vec<&str> = content.lines().collect();
     let mut in_frontmatter = false;
@@ -1,18 +1,16 @@
-use anyhow::{bail, Context};
-use autoflow_core::CodebaseAnalyzer;
+use anyhow::Context;
+use autoflow_core::{CodebaseAnalyzer, ANALYSIS_PATH};
+use autoflow_data::AutoFlowError;
 use colored::*;
 use std::path::Path;
 
 pub async fn run() -> anyhow::Result<()> {
     println!("{}", "🔍 Analyzing codebase...".bright_cyan().bold());
 
-    // Check if project is initialized
-    if !Path::new(".autoflow").exists() {
-        bail!(
-            "{}\nRun {} first",
-            "Project not initialized.".red(),
-            "autoflow init".bright_blue()
-        );
+    // Check if project is initialized (read-only analysis saves nothing, so needn't be)
+    let read_only = autoflow_data::is_read_only();
+    if !read_only && !Path::new(".autoflow").exists() {
+        return Err(AutoFlowError::NotInitialized.into());
     }
 
     // Get current directory
@@ -37,6 +35,12 @@ pub async fn run() -> anyhow::Result<()> {
         println!("  Version: {}", version.bright_blue());
     }
     println!("  Package Manager: {}", analysis.tech_stack.package_manager.bright_blue());
+    if let Some(test) = &analysis.tech_stack.test_command {
+        println!("  Test Command: {}", test.bright_blue());
+    }
+    if let Some(lint) = &analysis.tech_stack.lint_command {
+        println!("  Lint Command: {}", lint.bright_blue());
+    }
 
     if !analysis.frameworks.is_empty() {
         println!("\n{}", "Frameworks".bright_green().bold());
@@ -74,13 +78,21 @@ pub async fn run() -> anyhow::Result<()> {
         }
     }
 
+    if read_only {
+        println!("\n{}", "Read-only mode: analysis not saved".bright_yellow());
+        return Ok(());
+    }
+
     // Save to INTEGRATION_GUIDE.md
     let guide_path = ".autoflow/INTEGRATION_GUIDE.md";
     println!("\n{}", "Saving analysis...".bright_cyan());
     analysis.save(guide_path)
         .context("Failed to save integration guide")?;
 
-    println!("{} {}", "✅".green(), format!("Analysis saved to {}", guide_path).bright_green());
+    analysis.save_json(ANALYSIS_PATH)
+        .context("Failed to save analysis.json")?;
+
+    println!("{} {}", "✅".green(), format!("Analysis saved to {} and {}", guide_path, ANALYSIS_PATH).bright_green());
 
     println!("\n{}", "Next steps:".bright_cyan());
     println!("  1. Review {} for integration patterns", guide_path.bright_blue());
@@ -0,0 +1,23 @@
+use anyhow::Context;
+use autoflow_data::{read_signoffs, render_board, render_signoffs, write_board, AutoFlowError, SprintsYaml, BOARD_PATH, SIGNOFFS_PATH};
+use colored::*;
+use std::path::Path;
+
+pub async fn run(stdout: bool) -> anyhow::Result<()> {
+    let sprints_path = ".autoflow/SPRINTS.yml";
+    if !Path::new(sprints_path).exists() {
+        return Err(AutoFlowError::NotInitialized.into());
+    }
+
+    let sprints = SprintsYaml::load(sprints_path)
+        .context("Failed to load SPRINTS.yml")?;
+
+    if stdout {
+        print!("{}{}", render_board(&sprints), render_signoffs(&read_signoffs(SIGNOFFS_PATH)));
+    } else {
+        write_board(&sprints, BOARD_PATH).context("Failed to write BOARD.md")?;
+        println!("{} Board written to {}", "✓".green(), BOARD_PATH.bright_blue());
+    }
+
+    Ok(())
+}
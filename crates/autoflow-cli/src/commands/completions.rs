@@ -0,0 +1,18 @@
+use anyhow::Result;
+use clap::CommandFactory;
+use clap_complete::{generate, Shell};
+
+use crate::Cli;
+
+/// Print a completion script for `shell` to stdout
+///
+/// Usage:
+///   autoflow completions bash > ~/.local/share/bash-completion/completions/autoflow
+///   autoflow completions zsh > "${fpath[1]}/_autoflow"
+///   autoflow completions fish > ~/.config/fish/completions/autoflow.fish
+pub fn run(shell: Shell) -> Result<()> {
+    let mut cmd = Cli::command();
+    let name = cmd.get_name().to_string();
+    generate(shell, &mut cmd, name, &mut std::io::stdout());
+    Ok(())
+}
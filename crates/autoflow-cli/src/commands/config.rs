@@ -0,0 +1,54 @@
+use anyhow::Result;
+use autoflow_data::{ProjectConfig, PROJECT_CONFIG_FILE};
+use colored::*;
+
+use crate::ConfigCommands;
+
+pub fn run(cmd: ConfigCommands) -> Result<()> {
+    let root = std::env::current_dir()?;
+    let mut settings = ProjectConfig::load(&root)?;
+    match cmd {
+        ConfigCommands::Get { key: Some(key) } => {
+            // Bare value, for scripts; nothing when the default applies
+            if let Some((value, _)) = settings.resolve(&key)? {
+                println!("{}", value);
+            }
+        }
+        ConfigCommands::Get { key: None } => {
+            println!("{}", "⚙️  Project settings".bright_cyan().bold());
+            println!();
+            for (key, env) in ProjectConfig::KEYS {
+                match settings.resolve(key)? {
+                    Some((value, source)) => {
+                        println!("  {:<16} {:<28} {}", key, value.bright_blue(), format!("({})", source).bright_black())
+                    }
+                    None => println!("  {:<16} {:<28} {}", key, "(default)".bright_black(), format!("(env {})", env).bright_black()),
+                }
+            }
+        }
+        ConfigCommands::Set { key, value } => {
+            settings.set(&key, Some(&value))?;
+            settings.save(&root)?;
+            let saved = settings.get(&key)?.unwrap_or_default();
+            println!("{} {} = {} in {}", "✓".green(), key, saved.bright_blue(), PROJECT_CONFIG_FILE);
+            warn_if_overridden(&settings, &key)?;
+        }
+        ConfigCommands::Unset { key } => {
+            settings.set(&key, None)?;
+            settings.save(&root)?;
+            println!("{} {} removed from {}", "✓".green(), key, PROJECT_CONFIG_FILE);
+            warn_if_overridden(&settings, &key)?;
+        }
+    }
+    Ok(())
+}
+
+/// The environment variable still wins over what was just saved
+fn warn_if_overridden(settings: &ProjectConfig, key: &str) -> Result<()> {
+    if let Some((value, source)) = settings.resolve(key)? {
+        if source != PROJECT_CONFIG_FILE {
+            println!("  {} {} is set, so {} stays {} in this shell", "⚠".yellow(), source, key, value.bright_blue());
+        }
+    }
+    Ok(())
+}
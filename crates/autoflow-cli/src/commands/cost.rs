@@ -0,0 +1,103 @@
+use anyhow::{Context, Result};
+use autoflow_agents::{CostTracker, TokenUsage, COSTS_PATH};
+use colored::*;
+use std::path::Path;
+
+pub async fn run(run: Option<String>, json: bool) -> Result<()> {
+    let tracker = CostTracker::load(Path::new("."));
+    match run {
+        Some(prefix) => show_run(&tracker, &prefix, json),
+        None => show_all(&tracker, json),
+    }
+}
+
+fn show_all(tracker: &CostTracker, json: bool) -> Result<()> {
+    if json {
+        let report = serde_json::json!({
+            "total": tracker.total(),
+            "sprints": tracker.sprint_totals(),
+            "runs": tracker.runs,
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!("{}", "💰 Spend".bright_cyan().bold());
+    if tracker.runs.is_empty() {
+        println!("\n  {} Nothing recorded yet in {}", "ℹ".bright_cyan(), COSTS_PATH);
+        return Ok(());
+    }
+
+    println!("\n{}", "Runs".bright_white().bold());
+    print_header("Run");
+    for (run_id, run) in tracker.runs_by_start() {
+        let started = run.started_at.map(|t| t.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default();
+        print_row(&format!("{} {}", &run_id[..run_id.len().min(10)], started), &run.total);
+    }
+
+    let sprints = tracker.sprint_totals();
+    if !sprints.is_empty() {
+        println!("\n{}", "Sprints (all runs)".bright_white().bold());
+        print_header("Sprint");
+        for (sprint, usage) in &sprints {
+            print_row(&format!("#{}", sprint), usage);
+        }
+    }
+
+    println!();
+    print_row("Total", &tracker.total());
+    Ok(())
+}
+
+fn show_run(tracker: &CostTracker, prefix: &str, json: bool) -> Result<()> {
+    let (run_id, run) = tracker
+        .find_run(prefix)
+        .with_context(|| format!("No single run matching '{}' in {}", prefix, COSTS_PATH))?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&serde_json::json!({ "run_id": run_id, "cost": run }))?);
+        return Ok(());
+    }
+
+    println!("{} {}", "💰 Run".bright_cyan().bold(), run_id);
+    if !run.sprints.is_empty() {
+        println!("\n{}", "Sprints".bright_white().bold());
+        print_header("Sprint");
+        for (sprint, usage) in &run.sprints {
+            print_row(&format!("#{}", sprint), usage);
+        }
+    }
+    println!("\n{}", "Agents".bright_white().bold());
+    print_header("Agent");
+    let mut agents: Vec<_> = run.agents.iter().collect();
+    agents.sort_by(|a, b| b.1.cost_usd.total_cmp(&a.1.cost_usd));
+    for (agent, usage) in agents {
+        print_row(agent, usage);
+    }
+    println!();
+    print_row("Total", &run.total);
+    Ok(())
+}
+
+fn print_header(label: &str) {
+    println!(
+        "  {:<28} {:>6} {:>12} {:>12} {:>12} {:>10}",
+        label.dimmed(),
+        "Calls".dimmed(),
+        "Input".dimmed(),
+        "Cached".dimmed(),
+        "Output".dimmed(),
+        "Cost".dimmed()
+    );
+}
+
+fn print_row(label: &str, usage: &TokenUsage) {
+    println!(
+        "  {:<28} {:>6} {:>12} {:>12} {:>12} {:>10}",
+        label,
+        usage.calls,
+        usage.input_tokens,
+        usage.cache_read_tokens + usage.cache_write_tokens,
+        usage.output_tokens,
+        format!("${:.2}", usage.cost_usd).bright_green()
+    );
+}
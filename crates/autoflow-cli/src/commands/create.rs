@@ -1,14 +1,99 @@
 use anyhow::{Context, Result};
 use autoflow_agents::{execute_agent, execute_agent_with_retry};
-use autoflow_utils::{extract_yaml_from_output, Paths};
+use autoflow_core::{
+    applicable_doc_sets, endpoint_checklist, mark_integration_work, parse_openapi, render_api_spec_markdown, render_data_model_markdown,
+    select_workflows, workflow_mismatches, ApiSpec, CodebaseAnalysis, CodebaseAnalyzer,
+};
+use autoflow_data::{DocSet, SprintsYaml};
+use autoflow_utils::{extract_yaml_from_output, finish_run, start_run, Paths};
 use colored::*;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::Path;
+
+/// OpenAPI file passed with `--openapi`
+struct OpenApiImport {
+    /// Name the spec is stored under in .autoflow/docs
+    file_name: &'static str,
+    content: String,
+    spec: ApiSpec,
+}
+
+impl OpenApiImport {
+    fn load(path: &str) -> Result<Self> {
+        let content = fs::read_to_string(path).context(format!("Failed to read {}", path))?;
+        let spec = parse_openapi(&content).context(format!("Failed to parse {}", path))?;
+        if spec.endpoints.is_empty() {
+            anyhow::bail!("No endpoints found in {}", path);
+        }
+        let file_name = if path.ends_with(".json") { "openapi.json" } else { "openapi.yaml" };
+        Ok(Self { file_name, content, spec })
+    }
+
+    /// IDEA.md content when the user has none
+    fn idea(&self) -> String {
+        format!(
+            "# {}\n\n## Overview\n{}\n",
+            self.spec.title.as_deref().unwrap_or("API Project"),
+            self.spec
+                .description
+                .as_deref()
+                .unwrap_or("Backend service implementing the imported OpenAPI specification.")
+                .trim()
+        )
+    }
+
+    /// Endpoint list appended to the idea so every doc agent sees the API surface
+    fn api_section(&self) -> String {
+        format!(
+            "## API (imported from OpenAPI)\n\nThe OpenAPI spec in .autoflow/docs/{} is the source of truth for the API. Implement these endpoints:\n\n{}",
+            self.file_name,
+            endpoint_checklist(&self.spec)
+        )
+    }
+
+    /// Store the spec and seed API_SPEC.md and DATA_MODEL.md from it
+    fn seed_docs(&self) -> Result<()> {
+        fs::create_dir_all(".autoflow/docs")?;
+        fs::write(format!(".autoflow/docs/{}", self.file_name), &self.content)?;
+        fs::write(".autoflow/docs/API_SPEC.md", render_api_spec_markdown(&self.spec, self.file_name))?;
+        fs::write(".autoflow/docs/DATA_MODEL.md", render_data_model_markdown(&self.spec))?;
+        Ok(())
+    }
+}
 
-pub async fn run(project_name: Option<String>, idea_file: Option<String>) -> Result<()> {
+pub async fn run(
+    project_name: Option<String>,
+    idea_file: Option<String>,
+    openapi_file: Option<String>,
+    docs: Option<String>,
+    no_ui_docs: bool,
+) -> Result<()> {
     println!("{}", "🚀 Creating new AutoFlow project...".bright_cyan().bold());
     println!();
 
+    let requested_docs = docs.map(|list| DocSet::parse_list(&list)).transpose().map_err(anyhow::Error::msg)?;
+
+    // Parse the OpenAPI file first: its path is relative to where we were run
+    let openapi = match openapi_file {
+        Some(path) => {
+            let import = OpenApiImport::load(&path)?;
+            println!("{}", "📥 Reading OpenAPI spec...".bright_cyan());
+            println!(
+                "  {} {} endpoints, {} models from {}",
+                "✓".green(),
+                import.spec.endpoints.len(),
+                import.spec.models.len(),
+                path.bright_blue()
+            );
+            println!();
+            Some(import)
+        }
+        None => None,
+    };
+
+    // Agents, node_modules and worktrees need room; check before creating anything
+    autoflow_utils::ensure_resources(Path::new("."))?;
+
     // Determine if we're creating a new directory or using current directory
     let (project_dir, project_name, use_current_dir) = if let Some(name) = project_name {
         // Create new directory with given name
@@ -42,6 +127,12 @@ pub async fn run(project_name: Option<String>, idea_file: Option<String>) -> Res
             .context(format!("Failed to read {}", idea_path))?;
         println!("  {} Read from: {}", "✓".green(), idea_path.bright_blue());
         content
+    } else if let Some(import) = openapi
+        .as_ref()
+        .filter(|_| !(use_current_dir && Path::new("IDEA.md").exists()))
+    {
+        println!("{}", "📖 Deriving project idea from the OpenAPI spec...".bright_cyan());
+        import.idea()
     } else if use_current_dir {
         // Current directory mode - IDEA.md must exist
         if !Path::new("IDEA.md").exists() {
@@ -108,6 +199,11 @@ Any specific requirements or constraints:
         return Ok(());
     };
 
+    let idea_content = match &openapi {
+        Some(import) => format!("{}\n\n{}", idea_content.trim_end(), import.api_section()),
+        None => idea_content,
+    };
+
     println!();
 
     // 3. Change to project directory (if we created a new one)
@@ -118,7 +214,7 @@ Any specific requirements or constraints:
     // 4. Initialize git repository
     println!("{}", "🔧 Initializing git repository...".bright_cyan());
     std::process::Command::new("git")
-        .args(&["init"])
+        .args(["init"])
         .output()
         .context("Failed to initialize git repository")?;
     println!("  {} Git initialized", "✓".green());
@@ -127,8 +223,29 @@ Any specific requirements or constraints:
     // 5. Initialize AutoFlow
     println!("{}", "⚙️  Initializing AutoFlow...".bright_cyan());
     super::init::run(None).await?;
+    let run_id = start_run("create");
+    println!("  {} Run: {}", "ℹ".blue(), run_id.dimmed());
     println!();
 
+    // 5.1. An existing codebase is documented as it is, not re-imagined from IDEA.md
+    let existing_codebase: Option<CodebaseAnalysis> = if use_current_dir {
+        CodebaseAnalyzer::new(".").analyze().ok().filter(|a| a.is_existing_codebase())
+    } else {
+        None
+    };
+    if let Some(analysis) = &existing_codebase {
+        println!("{}", "🔍 Existing codebase detected".bright_cyan());
+        println!(
+            "  {} {} project, {} framework(s), {} integration point(s)",
+            "✓".green(),
+            analysis.tech_stack.language.bright_blue(),
+            analysis.frameworks.len(),
+            analysis.integration_points.len()
+        );
+        println!("  {} Docs will describe the current system; sprints are planned as integration work", "ℹ".blue());
+        println!();
+    }
+
     // 5.5. Create project-level Claude configuration
     println!("{}", "🤖 Setting up Claude configuration...".bright_cyan());
     fs::create_dir_all(".claude")?;
@@ -221,30 +338,53 @@ Not on creating documentation, summaries, or organizing files outside `src/` and
     println!("  {} Created .claude/CLAUDE.md with project structure rules", "✓".green());
     println!();
 
+    // 5.6. Seed API docs from the OpenAPI spec
+    if let Some(import) = &openapi {
+        println!("{}", "📥 Importing OpenAPI spec...".bright_cyan());
+        import.seed_docs()?;
+        println!(
+            "  {} Seeded API_SPEC.md and DATA_MODEL.md (spec saved as .autoflow/docs/{})",
+            "✓".green(),
+            import.file_name
+        );
+        println!();
+    }
+
     // 6. Generate comprehensive documentation (split into specialized agents)
     println!("{}", "📚 Generating project documentation...".bright_cyan());
+    let (doc_sets, source) = select_doc_sets(requested_docs, no_ui_docs, &idea_content, openapi.is_some());
+    let skipped: Vec<String> = DocSet::ALL.iter().filter(|s| !doc_sets.contains(s)).map(|s| s.to_string()).collect();
+    let listed: Vec<String> = doc_sets.iter().map(|s| s.to_string()).collect();
+    println!(
+        "  {} Docs: {} ({}{})",
+        "ℹ".blue(),
+        listed.join(", ").bright_blue(),
+        source,
+        if skipped.is_empty() { String::new() } else { format!("; skipping {}", skipped.join(", ")) }
+    );
 
     let base_context = format!(r#"Generate comprehensive project documentation from this idea:
 
 {}
 
 IMPORTANT: All documentation files MUST be created in .autoflow/docs/ directory, NOT in the project root.
-"#, idea_content);
+{}"#, idea_content, existing_codebase.as_ref().map(existing_codebase_section).unwrap_or_default());
 
     // 6.1 Generate foundation docs (BUILD_SPEC, ARCHITECTURE with error handling)
-    println!("  Spawning make-docs-foundation agent...");
-    match execute_agent("make-docs-foundation", &base_context, 15, None).await {
-        Ok(result) => {
-            if result.success {
-                println!("  {} Foundation docs generated (BUILD_SPEC, ARCHITECTURE)", "✓".green());
-            } else {
-                println!("  {} Foundation agent completed with warnings", "⚠".yellow());
+    if doc_sets.contains(&DocSet::Build) {
+        println!("  Spawning make-docs-foundation agent...");
+        match execute_agent("make-docs-foundation", &base_context, 15, None).await {
+            Ok(result) => {
+                if result.success {
+                    println!("  {} Foundation docs generated (BUILD_SPEC, ARCHITECTURE)", "✓".green());
+                } else {
+                    println!("  {} Foundation agent completed with warnings", "⚠".yellow());
+                }
             }
-        }
-        Err(e) => {
-            println!("  {} Failed to generate foundation docs: {}", "⚠".yellow(), e);
-            // Create minimal BUILD_SPEC as fallback
-            let minimal_spec = format!(r#"# Build Specification
+            Err(e) => {
+                println!("  {} Failed to generate foundation docs: {}", "⚠".yellow(), e);
+                // Create minimal BUILD_SPEC as fallback
+                let minimal_spec = format!(r#"# Build Specification
 
 ## Original Idea
 
@@ -256,36 +396,47 @@ To be determined during sprint planning.
 ## Architecture
 To be determined during implementation.
 "#, idea_content);
-            fs::write(".autoflow/docs/BUILD_SPEC.md", minimal_spec)?;
+                fs::write(".autoflow/docs/BUILD_SPEC.md", minimal_spec)?;
+            }
         }
     }
 
     // 6.2 Generate API docs (API_SPEC with data model and security)
-    println!("  Spawning make-docs-api agent...");
-    match execute_agent("make-docs-api", &base_context, 15, None).await {
-        Ok(result) => {
-            if result.success {
-                println!("  {} API docs generated (API_SPEC with data model and security)", "✓".green());
-            } else {
-                println!("  {} API agent completed with warnings", "⚠".yellow());
+    if doc_sets.contains(&DocSet::Api) {
+        let api_context = match &openapi {
+            Some(_) => format!(
+                "{}\nAPI_SPEC.md and DATA_MODEL.md were imported from the OpenAPI spec. Edit API_SPEC.md in place to add the overview, authentication, error handling and security sections. Do NOT remove, rename or change any endpoint, field or model.\n",
+                base_context
+            ),
+            None => base_context.clone(),
+        };
+        println!("  Spawning make-docs-api agent...");
+        match execute_agent("make-docs-api", &api_context, 15, None).await {
+            Ok(result) => {
+                if result.success {
+                    println!("  {} API docs generated (API_SPEC with data model and security)", "✓".green());
+                } else {
+                    println!("  {} API agent completed with warnings", "⚠".yellow());
+                }
+            }
+            Err(e) => {
+                println!("  {} API docs generation failed (may not be applicable): {}", "⚠".yellow(), e);
             }
-        }
-        Err(e) => {
-            println!("  {} API docs generation failed (may not be applicable): {}", "⚠".yellow(), e);
         }
     }
 
     // 6.3 Generate UI docs (UI_SPEC with state management, TESTING_STRATEGY)
-    println!("  Spawning make-docs-ui agent...");
-    match execute_agent_with_retry("make-docs-ui", &base_context, 15, None).await {
-        Ok(result) => {
-            if result.success {
-                println!("  {} UI docs generated (UI_SPEC, TESTING_STRATEGY)", "✓".green());
-            } else {
-                println!("  {} UI agent failed - check .autoflow/.debug/ logs for details", "⚠".yellow());
-                // Create minimal fallback if UI_SPEC doesn't exist
-                if !Path::new(".autoflow/docs/UI_SPEC.md").exists() {
-                    let minimal_ui = format!(r#"# UI Specification
+    if doc_sets.contains(&DocSet::Ui) {
+        println!("  Spawning make-docs-ui agent...");
+        match execute_agent_with_retry("make-docs-ui", &base_context, 15, None).await {
+            Ok(result) => {
+                if result.success {
+                    println!("  {} UI docs generated (UI_SPEC, TESTING_STRATEGY)", "✓".green());
+                } else {
+                    println!("  {} UI agent failed - check .autoflow/.debug/ logs for details", "⚠".yellow());
+                    // Create minimal fallback if UI_SPEC doesn't exist
+                    if !Path::new(".autoflow/docs/UI_SPEC.md").exists() {
+                        let minimal_ui = format!(r#"# UI Specification
 
 ## Original Idea
 
@@ -297,34 +448,19 @@ To be determined during implementation.
 ## Design System
 To be determined during implementation.
 "#, idea_content);
-                    fs::write(".autoflow/docs/UI_SPEC.md", minimal_ui)?;
-                }
-                if !Path::new(".autoflow/docs/TESTING_STRATEGY.md").exists() {
-                    let minimal_testing = r#"# Testing Strategy
-
-## Framework Choices
-- Unit: Vitest/Jest with React Testing Library
-- E2E: Playwright/Cypress
-- Backend: PHPUnit/Pest
-
-## Coverage Requirements
-- Overall: 80% minimum
-- Critical paths: 100%
-
-## What to Test
-- Unit: Business logic, utilities, components, hooks
-- Integration: API endpoints, database ops, auth flows
-- E2E: Critical user flows
-"#;
-                    fs::write(".autoflow/docs/TESTING_STRATEGY.md", minimal_testing)?;
+                        fs::write(".autoflow/docs/UI_SPEC.md", minimal_ui)?;
+                    }
+                    if !Path::new(".autoflow/docs/TESTING_STRATEGY.md").exists() {
+                        let minimal_testing = fallback_testing_strategy();
+                        fs::write(".autoflow/docs/TESTING_STRATEGY.md", minimal_testing)?;
+                    }
                 }
             }
-        }
-        Err(e) => {
-            println!("  {} UI docs generation failed: {}", "⚠".yellow(), e);
-            println!("  {} Creating minimal fallback docs...", "→".yellow());
-            // Create minimal fallback docs
-            let minimal_ui = format!(r#"# UI Specification
+            Err(e) => {
+                println!("  {} UI docs generation failed: {}", "⚠".yellow(), e);
+                println!("  {} Creating minimal fallback docs...", "→".yellow());
+                // Create minimal fallback docs
+                let minimal_ui = format!(r#"# UI Specification
 
 ## Original Idea
 
@@ -336,25 +472,11 @@ To be determined during implementation.
 ## Design System
 To be determined during implementation.
 "#, idea_content);
-            fs::write(".autoflow/docs/UI_SPEC.md", minimal_ui)?;
-
-            let minimal_testing = r#"# Testing Strategy
+                fs::write(".autoflow/docs/UI_SPEC.md", minimal_ui)?;
 
-## Framework Choices
-- Unit: Vitest/Jest with React Testing Library
-- E2E: Playwright/Cypress
-- Backend: PHPUnit/Pest
-
-## Coverage Requirements
-- Overall: 80% minimum
-- Critical paths: 100%
-
-## What to Test
-- Unit: Business logic, utilities, components, hooks
-- Integration: API endpoints, database ops, auth flows
-- E2E: Critical user flows
-"#;
-            fs::write(".autoflow/docs/TESTING_STRATEGY.md", minimal_testing)?;
+                let minimal_testing = fallback_testing_strategy();
+                fs::write(".autoflow/docs/TESTING_STRATEGY.md", minimal_testing)?;
+            }
         }
     }
 
@@ -363,10 +485,14 @@ To be determined during implementation.
     // 7. Create project directory structure
     println!("{}", "📂 Creating project structure...".bright_cyan());
 
-    // Always use a simple src/ and tests/ structure
-    fs::create_dir_all("src")?;
-    fs::create_dir_all("tests")?;
-    println!("  {} Created src/ and tests/ directories", "✓".green());
+    // New projects use a simple src/ and tests/ structure; existing ones keep theirs
+    if existing_codebase.is_none() {
+        fs::create_dir_all("src")?;
+        fs::create_dir_all("tests")?;
+        println!("  {} Created src/ and tests/ directories", "✓".green());
+    } else {
+        println!("  {} Keeping the existing layout", "✓".green());
+    }
     println!();
 
     // 8. Analyze and create integration guide (if applicable)
@@ -393,10 +519,10 @@ To be determined during implementation.
 
     // Load JSON schema - try global location first, then embedded
     let json_schema = {
-        let home = std::env::var("HOME").expect("HOME environment variable not set");
-        let schema_path = PathBuf::from(home).join(".autoflow/schemas/sprints.schema.json");
+        let global_schema = autoflow_utils::autoflow_dir()
+            .and_then(|dir| fs::read_to_string(dir.join("schemas/sprints.schema.json")).ok());
 
-        if let Ok(content) = fs::read_to_string(&schema_path) {
+        if let Some(content) = global_schema {
             content
         } else {
             // Fall back to embedded schema (compiled into binary)
@@ -404,6 +530,22 @@ To be determined during implementation.
         }
     };
 
+    // Imported specs bias planning toward covering every endpoint
+    let openapi_section = match &openapi {
+        Some(import) => format!(
+            "\n# OPENAPI IMPORT\n\nThis project was created from an OpenAPI spec. Plan sprints that implement every endpoint below, grouped by resource, with tasks referencing API_SPEC.md#<METHOD /path>. Do not plan endpoints that are not in the spec unless the docs require them.\n\n{}",
+            endpoint_checklist(&import.spec)
+        ),
+        None => String::new(),
+    };
+
+    // Brownfield plans change existing code, so every sprint declares what it touches
+    let integration_section = if existing_codebase.is_some() {
+        "\n# EXISTING CODEBASE\n\nThis plan extends an existing codebase (see INTEGRATION_GUIDE.md above). Every sprint is integration work: fill in integration_points (modifies, creates, tests_existing, patterns) for EVERY sprint, using the real files and patterns from INTEGRATION_GUIDE.md. Do not plan project setup or scaffolding that already exists.\n"
+    } else {
+        ""
+    };
+
     let sprints_context = format!(r#"Generate a complete sprint plan from the following project documentation:
 
 # JSON SCHEMA (CRITICAL - MUST FOLLOW EXACTLY)
@@ -418,8 +560,8 @@ IMPORTANT SCHEMA REQUIREMENTS:
 - All required fields MUST be present
 - All enum values must match EXACTLY (case-sensitive, use SCREAMING_SNAKE_CASE)
 - Valid task types: IMPLEMENTATION, DOCUMENTATION, TEST, INFRASTRUCTURE, REFACTOR, BUGFIX
-- Valid workflow types: IMPLEMENTATION, DOCUMENTATION, TEST, INFRASTRUCTURE, REFACTOR
-- Valid sprint statuses: PENDING, WRITE_UNIT_TESTS, WRITE_CODE, CODE_REVIEW, REVIEW_FIX, RUN_UNIT_TESTS, UNIT_FIX, WRITE_E2E_TESTS, RUN_E2E_TESTS, E2E_FIX, COMPLETE, DONE, BLOCKED
+- Valid workflow types: IMPLEMENTATION, DOCUMENTATION, TEST, INFRASTRUCTURE, REFACTOR, DEPLOYMENT
+- Valid sprint statuses: PENDING, WRITE_UNIT_TESTS, WRITE_CODE, CODE_REVIEW, REVIEW_FIX, RUN_UNIT_TESTS, UNIT_FIX, WRITE_E2E_TESTS, RUN_E2E_TESTS, E2E_FIX, DEPLOY, SMOKE_TEST, ROLLBACK, COMPLETE, DONE, BLOCKED
 - All sprints must start with status: PENDING
 - Include last_updated timestamp in ISO 8601 format
 - CRITICAL: dependencies MUST be an array of strings (sprint IDs), NOT maps/objects
@@ -463,7 +605,7 @@ Generate a complete sprint plan from the following project documentation:
 
 # INTEGRATION_GUIDE.md
 {}
-
+{}{}
 IMPORTANT:
 1. Read the documentation above carefully
 2. Break down the features into logical sprints
@@ -473,7 +615,7 @@ IMPORTANT:
 6. Output ONLY raw YAML - no markdown fences, no explanations
 
 The agent definition already contains the full YAML format. Just output the actual YAML content.
-"#, json_schema, build_spec, architecture, api_spec, ui_spec, data_model, testing_strategy, error_handling, state_management, security, deployment, integration_guide);
+"#, json_schema, build_spec, architecture, api_spec, ui_spec, data_model, testing_strategy, error_handling, state_management, security, deployment, integration_guide, openapi_section, integration_section);
 
     match execute_agent("make-sprints", &sprints_context, 20, None).await {
         Ok(result) => {
@@ -484,6 +626,11 @@ The agent definition already contains the full YAML format. Just output the actu
                 let yaml_content = extract_yaml_from_output(&result.output);
                 fs::write(Paths::SPRINTS_YML, yaml_content)?;
                 println!("  {} Saved to {}", "✓".green(), Paths::SPRINTS_YML.bright_blue());
+
+                if let Some(analysis) = &existing_codebase {
+                    mark_plan_as_integration_work(analysis)?;
+                }
+                select_plan_workflows()?;
             } else {
                 println!("  {} Failed to generate sprints", "✗".red());
                 anyhow::bail!("Sprint generation failed");
@@ -534,13 +681,97 @@ The agent definition already contains the full YAML format. Just output the actu
     println!("     {} .autoflow/SPRINTS.yml", "cat".bright_blue());
     println!();
     println!("  2. Start autonomous development:");
-    println!("     {} {}", "autoflow start --parallel".bright_green(), "(recommended)");
-    println!("     {} {}", "autoflow start".bright_blue(), "(sequential)");
+    println!("     {} (recommended)", "autoflow start --parallel".bright_green());
+    println!("     {} (sequential)", "autoflow start".bright_blue());
     println!();
     println!("  3. Monitor progress:");
     println!("     {}", "autoflow status".bright_blue());
     println!("     {}", "autoflow sprints list".bright_blue());
     println!();
 
+    finish_run("completed", &[]);
+    Ok(())
+}
+
+/// Doc sets to generate and where the choice came from: --docs, then
+/// `[defaults] create_docs`, then what IDEA.md needs; --no-ui-docs drops UI docs
+fn select_doc_sets(requested: Option<Vec<DocSet>>, no_ui_docs: bool, idea: &str, openapi: bool) -> (Vec<DocSet>, &'static str) {
+    let configured = autoflow_data::Config::global().map(|c| c.defaults.create_docs).unwrap_or_default();
+    let (mut sets, source) = match requested {
+        Some(sets) => (sets, "from --docs"),
+        None if !configured.is_empty() => (configured, "from [defaults] create_docs"),
+        None => {
+            let mut sets = applicable_doc_sets(idea);
+            // An imported spec is an API, whatever the idea says
+            if openapi && !sets.contains(&DocSet::Api) {
+                sets.push(DocSet::Api);
+                sets.sort();
+            }
+            (sets, "what IDEA.md needs")
+        }
+    };
+    if no_ui_docs {
+        sets.retain(|s| *s != DocSet::Ui);
+    }
+    (sets, source)
+}
+
+/// What the doc agents are told about the code already in the project
+/// TESTING_STRATEGY.md written when make-docs-ui doesn't produce one, following
+/// the conventions of whatever stack is already in the directory
+pub(crate) fn fallback_testing_strategy() -> String {
+    CodebaseAnalyzer::new(".")
+        .analyze()
+        .map(|analysis| analysis.tech_stack.testing_strategy())
+        .unwrap_or_default()
+}
+
+fn existing_codebase_section(analysis: &CodebaseAnalysis) -> String {
+    format!(
+        "\n# EXISTING CODEBASE\n\nThis directory already contains a {} project. BUILD_SPEC.md and ARCHITECTURE.md must describe the system as it exists - its stack, frameworks, layout, entry points and integration points, as analyzed below - and then how the idea above extends it. Do not propose a different stack, a new layout or a rewrite.\n\n{}",
+        analysis.tech_stack.language,
+        analysis.to_markdown()
+    )
+}
+
+/// Move sprints mislabeled as implementation to the workflow their content calls for
+fn select_plan_workflows() -> Result<()> {
+    let Ok(mut plan) = SprintsYaml::load_without_validation(Paths::SPRINTS_YML) else {
+        return Ok(());
+    };
+    let changed = select_workflows(&mut plan.sprints);
+    if !changed.is_empty() {
+        plan.save(Paths::SPRINTS_YML)?;
+    }
+    for change in &changed {
+        println!(
+            "  {} Sprint {} set to the {} workflow ({})",
+            "✓".green(),
+            change.sprint,
+            change.recommended,
+            change.reason
+        );
+    }
+    for mismatch in workflow_mismatches(&plan.sprints) {
+        println!("  {} {}", "⚠".yellow(), mismatch);
+    }
+    Ok(())
+}
+
+/// Fill in integration_points the agent left out, and point out sprints still without any
+fn mark_plan_as_integration_work(analysis: &CodebaseAnalysis) -> Result<()> {
+    let Ok(mut plan) = SprintsYaml::load_without_validation(Paths::SPRINTS_YML) else {
+        return Ok(());
+    };
+    let undeclared = mark_integration_work(&mut plan.sprints, &analysis.integration_points);
+    plan.save(Paths::SPRINTS_YML)?;
+    if !undeclared.is_empty() {
+        let ids: Vec<String> = undeclared.iter().map(|id| id.to_string()).collect();
+        println!(
+            "  {} Sprint(s) {} declare no integration_points - add the files they change before starting",
+            "⚠".yellow(),
+            ids.join(", ")
+        );
+    }
     Ok(())
 }
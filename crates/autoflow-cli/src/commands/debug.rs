@@ -0,0 +1,140 @@
+use anyhow::{bail, Context, Result};
+use autoflow_core::{open_repos, parse_phase, status_name, PhaseSnapshot};
+use autoflow_data::SprintsYaml;
+use autoflow_utils::Paths;
+use colored::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::DebugCommands;
+
+/// `.autoflow` content agents read besides SPRINTS.yml, copied into the replay worktree
+const REPLAY_STATE: &[&str] = &["docs", "agent-overrides", "analysis.json", "INTEGRATION_GUIDE.md"];
+
+pub async fn run(cmd: DebugCommands) -> Result<()> {
+    match cmd {
+        DebugCommands::At { sprint, phase, run, attempt, path } => {
+            debug_at(sprint, &phase, run.as_deref(), attempt, path)
+        }
+        DebugCommands::List { sprint } => list(sprint),
+    }
+}
+
+fn debug_at(
+    sprint_id: u32,
+    phase: &str,
+    run: Option<&str>,
+    attempt: Option<usize>,
+    path: Option<PathBuf>,
+) -> Result<()> {
+    let Some(status) = parse_phase(phase) else {
+        bail!("Unknown phase '{}' (e.g. WRITE_CODE, RUN_UNIT_TESTS, CODE_REVIEW)", phase);
+    };
+    let snapshots = PhaseSnapshot::load_all(Path::new("."))?;
+    let Some(snapshot) = PhaseSnapshot::find(&snapshots, sprint_id, status, run, attempt) else {
+        bail!(
+            "No snapshot of sprint {} at {} (see `autoflow debug list --sprint {}`)",
+            sprint_id,
+            status_name(status),
+            sprint_id
+        );
+    };
+
+    println!("{}", "⏪ Reconstructing phase state".bright_cyan().bold());
+    println!(
+        "  Sprint {} at {} (run {}, {})",
+        sprint_id,
+        status_name(status).bright_white().bold(),
+        snapshot.run_id,
+        snapshot.taken_at.format("%Y-%m-%d %H:%M:%S")
+    );
+
+    // Sprints in another repository were snapshotted in that checkout
+    let plan = SprintsYaml::load_without_validation(Paths::SPRINTS_YML).ok();
+    let repo = match (&snapshot.sprint.repo, &plan) {
+        (Some(name), Some(plan)) => open_repos(Path::new("."), &plan.project.repos)?
+            .remove(name)
+            .with_context(|| format!("Repo '{}' is no longer declared in project.repos", name))?,
+        _ => PathBuf::from("."),
+    };
+
+    let dest = path.unwrap_or_else(|| {
+        std::env::temp_dir().join(format!(
+            "autoflow-debug-{}-sprint-{}-{}",
+            snapshot.run_id.to_lowercase(),
+            sprint_id,
+            status_name(status).to_lowercase()
+        ))
+    });
+    if dest.exists() {
+        bail!("{} already exists; remove it or pass --path", dest.display());
+    }
+    snapshot.checkout(&repo, &dest)?;
+    println!("  {} Code checked out at {}", "✓".green(), snapshot.tree_commit.as_deref().unwrap_or_default());
+
+    // Sprint state: the snapshot's sprint, alone, in the current plan's project
+    let autoflow_dir = dest.join(Paths::AUTOFLOW_DIR);
+    fs::create_dir_all(&autoflow_dir)?;
+    for name in REPLAY_STATE {
+        let source = Path::new(Paths::AUTOFLOW_DIR).join(name);
+        if source.is_dir() {
+            crate::sync::copy_dir_recursive(&source, &autoflow_dir.join(name))?;
+        } else if source.is_file() {
+            fs::copy(&source, autoflow_dir.join(name))?;
+        }
+    }
+    if let Some(mut plan) = plan {
+        plan.sprints = vec![snapshot.sprint.clone()];
+        plan.project.total_sprints = 1;
+        plan.project.current_sprint = Some(sprint_id);
+        fs::write(autoflow_dir.join("SPRINTS.yml"), serde_yaml::to_string(&plan)?)?;
+        println!("  {} Sprint state restored to .autoflow/SPRINTS.yml", "✓".green());
+    }
+
+    if !snapshot.untracked.is_empty() {
+        println!(
+            "  {} {} untracked file(s) weren't captured: {}",
+            "⚠".yellow(),
+            snapshot.untracked.len(),
+            snapshot.untracked.join(", ")
+        );
+    }
+    println!("  {} Docs and analysis are copied as they are now", "ℹ".bright_cyan());
+
+    println!();
+    println!("{}", "Next:".bright_white().bold());
+    println!("  cd {}", dest.display().to_string().bright_blue());
+    println!("  {}", format!("autoflow start --sprint {}", sprint_id).bright_blue());
+    println!();
+    println!(
+        "Clean up with {}",
+        format!("git worktree remove --force {}", dest.display()).bright_blue()
+    );
+    Ok(())
+}
+
+fn list(sprint: Option<u32>) -> Result<()> {
+    let snapshots: Vec<PhaseSnapshot> = PhaseSnapshot::load_all(Path::new("."))?
+        .into_iter()
+        .filter(|s| sprint.is_none_or(|id| s.sprint_id == id))
+        .collect();
+    if snapshots.is_empty() {
+        println!("{}", "No phase snapshots recorded yet (they are taken as each phase starts)".yellow());
+        return Ok(());
+    }
+
+    println!("{}", "📸 Phase snapshots".bright_cyan().bold());
+    println!();
+    for snapshot in &snapshots {
+        let commit = snapshot.tree_commit.as_deref().map(|c| &c[..c.len().min(10)]).unwrap_or("-");
+        println!(
+            "  {}  {}  {:<18}  {}  {}",
+            snapshot.taken_at.format("%Y-%m-%d %H:%M:%S").to_string().bright_black(),
+            format!("Sprint {}", snapshot.sprint_id).bright_white().bold(),
+            status_name(snapshot.phase),
+            commit,
+            snapshot.run_id.bright_black()
+        );
+    }
+    Ok(())
+}
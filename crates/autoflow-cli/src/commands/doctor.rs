@@ -0,0 +1,200 @@
+use anyhow::Result;
+use autoflow_agents::{detect_claude_version, incompatible_agents, ClaudeCapabilities, CliFlag, MIN_CLAUDE_VERSION, PROTOCOL_VERSION};
+use autoflow_data::Config;
+use colored::*;
+use std::process::Command;
+
+/// Outcome of a single environment check
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: String,
+    pub passed: bool,
+    /// Required checks fail `autoflow doctor`; optional ones only warn
+    pub required: bool,
+    pub detail: String,
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &str, required: bool, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            passed: true,
+            required,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn fail(name: &str, required: bool, detail: impl Into<String>, hint: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            passed: false,
+            required,
+            detail: detail.into(),
+            hint: Some(hint.to_string()),
+        }
+    }
+}
+
+/// Check that an external tool is on PATH and report its version
+pub fn check_tool(name: &str, binary: &str, required: bool, hint: &str) -> Check {
+    match Command::new(binary).arg("--version").output() {
+        Ok(output) if output.status.success() => {
+            let version = String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .next()
+                .unwrap_or("")
+                .trim()
+                .to_string();
+            Check::pass(name, required, version)
+        }
+        _ => Check::fail(name, required, format!("{} not found", binary), hint),
+    }
+}
+
+/// Check the claude CLI is installed and new enough for the flags we pass
+pub fn check_claude_cli() -> Check {
+    let caps = match detect_claude_version() {
+        Ok(version) => ClaudeCapabilities::for_version(version),
+        Err(_) => return Check::fail("Claude CLI", true, "claude not found", "Install from https://claude.com/cli"),
+    };
+    let Some(version) = caps.version.clone() else {
+        return Check::pass("Claude CLI", true, "version unknown (assuming current)");
+    };
+    if caps.ensure_supported().is_err() {
+        return Check::fail(
+            "Claude CLI",
+            true,
+            format!("{} is older than the minimum {}", version, MIN_CLAUDE_VERSION),
+            "Run `npm install -g @anthropic-ai/claude-code@latest`",
+        );
+    }
+    if !caps.supports(CliFlag::IncludePartialMessages) {
+        return Check::pass("Claude CLI", true, format!("{} (upgrade for token-level live logs)", version));
+    }
+    Check::pass("Claude CLI", true, version.to_string())
+}
+
+/// Tools AutoFlow shells out to
+pub fn prerequisite_checks() -> Vec<Check> {
+    vec![
+        check_claude_cli(),
+        check_tool("Git", "git", true, "Install git from your package manager"),
+        check_tool("Node.js", "node", false, "Install Node.js (needed for MCP servers)"),
+        check_tool("Docker", "docker", false, "Install Docker (needed for `autoflow env`)"),
+    ]
+}
+
+/// Agents, skills, schemas and config under the home directory
+pub fn installation_checks() -> Vec<Check> {
+    let mut checks = Vec::new();
+    let Some(home) = autoflow_utils::home_dir() else {
+        checks.push(Check::fail(
+            "Home directory",
+            true,
+            "HOME is not set",
+            "Set HOME, or pass --home <dir> (AUTOFLOW_HOME)",
+        ));
+        return checks;
+    };
+
+    let agents_dir = home.join(".claude/agents");
+    let agent_count = std::fs::read_dir(&agents_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_name().to_string_lossy().ends_with(".agent.md"))
+                .count()
+        })
+        .unwrap_or(0);
+    checks.push(if agent_count > 0 {
+        Check::pass("Agents", true, format!("{} installed in {}", agent_count, agents_dir.display()))
+    } else {
+        Check::fail("Agents", true, "No AutoFlow agents installed", "Run `autoflow setup`")
+    });
+
+    let incompatible = incompatible_agents(&agents_dir);
+    if agent_count > 0 {
+        checks.push(if incompatible.is_empty() {
+            Check::pass("Agent protocol", true, format!("v{}", PROTOCOL_VERSION))
+        } else {
+            let agents: Vec<String> = incompatible.iter().map(|(name, v)| format!("{} (v{})", name, v)).collect();
+            Check::fail(
+                "Agent protocol",
+                true,
+                format!("AutoFlow speaks v{}, but: {}", PROTOCOL_VERSION, agents.join(", ")),
+                "Run `autoflow install --force` to reinstall matching agents",
+            )
+        });
+    }
+
+    let skills_dir = home.join(".claude/skills");
+    checks.push(if skills_dir.exists() {
+        Check::pass("Skills", false, skills_dir.display().to_string())
+    } else {
+        Check::fail("Skills", false, "No skills directory", "Run `autoflow setup`")
+    });
+
+    let schema = home.join(".autoflow/schemas/sprints.schema.json");
+    checks.push(if schema.exists() {
+        Check::pass("Schemas", false, schema.display().to_string())
+    } else {
+        Check::fail("Schemas", false, "sprints.schema.json missing (embedded copy will be used)", "Run `autoflow setup`")
+    });
+
+    let config_path = home.join(".autoflow/config.toml");
+    checks.push(if !config_path.exists() {
+        Check::fail("Global config", false, "~/.autoflow/config.toml not found", "Run `autoflow setup`")
+    } else {
+        match Config::load(&config_path) {
+            Ok(config) => Check::pass("Global config", false, format!("default model {}", config.defaults.model)),
+            Err(e) => Check::fail("Global config", true, format!("Invalid config: {}", e), "Fix or delete ~/.autoflow/config.toml and rerun `autoflow setup`"),
+        }
+    });
+
+    checks
+}
+
+/// Run every check
+pub fn run_checks() -> Vec<Check> {
+    let mut checks = prerequisite_checks();
+    checks.extend(installation_checks());
+    checks
+}
+
+/// Print checks and return true when no required check failed
+pub fn print_checks(checks: &[Check]) -> bool {
+    let mut ok = true;
+    for check in checks {
+        if check.passed {
+            println!("  {} {} {}", "✓".green(), check.name.bold(), check.detail.bright_black());
+        } else {
+            let marker = if check.required { "✗".red() } else { "⚠".yellow() };
+            println!("  {} {} {}", marker, check.name.bold(), check.detail);
+            if let Some(hint) = &check.hint {
+                println!("      {}", hint.bright_blue());
+            }
+            if check.required {
+                ok = false;
+            }
+        }
+    }
+    ok
+}
+
+pub async fn run() -> Result<()> {
+    println!("{}", "🩺 AutoFlow Doctor".bright_cyan().bold());
+    println!();
+
+    let checks = run_checks();
+    let ok = print_checks(&checks);
+
+    println!();
+    if ok {
+        println!("{}", "✅ Everything required is in place".bright_green().bold());
+        Ok(())
+    } else {
+        anyhow::bail!("Some required checks failed");
+    }
+}
@@ -1,5 +1,7 @@
 use crate::EnvCommands;
 use anyhow::{bail, Context};
+use autoflow_data::EnvConfig;
+use autoflow_utils::{detect_runners, resolve_env_command, CacheOutcome, EnvAction, EnvCache, EnvSource};
 use colored::*;
 use std::path::Path;
 use std::process::Command;
@@ -7,107 +9,105 @@ use std::process::Command;
 pub async fn run(cmd: EnvCommands) -> anyhow::Result<()> {
     println!("{}", "🐳 Environment".bright_cyan().bold());
 
-    // Check if docker-compose.yml exists
-    if !Path::new("docker-compose.yml").exists() {
-        println!("\n{}", "⚠️  No docker-compose.yml found".yellow());
-        println!("This project doesn't appear to have Docker configuration.");
-        return Ok(());
+    match cmd {
+        EnvCommands::Bootstrap => return bootstrap_env(),
+        EnvCommands::Cache { clear } => return cache_env(clear),
+        _ => {}
     }
 
+    let checkout = std::env::current_dir()?;
+    let config = autoflow_data::Config::global().map(|c| c.env).unwrap_or_default();
     match cmd {
-        EnvCommands::Start => start_env().await,
-        EnvCommands::Stop => stop_env().await,
-        EnvCommands::Restart => restart_env().await,
-        EnvCommands::Logs { follow } => logs_env(follow).await,
-        EnvCommands::Health => health_check().await,
+        EnvCommands::Start => run_action(&checkout, &config, EnvAction::Start, false),
+        EnvCommands::Stop => run_action(&checkout, &config, EnvAction::Stop, false),
+        EnvCommands::Restart if resolve_env_command(&checkout, &config, EnvAction::Restart).is_none() => {
+            run_action(&checkout, &config, EnvAction::Stop, false)?;
+            run_action(&checkout, &config, EnvAction::Start, false)
+        }
+        EnvCommands::Restart => run_action(&checkout, &config, EnvAction::Restart, false),
+        EnvCommands::Logs { follow } => run_action(&checkout, &config, EnvAction::Logs, follow),
+        EnvCommands::Health => run_action(&checkout, &config, EnvAction::Health, false),
+        EnvCommands::Targets => show_targets(&checkout, &config),
+        EnvCommands::Bootstrap | EnvCommands::Cache { .. } => unreachable!("handled above"),
     }
 }
 
-async fn start_env() -> anyhow::Result<()> {
-    println!("\n{}", "Starting Docker containers...".bright_cyan());
-
-    let status = Command::new("docker-compose")
-        .args(&["up", "-d"])
-        .status()
-        .context("Failed to execute docker-compose")?;
-
-    if status.success() {
-        println!("{} {}", "✅".green(), "Containers started successfully!".bright_green());
-    } else {
-        bail!("Failed to start containers");
+/// Run the command mapped to `action`: `[env]`, a task runner target, or docker compose
+fn run_action(checkout: &Path, config: &EnvConfig, action: EnvAction, follow: bool) -> anyhow::Result<()> {
+    let Some(mut env_command) = resolve_env_command(checkout, config, action) else {
+        println!("\n{}", format!("⚠️  Nothing to run for env {}", action.name()).yellow());
+        println!("No [env] {} command, task runner target ({}) or compose file found.", action.name(), action.targets().join(", "));
+        return Ok(());
+    };
+    // Only compose takes the flag; runner targets decide for themselves
+    if follow && env_command.source == EnvSource::Compose {
+        env_command.command.push_str(" -f");
     }
+    println!("\n{} {} {}", "Running".bright_cyan(), env_command.command.bright_blue(), format!("({})", env_command.source).bright_black());
 
-    Ok(())
-}
-
-async fn stop_env() -> anyhow::Result<()> {
-    println!("\n{}", "Stopping Docker containers...".bright_cyan());
-
-    let status = Command::new("docker-compose")
-        .args(&["down"])
+    let status = Command::new("sh")
+        .args(["-c", &env_command.command])
+        .current_dir(checkout)
         .status()
-        .context("Failed to execute docker-compose")?;
-
-    if status.success() {
-        println!("{} {}", "✅".green(), "Containers stopped successfully!".bright_green());
-    } else {
-        bail!("Failed to stop containers");
+        .with_context(|| format!("Failed to execute {}", env_command.command))?;
+    if !status.success() {
+        bail!("env {} failed: {} exited with {}", action.name(), env_command.command, status);
     }
-
+    println!("{} {}", "✅".green(), format!("env {} done", action.name()).bright_green());
     Ok(())
 }
 
-async fn restart_env() -> anyhow::Result<()> {
-    println!("\n{}", "Restarting Docker containers...".bright_cyan());
-
-    let status = Command::new("docker-compose")
-        .args(&["restart"])
-        .status()
-        .context("Failed to execute docker-compose")?;
-
-    if status.success() {
-        println!("{} {}", "✅".green(), "Containers restarted successfully!".bright_green());
-    } else {
-        bail!("Failed to restart containers");
+fn show_targets(checkout: &Path, config: &EnvConfig) -> anyhow::Result<()> {
+    println!();
+    for action in EnvAction::ALL {
+        match resolve_env_command(checkout, config, action) {
+            Some(env_command) => println!("  {:<8} {} {}", action.name(), env_command.command.bright_blue(), format!("({})", env_command.source).bright_black()),
+            None if action == EnvAction::Restart => println!("  {:<8} {}", action.name(), "stop, then start".bright_black()),
+            None => println!("  {:<8} {}", action.name(), "-".bright_black()),
+        }
     }
-
+    if config.detect_runners {
+        for found in detect_runners(checkout) {
+            println!("\n{} {}", found.runner.to_string().bright_cyan(), found.targets.join(", ").bright_black());
+        }
+    }
+    println!("\nOverride any action under [env] in ~/.autoflow/config.toml");
     Ok(())
 }
 
-async fn logs_env(follow: bool) -> anyhow::Result<()> {
-    println!("\n{}", "Viewing container logs...".bright_cyan());
-
-    let mut args = vec!["logs"];
-    if follow {
-        args.push("-f");
+fn bootstrap_env() -> anyhow::Result<()> {
+    let checkout = std::env::current_dir()?;
+    let Some(cache) = EnvCache::load(&checkout) else {
+        println!("\n{}", "Environment caching is disabled ([env_cache] enabled = false)".yellow());
+        return Ok(());
+    };
+    println!("\n{} {}", "Caches:".bright_cyan(), cache.root.display());
+
+    for step in cache.bootstrap(&checkout) {
+        let mark = match step.outcome {
+            CacheOutcome::Hit | CacheOutcome::Miss => "✅".green(),
+            CacheOutcome::Skipped(_) => "➖".normal(),
+            CacheOutcome::Failed(_) => "❌".red(),
+        };
+        println!("  {} {:<13} {} ({:.1}s)", mark, step.name, step.outcome, step.secs);
     }
-
-    let status = Command::new("docker-compose")
-        .args(&args)
-        .status()
-        .context("Failed to execute docker-compose")?;
-
-    if !status.success() {
-        bail!("Failed to view logs");
+    for (name, path) in cache.shared_env() {
+        println!("  {}={}", name.dimmed(), path.display());
     }
-
     Ok(())
 }
 
-async fn health_check() -> anyhow::Result<()> {
-    println!("\n{}", "Checking container health...".bright_cyan());
-
-    let output = Command::new("docker-compose")
-        .args(&["ps"])
-        .output()
-        .context("Failed to execute docker-compose")?;
-
-    if output.status.success() {
-        println!("\n{}", String::from_utf8_lossy(&output.stdout));
-        println!("{} {}", "✅".green(), "Health check complete".bright_green());
+fn cache_env(clear: bool) -> anyhow::Result<()> {
+    let Some(cache) = EnvCache::load(&std::env::current_dir()?) else {
+        println!("\n{}", "Environment caching is disabled ([env_cache] enabled = false)".yellow());
+        return Ok(());
+    };
+    let megabytes = cache.size() as f64 / (1024.0 * 1024.0);
+    if clear {
+        cache.clear()?;
+        println!("\n{} Cleared {} ({:.1} MB)", "✅".green(), cache.root.display(), megabytes);
     } else {
-        bail!("Failed to check health");
+        println!("\n{} ({:.1} MB)", cache.root.display(), megabytes);
     }
-
     Ok(())
 }
@@ -0,0 +1,43 @@
+use anyhow::Result;
+use colored::*;
+use std::path::{Path, PathBuf};
+
+pub async fn run(output: Option<PathBuf>, runs: usize) -> Result<()> {
+    if !Path::new(".autoflow").exists() {
+        anyhow::bail!("No .autoflow directory here. Run 'autoflow init' first.");
+    }
+    let output = output.unwrap_or_else(|| {
+        let name = format!("autoflow-bundle-{}.tar.gz", chrono::Local::now().format("%Y%m%d_%H%M%S"));
+        // The project may not be written to, so leave the bundle in the temp directory
+        if autoflow_data::is_read_only() {
+            std::env::temp_dir().join(name)
+        } else {
+            PathBuf::from(name)
+        }
+    });
+    let claude_cli = autoflow_agents::detect_claude_version().ok().flatten().map(|v| v.to_string());
+
+    println!("{}", "📦 Support bundle".bright_cyan().bold());
+    let manifest = autoflow_utils::write_support_bundle(Path::new("."), &output, runs, claude_cli)?;
+
+    println!("\n  {:<60} {:>10} {:>10}", "File".dimmed(), "Bytes".dimmed(), "Redacted".dimmed());
+    for file in &manifest.files {
+        let redacted = if file.redactions > 0 { file.redactions.to_string().bright_yellow() } else { "-".dimmed() };
+        let truncated = if file.truncated { " (last part only)".dimmed().to_string() } else { String::new() };
+        println!("  {:<60} {:>10} {:>10}{}", file.name, file.bytes, redacted, truncated);
+    }
+    let redactions: usize = manifest.files.iter().map(|f| f.redactions).sum();
+    println!(
+        "\n{} {} ({} files, {} runs, {} values redacted)",
+        "✓".green(),
+        output.display().to_string().bright_white(),
+        manifest.files.len(),
+        manifest.runs.len(),
+        redactions
+    );
+    println!(
+        "  {}",
+        "Secrets are redacted by pattern; look through the bundle before attaching it to a public issue.".dimmed()
+    );
+    Ok(())
+}
@@ -1,10 +1,42 @@
 use anyhow::{bail, Context};
-use autoflow_git::WorktreeManager;
-use autoflow_utils::{sanitize_branch_name, Paths};
+use autoflow_core::{BugRecord, BugRegistry, DUPLICATE_THRESHOLD};
+use autoflow_data::AutoFlowError;
+use autoflow_git::{bugfix_worktree_name, WorktreeManager};
+use autoflow_utils::{ensure_approved, finish_run, sanitize_branch_name, start_run, Paths, RunLock};
 use colored::*;
-use std::path::Path;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Words that suggest a bug is visible in the browser
+const UI_BUG_KEYWORDS: &[&str] = &[
+    "button", "click", "page", "screen", "modal", "dialog", "form", "input", "layout",
+    "css", "style", "render", "display", "ui", "menu", "navbar", "scroll", "dropdown",
+];
+
+/// How to build on a matching past investigation
+enum Followup {
+    /// Continue in the same bugfix worktree
+    Resume(BugRecord),
+    /// New investigation that starts from the old findings
+    Reference(BugRecord),
+}
+
+/// Failing E2E test and screenshot that demonstrate a bug
+struct Reproduction {
+    /// Test file, relative to the working directory
+    test_path: PathBuf,
+    screenshot: PathBuf,
+    confirmed: bool,
+}
 
-pub async fn run(description: String, auto_fix: bool, _playwright_headed: bool) -> anyhow::Result<()> {
+pub async fn run(
+    description: String,
+    auto_fix: bool,
+    playwright_headed: bool,
+    steal_lock: bool,
+    reproduce: bool,
+) -> anyhow::Result<()> {
     println!("{}", "🐛 Investigating bug...".bright_cyan().bold());
     println!("Bug: {}", description.bright_blue());
 
@@ -15,34 +47,102 @@ pub async fn run(description: String, auto_fix: bool, _playwright_headed: bool)
 
     // Check if project is initialized
     if !Path::new(Paths::AUTOFLOW_DIR).exists() {
-        bail!(
-            "{}\nRun {} first",
-            "Project not initialized.".red(),
-            "autoflow init".bright_blue()
-        );
+        return Err(AutoFlowError::NotInitialized.into());
     }
 
-    // Create bugfix worktree
+    ensure_approved("fix")?;
+    let run_id = start_run("fix");
+    let _lock = RunLock::acquire("fix", steal_lock)?;
+    println!("{} {}", "🆔 Run:".bright_black(), run_id.dimmed());
+
+    // Check past investigations for likely duplicates
+    let mut registry = BugRegistry::load(Path::new("."))?;
+    let followup = check_duplicates(&registry, &description)?;
+    let slug = match followup {
+        Some(Followup::Resume(ref bug)) => bug.id.clone(),
+        _ => sanitize_branch_name(&description, 5),
+    };
+    let previous_note = match followup {
+        Some(Followup::Resume(ref bug)) | Some(Followup::Reference(ref bug)) => bug
+            .latest_findings(Path::new("."))
+            .map(|findings| format!(
+                "\nA previous investigation of a similar bug (\"{}\") found:\n\n{}\n\nBuild on these findings instead of starting over.\n",
+                bug.description,
+                findings
+            ))
+            .unwrap_or_default(),
+        None => String::new(),
+    };
+
+    // Create (or reuse) the bugfix worktree so the investigation runs in isolation
     println!("\n{}", "Creating bugfix worktree...".bright_cyan());
-    let branch_name = format!("bugfix-{}", sanitize_branch_name(&description, 5));
+    let branch_name = bugfix_worktree_name(&slug);
 
     let manager = WorktreeManager::new(".")
         .context("Failed to open git repository")?;
 
-    // Use a high sprint ID for bugfix worktrees to avoid conflicts
-    let bugfix_sprint_id = 900;
-
-    match manager.create_worktree(bugfix_sprint_id, &branch_name) {
-        Ok(worktree) => {
-            println!("  {} Worktree created: {}", "✓".green(), worktree.path.display().to_string().bright_blue());
-            println!("  Branch: {}", worktree.branch.bright_green());
-            println!("  Port: {}", worktree.port.to_string().bright_yellow());
+    let worktree = match manager.find_worktree(&branch_name).ok().flatten() {
+        Some(existing) => {
+            println!("  {} Reusing worktree: {}", "ℹ".blue(), existing.display_path().bright_blue());
+            Some(existing)
         }
-        Err(e) => {
-            // Worktree might already exist
-            println!("  {} {}", "⚠".yellow(), format!("Worktree creation skipped: {}", e).yellow());
+        None => {
+            let options = super::worktree::worktree_options(None, true, true);
+            match manager.create_bugfix_worktree(&slug, &options) {
+                Ok(worktree) => {
+                    if let Err(e) = manager.setup_worktree_env(&worktree) {
+                        println!("  {} Environment setup skipped: {}", "⚠".yellow(), e);
+                    }
+                    println!("  {} Worktree created: {}", "✓".green(), worktree.display_path().bright_blue());
+                    Some(worktree)
+                }
+                Err(e) => {
+                    println!("  {} {}", "⚠".yellow(), format!("Worktree creation skipped: {}", e).yellow());
+                    None
+                }
+            }
+        }
+    };
+    if let Some(ref worktree) = worktree {
+        println!("  Branch: {}", worktree.branch.bright_green());
+        println!("  Port: {}", worktree.port.to_string().bright_yellow());
+    }
+    let worktree_note = worktree
+        .as_ref()
+        .map(|w| format!(
+            "\nWork in the isolated bugfix worktree at {} (branch {}, app port {}). Do not modify the main checkout.\n",
+            w.display_path(),
+            w.branch,
+            w.port
+        ))
+        .unwrap_or_default();
+
+    // Reproduce UI bugs in a browser first; auto-fix only trusts confirmed reproductions
+    let workdir = worktree.as_ref().map(|w| w.path.clone()).unwrap_or_else(|| PathBuf::from("."));
+    let reproduction = if reproduce || (auto_fix && looks_like_ui_bug(&description)) {
+        let port = worktree.as_ref().map(|w| w.port).unwrap_or(3000);
+        Some(reproduce_bug(&description, &slug, &workdir, port, playwright_headed).await?)
+    } else {
+        None
+    };
+    if let Some(ref repro) = reproduction {
+        if !repro.confirmed && auto_fix {
+            bail!(
+                "Could not reproduce the bug in the browser, so it was not auto-fixed.\n\
+                Add reproduction steps to the description, or run without {} to investigate anyway.",
+                "--auto-fix".bright_blue()
+            );
         }
     }
+    let reproduction_note = match reproduction {
+        Some(ref repro) if repro.confirmed => format!(
+            "\nThe bug is reproduced by the failing E2E test `{}` (screenshot: {}).\n\
+            Do not weaken or delete this test. The fix is complete only when it passes.\n",
+            repro.test_path.display(),
+            repro.screenshot.display()
+        ),
+        _ => String::new(),
+    };
 
     // Build context for bug-investigator agent
     println!("\n{}", "Running bug investigation...".bright_cyan());
@@ -57,14 +157,17 @@ Your tasks:
 2. Suggest a fix
 3. Implement the fix if possible
 4. Run relevant tests to verify the fix
-
+{}{}{}
 Provide a detailed analysis of:
 - Root cause
 - Affected files
 - Proposed solution
 - Test results
 "#,
-        description
+        description,
+        worktree_note,
+        reproduction_note,
+        previous_note
     );
 
     // Execute bug-investigator agent
@@ -85,12 +188,13 @@ Provide a detailed analysis of:
                 std::fs::create_dir_all(Paths::BUGS_DIR)?;
 
                 let timestamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
-                let analysis_file = format!("{}/bug-{}.md", Paths::BUGS_DIR, timestamp);
+                let analysis_file = format!("{}/bug-{}-{}.md", Paths::BUGS_DIR, timestamp, run_id);
 
                 let analysis_content = format!(
                     r#"# Bug Analysis
 
 **Date**: {}
+**Run**: {}
 **Description**: {}
 
 ## Investigation Results
@@ -100,17 +204,47 @@ Provide a detailed analysis of:
 ## Branch
 
 Bugfix branch: `{}`
-Worktree location: Check with `autoflow worktree list`
+Worktree location: {}
 "#,
                     chrono::Utc::now().format("%Y-%m-%d %H:%M:%S"),
+                    run_id,
                     description,
                     result.output,
-                    branch_name
+                    branch_name,
+                    worktree.as_ref().map(|w| w.display_path()).unwrap_or_else(|| "not created".to_string())
                 );
 
+                let analysis_content = match reproduction {
+                    Some(ref repro) => format!(
+                        "{}\n## Reproduction\n\nConfirmed: {}\nTest: `{}`\nScreenshot: {}\n",
+                        analysis_content,
+                        if repro.confirmed { "yes" } else { "no" },
+                        repro.test_path.display(),
+                        repro.screenshot.display()
+                    ),
+                    None => analysis_content,
+                };
+
                 std::fs::write(&analysis_file, analysis_content)?;
+                registry.record(&slug, &description, &analysis_file);
+                registry.save(Path::new("."))?;
                 println!("\n{} Analysis saved to {}", "✓".green(), analysis_file.bright_blue());
 
+                // The reproduction test is the acceptance check for the fix
+                if let Some(ref repro) = reproduction {
+                    if repro.confirmed {
+                        match run_playwright_test(&workdir, &repro.test_path, false) {
+                            Some(true) => println!("  {} Reproduction test now passes", "✓".green()),
+                            Some(false) => println!(
+                                "  {} Reproduction test still fails: {}",
+                                "⚠".yellow(),
+                                repro.test_path.display()
+                            ),
+                            None => {}
+                        }
+                    }
+                }
+
                 if auto_fix {
                     println!("\n{}", "Next steps:".bright_cyan());
                     println!("  1. Review the fix in the bugfix worktree");
@@ -136,12 +270,13 @@ Worktree location: Check with `autoflow worktree list`
             std::fs::create_dir_all(Paths::BUGS_DIR)?;
 
             let timestamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
-            let analysis_file = format!("{}/bug-{}.md", Paths::BUGS_DIR, timestamp);
+            let analysis_file = format!("{}/bug-{}-{}.md", Paths::BUGS_DIR, timestamp, run_id);
 
             let manual_report = format!(
                 r#"# Bug Report
 
 **Date**: {}
+**Run**: {}
 **Description**: {}
 
 ## Status
@@ -160,15 +295,164 @@ Bugfix branch: `{}`
 4. Merge when ready: `autoflow worktree merge {}`
 "#,
                 chrono::Utc::now().format("%Y-%m-%d %H:%M:%S"),
+                run_id,
                 description,
                 branch_name,
                 branch_name
             );
 
             std::fs::write(&analysis_file, manual_report)?;
+            registry.record(&slug, &description, &analysis_file);
+            registry.save(Path::new("."))?;
             println!("  {} Bug report created: {}", "✓".green(), analysis_file.bright_blue());
         }
     }
 
+    finish_run("completed", &[]);
     Ok(())
 }
+
+/// Offer to resume or reference a similar past investigation
+///
+/// Without a terminal the closest match is referenced, never resumed.
+fn check_duplicates(registry: &BugRegistry, description: &str) -> anyhow::Result<Option<Followup>> {
+    let matches = registry.find_similar(description, DUPLICATE_THRESHOLD);
+    let Some((_, best)) = matches.first() else {
+        return Ok(None);
+    };
+
+    println!("\n{}", "Similar bugs were investigated before:".bright_yellow());
+    for (score, bug) in matches.iter().take(3) {
+        println!(
+            "  {} {} ({:.0}% match, {} report(s), last {})",
+            "•".yellow(),
+            bug.description.bright_blue(),
+            score * 100.0,
+            bug.reports.len(),
+            bug.updated_at.format("%Y-%m-%d")
+        );
+    }
+
+    if !io::stdin().is_terminal() {
+        println!("  {} Referencing findings from: {}", "ℹ".blue(), best.description);
+        return Ok(Some(Followup::Reference((*best).clone())));
+    }
+
+    print!("  Resume it [r], start fresh with its findings [f], or ignore [n]? [f] ");
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(match input.trim().to_lowercase().as_str() {
+        "r" | "resume" => Some(Followup::Resume((*best).clone())),
+        "n" | "no" | "ignore" => None,
+        _ => Some(Followup::Reference((*best).clone())),
+    })
+}
+
+fn looks_like_ui_bug(description: &str) -> bool {
+    let description = description.to_lowercase();
+    description
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| UI_BUG_KEYWORDS.contains(&word))
+}
+
+/// Have the bug-reproducer agent capture a failing test and screenshot,
+/// then confirm natively that the test really fails
+async fn reproduce_bug(
+    description: &str,
+    slug: &str,
+    workdir: &Path,
+    port: u32,
+    headed: bool,
+) -> anyhow::Result<Reproduction> {
+    use autoflow_agents::execute_agent;
+
+    println!("\n{}", "Reproducing bug in the browser...".bright_cyan());
+
+    let test_path = PathBuf::from(format!("tests/e2e/bugs/{}.spec.ts", slug));
+    let screenshot = std::env::current_dir()?
+        .join(Paths::BUGS_DIR)
+        .join(slug)
+        .join("reproduction.png");
+    if let Some(parent) = screenshot.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let context = format!(
+        r#"Reproduce this bug in the browser:
+
+Bug Description: {}
+
+Working directory: {}
+App URL: http://localhost:{}
+Browser mode: {}
+
+Save the failing Playwright test at: {} (relative to the working directory)
+Save the screenshot at: {}
+"#,
+        description,
+        workdir.display(),
+        port,
+        if headed { "headed (pass --headed / headless: false)" } else { "headless" },
+        test_path.display(),
+        screenshot.display()
+    );
+
+    println!("  Spawning bug-reproducer agent...");
+    let verdict = match execute_agent("bug-reproducer", &context, 20, None).await {
+        Ok(result) if result.success => result.output.contains("REPRODUCTION: CONFIRMED"),
+        Ok(result) => {
+            println!("  {} Reproducer failed: {:?}", "⚠".yellow(), result.error);
+            false
+        }
+        Err(e) => {
+            println!("  {} Reproducer could not start: {}", "⚠".yellow(), e);
+            false
+        }
+    };
+
+    let has_test = workdir.join(&test_path).exists();
+    let has_screenshot = screenshot.exists();
+    // A reproduction only counts if the test fails when we run it ourselves
+    let fails_natively = if has_test {
+        run_playwright_test(workdir, &test_path, headed).map(|passed| !passed)
+    } else {
+        None
+    };
+    let confirmed = verdict && has_test && has_screenshot && fails_natively.unwrap_or(true);
+
+    if confirmed {
+        println!("  {} Reproduced: {}", "✓".green(), test_path.display().to_string().bright_blue());
+        println!("  {} Screenshot: {}", "✓".green(), screenshot.display().to_string().bright_blue());
+    } else {
+        let reason = if !verdict {
+            "agent could not reproduce it"
+        } else if !has_test {
+            "no test was saved"
+        } else if !has_screenshot {
+            "no screenshot was saved"
+        } else {
+            "the saved test passes"
+        };
+        println!("  {} Not reproduced ({})", "⚠".yellow(), reason);
+    }
+
+    Ok(Reproduction {
+        test_path,
+        screenshot,
+        confirmed,
+    })
+}
+
+/// Run one Playwright test; None when Playwright could not be started
+fn run_playwright_test(workdir: &Path, test_path: &Path, headed: bool) -> Option<bool> {
+    let mut cmd = Command::new("npx");
+    cmd.args(["playwright", "test"])
+        .arg(test_path)
+        .arg("--reporter=line")
+        .current_dir(workdir);
+    if headed {
+        cmd.arg("--headed");
+    }
+    cmd.output().ok().map(|output| output.status.success())
+}
@@ -0,0 +1,37 @@
+use anyhow::{Context, Result};
+use autoflow_core::{find_api_spec, generate_api_client, ClientLanguage};
+use colored::*;
+use std::path::PathBuf;
+
+use crate::GenerateCommands;
+
+pub async fn run(cmd: GenerateCommands) -> Result<()> {
+    match cmd {
+        GenerateCommands::ApiClient { spec, lang } => api_client(spec, lang),
+    }
+}
+
+fn api_client(spec: Option<String>, lang: Option<String>) -> Result<()> {
+    let project_path = std::env::current_dir()?;
+    let language: Option<ClientLanguage> = lang.map(|l| l.parse()).transpose()?;
+    let spec_path = spec
+        .map(PathBuf::from)
+        .or_else(|| find_api_spec(&project_path))
+        .context("No OpenAPI file or .autoflow/docs/API_SPEC.md found (use --spec)")?;
+
+    println!(
+        "{} {}",
+        "🔧 Generating API client from".bright_cyan().bold(),
+        spec_path.display()
+    );
+
+    let files = generate_api_client(&project_path, Some(&spec_path), language)?;
+    for file in &files {
+        println!("  {} {}", "✓".green(), file.display());
+    }
+    println!(
+        "\n{} Regenerated automatically after WRITE_CODE in infrastructure sprints.",
+        "ℹ".blue()
+    );
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use anyhow::{Context, Result};
+use autoflow_data::{AutoFlowError, SprintsYaml};
+use autoflow_utils::{add_hint, clear_hints, pending_hints, Paths};
+use colored::*;
+use std::path::Path;
+
+/// Queue, list or clear operator hints for a sprint's next agent run
+pub async fn run(sprint_id: u32, text: Option<String>, clear: bool) -> Result<()> {
+    if !Path::new(Paths::SPRINTS_YML).exists() {
+        return Err(AutoFlowError::NotInitialized.into());
+    }
+    let plan = SprintsYaml::load(Paths::SPRINTS_YML).context("Failed to load SPRINTS.yml")?;
+    plan.sprint(sprint_id).with_context(|| format!("Sprint {} not found", sprint_id))?;
+    let project = Path::new(".");
+
+    if clear {
+        let count = pending_hints(project, sprint_id).len();
+        clear_hints(project, sprint_id)?;
+        println!("{} Cleared {} hint(s) for Sprint {}", "✓".green(), count, sprint_id);
+        return Ok(());
+    }
+
+    match text.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        Some(hint) => {
+            add_hint(project, sprint_id, hint)?;
+            println!("{} Hint queued for Sprint {}", "✓".green(), sprint_id);
+            println!(
+                "  {}",
+                "The next agent run on this sprint gets it at the top of its context, then it's cleared".bright_black()
+            );
+        }
+        None => {
+            let hints = pending_hints(project, sprint_id);
+            if hints.is_empty() {
+                println!("No hints queued for Sprint {}", sprint_id);
+            } else {
+                println!("{}", format!("Hints for Sprint {}'s next agent run:", sprint_id).bold());
+                for hint in hints {
+                    println!("  • {}", hint);
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use anyhow::{bail, Context, Result};
+use autoflow_data::SprintsYaml;
+use colored::*;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::HooksCommands;
+
+const SPRINTS_FILE: &str = ".autoflow/SPRINTS.yml";
+
+/// Marker used to recognise hooks we installed (so we never clobber user hooks)
+const HOOK_MARKER: &str = "# Installed by autoflow hooks install";
+
+/// Hooks managed by AutoFlow
+const MANAGED_HOOKS: &[&str] = &["pre-commit", "post-merge"];
+
+pub async fn run(cmd: HooksCommands) -> Result<()> {
+    match cmd {
+        HooksCommands::Install { force } => install_hooks(force),
+        HooksCommands::Uninstall => uninstall_hooks(),
+        HooksCommands::Run { hook } => match hook.as_str() {
+            "pre-commit" => run_pre_commit(),
+            "post-merge" => run_post_merge(),
+            other => bail!("Unknown hook '{}' (expected pre-commit or post-merge)", other),
+        },
+    }
+}
+
+fn install_hooks(force: bool) -> Result<()> {
+    println!("{}", "🪝 Installing git hooks...".bright_cyan().bold());
+
+    let hooks_dir = hooks_dir()?;
+    fs::create_dir_all(&hooks_dir)?;
+
+    for hook in MANAGED_HOOKS {
+        let path = hooks_dir.join(hook);
+        if path.exists() && !force {
+            let existing = fs::read_to_string(&path).unwrap_or_default();
+            if !existing.contains(HOOK_MARKER) {
+                println!(
+                    "  {} {} already exists (not ours) - skipping, use --force to replace",
+                    "⚠".yellow(),
+                    hook.bright_blue()
+                );
+                continue;
+            }
+        }
+
+        let script = format!(
+            "#!/bin/sh\n{}\n# Validates the AutoFlow plan; remove this file to disable.\nexec autoflow hooks run {} \"$@\"\n",
+            HOOK_MARKER, hook
+        );
+        fs::write(&path, script).context(format!("Failed to write {}", path.display()))?;
+        make_executable(&path)?;
+        println!("  {} {}", "✓".green(), path.display());
+    }
+
+    println!();
+    println!("  pre-commit: blocks commits with a schema-invalid SPRINTS.yml");
+    println!("  post-merge: warns when the plan changed upstream");
+    Ok(())
+}
+
+fn uninstall_hooks() -> Result<()> {
+    let hooks_dir = hooks_dir()?;
+    for hook in MANAGED_HOOKS {
+        let path = hooks_dir.join(hook);
+        let ours = fs::read_to_string(&path)
+            .map(|c| c.contains(HOOK_MARKER))
+            .unwrap_or(false);
+        if ours {
+            fs::remove_file(&path)?;
+            println!("  {} Removed {}", "✓".green(), path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Validate the staged SPRINTS.yml (what will actually be committed)
+fn run_pre_commit() -> Result<()> {
+    let staged = git(&["diff", "--cached", "--name-only", "--diff-filter=ACM"])?;
+    if !staged.lines().any(|l| l.trim() == SPRINTS_FILE) {
+        return Ok(());
+    }
+
+    let content = git(&["show", &format!(":{}", SPRINTS_FILE)])?;
+    if let Err(errors) = SprintsYaml::validate_yaml_str(&content) {
+        eprintln!("{}", "✗ AutoFlow: staged SPRINTS.yml is invalid".red().bold());
+        eprintln!();
+        eprintln!("{}", errors);
+        eprintln!();
+        eprintln!("Fix the file or bypass with {}", "git commit --no-verify".bright_blue());
+        bail!("SPRINTS.yml failed schema validation");
+    }
+
+    Ok(())
+}
+
+/// Warn when a merge/pull changed the plan
+fn run_post_merge() -> Result<()> {
+    let changed = git(&["diff", "--name-only", "ORIG_HEAD", "HEAD"]).unwrap_or_default();
+    if !changed.lines().any(|l| l.trim() == SPRINTS_FILE) {
+        return Ok(());
+    }
+
+    println!("{}", "⚠ AutoFlow: SPRINTS.yml changed upstream".yellow().bold());
+    println!("  Review with {}", "git diff ORIG_HEAD HEAD -- .autoflow/SPRINTS.yml".bright_blue());
+    if let Err(errors) = SprintsYaml::validate_all_errors(SPRINTS_FILE) {
+        println!("{}", "  The merged plan is invalid:".red());
+        println!("{}", errors);
+    }
+
+    // Post-merge hooks cannot abort the merge, so never fail here
+    Ok(())
+}
+
+/// Hooks directory, honouring core.hooksPath and linked worktrees
+fn hooks_dir() -> Result<PathBuf> {
+    let path = git(&["rev-parse", "--git-path", "hooks"])
+        .context("Not a git repository")?;
+    Ok(PathBuf::from(path.trim()))
+}
+
+fn git(args: &[&str]) -> Result<String> {
+    let output = Command::new("git").args(args).output()?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(unix)]
+fn make_executable(path: &std::path::Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut perms = fs::metadata(path)?.permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(path, perms)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &std::path::Path) -> Result<()> {
+    Ok(())
+}
@@ -48,6 +48,12 @@ pub async fn run(template: Option<String>) -> anyhow::Result<()> {
     fs::write(".autoflow/SPRINTS.yml", sprints_template)?;
     info!("✓ SPRINTS.yml created");
 
+    // Fixture manifest for E2E seed data
+    fs::create_dir_all(".autoflow/fixtures")?;
+    let fixtures_template = include_str!("../../templates/fixtures.template.yml");
+    fs::write(".autoflow/fixtures/fixtures.yml", fixtures_template)?;
+    info!("✓ fixtures.yml created");
+
     // Copy CLAUDE.md template
     println!("{}", "Creating CLAUDE.md...".bright_white());
     let claude_template = include_str!("../../templates/CLAUDE.template.md");
@@ -67,6 +73,9 @@ pub async fn run(template: Option<String>) -> anyhow::Result<()> {
 # AutoFlow
 .autoflow/.debug/
 .autoflow/.failures/
+.autoflow/run.lock
+.autoflow/SPRINTS.yml.lock
+.autoflow/runtime-status.json
 
 # Environment
 .env
@@ -98,6 +107,7 @@ Thumbs.db
     println!("\n{}", "Directory structure:".bright_white());
     println!("  {}  - Sprint definitions", ".autoflow/SPRINTS.yml".bright_cyan());
     println!("  {}      - Design documentation", ".autoflow/docs/".bright_cyan());
+    println!("  {}    - E2E test fixtures", ".autoflow/fixtures/".bright_cyan());
     println!("  {}       - Claude configuration", ".claude/CLAUDE.md".bright_cyan());
 
     Ok(())
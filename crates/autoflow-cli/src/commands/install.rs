@@ -1,26 +1,63 @@
+use crate::cli::InstallComponent;
+use crate::embedded::{install_components, verify_components, FileAction, InstallReport};
+use anyhow::{bail, Result};
 use colored::*;
-use std::path::PathBuf;
-use tracing::{info, warn};
 
-pub async fn run(force: bool) -> anyhow::Result<()> {
-    println!("{}", "🚀 Installing AutoFlow...".bright_cyan().bold());
+pub async fn run(force: bool, components: Vec<InstallComponent>, verify: bool, json: bool) -> Result<()> {
+    let home = autoflow_utils::require_home()?;
+    let components = if components.is_empty() { InstallComponent::ALL.to_vec() } else { components };
 
-    let home = std::env::var("HOME")?;
-    let autoflow_dir = PathBuf::from(&home).join(".autoflow");
+    let report = if verify { verify_components(&home, &components) } else { install_components(&home, &components, force)? };
 
-    // Check if already installed
-    if autoflow_dir.exists() && !force {
-        warn!("AutoFlow is already installed at {}", autoflow_dir.display());
-        println!("\n{}", "Use --force to reinstall".yellow());
-        return Ok(());
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
     }
 
-    info!("Installation directory: {}", autoflow_dir.display());
+    if !report.ok() {
+        bail!("Some installed files failed verification");
+    }
+    Ok(())
+}
 
-    println!("\n{}", "Installation coming soon!".yellow());
-    println!("For now, run from source:");
-    println!("  {}", "cargo build --release".bright_blue());
-    println!("  {}", "cargo install --path crates/autoflow-cli".bright_blue());
+fn print_report(report: &InstallReport) {
+    let title = if report.verify_only { "🔍 Verifying AutoFlow install" } else { "🚀 Installing AutoFlow" };
+    println!("{} {}", title.bright_cyan().bold(), format!("v{}", report.version).dimmed());
 
-    Ok(())
+    let mut modified = false;
+    for component in &report.components {
+        let mark = if component.ok { "✓".bright_green() } else { "✗".bright_red() };
+        let count = |action: FileAction| component.files.iter().filter(|f| f.action == action).count();
+        let counts: Vec<String> = [
+            (FileAction::Installed, "installed"),
+            (FileAction::Updated, "updated"),
+            (FileAction::Unchanged, "unchanged"),
+            (FileAction::Modified, "modified"),
+            (FileAction::Missing, "missing"),
+        ]
+        .iter()
+        .filter(|(action, _)| count(*action) > 0)
+        .map(|(action, label)| format!("{} {}", count(*action), label))
+        .collect();
+        println!("\n  {} {:<12} {}", mark, component.component.bright_white().bold(), counts.join(", ").dimmed());
+
+        for file in &component.files {
+            if let Some(ref error) = file.error {
+                println!("      {} {}: {}", "✗".bright_red(), file.path.display(), error);
+            } else if file.action == FileAction::Modified {
+                modified = true;
+                println!("      {} {} differs from the built-in copy", "~".yellow(), file.path.display());
+            } else if matches!(file.action, FileAction::Installed | FileAction::Updated) {
+                println!("      {} {}", "+".bright_green(), file.path.display());
+            }
+        }
+        if let Some(ref note) = component.note {
+            println!("      {} {}", "ℹ".bright_cyan(), note);
+        }
+    }
+
+    if modified {
+        println!("\n  {} Run `autoflow install --force` to replace modified files", "💡".bright_yellow());
+    }
 }
@@ -1,10 +1,14 @@
 use anyhow::{Context, Result};
 use colored::*;
 use std::fs;
-use std::path::PathBuf;
-use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::time::Duration;
 
-pub async fn run(follow: bool, live: bool) -> Result<()> {
+/// How often `--follow` checks a live log for new events
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+
+pub async fn run(follow: bool, live: bool, run_id: Option<String>) -> Result<()> {
     let log_dir = if live {
         PathBuf::from(".autoflow/.debug/live")
     } else {
@@ -24,7 +28,10 @@ pub async fn run(follow: bool, live: bool) -> Result<()> {
             .filter_map(|e| e.ok())
             .filter(|entry| {
                 let path = entry.path();
-                path.is_file() && path.extension().map_or(false, |ext| ext == "jsonl")
+                // Rolled segments belong to the log whose tail is still at the plain path
+                path.is_file()
+                    && path.extension().is_some_and(|ext| ext == "jsonl")
+                    && !autoflow_agents::is_segment(path)
             })
             .filter_map(|entry| fs::metadata(entry.path()).ok().map(|m| (entry.into_path(), m)))
             .collect()
@@ -33,12 +40,26 @@ pub async fn run(follow: bool, live: bool) -> Result<()> {
             .filter_map(|entry| entry.ok())
             .filter(|entry| {
                 let path = entry.path();
-                path.is_file() && path.extension().map_or(false, |ext| ext == "log")
+                path.is_file() && path.extension().is_some_and(|ext| ext == "log")
             })
             .filter_map(|entry| fs::metadata(entry.path()).ok().map(|m| (entry.path(), m)))
             .collect()
     };
 
+    // Log filenames embed the run id after the timestamp
+    if let Some(ref id) = run_id {
+        let id = id.to_uppercase();
+        log_files.retain(|(path, _)| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|name| name.split('_').any(|part| part.starts_with(&id)))
+        });
+        if log_files.is_empty() {
+            println!("{}", format!("No logs found for run {} in {:?}", id, log_dir).red());
+            return Ok(());
+        }
+    }
+
     log_files.sort_by_key(|(_, metadata)| {
         metadata.modified().ok().unwrap_or(std::time::SystemTime::UNIX_EPOCH)
     });
@@ -51,17 +72,26 @@ pub async fn run(follow: bool, live: bool) -> Result<()> {
 
     if live {
         // Parse and display JSONL streaming events
-        display_live_log(&log_path, follow)?;
+        display_live_log(log_path, follow)?;
     } else {
         // Display regular log file
-        display_regular_log(&log_path, follow)?;
+        display_regular_log(log_path, follow)?;
     }
 
     Ok(())
 }
 
 fn display_live_log(path: &PathBuf, follow: bool) -> Result<()> {
-    use serde_json::Value;
+    let segments = autoflow_agents::log_segments(path);
+    if !segments.is_empty() {
+        println!(
+            "{}",
+            format!("   {} earlier segment(s) rolled over; showing the latest events", segments.len()).dimmed()
+        );
+        for segment in &segments {
+            println!("{}", format!("   {}", segment.display()).dimmed());
+        }
+    }
 
     let file = fs::File::open(path)?;
     let reader = BufReader::new(file);
@@ -69,27 +99,91 @@ fn display_live_log(path: &PathBuf, follow: bool) -> Result<()> {
     println!("\n{}", "Streaming Events:".bright_green().bold());
     println!("{}", "─".repeat(80).dimmed());
 
+    let mut offset = 0u64;
+    let mut stopped = false;
     for line in reader.lines() {
         let line = line?;
-        if line.is_empty() {
-            continue;
+        offset += line.len() as u64 + 1;
+        stopped |= display_line(&line);
+    }
+
+    if follow && !stopped {
+        follow_live_log(path, offset, segments.len())?;
+    }
+
+    Ok(())
+}
+
+/// Display one JSONL line; true once the agent's message has finished
+fn display_line(line: &str) -> bool {
+    if line.is_empty() {
+        return false;
+    }
+    match serde_json::from_str::<serde_json::Value>(line) {
+        Ok(event) => {
+            display_event(&event);
+            event.get("type").and_then(|v| v.as_str()) == Some("message_stop")
+        }
+        Err(e) => {
+            eprintln!("{}", format!("Failed to parse event: {}", e).red());
+            false
         }
+    }
+}
 
-        match serde_json::from_str::<Value>(&line) {
-            Ok(event) => {
-                display_event(&event);
+/// Keep printing events as the agent writes them, until its message stops
+///
+/// When the log rolls over, the rest of the segment it rolled into is read
+/// before starting again at the top of the new tail.
+fn follow_live_log(path: &Path, mut offset: u64, mut segments: usize) -> Result<()> {
+    println!("{}", "Following... (Ctrl+C to stop)".dimmed());
+    let mut pending = String::new();
+    loop {
+        let rolled = autoflow_agents::log_segments(path);
+        if rolled.len() > segments {
+            let finished = autoflow_agents::segment_path(path, segments as u32 + 1);
+            if read_from(&finished, offset, &mut pending)?.1 {
+                return Ok(());
             }
-            Err(e) => {
-                eprintln!("{}", format!("Failed to parse event: {}", e).red());
+            pending.clear();
+            segments = rolled.len();
+            offset = 0;
+        }
+
+        let len = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        if len < offset {
+            offset = 0;
+            pending.clear();
+        }
+        if len > offset {
+            let (read, stopped) = read_from(path, offset, &mut pending)?;
+            offset += read;
+            if stopped {
+                return Ok(());
             }
         }
+        std::thread::sleep(FOLLOW_INTERVAL);
     }
+}
 
-    if follow {
-        println!("\n{}", "Following mode not yet implemented...".yellow());
+/// Print complete lines from `offset` on, keeping a trailing partial line in `pending`
+///
+/// Returns the bytes read and whether the agent's message has finished.
+fn read_from(path: &Path, offset: u64, pending: &mut String) -> Result<(u64, bool)> {
+    let Ok(mut file) = fs::File::open(path) else {
+        return Ok((0, false));
+    };
+    file.seek(SeekFrom::Start(offset))?;
+    let mut chunk = Vec::new();
+    file.read_to_end(&mut chunk)?;
+    pending.push_str(&String::from_utf8_lossy(&chunk));
+
+    let mut stopped = false;
+    while let Some(end) = pending.find('\n') {
+        let line: String = pending.drain(..=end).collect();
+        stopped |= display_line(line.trim_end());
     }
-
-    Ok(())
+    Ok((chunk.len() as u64, stopped))
 }
 
 fn display_event(event: &serde_json::Value) {
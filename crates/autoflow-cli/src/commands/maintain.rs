@@ -0,0 +1,60 @@
+use anyhow::{bail, Context, Result};
+use autoflow_core::{maintenance_due, maintenance_sprints, record_maintenance_run, scan_maintenance, CodebaseAnalyzer};
+use autoflow_data::{AutoFlowError, Config, SprintsYaml};
+use autoflow_utils::Paths;
+use chrono::Utc;
+use colored::*;
+use std::path::Path;
+
+/// Scan for maintenance work and append a sprint for each kind found
+pub async fn run(dry_run: bool, if_due: bool) -> Result<()> {
+    if !Path::new(Paths::SPRINTS_YML).exists() {
+        return Err(AutoFlowError::NotInitialized.into());
+    }
+    let config = Config::global().map(|c| c.maintenance).unwrap_or_default();
+    let project = Path::new(".");
+    let now = Utc::now();
+    if if_due && !maintenance_due(project, config.interval_days, now) {
+        println!("Maintenance isn't due yet (every {} days)", config.interval_days);
+        return Ok(());
+    }
+
+    println!("{}", "🧹 Scanning for maintenance work...".bright_cyan().bold());
+    let analysis = CodebaseAnalyzer::new(".").analyze().context("Failed to analyze the project")?;
+    let findings = scan_maintenance(project, &analysis.tech_stack, &config);
+
+    println!("  Outdated dependencies: {}", findings.outdated.len().to_string().bright_blue());
+    println!(
+        "  TODO/FIXME markers: {} ({:.1} per 1000 lines, threshold {})",
+        findings.debt.markers.to_string().bright_blue(),
+        findings.debt.per_kloc(),
+        config.max_debt_per_kloc
+    );
+    println!("  Flaky tests: {}", findings.flaky.len().to_string().bright_blue());
+    for skipped in &findings.skipped {
+        println!("  {} Skipped {}", "⚠".yellow(), skipped);
+    }
+
+    let mut plan = SprintsYaml::load(Paths::SPRINTS_YML).context("Failed to load SPRINTS.yml")?;
+    let sprints = maintenance_sprints(&findings, &plan.sprints, &config);
+    println!();
+    if sprints.is_empty() {
+        println!("{} Nothing new to schedule", "✓".green());
+    }
+    for sprint in &sprints {
+        println!("  {} Sprint {}: {} ({})", "+".green(), sprint.id, sprint.goal, sprint.workflow_type);
+    }
+
+    if dry_run {
+        println!("\n{}", "Dry run - SPRINTS.yml not modified".yellow());
+        return Ok(());
+    }
+    for sprint in sprints {
+        if let Err(e) = plan.add_sprint(sprint, None) {
+            bail!("Refusing to save SPRINTS.yml, patched plan is invalid:\n{}", e);
+        }
+    }
+    plan.save(Paths::SPRINTS_YML).context("Failed to save SPRINTS.yml")?;
+    record_maintenance_run(project, now)?;
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use colored::*;
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -35,7 +35,7 @@ pub async fn run_install(servers: Vec<String>, project_level: bool) -> Result<()
         project_config
     } else {
         // Global installation (Claude Code user scope)
-        let home = std::env::var("HOME")?;
+        let home = autoflow_utils::require_home()?;
         let claude_dir = PathBuf::from(&home).join(".claude");
         fs::create_dir_all(&claude_dir)?;
 
@@ -158,7 +158,7 @@ pub async fn run_list() -> Result<()> {
     println!("{}", "📋 Installed MCP Servers".bright_cyan().bold());
     println!();
 
-    let home = std::env::var("HOME")?;
+    let home = autoflow_utils::require_home()?;
     let user_config_path = PathBuf::from(&home).join(".claude/settings.local.json");
     let project_config_path = PathBuf::from(".mcp.json");
 
@@ -166,35 +166,29 @@ pub async fn run_list() -> Result<()> {
 
     // Load user-level servers
     if user_config_path.exists() {
-        match fs::read_to_string(&user_config_path) {
-            Ok(content) => {
-                if let Ok(config) = serde_json::from_str::<ClaudeConfig>(&content) {
-                    println!("{}", "User-level servers (all projects):".bright_cyan());
-                    for (name, _) in &config.mcp_servers {
-                        println!("  • {}", name.bright_blue());
-                        all_servers.insert(name.clone(), "user");
-                    }
-                    println!();
+        if let Ok(content) = fs::read_to_string(&user_config_path) {
+            if let Ok(config) = serde_json::from_str::<ClaudeConfig>(&content) {
+                println!("{}", "User-level servers (all projects):".bright_cyan());
+                for name in config.mcp_servers.keys() {
+                    println!("  • {}", name.bright_blue());
+                    all_servers.insert(name.clone(), "user");
                 }
+                println!();
             }
-            Err(_) => {}
         }
     }
 
     // Load project-level servers
     if project_config_path.exists() {
-        match fs::read_to_string(&project_config_path) {
-            Ok(content) => {
-                if let Ok(config) = serde_json::from_str::<ClaudeConfig>(&content) {
-                    println!("{}", "Project-level servers (this project only):".bright_cyan());
-                    for (name, _) in &config.mcp_servers {
-                        println!("  • {}", name.bright_blue());
-                        all_servers.insert(name.clone(), "project");
-                    }
-                    println!();
+        if let Ok(content) = fs::read_to_string(&project_config_path) {
+            if let Ok(config) = serde_json::from_str::<ClaudeConfig>(&content) {
+                println!("{}", "Project-level servers (this project only):".bright_cyan());
+                for name in config.mcp_servers.keys() {
+                    println!("  • {}", name.bright_blue());
+                    all_servers.insert(name.clone(), "project");
                 }
+                println!();
             }
-            Err(_) => {}
         }
     }
 
@@ -239,6 +233,12 @@ pub async fn run_info(server_name: Option<String>) -> Result<()> {
             println!();
             println!("{}: {}", "Description".bright_cyan(), info.description);
             println!("{}: {}", "Use Case".bright_cyan(), info.use_case);
+            if let Some(package) = &info.npm_package {
+                println!("{}: {}", "Package".bright_cyan(), package);
+            }
+            if let Some(repo) = &info.github_repo {
+                println!("{}: https://github.com/{}", "Source".bright_cyan(), repo);
+            }
             println!();
             println!("{}", "Capabilities:".bright_cyan());
             for capability in &info.capabilities {
@@ -471,11 +471,11 @@ async fn install_server(
         }
         "filesystem" => {
             // Allow access to home directory by default
-            let home = std::env::var("HOME")?;
+            let home = autoflow_utils::require_home()?;
             server_config.args = Some(vec![
                 "-y".to_string(),
                 "@modelcontextprotocol/server-filesystem".to_string(),
-                home,
+                home.display().to_string(),
             ]);
         }
         _ => {}
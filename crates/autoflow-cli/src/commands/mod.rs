@@ -6,7 +6,12 @@ pub mod status;
 pub mod analyze;
 pub mod add;
 pub mod fix;
+pub mod hint;
+pub mod maintain;
+pub mod patch;
 pub mod pivot;
+pub mod quick;
+pub mod resolve;
 pub mod rollback;
 pub mod worktree;
 pub mod validate;
@@ -17,3 +22,17 @@ pub mod env;
 pub mod mcp;
 pub mod logs;
 pub mod update;
+pub mod completions;
+pub mod doctor;
+pub mod setup;
+pub mod telemetry;
+pub mod config;
+pub mod board;
+pub mod hooks;
+pub mod generate;
+pub mod workflows;
+pub mod runs;
+pub mod debug;
+pub mod cost;
+pub mod watch;
+pub mod export_bundle;
@@ -0,0 +1,107 @@
+use crate::PatchCommands;
+use anyhow::{bail, Result};
+use autoflow_core::{apply_patches, discard_patches, patch_stat, status_name, undo_patches, PatchQueue, PATCHES_DIR};
+use autoflow_data::AutoFlowError;
+use autoflow_utils::{record_signoff, request_approval, Paths};
+use colored::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub async fn run(cmd: PatchCommands) -> Result<()> {
+    if !Path::new(Paths::SPRINTS_YML).exists() {
+        return Err(AutoFlowError::NotInitialized.into());
+    }
+    let project = Path::new(".");
+    match cmd {
+        PatchCommands::List => list(project),
+        PatchCommands::Show { file } => {
+            let path = project.join(PATCHES_DIR).join(&file);
+            if !path.exists() {
+                bail!("No patch bundle {} in {}", file, PATCHES_DIR);
+            }
+            print!("{}", fs::read_to_string(path)?);
+            Ok(())
+        }
+        PatchCommands::Apply { sprint } => apply(project, sprint),
+        PatchCommands::Undo { sprint } => undo(project, sprint),
+        PatchCommands::Discard => {
+            let pending = PatchQueue::load(project)?.pending(None).len();
+            request_approval(&format!("Discard {} pending patch bundle(s) and the patch checkout?", pending))?;
+            let dropped = discard_patches(project)?;
+            println!("{} Discarded {} bundle(s); the next run starts from HEAD", "✓".green(), dropped);
+            Ok(())
+        }
+    }
+}
+
+fn list(project: &Path) -> Result<()> {
+    let queue = PatchQueue::load(project)?;
+    if queue.bundles.is_empty() {
+        println!("No patch bundles in {}", PATCHES_DIR);
+        return Ok(());
+    }
+    println!("{}", "🩹 Patch bundles (oldest first)".bright_cyan().bold());
+    for bundle in &queue.bundles {
+        let state = match bundle.applied_at {
+            Some(at) => format!("applied {}", at.format("%Y-%m-%d %H:%M")).green(),
+            None => "pending".yellow(),
+        };
+        println!(
+            "  {}  sprint {}  {}  {}",
+            bundle.file.bright_blue(),
+            bundle.sprint,
+            status_name(bundle.phase).bright_black(),
+            state
+        );
+    }
+    if !queue.pending(None).is_empty() {
+        println!(
+            "\nReview with {} and apply with {}",
+            "autoflow patch show <file>".bright_blue(),
+            "autoflow patch apply".bright_blue()
+        );
+    }
+    Ok(())
+}
+
+fn apply(project: &Path, sprint: Option<u32>) -> Result<()> {
+    let queue = PatchQueue::load(project)?;
+    let pending: Vec<String> = queue.pending(sprint).iter().map(|b| b.file.clone()).collect();
+    if pending.is_empty() {
+        println!("No pending patch bundles");
+        return Ok(());
+    }
+    // Bundles build on each other, so a sprint's can't skip ahead of earlier ones
+    if let Some(first) = queue.pending(None).first().filter(|b| sprint.is_some_and(|id| b.sprint != id)) {
+        bail!("Apply {} (sprint {}) first: bundles build on the ones before them", first.file, first.sprint);
+    }
+
+    for file in &pending {
+        println!("{}", file.bright_blue());
+        print!("{}", patch_stat(project, file)?);
+    }
+    request_approval(&format!("Apply {} patch bundle(s) to the working tree?", pending.len()))?;
+    // One sign-off per sprint, hashing the bundles exactly as reviewed
+    let mut sprints: Vec<u32> = queue.pending(sprint).iter().map(|b| b.sprint).collect();
+    sprints.dedup();
+    for id in sprints {
+        let bundles: Vec<PathBuf> =
+            queue.pending(Some(id)).iter().map(|b| Path::new(PATCHES_DIR).join(&b.file)).collect();
+        record_signoff(project, "patch-apply", Some(id), &format!("{} bundle(s)", bundles.len()), &bundles)?;
+    }
+    apply_patches(project, &pending)?;
+    println!("{} Applied {} bundle(s); review and commit them with git", "✓".green(), pending.len());
+    Ok(())
+}
+
+fn undo(project: &Path, sprint: Option<u32>) -> Result<()> {
+    let queue = PatchQueue::load(project)?;
+    let applied: Vec<String> = queue.applied(sprint).iter().map(|b| b.file.clone()).collect();
+    if applied.is_empty() {
+        println!("No applied patch bundles to undo");
+        return Ok(());
+    }
+    undo_patches(project, &applied)?;
+    println!("{} Undid {} bundle(s)", "✓".green(), applied.len());
+    Ok(())
+}
@@ -1,12 +1,12 @@
 use anyhow::{Context, Result, bail};
 use autoflow_agents::execute_agent;
-use autoflow_data::{SprintsYaml, SprintStatus};
-use autoflow_utils::{extract_yaml_from_output, Paths};
+use autoflow_data::{Sprint, SprintsYaml, SprintStatus};
+use autoflow_utils::{ensure_approved, extract_yaml_from_output, finish_run, start_run, Paths, RunLock};
 use colored::*;
 use std::fs;
 use std::path::Path;
 
-pub async fn run(instruction: String) -> Result<()> {
+pub async fn run(instruction: String, steal_lock: bool) -> Result<()> {
     println!("{}", "🔄 Pivoting project based on your feedback...".bright_cyan().bold());
     println!();
 
@@ -19,6 +19,11 @@ pub async fn run(instruction: String) -> Result<()> {
         );
     }
 
+    ensure_approved("pivot")?;
+    let run_id = start_run("pivot");
+    let _lock = RunLock::acquire("pivot", steal_lock)?;
+    println!("{} {}", "🆔 Run:".bright_black(), run_id.dimmed());
+
     // Read all existing documentation
     println!("{}", "📖 Reading current documentation...".bright_cyan());
     let build_spec = fs::read_to_string(".autoflow/docs/BUILD_SPEC.md").unwrap_or_default();
@@ -46,9 +51,8 @@ pub async fn run(instruction: String) -> Result<()> {
         println!("{}", "📋 Saving current sprint states...".bright_cyan());
         match SprintsYaml::load(Paths::SPRINTS_YML) {
             Ok(data) => {
-                let states: Vec<(u32, SprintStatus)> = data.sprints.iter()
-                    .map(|s| (s.id, s.status.clone()))
-                    .collect();
+                // Whole sprints, so their uids carry over along with their states
+                let states: Vec<Sprint> = data.sprints;
                 println!("  {} Saved {} sprint states", "✓".green(), states.len().to_string().bright_blue());
                 println!();
                 Some(states)
@@ -219,7 +223,7 @@ The agent definition already contains the full YAML format. Just output the actu
                                 fs::write(Paths::SPRINTS_YML, updated_yaml)?;
                                 println!("  {} Restored sprint states (kept {} active/completed sprints)",
                                     "✓".green(),
-                                    states.iter().filter(|(_, s)| !matches!(s, SprintStatus::Pending | SprintStatus::Blocked)).count()
+                                    states.iter().filter(|s| !matches!(s.status, SprintStatus::Pending | SprintStatus::Blocked)).count()
                                 );
                             }
                             Err(_) => {
@@ -281,38 +285,34 @@ The agent definition already contains the full YAML format. Just output the actu
     }
     println!();
 
+    finish_run("completed", &[]);
     Ok(())
 }
 
 /// Try to restore sprint states from old sprints into new sprint plan
-fn restore_sprint_states(new_yaml: &str, old_states: &[(u32, SprintStatus)]) -> Result<String> {
+fn restore_sprint_states(new_yaml: &str, old_sprints: &[Sprint]) -> Result<String> {
     use autoflow_data::SprintsYaml;
 
     // Parse the new YAML
     let mut sprints_data: SprintsYaml = serde_yaml::from_str(new_yaml)
         .context("Failed to parse new sprints YAML")?;
 
-    // Create a map of old states
-    let state_map: std::collections::HashMap<u32, SprintStatus> = old_states.iter()
-        .cloned()
+    // Update sprints matching an old one, by uid if the agent kept it, else by ID
+    let matches: Vec<(u32, &Sprint)> = sprints_data
+        .sprints
+        .iter()
+        .filter_map(|sprint| {
+            old_sprints
+                .iter()
+                .find(|old| sprint.uid.is_some() && old.uid == sprint.uid)
+                .or_else(|| old_sprints.iter().find(|old| old.id == sprint.id))
+                .map(|old| (sprint.id, old))
+        })
         .collect();
-
-    // Update sprint states where IDs match
-    for sprint in &mut sprints_data.sprints {
-        if let Some(old_status) = state_map.get(&sprint.id) {
-            // Preserve all states except PENDING and BLOCKED
-            // PENDING and BLOCKED get reset to PENDING
-            match old_status {
-                SprintStatus::Pending | SprintStatus::Blocked => {
-                    sprint.status = SprintStatus::Pending;
-                }
-                _ => {
-                    // Preserve active/completed states
-                    sprint.status = old_status.clone();
-                }
-            }
-        }
+    for (id, old) in matches {
+        sprints_data.carry_over(id, old)?;
     }
+    sprints_data.assign_uids();
 
     // Serialize back to YAML
     let updated_yaml = serde_yaml::to_string(&sprints_data)
@@ -0,0 +1,216 @@
+use anyhow::{bail, Context, Result};
+use autoflow_core::Orchestrator;
+use autoflow_data::{AutoFlowError, Priority, Sprint, SprintStatus, SprintsYaml, Task, TaskStatus, TaskType, WorkflowType};
+use autoflow_utils::{ensure_approved, finish_run, sanitize_branch_name, start_run, Paths, RunLock};
+use chrono::Utc;
+use colored::*;
+use std::path::Path;
+use std::process::Command;
+
+/// Sprint id used when the quick sprint is not saved to SPRINTS.yml
+const QUICK_SPRINT_ID: u32 = 0;
+
+/// Quick sprints are small; give up well before a full sprint would
+const QUICK_MAX_ITERATIONS: u32 = 20;
+
+pub async fn run(instruction: String, save: bool, steal_lock: bool) -> Result<()> {
+    println!("{}", "⚡ Quick change".bright_cyan().bold());
+    println!("Instruction: {}", instruction.bright_blue());
+
+    if !Path::new(".git").exists() {
+        bail!("{}", "Not a git repository. Initialize git first.".red());
+    }
+    if !Path::new(Paths::AUTOFLOW_DIR).exists() {
+        return Err(AutoFlowError::NotInitialized.into());
+    }
+
+    // The branch should only contain the quick change
+    let dirty = git(&["status", "--porcelain", "--", ".", ":(exclude).autoflow/"])?;
+    if !dirty.trim().is_empty() {
+        bail!(
+            "{}\nCommit or stash your changes before running {}",
+            "Working tree has uncommitted changes.".red(),
+            "autoflow quick".bright_blue()
+        );
+    }
+
+    let active_policy = ensure_approved("quick")?;
+    let run_id = start_run("quick");
+    let _lock = RunLock::acquire("quick", steal_lock)?;
+    println!("{} {}", "🆔 Run:".bright_black(), run_id.dimmed());
+
+    let sprints_path = format!("{}/SPRINTS.yml", Paths::AUTOFLOW_DIR);
+    let mut plan = if save {
+        Some(SprintsYaml::load(&sprints_path).context("Failed to load SPRINTS.yml (needed for --save)")?)
+    } else {
+        None
+    };
+    let sprint_id = plan
+        .as_ref()
+        .map(|p| p.sprints.iter().map(|s| s.id).max().unwrap_or(0) + 1)
+        .unwrap_or(QUICK_SPRINT_ID);
+    let mut sprint = quick_sprint(sprint_id, &instruction);
+
+    // Work on a fresh branch so the change is easy to review or throw away
+    let original_branch = git(&["rev-parse", "--abbrev-ref", "HEAD"])?.trim().to_string();
+    let base = git(&["rev-parse", "HEAD"])?.trim().to_string();
+    let mut branch = format!("quick/{}", sanitize_branch_name(&instruction, 5));
+    if git(&["rev-parse", "--verify", "--quiet", &format!("refs/heads/{}", branch)]).is_ok() {
+        branch = format!("{}-{}", branch, run_id[run_id.len() - 6..].to_lowercase());
+    }
+    git(&["checkout", "-b", &branch])?;
+    println!("{} Branch: {} (from {})", "✓".green(), branch.bright_green(), original_branch);
+
+    println!("\n{}", "Implement → test → review...".bright_cyan());
+    let project_path = std::env::current_dir()?;
+    let allow_submodule_bumps = autoflow_data::Config::global()
+        .map(|c| c.defaults.allow_submodule_bumps)
+        .unwrap_or(false);
+    let commit_policy = autoflow_data::Config::global()
+        .map(|c| c.commit)
+        .unwrap_or_default();
+    let orchestrator = Orchestrator::new(QUICK_MAX_ITERATIONS)
+        .with_project_path(project_path)
+        .with_auto_commit(autoflow_data::ProjectConfig::current().auto_commit().unwrap_or(true))
+        .with_submodule_bumps(allow_submodule_bumps)
+        .with_commit_policy(commit_policy)
+        .with_token_budget(active_policy.policy.max_run_tokens);
+    let result = orchestrator.run_sprint(&mut sprint).await;
+
+    match &result {
+        Ok(_) => println!("\n{} Quick change completed", "✅".green()),
+        Err(e) => println!("\n{} Quick change stopped at {:?}: {}", "❌".red(), sprint.status, e),
+    }
+
+    report_diff(&base, &branch)?;
+
+    if let Some(ref mut plan) = plan {
+        plan.add_sprint(sprint.clone(), None).context("Quick sprint doesn't fit SPRINTS.yml")?;
+        plan.save(&sprints_path).context("Failed to save SPRINTS.yml")?;
+        println!("{} Saved as Sprint {} in SPRINTS.yml", "✓".green(), sprint.id);
+    }
+
+    let outcome = match sprint.status {
+        SprintStatus::Done => "completed",
+        SprintStatus::Blocked => "blocked",
+        _ => "incomplete",
+    };
+    if let Some(path) = finish_run(outcome, std::slice::from_ref(&sprint)) {
+        println!("{} {}", "📄 Run summary:".bright_black(), path.display().to_string().dimmed());
+    }
+
+    println!("\n{}", "Next steps:".bold());
+    println!("  Review: {}", format!("git diff {}..{}", original_branch, branch).bright_blue());
+    println!(
+        "  Merge:  {}",
+        format!("git checkout {} && git merge {}", original_branch, branch).bright_blue()
+    );
+    println!(
+        "  Drop:   {}",
+        format!("git checkout {} && git branch -D {}", original_branch, branch).bright_blue()
+    );
+
+    result.map_err(Into::into)
+}
+
+/// Single-task sprint for the instruction, run with the reduced QUICK workflow
+fn quick_sprint(id: u32, instruction: &str) -> Sprint {
+    let now = Utc::now();
+    Sprint {
+        id,
+        uid: None,
+        goal: instruction.to_string(),
+        status: SprintStatus::Pending,
+        workflow_type: WorkflowType::Quick,
+        workflow: None,
+        duration: None,
+        total_effort: "1h".to_string(),
+        max_effort: "2h".to_string(),
+        started: None,
+        last_updated: now,
+        completed_at: None,
+        deliverables: vec![instruction.to_string()],
+        tasks: vec![Task {
+            id: format!("task-{}01", id),
+            uid: None,
+            title: instruction.to_string(),
+            description: Some(format!(
+                "Small, self-contained change requested directly by the user: {}\nKeep the change minimal and write or update unit tests for it in the same phase.",
+                instruction
+            )),
+            r#type: TaskType::Implementation,
+            doc_reference: None,
+            acceptance_criteria: vec![
+                "The requested change is implemented".to_string(),
+                "Unit tests cover the change and pass".to_string(),
+                "No unrelated files are modified".to_string(),
+            ],
+            test_specification: None,
+            effort: "1h".to_string(),
+            priority: Priority::Medium,
+            feature: "quick".to_string(),
+            docs: vec![],
+            business_rules: vec![],
+            integration_notes: None,
+            files: vec![],
+            testing: autoflow_data::TestingRequirements {
+                unit_tests: None,
+                integration_tests: None,
+                e2e_tests: None,
+            },
+            status: TaskStatus::Pending,
+            committed_at: None,
+            reviewed_at: None,
+            tested_at: None,
+            done_at: None,
+            git_commit: None,
+            review_findings: vec![],
+        }],
+        dependencies: vec![],
+        integration_points: None,
+        blocked_count: None,
+        must_complete_first: false,
+        priority: None,
+        value: None,
+        failure_reports: vec![],
+        repo: None,
+        uses_blocker_resolver: false,
+        issue_url: None,
+        skipped: None,
+        cancelled: None,
+        paused_from: None,
+    }
+}
+
+/// Commits and file stats the quick change added on top of `base`
+fn report_diff(base: &str, branch: &str) -> Result<()> {
+    let range = format!("{}..{}", base, branch);
+    let log = git(&["log", "--oneline", &range])?;
+    let stat = git(&["diff", "--stat", &range])?;
+
+    println!("\n{}", "Changes".bright_cyan().bold());
+    if log.trim().is_empty() {
+        println!("  {} No commits were made", "ℹ".blue());
+        return Ok(());
+    }
+    for line in log.lines() {
+        println!("  {}", line);
+    }
+    println!();
+    for line in stat.lines() {
+        println!("  {}", line);
+    }
+    Ok(())
+}
+
+fn git(args: &[&str]) -> Result<String> {
+    let output = Command::new("git").args(args).output()?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
@@ -0,0 +1,338 @@
+use anyhow::{bail, Context, Result};
+use autoflow_agents::{build_fixer_context, execute_agent};
+use autoflow_core::{completed_phases, workflow_for, last_phase, parse_phase, status_name};
+use autoflow_data::{AutoFlowError, Sprint, SprintStatus, SprintsYaml};
+use autoflow_utils::{blocked_issue_body, read_run_events, Paths, RunEvent, RunLock, RunSummary, RUN_EVENTS_PATH};
+use colored::*;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Where the sprint's failure reports live
+const FAILURES_DIR: &str = ".autoflow/.failures";
+
+/// Turns the blocker-resolver gets, as in the run loop
+const RESOLVER_MAX_TURNS: u32 = 10;
+
+/// Interactive session for a blocked sprint: triage report, then actions until it's unblocked
+pub async fn run(sprint_id: u32) -> Result<()> {
+    if !Path::new(Paths::SPRINTS_YML).exists() {
+        return Err(AutoFlowError::NotInitialized.into());
+    }
+    if !io::stdin().is_terminal() {
+        bail!("`autoflow resolve` is interactive; run it in a terminal");
+    }
+
+    let plan = load_plan()?;
+    let sprint = plan.sprint(sprint_id).with_context(|| format!("Sprint {} not found", sprint_id))?;
+    if sprint.status != SprintStatus::Blocked {
+        bail!(
+            "Sprint {} is {}, not BLOCKED - nothing to resolve",
+            sprint_id,
+            status_name(sprint.status)
+        );
+    }
+
+    println!("{}", format!("🩺 Resolving Sprint {}: {}", sprint.id, sprint.goal).bright_cyan().bold());
+    println!();
+    println!("{}", triage_report(sprint, &plan));
+
+    loop {
+        println!();
+        println!("{}", "Actions:".bold());
+        println!("  [r] Re-run from a phase          [o] Open a failure report");
+        println!("  [h] Run blocker-resolver with hints");
+        println!("  [e] Edit the sprint              [s] Mark skipped");
+        println!("  [t] Show the triage report       [q] Quit");
+        let done = match ask("Action")?.to_lowercase().as_str() {
+            "r" => rerun_phase(sprint_id)?,
+            "o" => open_report(sprint_id)?,
+            "h" => run_resolver(sprint_id).await?,
+            "e" => edit_sprint(sprint_id)?,
+            "s" => skip_sprint(sprint_id)?,
+            "t" => {
+                let plan = load_plan()?;
+                if let Some(sprint) = plan.sprint(sprint_id) {
+                    println!("{}", triage_report(sprint, &plan));
+                }
+                false
+            }
+            "q" => true,
+            "" => false,
+            other => {
+                println!("  {} Unknown action '{}'", "⚠".yellow(), other);
+                false
+            }
+        };
+        if done {
+            return Ok(());
+        }
+    }
+}
+
+fn load_plan() -> Result<SprintsYaml> {
+    SprintsYaml::load(Paths::SPRINTS_YML).context("Failed to load SPRINTS.yml")
+}
+
+/// Write `sprint` into the plan on disk, leaving the rest of the plan as it is there
+fn save_sprint(sprint: &Sprint) -> Result<()> {
+    SprintsYaml::update_sprint(Paths::SPRINTS_YML, sprint)
+        .context("Failed to save SPRINTS.yml")?
+        .with_context(|| format!("Sprint {} is no longer in SPRINTS.yml", sprint.id))?;
+    Ok(())
+}
+
+/// The report a forge issue would carry, from the last run that touched the sprint
+fn triage_report(sprint: &Sprint, plan: &SprintsYaml) -> String {
+    let events = read_run_events(RUN_EVENTS_PATH, None).unwrap_or_default();
+    let run_id = events
+        .iter()
+        .rev()
+        .find(|e| e.is_for(sprint))
+        .map(|e| e.run_id.clone())
+        .unwrap_or_default();
+    let run_events: Vec<RunEvent> = events.into_iter().filter(|e| e.run_id == run_id).collect();
+    let summary = RunSummary::from_events(&run_id, &run_events, &plan.sprints, "blocked", Vec::new());
+    blocked_issue_body(sprint, &summary, Path::new("."))
+}
+
+/// Send the sprint back to a phase and, if asked, run it; true once it's no longer blocked
+fn rerun_phase(sprint_id: u32) -> Result<bool> {
+    let mut plan = load_plan()?;
+    let sprint = plan.sprint(sprint_id).with_context(|| format!("Sprint {} not found", sprint_id))?;
+    let events = read_run_events(RUN_EVENTS_PATH, None).unwrap_or_default();
+    let workflow = workflow_for(sprint);
+    let default = last_phase(sprint, &events).unwrap_or_else(|| workflow.unblock_status());
+
+    let phases: Vec<String> = workflow.phases.iter().map(|p| status_name(p.status)).collect();
+    println!("  Phases: {}", phases.join(", "));
+    let answer = ask(&format!("Phase to run from [{}]", status_name(default)))?;
+    let phase = if answer.is_empty() {
+        default
+    } else {
+        match parse_phase(&answer).filter(|p| workflow.get_phase(*p).is_some()) {
+            Some(phase) => phase,
+            None => {
+                println!("  {} '{}' is not a phase of the {} workflow", "⚠".yellow(), answer, workflow.name);
+                return Ok(false);
+            }
+        }
+    };
+    // The phase already passed once: `start` must be told to redo it
+    let redo = completed_phases(sprint, &events).contains(&phase);
+
+    plan.update_status(sprint_id, phase)?;
+    plan.reset_blocked_count(sprint_id)?;
+    save_sprint(plan.sprint(sprint_id).context("Sprint not found")?)?;
+    println!("  {} Sprint {} set to {}", "✓".green(), sprint_id, status_name(phase));
+
+    let mut start_args = vec!["start".to_string(), "--sprint".to_string(), sprint_id.to_string()];
+    if redo {
+        start_args.push("--force-redo".to_string());
+    }
+    if !confirm("Start it now?", true)? {
+        println!("  Run {} when ready", format!("autoflow {}", start_args.join(" ")).bright_blue());
+        return Ok(true);
+    }
+
+    let status = Command::new(std::env::current_exe()?)
+        .args(&start_args)
+        .status()
+        .context("Failed to run autoflow start")?;
+    let blocked_again = load_plan()?.sprint(sprint_id).is_some_and(|s| s.status == SprintStatus::Blocked);
+    if blocked_again {
+        println!("  {} Sprint {} blocked again - back to the menu", "⚠".yellow(), sprint_id);
+    } else if !status.success() {
+        println!("  {} autoflow start exited with {}", "⚠".yellow(), status);
+    }
+    Ok(!blocked_again)
+}
+
+/// The sprint's current failure reports, oldest name first
+fn sprint_reports(sprint_id: u32) -> Vec<PathBuf> {
+    let prefix = format!("sprint-{}-", sprint_id);
+    let mut reports: Vec<PathBuf> = fs::read_dir(FAILURES_DIR)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| {
+                    let name = e.file_name().to_string_lossy().to_string();
+                    name.starts_with(&prefix) && name.ends_with(".md")
+                })
+                .map(|e| e.path())
+                .collect()
+        })
+        .unwrap_or_default();
+    reports.sort();
+    reports
+}
+
+fn open_report(sprint_id: u32) -> Result<bool> {
+    let reports = sprint_reports(sprint_id);
+    if reports.is_empty() {
+        println!("  No failure reports for sprint {} in {}", sprint_id, FAILURES_DIR);
+        return Ok(false);
+    }
+    for (i, path) in reports.iter().enumerate() {
+        println!("  [{}] {}", i + 1, path.display());
+    }
+    let answer = ask("Report [1]")?;
+    let index = if answer.is_empty() { Some(0) } else { answer.parse::<usize>().ok().and_then(|n| n.checked_sub(1)) };
+    let Some(path) = index.and_then(|i| reports.get(i)) else {
+        println!("  {} No report '{}'", "⚠".yellow(), answer);
+        return Ok(false);
+    };
+
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| "less".to_string());
+    if !open_with(&pager, path) {
+        println!("{}", fs::read_to_string(path)?);
+    }
+    Ok(false)
+}
+
+/// Run the blocker-resolver with the developer's hints added to its context
+async fn run_resolver(sprint_id: u32) -> Result<bool> {
+    println!("  Hints for the blocker-resolver (the cause you suspect, what to try). End with an empty line:");
+    let mut hints = String::new();
+    loop {
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        hints.push_str(&line);
+    }
+
+    // Not alongside a run, which would be changing the same files
+    let _lock = RunLock::acquire("resolve", false)?;
+    let plan = load_plan()?;
+    let sprint = plan.sprint(sprint_id).with_context(|| format!("Sprint {} not found", sprint_id))?;
+    let mut context = build_fixer_context(sprint, Path::new("."));
+    if !hints.trim().is_empty() {
+        context.push_str("\n\n## Hints from the Developer\n\nA human looked at this blocker. Start from what they say:\n\n");
+        context.push_str(hints.trim());
+        context.push('\n');
+    }
+
+    println!("  Spawning blocker-resolver...");
+    let result = execute_agent("blocker-resolver", &context, RESOLVER_MAX_TURNS, Some(sprint_id)).await?;
+    if !result.success {
+        println!(
+            "  {} blocker-resolver failed: {}",
+            "⚠".yellow(),
+            result.error.unwrap_or_else(|| "unknown error".to_string())
+        );
+        return Ok(false);
+    }
+
+    println!();
+    println!("{}", result.output.trim());
+    fs::create_dir_all(FAILURES_DIR)?;
+    fs::write(
+        Path::new(FAILURES_DIR).join(format!("sprint-{}-blocker-analysis.md", sprint_id)),
+        format!("# Blocker Analysis - Sprint {}\n\n{}\n", sprint_id, result.output.trim()),
+    )?;
+    // As after the run loop's own resolver pass: later failures come back to BLOCKED.
+    // Reloaded, as the agent may have edited the plan itself
+    let mut plan = load_plan()?;
+    if plan.mark_blocker_resolver_used(sprint_id).is_ok() {
+        save_sprint(plan.sprint(sprint_id).context("Sprint not found")?)?;
+    }
+    println!();
+    println!("  {} Analysis saved; use [r] to re-run the failing phase and check the fix", "✓".green());
+    Ok(false)
+}
+
+/// Edit the sprint's YAML in $EDITOR; saved only if it still parses and validates
+fn edit_sprint(sprint_id: u32) -> Result<bool> {
+    let plan = load_plan()?;
+    let sprint = plan.sprint(sprint_id).with_context(|| format!("Sprint {} not found", sprint_id))?;
+    let path = std::env::temp_dir().join(format!("autoflow-sprint-{}-{}.yml", sprint_id, std::process::id()));
+    fs::write(&path, serde_yaml::to_string(sprint)?)?;
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    if !open_with(&editor, &path) {
+        println!("  {} Couldn't run '{}'; set $EDITOR", "⚠".yellow(), editor);
+        return Ok(false);
+    }
+
+    let edited: Sprint = match serde_yaml::from_str(&fs::read_to_string(&path)?) {
+        Ok(sprint) => sprint,
+        Err(e) => {
+            println!("  {} Not saved ({}); your edit is in {}", "⚠".yellow(), e, path.display());
+            return Ok(false);
+        }
+    };
+    if edited.id != sprint_id {
+        println!("  {} Not saved: the sprint id can't change here", "⚠".yellow());
+        return Ok(false);
+    }
+    match save_sprint(&edited) {
+        Ok(()) => {
+            let _ = fs::remove_file(&path);
+            println!("  {} Sprint {} saved", "✓".green(), sprint_id);
+        }
+        Err(e) => println!("  {} Not saved ({}); your edit is in {}", "⚠".yellow(), e, path.display()),
+    }
+    Ok(false)
+}
+
+/// Mark the sprint SKIPPED without its work; true once skipped
+fn skip_sprint(sprint_id: u32) -> Result<bool> {
+    let mut plan = load_plan()?;
+    let id = sprint_id.to_string();
+    let dependents: Vec<String> = plan
+        .sprints
+        .iter()
+        .filter(|s| s.dependencies.contains(&id))
+        .map(|s| s.id.to_string())
+        .collect();
+    if !dependents.is_empty() {
+        println!("  {} Sprints depending on it will run without its work: {}", "⚠".yellow(), dependents.join(", "));
+    }
+
+    let reason = ask("Reason for skipping")?;
+    if reason.is_empty() {
+        println!("  A reason is required, so the plan records why");
+        return Ok(false);
+    }
+    if !confirm(&format!("Mark sprint {} SKIPPED without finishing it?", sprint_id), false)? {
+        return Ok(false);
+    }
+    plan.skip_sprint(sprint_id, &reason)?;
+    save_sprint(plan.sprint(sprint_id).context("Sprint not found")?)?;
+    println!("  {} Sprint {} skipped", "✓".green(), sprint_id);
+    Ok(true)
+}
+
+/// Open `path` with a command from the environment (which may carry its own arguments)
+fn open_with(command: &str, path: &Path) -> bool {
+    Command::new("sh")
+        .args(["-c", &format!("{} \"$1\"", command), "sh"])
+        .arg(path)
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+fn ask(question: &str) -> Result<String> {
+    print!("  {}: ", question);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_string())
+}
+
+fn confirm(question: &str, default: bool) -> Result<bool> {
+    let suffix = if default { "[Y/n]" } else { "[y/N]" };
+    print!("  {} {} ", question, suffix);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(match input.trim().to_lowercase().as_str() {
+        "" => default,
+        "y" | "yes" => true,
+        _ => false,
+    })
+}
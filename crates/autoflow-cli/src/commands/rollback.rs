@@ -1,5 +1,5 @@
-use anyhow::{bail, Context};
-use autoflow_data::{SprintsYaml, SprintStatus};
+use anyhow::Context;
+use autoflow_data::{AutoFlowError, SprintsYaml, SprintStatus};
 use autoflow_git::WorktreeManager;
 use colored::*;
 use std::path::Path;
@@ -10,11 +10,7 @@ pub async fn run(sprint: Option<u32>) -> anyhow::Result<()> {
     // Check if project is initialized
     let sprints_path = ".autoflow/SPRINTS.yml";
     if !Path::new(sprints_path).exists() {
-        bail!(
-            "{}\nRun {} first",
-            "Project not initialized.".red(),
-            "autoflow init".bright_blue()
-        );
+        return Err(AutoFlowError::NotInitialized.into());
     }
 
     // Load sprints
@@ -29,7 +25,7 @@ pub async fn run(sprint: Option<u32>) -> anyhow::Result<()> {
         sprints_data
             .sprints
             .iter()
-            .filter(|s| s.status != SprintStatus::Done && s.status != SprintStatus::Pending)
+            .filter(|s| s.status.is_runnable() && s.status != SprintStatus::Pending)
             .max_by_key(|s| s.id)
             .map(|s| s.id)
             .ok_or_else(|| anyhow::anyhow!("No active sprint to rollback"))?
@@ -39,9 +35,7 @@ pub async fn run(sprint: Option<u32>) -> anyhow::Result<()> {
 
     // Find the sprint
     let sprint = sprints_data
-        .sprints
-        .iter_mut()
-        .find(|s| s.id == sprint_id)
+        .sprint(sprint_id)
         .ok_or_else(|| anyhow::anyhow!("Sprint {} not found", sprint_id))?;
 
     println!("Sprint goal: {}", sprint.goal.bright_blue());
@@ -72,20 +66,10 @@ pub async fn run(sprint: Option<u32>) -> anyhow::Result<()> {
 
     // Reset sprint status
     println!("\n{}", "Resetting sprint status...".bright_cyan());
-    sprint.status = SprintStatus::Pending;
-    sprint.started = None;
-    sprint.completed_at = None;
-    sprint.last_updated = chrono::Utc::now();
+    sprints_data.update_status(sprint_id, SprintStatus::Pending)?;
 
     // Reset task statuses
-    for task in &mut sprint.tasks {
-        task.status = autoflow_data::TaskStatus::Pending;
-        task.committed_at = None;
-        task.reviewed_at = None;
-        task.tested_at = None;
-        task.done_at = None;
-        task.git_commit = None;
-    }
+    sprints_data.reset_tasks(sprint_id)?;
 
     // Save updated sprints
     sprints_data.save(sprints_path)
@@ -0,0 +1,71 @@
+use anyhow::Result;
+use autoflow_utils::{compare_runs, ComparisonRow, RunMetrics, RunSummary};
+use colored::*;
+use std::path::Path;
+
+use crate::RunsCommands;
+
+pub async fn run(cmd: RunsCommands) -> Result<()> {
+    match cmd {
+        RunsCommands::Compare { run_a, run_b, json } => compare(&run_a, &run_b, json),
+    }
+}
+
+fn compare(run_a: &str, run_b: &str, json: bool) -> Result<()> {
+    let a = RunMetrics::from_summary(&RunSummary::find(Path::new("."), run_a)?);
+    let b = RunMetrics::from_summary(&RunSummary::find(Path::new("."), run_b)?);
+    let rows = compare_runs(&a, &b);
+
+    if json {
+        let report = serde_json::json!({ "a": a, "b": b, "rows": rows });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!("{}", "⚖️  Run Comparison".bright_cyan().bold());
+    println!();
+    println!("  {} {} ({})", "A:".bright_white().bold(), a.run_id, a.outcome);
+    println!("  {} {} ({})", "B:".bright_white().bold(), b.run_id, b.outcome);
+    println!();
+
+    let metric_width = rows.iter().map(|r| r.metric.len()).max().unwrap_or(0).max(6);
+    let value_width = rows.iter().flat_map(|r| [r.a.len(), r.b.len()]).max().unwrap_or(0).max(1);
+    println!(
+        "  {:<mw$}  {:>vw$}  {:>vw$}  {}",
+        "Metric".bright_white().bold(),
+        "A".bright_white().bold(),
+        "B".bright_white().bold(),
+        "Change".bright_white().bold(),
+        mw = metric_width,
+        vw = value_width
+    );
+    for row in &rows {
+        println!(
+            "  {:<mw$}  {:>vw$}  {:>vw$}  {}",
+            row.metric,
+            row.a,
+            row.b,
+            colour_change(row),
+            mw = metric_width,
+            vw = value_width
+        );
+    }
+    Ok(())
+}
+
+/// Green when B improved on A, red when it got worse
+///
+/// More is better for success rates and completed sprints; less is better
+/// for everything else that's measured.
+fn colour_change(row: &ComparisonRow) -> ColoredString {
+    let change = row.change.as_str();
+    if change.is_empty() || change == "=" {
+        return change.bright_black();
+    }
+    let higher_is_better = row.metric.ends_with("success") || row.metric == "Sprints done";
+    if change.starts_with('+') == higher_is_better {
+        change.green()
+    } else {
+        change.red()
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::Result;
+use autoflow_data::Config;
+use colored::*;
+use std::io::{self, Write};
+
+use super::doctor;
+
+/// Guided first-run setup: prerequisites, assets, MCP servers, config, verification
+pub async fn run(yes: bool) -> Result<()> {
+    println!("{}", "🚀 AutoFlow Setup".bright_cyan().bold());
+    println!();
+
+    // Step 1: prerequisites
+    println!("{}", "1/5 Checking prerequisites...".bright_cyan());
+    let prereqs = doctor::prerequisite_checks();
+    let prereqs_ok = doctor::print_checks(&prereqs);
+    println!();
+    if !prereqs_ok && !confirm("Required tools are missing. Continue anyway?", false, yes)? {
+        anyhow::bail!("Setup aborted: install the missing tools and rerun `autoflow setup`");
+    }
+
+    // Step 2: agents, skills, schemas and templates
+    println!("{}", "2/5 Installing agents, skills and schemas...".bright_cyan());
+    crate::embedded::extract_embedded_assets()?;
+    println!("  {} Installed to ~/.claude/ and ~/.autoflow/", "✓".green());
+    println!();
+
+    // Step 3: MCP servers
+    println!("{}", "3/5 MCP servers".bright_cyan());
+    let node_available = prereqs.iter().any(|c| c.name == "Node.js" && c.passed);
+    if !node_available {
+        println!("  {} Node.js not found, skipping (run `autoflow mcp install` later)", "⚠".yellow());
+        println!();
+    } else if confirm("Install recommended MCP servers?", true, yes)? {
+        super::mcp::run_install(vec![], false).await?;
+    } else {
+        println!("  {} Skipped (run `autoflow mcp install` later)", "→".yellow());
+        println!();
+    }
+
+    // Step 4: global config
+    println!("{}", "4/5 Writing global config...".bright_cyan());
+    let config_path = Config::global_path()?;
+    if config_path.exists() {
+        println!("  {} Keeping existing {}", "→".yellow(), config_path.display());
+    } else {
+        let home = autoflow_utils::require_home()?;
+        let mut config = Config::new_for_home(&home);
+        let model = ask("Default model", &config.defaults.model, yes)?;
+        config.defaults.model = model;
+        config.save(&config_path)?;
+        println!("  {} Wrote {}", "✓".green(), config_path.display());
+    }
+    println!();
+
+    // Step 5: verify
+    println!("{}", "5/5 Verifying installation...".bright_cyan());
+    let ok = doctor::print_checks(&doctor::run_checks());
+    println!();
+
+    if ok {
+        println!("{}", "✅ AutoFlow is ready!".bright_green().bold());
+        println!();
+        println!("Next steps:");
+        println!("  {}", "autoflow create my-app --idea IDEA.md".bright_blue());
+        println!("  {}", "autoflow init   (in an existing project)".bright_blue());
+        Ok(())
+    } else {
+        anyhow::bail!("Setup finished with failing checks; see `autoflow doctor`");
+    }
+}
+
+/// Ask a yes/no question; answers yes without prompting when `assume_yes` is set
+fn confirm(question: &str, default: bool, assume_yes: bool) -> Result<bool> {
+    if assume_yes {
+        return Ok(true);
+    }
+
+    let suffix = if default { "[Y/n]" } else { "[y/N]" };
+    print!("  {} {} ", question, suffix);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(match input.trim().to_lowercase().as_str() {
+        "" => default,
+        "y" | "yes" => true,
+        _ => false,
+    })
+}
+
+/// Ask for a value, falling back to `default` on empty input
+fn ask(question: &str, default: &str, assume_yes: bool) -> Result<String> {
+    if assume_yes {
+        return Ok(default.to_string());
+    }
+
+    print!("  {} [{}]: ", question, default);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim();
+    Ok(if input.is_empty() { default.to_string() } else { input.to_string() })
+}
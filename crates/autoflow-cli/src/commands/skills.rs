@@ -1,13 +1,12 @@
 use colored::*;
 use std::fs;
-use std::path::PathBuf;
 
 pub async fn run() -> anyhow::Result<()> {
     println!("{}", "🛠️  Available Skills".bright_cyan().bold());
 
     // Load skills from ~/.claude/skills/
-    let home = std::env::var("HOME")?;
-    let skills_dir = PathBuf::from(home).join(".claude/skills");
+    let home = autoflow_utils::require_home()?;
+    let skills_dir = home.join(".claude/skills");
 
     if !skills_dir.exists() {
         println!("\n{}", "No skills directory found".yellow());
@@ -34,8 +33,7 @@ pub async fn run() -> anyhow::Result<()> {
             let content = fs::read_to_string(&path)?;
             let description = content
                 .lines()
-                .skip_while(|l| l.trim().is_empty() || l.starts_with('#'))
-                .next()
+                .find(|l| !(l.trim().is_empty() || l.starts_with('#')))
                 .unwrap_or("No description")
                 .trim()
                 .to_string();
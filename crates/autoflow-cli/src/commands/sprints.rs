@@ -1,24 +1,69 @@
 use anyhow::{bail, Context};
-use autoflow_data::{SprintsYaml, SprintStatus};
+use autoflow_data::{
+    empty_plan, export_csv, export_json, export_markdown, load_sprint_templates, merge_import, parse_csv,
+    parse_structured, sprint_templates_dir, AutoFlowError, ConflictStrategy, PlanFormat, SprintStatus, SprintsYaml,
+};
 use colored::*;
+use std::collections::HashMap;
+use std::fs;
 use std::path::Path;
 
 pub async fn run(cmd: crate::SprintsCommands) -> anyhow::Result<()> {
-    // Check if project is initialized
     let sprints_path = ".autoflow/SPRINTS.yml";
+
+    // Import can seed a fresh plan, everything else needs an existing one
+    if let crate::SprintsCommands::Import { file, format, on_conflict, dry_run } = cmd {
+        return import_sprints(sprints_path, &file, format, &on_conflict, dry_run).await;
+    }
+
+    // Check if project is initialized
     if !Path::new(sprints_path).exists() {
-        bail!(
-            "{}\nRun {} first",
-            "Project not initialized.".red(),
-            "autoflow init".bright_blue()
-        );
+        return Err(AutoFlowError::NotInitialized.into());
     }
 
     match cmd {
         crate::SprintsCommands::List => list_sprints(sprints_path).await,
         crate::SprintsCommands::Show { id, integration } => show_sprint(sprints_path, id, integration).await,
-        crate::SprintsCommands::Create => create_sprint().await,
+        crate::SprintsCommands::Create { template, params, dry_run } => {
+            create_sprint(sprints_path, template, &params, dry_run).await
+        }
+        crate::SprintsCommands::Export { format, output } => export_sprints(sprints_path, &format, output).await,
+        crate::SprintsCommands::Import { .. } => unreachable!("handled above"),
+        crate::SprintsCommands::Pause { id } => hold_sprint(sprints_path, id, Hold::Pause),
+        crate::SprintsCommands::Unpause { id } => hold_sprint(sprints_path, id, Hold::Unpause),
+        crate::SprintsCommands::Skip { id, reason } => hold_sprint(sprints_path, id, Hold::Skip(reason)),
+        crate::SprintsCommands::Cancel { id, reason } => hold_sprint(sprints_path, id, Hold::Cancel(reason)),
+    }
+}
+
+/// A status change a human makes outside the pipeline
+enum Hold {
+    Pause,
+    Unpause,
+    Skip(String),
+    Cancel(String),
+}
+
+fn hold_sprint(sprints_path: &str, id: u32, hold: Hold) -> anyhow::Result<()> {
+    let mut plan = SprintsYaml::load(sprints_path).context("Failed to load SPRINTS.yml")?;
+    if plan.sprint(id).is_none() {
+        bail!("Sprint {} not found", id);
     }
+    let message = match hold {
+        Hold::Pause => format!("Sprint {} paused at {:?}", id, plan.pause_sprint(id)?),
+        Hold::Unpause => format!("Sprint {} back at {:?}", id, plan.unpause_sprint(id)?),
+        Hold::Skip(reason) => {
+            plan.skip_sprint(id, &reason)?;
+            format!("Sprint {} skipped; sprints depending on it can run", id)
+        }
+        Hold::Cancel(reason) => {
+            plan.cancel_sprint(id, &reason)?;
+            format!("Sprint {} cancelled; sprints depending on it will wait", id)
+        }
+    };
+    plan.save(sprints_path).context("Failed to save SPRINTS.yml")?;
+    println!("{} {}", "✓".green(), message);
+    Ok(())
 }
 
 async fn list_sprints(sprints_path: &str) -> anyhow::Result<()> {
@@ -39,12 +84,15 @@ async fn list_sprints(sprints_path: &str) -> anyhow::Result<()> {
     // Group sprints by status
     let done: Vec<_> = sprints.sprints.iter().filter(|s| s.status == SprintStatus::Done).collect();
     let in_progress: Vec<_> = sprints.sprints.iter().filter(|s|
-        s.status != SprintStatus::Done &&
+        s.status.is_runnable() &&
         s.status != SprintStatus::Pending &&
         s.status != SprintStatus::Blocked
     ).collect();
     let blocked: Vec<_> = sprints.sprints.iter().filter(|s| s.status == SprintStatus::Blocked).collect();
     let pending: Vec<_> = sprints.sprints.iter().filter(|s| s.status == SprintStatus::Pending).collect();
+    let paused: Vec<_> = sprints.sprints.iter().filter(|s| s.status == SprintStatus::Paused).collect();
+    let skipped: Vec<_> = sprints.sprints.iter().filter(|s| s.status == SprintStatus::Skipped).collect();
+    let cancelled: Vec<_> = sprints.sprints.iter().filter(|s| s.status == SprintStatus::Cancelled).collect();
 
     if !in_progress.is_empty() {
         println!("{}", "In Progress:".bright_green().bold());
@@ -81,6 +129,18 @@ async fn list_sprints(sprints_path: &str) -> anyhow::Result<()> {
         println!();
     }
 
+    if !paused.is_empty() {
+        println!("{}", "Paused:".bright_black().bold());
+        for sprint in &paused {
+            println!("  {} - {} (at {:?})",
+                format!("Sprint {}", sprint.id).bright_blue(),
+                sprint.goal,
+                sprint.paused_from.unwrap_or(SprintStatus::Pending)
+            );
+        }
+        println!();
+    }
+
     if !done.is_empty() {
         println!("{}", "Completed:".green().bold());
         for sprint in &done {
@@ -92,12 +152,32 @@ async fn list_sprints(sprints_path: &str) -> anyhow::Result<()> {
         println!();
     }
 
+    for (label, closed) in [("Skipped:", &skipped), ("Cancelled:", &cancelled)] {
+        if closed.is_empty() {
+            continue;
+        }
+        println!("{}", label.bright_black().bold());
+        for sprint in closed {
+            println!("  {} - {} {}",
+                format!("Sprint {}", sprint.id).bright_blue(),
+                sprint.goal,
+                sprint.skipped.as_ref().or(sprint.cancelled.as_ref()).map(|r| format!("({})", r)).unwrap_or_default().dimmed()
+            );
+        }
+        println!();
+    }
+
     // Summary
     println!("{}", "Summary:".bold());
     println!("  {}: {}/{}", "Completed".green(), done.len(), sprints.sprints.len());
     println!("  {}: {}", "In Progress".blue(), in_progress.len());
     println!("  {}: {}", "Pending".yellow(), pending.len());
     println!("  {}: {}", "Blocked".red(), blocked.len());
+    for (label, count) in [("Paused", paused.len()), ("Skipped", skipped.len()), ("Cancelled", cancelled.len())] {
+        if count > 0 {
+            println!("  {}: {}", label.bright_black(), count);
+        }
+    }
 
     Ok(())
 }
@@ -120,6 +200,7 @@ async fn show_sprint(sprints_path: &str, id: u32, integration: bool) -> anyhow::
         SprintStatus::Done => format!("{:?}", sprint.status).green(),
         SprintStatus::Blocked => format!("{:?}", sprint.status).red(),
         SprintStatus::Pending => format!("{:?}", sprint.status).yellow(),
+        SprintStatus::Paused | SprintStatus::Skipped | SprintStatus::Cancelled => format!("{:?}", sprint.status).bright_black(),
         _ => format!("{:?}", sprint.status).bright_blue(),
     };
     println!("{}: {}", "Status".bold(), status_colored);
@@ -140,6 +221,44 @@ async fn show_sprint(sprints_path: &str, id: u32, integration: bool) -> anyhow::
         println!("{}: {}", "Blocked Count".bold(), blocked_count.to_string().red());
     }
 
+    if let Some(ref url) = sprint.issue_url {
+        println!("{}: {}", "Issue".bold(), url.bright_blue());
+    }
+
+    if let Some(from) = sprint.paused_from {
+        println!("{}: {:?}", "Paused At".bold(), from);
+    }
+
+    if let Some(ref reason) = sprint.skipped {
+        println!("{}: {}", "Skipped".bold(), reason.yellow());
+    }
+
+    if let Some(ref reason) = sprint.cancelled {
+        println!("{}: {}", "Cancelled".bold(), reason.yellow());
+    }
+
+    let hints = autoflow_utils::pending_hints(Path::new("."), sprint.id);
+    if !hints.is_empty() {
+        println!("{}:", "Pending Hints".bold());
+        for hint in hints {
+            println!("  • {}", hint.bright_yellow());
+        }
+    }
+
+    let signoffs: Vec<_> = autoflow_data::read_signoffs(autoflow_data::SIGNOFFS_PATH).into_iter().filter(|s| s.sprint == Some(sprint.id)).collect();
+    if !signoffs.is_empty() {
+        println!("{}:", "Sign-off".bold());
+        for signoff in signoffs {
+            println!(
+                "  • {} approved by {} at {} ({} artifact(s))",
+                signoff.gate,
+                signoff.approver.bright_green(),
+                signoff.approved_at.format("%Y-%m-%d %H:%M UTC"),
+                signoff.artifacts.len()
+            );
+        }
+    }
+
     println!();
     println!("{}", "Deliverables:".bold());
     for deliverable in &sprint.deliverables {
@@ -156,6 +275,9 @@ async fn show_sprint(sprints_path: &str, id: u32, integration: bool) -> anyhow::
         );
         println!("    Priority: {:?}", task.priority);
         println!("    Business Rules: {}", task.business_rules.len());
+        for finding in &task.review_findings {
+            println!("    {} {}", "✗".red(), finding);
+        }
     }
 
     if !sprint.dependencies.is_empty() {
@@ -207,10 +329,171 @@ async fn show_sprint(sprints_path: &str, id: u32, integration: bool) -> anyhow::
     Ok(())
 }
 
-async fn create_sprint() -> anyhow::Result<()> {
-    println!("{}", "📋 Creating sprint...".bright_cyan().bold());
-    println!("\n{}", "💡 Tip:".bright_yellow());
-    println!("  Use {} to add a new feature", "autoflow add \"Feature Description\"".bright_blue());
-    println!("  This will automatically create a sprint");
+async fn create_sprint(
+    sprints_path: &str,
+    template: Option<String>,
+    params: &[String],
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    let templates_dir = sprint_templates_dir();
+    let templates = load_sprint_templates(&templates_dir).context("Failed to load sprint templates")?;
+
+    let Some(name) = template else {
+        println!("{}", "📋 Sprint templates".bright_cyan().bold());
+        if templates.is_empty() {
+            println!("\n{} No templates in {}", "ℹ".blue(), templates_dir.display());
+        }
+        for template in &templates {
+            println!("\n  {} - {}", template.name.bright_blue(), template.description);
+            for param in &template.params {
+                let default = param.default.as_ref().map(|d| format!(" [default: {}]", d)).unwrap_or_default();
+                println!("    --param {}=...  {}{}", param.name, param.description, default.dimmed());
+            }
+        }
+        println!("\n{}", "💡 Tip:".bright_yellow());
+        println!("  {}", "autoflow sprints create --template crud --param resource=Invoice".bright_blue());
+        println!("  Or use {} to plan a feature with the LLM", "autoflow add \"Feature Description\"".bright_blue());
+        return Ok(());
+    };
+
+    let template = templates.iter().find(|t| t.name == name).with_context(|| {
+        let available = templates.iter().map(|t| t.name.as_str()).collect::<Vec<_>>().join(", ");
+        format!("No sprint template '{}' in {} (available: {})", name, templates_dir.display(), available)
+    })?;
+
+    let mut values = HashMap::new();
+    for param in params {
+        let (key, value) = param
+            .split_once('=')
+            .with_context(|| format!("Invalid --param '{}', expected KEY=VALUE", param))?;
+        values.insert(key.trim().to_string(), value.to_string());
+    }
+
+    let mut plan = SprintsYaml::load(sprints_path).context("Failed to load SPRINTS.yml")?;
+    let next_id = plan.next_id();
+    let sprint = template.render(next_id, &values)?;
+
+    if dry_run {
+        print!("{}", serde_yaml::to_string(&sprint)?);
+        println!("\n{}", "Dry run - SPRINTS.yml not modified".yellow());
+        return Ok(());
+    }
+
+    let goal = sprint.goal.clone();
+    if let Err(errors) = plan.add_sprint(sprint, None) {
+        bail!(
+            "{}\n\n{}\n\n{}",
+            format!("Template '{}' produced a sprint that fails schema validation", name).red().bold(),
+            errors,
+            "SPRINTS.yml was not modified".yellow()
+        );
+    }
+
+    plan.save(sprints_path).context("Failed to save SPRINTS.yml")?;
+    println!(
+        "{} Created {} - {} from template {}",
+        "✅".green(),
+        format!("Sprint {}", next_id).bright_blue(),
+        goal,
+        name.bright_blue()
+    );
+
+    Ok(())
+}
+
+async fn export_sprints(sprints_path: &str, format: &str, output: Option<String>) -> anyhow::Result<()> {
+    let sprints = SprintsYaml::load(sprints_path)
+        .context("Failed to load SPRINTS.yml")?;
+
+    let format: PlanFormat = format.parse().map_err(anyhow::Error::msg)?;
+    let content = match format {
+        PlanFormat::Json => export_json(&sprints)?,
+        PlanFormat::Csv => export_csv(&sprints)?,
+        PlanFormat::Markdown => export_markdown(&sprints),
+        PlanFormat::Yaml => serde_yaml::to_string(&sprints)?,
+    };
+
+    match output {
+        Some(path) => {
+            fs::write(&path, content).context(format!("Failed to write {}", path))?;
+            eprintln!("{} Exported {} sprints to {}", "✓".green(), sprints.sprints.len(), path.bright_blue());
+        }
+        None => print!("{}", content),
+    }
+
+    Ok(())
+}
+
+async fn import_sprints(
+    sprints_path: &str,
+    file: &str,
+    format: Option<String>,
+    on_conflict: &str,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    println!("{}", format!("📥 Importing sprints from {}...", file).bright_cyan().bold());
+
+    let format = match format {
+        Some(f) => f.parse().map_err(anyhow::Error::msg)?,
+        None => PlanFormat::from_path(file)
+            .context("Cannot tell the format from the file extension, pass --format")?,
+    };
+    let strategy: ConflictStrategy = on_conflict.parse().map_err(anyhow::Error::msg)?;
+
+    let mut plan = if Path::new(sprints_path).exists() {
+        SprintsYaml::load(sprints_path).context("Failed to load SPRINTS.yml")?
+    } else {
+        let name = std::env::current_dir()?
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "project".to_string());
+        empty_plan(&name)
+    };
+
+    let content = fs::read_to_string(file).context(format!("Failed to read {}", file))?;
+    let imported = match format {
+        PlanFormat::Csv => parse_csv(&content, &plan.sprints)?,
+        PlanFormat::Json | PlanFormat::Yaml => parse_structured(&content, format)?,
+        PlanFormat::Markdown => bail!("Markdown exports are read-only; import json, yaml or csv"),
+    };
+    println!("  Read {} sprints", imported.len().to_string().bright_blue());
+
+    let summary = merge_import(&mut plan, imported, strategy)?;
+
+    if let Err(errors) = plan.validate_errors() {
+        bail!(
+            "{}\n\n{}\n\n{}",
+            "Imported plan failed schema validation".red().bold(),
+            errors,
+            "SPRINTS.yml was not modified".yellow()
+        );
+    }
+    println!("  {} Schema validation passed", "✓".green());
+
+    let ids = |ids: &[u32]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+    if !summary.added.is_empty() {
+        println!("  {}: {}", "Added".green(), ids(&summary.added));
+    }
+    if !summary.replaced.is_empty() {
+        println!("  {}: {}", "Replaced".yellow(), ids(&summary.replaced));
+    }
+    if !summary.skipped.is_empty() {
+        println!("  {}: {}", "Skipped".bright_black(), ids(&summary.skipped));
+    }
+    for (from, to) in &summary.renumbered {
+        println!("  {}: {} → {}", "Renumbered".bright_blue(), from, to);
+    }
+
+    if dry_run {
+        println!("\n{}", "Dry run - SPRINTS.yml not modified".yellow());
+        return Ok(());
+    }
+
+    if let Some(parent) = Path::new(sprints_path).parent() {
+        fs::create_dir_all(parent)?;
+    }
+    plan.save(sprints_path).context("Failed to save SPRINTS.yml")?;
+    println!("\n{} Saved {}", "✅".green(), sprints_path.bright_blue());
+
     Ok(())
 }
@@ -1,22 +1,65 @@
 use anyhow::{bail, Context};
-use autoflow_core::Orchestrator;
-use autoflow_data::{SprintsYaml, SprintStatus};
+use autoflow_core::{docs_size, forecast_run, next_sprint, phases_to_redo, preempting_sprint, AgentUsage, Checkpoint, Orchestrator, RunForecast, CHECKPOINT_PATH};
+use autoflow_data::{AutoFlowError, SchedulingPolicy, SprintsYaml, SprintStatus};
 use autoflow_utils::{
     check_for_updates, should_check_for_updates, prompt_and_update, update_check_timestamp,
-    check_binary_update, prompt_and_install_binary_update,
+    check_binary_update, prompt_and_install_binary_update, check_resources, ensure_approved, ensure_resources, finish_run, record_run_event, start_run, RunLock,
+    active_policy, read_run_events, RUN_EVENTS_PATH,
 };
 use colored::*;
-use std::path::{Path, PathBuf};
+use std::path::Path;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn run(
+    parallel: bool,
+    jobs: usize,
+    sprint: Option<u32>,
+    live: bool,
+    steal_lock: bool,
+    sync_docs: bool,
+    schedule: Option<String>,
+    dry_run: bool,
+    force_redo: bool,
+    resume: bool,
+) -> anyhow::Result<()> {
+    let schedule: SchedulingPolicy = match schedule {
+        Some(schedule) => schedule.parse().map_err(anyhow::Error::msg)?,
+        None => autoflow_data::Config::global().map(|c| c.defaults.scheduling).unwrap_or_default(),
+    };
 
-pub async fn run(parallel: bool, sprint: Option<u32>, live: bool) -> anyhow::Result<()> {
     let version = env!("CARGO_PKG_VERSION");
     println!("{} {}", "🚀 Starting AutoFlow".bright_cyan().bold(), format!("v{}", version).dimmed());
 
-    // Live logging is now enabled by default
-    let live_enabled = live;
+    if dry_run {
+        return show_dry_run(sprint);
+    }
+
+    // `autoflow resume` continues the loops a killed run left behind
+    let checkpoint = if resume {
+        let checkpoint = Checkpoint::load(Path::new(".")).with_context(|| {
+            format!("No checkpoint at {}; run {} instead", CHECKPOINT_PATH, "autoflow start".bright_blue())
+        })?;
+        let sprints: Vec<String> = checkpoint.sprints.keys().map(|id| id.to_string()).collect();
+        println!(
+            "{} sprint(s) {} from run {}",
+            "⏯️  Resuming".bright_cyan(),
+            sprints.join(", "),
+            checkpoint.run_id.as_deref().unwrap_or("unknown").dimmed()
+        );
+        Some(checkpoint)
+    } else {
+        None
+    };
+
+    // Live logging is on by default; config.yml or AUTOFLOW_LIVE_LOGGING=0 turn it off
+    let project_settings = autoflow_data::ProjectConfig::current();
+    let live_enabled = live && project_settings.live_logging().unwrap_or(true);
     if live_enabled {
         println!("{}", "📡 Live logging enabled - streaming to .autoflow/.debug/live/".bright_green());
         std::env::set_var("AUTOFLOW_LIVE_LOGGING", "1");
+    } else {
+        // So agents don't turn it back on from config.yml
+        std::env::set_var("AUTOFLOW_LIVE_LOGGING", "0");
     }
 
     // Check for updates (if enabled and interval has passed)
@@ -75,6 +118,26 @@ pub async fn run(parallel: bool, sprint: Option<u32>, live: bool) -> anyhow::Res
         std::fs::create_dir_all(".autoflow/docs")?;
     }
 
+    // Profile guardrails (approval, token budget, tools) for this checkout
+    let active_policy = ensure_approved("start")?;
+
+    // Only one run may drive this checkout at a time
+    let run_id = start_run("start");
+    let _lock = RunLock::acquire("start", steal_lock)?;
+    println!("{} {}", "🆔 Run:".bright_black(), run_id.dimmed());
+
+    // Drop merged branches past [defaults] worktree_retention_days before checking disk space
+    super::worktree::apply_retention_policy();
+
+    // Refuse to start when disk, memory or file descriptors are already short
+    ensure_resources(Path::new("."))?;
+
+    // Fail fast on an incompatible claude CLI rather than mid-sprint
+    autoflow_agents::claude_capabilities()?;
+
+    // Index doc headings once so agent context can reference sections cheaply
+    autoflow_agents::warm_doc_index(autoflow_agents::doc_index::DOCS_DIR);
+
     // Load sprints with comprehensive validation to collect ALL errors
     println!("\n{}", "Checking project status...".bright_cyan());
 
@@ -97,6 +160,7 @@ pub async fn run(parallel: bool, sprint: Option<u32>, live: bool) -> anyhow::Res
                 total_sprints: 0,
                 current_sprint: None,
                 last_updated: chrono::Utc::now(),
+                repos: vec![],
             },
             sprints: vec![],
         }
@@ -121,6 +185,9 @@ pub async fn run(parallel: bool, sprint: Option<u32>, live: bool) -> anyhow::Res
                     | SprintStatus::WriteE2eTests
                     | SprintStatus::RunE2eTests
                     | SprintStatus::E2eFix
+                    | SprintStatus::Deploy
+                    | SprintStatus::SmokeTest
+                    | SprintStatus::Rollback
             ));
 
             if has_in_progress {
@@ -151,7 +218,7 @@ Steps:
 2. Review ALL the validation errors listed above
 3. Fix ALL occurrences at once:
    - Add missing 'type' field to EVERY task (IMPLEMENTATION, DOCUMENTATION, TEST, INFRASTRUCTURE, REFACTOR, BUGFIX)
-   - Add missing 'workflow_type' field to EVERY sprint (IMPLEMENTATION, DOCUMENTATION, TEST, INFRASTRUCTURE, REFACTOR)
+   - Add missing 'workflow_type' field to EVERY sprint (IMPLEMENTATION, DOCUMENTATION, TEST, INFRASTRUCTURE, REFACTOR, DEPLOYMENT)
    - Add missing 'last_updated' field to project AND every sprint (use current timestamp: "2025-11-06T17:30:00Z")
    - Fix enum values to match SCREAMING_SNAKE_CASE
 4. Use the Write tool to save the corrected SPRINTS.yml
@@ -290,22 +357,7 @@ To be determined during implementation.
                                 std::fs::write(".autoflow/docs/UI_SPEC.md", minimal_ui)?;
                             }
                             if !std::path::Path::new(".autoflow/docs/TESTING_STRATEGY.md").exists() {
-                                let minimal_testing = r#"# Testing Strategy
-
-## Framework Choices
-- Unit: Vitest/Jest with React Testing Library
-- E2E: Playwright/Cypress
-- Backend: PHPUnit/Pest
-
-## Coverage Requirements
-- Overall: 80% minimum
-- Critical paths: 100%
-
-## What to Test
-- Unit: Business logic, utilities, components, hooks
-- Integration: API endpoints, database ops, auth flows
-- E2E: Critical user flows
-"#;
+                                let minimal_testing = super::create::fallback_testing_strategy();
                                 std::fs::write(".autoflow/docs/TESTING_STRATEGY.md", minimal_testing)?;
                             }
                         }
@@ -329,22 +381,7 @@ To be determined during implementation.
 "#, idea_content);
                         std::fs::write(".autoflow/docs/UI_SPEC.md", minimal_ui)?;
 
-                        let minimal_testing = r#"# Testing Strategy
-
-## Framework Choices
-- Unit: Vitest/Jest with React Testing Library
-- E2E: Playwright/Cypress
-- Backend: PHPUnit/Pest
-
-## Coverage Requirements
-- Overall: 80% minimum
-- Critical paths: 100%
-
-## What to Test
-- Unit: Business logic, utilities, components, hooks
-- Integration: API endpoints, database ops, auth flows
-- E2E: Critical user flows
-"#;
+                        let minimal_testing = super::create::fallback_testing_strategy();
                         std::fs::write(".autoflow/docs/TESTING_STRATEGY.md", minimal_testing)?;
                     }
                 }
@@ -365,10 +402,10 @@ To be determined during implementation.
 
             // Load JSON schema - try global location first, then embedded
             let json_schema = {
-                let home = std::env::var("HOME").expect("HOME environment variable not set");
-                let schema_path = PathBuf::from(home).join(".autoflow/schemas/sprints.schema.json");
+                let global_schema = autoflow_utils::autoflow_dir()
+                    .and_then(|dir| std::fs::read_to_string(dir.join("schemas/sprints.schema.json")).ok());
 
-                if let Ok(content) = std::fs::read_to_string(&schema_path) {
+                if let Some(content) = global_schema {
                     content
                 } else {
                     // Fall back to embedded schema (compiled into binary)
@@ -390,8 +427,8 @@ IMPORTANT SCHEMA REQUIREMENTS:
 - All required fields MUST be present
 - All enum values must match EXACTLY (case-sensitive, use SCREAMING_SNAKE_CASE)
 - Valid task types: IMPLEMENTATION, DOCUMENTATION, TEST, INFRASTRUCTURE, REFACTOR, BUGFIX
-- Valid workflow types: IMPLEMENTATION, DOCUMENTATION, TEST, INFRASTRUCTURE, REFACTOR
-- Valid sprint statuses: PENDING, WRITE_UNIT_TESTS, WRITE_CODE, CODE_REVIEW, REVIEW_FIX, RUN_UNIT_TESTS, UNIT_FIX, WRITE_E2E_TESTS, RUN_E2E_TESTS, E2E_FIX, COMPLETE, DONE, BLOCKED
+- Valid workflow types: IMPLEMENTATION, DOCUMENTATION, TEST, INFRASTRUCTURE, REFACTOR, DEPLOYMENT
+- Valid sprint statuses: PENDING, WRITE_UNIT_TESTS, WRITE_CODE, CODE_REVIEW, REVIEW_FIX, RUN_UNIT_TESTS, UNIT_FIX, WRITE_E2E_TESTS, RUN_E2E_TESTS, E2E_FIX, DEPLOY, SMOKE_TEST, ROLLBACK, COMPLETE, DONE, BLOCKED
 - All sprints must start with status: PENDING
 - Include last_updated timestamp in ISO 8601 format
 - CRITICAL: dependencies MUST be an array of strings (sprint IDs), NOT maps/objects
@@ -460,7 +497,7 @@ TASK: Fix the SPRINTS.yml file at `.autoflow/SPRINTS.yml`
 
 Common fixes:
 - Add missing 'type' field to tasks (IMPLEMENTATION, DOCUMENTATION, TEST, INFRASTRUCTURE, REFACTOR, BUGFIX)
-- Add missing 'workflow_type' field to sprints (IMPLEMENTATION, DOCUMENTATION, TEST, INFRASTRUCTURE, REFACTOR)
+- Add missing 'workflow_type' field to sprints (IMPLEMENTATION, DOCUMENTATION, TEST, INFRASTRUCTURE, REFACTOR, DEPLOYMENT)
 - Fix enum values to match schema (SCREAMING_SNAKE_CASE)
 - Add missing required fields (last_updated, etc.)
 - Fix YAML syntax errors (quotes, indentation)
@@ -563,6 +600,23 @@ Only fix what's broken - preserve all existing content."#,
 
     println!("{}", "Loading sprints...".bright_cyan());
 
+    // The checkpoint may be ahead of SPRINTS.yml, e.g. when a save failed or the run was killed between the two
+    if let Some(ref checkpoint) = checkpoint {
+        let moved = checkpoint.apply_statuses(&mut sprints_data.sprints);
+        if !moved.is_empty() {
+            for id in &moved {
+                println!("  {} Sprint {} restored to its checkpointed status", "↻".bright_cyan(), id);
+                if let Some(sprint) = sprints_data.sprint(*id) {
+                    SprintsYaml::update_sprint(sprints_path, sprint)
+                        .with_context(|| format!("Failed to save sprint {} progress", id))?;
+                }
+            }
+        }
+    }
+
+    // Plans from before uids existed get them now, so the run's history links to them
+    sprints_data = SprintsYaml::migrate_uids(sprints_path).context("Failed to save SPRINTS.yml")?;
+
     // Filter sprints based on flags - get indices instead of refs
     let sprint_indices: Vec<usize> = if let Some(sprint_id) = sprint {
         // Run specific sprint
@@ -604,20 +658,22 @@ Only fix what's broken - preserve all existing content."#,
             .iter()
             .enumerate()
             .filter(|(_, s)| {
-                // Must be runnable status (BLOCKED is runnable - blocker-resolver handles it)
-                let is_runnable_status = s.status != SprintStatus::Done;
+                // Must be runnable status (BLOCKED is runnable - blocker-resolver handles it;
+                // PAUSED, SKIPPED and CANCELLED are left alone)
+                let is_runnable_status = s.status.is_runnable();
 
                 if !is_runnable_status {
                     return false;
                 }
 
-                // Check if all dependencies are satisfied
+                // Check if all dependencies are satisfied; in parallel mode a dependency
+                // that will run too is enough, as the scheduler starts this sprint after it
                 let dependencies_satisfied = s.dependencies.iter().all(|dep_id| {
                     sprints_data
                         .sprints
                         .iter()
                         .find(|other| other.id.to_string() == *dep_id)
-                        .map(|dep| dep.status == SprintStatus::Done)
+                        .map(|dep| dep.status.satisfies_dependents() || (parallel && dep.status.is_runnable()))
                         .unwrap_or(true) // If dependency not found, allow (to not break things)
                 });
 
@@ -635,51 +691,114 @@ Only fix what's broken - preserve all existing content."#,
         }
     };
 
+    // Forecast the work left so an oversized plan can be trimmed before tokens are spent
+    let remaining: Vec<&autoflow_data::Sprint> = match sprint {
+        Some(_) => sprint_indices.iter().map(|&idx| &sprints_data.sprints[idx]).collect(),
+        None => sprints_data.sprints.iter().collect(),
+    };
+    let forecast = forecast_remaining(&remaining);
+    print_forecast(&forecast, active_policy.policy.max_run_tokens);
+
+    // A status edited back to a finished phase would redo that work and commit it again
+    check_phase_regressions(&remaining, force_redo)?;
+
     // Create orchestrator
-    let max_iterations = 50;
+    let max_iterations = project_settings.max_iterations().unwrap_or(50);
+    let auto_commit = project_settings.auto_commit().unwrap_or(true);
 
     // Get current directory for git commits
     let project_path = std::env::current_dir()?;
 
-    // Only use save callback in sequential mode to avoid race conditions
-    // In parallel mode, we save once after all sprints complete
+    // Meta-project: every sprint's repo must be declared and checked out
+    let repos = if sprints_data.project.repos.is_empty() {
+        Default::default()
+    } else {
+        sprints_data.check_repos().map_err(anyhow::Error::msg)?;
+        let repos = autoflow_core::open_repos(&project_path, &sprints_data.project.repos)?;
+        println!("{} {}", "📦 Repos:".bright_black(), repos.keys().cloned().collect::<Vec<_>>().join(", "));
+        repos
+    };
+    let allow_submodule_bumps = autoflow_data::Config::global()
+        .map(|c| c.defaults.allow_submodule_bumps)
+        .unwrap_or(false);
+    let task_parallelism = autoflow_data::Config::global()
+        .map(|c| c.defaults.parallel_tasks)
+        .unwrap_or(0);
+    let fix_budget = autoflow_data::Config::global()
+        .map(|c| c.defaults.max_fix_attempts)
+        .unwrap_or_default();
+    let vcs_backend = autoflow_data::Config::global()
+        .map(|c| c.vcs.backend)
+        .unwrap_or_default();
+    let commit_policy = autoflow_data::Config::global()
+        .map(|c| c.commit)
+        .unwrap_or_default();
+
+    // Patch mode: agents work in a shadow checkout and their changes wait for review
+    let patch_checkout = if active_policy.policy.patches {
+        if !repos.is_empty() {
+            bail!("Patch mode doesn't support meta-projects with repos");
+        }
+        let shadow = autoflow_core::prepare_shadow(&project_path)?;
+        println!(
+            "{} agents work in {}; review with {}",
+            "🩹 Patch mode:".bright_black(),
+            shadow.display(),
+            "autoflow patch list".bright_blue()
+        );
+        Some(shadow)
+    } else {
+        None
+    };
+
     let orchestrator = if parallel && sprint_indices.len() > 1 {
-        // Parallel mode: no save callback (prevents race conditions)
+        // Parallel mode: each sprint saves its own progress under the plan's lock
         Orchestrator::new(max_iterations)
             .with_project_path(project_path)
-            .with_auto_commit(true)
+            .with_repos(repos)
+            .with_auto_commit(auto_commit)
+            .with_submodule_bumps(allow_submodule_bumps)
+            .with_vcs(vcs_backend)
+            .with_commit_policy(commit_policy.clone())
+            .with_patch_checkout(patch_checkout.clone())
+            .with_task_parallelism(task_parallelism)
+            .with_token_budget(active_policy.policy.max_run_tokens)
+            .with_fix_budget(fix_budget)
+            .with_resume(resume)
+            .with_save_callback(save_sprint_progress(sprints_path))
     } else {
         // Sequential mode: save after each iteration
-        let sprints_path_for_callback = sprints_path.to_string();
+        let sprints_path_for_preempt = sprints_path.to_string();
+        let preempt = schedule == SchedulingPolicy::Weighted && sprint.is_none();
         Orchestrator::new(max_iterations)
             .with_project_path(project_path)
-            .with_auto_commit(true)
-            .with_save_callback({
-                move |updated_sprint| {
-                    // Load current file, update the specific sprint, save back
-                    match SprintsYaml::load(&sprints_path_for_callback) {
-                        Ok(mut data) => {
-                            // Find and update the sprint
-                            if let Some(sprint) = data.sprints.iter_mut().find(|s| s.id == updated_sprint.id) {
-                                *sprint = updated_sprint.clone();
-                                data.project.last_updated = chrono::Utc::now();
-                                data.save(&sprints_path_for_callback)?;
-                            }
-                            Ok(())
-                        }
-                        Err(e) => {
-                            // Non-fatal: log but don't fail the sprint
-                            tracing::warn!("Failed to save sprint progress: {}", e);
-                            Ok(())
-                        }
-                    }
+            .with_repos(repos)
+            .with_auto_commit(auto_commit)
+            .with_submodule_bumps(allow_submodule_bumps)
+            .with_vcs(vcs_backend)
+            .with_commit_policy(commit_policy)
+            .with_patch_checkout(patch_checkout)
+            .with_task_parallelism(task_parallelism)
+            .with_token_budget(active_policy.policy.max_run_tokens)
+            .with_fix_budget(fix_budget)
+            .with_resume(resume)
+            .with_save_callback(save_sprint_progress(sprints_path))
+            .with_preempt_check(move |current| {
+                // Read the plan from disk: critical sprints may be added or unblocked mid-run
+                if !preempt {
+                    return None;
                 }
+                SprintsYaml::load(&sprints_path_for_preempt)
+                    .ok()
+                    .and_then(|data| preempting_sprint(current, &data.sprints))
             })
     };
 
     // Execute sprints
+    let mut paused = false;
     if parallel && sprint_indices.len() > 1 {
-        println!("\n{}", "Mode: Parallel execution".bright_green());
+        let limit = if jobs == 0 { "no limit".to_string() } else { format!("{} at a time", jobs) };
+        println!("\n{} {}", "Mode: Parallel execution".bright_green(), format!("(dependency order, {})", limit).bright_black());
 
         // Extract sprints to run
         let mut sprints_to_run: Vec<_> = sprint_indices
@@ -688,7 +807,7 @@ Only fix what's broken - preserve all existing content."#,
             .collect();
 
         // Run in parallel
-        let results = orchestrator.run_parallel(&mut sprints_to_run).await?;
+        let results = orchestrator.run_parallel(&mut sprints_to_run, &sprints_data.sprints, jobs).await?;
 
         // Update original sprints with results
         for (i, &idx) in sprint_indices.iter().enumerate() {
@@ -705,6 +824,14 @@ Only fix what's broken - preserve all existing content."#,
                         sprints_to_run[i].id
                     );
                 }
+                Err(AutoFlowError::MissingDependency { missing, .. }) => {
+                    println!(
+                        "{} Sprint {} not started: sprint {} didn't finish",
+                        "⏭️ ".yellow(),
+                        sprints_to_run[i].id,
+                        missing
+                    );
+                }
                 Err(e) => {
                     println!(
                         "{} Sprint {} failed: {}",
@@ -716,9 +843,15 @@ Only fix what's broken - preserve all existing content."#,
             }
         }
 
-        // Save progress after parallel execution
-        sprints_data.save(sprints_path)
-            .context("Failed to save sprint progress")?;
+        for sprint in &sprints_to_run {
+            record_sprint_outcome(sprint);
+        }
+
+        // Save each sprint's final state into the plan on disk, keeping
+        // changes other orchestrators made to it meanwhile
+        for sprint in &sprints_to_run {
+            sprints_data = save_sprint(sprints_path, sprint)?;
+        }
     } else {
         // Run sequentially - keep running until no more runnable sprints
         println!("\n{}", "Mode: Sequential execution (continuous)".bright_green());
@@ -735,15 +868,23 @@ Only fix what's broken - preserve all existing content."#,
         if !indices_to_run.is_empty() {
             indices_to_run.sort_by_key(|&idx| {
                 let sprint = &sprints_data.sprints[idx];
-                let is_in_progress = sprint.status != SprintStatus::Pending && sprint.status != SprintStatus::Done;
+                let is_in_progress = sprint.status != SprintStatus::Pending && sprint.status.is_runnable();
                 let is_critical = sprint.must_complete_first;
                 (!is_in_progress, !is_critical, sprint.id)
             });
         }
 
         if !indices_to_run.is_empty() {
-            // Run specific sprint(s) first
-            for idx in indices_to_run {
+            // Run specific sprint(s) first, by id: the plan is reloaded between them
+            let ids: Vec<u32> = indices_to_run.iter().map(|&idx| sprints_data.sprints[idx].id).collect();
+            for id in ids {
+                if resources_exhausted() {
+                    write_run_summary(&sprints_data, true);
+                    return Ok(());
+                }
+                let Some(idx) = sprints_data.sprints.iter().position(|s| s.id == id) else {
+                    continue;
+                };
                 let sprint = &mut sprints_data.sprints[idx];
 
                 println!(
@@ -762,6 +903,12 @@ Only fix what's broken - preserve all existing content."#,
                             sprint_id
                         );
                     }
+                    Err(e @ AutoFlowError::BudgetExceeded { .. }) => {
+                        println!("{} {}", "⏸".yellow(), e);
+                        sprints_data = save_sprint(sprints_path, &sprints_data.sprints[idx])?;
+                        write_run_summary(&sprints_data, true);
+                        return Ok(());
+                    }
                     Err(e) => {
                         println!(
                             "{} Sprint {} failed: {}",
@@ -781,9 +928,10 @@ Only fix what's broken - preserve all existing content."#,
                     }
                 }
 
+                record_sprint_outcome(&sprints_data.sprints[idx]);
+
                 // Save progress after each sprint
-                sprints_data.save(sprints_path)
-                    .context("Failed to save sprint progress")?;
+                sprints_data = save_sprint(sprints_path, &sprints_data.sprints[idx])?;
             }
 
             // After running specific sprints, continue in continuous mode if no --sprint flag was provided
@@ -792,70 +940,27 @@ Only fix what's broken - preserve all existing content."#,
                 println!("\n{}", "Transitioning to continuous mode...".bright_green());
             } else {
                 // Specific sprint(s) requested via --sprint flag, exit after completing them
+                write_run_summary(&sprints_data, false);
                 return Ok(());
             }
         }
 
         // Continuous mode loop
+        if schedule == SchedulingPolicy::Weighted {
+            println!("{}", "Scheduling: weighted priority (critical sprints preempt)".bright_black());
+        }
         loop {
-                // Re-evaluate runnable sprints after each completion
-                let runnable: Vec<usize> = sprints_data
-                .sprints
-                .iter()
-                .enumerate()
-                .filter(|(_, s)| {
-                    // Must be runnable status (BLOCKED is runnable now - blocker-resolver will handle it)
-                    let is_runnable_status = s.status != SprintStatus::Done;
-
-                    if !is_runnable_status {
-                        return false;
-                    }
-
-                    // Check if any must_complete_first sprint is not done
-                    // If so, only allow that sprint to run (blocks all others)
-                    let has_incomplete_critical = sprints_data
-                        .sprints
-                        .iter()
-                        .any(|other| other.must_complete_first && other.status != SprintStatus::Done);
-
-                    if has_incomplete_critical && !s.must_complete_first {
-                        return false; // Block non-critical sprints when critical sprint is incomplete
-                    }
-
-                    // Check if all dependencies are satisfied
-                    let dependencies_satisfied = s.dependencies.iter().all(|dep_id| {
-                        sprints_data
-                            .sprints
-                            .iter()
-                            .find(|other| other.id.to_string() == *dep_id)
-                            .map(|dep| dep.status == SprintStatus::Done)
-                            .unwrap_or(true)
-                    });
-
-                    dependencies_satisfied
-                })
-                .map(|(idx, _)| idx)
-                .collect();
-
-            if runnable.is_empty() {
+            // Re-evaluate runnable sprints after each completion
+            let Some(idx) = next_sprint(&sprints_data.sprints, schedule, chrono::Utc::now()) else {
                 println!("\n{}", "No more runnable sprints.".yellow());
                 break;
-            }
+            };
 
-            // Select sprint with priority:
-            // 1. In-progress sprints (finish what we started)
-            // 2. must_complete_first sprints (critical foundation work)
-            // 3. Lowest ID (maintain sequential order)
-            let idx = *runnable.iter().min_by_key(|&&i| {
-                let sprint = &sprints_data.sprints[i];
-                let is_in_progress = sprint.status != SprintStatus::Pending && sprint.status != SprintStatus::Done;
-                let is_critical = sprint.must_complete_first;
+            if resources_exhausted() {
+                paused = true;
+                break;
+            }
 
-                // Priority tuple: (not in_progress, not critical, id)
-                // Lower values are selected first, so we negate booleans to prioritize true values
-                // false=0 true=1, so !true=0 (selected first), !false=1 (selected last)
-                (!is_in_progress, !is_critical, sprint.id)
-            }).unwrap();
             let sprint = &mut sprints_data.sprints[idx];
 
             println!(
@@ -874,6 +979,29 @@ Only fix what's broken - preserve all existing content."#,
                         sprint_id
                     );
                 }
+                Err(AutoFlowError::SprintPreempted { by, .. }) => {
+                    println!(
+                        "{} Sprint {} paused at phase {:?}: critical sprint {} is runnable",
+                        "⏸".yellow(),
+                        sprint_id,
+                        sprint.status,
+                        by
+                    );
+
+                    // Pick up the sprint that preempted us (and any other plan edits)
+                    sprints_data = save_sprint(sprints_path, &sprints_data.sprints[idx])?;
+                    continue;
+                }
+                Err(
+                    e @ (AutoFlowError::BudgetExceeded { .. }
+                    | AutoFlowError::DeploymentNotApproved(_)
+                    | AutoFlowError::ApplyNotApproved(_)),
+                ) => {
+                    println!("{} {}", "⏸".yellow(), e);
+                    sprints_data = save_sprint(sprints_path, &sprints_data.sprints[idx])?;
+                    paused = true;
+                    break;
+                }
                 Err(e) => {
                     println!(
                         "{} Sprint {} failed: {}",
@@ -884,26 +1012,27 @@ Only fix what's broken - preserve all existing content."#,
 
                     // If sprint is blocked, stop execution
                     if sprint.status == SprintStatus::Blocked {
+                        record_sprint_outcome(sprint);
                         println!(
                             "{} Sprint {} is blocked, stopping execution",
                             "⚠️".yellow(),
                             sprint_id
                         );
+                        sprints_data = save_sprint(sprints_path, &sprints_data.sprints[idx])?;
                         break;
                     }
                 }
             }
 
+            record_sprint_outcome(&sprints_data.sprints[idx]);
+
             // Save progress after each sprint
-            sprints_data.save(sprints_path)
-                .context("Failed to save sprint progress")?;
+            sprints_data = save_sprint(sprints_path, &sprints_data.sprints[idx])?;
         }
     }
 
-    // Save updated sprints
-    println!("\n{}", "Saving progress...".bright_cyan());
-    sprints_data.save(sprints_path)
-        .context("Failed to save SPRINTS.yml")?;
+    // Each sprint was saved as it finished; show the plan as it is on disk now
+    refresh_board(&sprints_data);
 
     // Display summary
     println!("\n{}", "Summary".bright_cyan().bold());
@@ -922,10 +1051,11 @@ Only fix what's broken - preserve all existing content."#,
     let in_progress = sprints_data
         .sprints
         .iter()
-        .filter(|s| s.status != SprintStatus::Done
+        .filter(|s| s.status.is_runnable()
                  && s.status != SprintStatus::Blocked
                  && s.status != SprintStatus::Pending)
         .count();
+    let count = |status: SprintStatus| sprints_data.sprints.iter().filter(|s| s.status == status).count();
 
     println!("{}: {}/{}", "Completed".green(), done, sprints_data.sprints.len());
     if in_progress > 0 {
@@ -934,8 +1064,219 @@ Only fix what's broken - preserve all existing content."#,
     if blocked > 0 {
         println!("{}: {}", "Blocked".red(), blocked);
     }
+    for (label, status) in [("Paused", SprintStatus::Paused), ("Skipped", SprintStatus::Skipped), ("Cancelled", SprintStatus::Cancelled)] {
+        if count(status) > 0 {
+            println!("{}: {}", label.bright_black(), count(status));
+        }
+    }
+
+    let sync_docs = sync_docs
+        || autoflow_data::Config::global().map(|c| c.defaults.docs_sync).unwrap_or(false);
+    if sync_docs && done > 0 {
+        sync_documentation(&sprints_data).await;
+    }
 
+    write_run_summary(&sprints_data, paused);
     println!("\n{}", "✨ AutoFlow session complete!".bright_green().bold());
+    println!("{} {}", "Run:".bright_black(), run_id.dimmed());
+
+    Ok(())
+}
+
+/// Run the docs-sync phase; failures are reported but never fail the run
+async fn sync_documentation(sprints_data: &SprintsYaml) {
+    println!("\n{}", "📝 Syncing documentation with the built code...".bright_cyan());
+    let project_path = match std::env::current_dir() {
+        Ok(path) => path,
+        Err(_) => return,
+    };
+    match autoflow_core::run_docs_sync(&project_path, sprints_data).await {
+        Ok(Some(result)) if result.success => {
+            println!("  {} Documentation updated", "✓".green());
+        }
+        Ok(Some(result)) => {
+            println!("  {} Docs sync failed: {}", "⚠".yellow(), result.error.unwrap_or_default());
+        }
+        Ok(None) => println!("  {} Docs already up to date", "ℹ".blue()),
+        Err(e) => println!("  {} Docs sync failed: {}", "⚠".yellow(), e),
+    }
+}
 
+/// `--dry-run`: list the sprints a run would execute and what they are likely to cost
+fn show_dry_run(sprint: Option<u32>) -> anyhow::Result<()> {
+    let sprints_data = SprintsYaml::load(".autoflow/SPRINTS.yml")
+        .context("Dry run needs .autoflow/SPRINTS.yml; run `autoflow init` or `autoflow start` first")?;
+    let sprints: Vec<&autoflow_data::Sprint> = match sprint {
+        Some(id) => vec![sprints_data
+            .sprints
+            .iter()
+            .find(|s| s.id == id)
+            .context(format!("Sprint {} not found", id))?],
+        None => sprints_data.sprints.iter().filter(|s| s.status.is_runnable()).collect(),
+    };
+
+    println!("\n{}", "Dry run - nothing will be executed".bright_cyan().bold());
+    if sprints.is_empty() {
+        println!("{} No sprints left to run", "✅".green());
+        return Ok(());
+    }
+    for s in &sprints {
+        println!(
+            "  Sprint {}: {} {}",
+            s.id.to_string().bright_blue(),
+            s.goal,
+            format!("[{:?}, {:?}, {} tasks]", s.workflow_type, s.status, s.tasks.len()).dimmed()
+        );
+    }
+
+    let budget = active_policy()?.policy.max_run_tokens;
+    print_forecast(&forecast_remaining(&sprints), budget);
     Ok(())
 }
+
+/// Warn about sprints sent back to phases they already finished; refuse unless forced
+fn check_phase_regressions(sprints: &[&autoflow_data::Sprint], force_redo: bool) -> anyhow::Result<()> {
+    let events = read_run_events(RUN_EVENTS_PATH, None).unwrap_or_default();
+    let mut regressed = None;
+    for sprint in sprints.iter().filter(|s| s.status.is_runnable()) {
+        let redo = phases_to_redo(sprint, &events);
+        if redo.is_empty() {
+            continue;
+        }
+        let phases: Vec<String> = redo.iter().map(|p| format!("{:?}", p)).collect();
+        println!(
+            "  {} Sprint {} is at {:?}, but it already completed: {}",
+            "⚠".yellow(),
+            sprint.id,
+            sprint.status,
+            phases.join(", ")
+        );
+        if force_redo {
+            record_run_event("reset", Some(sprint.id), &format!("{:?}", sprint.status));
+        } else {
+            regressed.get_or_insert((sprint.id, sprint.status));
+        }
+    }
+
+    match regressed {
+        Some((sprint, phase)) => {
+            Err(AutoFlowError::PhaseAlreadyCompleted { sprint, phase: format!("{:?}", phase) }.into())
+        }
+        None => Ok(()),
+    }
+}
+
+fn forecast_remaining(sprints: &[&autoflow_data::Sprint]) -> RunForecast {
+    let events = read_run_events(RUN_EVENTS_PATH, None).unwrap_or_default();
+    forecast_run(sprints, docs_size(Path::new(".")), &AgentUsage::from_events(&events))
+}
+
+fn print_forecast(forecast: &RunForecast, budget: u64) {
+    if forecast.agent_calls == 0 {
+        return;
+    }
+    let (cost, high_cost) = forecast.cost_usd();
+    println!(
+        "\n{} ~{} output tokens (range {}-{}) over {} agent call(s) in {} sprint(s)",
+        "📊 Forecast:".bright_cyan(),
+        forecast.expected_tokens.to_string().bright_white(),
+        forecast.low_tokens,
+        forecast.high_tokens,
+        forecast.agent_calls,
+        forecast.sprints
+    );
+    println!(
+        "   ~{} context tokens read, est. ${:.2} (up to ${:.2}); {}/{} phases from run history",
+        forecast.input_tokens, cost, high_cost, forecast.from_history, forecast.agent_calls
+    );
+    if forecast.exceeds(budget) {
+        println!(
+            "{} Forecast exceeds the token budget of {} - consider trimming the plan or running fewer sprints",
+            "⚠".yellow(),
+            budget
+        );
+    }
+}
+
+/// Write `.autoflow/runs/<run-id>/summary.json` for this run
+fn write_run_summary(sprints_data: &SprintsYaml, paused: bool) {
+    let sprints = &sprints_data.sprints;
+    let outcome = if paused {
+        "paused"
+    } else if sprints.iter().any(|s| s.status == SprintStatus::Blocked) {
+        "blocked"
+    } else if sprints.iter().all(|s| s.status.is_closed()) {
+        "completed"
+    } else {
+        "incomplete"
+    };
+    if let Some(path) = finish_run(outcome, sprints) {
+        println!("{} {}", "📄 Run summary:".bright_black(), path.display().to_string().dimmed());
+    }
+}
+
+/// Between-sprint resource check; progress is already saved, so stopping here is clean
+fn resources_exhausted() -> bool {
+    let shortages = check_resources(Path::new("."));
+    if shortages.is_empty() {
+        return false;
+    }
+    println!("\n{}", "⏸  Pausing run: resources are running low".yellow().bold());
+    for shortage in &shortages {
+        println!("  {} {}", "⚠".yellow(), shortage);
+    }
+    println!("  Free some space, then run {} to resume", "autoflow start".bright_blue());
+    let detail = shortages.iter().map(|s| s.to_string()).collect::<Vec<_>>().join("; ");
+    record_run_event("paused", None, &detail);
+    true
+}
+
+/// Report a sprint's final status to telemetry (no-op unless opted in)
+fn record_sprint_outcome(sprint: &autoflow_data::Sprint) {
+    autoflow_utils::record_event(autoflow_utils::TelemetryEvent::SprintOutcome {
+        workflow_type: format!("{:?}", sprint.workflow_type),
+        status: format!("{:?}", sprint.status),
+    });
+}
+
+/// Save callback writing one sprint's progress into SPRINTS.yml
+///
+/// Goes through the plan's lock, so sprints running in parallel (and other
+/// orchestrators sharing the file) don't overwrite each other's progress.
+fn save_sprint_progress(sprints_path: &str) -> impl Fn(&autoflow_data::Sprint) -> autoflow_data::Result<()> + Send + Sync + 'static {
+    let sprints_path = sprints_path.to_string();
+    move |updated_sprint| match SprintsYaml::update_sprint(&sprints_path, updated_sprint) {
+        Ok(Some(data)) => {
+            refresh_board(&data);
+            Ok(())
+        }
+        Ok(None) => Ok(()),
+        // Non-fatal: an unreadable plan (mid-edit, say) is logged rather than failing the sprint
+        Err(e @ AutoFlowError::SprintsParseError(_)) => {
+            tracing::warn!("Failed to save sprint progress: {}", e);
+            Ok(())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Save `sprint` into the plan on disk and return the plan as saved
+///
+/// Only this sprint is written, so edits others made to the plan meanwhile
+/// (new or unblocked sprints, other orchestrators' progress) are kept.
+fn save_sprint(sprints_path: &str, sprint: &autoflow_data::Sprint) -> anyhow::Result<SprintsYaml> {
+    match SprintsYaml::update_sprint(sprints_path, sprint)
+        .with_context(|| format!("Failed to save sprint {} progress", sprint.id))?
+    {
+        Some(data) => Ok(data),
+        // Removed from the plan while it ran
+        None => SprintsYaml::load(sprints_path).context("Failed to reload SPRINTS.yml"),
+    }
+}
+
+/// Regenerate .autoflow/BOARD.md; a stale board is not worth failing a run over
+fn refresh_board(sprints_data: &SprintsYaml) {
+    if let Err(e) = autoflow_data::write_board(sprints_data, autoflow_data::BOARD_PATH) {
+        tracing::warn!("Failed to update BOARD.md: {}", e);
+    }
+}
@@ -1,8 +1,16 @@
-use autoflow_data::{SprintsYaml, SprintStatus};
+use autoflow_data::{SprintsYaml, SprintStatus, Task, TaskStatus};
+use autoflow_utils::{read_run_events, RunSummary, RuntimeStatus, RUNTIME_STATUS_PATH, RUN_EVENTS_PATH};
 use colored::*;
 use std::path::Path;
 
-pub async fn run(json: bool) -> anyhow::Result<()> {
+pub async fn run(json: bool, run_id: Option<String>, last_run: bool) -> anyhow::Result<()> {
+    if let Some(id) = run_id {
+        return show_run(&id, json);
+    }
+    if last_run {
+        return show_last_run(json);
+    }
+
     // Check if initialized
     if !Path::new(".autoflow/SPRINTS.yml").exists() {
         println!("{}", "📊 AutoFlow Status".bright_cyan().bold());
@@ -33,6 +41,19 @@ pub async fn run(json: bool) -> anyhow::Result<()> {
     }
 
     println!("{}: {}", "Last Updated".bright_white().bold(), sprints.project.last_updated.format("%Y-%m-%d %H:%M:%S"));
+    // A run in progress knows more than SPRINTS.yml, which lags a phase behind
+    let live = RuntimeStatus::load(RUNTIME_STATUS_PATH).filter(|s| s.is_fresh());
+    if let Some(ref live) = live {
+        show_live(live);
+    } else if let Some(last) = read_run_events(RUN_EVENTS_PATH, None)?.into_iter().rev().find(|e| e.event == "started") {
+        println!(
+            "{}: {} ({}, {})",
+            "Last Run".bright_white().bold(),
+            last.run_id,
+            last.detail,
+            last.timestamp.format("%Y-%m-%d %H:%M:%S")
+        );
+    }
 
     if sprints.sprints.is_empty() {
         println!("\n{}", "No sprints defined yet.".yellow());
@@ -48,11 +69,14 @@ pub async fn run(json: bool) -> anyhow::Result<()> {
 
     for sprint in &sprints.sprints {
         let status_str = format!("{:?}", sprint.status);
-        let status_colored = match sprint.status {
-            SprintStatus::Done => status_str.green(),
-            SprintStatus::Blocked => status_str.red(),
-            SprintStatus::Pending => status_str.yellow(),
-            _ => status_str.bright_blue(),
+        let live_phase = live.as_ref().and_then(|l| l.sprints.get(&sprint.id)).filter(|p| **p != status_str);
+        let status_colored = match (live_phase, sprint.status) {
+            (Some(phase), _) => format!("{} (live)", phase).bright_blue(),
+            (None, SprintStatus::Done) => status_str.green(),
+            (None, SprintStatus::Blocked) => status_str.red(),
+            (None, SprintStatus::Pending) => status_str.yellow(),
+            (None, SprintStatus::Paused | SprintStatus::Skipped | SprintStatus::Cancelled) => status_str.bright_black(),
+            (None, _) => status_str.bright_blue(),
         };
 
         println!(
@@ -69,10 +93,30 @@ pub async fn run(json: bool) -> anyhow::Result<()> {
             sprint.total_effort,
             "│".bright_black(),
             "Tasks:".bright_black(),
-            sprint.tasks.len(),
+            task_progress(&sprint.tasks),
             "│".bright_black(),
         );
 
+        // Where each task stands while the sprint is being worked on
+        if sprint.status.is_runnable() && sprint.status != SprintStatus::Pending {
+            for task in &sprint.tasks {
+                let mark = match task.status {
+                    TaskStatus::Done | TaskStatus::Tested => "✓".green(),
+                    TaskStatus::Pending => "·".bright_black(),
+                    _ => "◐".bright_blue(),
+                };
+                println!("    {} {} {} {}", mark, task.id.bright_black(), task.title, format!("{:?}", task.status).to_uppercase().dimmed());
+            }
+        }
+
+        if sprint.status == SprintStatus::Blocked && !sprint.failure_reports.is_empty() {
+            println!("  {} {}", "Failures:".bright_black(), sprint.failure_reports.join(", ").red());
+        }
+
+        if let Some(ref repo) = sprint.repo {
+            println!("  {} {}", "Repo:".bright_black(), repo.bright_blue());
+        }
+
         if !sprint.deliverables.is_empty() {
             println!("  {} {}", "Deliverables:".bright_black(), sprint.deliverables.join(", "));
         }
@@ -92,9 +136,16 @@ pub async fn run(json: bool) -> anyhow::Result<()> {
     let total = sprints.sprints.len();
     let done = sprints.sprints.iter().filter(|s| s.status == SprintStatus::Done).count();
     let in_progress = sprints.sprints.iter().filter(|s| {
-        !matches!(s.status, SprintStatus::Done | SprintStatus::Pending | SprintStatus::Blocked)
+        s.status.is_runnable() && !matches!(s.status, SprintStatus::Pending | SprintStatus::Blocked)
     }).count();
     let blocked = sprints.sprints.iter().filter(|s| s.status == SprintStatus::Blocked).count();
+    let held: Vec<String> = [SprintStatus::Paused, SprintStatus::Skipped, SprintStatus::Cancelled]
+        .into_iter()
+        .filter_map(|status| {
+            let count = sprints.sprints.iter().filter(|s| s.status == status).count();
+            (count > 0).then(|| format!("{} {:?}", count, status).to_lowercase())
+        })
+        .collect();
 
     println!("{}", "Summary:".bright_white().bold());
     println!("  {} {}/{}", "Completed:".green(), done, total);
@@ -104,6 +155,188 @@ pub async fn run(json: bool) -> anyhow::Result<()> {
     if blocked > 0 {
         println!("  {} {}", "Blocked:".red(), blocked);
     }
+    if !held.is_empty() {
+        println!("  {} {}", "Not running:".bright_black(), held.join(", "));
+    }
+
+    // Meta-projects: the same counts per repository
+    if !sprints.project.repos.is_empty() {
+        println!("\n{}", "Repositories:".bright_white().bold());
+        for progress in autoflow_core::repo_progress(&sprints) {
+            let (name, checkout) = match progress.repo {
+                Some(ref name) => {
+                    let spec = sprints.project.repos.iter().find(|r| &r.name == name);
+                    let cloned = spec.is_some_and(|r| Path::new(&r.path).join(".git").exists());
+                    let checkout = spec.map(|r| r.path.clone()).unwrap_or_default();
+                    (name.as_str(), if cloned { checkout.dimmed() } else { format!("{} (not cloned)", checkout).yellow() })
+                }
+                None => ("(coordinator)", ".".dimmed()),
+            };
+            let mut line = format!("  {} {}/{} done", name.bright_blue(), progress.done, progress.total);
+            if progress.in_progress > 0 {
+                line.push_str(&format!(", {} in progress", progress.in_progress));
+            }
+            if progress.blocked > 0 {
+                line.push_str(&format!(", {}", format!("{} blocked", progress.blocked).red()));
+            }
+            println!("{}  {}", line, checkout);
+        }
+    }
+
+    Ok(())
+}
+
+/// `5 (2 tested, 3 committed)`: the task count, then how many have reached each status past PENDING
+fn task_progress(tasks: &[Task]) -> String {
+    let reached: Vec<String> = [TaskStatus::Done, TaskStatus::Tested, TaskStatus::Reviewed, TaskStatus::Committed, TaskStatus::InProgress]
+        .into_iter()
+        .filter_map(|status| {
+            let count = tasks.iter().filter(|t| t.status == status).count();
+            let name = match status {
+                TaskStatus::InProgress => "in progress".to_string(),
+                _ => format!("{:?}", status).to_lowercase(),
+            };
+            (count > 0).then(|| format!("{} {}", count, name))
+        })
+        .collect();
+    if reached.is_empty() {
+        tasks.len().to_string()
+    } else {
+        format!("{} ({})", tasks.len(), reached.join(", "))
+    }
+}
+
+/// The run in progress, from `.autoflow/runtime-status.json`
+fn show_live(live: &RuntimeStatus) {
+    let now = chrono::Utc::now();
+    let ago = |at: chrono::DateTime<chrono::Utc>| {
+        let secs = (now - at).num_seconds().max(0);
+        format!("{}m {}s", secs / 60, secs % 60)
+    };
+    println!(
+        "{}: {} ({}, running {})",
+        "Live Run".bright_white().bold(),
+        live.run_id.bright_green(),
+        live.command,
+        ago(live.started_at)
+    );
+    if let Some(since) = live.waiting_for_network {
+        println!("  {} {}", "⏸".bright_yellow(), format!("Waiting for network ({})", ago(since)).bright_yellow());
+    }
+    for agent in &live.agents {
+        let sprint = agent.sprint.map(|id| format!("Sprint {} ", id)).unwrap_or_default();
+        println!("  {} {}{} for {}", "▶".bright_green(), sprint, agent.agent.bright_blue(), ago(agent.started_at));
+    }
+    println!("  {} {} output tokens", "Tokens:".bright_black(), live.tokens);
+    if let Some(ref last) = live.last_event {
+        println!("  {} {} ({} ago)", "Last event:".bright_black(), last, ago(live.updated_at));
+    }
+}
+
+/// Timeline of one run from the run event stream
+fn show_run(run_id: &str, json: bool) -> anyhow::Result<()> {
+    let events = read_run_events(RUN_EVENTS_PATH, Some(run_id))?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&events)?);
+        return Ok(());
+    }
+
+    println!("{}", format!("📊 AutoFlow Run {}", run_id).bright_cyan().bold());
+    if events.is_empty() {
+        println!("\n{}", format!("No events recorded for run {} in {}", run_id, RUN_EVENTS_PATH).yellow());
+        return Ok(());
+    }
+
+    println!("{}", "─".repeat(80).bright_black());
+    for event in &events {
+        let sprint = event.sprint.map(|id| format!("Sprint {} ", id)).unwrap_or_default();
+        println!(
+            "{} {} {}{}",
+            event.timestamp.format("%Y-%m-%d %H:%M:%S").to_string().bright_black(),
+            format!("{:<12}", event.event).bright_blue(),
+            sprint.bright_white(),
+            event.detail
+        );
+    }
+
+    let run_ids: std::collections::BTreeSet<&str> = events.iter().map(|e| e.run_id.as_str()).collect();
+    if run_ids.len() > 1 {
+        let listing = run_ids.into_iter().collect::<Vec<_>>().join(", ");
+        println!("\n{}", format!("⚠ Prefix matched several runs: {}", listing).yellow());
+    }
+
+    Ok(())
+}
+
+/// Summary written at the end of the most recent run
+fn show_last_run(json: bool) -> anyhow::Result<()> {
+    let Some(summary) = RunSummary::latest(Path::new("."))? else {
+        println!("{}", "No run summaries found in .autoflow/runs/".yellow());
+        return Ok(());
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+        return Ok(());
+    }
+
+    let outcome = match summary.outcome.as_str() {
+        "completed" => summary.outcome.green(),
+        "blocked" | "failed" => summary.outcome.red(),
+        _ => summary.outcome.yellow(),
+    };
+    println!("{}", "📊 Last AutoFlow Run".bright_cyan().bold());
+    println!();
+    println!("{}: {}", "Run".bright_white().bold(), summary.run_id);
+    println!("{}: autoflow {}", "Command".bright_white().bold(), summary.command);
+    println!("{}: {}", "Outcome".bright_white().bold(), outcome);
+    println!(
+        "{}: {} ({}m {}s)",
+        "Started".bright_white().bold(),
+        summary.started_at.format("%Y-%m-%d %H:%M:%S"),
+        summary.duration_secs / 60,
+        summary.duration_secs % 60
+    );
+    println!(
+        "{}: {} phases, {} agent runs, {} output tokens, {} commits",
+        "Work".bright_white().bold(),
+        summary.phases_executed,
+        summary.agents_run,
+        summary.tokens,
+        summary.commits.len()
+    );
+
+    if !summary.sprints.is_empty() {
+        println!("\n{}", "Sprints:".bright_white().bold());
+        for sprint in &summary.sprints {
+            let phases: Vec<&str> = sprint.phases.iter().map(|p| p.status.as_str()).collect();
+            println!(
+                "  {} {} {} - {}",
+                format!("Sprint {}", sprint.id).bright_white().bold(),
+                sprint.final_status.bright_blue(),
+                format!("({}s)", sprint.duration_secs).bright_black(),
+                sprint.goal
+            );
+            if !phases.is_empty() {
+                println!("    {} {}", "Phases:".bright_black(), phases.join(" → "));
+            }
+        }
+    }
+
+    if !summary.errors.is_empty() {
+        println!("\n{}", "Errors:".red().bold());
+        for error in &summary.errors {
+            println!("  {} {}", "✗".red(), error);
+        }
+    }
+
+    if !summary.artifacts.is_empty() {
+        println!("\n{}", "Artifacts:".bright_white().bold());
+        for url in &summary.artifacts {
+            println!("  {}", url);
+        }
+    }
 
     Ok(())
 }
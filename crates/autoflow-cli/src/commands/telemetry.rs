@@ -0,0 +1,61 @@
+use anyhow::Result;
+use autoflow_utils::{
+    disable_telemetry, enable_telemetry, queued_event_count, telemetry_enabled, TelemetrySettings,
+};
+use colored::*;
+
+use crate::TelemetryCommands;
+
+pub async fn run(cmd: TelemetryCommands) -> Result<()> {
+    match cmd {
+        TelemetryCommands::On { endpoint } => {
+            let settings = enable_telemetry(endpoint)?;
+            println!("{} Telemetry enabled - thank you!", "✓".green());
+            if settings.effective_endpoint().is_none() {
+                println!(
+                    "  {} No endpoint configured; events stay queued locally until one is set with {}",
+                    "ℹ".blue(),
+                    "autoflow telemetry on --endpoint <url>".bright_blue()
+                );
+            }
+            print_collected();
+        }
+        TelemetryCommands::Off => {
+            disable_telemetry()?;
+            println!("{} Telemetry disabled and queued events discarded", "✓".green());
+        }
+        TelemetryCommands::Status => {
+            let settings = TelemetrySettings::load();
+            println!("{}", "📊 Telemetry".bright_cyan().bold());
+            println!();
+            let state = if telemetry_enabled() { "enabled".green() } else { "disabled".yellow() };
+            println!("  Status:     {}", state);
+            if settings.enabled && !telemetry_enabled() {
+                println!("              {}", "(overridden by AUTOFLOW_TELEMETRY)".bright_black());
+            }
+            println!(
+                "  Endpoint:   {}",
+                settings.effective_endpoint().unwrap_or_else(|| "(none)".to_string())
+            );
+            if !settings.install_id.is_empty() {
+                println!("  Install ID: {}", settings.install_id);
+            }
+            println!("  Queued:     {}", queued_event_count());
+            print_collected();
+        }
+    }
+
+    Ok(())
+}
+
+fn print_collected() {
+    println!();
+    println!("{}", "What is collected:".bright_cyan());
+    println!("  • Command names (e.g. \"start\", \"worktree create\")");
+    println!("  • Sprint outcomes (workflow type and final status)");
+    println!("  • Error classes (e.g. \"SprintBlocked\"), never messages");
+    println!("  • AutoFlow version, OS and a random install ID");
+    println!();
+    println!("Never collected: code, prompts, file paths, project or branch names.");
+    println!("Details: {}", "docs/TELEMETRY.md".bright_blue());
+}
@@ -37,9 +37,16 @@ fn validate_sprints(fix: bool) -> Result<()> {
 
     // Try to parse as-is
     match SprintsYaml::load(sprints_path) {
-        Ok(_) => {
+        Ok(sprints) => {
             println!("{}", "✅ SPRINTS.yml is valid!".bright_green());
             println!();
+            let mismatches = autoflow_core::workflow_mismatches(&sprints.sprints);
+            for mismatch in &mismatches {
+                println!("{} {}", "⚠".yellow(), mismatch);
+            }
+            if !mismatches.is_empty() {
+                println!();
+            }
             Ok(())
         }
         Err(e) => {
@@ -0,0 +1,495 @@
+use anyhow::Result;
+use autoflow_data::{Sprint, SprintStatus, SprintsYaml};
+use autoflow_utils::{ActiveAgent, RuntimeStatus, RUNTIME_STATUS_PATH};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, LineGauge, List, ListItem, Paragraph, Row, Table};
+use ratatui::Frame;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const LIVE_DIR: &str = ".autoflow/.debug/live";
+const SPRINTS_PATH: &str = ".autoflow/SPRINTS.yml";
+
+/// Live logs untouched for longer than this are left out
+const RECENT: Duration = Duration::from_secs(60 * 60);
+
+/// Tool calls kept for the recent calls pane
+const MAX_TOOL_CALLS: usize = 100;
+
+/// Characters of a tool call's input shown beside its name
+const MAX_INPUT_CHARS: usize = 120;
+
+/// Input fields that say what a tool call is about, most telling first
+const INPUT_SUMMARY_KEYS: &[&str] = &["command", "file_path", "path", "pattern", "url", "query", "description", "prompt"];
+
+pub async fn run(interval: u64) -> Result<()> {
+    if !Path::new(".autoflow").exists() {
+        anyhow::bail!("No .autoflow directory here. Run 'autoflow init' first.");
+    }
+    let interval = Duration::from_millis(interval.max(50));
+    let mut dashboard = Dashboard::default();
+    let mut terminal = ratatui::init();
+    let result = (|| -> Result<()> {
+        loop {
+            dashboard.refresh();
+            terminal.draw(|frame| dashboard.draw(frame))?;
+            if event::poll(interval)? {
+                if let Event::Key(key) = event::read()? {
+                    let ctrl_c = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                    if key.kind == KeyEventKind::Press && (matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) || ctrl_c) {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    })();
+    ratatui::restore();
+    result
+}
+
+/// One finished tool call, as read from an agent's live log
+struct ToolCall {
+    at: chrono::DateTime<chrono::Local>,
+    sprint: Option<u32>,
+    agent: String,
+    tool: String,
+    input: String,
+}
+
+/// An agent's live log, read as it grows
+struct LiveStream {
+    sprint: Option<u32>,
+    agent: String,
+    model: String,
+    offset: u64,
+    segments: usize,
+    /// Bytes after the last newline, kept until their line is complete
+    pending: Vec<u8>,
+    /// Output tokens of the messages that have finished, and of the one streaming
+    finished_tokens: u64,
+    message_tokens: u64,
+    /// The agent's closing count, which replaces the running sum
+    final_tokens: Option<u64>,
+    in_message: bool,
+    tool_calls: u32,
+    /// Tool call whose input is still streaming: its name and the JSON so far
+    tool: Option<(String, String)>,
+    /// Last line of text the agent wrote
+    said: String,
+    modified: SystemTime,
+}
+
+impl LiveStream {
+    fn new(path: &Path, modified: SystemTime) -> Self {
+        let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let sprint = path
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .and_then(|name| name.to_str()?.strip_prefix("sprint-")?.parse().ok());
+        Self {
+            sprint,
+            // `<date>_<time>[_<run id>]_<agent>`; agent names don't contain underscores
+            agent: stem.rsplit('_').next().unwrap_or_default().to_string(),
+            model: String::new(),
+            offset: 0,
+            segments: 0,
+            pending: Vec::new(),
+            finished_tokens: 0,
+            message_tokens: 0,
+            final_tokens: None,
+            in_message: false,
+            tool_calls: 0,
+            tool: None,
+            said: String::new(),
+            modified,
+        }
+    }
+
+    fn output_tokens(&self) -> u64 {
+        self.final_tokens.unwrap_or(self.finished_tokens + self.message_tokens)
+    }
+
+    /// Read whatever was written since the last call, including the rest of a segment the log rolled into
+    fn read_new(&mut self, path: &Path, calls: &mut VecDeque<ToolCall>) {
+        let rolled = autoflow_agents::log_segments(path);
+        if rolled.len() > self.segments {
+            // A compressed segment can't be read from where we were; its events are skipped
+            let finished = autoflow_agents::segment_path(path, self.segments as u32 + 1);
+            self.read_from(&finished, calls);
+            self.pending.clear();
+            self.segments = rolled.len();
+            self.offset = 0;
+        }
+        let len = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        if len < self.offset {
+            self.offset = 0;
+            self.pending.clear();
+        }
+        if len > self.offset {
+            self.offset += self.read_from(path, calls);
+        }
+    }
+
+    /// Take in complete lines from the current offset of `path`; returns the bytes read
+    fn read_from(&mut self, path: &Path, calls: &mut VecDeque<ToolCall>) -> u64 {
+        let Ok(mut file) = fs::File::open(path) else {
+            return 0;
+        };
+        let mut chunk = Vec::new();
+        if file.seek(SeekFrom::Start(self.offset)).is_err() || file.read_to_end(&mut chunk).is_err() {
+            return 0;
+        }
+        self.take_in(&chunk, calls);
+        chunk.len() as u64
+    }
+
+    /// Add `chunk` to what's pending and observe each line it completes
+    ///
+    /// Lines are decoded only once whole, so a character split between two
+    /// reads arrives intact.
+    fn take_in(&mut self, chunk: &[u8], calls: &mut VecDeque<ToolCall>) {
+        self.pending.extend_from_slice(chunk);
+        while let Some(end) = self.pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            if let Ok(event) = serde_json::from_str::<serde_json::Value>(String::from_utf8_lossy(&line).trim_end()) {
+                self.observe(&event, calls);
+            }
+        }
+    }
+
+    fn observe(&mut self, event: &serde_json::Value, calls: &mut VecDeque<ToolCall>) {
+        match event.get("type").and_then(|t| t.as_str()) {
+            Some("message_start") => {
+                if let Some(model) = event.pointer("/message/model").and_then(|m| m.as_str()) {
+                    self.model = model.to_string();
+                }
+                self.finished_tokens += std::mem::take(&mut self.message_tokens);
+                self.in_message = true;
+            }
+            Some("message_delta") => {
+                if let Some(tokens) = event.pointer("/usage/output_tokens").and_then(|t| t.as_u64()) {
+                    // The agent's closing delta comes after the last message has stopped
+                    if !self.in_message {
+                        self.final_tokens = Some(tokens);
+                    } else {
+                        self.message_tokens = tokens;
+                    }
+                }
+            }
+            Some("message_stop") => {
+                self.finished_tokens += std::mem::take(&mut self.message_tokens);
+                self.in_message = false;
+            }
+            Some("content_block_start") if event.pointer("/content_block/type").and_then(|t| t.as_str()) == Some("tool_use") => {
+                let name = event.pointer("/content_block/name").and_then(|n| n.as_str()).unwrap_or("tool");
+                self.tool = Some((name.to_string(), String::new()));
+            }
+            Some("content_block_delta") => {
+                if let Some(json) = event.pointer("/delta/partial_json").and_then(|j| j.as_str()) {
+                    if let Some((_, ref mut input)) = self.tool {
+                        input.push_str(json);
+                    }
+                } else if let Some(text) = event.pointer("/delta/text").and_then(|t| t.as_str()) {
+                    self.said.push_str(text);
+                    if let Some(last) = self.said.lines().rfind(|l| !l.trim().is_empty()) {
+                        self.said = last.trim().to_string();
+                    }
+                }
+            }
+            Some("content_block_stop") => {
+                if let Some((tool, input)) = self.tool.take() {
+                    self.tool_calls += 1;
+                    calls.push_front(ToolCall {
+                        at: chrono::Local::now(),
+                        sprint: self.sprint,
+                        agent: self.agent.clone(),
+                        tool,
+                        input: summarize_input(&input),
+                    });
+                    calls.truncate(MAX_TOOL_CALLS);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The field of a tool call's input that says what it's about, on one line
+fn summarize_input(input: &str) -> String {
+    let value = serde_json::from_str::<serde_json::Value>(input).unwrap_or_default();
+    let summary = INPUT_SUMMARY_KEYS
+        .iter()
+        .find_map(|key| value.get(*key).and_then(|v| v.as_str()))
+        .map(str::to_string)
+        .unwrap_or_else(|| input.to_string());
+    let summary = summary.split_whitespace().collect::<Vec<_>>().join(" ");
+    match summary.char_indices().nth(MAX_INPUT_CHARS) {
+        Some((end, _)) => format!("{}…", &summary[..end]),
+        None => summary,
+    }
+}
+
+#[derive(Default)]
+struct Dashboard {
+    sprints: Option<SprintsYaml>,
+    sprints_modified: Option<SystemTime>,
+    sprints_error: Option<String>,
+    runtime: Option<RuntimeStatus>,
+    streams: HashMap<PathBuf, LiveStream>,
+    calls: VecDeque<ToolCall>,
+}
+
+impl Dashboard {
+    fn refresh(&mut self) {
+        let modified = fs::metadata(SPRINTS_PATH).and_then(|m| m.modified()).ok();
+        if modified != self.sprints_modified {
+            self.sprints_modified = modified;
+            match SprintsYaml::load(SPRINTS_PATH) {
+                Ok(sprints) => {
+                    self.sprints = Some(sprints);
+                    self.sprints_error = None;
+                }
+                // Caught mid-write, or not generated yet: keep showing what we had
+                Err(e) => self.sprints_error = Some(e.to_string()),
+            }
+        }
+        self.runtime = RuntimeStatus::load(RUNTIME_STATUS_PATH).filter(|s| s.is_fresh());
+        self.tail_live_logs();
+    }
+
+    /// Pick up new live logs and read what every recent one has added
+    fn tail_live_logs(&mut self) {
+        let now = SystemTime::now();
+        let mut logs: Vec<(PathBuf, SystemTime)> = walkdir::WalkDir::new(LIVE_DIR)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| {
+                let path = e.path();
+                // Rolled segments are read through the log whose tail is still at the plain path
+                path.is_file() && path.extension().is_some_and(|ext| ext == "jsonl") && !autoflow_agents::is_segment(path)
+            })
+            .filter_map(|e| Some((e.path().to_path_buf(), e.metadata().ok()?.modified().ok()?)))
+            .filter(|(_, modified)| now.duration_since(*modified).unwrap_or_default() < RECENT)
+            .collect();
+        // Oldest first, so recent tool calls come out in the order they were made
+        logs.sort_by_key(|(_, modified)| *modified);
+        self.streams.retain(|path, _| logs.iter().any(|(p, _)| p == path));
+        for (path, modified) in logs {
+            let stream = self.streams.entry(path.clone()).or_insert_with(|| LiveStream::new(&path, modified));
+            stream.modified = modified;
+            stream.read_new(&path, &mut self.calls);
+        }
+    }
+
+    /// The newest live log of an agent the run says is running
+    fn stream_for(&self, agent: &ActiveAgent) -> Option<&LiveStream> {
+        self.streams.values().filter(|s| s.agent == agent.agent && s.sprint == agent.sprint).max_by_key(|s| s.modified)
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let sprint_rows = self.sprints.as_ref().map_or(1, |s| s.sprints.len().max(1)) as u16;
+        let agent_rows = self.runtime.as_ref().map_or(1, |r| r.agents.len().max(1)) as u16;
+        let [header, sprints, agents, calls, footer] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Max(sprint_rows + 2),
+            Constraint::Length(agent_rows + 3),
+            Constraint::Min(5),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        self.draw_header(frame, header);
+        self.draw_sprints(frame, sprints);
+        self.draw_agents(frame, agents);
+        self.draw_calls(frame, calls);
+        frame.render_widget(Paragraph::new(Line::from(" q quit").style(Style::default().fg(Color::DarkGray))), footer);
+    }
+
+    fn draw_header(&self, frame: &mut Frame, area: Rect) {
+        let project = self.sprints.as_ref().map(|s| s.project.name.clone()).unwrap_or_else(|| "AutoFlow".to_string());
+        let mut spans = vec![Span::styled(project, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))];
+        match self.runtime {
+            Some(ref live) => {
+                spans.push(Span::raw(format!("  run {} ({}, running {})", live.run_id, live.command, elapsed(live.started_at))));
+                spans.push(Span::styled(format!("  {} output tokens", live.tokens), Style::default().fg(Color::Green)));
+                if live.waiting_for_network.is_some() {
+                    spans.push(Span::styled("  ⏸ waiting for network", Style::default().fg(Color::Yellow)));
+                }
+            }
+            None => spans.push(Span::styled("  No run in progress", Style::default().fg(Color::DarkGray))),
+        }
+        if let Some(ref error) = self.sprints_error {
+            spans.push(Span::styled(format!("  SPRINTS.yml: {}", error), Style::default().fg(Color::Red)));
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)).block(Block::default().borders(Borders::ALL).title(" autoflow watch ")), area);
+    }
+
+    fn draw_sprints(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL).title(" Sprints ");
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        let Some(ref sprints) = self.sprints else {
+            frame.render_widget(Paragraph::new("No SPRINTS.yml yet"), inner);
+            return;
+        };
+        // Start at the first open sprint when they don't all fit
+        let rows = inner.height as usize;
+        let first_open = sprints.sprints.iter().position(|s| !s.status.is_closed()).unwrap_or(0);
+        let start = first_open.min(sprints.sprints.len().saturating_sub(rows));
+        for (row, sprint) in sprints.sprints.iter().skip(start).take(rows).enumerate() {
+            let live_phase = self.runtime.as_ref().and_then(|r| r.sprints.get(&sprint.id));
+            let (ratio, phase) = phase_progress(sprint, live_phase.map(String::as_str));
+            let color = match phase {
+                SprintStatus::Done => Color::Green,
+                SprintStatus::Blocked => Color::Red,
+                SprintStatus::Pending => Color::Yellow,
+                SprintStatus::Paused | SprintStatus::Skipped | SprintStatus::Cancelled => Color::DarkGray,
+                _ => Color::Blue,
+            };
+            let label = format!("#{:<3} {:<14} {}", sprint.id, format!("{:?}", phase), sprint.goal);
+            let gauge = LineGauge::default()
+                .ratio(ratio)
+                .label(truncate(&label, inner.width as usize / 2))
+                .filled_style(Style::default().fg(color))
+                .unfilled_style(Style::default().fg(Color::DarkGray));
+            frame.render_widget(gauge, Rect { y: inner.y + row as u16, height: 1, ..inner });
+        }
+    }
+
+    fn draw_agents(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL).title(" Running agents ");
+        let agents = self.runtime.as_ref().map(|r| r.agents.as_slice()).unwrap_or_default();
+        if agents.is_empty() {
+            frame.render_widget(Paragraph::new("None").style(Style::default().fg(Color::DarkGray)).block(block), area);
+            return;
+        }
+        let rows = agents.iter().map(|agent| {
+            let stream = self.stream_for(agent);
+            Row::new(vec![
+                agent.sprint.map(|id| format!("#{}", id)).unwrap_or_default(),
+                agent.agent.clone(),
+                stream.map(|s| s.model.clone()).unwrap_or_default(),
+                elapsed(agent.started_at),
+                stream.map(|s| s.output_tokens().to_string()).unwrap_or_default(),
+                stream.map(|s| s.tool_calls.to_string()).unwrap_or_default(),
+                stream.map(|s| s.said.clone()).unwrap_or_default(),
+            ])
+        });
+        let widths = [
+            Constraint::Length(6),
+            Constraint::Length(22),
+            Constraint::Length(20),
+            Constraint::Length(9),
+            Constraint::Length(8),
+            Constraint::Length(6),
+            Constraint::Fill(1),
+        ];
+        let header = Row::new(["Sprint", "Agent", "Model", "Running", "Tokens", "Tools", "Last said"])
+            .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(Table::new(rows, widths).header(header).block(block), area);
+    }
+
+    fn draw_calls(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL).title(" Recent tool calls ");
+        if self.calls.is_empty() {
+            let hint = "None yet (runs started with --no-live write no live logs)";
+            frame.render_widget(Paragraph::new(hint).style(Style::default().fg(Color::DarkGray)).block(block), area);
+            return;
+        }
+        let items: Vec<ListItem> = self
+            .calls
+            .iter()
+            .take(area.height as usize)
+            .map(|call| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(call.at.format("%H:%M:%S ").to_string(), Style::default().fg(Color::DarkGray)),
+                    Span::raw(format!("{:<5}", call.sprint.map(|id| format!("#{}", id)).unwrap_or_default())),
+                    Span::styled(format!("{:<20} ", call.agent), Style::default().fg(Color::Blue)),
+                    Span::styled(format!("{:<10} ", call.tool), Style::default().fg(Color::Cyan)),
+                    Span::raw(call.input.clone()),
+                ]))
+            })
+            .collect();
+        frame.render_widget(List::new(items).block(block), area);
+    }
+}
+
+/// How far through its workflow a sprint is, and the phase it's in
+///
+/// A run's live phase wins over SPRINTS.yml, which lags behind. Fix phases
+/// count as the validation phase they fix, a paused sprint as the phase it
+/// was paused in.
+fn phase_progress(sprint: &Sprint, live_phase: Option<&str>) -> (f64, SprintStatus) {
+    let workflow = autoflow_core::workflow_for(sprint);
+    let phase = live_phase
+        .and_then(|name| workflow.phases.iter().map(|p| p.status).find(|s| format!("{:?}", s) == name))
+        .unwrap_or(sprint.status);
+    if phase.is_closed() {
+        return (1.0, phase);
+    }
+    let steps: Vec<SprintStatus> = workflow.phases.iter().map(|p| p.status).filter(|s| !workflow.is_fix_phase(*s)).collect();
+    let at = match phase {
+        SprintStatus::Paused => sprint.paused_from.unwrap_or(phase),
+        _ => phase,
+    };
+    let at = workflow.get_validation_phase_for_fix(at).map(|p| p.status).unwrap_or(at);
+    let ratio = match steps.iter().position(|s| *s == at) {
+        Some(index) if steps.len() > 1 => index as f64 / (steps.len() - 1) as f64,
+        _ => 0.0,
+    };
+    (ratio, phase)
+}
+
+fn elapsed(since: chrono::DateTime<chrono::Utc>) -> String {
+    let secs = (chrono::Utc::now() - since).num_seconds().max(0);
+    format!("{}m {:02}s", secs / 60, secs % 60)
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream() -> LiveStream {
+        LiveStream::new(Path::new(".autoflow/logs/sprint-3/20250101_120000_reviewer.log"), SystemTime::now())
+    }
+
+    #[test]
+    fn test_partial_lines_wait_for_their_newline() {
+        let mut stream = stream();
+        let mut calls = VecDeque::new();
+        let line = br#"{"type":"message_start","message":{"model":"claude-x"}}"#;
+        stream.take_in(&line[..20], &mut calls);
+        assert_eq!(stream.model, "");
+        stream.take_in(&line[20..], &mut calls);
+        assert_eq!(stream.model, "");
+        stream.take_in(b"\n{\"type\":", &mut calls);
+        assert_eq!((stream.sprint, stream.agent.as_str(), stream.model.as_str()), (Some(3), "reviewer", "claude-x"));
+        assert_eq!(stream.pending, br#"{"type":"#);
+    }
+
+    #[test]
+    fn test_characters_split_between_reads_arrive_intact() {
+        let mut stream = stream();
+        let mut calls = VecDeque::new();
+        let line = "{\"type\":\"content_block_delta\",\"delta\":{\"text\":\"Déjà vu 🚀\"}}\n".as_bytes();
+        // Split inside the four bytes of the rocket
+        let split = line.len() - 6;
+        assert!(std::str::from_utf8(&line[..split]).is_err());
+        stream.take_in(&line[..split], &mut calls);
+        stream.take_in(&line[split..], &mut calls);
+        assert_eq!(stream.said, "Déjà vu 🚀");
+        assert!(stream.pending.is_empty());
+    }
+}
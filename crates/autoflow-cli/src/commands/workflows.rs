@@ -0,0 +1,94 @@
+use anyhow::Result;
+use autoflow_core::{custom_workflows, find_workflow, get_workflow_definition, on_failure, on_success, status_name, workflow_mermaid, WorkflowDefinition, WORKFLOWS_DIR};
+use autoflow_data::WorkflowType;
+use colored::*;
+
+use crate::WorkflowsCommands;
+
+pub async fn run(cmd: WorkflowsCommands) -> Result<()> {
+    match cmd {
+        WorkflowsCommands::List => list(),
+        WorkflowsCommands::Show { workflow, mermaid } => {
+            let definition = find_workflow(&workflow).ok_or_else(|| {
+                anyhow::anyhow!("Unknown workflow '{}': not a workflow type or a name in {}", workflow, WORKFLOWS_DIR)
+            })?;
+            show(&definition, mermaid);
+        }
+    }
+    Ok(())
+}
+
+fn list() {
+    println!("{}", "🔀 Workflows".bright_cyan().bold());
+    println!();
+    let builtins = WorkflowType::ALL.into_iter().map(get_workflow_definition);
+    let custom = custom_workflows().iter().filter(|w| w.name.parse::<WorkflowType>().is_err()).cloned();
+    for workflow in builtins.chain(custom) {
+        let path: Vec<String> = workflow
+            .phases
+            .iter()
+            .filter(|p| !workflow.is_fix_phase(p.status))
+            .map(|p| status_name(p.status))
+            .collect();
+        let origin = if workflow.name.parse::<WorkflowType>().is_err() {
+            format!(" (custom, {} base)", workflow.workflow_type)
+        } else if custom_workflows().iter().any(|w| w.name == workflow.name) {
+            format!(" (from {})", WORKFLOWS_DIR)
+        } else {
+            String::new()
+        };
+        println!("  {}{}", workflow.name.bright_white().bold(), origin.dimmed());
+        println!("    {}", path.join(" → ").dimmed());
+    }
+    println!();
+    println!("Details: {}", "autoflow workflows show <workflow> [--mermaid]".bright_blue());
+}
+
+fn show(workflow: &WorkflowDefinition, mermaid: bool) {
+    println!(
+        "{}",
+        format!("🔀 {} workflow", workflow.name).bright_cyan().bold()
+    );
+    println!();
+    println!("  #   PHASE              AGENT                  TURNS RETRIES  ON SUCCESS         ON FAILURE");
+
+    for (i, phase) in workflow.phases.iter().enumerate() {
+        let agent = match phase.agent {
+            "none" => "-".to_string(),
+            agent => agent.to_string(),
+        };
+        let success = on_success(workflow, phase).map(status_name).unwrap_or_else(|| "-".to_string());
+        let failure = match on_failure(phase) {
+            Some(fix) if fix != phase.status => format!("{}, BLOCKED after {}", status_name(fix), phase.max_retries),
+            Some(_) if phase.max_retries > 1 => format!("retry, BLOCKED after {}", phase.max_retries),
+            Some(_) => "BLOCKED".to_string(),
+            None => "-".to_string(),
+        };
+        let name = status_name(phase.status);
+        let name = if workflow.is_fix_phase(phase.status) {
+            format!("↺ {}", name).yellow()
+        } else {
+            name.normal()
+        };
+        println!(
+            "  {:<3} {:<18} {:<22} {:>5} {:>7}  {:<18} {}",
+            i + 1,
+            name,
+            agent,
+            phase.max_turns,
+            phase.max_retries,
+            success,
+            failure.dimmed()
+        );
+    }
+
+    println!();
+    println!("  {} fix phase: runs when the phase before it fails, then loops back", "↺".yellow());
+
+    if mermaid {
+        println!();
+        println!("```mermaid");
+        print!("{}", workflow_mermaid(workflow));
+        println!("```");
+    }
+}
@@ -1,5 +1,8 @@
 use anyhow::{bail, Context};
-use autoflow_git::WorktreeManager;
+use autoflow_core::publish_sprint_branch;
+use autoflow_data::{Config, SprintsYaml};
+use autoflow_git::{bugfix_worktree_name, parse_age, StaleBranch, WorktreeKind, BUGFIX_PREFIX, WorktreeManager, WorktreeOptions};
+use autoflow_utils::{sanitize_branch_name, Paths};
 use colored::*;
 use std::path::Path;
 
@@ -14,10 +17,21 @@ pub async fn run(cmd: crate::WorktreeCommands) -> anyhow::Result<()> {
 
     match cmd {
         crate::WorktreeCommands::List { r#type } => list_worktrees(&manager, r#type).await,
-        crate::WorktreeCommands::Create { branch } => create_worktree(&manager, &branch).await,
+        crate::WorktreeCommands::Create { branch, bugfix, base, no_fetch, no_track } => {
+            let options = worktree_options(base, !no_fetch, !no_track);
+            match (branch, bugfix) {
+                (_, Some(slug)) => create_bugfix_worktree(&manager, &slug, &options).await,
+                (Some(branch), None) => create_worktree(&manager, &branch, &options).await,
+                (None, None) => bail!("Specify a branch or --bugfix <slug>"),
+            }
+        }
         crate::WorktreeCommands::Merge { branch } => merge_worktree(&manager, &branch).await,
+        crate::WorktreeCommands::Pr { branch, draft } => open_pull_request(&manager, &branch, draft).await,
         crate::WorktreeCommands::Delete { branch, force } => delete_worktree(&manager, &branch, force).await,
-        crate::WorktreeCommands::Prune => prune_worktrees(&manager).await,
+        crate::WorktreeCommands::Prune { merged: false, .. } => prune_worktrees(&manager).await,
+        crate::WorktreeCommands::Prune { merged: true, older_than, dry_run } => {
+            prune_merged(&manager, older_than, dry_run).await
+        }
     }
 }
 
@@ -30,9 +44,11 @@ async fn list_worktrees(manager: &WorktreeManager, filter_type: Option<String>)
     // Filter by type if specified
     if let Some(wtype) = filter_type {
         worktrees.retain(|w| {
+            let kind = WorktreeKind::from_name(&w.name);
             match wtype.as_str() {
-                "sprint" => w.name.starts_with("sprint-"),
-                "bugfix" => w.name.starts_with("bugfix-"),
+                "sprint" => kind == WorktreeKind::Sprint,
+                "bugfix" => kind == WorktreeKind::Bugfix,
+                "other" => kind == WorktreeKind::Other,
                 _ => true,
             }
         });
@@ -57,9 +73,25 @@ async fn list_worktrees(manager: &WorktreeManager, filter_type: Option<String>)
     Ok(())
 }
 
-async fn create_worktree(manager: &WorktreeManager, branch: &str) -> anyhow::Result<()> {
+/// Worktree options from flags, falling back to the global config for the base branch
+pub fn worktree_options(base: Option<String>, fetch: bool, track: bool) -> WorktreeOptions {
+    let mut options = WorktreeOptions {
+        fetch,
+        track,
+        ..Default::default()
+    };
+    if let Some(base) = base.or_else(|| {
+        Config::global().ok().and_then(|c| c.defaults.worktree_base_branch)
+    }) {
+        options.base_branch = base;
+    }
+    options
+}
+
+async fn create_worktree(manager: &WorktreeManager, branch: &str, options: &WorktreeOptions) -> anyhow::Result<()> {
     println!("{}", "🌳 Creating worktree...".bright_cyan().bold());
     println!("Branch: {}", branch.bright_blue());
+    println!("Base: {}", options.base_branch.bright_blue());
 
     // Extract sprint ID from branch name (e.g., "sprint-2" -> 2)
     let sprint_id = if branch.starts_with("sprint-") {
@@ -70,7 +102,7 @@ async fn create_worktree(manager: &WorktreeManager, branch: &str) -> anyhow::Res
         bail!("Branch name must start with 'sprint-' (e.g., 'sprint-2')");
     };
 
-    let worktree = manager.create_worktree(sprint_id, branch)
+    let worktree = manager.create_worktree_with(sprint_id, branch, options)
         .context("Failed to create worktree")?;
 
     // Setup environment
@@ -93,6 +125,34 @@ async fn create_worktree(manager: &WorktreeManager, branch: &str) -> anyhow::Res
     Ok(())
 }
 
+async fn create_bugfix_worktree(manager: &WorktreeManager, slug: &str, options: &WorktreeOptions) -> anyhow::Result<()> {
+    let slug = sanitize_branch_name(slug, 8);
+    println!("{}", "🐛 Creating bugfix worktree...".bright_cyan().bold());
+    println!("Branch: {}", bugfix_worktree_name(&slug).bright_blue());
+    println!("Base: {}", options.base_branch.bright_blue());
+
+    let worktree = manager.create_bugfix_worktree(&slug, options)
+        .context("Failed to create bugfix worktree")?;
+
+    println!("\n{}", "Setting up environment...".bright_cyan());
+    manager.setup_worktree_env(&worktree)
+        .context("Failed to setup worktree environment")?;
+
+    println!("\n{}", "✅ Bugfix worktree created successfully!".green().bold());
+    println!("\n{}", "Worktree Details:".bold());
+    println!("  Name: {}", worktree.name.bright_blue());
+    println!("  Path: {}", worktree.display_path());
+    println!("  Branch: {}", worktree.branch.bright_green());
+    println!("  Port: {}", worktree.port.to_string().bright_yellow());
+
+    println!("\n{}", "Next steps:".bright_cyan());
+    println!("  1. cd {}", worktree.display_path().bright_blue());
+    println!("  2. Reproduce and fix the bug in isolation");
+    println!("  3. Use {} when done", format!("autoflow worktree merge {}", worktree.branch).bright_blue());
+
+    Ok(())
+}
+
 async fn merge_worktree(manager: &WorktreeManager, branch: &str) -> anyhow::Result<()> {
     println!("{}", "🔀 Merging worktree...".bright_cyan().bold());
     println!("Branch: {}", branch.bright_blue());
@@ -108,11 +168,48 @@ async fn merge_worktree(manager: &WorktreeManager, branch: &str) -> anyhow::Resu
     Ok(())
 }
 
+async fn open_pull_request(manager: &WorktreeManager, branch: &str, draft: bool) -> anyhow::Result<()> {
+    println!("{}", "📬 Opening pull request...".bright_cyan().bold());
+    println!("Branch: {}", branch.bright_blue());
+
+    let worktree = manager.list_worktrees()
+        .context("Failed to list worktrees")?
+        .into_iter()
+        .find(|w| w.branch == branch)
+        .with_context(|| format!("No worktree has branch '{}' checked out", branch))?;
+    let sprint_id = branch.strip_prefix("sprint-")
+        .and_then(|id| id.parse::<u32>().ok())
+        .context("Pull requests are opened for sprint branches (e.g., 'sprint-2')")?;
+
+    // The worktree's own plan has the sprint's final state; fall back to this checkout's
+    let plan_path = [worktree.path.join(Paths::SPRINTS_YML), Path::new(Paths::SPRINTS_YML).to_path_buf()]
+        .into_iter()
+        .find(|p| p.exists())
+        .context("No SPRINTS.yml in the worktree or here")?;
+    let plan = SprintsYaml::load_without_validation(&plan_path)
+        .with_context(|| format!("Failed to load {}", plan_path.display()))?;
+    let sprint = plan.sprints.iter().find(|s| s.id == sprint_id)
+        .with_context(|| format!("Sprint {} not found in {}", sprint_id, plan_path.display()))?;
+    if !sprint.is_done() {
+        println!("{} Sprint {} is {:?}, opening a draft", "⚠".yellow(), sprint.id, sprint.status);
+    }
+
+    let mut forge = Config::global().map(|c| c.forge).unwrap_or_default();
+    forge.draft_pull_requests |= draft;
+    let url = publish_sprint_branch(&worktree.path, sprint, branch, &forge)
+        .context("Failed to open pull request")?;
+
+    println!("\n{}", "✅ Pull request open!".green().bold());
+    println!("  {}", url.bright_blue());
+
+    Ok(())
+}
+
 async fn delete_worktree(manager: &WorktreeManager, branch: &str, _force: bool) -> anyhow::Result<()> {
     println!("{}", "🗑️  Deleting worktree...".bright_cyan().bold());
 
     // Extract worktree name from branch
-    let worktree_name = if branch.starts_with("sprint-") {
+    let worktree_name = if branch.starts_with("sprint-") || branch.starts_with(BUGFIX_PREFIX) {
         branch.to_string()
     } else {
         format!("sprint-{}", branch)
@@ -136,3 +233,118 @@ async fn prune_worktrees(manager: &WorktreeManager) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+async fn prune_merged(manager: &WorktreeManager, older_than: Option<String>, dry_run: bool) -> anyhow::Result<()> {
+    let min_age = match older_than {
+        Some(text) => parse_age(&text).with_context(|| format!("Invalid age '{}' (use e.g. 14d, 36h or 2w)", text))?,
+        None => chrono::Duration::days(retention_days() as i64),
+    };
+    let base = worktree_options(None, false, false).base_branch;
+    println!("{}", "🧹 Pruning merged branches...".bright_cyan().bold());
+    println!("Merged into: {}", base.bright_blue());
+    if min_age > chrono::Duration::zero() {
+        println!("Last commit before: {} days ago", min_age.num_days().to_string().bright_blue());
+    }
+
+    let stale = manager
+        .merged_branches(&base, min_age)
+        .context("Failed to find merged branches")?;
+    if stale.is_empty() {
+        println!("\n{}", "Nothing to prune.".green());
+        return Ok(());
+    }
+
+    println!();
+    let reclaimed = remove_stale_branches(manager, &stale, dry_run, true);
+    let verb = if dry_run { "Would reclaim" } else { "Reclaimed" };
+    println!(
+        "\n{} {} {} from {} branch(es)",
+        "✅".green(),
+        verb,
+        format_bytes(reclaimed).bright_green(),
+        stale.len()
+    );
+    Ok(())
+}
+
+/// `[defaults] worktree_retention_days`; 0 keeps merged branches
+fn retention_days() -> u32 {
+    Config::global().map(|c| c.defaults.worktree_retention_days).unwrap_or(0)
+}
+
+/// Remove merged AutoFlow branches past the configured retention (run by `start`)
+///
+/// Best effort: a repository without a base branch or an unremovable
+/// worktree is only logged.
+pub fn apply_retention_policy() {
+    let days = retention_days();
+    if days == 0 || autoflow_data::is_read_only() {
+        return;
+    }
+    let Ok(manager) = WorktreeManager::new(".") else {
+        return;
+    };
+    let base = worktree_options(None, false, false).base_branch;
+    match manager.merged_branches(&base, chrono::Duration::days(days as i64)) {
+        Ok(stale) if !stale.is_empty() => {
+            println!("{}", format!("🧹 Removing merged branches older than {} days", days).bright_black());
+            let reclaimed = remove_stale_branches(&manager, &stale, false, false);
+            println!("   Reclaimed {}", format_bytes(reclaimed));
+        }
+        Ok(_) => {}
+        Err(e) => tracing::debug!("Worktree retention skipped: {}", e),
+    }
+}
+
+/// Remove (or, for a dry run, list) each branch; returns the bytes freed
+///
+/// Without `force`, branches whose worktree has uncommitted changes are skipped.
+fn remove_stale_branches(manager: &WorktreeManager, stale: &[StaleBranch], dry_run: bool, force: bool) -> u64 {
+    let mut reclaimed = 0;
+    for branch in stale {
+        let age = (chrono::Utc::now() - branch.last_commit).num_days();
+        let mut detail = format!("last commit {} days ago", age);
+        if let Some(ref path) = branch.worktree {
+            detail.push_str(&format!(", worktree {} ({})", path.display(), format_bytes(branch.bytes)));
+        }
+        if let Some(port) = branch.port {
+            detail.push_str(&format!(", port {}", port));
+        }
+        if branch.dirty {
+            detail.push_str(", uncommitted changes");
+        }
+
+        if dry_run {
+            println!("  {} {} ({})", "-".bright_black(), branch.branch.bright_blue(), detail.dimmed());
+            reclaimed += branch.bytes;
+            continue;
+        }
+        if branch.dirty && !force {
+            println!("  {} {} ({}; kept)", "-".bright_black(), branch.branch.bright_blue(), detail.dimmed());
+            continue;
+        }
+        match manager.remove_stale_branch(branch, force) {
+            Ok(()) => {
+                println!("  {} {} ({})", "✓".green(), branch.branch.bright_blue(), detail.dimmed());
+                reclaimed += branch.bytes;
+            }
+            Err(e) => println!("  {} {}: {}", "✗".red(), branch.branch, e),
+        }
+    }
+    reclaimed
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
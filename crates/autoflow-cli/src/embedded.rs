@@ -1,9 +1,12 @@
 /// Embedded assets for distribution
 /// This ensures agents, skills, schemas, and templates are available even when
 /// installed via `cargo install` or binary releases without a git repository.
+use crate::cli::{InstallComponent, Shell};
 use anyhow::{Context, Result};
+use clap::CommandFactory;
+use serde::Serialize;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 // Embed all agent files
 macro_rules! embed_agents {
@@ -18,8 +21,11 @@ macro_rules! embed_agents {
 
 const EMBEDDED_AGENTS: &[(&str, &str)] = embed_agents![
     "blocker-resolver",
+    "bug-reproducer",
     "code-implementer",
     "debug-blocker",
+    "deployer",
+    "docs-sync",
     "e2e-fixer",
     "e2e-test-runner",
     "e2e-writer",
@@ -73,89 +79,283 @@ const EMBEDDED_SKILLS: &[(&str, &str)] = embed_skills![
 const EMBEDDED_TEMPLATE_SPRINTS: &str = include_str!("../../../templates/SPRINTS.template.yml");
 const EMBEDDED_TEMPLATE_CLAUDE: &str = include_str!("../../../templates/CLAUDE.template.md");
 
+// Embed the built-in sprint template library
+macro_rules! embed_sprint_templates {
+    ($($name:literal),* $(,)?) => {
+        &[
+            $(
+                ($name, include_str!(concat!("../../../templates/sprint-templates/", $name, ".yml"))),
+            )*
+        ]
+    };
+}
+
+const EMBEDDED_SPRINT_TEMPLATES: &[(&str, &str)] = embed_sprint_templates![
+    "auth-provider",
+    "ci-pipeline",
+    "crud",
+];
+
+// Embed schemas
+const EMBEDDED_SCHEMA_SPRINTS: &str = include_str!("../schemas/sprints.schema.json");
+
+/// Components installed on first run: everything but shell completions
+const STARTUP_COMPONENTS: &[InstallComponent] =
+    &[InstallComponent::Agents, InstallComponent::Skills, InstallComponent::Templates, InstallComponent::Schemas];
+
 /// Extract all embedded assets to their target directories
 /// This is called on first run or when assets are missing/outdated
 pub fn extract_embedded_assets() -> Result<()> {
-    let home = std::env::var("HOME").unwrap_or_else(|_| "/root".to_string());
-
-    extract_agents(&home)?;
-    extract_skills(&home)?;
-    extract_templates(&home)?;
-
+    let home = autoflow_utils::require_home()?;
+    let report = install_components(&home, STARTUP_COMPONENTS, false)?;
+    for file in report.components.iter().flat_map(|c| &c.files) {
+        if let Some(ref error) = file.error {
+            tracing::warn!("{}: {}", file.path.display(), error);
+        }
+    }
     Ok(())
 }
 
-/// Extract agents to ~/.claude/agents/
-fn extract_agents(home: &str) -> Result<()> {
-    let agents_dir = PathBuf::from(home).join(".claude/agents");
-    fs::create_dir_all(&agents_dir)
-        .context("Failed to create agents directory")?;
+/// What happened to one file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileAction {
+    /// Written where there was nothing
+    Installed,
+    /// Overwritten with the built-in copy (`--force`)
+    Updated,
+    /// Already the built-in copy
+    Unchanged,
+    /// Differs from the built-in copy and was left as it is
+    Modified,
+    Missing,
+}
 
-    for (name, content) in EMBEDDED_AGENTS {
-        let dest = agents_dir.join(format!("{}.agent.md", name));
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub action: FileAction,
+    /// SHA-256 of the file as installed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Why the file can't be used, when it can't
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
 
-        // Only write if file doesn't exist or is older than build time
-        let should_write = !dest.exists();
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentReport {
+    pub component: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub files: Vec<FileReport>,
+}
 
-        if should_write {
-            fs::write(&dest, content)
-                .context(format!("Failed to write agent: {}", name))?;
-        }
+/// Outcome of `autoflow install`, one entry per component
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallReport {
+    pub version: String,
+    pub home: PathBuf,
+    /// Checked only, nothing written (`--verify`)
+    pub verify_only: bool,
+    pub components: Vec<ComponentReport>,
+}
+
+impl InstallReport {
+    pub fn ok(&self) -> bool {
+        self.components.iter().all(|c| c.ok)
     }
+}
 
-    Ok(())
+/// How an installed file is checked beyond its hash
+#[derive(Debug, Clone, Copy)]
+enum AssetKind {
+    Agent,
+    Skill,
+    Yaml,
+    Json,
+    Script,
 }
 
-/// Extract skills to ~/.claude/skills/
-fn extract_skills(home: &str) -> Result<()> {
-    let skills_dir = PathBuf::from(home).join(".claude/skills");
-    fs::create_dir_all(&skills_dir)
-        .context("Failed to create skills directory")?;
+/// One file a component installs and its built-in content
+struct Asset {
+    path: PathBuf,
+    content: String,
+    kind: AssetKind,
+}
 
-    for (name, content) in EMBEDDED_SKILLS {
-        let skill_dir = skills_dir.join(name);
-        fs::create_dir_all(&skill_dir)
-            .context(format!("Failed to create skill directory: {}", name))?;
+/// The files `component` installs under `home`; a note when it installs nothing here
+fn component_assets(home: &Path, component: InstallComponent) -> (Vec<Asset>, Option<String>) {
+    let asset = |path: PathBuf, content: &str, kind: AssetKind| Asset { path, content: content.to_string(), kind };
+    let assets = match component {
+        InstallComponent::Agents => EMBEDDED_AGENTS
+            .iter()
+            .map(|(name, content)| asset(home.join(".claude/agents").join(format!("{}.agent.md", name)), content, AssetKind::Agent))
+            .collect(),
+        InstallComponent::Skills => EMBEDDED_SKILLS
+            .iter()
+            .map(|(name, content)| asset(home.join(".claude/skills").join(name).join("SKILL.md"), content, AssetKind::Skill))
+            .collect(),
+        InstallComponent::Templates => {
+            let templates_dir = home.join(".autoflow/templates");
+            let mut assets = vec![
+                asset(templates_dir.join("SPRINTS.template.yml"), EMBEDDED_TEMPLATE_SPRINTS, AssetKind::Yaml),
+                asset(templates_dir.join("CLAUDE.template.md"), EMBEDDED_TEMPLATE_CLAUDE, AssetKind::Script),
+            ];
+            // Sprint templates: user edits and additions are kept
+            for (name, content) in EMBEDDED_SPRINT_TEMPLATES {
+                assets.push(asset(home.join(".autoflow/sprint-templates").join(format!("{}.yml", name)), content, AssetKind::Yaml));
+            }
+            assets
+        }
+        InstallComponent::Schemas => {
+            let schemas_dir = home.join(".autoflow/schemas");
+            vec![
+                asset(schemas_dir.join("sprints.schema.json"), EMBEDDED_SCHEMA_SPRINTS, AssetKind::Json),
+                asset(schemas_dir.join("webhook.v1.schema.json"), autoflow_utils::WEBHOOK_SCHEMA, AssetKind::Json),
+            ]
+        }
+        InstallComponent::Completions => {
+            let shell = std::env::var("SHELL").unwrap_or_default();
+            let Some((shell, path, note)) = completion_target(home, &shell) else {
+                let note = "No bash, zsh or fish in $SHELL; print a script with `autoflow completions <shell>`";
+                return (Vec::new(), Some(note.to_string()));
+            };
+            let mut cmd = crate::Cli::command();
+            let name = cmd.get_name().to_string();
+            let mut script = Vec::new();
+            clap_complete::generate(shell, &mut cmd, name, &mut script);
+            return (vec![asset(path, &String::from_utf8_lossy(&script), AssetKind::Script)], note);
+        }
+    };
+    (assets, None)
+}
 
-        let dest = skill_dir.join("SKILL.md");
+/// Where the completion script for the shell at `shell` goes, and what else it needs
+fn completion_target(home: &Path, shell: &str) -> Option<(Shell, PathBuf, Option<String>)> {
+    match Path::new(shell).file_name()?.to_str()? {
+        "bash" => Some((Shell::Bash, home.join(".local/share/bash-completion/completions/autoflow"), None)),
+        "zsh" => Some((Shell::Zsh, home.join(".zfunc/_autoflow"), Some("Add `fpath+=~/.zfunc` before `compinit` in ~/.zshrc".to_string()))),
+        "fish" => Some((Shell::Fish, home.join(".config/fish/completions/autoflow.fish"), None)),
+        _ => None,
+    }
+}
 
-        // Only write if file doesn't exist
-        if !dest.exists() {
-            fs::write(&dest, content)
-                .context(format!("Failed to write skill: {}", name))?;
+/// Install `components` under `home`, then check every file they own
+///
+/// Files that are missing are written. Files that differ from the built-in
+/// copy are taken to be user edits and kept, unless `force` is set. Running
+/// it again changes nothing.
+pub fn install_components(home: &Path, components: &[InstallComponent], force: bool) -> Result<InstallReport> {
+    let mut reports = Vec::new();
+    for &component in components {
+        let (assets, note) = component_assets(home, component);
+        let mut files = Vec::new();
+        for asset in assets {
+            let existing = fs::read_to_string(&asset.path).ok();
+            let action = match existing {
+                Some(ref content) if *content == asset.content => FileAction::Unchanged,
+                Some(_) if !force => FileAction::Modified,
+                existing => {
+                    if let Some(parent) = asset.path.parent() {
+                        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+                    }
+                    fs::write(&asset.path, &asset.content).with_context(|| format!("Failed to write {}", asset.path.display()))?;
+                    if existing.is_some() {
+                        FileAction::Updated
+                    } else {
+                        FileAction::Installed
+                    }
+                }
+            };
+            files.push(check_asset(&asset, action));
         }
+        reports.push(component_report(component, note, files));
     }
+    Ok(InstallReport { version: env!("CARGO_PKG_VERSION").to_string(), home: home.to_path_buf(), verify_only: false, components: reports })
+}
 
-    Ok(())
+/// Check `components` under `home` without writing anything
+pub fn verify_components(home: &Path, components: &[InstallComponent]) -> InstallReport {
+    let components = components
+        .iter()
+        .map(|&component| {
+            let (assets, note) = component_assets(home, component);
+            let files = assets
+                .iter()
+                .map(|asset| match fs::read_to_string(&asset.path) {
+                    Ok(content) if content == asset.content => check_asset(asset, FileAction::Unchanged),
+                    Ok(_) => check_asset(asset, FileAction::Modified),
+                    Err(_) => FileReport {
+                        path: asset.path.clone(),
+                        action: FileAction::Missing,
+                        sha256: None,
+                        error: Some("not installed".to_string()),
+                    },
+                })
+                .collect();
+            component_report(component, note, files)
+        })
+        .collect();
+    InstallReport { version: env!("CARGO_PKG_VERSION").to_string(), home: home.to_path_buf(), verify_only: true, components }
 }
 
-/// Extract templates to ~/.autoflow/templates/
-fn extract_templates(home: &str) -> Result<()> {
-    let templates_dir = PathBuf::from(home).join(".autoflow/templates");
-    fs::create_dir_all(&templates_dir)
-        .context("Failed to create templates directory")?;
+fn component_report(component: InstallComponent, note: Option<String>, files: Vec<FileReport>) -> ComponentReport {
+    ComponentReport { component: component.name().to_string(), ok: files.iter().all(|f| f.error.is_none()), note, files }
+}
 
-    let sprints_template = templates_dir.join("SPRINTS.template.yml");
-    if !sprints_template.exists() {
-        fs::write(&sprints_template, EMBEDDED_TEMPLATE_SPRINTS)
-            .context("Failed to write SPRINTS.template.yml")?;
-    }
+/// Hash the installed file and make sure it can be used
+fn check_asset(asset: &Asset, action: FileAction) -> FileReport {
+    let (sha256, error) = match fs::read_to_string(&asset.path) {
+        Ok(content) => {
+            let digest = ring::digest::digest(&ring::digest::SHA256, content.as_bytes());
+            let sha256 = digest.as_ref().iter().map(|b| format!("{:02x}", b)).collect::<String>();
+            (Some(sha256), validate_asset(asset, &content).err())
+        }
+        Err(e) => (None, Some(format!("unreadable: {}", e))),
+    };
+    FileReport { path: asset.path.clone(), action, sha256, error }
+}
 
-    let claude_template = templates_dir.join("CLAUDE.template.md");
-    if !claude_template.exists() {
-        fs::write(&claude_template, EMBEDDED_TEMPLATE_CLAUDE)
-            .context("Failed to write CLAUDE.template.md")?;
+/// Whether `content` parses as the kind of file `asset` is
+fn validate_asset(asset: &Asset, content: &str) -> std::result::Result<(), String> {
+    match asset.kind {
+        AssetKind::Agent => {
+            let frontmatter = frontmatter(content).ok_or("no frontmatter")?;
+            if !frontmatter.lines().any(|l| l.starts_with("model:")) {
+                return Err("frontmatter has no model".to_string());
+            }
+            let name = asset.path.file_name().and_then(|n| n.to_str()).unwrap_or_default().trim_end_matches(".agent.md");
+            autoflow_agents::check_protocol(name, autoflow_agents::declared_protocol(content)).map_err(|e| e.to_string())
+        }
+        AssetKind::Skill => {
+            let frontmatter = frontmatter(content).ok_or("no frontmatter")?;
+            for field in ["name:", "description:"] {
+                if !frontmatter.lines().any(|l| l.starts_with(field)) {
+                    return Err(format!("frontmatter has no {}", field.trim_end_matches(':')));
+                }
+            }
+            Ok(())
+        }
+        AssetKind::Yaml => serde_yaml::from_str::<serde_yaml::Value>(content).map(|_| ()).map_err(|e| e.to_string()),
+        AssetKind::Json => serde_json::from_str::<serde_json::Value>(content).map(|_| ()).map_err(|e| e.to_string()),
+        AssetKind::Script => if content.trim().is_empty() { Err("empty".to_string()) } else { Ok(()) },
     }
+}
 
-    Ok(())
+/// The text between a leading `---` line and the next one
+fn frontmatter(content: &str) -> Option<&str> {
+    let rest = content.strip_prefix("---\n")?;
+    rest.find("\n---").map(|end| &rest[..end])
 }
 
 /// Check if embedded assets need to be extracted
 /// Returns true if any required asset is missing
 pub fn needs_extraction() -> bool {
-    let home = match std::env::var("HOME") {
-        Ok(h) => h,
-        Err(_) => return true, // Can't check, so extract to be safe
+    let home = match autoflow_utils::home_dir() {
+        Some(h) => h,
+        None => return false, // Nowhere to extract to
     };
 
     // Check if at least one agent exists
@@ -187,6 +387,15 @@ pub fn needs_extraction() -> bool {
         return true;
     }
 
+    if !PathBuf::from(&home).join(".autoflow/sprint-templates").exists() {
+        return true;
+    }
+
+    // Check schemas
+    if !PathBuf::from(&home).join(".autoflow/schemas/sprints.schema.json").exists() {
+        return true;
+    }
+
     false
 }
 
@@ -209,4 +418,53 @@ mod tests {
         assert!(!EMBEDDED_TEMPLATE_SPRINTS.is_empty(), "SPRINTS template should not be empty");
         assert!(!EMBEDDED_TEMPLATE_CLAUDE.is_empty(), "CLAUDE template should not be empty");
     }
+
+    #[test]
+    fn test_embedded_sprint_templates_parse() {
+        for (name, content) in EMBEDDED_SPRINT_TEMPLATES {
+            let template: autoflow_data::SprintTemplate = serde_yaml::from_str(content)
+                .unwrap_or_else(|e| panic!("Sprint template {} should parse: {}", name, e));
+            assert_eq!(template.name, *name);
+
+            let params = template
+                .params
+                .iter()
+                .filter(|p| p.default.is_none())
+                .map(|p| (p.name.clone(), "Example".to_string()))
+                .collect();
+            template.render(1, &params)
+                .unwrap_or_else(|e| panic!("Sprint template {} should render: {}", name, e));
+        }
+    }
+
+    #[test]
+    fn test_install_is_idempotent_and_verified() {
+        let home = std::env::temp_dir().join(format!("autoflow-install-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        let components = [InstallComponent::Agents, InstallComponent::Skills, InstallComponent::Templates, InstallComponent::Schemas];
+
+        let report = install_components(&home, &components, false).unwrap();
+        assert!(report.ok(), "Built-in assets should verify: {:?}", report);
+        let files = report.components.iter().flat_map(|c| &c.files);
+        assert!(files.clone().all(|f| f.action == FileAction::Installed && f.sha256.as_ref().is_some_and(|h| h.len() == 64)));
+
+        let report = install_components(&home, &components, false).unwrap();
+        assert!(report.components.iter().flat_map(|c| &c.files).all(|f| f.action == FileAction::Unchanged));
+
+        // A local edit is kept and reported, and a broken schema fails verification
+        let schema = home.join(".autoflow/schemas/sprints.schema.json");
+        fs::write(&schema, "{ not json").unwrap();
+        let report = verify_components(&home, &[InstallComponent::Schemas]);
+        assert!(!report.ok());
+        assert_eq!(report.components[0].files[0].action, FileAction::Modified);
+        let report = install_components(&home, &[InstallComponent::Schemas], false).unwrap();
+        assert_eq!(report.components[0].files[0].action, FileAction::Modified);
+        let report = install_components(&home, &[InstallComponent::Schemas], true).unwrap();
+        assert_eq!(report.components[0].files[0].action, FileAction::Updated);
+        assert!(report.ok());
+
+        fs::remove_file(&schema).unwrap();
+        assert_eq!(verify_components(&home, &[InstallComponent::Schemas]).components[0].files[0].action, FileAction::Missing);
+        let _ = fs::remove_dir_all(&home);
+    }
 }
@@ -1,273 +1,34 @@
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches};
+use colored::*;
 
+mod cli;
 mod commands;
 mod embedded;
 mod sync;
 mod update;
 
-#[derive(Parser)]
-#[command(name = "autoflow")]
-#[command(version = env!("CARGO_PKG_VERSION"))]
-#[command(about = "Best-in-class autonomous coding agent", long_about = None)]
-struct Cli {
-    #[command(subcommand)]
-    command: Commands,
-
-    /// Enable verbose logging
-    #[arg(short, long, global = true)]
-    verbose: bool,
-}
-
-#[derive(Subcommand)]
-enum Commands {
-    /// Install AutoFlow globally to ~/.autoflow/
-    Install {
-        /// Force reinstall even if already installed
-        #[arg(long)]
-        force: bool,
-    },
-
-    /// Create new project from IDEA.md (full autonomous setup)
-    Create {
-        /// Project name (optional, uses current directory if IDEA.md exists)
-        #[arg(value_name = "NAME", required = false)]
-        name: Option<String>,
-
-        /// Path to IDEA.md file (optional, uses ./IDEA.md or creates template if not provided)
-        #[arg(short, long)]
-        idea: Option<String>,
-    },
-
-    /// Initialize new project with AutoFlow
-    Init {
-        /// Use specific template (e.g., "react-node", "laravel-react")
-        #[arg(short, long)]
-        template: Option<String>,
-    },
-
-    /// Start autonomous development (execute sprints)
-    Start {
-        /// Execute sprints in parallel
-        #[arg(short, long)]
-        parallel: bool,
-
-        /// Run specific sprint by ID
-        #[arg(short, long)]
-        sprint: Option<u32>,
-
-        /// Disable live streaming logs (enabled by default)
-        #[arg(long)]
-        no_live: bool,
-    },
-
-    /// Show sprint progress and status
-    Status {
-        /// Output as JSON
-        #[arg(short, long)]
-        json: bool,
-    },
-
-    /// Analyze existing codebase
-    Analyze,
-
-    /// Add new feature to existing codebase
-    Add {
-        /// Feature description
-        description: String,
-
-        /// Additional requirements
-        #[arg(short, long)]
-        requirements: Option<String>,
-    },
-
-    /// Fix a bug
-    Fix {
-        /// Bug description
-        description: String,
-
-        /// Auto-implement fix after investigation
-        #[arg(long)]
-        auto_fix: bool,
-
-        /// Launch Playwright in headed mode
-        #[arg(long)]
-        playwright_headed: bool,
-    },
-
-    /// Update documentation and regenerate sprints based on feedback
-    Pivot {
-        /// Feedback/instruction for updating documentation
-        instruction: String,
-    },
-
-    /// Rollback sprint
-    Rollback {
-        /// Sprint ID to rollback (default: last sprint)
-        #[arg(short, long)]
-        sprint: Option<u32>,
-    },
-
-    /// Manage git worktrees
-    #[command(subcommand)]
-    Worktree(WorktreeCommands),
-
-    /// Validate project (quality gates, infrastructure, etc.)
-    Validate {
-        /// Validate infrastructure
-        #[arg(long)]
-        infrastructure: bool,
-
-        /// Validate integration
-        #[arg(long)]
-        integration: bool,
-
-        /// Auto-fix issues where possible
-        #[arg(long)]
-        fix: bool,
-    },
-
-    /// Manage sprints
-    #[command(subcommand)]
-    Sprints(SprintsCommands),
-
-    /// List available agents
-    Agents {
-        /// Show detailed information
-        #[arg(short, long)]
-        detailed: bool,
-    },
-
-    /// List available skills
-    Skills,
-
-    /// Manage development environment
-    #[command(subcommand)]
-    Env(EnvCommands),
-
-    /// Manage MCP servers
-    #[command(subcommand)]
-    Mcp(McpCommands),
-
-    /// View agent execution logs
-    Logs {
-        /// Follow log output (tail -f style)
-        #[arg(short, long)]
-        follow: bool,
-
-        /// View live streaming logs (.jsonl format)
-        #[arg(short, long)]
-        live: bool,
-    },
-
-    /// Check for and install updates
-    Update {
-        /// Force update check (ignore 24h interval)
-        #[arg(short, long)]
-        force: bool,
-    },
-}
-
-#[derive(Subcommand, Debug)]
-enum WorktreeCommands {
-    /// List all worktrees
-    List {
-        /// Filter by type (sprint, bugfix, other)
-        #[arg(long)]
-        r#type: Option<String>,
-    },
-
-    /// Create new worktree
-    Create {
-        /// Branch name
-        branch: String,
-    },
-
-    /// Merge worktree to main
-    Merge {
-        /// Branch name
-        branch: String,
-    },
-
-    /// Delete worktree
-    Delete {
-        /// Branch name
-        branch: String,
-
-        /// Force delete even if not merged
-        #[arg(short, long)]
-        force: bool,
-    },
-
-    /// Clean up merged worktrees
-    Prune,
-}
-
-#[derive(Subcommand, Debug)]
-enum SprintsCommands {
-    /// List all sprints
-    List,
-
-    /// Show sprint details
-    Show {
-        /// Sprint ID
-        id: u32,
-
-        /// Show integration points
-        #[arg(long)]
-        integration: bool,
-    },
-
-    /// Create new sprint manually
-    Create,
-}
-
-#[derive(Subcommand, Debug)]
-enum EnvCommands {
-    /// Start development environment
-    Start,
+use cli::*;
 
-    /// Stop development environment
-    Stop,
+fn main() -> anyhow::Result<()> {
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
 
-    /// Restart development environment
-    Restart,
-
-    /// View environment logs
-    Logs {
-        /// Follow logs
-        #[arg(short, long)]
-        follow: bool,
-    },
-
-    /// Check environment health
-    Health,
-}
-
-#[derive(Subcommand, Debug)]
-enum McpCommands {
-    /// Install MCP servers (installs all recommended if none specified)
-    Install {
-        /// Specific servers to install (memory, playwright, github, postgres, etc.)
-        servers: Vec<String>,
-
-        /// Install to project level (.autoflow/settings.json) instead of global
-        #[arg(long)]
-        project: bool,
-    },
-
-    /// List installed MCP servers
-    List,
-
-    /// Show information about available servers
-    Info {
-        /// Server name (optional, shows all if not specified)
-        server: Option<String>,
-    },
-}
+    // Completion scripts go to stdout, so skip startup work that may print
+    if let Commands::Completions { shell } = &cli.command {
+        let shell = *shell;
+        return commands::completions::run(shell);
+    }
 
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    let cli = Cli::parse();
+    // The environment is set up here, before the runtime starts: set_var is
+    // only sound while no other thread can be reading it.
+    // Agents and subcommands resolve the profile from the environment
+    if let Some(ref profile) = cli.profile {
+        std::env::set_var(autoflow_utils::PROFILE_ENV, profile);
+    }
+    // Likewise the home directory override
+    if let Some(ref home) = cli.home {
+        std::env::set_var(autoflow_utils::HOME_ENV, home);
+    }
 
     // Set up logging
     let log_level = if cli.verbose { "debug" } else { "info" };
@@ -276,6 +37,61 @@ async fn main() -> anyhow::Result<()> {
         .with_target(false)
         .init();
 
+    // Settle the project and read-only mode before any startup work writes to disk
+    let entered = enter_project(&cli);
+    let read_only = read_only_requested(&cli, entered.is_ok());
+    if read_only {
+        std::env::set_var(autoflow_data::READ_ONLY_ENV, "1");
+    }
+
+    let command = command_name(&matches);
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(run(cli, command, entered, read_only))
+}
+
+async fn run(cli: Cli, command: String, entered: anyhow::Result<()>, read_only: bool) -> anyhow::Result<()> {
+    if !read_only {
+        startup(&cli).await;
+    }
+
+    autoflow_utils::record_event(autoflow_utils::TelemetryEvent::Command {
+        name: command.clone(),
+    });
+
+    let json_errors = cli
+        .error_format
+        .clone()
+        .or_else(|| std::env::var("AUTOFLOW_ERROR_FORMAT").ok())
+        .is_some_and(|f| f.eq_ignore_ascii_case("json"));
+
+    let result = match entered.and_then(|()| enforce_read_only(&cli.command, &command, read_only)) {
+        Ok(()) => execute(cli).await,
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = &result {
+        autoflow_utils::record_event(autoflow_utils::TelemetryEvent::Error {
+            class: autoflow_utils::error_class(e),
+        });
+    }
+    let _ = autoflow_utils::flush_telemetry().await;
+
+    if let Err(e) = result {
+        let report = autoflow_utils::ErrorReport::from_error(&e);
+        if json_errors {
+            eprintln!("{}", report.render_json());
+        } else {
+            eprintln!("{} {}", "✗".red(), report.render_text());
+        }
+        std::process::exit(report.exit_code());
+    }
+    Ok(())
+}
+
+/// Startup work that writes outside the project: asset extraction, agent sync and the update check
+async fn startup(cli: &Cli) {
     // Extract embedded assets if needed (first run or missing assets)
     if embedded::needs_extraction() {
         if cli.verbose {
@@ -303,25 +119,132 @@ async fn main() -> anyhow::Result<()> {
             }
         }
     }
+}
 
-    // Execute command
+/// Switch to the project root, from `--project` or found by walking up
+///
+/// Setup-style commands only move for an explicit `--project`. Path
+/// arguments were already made absolute by `cli::user_path` while parsing.
+fn enter_project(cli: &Cli) -> anyhow::Result<()> {
+    let discover = !matches!(
+        cli.command,
+        Commands::Setup { .. }
+            | Commands::Doctor
+            | Commands::Install { .. }
+            | Commands::Create { .. }
+            | Commands::Init { .. }
+            | Commands::Agents { command: None, .. }
+            | Commands::Skills
+            | Commands::Update { .. }
+            | Commands::Telemetry(_)
+            | Commands::Completions { .. }
+    );
+    let root = match cli.project {
+        Some(ref path) => Some(autoflow_utils::ProjectRoot::new(path)?),
+        None if discover => std::env::current_dir().ok().and_then(|cwd| autoflow_utils::ProjectRoot::discover(&cwd)),
+        None => None,
+    };
+    let Some(root) = root else {
+        return Ok(());
+    };
+
+    tracing::debug!("Project root: {}", root.path().display());
+    root.enter()?;
+    Ok(())
+}
+
+/// Whether this run is read-only: `--read-only`, or `[policy] read_only`
+///
+/// The policy is resolved in the project, so a profile chosen by branch
+/// applies; it is only consulted once the project has been entered.
+fn read_only_requested(cli: &Cli, in_project: bool) -> bool {
+    cli.read_only || (in_project && autoflow_utils::active_policy().is_ok_and(|active| active.policy.read_only))
+}
+
+/// Refuse commands that would change the project when `read_only` is on
+fn enforce_read_only(cmd: &Commands, command: &str, read_only: bool) -> anyhow::Result<()> {
+    if !read_only {
+        return Ok(());
+    }
+    if !allowed_in_read_only(cmd) {
+        return Err(autoflow_data::AutoFlowError::ReadOnly(format!("`autoflow {}`", command)).into());
+    }
+    eprintln!("{} {}", "🔒".bright_black(), "Read-only mode".bright_yellow());
+    Ok(())
+}
+
+/// Commands that only inspect, and so still run in read-only mode
+fn allowed_in_read_only(cmd: &Commands) -> bool {
+    matches!(
+        cmd,
+        Commands::Analyze
+            | Commands::Status { .. }
+            | Commands::Validate { fix: false, .. }
+            | Commands::Start { dry_run: true, .. }
+            | Commands::Board { stdout: true }
+            | Commands::Doctor
+            | Commands::Install { verify: true, .. }
+            | Commands::Agents { .. }
+            | Commands::Skills
+            | Commands::Workflows(_)
+            | Commands::Runs(_)
+            | Commands::Cost { .. }
+            | Commands::Config(ConfigCommands::Get { .. })
+            | Commands::ExportBundle { .. }
+            | Commands::Debug(DebugCommands::List { .. })
+            | Commands::Env(EnvCommands::Cache { clear: false })
+            | Commands::Env(EnvCommands::Targets)
+            | Commands::Logs { .. }
+            | Commands::Watch { .. }
+            | Commands::Hint { text: None, clear: false, .. }
+            | Commands::Maintain { dry_run: true, .. }
+            | Commands::Patch(PatchCommands::List | PatchCommands::Show { .. })
+    )
+}
+
+/// Subcommand path without arguments, e.g. "worktree create"
+fn command_name(matches: &clap::ArgMatches) -> String {
+    let mut parts = Vec::new();
+    let mut current = matches;
+    while let Some((name, sub)) = current.subcommand() {
+        parts.push(name.to_string());
+        current = sub;
+    }
+    parts.join(" ")
+}
+
+async fn execute(cli: Cli) -> anyhow::Result<()> {
     match cli.command {
-        Commands::Install { force } => {
-            commands::install::run(force).await?;
+        Commands::Setup { yes } => {
+            commands::setup::run(yes).await?;
+        }
+        Commands::Doctor => {
+            commands::doctor::run().await?;
+        }
+        Commands::Install { force, components, verify, json } => {
+            commands::install::run(force, components, verify, json).await?;
         }
-        Commands::Create { name, idea } => {
-            commands::create::run(name, idea).await?;
+        Commands::Create { name, idea, openapi, docs, no_ui_docs } => {
+            commands::create::run(name, idea, openapi, docs, no_ui_docs).await?;
         }
         Commands::Init { template } => {
             commands::init::run(template).await?;
         }
-        Commands::Start { parallel, sprint, no_live } => {
+        Commands::Start { parallel, jobs, sprint, no_live, steal_lock, sync_docs, schedule, dry_run, force_redo } => {
             // Live logging is enabled by default, disabled with --no-live
             let live = !no_live;
-            commands::start::run(parallel, sprint, live).await?;
+            let parallel = parallel || jobs.is_some();
+            commands::start::run(parallel, jobs.unwrap_or(0), sprint, live, steal_lock, sync_docs, schedule, dry_run, force_redo, false).await?;
         }
-        Commands::Status { json } => {
-            commands::status::run(json).await?;
+        Commands::Resume { parallel, jobs, sprint, no_live, steal_lock } => {
+            let parallel = parallel || jobs.is_some();
+            commands::start::run(parallel, jobs.unwrap_or(0), sprint, !no_live, steal_lock, false, None, false, false, true).await?;
+        }
+        Commands::Status { json, run_id, last_run } => {
+            commands::status::run(json, run_id, last_run).await?;
+        }
+        Commands::Board { stdout } => {
+            commands::board::run(stdout).await?;
         }
         Commands::Analyze => {
             commands::analyze::run().await?;
@@ -329,22 +252,38 @@ async fn main() -> anyhow::Result<()> {
         Commands::Add {
             description,
             requirements,
+            after,
+            depends_on,
         } => {
-            commands::add::run(description, requirements).await?;
+            commands::add::run(description, requirements, after, depends_on).await?;
         }
         Commands::Fix {
             description,
             auto_fix,
             playwright_headed,
+            steal_lock,
+            reproduce,
         } => {
-            commands::fix::run(description, auto_fix, playwright_headed).await?;
+            commands::fix::run(description, auto_fix, playwright_headed, steal_lock, reproduce).await?;
+        }
+        Commands::Quick { instruction, save, steal_lock } => {
+            commands::quick::run(instruction, save, steal_lock).await?;
         }
-        Commands::Pivot { instruction } => {
-            commands::pivot::run(instruction).await?;
+        Commands::Pivot { instruction, steal_lock } => {
+            commands::pivot::run(instruction, steal_lock).await?;
         }
         Commands::Rollback { sprint } => {
             commands::rollback::run(sprint).await?;
         }
+        Commands::Resolve { sprint } => {
+            commands::resolve::run(sprint).await?;
+        }
+        Commands::Maintain { dry_run, if_due } => {
+            commands::maintain::run(dry_run, if_due).await?;
+        }
+        Commands::Hint { sprint, text, clear } => {
+            commands::hint::run(sprint, text, clear).await?;
+        }
         Commands::Worktree(cmd) => {
             commands::worktree::run(cmd).await?;
         }
@@ -358,12 +297,15 @@ async fn main() -> anyhow::Result<()> {
         Commands::Sprints(cmd) => {
             commands::sprints::run(cmd).await?;
         }
-        Commands::Agents { detailed } => {
-            commands::agents::run(detailed).await?;
+        Commands::Agents { detailed, command } => {
+            commands::agents::run(detailed, command).await?;
         }
         Commands::Skills => {
             commands::skills::run().await?;
         }
+        Commands::Patch(cmd) => {
+            commands::patch::run(cmd).await?;
+        }
         Commands::Env(cmd) => {
             commands::env::run(cmd).await?;
         }
@@ -378,13 +320,146 @@ async fn main() -> anyhow::Result<()> {
                 commands::mcp::run_info(server).await?;
             }
         },
-        Commands::Logs { follow, live } => {
-            commands::logs::run(follow, live).await?;
+        Commands::Watch { interval } => {
+            commands::watch::run(interval).await?;
+        }
+        Commands::Logs { follow, live, run_id } => {
+            commands::logs::run(follow, live, run_id).await?;
+        }
+        Commands::Update { force } => {
+            commands::update::run(force).await?;
+        }
+        Commands::Hooks(cmd) => {
+            commands::hooks::run(cmd).await?;
+        }
+        Commands::Telemetry(cmd) => {
+            commands::telemetry::run(cmd).await?;
+        }
+        Commands::Config(cmd) => {
+            commands::config::run(cmd)?;
+        }
+        Commands::Workflows(cmd) => {
+            commands::workflows::run(cmd).await?;
+        }
+        Commands::Runs(cmd) => {
+            commands::runs::run(cmd).await?;
+        }
+        Commands::Cost { run, json } => {
+            commands::cost::run(run, json).await?;
         }
-        Commands::Update { force: _force } => {
-            update::update_now(cli.verbose).await?;
+        Commands::ExportBundle { output, runs } => {
+            commands::export_bundle::run(output, runs).await?;
+        }
+        Commands::Debug(cmd) => {
+            commands::debug::run(cmd).await?;
+        }
+        Commands::Generate(cmd) => {
+            commands::generate::run(cmd).await?;
+        }
+        Commands::Completions { shell } => {
+            commands::completions::run(shell)?;
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Parser, ValueHint};
+
+    /// Every argument of `cmd` and its subcommands, with the subcommand path for messages
+    fn all_args(cmd: &clap::Command, path: &str, out: &mut Vec<(String, clap::Arg)>) {
+        for arg in cmd.get_arguments() {
+            out.push((format!("{} --{}", path, arg.get_id()), arg.clone()));
+        }
+        for sub in cmd.get_subcommands() {
+            all_args(sub, &format!("{} {}", path, sub.get_name()), out);
+        }
+    }
+
+    fn allowed(args: &str) -> bool {
+        let cli = Cli::try_parse_from(format!("autoflow {}", args).split_whitespace()).unwrap();
+        allowed_in_read_only(&cli.command)
+    }
+
+    #[test]
+    fn test_read_only_allows_only_inspection() {
+        for args in [
+            "status",
+            "validate",
+            "start --dry-run",
+            "board --stdout",
+            "install --verify",
+            "config get",
+            "config get model",
+            "debug list",
+            "env cache",
+            "hint 3",
+            "maintain --dry-run",
+            "patch list",
+            "patch show sprint-3-write_code.patch",
+        ] {
+            assert!(allowed(args), "`autoflow {}` should run read-only", args);
+        }
+        for args in [
+            "start",
+            "validate --fix",
+            "board",
+            "install",
+            "config set model opus",
+            "config unset model",
+            "env cache --clear",
+            "hint 3 use-the-staging-db",
+            "hint 3 --clear",
+            "maintain",
+            "patch apply",
+            "fix typo",
+        ] {
+            assert!(!allowed(args), "`autoflow {}` should be refused read-only", args);
+        }
+
+        let cli = Cli::try_parse_from(["autoflow", "start"]).unwrap();
+        assert!(enforce_read_only(&cli.command, "start", false).is_ok());
+        let err = enforce_read_only(&cli.command, "start", true).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(autoflow_data::AutoFlowError::ReadOnly(_))));
+    }
+
+    #[test]
+    fn test_path_args_resolve_against_the_invocation_directory() {
+        let cmd = Cli::command();
+        let mut args = Vec::new();
+        all_args(&cmd, "autoflow", &mut args);
+        let cwd = std::env::current_dir().unwrap();
+
+        for (name, arg) in &args {
+            let id = arg.get_id().as_str();
+            let path_hint = matches!(arg.get_value_hint(), ValueHint::FilePath | ValueHint::DirPath | ValueHint::AnyPath);
+            let named_like_path = ["path", "file", "dir", "output", "spec"].iter().any(|n| id == *n || id.ends_with(&format!("_{}", n)));
+            // An explicit non-path value_name (e.g. BUNDLE) marks a name, not a path
+            let default_name = id.to_uppercase();
+            let renamed = arg
+                .get_value_names()
+                .is_some_and(|names| !names.iter().any(|n| ["PATH", "FILE", "DIR", default_name.as_str()].contains(&n.as_str())));
+            assert!(path_hint || !named_like_path || renamed, "{} looks like a path but has no path value_hint", name);
+            if !path_hint {
+                continue;
+            }
+
+            // The parser, not enter_project, is what keeps the path relative to where autoflow was run
+            let single = clap::Command::new("t").arg(arg.clone().required(false).global(false));
+            let argv = match arg.get_long() {
+                Some(long) => vec!["t".to_string(), format!("--{}", long), "sub/x".to_string()],
+                None => vec!["t".to_string(), "sub/x".to_string()],
+            };
+            let matches = single.try_get_matches_from(argv).unwrap();
+            let parsed = match matches.try_get_one::<String>(id) {
+                Ok(value) => value.map(std::path::PathBuf::from),
+                Err(_) => matches.try_get_one::<std::path::PathBuf>(id).unwrap().cloned(),
+            };
+            let parsed = parsed.unwrap_or_else(|| panic!("{} didn't parse", name));
+            assert_eq!(parsed, cwd.join("sub/x"), "{}", name);
+        }
+    }
+}
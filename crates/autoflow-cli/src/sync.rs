@@ -1,7 +1,7 @@
 /// Auto-sync agents and skills to ~/.claude/ on startup
 use anyhow::{Context, Result};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Sync agents and skills from source to ~/.claude/
 /// This ensures they're always up-to-date without manual reinstall
@@ -10,13 +10,13 @@ pub async fn sync_agents_and_skills() -> Result<()> {
     let exe_path = std::env::current_exe()?;
     let exe_dir = exe_path.parent().context("Failed to get binary directory")?;
 
-    let possible_agent_sources = vec![
+    let possible_agent_sources = [
         PathBuf::from("./agents"),
         exe_dir.join("../agents"),
         exe_dir.join("../../agents"), // For development builds in target/release
     ];
 
-    let possible_skill_sources = vec![
+    let possible_skill_sources = [
         PathBuf::from("./skills"),
         exe_dir.join("../skills"),
         exe_dir.join("../../skills"),
@@ -27,7 +27,9 @@ pub async fn sync_agents_and_skills() -> Result<()> {
     let skill_source = possible_skill_sources.iter().find(|p| p.exists());
 
     // Claude directories
-    let home = std::env::var("HOME").unwrap_or_else(|_| "/root".to_string());
+    let Some(home) = autoflow_utils::home_dir() else {
+        return Ok(());
+    };
     let claude_agents_dir = PathBuf::from(&home).join(".claude/agents");
     let claude_skills_dir = PathBuf::from(&home).join(".claude/skills");
 
@@ -48,7 +50,7 @@ pub async fn sync_agents_and_skills() -> Result<()> {
     Ok(())
 }
 
-fn sync_agents(source: &PathBuf, dest: &PathBuf) -> Result<()> {
+fn sync_agents(source: &Path, dest: &Path) -> Result<()> {
     let entries = fs::read_dir(source)
         .context(format!("Failed to read agents directory: {:?}", source))?;
 
@@ -83,7 +85,7 @@ fn sync_agents(source: &PathBuf, dest: &PathBuf) -> Result<()> {
     Ok(())
 }
 
-fn sync_skills(source: &PathBuf, dest: &PathBuf) -> Result<()> {
+fn sync_skills(source: &Path, dest: &Path) -> Result<()> {
     // Handle both directory-based skills (skills/*/SKILL.md) and flat skills (skills/*.md)
 
     // Directory-based skills
@@ -151,7 +153,7 @@ fn sync_skills(source: &PathBuf, dest: &PathBuf) -> Result<()> {
     Ok(())
 }
 
-fn copy_dir_recursive(src: &PathBuf, dest: &PathBuf) -> Result<()> {
+pub(crate) fn copy_dir_recursive(src: &PathBuf, dest: &PathBuf) -> Result<()> {
     fs::create_dir_all(dest)?;
 
     for entry in fs::read_dir(src)? {
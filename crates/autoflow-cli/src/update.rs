@@ -3,21 +3,21 @@
 use anyhow::{Context, Result};
 use std::env;
 use std::fs;
-use std::path::PathBuf;
 
 const GITHUB_REPO: &str = "ddunford/autoflow";
 const UPDATE_CHECK_FILE: &str = ".last_update_check";
 
 /// Check if auto-update is enabled (default: true)
 pub fn is_auto_update_enabled() -> bool {
-    // Check environment variable
-    if let Ok(val) = env::var("AUTOFLOW_AUTO_UPDATE") {
-        return val != "0" && val.to_lowercase() != "false";
+    // Check AUTOFLOW_AUTO_UPDATE, then the project's config.yml
+    if let Some(enabled) = autoflow_data::ProjectConfig::current().update_check() {
+        return enabled;
     }
 
     // Check config file
-    let home = env::var("HOME").unwrap_or_else(|_| "/root".to_string());
-    let config_path = PathBuf::from(home).join(".autoflow/config.toml");
+    let Ok(config_path) = autoflow_data::Config::global_path() else {
+        return false;
+    };
 
     if let Ok(content) = fs::read_to_string(config_path) {
         // Simple parsing - look for auto_update = false
@@ -35,8 +35,10 @@ pub fn should_check_for_updates() -> bool {
         return false;
     }
 
-    let home = env::var("HOME").unwrap_or_else(|_| "/root".to_string());
-    let check_file = PathBuf::from(home).join(".autoflow").join(UPDATE_CHECK_FILE);
+    let Some(autoflow_dir) = autoflow_utils::autoflow_dir() else {
+        return false;
+    };
+    let check_file = autoflow_dir.join(UPDATE_CHECK_FILE);
 
     // If file doesn't exist, we should check
     if !check_file.exists() {
@@ -58,8 +60,7 @@ pub fn should_check_for_updates() -> bool {
 
 /// Update the last check timestamp
 fn update_check_timestamp() -> Result<()> {
-    let home = env::var("HOME").unwrap_or_else(|_| "/root".to_string());
-    let autoflow_dir = PathBuf::from(home).join(".autoflow");
+    let autoflow_dir = autoflow_utils::autoflow_dir().ok_or(autoflow_data::AutoFlowError::HomeNotFound)?;
     fs::create_dir_all(&autoflow_dir)?;
 
     let check_file = autoflow_dir.join(UPDATE_CHECK_FILE);
@@ -116,20 +117,20 @@ pub async fn check_and_update(verbose: bool) -> Result<()> {
         return Ok(());
     }
 
-    eprintln!("");
+    eprintln!();
     eprintln!("🎉 New version available: {} → {}", current_version, latest_version);
     eprintln!("   Installing update...");
-    eprintln!("");
+    eprintln!();
 
     // Download and install the new version
     install_update(&release, verbose).await?;
 
     update_check_timestamp()?;
 
-    eprintln!("");
+    eprintln!();
     eprintln!("✅ AutoFlow updated to version {}", latest_version);
     eprintln!("   Restart your command to use the new version");
-    eprintln!("");
+    eprintln!();
 
     Ok(())
 }
@@ -221,12 +222,6 @@ async fn install_update(release: &serde_json::Value, verbose: bool) -> Result<()
     Ok(())
 }
 
-/// Manual update command
-pub async fn update_now(verbose: bool) -> Result<()> {
-    eprintln!("🔄 Checking for updates...");
-    check_and_update(verbose).await
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
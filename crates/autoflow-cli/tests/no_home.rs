@@ -0,0 +1,64 @@
+// The CLI in a minimal container: no HOME, no XDG dirs
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn autoflow(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_autoflow"))
+        .args(args)
+        .current_dir(dir)
+        .env_remove("HOME")
+        .env_remove("AUTOFLOW_HOME")
+        .env_remove("XDG_CONFIG_HOME")
+        .env("AUTOFLOW_AUTO_UPDATE", "0")
+        .env("AUTOFLOW_TELEMETRY", "0")
+        .output()
+        .expect("failed to run autoflow")
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("autoflow-no-home-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_commands_run_without_home() {
+    let dir = scratch_dir("status");
+
+    let status = autoflow(&dir, &["status"]);
+    let stderr = String::from_utf8_lossy(&status.stderr);
+    assert!(status.status.success(), "status failed: {}", stderr);
+    assert!(!stderr.contains("panicked"));
+
+    let doctor = autoflow(&dir, &["doctor"]);
+    assert!(!String::from_utf8_lossy(&doctor.stderr).contains("panicked"));
+    assert!(String::from_utf8_lossy(&doctor.stdout).contains("HOME is not set"));
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_home_commands_fail_with_error_code() {
+    let dir = scratch_dir("agents");
+
+    let agents = autoflow(&dir, &["agents", "--error-format", "json"]);
+    assert_eq!(agents.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&agents.stderr);
+    assert!(stderr.contains(r#""code":"AF104""#), "unexpected error: {}", stderr);
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_home_flag_stands_in_for_home() {
+    let dir = scratch_dir("flag");
+    let home = dir.join("home");
+
+    let skills = autoflow(&dir, &["--home", home.to_str().unwrap(), "skills"]);
+    assert!(skills.status.success(), "skills failed: {}", String::from_utf8_lossy(&skills.stderr));
+    assert!(home.join(".claude/agents").is_dir());
+
+    let _ = std::fs::remove_dir_all(dir);
+}
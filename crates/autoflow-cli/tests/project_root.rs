@@ -0,0 +1,47 @@
+// Commands run from inside a project resolve its root
+
+use std::path::PathBuf;
+use std::process::Command;
+
+fn scratch_project(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("autoflow-project-root-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join(".autoflow")).unwrap();
+    std::fs::create_dir_all(dir.join("src/components")).unwrap();
+    std::fs::write(
+        dir.join(".autoflow/SPRINTS.yml"),
+        include_str!("../templates/SPRINTS.template.yml").replace("New Project", "Root Finder"),
+    )
+    .unwrap();
+    dir
+}
+
+fn autoflow(dir: &PathBuf, args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_autoflow"))
+        .args(args)
+        .current_dir(dir)
+        .env("AUTOFLOW_AUTO_UPDATE", "0")
+        .env("AUTOFLOW_TELEMETRY", "0")
+        .output()
+        .expect("failed to run autoflow")
+}
+
+#[test]
+fn test_subdirectory_and_project_flag() {
+    let project = scratch_project("walk");
+
+    let from_subdir = autoflow(&project.join("src/components"), &["status"]);
+    assert!(from_subdir.status.success());
+    assert!(String::from_utf8_lossy(&from_subdir.stdout).contains("Project: Root Finder"));
+
+    let elsewhere = std::env::temp_dir();
+    let flag = project.display().to_string();
+    let explicit = autoflow(&elsewhere, &["--project", &flag, "status"]);
+    assert!(String::from_utf8_lossy(&explicit.stdout).contains("Project: Root Finder"));
+
+    let missing = autoflow(&elsewhere, &["--project", "/definitely/not/here", "status"]);
+    assert_eq!(missing.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("AF105"));
+
+    let _ = std::fs::remove_dir_all(project);
+}
@@ -1,9 +1,12 @@
-use autoflow_data::Result;
+use autoflow_data::{IgnoreRules, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+/// Machine-readable analysis written by `autoflow analyze`
+pub const ANALYSIS_PATH: &str = ".autoflow/analysis.json";
+
 /// Codebase analysis result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodebaseAnalysis {
@@ -22,6 +25,62 @@ impl CodebaseAnalysis {
         Ok(())
     }
 
+    /// Save analysis as JSON (analysis.json) for agents and the orchestrator
+    pub fn save_json(&self, path: &str) -> Result<()> {
+        autoflow_data::write_state(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Load a saved analysis; None when missing or unreadable
+    pub fn load_json<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let content = autoflow_data::read_state_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Compact "Project Facts" block for agent contexts; None for unknown stacks
+    pub fn project_facts(&self) -> Option<String> {
+        let stack = &self.tech_stack;
+        if stack.language == "Unknown" {
+            return None;
+        }
+
+        let mut facts = String::from("# Project Facts\n\nDetected from the codebase - use these instead of guessing:\n");
+        match &stack.version {
+            Some(version) => facts.push_str(&format!("- Language: {} ({})\n", stack.language, version)),
+            None => facts.push_str(&format!("- Language: {}\n", stack.language)),
+        }
+        facts.push_str(&format!(
+            "- Package manager: {} (don't install or run scripts with any other)\n",
+            stack.package_manager
+        ));
+        if let Some(test) = &stack.test_command {
+            facts.push_str(&format!("- Test command: `{}`\n", test));
+        }
+        if let Some(lint) = &stack.lint_command {
+            facts.push_str(&format!("- Lint command: `{}`\n", lint));
+        }
+        if let Some(coverage) = &stack.coverage_command {
+            facts.push_str(&format!("- Coverage command: `{}`\n", coverage));
+        }
+        if !self.frameworks.is_empty() {
+            let frameworks: Vec<String> = self
+                .frameworks
+                .iter()
+                .map(|f| match &f.version {
+                    Some(version) => format!("{} {} ({})", f.name, version, f.framework_type),
+                    None => format!("{} ({})", f.name, f.framework_type),
+                })
+                .collect();
+            facts.push_str(&format!("- Frameworks: {}\n", frameworks.join(", ")));
+        }
+        Some(facts)
+    }
+
+    /// True when the analyzer recognised code already in the project
+    pub fn is_existing_codebase(&self) -> bool {
+        self.tech_stack.language != "Unknown" || !self.frameworks.is_empty() || !self.integration_points.is_empty()
+    }
+
     /// Generate markdown documentation
     pub fn to_markdown(&self) -> String {
         let mut md = String::new();
@@ -29,12 +88,7 @@ impl CodebaseAnalysis {
         md.push_str("# Integration Guide\n\n");
         md.push_str(&format!("**Generated**: {}\n\n", chrono::Utc::now().format("%Y-%m-%d %H:%M:%S")));
 
-        // Check if this is an empty/new project
-        let is_empty_project = self.tech_stack.language == "Unknown"
-            && self.frameworks.is_empty()
-            && self.integration_points.is_empty();
-
-        if is_empty_project {
+        if !self.is_existing_codebase() {
             md.push_str(r#"
 > **Note**: This is a new project with no existing code. This guide is generated automatically
 > by `autoflow analyze` and is used when adding features to existing codebases.
@@ -74,7 +128,16 @@ If you have existing code that wasn't detected:
             md.push_str(&format!("- **Version**: {}\n", version));
         }
         md.push_str(&format!("- **Package Manager**: {}\n", self.tech_stack.package_manager));
-        md.push_str("\n");
+        if let Some(test) = &self.tech_stack.test_command {
+            md.push_str(&format!("- **Test Command**: `{}`\n", test));
+        }
+        if let Some(lint) = &self.tech_stack.lint_command {
+            md.push_str(&format!("- **Lint Command**: `{}`\n", lint));
+        }
+        if let Some(coverage) = &self.tech_stack.coverage_command {
+            md.push_str(&format!("- **Coverage Command**: `{}`\n", coverage));
+        }
+        md.push('\n');
 
         // Frameworks
         if !self.frameworks.is_empty() {
@@ -85,7 +148,7 @@ If you have existing code that wasn't detected:
                 if let Some(version) = &framework.version {
                     md.push_str(&format!("- **Version**: {}\n", version));
                 }
-                md.push_str("\n");
+                md.push('\n');
             }
         }
 
@@ -114,7 +177,7 @@ If you have existing code that wasn't detected:
                         md.push_str(&format!("- `{}`\n", file));
                     }
                 }
-                md.push_str("\n");
+                md.push('\n');
             }
         }
 
@@ -127,6 +190,135 @@ pub struct TechStack {
     pub language: String,
     pub version: Option<String>,
     pub package_manager: String,
+
+    /// How the project runs its tests
+    #[serde(default)]
+    pub test_command: Option<String>,
+
+    /// How the project lints its code
+    #[serde(default)]
+    pub lint_command: Option<String>,
+
+    /// How the project measures test coverage
+    #[serde(default)]
+    pub coverage_command: Option<String>,
+}
+
+impl TechStack {
+    /// Whether any of `commands` (Bash commands an agent ran) runs the detected test command
+    ///
+    /// True when nothing is known: no test command detected or no commands captured.
+    pub fn ran_test_command(&self, commands: &[String]) -> bool {
+        let Some(expected) = &self.test_command else {
+            return true;
+        };
+        if commands.is_empty() {
+            return true;
+        }
+        let expected = normalize_command(expected);
+        let mut accepted = vec![expected.clone()];
+        // `npm test` and `npm run test` are the same script
+        if let Some(tool) = expected.strip_suffix(" test") {
+            accepted.push(format!("{} run test", tool));
+        }
+        // `poetry run pytest` is plain `pytest` inside an activated environment
+        if let Some(bare) = ["poetry run ", "uv run "].iter().find_map(|prefix| expected.strip_prefix(prefix)) {
+            accepted.push(bare.to_string());
+        }
+        commands
+            .iter()
+            .map(|c| normalize_command(c))
+            .any(|command| accepted.iter().any(|a| command.contains(a.as_str())))
+    }
+
+    /// Fallback TESTING_STRATEGY.md for when the docs agent doesn't write one
+    ///
+    /// Follows the detected ecosystem's conventions; a project with no code yet
+    /// gets the web defaults.
+    pub fn testing_strategy(&self) -> String {
+        let (frameworks, heading, conventions, coverage_tool) = match self.package_manager.as_str() {
+            "cargo" => (
+                "- Unit: `#[test]` functions in a `#[cfg(test)] mod tests` beside the code\n\
+                 - Integration: `tests/*.rs`, exercising the public API only\n\
+                 - Runner: cargo test (cargo-nextest when `.config/nextest.toml` exists)",
+                "Conventions",
+                "- Unit tests live in the module they test\n\
+                 - Integration tests go in `tests/`, one file per feature\n\
+                 - Doc examples are tests too: keep them compiling",
+                "cargo-llvm-cov (or cargo-tarpaulin)",
+            ),
+            "pip" | "poetry" | "uv" => (
+                "- Unit and integration: pytest with plain `assert` and fixtures in `conftest.py`\n\
+                 - Mocking: `unittest.mock` / pytest-mock\n\
+                 - E2E (web apps only): Playwright for Python",
+                "Conventions",
+                "- Tests live in `tests/`, files named `test_*.py`, functions named `test_*`\n\
+                 - Shared fixtures go in `tests/conftest.py`\n\
+                 - Run tests inside the project's environment (poetry/uv run when used)",
+                "pytest-cov (coverage.py)",
+            ),
+            "go" => (
+                "- Unit: the standard `testing` package with table-driven tests\n\
+                 - HTTP: `net/http/httptest`\n\
+                 - Runner: go test ./...",
+                "Conventions",
+                "- Tests live beside the code in `*_test.go` files\n\
+                 - Test functions are named `TestXxx(t *testing.T)`\n\
+                 - Fixtures go in a `testdata/` directory",
+                "go test -cover / -coverprofile",
+            ),
+            _ => (
+                "- Unit: Vitest/Jest with React Testing Library\n\
+                 - E2E: Playwright/Cypress\n\
+                 - Backend: PHPUnit/Pest",
+                "What to Test",
+                "- Unit: Business logic, utilities, components, hooks\n\
+                 - Integration: API endpoints, database ops, auth flows\n\
+                 - E2E: Critical user flows",
+                "the test runner's built-in coverage",
+            ),
+        };
+
+        let mut commands = String::new();
+        for (label, command) in [
+            ("Tests", &self.test_command),
+            ("Lint", &self.lint_command),
+            ("Coverage", &self.coverage_command),
+        ] {
+            if let Some(command) = command {
+                commands.push_str(&format!("- {}: `{}`\n", label, command));
+            }
+        }
+        if !commands.is_empty() {
+            commands = format!("\n## Commands\n{}", commands);
+        }
+
+        format!(
+            "# Testing Strategy\n\n\
+             ## Framework Choices\n{}\n\n\
+             ## Coverage Requirements\n\
+             - Overall: 80% minimum, measured with {}\n\
+             - Critical paths: 100%\n\n\
+             ## {}\n{}\n{}",
+            frameworks,
+            coverage_tool,
+            heading,
+            conventions,
+            commands
+        )
+    }
+}
+
+/// `command` run inside the project's Python environment
+fn python_run(package_manager: &str, command: &str) -> String {
+    match package_manager {
+        "poetry" | "uv" => format!("{} run {}", package_manager, command),
+        _ => command.to_string(),
+    }
+}
+
+fn normalize_command(command: &str) -> String {
+    command.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -155,12 +347,15 @@ pub struct IntegrationPoint {
 /// Codebase analyzer
 pub struct CodebaseAnalyzer {
     root: PathBuf,
+    /// Vendored and generated paths from `.autoflowignore`
+    ignore: IgnoreRules,
 }
 
 impl CodebaseAnalyzer {
     pub fn new<P: AsRef<Path>>(root: P) -> Self {
         Self {
             root: root.as_ref().to_path_buf(),
+            ignore: IgnoreRules::load(root.as_ref()),
         }
     }
 
@@ -180,8 +375,88 @@ impl CodebaseAnalyzer {
         })
     }
 
-    /// Detect tech stack (language, package manager)
+    /// Detect tech stack (language, package manager, test, lint and coverage commands)
     fn detect_tech_stack(&self) -> TechStack {
+        let mut stack = self.detect_language();
+        let (test_command, lint_command) = self.detect_commands(&stack.package_manager);
+        stack.coverage_command = self.detect_coverage_command(&stack.package_manager);
+        stack.test_command = test_command;
+        stack.lint_command = lint_command;
+        stack
+    }
+
+    /// Test and lint commands for the detected package manager
+    fn detect_commands(&self, package_manager: &str) -> (Option<String>, Option<String>) {
+        match package_manager {
+            "npm" | "pnpm" | "yarn" => {
+                let scripts = self.read_package_json_scripts();
+                let test = scripts.iter().any(|s| s == "test").then(|| format!("{} test", package_manager));
+                let lint = scripts.iter().any(|s| s == "lint").then(|| format!("{} run lint", package_manager));
+                (test, lint)
+            }
+            "composer" => {
+                let test = if self.root.join("artisan").exists() {
+                    "php artisan test"
+                } else {
+                    "vendor/bin/phpunit"
+                };
+                let lint = self.root.join("vendor/bin/pint").exists().then(|| "vendor/bin/pint --test".to_string());
+                (Some(test.to_string()), lint)
+            }
+            "cargo" => {
+                let test = if self.uses_nextest() { "cargo nextest run" } else { "cargo test" };
+                (Some(test.to_string()), Some("cargo clippy".to_string()))
+            }
+            "pip" | "poetry" | "uv" => {
+                let lint = if self.python_config_mentions("ruff") {
+                    Some("ruff check .")
+                } else if self.python_config_mentions("flake8") {
+                    Some("flake8")
+                } else {
+                    None
+                };
+                let run = |command: &str| python_run(package_manager, command);
+                (Some(run("pytest")), lint.map(run))
+            }
+            "go" => (Some("go test ./...".to_string()), Some("go vet ./...".to_string())),
+            _ => (None, None),
+        }
+    }
+
+    /// Coverage command for the detected package manager, from the tooling the project has
+    fn detect_coverage_command(&self, package_manager: &str) -> Option<String> {
+        match package_manager {
+            "npm" | "pnpm" | "yarn" => {
+                let scripts = self.read_package_json_scripts();
+                ["test:coverage", "coverage"]
+                    .iter()
+                    .find(|name| scripts.iter().any(|s| s == *name))
+                    .map(|name| format!("{} run {}", package_manager, name))
+            }
+            "composer" => Some(if self.root.join("artisan").exists() {
+                "php artisan test --coverage".to_string()
+            } else {
+                "vendor/bin/phpunit --coverage-text".to_string()
+            }),
+            "cargo" => Some(if self.has_any_file(&["tarpaulin.toml", ".tarpaulin.toml"]) {
+                "cargo tarpaulin".to_string()
+            } else if self.uses_nextest() {
+                "cargo llvm-cov nextest".to_string()
+            } else {
+                "cargo llvm-cov".to_string()
+            }),
+            "pip" | "poetry" | "uv" => Some(if self.python_config_mentions("pytest-cov") {
+                python_run(package_manager, "pytest --cov")
+            } else {
+                python_run(package_manager, "coverage run -m pytest")
+            }),
+            "go" => Some("go test -coverprofile=coverage.out ./...".to_string()),
+            _ => None,
+        }
+    }
+
+    /// Language and package manager from marker files
+    fn detect_language(&self) -> TechStack {
         // Check for Node.js
         if self.root.join("package.json").exists() {
             let version = self.read_package_json_field("version");
@@ -195,6 +470,9 @@ impl CodebaseAnalyzer {
                 } else {
                     "npm".to_string()
                 },
+                test_command: None,
+                lint_command: None,
+                coverage_command: None,
             };
         }
 
@@ -204,6 +482,9 @@ impl CodebaseAnalyzer {
                 language: "PHP".to_string(),
                 version: None,
                 package_manager: "composer".to_string(),
+                test_command: None,
+                lint_command: None,
+                coverage_command: None,
             };
         }
 
@@ -213,15 +494,28 @@ impl CodebaseAnalyzer {
                 language: "Rust".to_string(),
                 version: None,
                 package_manager: "cargo".to_string(),
+                test_command: None,
+                lint_command: None,
+                coverage_command: None,
             };
         }
 
         // Check for Python
-        if self.root.join("requirements.txt").exists() || self.root.join("pyproject.toml").exists() {
+        if self.has_any_file(&["requirements.txt", "pyproject.toml", "setup.py"]) {
+            let package_manager = if self.root.join("poetry.lock").exists() || self.python_config_mentions("[tool.poetry]") {
+                "poetry"
+            } else if self.root.join("uv.lock").exists() {
+                "uv"
+            } else {
+                "pip"
+            };
             return TechStack {
                 language: "Python".to_string(),
                 version: None,
-                package_manager: "pip".to_string(),
+                package_manager: package_manager.to_string(),
+                test_command: None,
+                lint_command: None,
+                coverage_command: None,
             };
         }
 
@@ -231,6 +525,9 @@ impl CodebaseAnalyzer {
                 language: "Go".to_string(),
                 version: None,
                 package_manager: "go".to_string(),
+                test_command: None,
+                lint_command: None,
+                coverage_command: None,
             };
         }
 
@@ -238,6 +535,9 @@ impl CodebaseAnalyzer {
             language: "Unknown".to_string(),
             version: None,
             package_manager: "Unknown".to_string(),
+            test_command: None,
+            lint_command: None,
+            coverage_command: None,
         }
     }
 
@@ -311,7 +611,7 @@ impl CodebaseAnalyzer {
 
         // Common source directories
         for dir in &["src", "app", "lib"] {
-            if self.root.join(dir).is_dir() {
+            if self.has_dir(dir) {
                 source_dir = Some(dir.to_string());
                 break;
             }
@@ -319,7 +619,7 @@ impl CodebaseAnalyzer {
 
         // Common test directories
         for dir in &["tests", "test", "__tests__", "spec"] {
-            if self.root.join(dir).is_dir() {
+            if self.has_dir(dir) {
                 test_dir = Some(dir.to_string());
                 break;
             }
@@ -327,7 +627,7 @@ impl CodebaseAnalyzer {
 
         // Common config directories
         for dir in &["config", "conf", ".config"] {
-            if self.root.join(dir).is_dir() {
+            if self.has_dir(dir) {
                 config_dir = Some(dir.to_string());
                 break;
             }
@@ -335,7 +635,7 @@ impl CodebaseAnalyzer {
 
         // Entry points
         for file in &["index.js", "index.ts", "main.js", "main.ts", "app.js", "server.js"] {
-            if self.root.join(file).exists() {
+            if self.root.join(file).exists() && !self.ignore.is_ignored(file, false) {
                 entry_points.push(file.to_string());
             }
         }
@@ -353,7 +653,7 @@ impl CodebaseAnalyzer {
         let mut points = Vec::new();
 
         // API endpoints (Laravel routes)
-        if self.root.join("routes/api.php").exists() {
+        if self.root.join("routes/api.php").exists() && !self.ignore.is_ignored("routes/api.php", false) {
             points.push(IntegrationPoint {
                 name: "API Routes".to_string(),
                 point_type: "api".to_string(),
@@ -363,7 +663,7 @@ impl CodebaseAnalyzer {
         }
 
         // Models (Laravel)
-        if self.root.join("app/Models").is_dir() {
+        if self.has_dir("app/Models") {
             let models = self.find_files_in_dir("app/Models", "php");
             points.push(IntegrationPoint {
                 name: "Models".to_string(),
@@ -412,6 +712,30 @@ impl CodebaseAnalyzer {
         None
     }
 
+    fn read_package_json_scripts(&self) -> Vec<String> {
+        fs::read_to_string(self.root.join("package.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .and_then(|json| json.get("scripts").and_then(|s| s.as_object()).map(|s| s.keys().cloned().collect()))
+            .unwrap_or_default()
+    }
+
+    fn python_config_mentions(&self, tool: &str) -> bool {
+        ["pyproject.toml", "requirements.txt", "requirements-dev.txt", "setup.cfg"]
+            .iter()
+            .filter_map(|file| fs::read_to_string(self.root.join(file)).ok())
+            .any(|content| content.contains(tool))
+    }
+
+    /// cargo-nextest is configured for the workspace
+    fn uses_nextest(&self) -> bool {
+        self.root.join(".config/nextest.toml").exists()
+    }
+
+    fn has_any_file(&self, files: &[&str]) -> bool {
+        files.iter().any(|file| self.root.join(file).exists())
+    }
+
     fn read_package_json_field(&self, field: &str) -> Option<String> {
         if let Ok(content) = fs::read_to_string(self.root.join("package.json")) {
             if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
@@ -423,7 +747,7 @@ impl CodebaseAnalyzer {
 
     fn find_components_dir(&self) -> Option<String> {
         for dir in &["src/components", "app/components", "components"] {
-            if self.root.join(dir).is_dir() {
+            if self.has_dir(dir) {
                 return Some(dir.to_string());
             }
         }
@@ -435,12 +759,14 @@ impl CodebaseAnalyzer {
         let full_path = self.root.join(dir);
 
         if full_path.exists() {
-            for entry in WalkDir::new(full_path).max_depth(3) {
-                if let Ok(entry) = entry {
-                    if entry.path().extension().and_then(|s| s.to_str()) == Some(extension) {
-                        if let Ok(relative) = entry.path().strip_prefix(&self.root) {
-                            files.push(relative.to_string_lossy().to_string());
-                        }
+            let walker = WalkDir::new(full_path).max_depth(3).into_iter().filter_entry(|entry| {
+                let relative = entry.path().strip_prefix(&self.root).unwrap_or(entry.path());
+                !self.ignore.is_ignored(&relative.to_string_lossy(), entry.file_type().is_dir())
+            });
+            for entry in walker.flatten() {
+                if entry.path().extension().and_then(|s| s.to_str()) == Some(extension) {
+                    if let Ok(relative) = entry.path().strip_prefix(&self.root) {
+                        files.push(relative.to_string_lossy().to_string());
                     }
                 }
             }
@@ -448,4 +774,90 @@ impl CodebaseAnalyzer {
 
         files
     }
+
+    /// An existing directory that `.autoflowignore` doesn't exclude
+    fn has_dir(&self, dir: &str) -> bool {
+        self.root.join(dir).is_dir() && !self.ignore.is_ignored(dir, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_facts_from_package_json() {
+        let project = std::env::temp_dir().join(format!("autoflow-analyzer-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&project);
+        fs::create_dir_all(&project).unwrap();
+        fs::write(
+            project.join("package.json"),
+            r#"{"scripts": {"test": "vitest run", "lint": "eslint ."}, "dependencies": {"react": "18.2.0"}}"#,
+        )
+        .unwrap();
+        fs::write(project.join("pnpm-lock.yaml"), "").unwrap();
+
+        let analysis = CodebaseAnalyzer::new(&project).analyze().unwrap();
+        assert_eq!(analysis.tech_stack.test_command.as_deref(), Some("pnpm test"));
+        assert_eq!(analysis.tech_stack.lint_command.as_deref(), Some("pnpm run lint"));
+
+        let facts = analysis.project_facts().unwrap();
+        assert!(facts.contains("- Package manager: pnpm"));
+        assert!(facts.contains("- Test command: `pnpm test`"));
+        assert!(facts.contains("React 18.2.0 (frontend)"));
+
+        let _ = fs::remove_dir_all(project);
+    }
+
+    #[test]
+    fn test_python_rust_and_go_conventions() {
+        let project = std::env::temp_dir().join(format!("autoflow-analyzer-eco-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&project);
+        fs::create_dir_all(&project).unwrap();
+        fs::write(project.join("pyproject.toml"), "[tool.poetry]\n[tool.poetry.group.dev.dependencies]\npytest-cov = \"*\"\nruff = \"*\"\n").unwrap();
+
+        let stack = CodebaseAnalyzer::new(&project).analyze().unwrap().tech_stack;
+        assert_eq!(stack.package_manager, "poetry");
+        assert_eq!(stack.test_command.as_deref(), Some("poetry run pytest"));
+        assert_eq!(stack.lint_command.as_deref(), Some("poetry run ruff check ."));
+        assert_eq!(stack.coverage_command.as_deref(), Some("poetry run pytest --cov"));
+        assert!(stack.ran_test_command(&["pytest -x tests/".to_string()]));
+        let strategy = stack.testing_strategy();
+        assert!(strategy.contains("conftest.py"));
+        assert!(strategy.contains("- Coverage: `poetry run pytest --cov`"));
+        assert!(!strategy.contains("Vitest"));
+
+        fs::remove_file(project.join("pyproject.toml")).unwrap();
+        fs::write(project.join("Cargo.toml"), "[package]\n").unwrap();
+        fs::create_dir_all(project.join(".config")).unwrap();
+        fs::write(project.join(".config/nextest.toml"), "").unwrap();
+        let stack = CodebaseAnalyzer::new(&project).analyze().unwrap().tech_stack;
+        assert_eq!(stack.test_command.as_deref(), Some("cargo nextest run"));
+        assert_eq!(stack.coverage_command.as_deref(), Some("cargo llvm-cov nextest"));
+        assert!(stack.testing_strategy().contains("## Conventions"));
+
+        fs::remove_file(project.join("Cargo.toml")).unwrap();
+        fs::write(project.join("go.mod"), "module example.com/app\n").unwrap();
+        let stack = CodebaseAnalyzer::new(&project).analyze().unwrap().tech_stack;
+        assert_eq!(stack.coverage_command.as_deref(), Some("go test -coverprofile=coverage.out ./..."));
+        assert!(stack.testing_strategy().contains("*_test.go"));
+
+        let _ = fs::remove_dir_all(project);
+    }
+
+    #[test]
+    fn test_ran_test_command() {
+        let stack = TechStack {
+            language: "JavaScript/TypeScript".to_string(),
+            version: None,
+            package_manager: "pnpm".to_string(),
+            test_command: Some("pnpm test".to_string()),
+            lint_command: None,
+            coverage_command: None,
+        };
+        assert!(stack.ran_test_command(&["cd web && pnpm  test -- --reporter=dot".to_string()]));
+        assert!(stack.ran_test_command(&["pnpm run test".to_string()]));
+        assert!(!stack.ran_test_command(&["npx jest".to_string(), "ls".to_string()]));
+        assert!(stack.ran_test_command(&[]));
+    }
 }
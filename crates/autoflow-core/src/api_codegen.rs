@@ -0,0 +1,1092 @@
+// Deterministic API client/type generation from API_SPEC.md or an OpenAPI file
+
+use crate::analyzer::CodebaseAnalyzer;
+use autoflow_data::{AutoFlowError, Result};
+use serde_yaml::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// OpenAPI/Swagger files looked for (project root first, then .autoflow/docs)
+const OPENAPI_CANDIDATES: &[&str] = &[
+    "openapi.yaml",
+    "openapi.yml",
+    "openapi.json",
+    "swagger.yaml",
+    "swagger.yml",
+    "swagger.json",
+];
+
+/// Markdown spec used when no OpenAPI file exists
+pub const API_SPEC_MD: &str = ".autoflow/docs/API_SPEC.md";
+
+const HTTP_METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE"];
+
+/// Language-neutral field type
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    Array(Box<FieldType>),
+    /// Reference to a named model
+    Ref(String),
+    /// Free-form object or unknown type
+    Any,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiField {
+    pub name: String,
+    pub ty: FieldType,
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiModel {
+    pub name: String,
+    pub fields: Vec<ApiField>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiEndpoint {
+    pub method: String,
+    pub path: String,
+    /// camelCase function name
+    pub name: String,
+    pub summary: Option<String>,
+    pub request: Option<FieldType>,
+    pub response: Option<FieldType>,
+}
+
+impl ApiEndpoint {
+    /// `{id}` / `:id` path parameters in order
+    pub fn path_params(&self) -> Vec<String> {
+        self.path
+            .split('/')
+            .filter_map(|seg| {
+                seg.strip_prefix('{')
+                    .and_then(|s| s.strip_suffix('}'))
+                    .or_else(|| seg.strip_prefix(':'))
+                    .map(str::to_string)
+            })
+            .collect()
+    }
+}
+
+/// Parsed API description
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ApiSpec {
+    /// `info.title` / `info.description` from OpenAPI
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub models: Vec<ApiModel>,
+    pub endpoints: Vec<ApiEndpoint>,
+}
+
+/// Target language for generated code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientLanguage {
+    TypeScript,
+    Rust,
+    Python,
+}
+
+impl std::str::FromStr for ClientLanguage {
+    type Err = AutoFlowError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "ts" | "typescript" | "js" | "javascript" | "javascript/typescript" => Ok(Self::TypeScript),
+            "rust" | "rs" => Ok(Self::Rust),
+            "python" | "py" => Ok(Self::Python),
+            other => Err(AutoFlowError::ValidationError(format!(
+                "No API client generator for '{}' (supported: typescript, rust, python)",
+                other
+            ))),
+        }
+    }
+}
+
+/// A generated file, relative to the project root
+#[derive(Debug, Clone)]
+pub struct GeneratedFile {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// Find the API description: an OpenAPI file if present, else API_SPEC.md
+pub fn find_api_spec(project_path: &Path) -> Option<PathBuf> {
+    for dir in [project_path.to_path_buf(), project_path.join(".autoflow/docs")] {
+        for name in OPENAPI_CANDIDATES {
+            let path = dir.join(name);
+            if path.exists() {
+                return Some(path);
+            }
+        }
+    }
+    let md = project_path.join(API_SPEC_MD);
+    md.exists().then_some(md)
+}
+
+/// Parse a spec file, choosing the parser from its extension
+pub fn load_api_spec(path: &Path) -> Result<ApiSpec> {
+    let content = fs::read_to_string(path)?;
+    if path.extension().is_some_and(|e| e == "md") {
+        Ok(parse_markdown_spec(&content))
+    } else {
+        parse_openapi(&content)
+    }
+}
+
+/// Generate client code for the project and write it
+///
+/// `language` defaults to the language detected by the codebase analyzer.
+pub fn generate_api_client(
+    project_path: &Path,
+    spec_path: Option<&Path>,
+    language: Option<ClientLanguage>,
+) -> Result<Vec<PathBuf>> {
+    let spec_path = match spec_path {
+        Some(p) => p.to_path_buf(),
+        None => find_api_spec(project_path).ok_or_else(|| {
+            AutoFlowError::ValidationError("No OpenAPI file or API_SPEC.md found".to_string())
+        })?,
+    };
+    let language = match language {
+        Some(l) => l,
+        None => CodebaseAnalyzer::new(project_path).analyze()?.tech_stack.language.parse()?,
+    };
+
+    let spec = load_api_spec(&spec_path)?;
+    if spec.endpoints.is_empty() && spec.models.is_empty() {
+        return Err(AutoFlowError::ValidationError(format!(
+            "No endpoints or models found in {}",
+            spec_path.display()
+        )));
+    }
+
+    let source = spec_path
+        .strip_prefix(project_path)
+        .unwrap_or(&spec_path)
+        .display()
+        .to_string();
+    let files = render_client(&spec, language, &source, project_path.join("src").is_dir());
+
+    let mut written = Vec::new();
+    for file in files {
+        let path = project_path.join(&file.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, file.content)?;
+        written.push(file.path);
+    }
+    Ok(written)
+}
+
+/// Render files for `language`; `has_src` places TypeScript output under src/
+pub fn render_client(spec: &ApiSpec, language: ClientLanguage, source: &str, has_src: bool) -> Vec<GeneratedFile> {
+    match language {
+        ClientLanguage::TypeScript => {
+            let dir = if has_src { "src/api/generated" } else { "api/generated" };
+            vec![
+                GeneratedFile { path: PathBuf::from(dir).join("types.ts"), content: typescript_types(spec, source) },
+                GeneratedFile { path: PathBuf::from(dir).join("client.ts"), content: typescript_client(spec, source) },
+            ]
+        }
+        ClientLanguage::Rust => vec![GeneratedFile {
+            path: PathBuf::from("src/api_types.rs"),
+            content: rust_types(spec, source),
+        }],
+        ClientLanguage::Python => vec![
+            GeneratedFile { path: PathBuf::from("api_client/__init__.py"), content: String::new() },
+            GeneratedFile { path: PathBuf::from("api_client/types.py"), content: python_types(spec, source) },
+            GeneratedFile { path: PathBuf::from("api_client/client.py"), content: python_client(spec, source) },
+        ],
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Parsing
+// ---------------------------------------------------------------------------
+
+/// Parse endpoints from API_SPEC.md
+///
+/// Endpoints are headings like `### POST /v1/auth/login`. A table after a
+/// "Request Body" label becomes the request model; the first JSON block after
+/// a "Response" label is used to infer the response model.
+pub fn parse_markdown_spec(content: &str) -> ApiSpec {
+    #[derive(PartialEq)]
+    enum Expect {
+        Nothing,
+        RequestTable,
+        ResponseJson,
+    }
+
+    let mut spec = ApiSpec::default();
+    let mut expect = Expect::Nothing;
+    let mut request_fields: Vec<ApiField> = Vec::new();
+    let mut json_block: Option<String> = None;
+    // Model names given as "**Request Body** (Name):" / "**Success Response**: `Name`"
+    let mut request_name: Option<String> = None;
+    let mut response_hint: Option<String> = None;
+
+    fn finish(spec: &mut ApiSpec, fields: &mut Vec<ApiField>, name: &mut Option<String>) {
+        if let Some(endpoint) = spec.endpoints.last_mut() {
+            if !fields.is_empty() && endpoint.request.is_none() {
+                let name = name.take().unwrap_or_else(|| format!("{}Request", pascal_case(&endpoint.name)));
+                endpoint.request = Some(register_model(&mut spec.models, &name, std::mem::take(fields)));
+            }
+        }
+        fields.clear();
+        *name = None;
+    }
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if let Some(block) = json_block.as_mut() {
+            if trimmed.starts_with("```") {
+                let block = json_block.take().unwrap_or_default();
+                if let Ok(value) = serde_json::from_str::<serde_json::Value>(&block) {
+                    if let Some(endpoint) = spec.endpoints.last_mut() {
+                        if endpoint.response.is_none() {
+                            let name = format!("{}Response", pascal_case(&endpoint.name));
+                            let ty = match response_hint.take().map(|h| parse_type_name(&h)) {
+                                Some(FieldType::Ref(model)) => infer_json_type(&value, &model, &mut spec.models),
+                                Some(FieldType::Array(inner)) => match (*inner, value.as_array().and_then(|a| a.first())) {
+                                    (FieldType::Ref(model), Some(item)) => FieldType::Array(Box::new(
+                                        infer_json_type(item, &model, &mut spec.models),
+                                    )),
+                                    _ => infer_json_type(&value, &name, &mut spec.models),
+                                },
+                                _ => infer_json_type(&value, &name, &mut spec.models),
+                            };
+                            endpoint.response = Some(ty);
+                        }
+                    }
+                }
+                expect = Expect::Nothing;
+            } else {
+                block.push_str(line);
+                block.push('\n');
+            }
+            continue;
+        }
+
+        if trimmed.starts_with('#') {
+            let heading = trimmed.trim_start_matches('#').trim().replace('`', "");
+            let mut parts = heading.split_whitespace();
+            if let (Some(method), Some(path)) = (parts.next(), parts.next()) {
+                let method = method.to_uppercase();
+                if HTTP_METHODS.contains(&method.as_str()) && path.starts_with('/') {
+                    finish(&mut spec, &mut request_fields, &mut request_name);
+                    spec.endpoints.push(ApiEndpoint {
+                        name: endpoint_name(&method, path),
+                        method,
+                        path: path.to_string(),
+                        summary: None,
+                        request: None,
+                        response: None,
+                    });
+                    expect = Expect::Nothing;
+                    continue;
+                }
+            }
+            if spec.endpoints.last().is_some() {
+                // Any other heading ends the endpoint's section
+                finish(&mut spec, &mut request_fields, &mut request_name);
+                expect = Expect::Nothing;
+            }
+            continue;
+        }
+
+        if spec.endpoints.is_empty() {
+            continue;
+        }
+
+        let lower = trimmed.to_lowercase();
+        if let Some(description) = trimmed.strip_prefix("**Description**:") {
+            if let Some(endpoint) = spec.endpoints.last_mut() {
+                endpoint.summary = Some(description.trim().to_string());
+            }
+            continue;
+        }
+        if lower.contains("request body") {
+            request_name = trimmed
+                .split_once('(')
+                .and_then(|(_, rest)| rest.split_once(')'))
+                .map(|(name, _)| name.trim().to_string())
+                .filter(|name| matches!(parse_type_name(name), FieldType::Ref(_)));
+            expect = Expect::RequestTable;
+            continue;
+        }
+        if lower.contains("response") && (lower.starts_with("**") || lower.starts_with("response")) && !lower.contains("error") {
+            finish(&mut spec, &mut request_fields, &mut request_name);
+            response_hint = trimmed.split('`').nth(1).map(str::to_string);
+            expect = Expect::ResponseJson;
+            continue;
+        }
+
+        match expect {
+            Expect::RequestTable if trimmed.starts_with('|') => {
+                let cells: Vec<&str> = trimmed.trim_matches('|').split('|').map(str::trim).collect();
+                let is_header = cells.first().is_some_and(|c| c.eq_ignore_ascii_case("field") || c.eq_ignore_ascii_case("name"));
+                let is_separator = cells.iter().all(|c| c.chars().all(|ch| ch == '-' || ch == ':'));
+                if cells.len() >= 2 && !is_header && !is_separator {
+                    let name = cells[0].trim_matches('`').to_string();
+                    let required = cells.get(2).map(|c| c.to_lowercase().starts_with('y')).unwrap_or(true);
+                    request_fields.push(ApiField { name, ty: parse_type_name(cells[1]), required });
+                }
+            }
+            Expect::RequestTable if !trimmed.is_empty() && !request_fields.is_empty() => {
+                finish(&mut spec, &mut request_fields, &mut request_name);
+                expect = Expect::Nothing;
+            }
+            Expect::ResponseJson if trimmed.starts_with("```") => {
+                json_block = Some(String::new());
+            }
+            _ => {}
+        }
+    }
+    finish(&mut spec, &mut request_fields, &mut request_name);
+    spec
+}
+
+/// Parse an OpenAPI 3 or Swagger 2 document (YAML or JSON)
+pub fn parse_openapi(content: &str) -> Result<ApiSpec> {
+    let doc: Value = serde_yaml::from_str(content)
+        .map_err(|e| AutoFlowError::ValidationError(format!("Invalid OpenAPI document: {}", e)))?;
+    let mut spec = ApiSpec {
+        title: doc.get("info").and_then(|i| i.get("title")).and_then(Value::as_str).map(str::to_string),
+        description: doc.get("info").and_then(|i| i.get("description")).and_then(Value::as_str).map(str::to_string),
+        ..Default::default()
+    };
+
+    let schemas = doc
+        .get("components")
+        .and_then(|c| c.get("schemas"))
+        .or_else(|| doc.get("definitions"));
+    if let Some(Value::Mapping(schemas)) = schemas {
+        for (name, schema) in schemas {
+            // Non-object schemas (enums, aliases) register nothing
+            if let Some(name) = name.as_str() {
+                schema_type(schema, &pascal_case(name), &mut spec.models);
+            }
+        }
+    }
+
+    if let Some(Value::Mapping(paths)) = doc.get("paths") {
+        for (path, item) in paths {
+            let (Some(path), Value::Mapping(item)) = (path.as_str(), item) else { continue };
+            for (method, op) in item {
+                let Some(method) = method.as_str().map(str::to_uppercase) else { continue };
+                if !HTTP_METHODS.contains(&method.as_str()) {
+                    continue;
+                }
+                let name = op
+                    .get("operationId")
+                    .and_then(Value::as_str)
+                    .map(camel_case)
+                    .unwrap_or_else(|| endpoint_name(&method, path));
+                let base = pascal_case(&name);
+
+                let request = op
+                    .get("requestBody")
+                    .and_then(|b| b.get("content"))
+                    .and_then(|c| c.get("application/json"))
+                    .and_then(|j| j.get("schema"))
+                    .or_else(|| {
+                        op.get("parameters")
+                            .and_then(Value::as_sequence)
+                            .and_then(|ps| ps.iter().find(|p| p.get("in").and_then(Value::as_str) == Some("body")))
+                            .and_then(|p| p.get("schema"))
+                    })
+                    .map(|s| schema_type(s, &format!("{}Request", base), &mut spec.models));
+
+                let response = op
+                    .get("responses")
+                    .and_then(|r| r.get("200").or_else(|| r.get("201")).or_else(|| r.get(200)).or_else(|| r.get(201)))
+                    .and_then(|r| {
+                        r.get("content")
+                            .and_then(|c| c.get("application/json"))
+                            .and_then(|j| j.get("schema"))
+                            .or_else(|| r.get("schema"))
+                    })
+                    .map(|s| schema_type(s, &format!("{}Response", base), &mut spec.models));
+
+                let summary = op
+                    .get("summary")
+                    .or_else(|| op.get("description"))
+                    .and_then(Value::as_str)
+                    .map(|s| s.trim().to_string());
+
+                spec.endpoints.push(ApiEndpoint {
+                    method,
+                    path: path.to_string(),
+                    name,
+                    summary,
+                    request,
+                    response,
+                });
+            }
+        }
+    }
+
+    Ok(spec)
+}
+
+/// Convert a JSON schema to a field type, registering inline objects as models
+fn schema_type(schema: &Value, name_hint: &str, models: &mut Vec<ApiModel>) -> FieldType {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return FieldType::Ref(pascal_case(reference.rsplit('/').next().unwrap_or(reference)));
+    }
+    match schema.get("type").and_then(Value::as_str) {
+        Some("string") => FieldType::String,
+        Some("integer") => FieldType::Integer,
+        Some("number") => FieldType::Number,
+        Some("boolean") => FieldType::Boolean,
+        Some("array") => {
+            let items = schema
+                .get("items")
+                .map(|i| schema_type(i, &format!("{}Item", name_hint), models))
+                .unwrap_or(FieldType::Any);
+            FieldType::Array(Box::new(items))
+        }
+        _ => {
+            let Some(Value::Mapping(props)) = schema.get("properties") else {
+                return FieldType::Any;
+            };
+            let required: Vec<&str> = schema
+                .get("required")
+                .and_then(Value::as_sequence)
+                .map(|r| r.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            let mut fields = Vec::new();
+            for (field, field_schema) in props {
+                let Some(field) = field.as_str() else { continue };
+                let hint = format!("{}{}", name_hint, pascal_case(field));
+                fields.push(ApiField {
+                    name: field.to_string(),
+                    ty: schema_type(field_schema, &hint, models),
+                    required: required.contains(&field),
+                });
+            }
+            register_model(models, name_hint, fields)
+        }
+    }
+}
+
+/// Infer a type from example JSON, registering objects as models
+fn infer_json_type(value: &serde_json::Value, name_hint: &str, models: &mut Vec<ApiModel>) -> FieldType {
+    use serde_json::Value as J;
+    match value {
+        J::String(_) => FieldType::String,
+        J::Number(n) if n.is_i64() || n.is_u64() => FieldType::Integer,
+        J::Number(_) => FieldType::Number,
+        J::Bool(_) => FieldType::Boolean,
+        J::Array(items) => FieldType::Array(Box::new(
+            items
+                .first()
+                .map(|i| infer_json_type(i, &format!("{}Item", name_hint), models))
+                .unwrap_or(FieldType::Any),
+        )),
+        J::Object(map) => {
+            let fields = map
+                .iter()
+                .map(|(k, v)| ApiField {
+                    name: k.clone(),
+                    ty: infer_json_type(v, &format!("{}{}", name_hint, pascal_case(k)), models),
+                    required: !v.is_null(),
+                })
+                .collect();
+            register_model(models, name_hint, fields)
+        }
+        J::Null => FieldType::Any,
+    }
+}
+
+fn register_model(models: &mut Vec<ApiModel>, name: &str, fields: Vec<ApiField>) -> FieldType {
+    let name = pascal_case(name);
+    if !models.iter().any(|m| m.name == name) {
+        models.push(ApiModel { name: name.clone(), fields });
+    }
+    FieldType::Ref(name)
+}
+
+/// Map a type name from a markdown table ("string", "integer", "User[]", ...)
+fn parse_type_name(name: &str) -> FieldType {
+    let name = name.trim().trim_matches('`');
+    if let Some(inner) = name.strip_suffix("[]") {
+        return FieldType::Array(Box::new(parse_type_name(inner)));
+    }
+    let lower = name.to_lowercase();
+    if let Some(inner) = lower.strip_prefix("array<").and_then(|s| s.strip_suffix('>')) {
+        return FieldType::Array(Box::new(parse_type_name(inner)));
+    }
+    match lower.as_str() {
+        "string" | "str" | "text" | "uuid" | "email" | "url" | "date" | "datetime" | "timestamp" | "password" => FieldType::String,
+        "integer" | "int" | "long" | "int32" | "int64" => FieldType::Integer,
+        "number" | "float" | "double" | "decimal" => FieldType::Number,
+        "boolean" | "bool" => FieldType::Boolean,
+        "array" | "list" => FieldType::Array(Box::new(FieldType::Any)),
+        _ if name.chars().next().is_some_and(char::is_uppercase) && name.chars().all(char::is_alphanumeric) => {
+            FieldType::Ref(name.to_string())
+        }
+        _ => FieldType::Any,
+    }
+}
+
+/// `POST /v1/users/{id}/avatar` -> `postUsersByIdAvatar`
+fn endpoint_name(method: &str, path: &str) -> String {
+    let mut name = method.to_lowercase();
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        let is_version = segment.len() > 1
+            && segment.starts_with('v')
+            && segment[1..].chars().all(|c| c.is_ascii_digit());
+        if is_version || segment == "api" {
+            continue;
+        }
+        let param = segment
+            .strip_prefix('{')
+            .and_then(|s| s.strip_suffix('}'))
+            .or_else(|| segment.strip_prefix(':'));
+        match param {
+            Some(param) => {
+                name.push_str("By");
+                name.push_str(&pascal_case(param));
+            }
+            None => name.push_str(&pascal_case(segment)),
+        }
+    }
+    name
+}
+
+fn words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for c in s.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn pascal_case(s: &str) -> String {
+    words(s)
+        .iter()
+        .map(|w| {
+            let mut chars = w.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn camel_case(s: &str) -> String {
+    let pascal = pascal_case(s);
+    let mut chars = pascal.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn snake_case(s: &str) -> String {
+    words(s).iter().map(|w| w.to_lowercase()).collect::<Vec<_>>().join("_")
+}
+
+// ---------------------------------------------------------------------------
+// Emitters
+// ---------------------------------------------------------------------------
+
+fn header(comment: &str, source: &str) -> String {
+    format!(
+        "{} Generated by AutoFlow from {}. Do not edit; rerun `autoflow generate api-client`.\n",
+        comment, source
+    )
+}
+
+fn ts_type(ty: &FieldType, prefix: &str) -> String {
+    match ty {
+        FieldType::String => "string".to_string(),
+        FieldType::Integer | FieldType::Number => "number".to_string(),
+        FieldType::Boolean => "boolean".to_string(),
+        FieldType::Array(inner) => format!("{}[]", ts_type(inner, prefix)),
+        FieldType::Ref(name) => format!("{}{}", prefix, name),
+        FieldType::Any => "unknown".to_string(),
+    }
+}
+
+fn typescript_types(spec: &ApiSpec, source: &str) -> String {
+    let mut out = header("//", source);
+    for model in &spec.models {
+        out.push_str(&format!("\nexport interface {} {{\n", model.name));
+        for field in &model.fields {
+            let name = if field.name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                field.name.clone()
+            } else {
+                format!("'{}'", field.name)
+            };
+            let optional = if field.required { "" } else { "?" };
+            out.push_str(&format!("  {}{}: {};\n", name, optional, ts_type(&field.ty, "")));
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+fn typescript_client(spec: &ApiSpec, source: &str) -> String {
+    let mut out = header("//", source);
+    out.push_str("import type * as T from './types';\n\n");
+    out.push_str(
+        "export class ApiClient {\n  constructor(private baseUrl: string, private fetchImpl: typeof fetch = fetch) {}\n\n  private async request<R>(method: string, path: string, body?: unknown): Promise<R> {\n    const res = await this.fetchImpl(this.baseUrl + path, {\n      method,\n      headers: body === undefined ? {} : { 'Content-Type': 'application/json' },\n      body: body === undefined ? undefined : JSON.stringify(body),\n    });\n    if (!res.ok) {\n      throw new Error(`${method} ${path} failed with ${res.status}`);\n    }\n    return (res.status === 204 ? undefined : await res.json()) as R;\n  }\n",
+    );
+    for endpoint in &spec.endpoints {
+        let params = endpoint.path_params();
+        let mut args: Vec<String> = params.iter().map(|p| format!("{}: string | number", camel_case(p))).collect();
+        if let Some(request) = &endpoint.request {
+            args.push(format!("body: {}", ts_type(request, "T.")));
+        }
+        let mut path = endpoint.path.clone();
+        for p in &params {
+            let placeholder = format!("${{encodeURIComponent(String({}))}}", camel_case(p));
+            path = path.replace(&format!("{{{}}}", p), &placeholder).replace(&format!(":{}", p), &placeholder);
+        }
+        let response = endpoint.response.as_ref().map(|r| ts_type(r, "T.")).unwrap_or_else(|| "void".to_string());
+        let body = if endpoint.request.is_some() { ", body" } else { "" };
+        out.push_str(&format!(
+            "\n  {}({}): Promise<{}> {{\n    return this.request('{}', `{}`{});\n  }}\n",
+            endpoint.name,
+            args.join(", "),
+            response,
+            endpoint.method,
+            path,
+            body
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn rust_type(ty: &FieldType) -> String {
+    match ty {
+        FieldType::String => "String".to_string(),
+        FieldType::Integer => "i64".to_string(),
+        FieldType::Number => "f64".to_string(),
+        FieldType::Boolean => "bool".to_string(),
+        FieldType::Array(inner) => format!("Vec<{}>", rust_type(inner)),
+        FieldType::Ref(name) => name.clone(),
+        FieldType::Any => "serde_json::Value".to_string(),
+    }
+}
+
+fn rust_types(spec: &ApiSpec, source: &str) -> String {
+    let mut out = header("//", source);
+    out.push_str("use serde::{Deserialize, Serialize};\n");
+    for model in &spec.models {
+        out.push_str(&format!(
+            "\n#[derive(Debug, Clone, Serialize, Deserialize)]\npub struct {} {{\n",
+            model.name
+        ));
+        for field in &model.fields {
+            let mut ident = snake_case(&field.name);
+            if ident == "type" || ident == "ref" || ident == "match" || ident == "self" {
+                ident = format!("r#{}", ident);
+            }
+            if ident.trim_start_matches("r#") != field.name {
+                out.push_str(&format!("    #[serde(rename = \"{}\")]\n", field.name));
+            }
+            if field.required {
+                out.push_str(&format!("    pub {}: {},\n", ident, rust_type(&field.ty)));
+            } else {
+                out.push_str("    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n");
+                out.push_str(&format!("    pub {}: Option<{}>,\n", ident, rust_type(&field.ty)));
+            }
+        }
+        out.push_str("}\n");
+    }
+    if !spec.endpoints.is_empty() {
+        out.push_str("\n/// (method, path) for every endpoint\npub mod endpoints {\n");
+        for endpoint in &spec.endpoints {
+            out.push_str(&format!(
+                "    pub const {}: (&str, &str) = (\"{}\", \"{}\");\n",
+                snake_case(&endpoint.name).to_uppercase(),
+                endpoint.method,
+                endpoint.path
+            ));
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+fn python_type(ty: &FieldType) -> String {
+    match ty {
+        FieldType::String => "str".to_string(),
+        FieldType::Integer => "int".to_string(),
+        FieldType::Number => "float".to_string(),
+        FieldType::Boolean => "bool".to_string(),
+        FieldType::Array(inner) => format!("List[{}]", python_type(inner)),
+        FieldType::Ref(name) => format!("\"{}\"", name),
+        FieldType::Any => "Any".to_string(),
+    }
+}
+
+fn python_types(spec: &ApiSpec, source: &str) -> String {
+    let mut out = header("#", source);
+    out.push_str("from typing import Any, List, TypedDict\n\n");
+    if spec.models.is_empty() {
+        return out;
+    }
+    // Required and optional keys need separate TypedDicts
+    for model in &spec.models {
+        let (required, optional): (Vec<&ApiField>, Vec<&ApiField>) = model.fields.iter().partition(|f| f.required);
+        let base = if optional.is_empty() {
+            "TypedDict".to_string()
+        } else {
+            out.push_str(&format!("\nclass _{}Optional(TypedDict, total=False):\n", model.name));
+            for field in &optional {
+                out.push_str(&format!("    {}: {}\n", field.name, python_type(&field.ty)));
+            }
+            format!("_{}Optional", model.name)
+        };
+        out.push_str(&format!("\nclass {}({}):\n", model.name, base));
+        if required.is_empty() {
+            out.push_str("    pass\n");
+        }
+        for field in &required {
+            out.push_str(&format!("    {}: {}\n", field.name, python_type(&field.ty)));
+        }
+    }
+    out
+}
+
+fn python_client(spec: &ApiSpec, source: &str) -> String {
+    let mut out = header("#", source);
+    out.push_str(
+        "import json\nimport urllib.parse\nimport urllib.request\nfrom typing import Any, Optional\n\n\nclass ApiClient:\n    def __init__(self, base_url: str):\n        self.base_url = base_url.rstrip(\"/\")\n\n    def _request(self, method: str, path: str, body: Optional[Any] = None) -> Any:\n        data = None if body is None else json.dumps(body).encode()\n        headers = {} if body is None else {\"Content-Type\": \"application/json\"}\n        req = urllib.request.Request(self.base_url + path, data=data, method=method, headers=headers)\n        with urllib.request.urlopen(req) as res:\n            payload = res.read()\n            return json.loads(payload) if payload else None\n",
+    );
+    for endpoint in &spec.endpoints {
+        let params = endpoint.path_params();
+        let mut args = vec!["self".to_string()];
+        args.extend(params.iter().map(|p| snake_case(p)));
+        if endpoint.request.is_some() {
+            args.push("body".to_string());
+        }
+        let mut path = endpoint.path.clone();
+        for p in &params {
+            let placeholder = format!("{{urllib.parse.quote(str({}))}}", snake_case(p));
+            path = path.replace(&format!("{{{}}}", p), &placeholder).replace(&format!(":{}", p), &placeholder);
+        }
+        let body = if endpoint.request.is_some() { ", body" } else { "" };
+        out.push_str(&format!(
+            "\n    def {}({}):\n        return self._request(\"{}\", f\"{}\"{})\n",
+            snake_case(&endpoint.name),
+            args.join(", "),
+            endpoint.method,
+            path,
+            body
+        ));
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Markdown docs (OpenAPI import)
+// ---------------------------------------------------------------------------
+
+/// API_SPEC.md seeded from a parsed spec
+///
+/// Uses the same layout `parse_markdown_spec` reads, so client generation
+/// still works if the OpenAPI file is later removed.
+pub fn render_api_spec_markdown(spec: &ApiSpec, source: &str) -> String {
+    let mut out = format!("# {}\n\n", spec.title.as_deref().unwrap_or("API Specification"));
+    if let Some(description) = &spec.description {
+        out.push_str(description.trim());
+        out.push_str("\n\n");
+    }
+    out.push_str(&format!("Imported from `{}`. Data models are described in DATA_MODEL.md.\n", source));
+
+    let mut current_group = String::new();
+    for endpoint in &spec.endpoints {
+        let group = resource_group(&endpoint.path);
+        if group != current_group {
+            out.push_str(&format!("\n## {}\n", pascal_case(&group)));
+            current_group = group;
+        }
+        out.push_str(&format!("\n### {} {}\n", endpoint.method, endpoint.path));
+        if let Some(summary) = &endpoint.summary {
+            out.push_str(&format!("**Description**: {}\n", summary));
+        }
+        match &endpoint.request {
+            Some(FieldType::Ref(name)) => {
+                let fields = spec.models.iter().find(|m| &m.name == name).map(|m| m.fields.as_slice());
+                out.push_str(&format!("\n**Request Body** ({}):\n", name));
+                out.push_str(&field_table(fields.unwrap_or_default()));
+            }
+            Some(other) => out.push_str(&format!("\n**Request Body**: `{}`\n", type_label(other))),
+            None => {}
+        }
+        if let Some(response) = &endpoint.response {
+            let example = example_json(response, &spec.models, 0);
+            out.push_str(&format!(
+                "\n**Success Response** (200): `{}`\n```json\n{}\n```\n",
+                type_label(response),
+                serde_json::to_string_pretty(&example).unwrap_or_default()
+            ));
+        }
+    }
+    out
+}
+
+/// DATA_MODEL.md seeded from the spec's schemas
+pub fn render_data_model_markdown(spec: &ApiSpec) -> String {
+    let mut out = String::from("# Data Model\n\nEntities from the imported API schemas.\n");
+    for model in &spec.models {
+        out.push_str(&format!("\n## {}\n\n", model.name));
+        out.push_str(&field_table(&model.fields));
+    }
+    out
+}
+
+/// One line per endpoint, used to steer sprint planning
+pub fn endpoint_checklist(spec: &ApiSpec) -> String {
+    spec.endpoints
+        .iter()
+        .map(|e| match &e.summary {
+            Some(summary) => format!("- {} {} - {}\n", e.method, e.path, summary),
+            None => format!("- {} {}\n", e.method, e.path),
+        })
+        .collect()
+}
+
+fn field_table(fields: &[ApiField]) -> String {
+    let mut out = String::from("| Field | Type | Required |\n|-------|------|----------|\n");
+    for field in fields {
+        out.push_str(&format!(
+            "| {} | {} | {} |\n",
+            field.name,
+            type_label(&field.ty),
+            if field.required { "Yes" } else { "No" }
+        ));
+    }
+    out
+}
+
+/// Type as written in doc tables; read back by `parse_type_name`
+fn type_label(ty: &FieldType) -> String {
+    match ty {
+        FieldType::String => "string".to_string(),
+        FieldType::Integer => "integer".to_string(),
+        FieldType::Number => "number".to_string(),
+        FieldType::Boolean => "boolean".to_string(),
+        FieldType::Array(inner) => format!("{}[]", type_label(inner)),
+        FieldType::Ref(name) => name.clone(),
+        FieldType::Any => "object".to_string(),
+    }
+}
+
+/// First path segment after any `/api` or `/v1` prefix
+fn resource_group(path: &str) -> String {
+    path.split('/')
+        .filter(|s| !s.is_empty() && *s != "api")
+        .find(|s| !(s.len() > 1 && s.starts_with('v') && s[1..].chars().all(|c| c.is_ascii_digit())))
+        .unwrap_or("root")
+        .trim_matches(|c| c == '{' || c == '}')
+        .to_string()
+}
+
+/// Example payload for a type; recursion is cut off for self-referencing models
+fn example_json(ty: &FieldType, models: &[ApiModel], depth: usize) -> serde_json::Value {
+    use serde_json::Value as J;
+    match ty {
+        FieldType::String => J::from("string"),
+        FieldType::Integer => J::from(1),
+        FieldType::Number => J::from(1.5),
+        FieldType::Boolean => J::from(true),
+        FieldType::Array(inner) => J::Array(vec![example_json(inner, models, depth + 1)]),
+        FieldType::Ref(name) if depth < 4 => match models.iter().find(|m| &m.name == name) {
+            Some(model) => J::Object(
+                model
+                    .fields
+                    .iter()
+                    .map(|f| (f.name.clone(), example_json(&f.ty, models, depth + 1)))
+                    .collect(),
+            ),
+            None => J::Object(Default::default()),
+        },
+        FieldType::Ref(_) | FieldType::Any => J::Object(Default::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MARKDOWN: &str = r#"# API Specification
+
+## Authentication
+
+### POST /v1/auth/login
+**Description**: Authenticate user
+
+**Request Body**:
+| Field | Type | Required | Description |
+|-------|------|----------|-------------|
+| email | string | Yes | User email |
+| remember | boolean | No | Keep session |
+
+**Success Response** (200):
+```json
+{"token": "abc", "expiresIn": 3600, "user": {"id": "u1", "name": "Jo"}}
+```
+
+**Error Responses**:
+- **401 Unauthorized**
+
+### GET /v1/users/{id}
+**Success Response** (200):
+```json
+{"id": "u1", "tags": ["a"]}
+```
+"#;
+
+    #[test]
+    fn test_parse_markdown_spec() {
+        let spec = parse_markdown_spec(MARKDOWN);
+        assert_eq!(spec.endpoints.len(), 2);
+
+        let login = &spec.endpoints[0];
+        assert_eq!(login.name, "postAuthLogin");
+        assert_eq!(login.request, Some(FieldType::Ref("PostAuthLoginRequest".to_string())));
+        let request = spec.models.iter().find(|m| m.name == "PostAuthLoginRequest").unwrap();
+        assert_eq!(request.fields.len(), 2);
+        assert!(!request.fields[1].required);
+
+        let response = spec.models.iter().find(|m| m.name == "PostAuthLoginResponse").unwrap();
+        assert!(response.fields.iter().any(|f| f.name == "expiresIn" && f.ty == FieldType::Integer));
+        assert!(spec.models.iter().any(|m| m.name == "PostAuthLoginResponseUser"));
+
+        let get_user = &spec.endpoints[1];
+        assert_eq!(get_user.name, "getUsersById");
+        assert_eq!(get_user.path_params(), vec!["id".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_openapi() {
+        let doc = r#"
+openapi: 3.0.0
+paths:
+  /pets/{petId}:
+    get:
+      operationId: show_pet
+      responses:
+        '200':
+          content:
+            application/json:
+              schema:
+                $ref: '#/components/schemas/Pet'
+  /pets:
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: '#/components/schemas/Pet'
+      responses:
+        '201':
+          description: created
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [id]
+      properties:
+        id: {type: integer}
+        tags: {type: array, items: {type: string}}
+"#;
+        let spec = parse_openapi(doc).unwrap();
+        let pet = spec.models.iter().find(|m| m.name == "Pet").unwrap();
+        assert!(pet.fields[0].required);
+        assert_eq!(pet.fields[1].ty, FieldType::Array(Box::new(FieldType::String)));
+
+        let show = spec.endpoints.iter().find(|e| e.name == "showPet").unwrap();
+        assert_eq!(show.response, Some(FieldType::Ref("Pet".to_string())));
+        let create = spec.endpoints.iter().find(|e| e.method == "POST").unwrap();
+        assert_eq!(create.name, "postPets");
+        assert_eq!(create.request, Some(FieldType::Ref("Pet".to_string())));
+    }
+
+    #[test]
+    fn test_typescript_output() {
+        let spec = parse_markdown_spec(MARKDOWN);
+        let files = render_client(&spec, ClientLanguage::TypeScript, "API_SPEC.md", true);
+        assert_eq!(files[0].path, PathBuf::from("src/api/generated/types.ts"));
+        assert!(files[0].content.contains("export interface PostAuthLoginRequest {\n  email: string;\n  remember?: boolean;\n}"));
+        assert!(files[1].content.contains("postAuthLogin(body: T.PostAuthLoginRequest): Promise<T.PostAuthLoginResponse>"));
+        assert!(files[1].content.contains("`/v1/users/${encodeURIComponent(String(id))}`"));
+    }
+
+    #[test]
+    fn test_rust_output_renames_fields() {
+        let spec = parse_markdown_spec(MARKDOWN);
+        let rust = rust_types(&spec, "API_SPEC.md");
+        assert!(rust.contains("#[serde(rename = \"expiresIn\")]\n    pub expires_in: i64,"));
+        assert!(rust.contains("pub const GET_USERS_BY_ID: (&str, &str) = (\"GET\", \"/v1/users/{id}\");"));
+    }
+
+    #[test]
+    fn test_imported_markdown_round_trips() {
+        let mut spec = ApiSpec {
+            title: Some("Pet Store".to_string()),
+            ..Default::default()
+        };
+        spec.models.push(ApiModel {
+            name: "Pet".to_string(),
+            fields: vec![
+                ApiField { name: "id".to_string(), ty: FieldType::Integer, required: true },
+                ApiField { name: "tags".to_string(), ty: FieldType::Array(Box::new(FieldType::String)), required: false },
+            ],
+        });
+        spec.endpoints.push(ApiEndpoint {
+            method: "POST".to_string(),
+            path: "/v1/pets".to_string(),
+            name: "createPet".to_string(),
+            summary: Some("Create a pet".to_string()),
+            request: Some(FieldType::Ref("Pet".to_string())),
+            response: Some(FieldType::Array(Box::new(FieldType::Ref("Pet".to_string())))),
+        });
+
+        let markdown = render_api_spec_markdown(&spec, "openapi.yaml");
+        assert!(markdown.starts_with("# Pet Store"));
+        assert!(markdown.contains("## Pets\n\n### POST /v1/pets"));
+
+        let parsed = parse_markdown_spec(&markdown);
+        let endpoint = &parsed.endpoints[0];
+        assert_eq!(endpoint.summary.as_deref(), Some("Create a pet"));
+        assert_eq!(endpoint.request, Some(FieldType::Ref("Pet".to_string())));
+        assert_eq!(endpoint.response, Some(FieldType::Array(Box::new(FieldType::Ref("Pet".to_string())))));
+        assert_eq!(parsed.models, spec.models);
+
+        assert!(render_data_model_markdown(&spec).contains("## Pet\n\n| Field | Type | Required |"));
+        assert_eq!(endpoint_checklist(&spec), "- POST /v1/pets - Create a pet\n");
+    }
+}
@@ -0,0 +1,188 @@
+// Registry of past bug investigations, used to spot duplicate `autoflow fix` runs
+
+use autoflow_data::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Keyword index of investigations, next to the bug reports
+pub const BUG_INDEX: &str = ".autoflow/bugs/index.yml";
+
+/// Keyword overlap (Jaccard) at which a past bug counts as a likely duplicate
+pub const DUPLICATE_THRESHOLD: f64 = 0.4;
+
+/// Findings carried into a follow-up investigation
+const MAX_FINDINGS_CHARS: usize = 4000;
+
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "can", "cannot", "does", "doesn",
+    "for", "from", "has", "have", "in", "is", "isn", "it", "its", "not", "of", "on", "or",
+    "the", "this", "that", "to", "when", "where", "with", "won", "after", "before", "bug",
+    "issue", "error", "broken", "work", "working", "works", "fix", "should", "there", "aren",
+    "don", "didn", "wasn",
+];
+
+/// One investigated bug
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BugRecord {
+    /// Slug shared with the bugfix worktree (`bugfix-<id>`)
+    pub id: String,
+    pub description: String,
+    pub keywords: Vec<String>,
+    /// Analysis files under .autoflow/bugs/, oldest first
+    #[serde(default)]
+    pub reports: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl BugRecord {
+    /// Investigation results from the latest report, truncated for agent context
+    pub fn latest_findings(&self, project_path: &Path) -> Option<String> {
+        let report = std::fs::read_to_string(project_path.join(self.reports.last()?)).ok()?;
+        let findings = report
+            .split_once("## Investigation Results")
+            .map(|(_, rest)| rest.split("\n## ").next().unwrap_or(rest))
+            .unwrap_or(&report)
+            .trim();
+        if findings.len() <= MAX_FINDINGS_CHARS {
+            return Some(findings.to_string());
+        }
+        let mut end = MAX_FINDINGS_CHARS;
+        while !findings.is_char_boundary(end) {
+            end -= 1;
+        }
+        Some(format!("{}\n... (truncated, see {})", &findings[..end], self.reports.last()?))
+    }
+}
+
+/// `.autoflow/bugs/index.yml`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BugRegistry {
+    #[serde(default)]
+    pub bugs: Vec<BugRecord>,
+}
+
+impl BugRegistry {
+    pub fn path(project_path: &Path) -> PathBuf {
+        project_path.join(BUG_INDEX)
+    }
+
+    /// Load the index; a missing file is an empty registry
+    pub fn load(project_path: &Path) -> Result<Self> {
+        let path = Self::path(project_path);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_yaml::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, project_path: &Path) -> Result<()> {
+        let path = Self::path(project_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_yaml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Past bugs similar to `description`, best match first
+    pub fn find_similar(&self, description: &str, threshold: f64) -> Vec<(f64, &BugRecord)> {
+        let wanted: BTreeSet<String> = keywords(description).into_iter().collect();
+        let mut matches: Vec<(f64, &BugRecord)> = self
+            .bugs
+            .iter()
+            .map(|bug| {
+                let theirs: BTreeSet<String> = bug.keywords.iter().cloned().collect();
+                (jaccard(&wanted, &theirs), bug)
+            })
+            .filter(|(score, _)| *score >= threshold)
+            .collect();
+        matches.sort_by(|a, b| b.0.total_cmp(&a.0));
+        matches
+    }
+
+    pub fn get(&self, id: &str) -> Option<&BugRecord> {
+        self.bugs.iter().find(|b| b.id == id)
+    }
+
+    /// Add an investigation report, creating the record if needed
+    pub fn record(&mut self, id: &str, description: &str, report: &str) {
+        let now = Utc::now();
+        match self.bugs.iter_mut().find(|b| b.id == id) {
+            Some(bug) => {
+                bug.reports.push(report.to_string());
+                bug.updated_at = now;
+            }
+            None => self.bugs.push(BugRecord {
+                id: id.to_string(),
+                description: description.to_string(),
+                keywords: keywords(description),
+                reports: vec![report.to_string()],
+                created_at: now,
+                updated_at: now,
+            }),
+        }
+    }
+}
+
+/// Normalised, de-duplicated keywords for matching
+pub fn keywords(text: &str) -> Vec<String> {
+    let set: BTreeSet<String> = text
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() > 1 && !STOP_WORDS.contains(w))
+        .map(stem)
+        .collect();
+    set.into_iter().collect()
+}
+
+/// Crude suffix stripping so "buttons"/"button" and "failing"/"fails" match
+fn stem(word: &str) -> String {
+    for suffix in ["ing", "ed", "es", "s"] {
+        if let Some(stripped) = word.strip_suffix(suffix) {
+            if stripped.len() >= 3 {
+                return stripped.to_string();
+            }
+        }
+    }
+    word.to_string()
+}
+
+fn jaccard(a: &BTreeSet<String>, b: &BTreeSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / a.union(b).count() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keywords_drop_stop_words_and_stem() {
+        assert_eq!(
+            keywords("The login buttons aren't working on mobile"),
+            vec!["button", "login", "mobile"]
+        );
+    }
+
+    #[test]
+    fn test_find_similar_ranks_duplicates() {
+        let mut registry = BugRegistry::default();
+        registry.record("login-button-mobile", "Login button not working on mobile", ".autoflow/bugs/bug-1.md");
+        registry.record("csv-export-timeout", "CSV export times out for large reports", ".autoflow/bugs/bug-2.md");
+
+        let matches = registry.find_similar("Login buttons broken on mobile Safari", DUPLICATE_THRESHOLD);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].1.id, "login-button-mobile");
+        assert!(registry.find_similar("Dark mode colours wrong", DUPLICATE_THRESHOLD).is_empty());
+
+        registry.record("login-button-mobile", "ignored", ".autoflow/bugs/bug-3.md");
+        let bug = registry.get("login-button-mobile").unwrap();
+        assert_eq!(bug.reports.len(), 2);
+        assert_eq!(bug.description, "Login button not working on mobile");
+    }
+}
@@ -0,0 +1,145 @@
+// Checkpoints: where each sprint's loop stood after its last phase, for `autoflow resume`
+
+use autoflow_data::{Result, Sprint, SprintStatus};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Written by the orchestrator after every phase
+pub const CHECKPOINT_PATH: &str = ".autoflow/.state/checkpoint.json";
+
+/// One sprint's loop state, as of the end of its last phase
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SprintCheckpoint {
+    /// Status the sprint moved to; in parallel runs SPRINTS.yml only catches up at the end
+    pub status: Option<SprintStatus>,
+    pub iteration: u32,
+    /// Failed attempts at each phase since it last passed
+    #[serde(default)]
+    pub retry_count: HashMap<SprintStatus, u32>,
+    /// Fix phases entered so far, counted against the fix budget
+    #[serde(default)]
+    pub fix_attempts: Vec<SprintStatus>,
+    /// Signature of each validation phase's last failure report
+    #[serde(default)]
+    pub last_failures: HashMap<SprintStatus, String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Contents of `.autoflow/.state/checkpoint.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Run that wrote the checkpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    #[serde(default)]
+    pub sprints: BTreeMap<u32, SprintCheckpoint>,
+}
+
+impl Checkpoint {
+    /// None when there is no checkpoint, or it can't be read
+    pub fn load(project_path: &Path) -> Option<Self> {
+        autoflow_data::read_state_to_string(project_path.join(CHECKPOINT_PATH))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    /// Save, or remove the file once no sprint is left in it
+    pub fn save(&self, project_path: &Path) -> Result<()> {
+        let path = project_path.join(CHECKPOINT_PATH);
+        if self.sprints.is_empty() {
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        autoflow_data::write_state(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// Record `checkpoint` for sprint `id`, keeping the other sprints' entries
+    pub fn update(project_path: &Path, run_id: Option<String>, id: u32, checkpoint: SprintCheckpoint) -> Result<()> {
+        let mut saved = Self::load(project_path).unwrap_or_default();
+        saved.run_id = run_id;
+        saved.sprints.insert(id, checkpoint);
+        saved.save(project_path)
+    }
+
+    /// Forget sprint `id` once its loop has nothing left to resume
+    pub fn clear(project_path: &Path, id: u32) -> Result<()> {
+        let Some(mut saved) = Self::load(project_path) else {
+            return Ok(());
+        };
+        if saved.sprints.remove(&id).is_some() {
+            saved.save(project_path)?;
+        }
+        Ok(())
+    }
+
+    /// Move sprints SPRINTS.yml fell behind on to their checkpointed status
+    ///
+    /// Only a checkpoint newer than the sprint's last update counts: a status
+    /// changed by hand since then wins. Returns the ids of the sprints moved.
+    pub fn apply_statuses(&self, sprints: &mut [Sprint]) -> Vec<u32> {
+        let mut moved = Vec::new();
+        for sprint in sprints.iter_mut() {
+            let Some(saved) = self.sprints.get(&sprint.id) else {
+                continue;
+            };
+            if let (Some(status), Some(updated_at)) = (saved.status, saved.updated_at) {
+                if status != sprint.status && updated_at > sprint.last_updated {
+                    sprint.status = status;
+                    sprint.last_updated = updated_at;
+                    moved.push(sprint.id);
+                }
+            }
+        }
+        moved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let project = std::env::temp_dir().join(format!("autoflow-checkpoint-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&project);
+        std::fs::create_dir_all(&project).unwrap();
+        assert!(Checkpoint::load(&project).is_none());
+
+        let saved = SprintCheckpoint {
+            status: Some(SprintStatus::UnitFix),
+            iteration: 7,
+            retry_count: HashMap::from([(SprintStatus::RunUnitTests, 2)]),
+            fix_attempts: vec![SprintStatus::ReviewFix, SprintStatus::UnitFix],
+            last_failures: HashMap::from([(SprintStatus::RunUnitTests, "0123abcd".to_string())]),
+            updated_at: Some(Utc::now()),
+        };
+        Checkpoint::update(&project, Some("01J9Z".to_string()), 3, saved.clone()).unwrap();
+        Checkpoint::update(&project, Some("01J9Z".to_string()), 4, SprintCheckpoint::default()).unwrap();
+        let loaded = Checkpoint::load(&project).unwrap();
+        assert_eq!(loaded.run_id.as_deref(), Some("01J9Z"));
+        assert_eq!(loaded.sprints[&3], saved);
+
+        // A status the checkpoint got to first wins over a stale SPRINTS.yml, not over a later edit
+        let yaml = "id: 3\ngoal: Cart\nstatus: RUN_UNIT_TESTS\ntotal_effort: 2h\nmax_effort: 4h\n\
+                    last_updated: 2025-01-01T00:00:00Z\ndeliverables: []\ntasks: []\n";
+        let mut sprints: Vec<Sprint> = vec![serde_yaml::from_str(yaml).unwrap()];
+        sprints[0].last_updated = saved.updated_at.unwrap() - chrono::Duration::seconds(5);
+        assert_eq!(loaded.apply_statuses(&mut sprints), [3]);
+        assert_eq!(sprints[0].status, SprintStatus::UnitFix);
+        sprints[0].status = SprintStatus::WriteCode;
+        sprints[0].last_updated = Utc::now();
+        assert!(loaded.apply_statuses(&mut sprints).is_empty());
+
+        Checkpoint::clear(&project, 3).unwrap();
+        Checkpoint::clear(&project, 4).unwrap();
+        assert!(!project.join(CHECKPOINT_PATH).exists());
+        let _ = std::fs::remove_dir_all(&project);
+    }
+}
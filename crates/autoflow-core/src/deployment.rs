@@ -0,0 +1,325 @@
+// Deployment sprints: deploy manifest, native smoke checks and rollback to the last good state
+
+use autoflow_data::{AutoFlowError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+/// Deploy manifest and state live here
+pub const DEPLOY_DIR: &str = ".autoflow/deploy";
+
+/// Manifest with the smoke checks and rollback commands
+pub const DEPLOY_MANIFEST: &str = "deploy.yml";
+
+/// Last good and in-progress deployments, written by AutoFlow
+pub const DEPLOY_STATE: &str = "state.json";
+
+/// Rollback commands see the git ref of the last good deployment here
+pub const ROLLBACK_REF_ENV: &str = "AUTOFLOW_ROLLBACK_REF";
+
+/// Contents of `.autoflow/deploy/deploy.yml`
+///
+/// Rollback commands run with `sh -c` from the project root, with
+/// `AUTOFLOW_ROLLBACK_REF` set to the commit that was last deployed successfully.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployConfig {
+    /// Ask a human before the deploy agent runs
+    #[serde(default = "default_require_approval")]
+    pub require_approval: bool,
+
+    /// URLs checked after the deploy; all must pass
+    #[serde(default)]
+    pub smoke_checks: Vec<SmokeCheck>,
+
+    /// Tries per check, to give the new release time to come up
+    #[serde(default = "default_attempts")]
+    pub attempts: u32,
+
+    /// Pause between tries
+    #[serde(default = "default_retry_delay_secs")]
+    pub retry_delay_secs: u64,
+
+    /// Commands that restore the previous release
+    #[serde(default)]
+    pub rollback: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmokeCheck {
+    pub url: String,
+
+    #[serde(default = "default_expect_status")]
+    pub expect_status: u16,
+
+    /// Text the response body must contain
+    #[serde(default)]
+    pub contains: Option<String>,
+
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_require_approval() -> bool {
+    true
+}
+
+fn default_attempts() -> u32 {
+    5
+}
+
+fn default_retry_delay_secs() -> u64 {
+    10
+}
+
+fn default_expect_status() -> u16 {
+    200
+}
+
+fn default_timeout_secs() -> u64 {
+    10
+}
+
+impl DeployConfig {
+    /// Read the manifest; deployment sprints can't run without smoke checks
+    pub fn load(project_path: &Path) -> Result<Self> {
+        let manifest = project_path.join(DEPLOY_DIR).join(DEPLOY_MANIFEST);
+        if !manifest.exists() {
+            return Err(AutoFlowError::ValidationError(format!(
+                "Deployment sprints need {}/{} with at least one smoke check",
+                DEPLOY_DIR, DEPLOY_MANIFEST
+            )));
+        }
+        let config: DeployConfig = serde_yaml::from_str(&std::fs::read_to_string(&manifest)?)?;
+        if config.smoke_checks.is_empty() {
+            return Err(AutoFlowError::ValidationError(format!(
+                "{}/{} has no smoke_checks",
+                DEPLOY_DIR, DEPLOY_MANIFEST
+            )));
+        }
+        Ok(config)
+    }
+
+    /// Run every smoke check; returns one message per failing check
+    pub async fn run_smoke_checks(&self) -> Vec<String> {
+        let client = reqwest::Client::new();
+        let mut failures = Vec::new();
+        for check in &self.smoke_checks {
+            let mut last_error = String::new();
+            let mut passed = false;
+            for attempt in 1..=self.attempts.max(1) {
+                match check.run(&client).await {
+                    Ok(()) => {
+                        passed = true;
+                        break;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Smoke check {} failed (attempt {}/{}): {}", check.url, attempt, self.attempts, e);
+                        last_error = e;
+                    }
+                }
+                if attempt < self.attempts {
+                    tokio::time::sleep(Duration::from_secs(self.retry_delay_secs)).await;
+                }
+            }
+            if !passed {
+                failures.push(format!("{}: {}", check.url, last_error));
+            }
+        }
+        failures
+    }
+
+    /// Restore the last good deployment; returns the ref rolled back to
+    pub fn rollback(&self, project_path: &Path, state: &DeployState) -> Result<Option<String>> {
+        let target = state.last_good.as_ref().map(|r| r.git_ref.clone());
+        if self.rollback.is_empty() {
+            return Err(AutoFlowError::ValidationError(format!(
+                "No rollback commands in {}/{}; restore {} manually",
+                DEPLOY_DIR,
+                DEPLOY_MANIFEST,
+                target.as_deref().unwrap_or("the previous release")
+            )));
+        }
+        for command in &self.rollback {
+            tracing::info!("Rollback: {}", command);
+            let output = Command::new("sh")
+                .args(["-c", command])
+                .current_dir(project_path)
+                .env(ROLLBACK_REF_ENV, target.as_deref().unwrap_or_default())
+                .output()?;
+            if !output.status.success() {
+                return Err(AutoFlowError::CommandFailed(format!(
+                    "Rollback command failed: {}\n{}",
+                    command,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+        }
+        Ok(target)
+    }
+}
+
+impl SmokeCheck {
+    async fn run(&self, client: &reqwest::Client) -> std::result::Result<(), String> {
+        let response = client
+            .get(&self.url)
+            .timeout(Duration::from_secs(self.timeout_secs))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = response.status().as_u16();
+        if status != self.expect_status {
+            return Err(format!("expected HTTP {}, got {}", self.expect_status, status));
+        }
+        if let Some(ref expected) = self.contains {
+            let body = response.text().await.map_err(|e| e.to_string())?;
+            if !body.contains(expected.as_str()) {
+                return Err(format!("response does not contain {:?}", expected));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One deployment of the project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployRecord {
+    pub git_ref: String,
+    pub sprint: u32,
+    pub deployed_at: DateTime<Utc>,
+}
+
+/// Contents of `.autoflow/deploy/state.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeployState {
+    /// Last deployment that passed its smoke checks; the rollback target
+    #[serde(default)]
+    pub last_good: Option<DeployRecord>,
+
+    /// Deployment started but not yet verified
+    #[serde(default)]
+    pub in_progress: Option<DeployRecord>,
+}
+
+impl DeployState {
+    pub fn load(project_path: &Path) -> Self {
+        std::fs::read_to_string(project_path.join(DEPLOY_DIR).join(DEPLOY_STATE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, project_path: &Path) -> Result<()> {
+        let dir = project_path.join(DEPLOY_DIR);
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join(DEPLOY_STATE), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Record the commit about to be deployed, keeping the last good one as the rollback target
+    pub fn begin(&mut self, sprint: u32, git_ref: String) {
+        self.in_progress = Some(DeployRecord { git_ref, sprint, deployed_at: Utc::now() });
+    }
+
+    /// Smoke checks passed: the in-progress deployment becomes the rollback target
+    pub fn mark_good(&mut self) {
+        if let Some(record) = self.in_progress.take() {
+            self.last_good = Some(record);
+        }
+    }
+
+    /// Rolled back: forget the failed deployment
+    pub fn abandon(&mut self) {
+        self.in_progress = None;
+    }
+}
+
+/// Commit checked out in `project_path`
+pub fn head_ref(project_path: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(project_path)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Serve `responses` (status, body) in order, one per connection
+    fn serve(responses: Vec<(u16, &'static str)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf);
+                let response = format!(
+                    "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        format!("http://{}/health", addr)
+    }
+
+    fn config(url: String, attempts: u32) -> DeployConfig {
+        DeployConfig {
+            require_approval: true,
+            smoke_checks: vec![SmokeCheck {
+                url,
+                expect_status: 200,
+                contains: Some("ok".to_string()),
+                timeout_secs: 5,
+            }],
+            attempts,
+            retry_delay_secs: 0,
+            rollback: vec!["echo \"$AUTOFLOW_ROLLBACK_REF\" > rolled-back.txt".to_string()],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_smoke_checks_retry_then_report_failures() {
+        // Comes up on the second try
+        let url = serve(vec![(503, "starting"), (200, "status: ok")]);
+        assert!(config(url, 2).run_smoke_checks().await.is_empty());
+
+        let url = serve(vec![(200, "degraded")]);
+        let failures = config(url, 1).run_smoke_checks().await;
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].contains("does not contain"));
+    }
+
+    #[test]
+    fn test_rollback_targets_last_good_deployment() {
+        let project = std::env::temp_dir().join(format!("autoflow-deploy-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&project);
+        fs::create_dir_all(&project).unwrap();
+
+        let mut state = DeployState::default();
+        state.begin(3, "aaa111".to_string());
+        state.mark_good();
+        state.begin(4, "bbb222".to_string());
+        state.save(&project).unwrap();
+
+        let state = DeployState::load(&project);
+        let target = config(String::new(), 1).rollback(&project, &state).unwrap();
+        assert_eq!(target.as_deref(), Some("aaa111"));
+        assert_eq!(fs::read_to_string(project.join("rolled-back.txt")).unwrap(), "aaa111\n");
+
+        let _ = fs::remove_dir_all(project);
+    }
+}
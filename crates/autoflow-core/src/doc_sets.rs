@@ -0,0 +1,77 @@
+// Doc sets: which project docs an idea needs, so create doesn't run agents for docs that can't apply
+
+use autoflow_data::DocSet;
+
+/// Words that mean the project has a user interface
+const UI_TERMS: &[&str] = &[
+    "ui", "frontend", "front-end", "website", "webapp", "web app", "web application", "dashboard", "page", "pages",
+    "screen", "screens", "browser", "mobile", "react", "vue", "angular", "svelte", "next.js", "tailwind",
+    "user interface", "gui", "desktop app",
+];
+
+/// Words that mean the project serves or consumes an API
+const API_TERMS: &[&str] = &[
+    "api", "apis", "endpoint", "endpoints", "rest", "graphql", "grpc", "backend", "back-end", "server",
+    "http", "webhook", "webhooks", "database",
+];
+
+/// Words that describe a project with neither, e.g. a command-line tool or a library
+const HEADLESS_TERMS: &[&str] = &[
+    "cli", "command-line", "command line", "terminal", "library", "crate", "sdk", "daemon", "script",
+];
+
+/// Doc sets worth generating for an idea
+///
+/// BUILD_SPEC/ARCHITECTURE always apply. UI and API docs are only dropped when
+/// the idea describes a headless project (a CLI, library, daemon...) and says
+/// nothing about a UI or an API; when in doubt they're generated.
+pub fn applicable_doc_sets(idea: &str) -> Vec<DocSet> {
+    let text = normalize(idea);
+    let mentions = |terms: &[&str]| terms.iter().any(|t| text.contains(&format!(" {} ", t)));
+    let headless = mentions(HEADLESS_TERMS);
+
+    let mut sets = vec![DocSet::Build];
+    if !headless || mentions(API_TERMS) {
+        sets.push(DocSet::Api);
+    }
+    if !headless || mentions(UI_TERMS) {
+        sets.push(DocSet::Ui);
+    }
+    sets
+}
+
+/// Lowercase words separated by single spaces, padded so every word has a space either side
+fn normalize(text: &str) -> String {
+    let words: Vec<&str> = text
+        .split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '.'))
+        .map(|w| w.trim_matches('.'))
+        .filter(|w| !w.is_empty())
+        .collect();
+    format!(" {} ", words.join(" ").to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_applicable_doc_sets() {
+        let cli = "# Project Idea\n\nA command-line tool that renames photos by their EXIF date.";
+        assert_eq!(applicable_doc_sets(cli), vec![DocSet::Build]);
+
+        let client = "A CLI for the GitHub REST API that lists stale pull requests.";
+        assert_eq!(applicable_doc_sets(client), vec![DocSet::Build, DocSet::Api]);
+
+        let shop = "An online shop with a React storefront and an admin dashboard.";
+        assert_eq!(applicable_doc_sets(shop), DocSet::ALL.to_vec());
+
+        // Says nothing either way
+        assert_eq!(applicable_doc_sets("Track my reading list."), DocSet::ALL.to_vec());
+
+        // Words inside other words don't count
+        assert_eq!(applicable_doc_sets("A library for building quick restful guides"), vec![DocSet::Build]);
+
+        assert_eq!(DocSet::parse_list("ui, build,api,build"), Ok(DocSet::ALL.to_vec()));
+        assert!(DocSet::parse_list("build,docs").is_err());
+    }
+}
@@ -0,0 +1,197 @@
+// End-of-run documentation sync: bring .autoflow/docs in line with what was built
+
+use crate::analyzer::CodebaseAnalyzer;
+use autoflow_agents::{doc_index, execute_agent, AgentResult};
+use autoflow_data::{AutoFlowError, Result, SprintsYaml};
+use chrono::{DateTime, Utc};
+use std::path::Path;
+use std::process::Command;
+
+/// Timestamp of the last successful docs sync
+pub const DOCS_SYNC_MARKER: &str = ".autoflow/.docs-synced-at";
+
+/// Commit log lines passed to the agent; the rest is summarised
+const MAX_COMMIT_LINES: usize = 400;
+
+/// When the docs were last known to match the code
+///
+/// The last sync if there was one, otherwise the start of the earliest sprint.
+pub fn docs_sync_since(project_path: &Path, plan: &SprintsYaml) -> Option<DateTime<Utc>> {
+    std::fs::read_to_string(project_path.join(DOCS_SYNC_MARKER))
+        .ok()
+        .and_then(|c| DateTime::parse_from_rfc3339(c.trim()).ok())
+        .map(|t| t.with_timezone(&Utc))
+        .or_else(|| plan.sprints.iter().filter_map(|s| s.started).min())
+}
+
+/// Commits (with changed files) since `since`, excluding AutoFlow state
+pub fn recent_commit_summary(project_path: &Path, since: Option<DateTime<Utc>>) -> String {
+    let mut args = vec!["log".to_string(), "--name-status".to_string(), "--format=%h %s".to_string()];
+    if let Some(since) = since {
+        args.push(format!("--since={}", since.to_rfc3339()));
+    } else {
+        args.push("-n".to_string());
+        args.push("50".to_string());
+    }
+    args.extend(["--".to_string(), ".".to_string(), ":(exclude).autoflow/".to_string()]);
+
+    let output = match Command::new("git").current_dir(project_path).args(&args).output() {
+        Ok(output) if output.status.success() => output,
+        _ => return String::new(),
+    };
+
+    let log = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = log.lines().filter(|l| !l.trim().is_empty()).collect();
+    if lines.len() <= MAX_COMMIT_LINES {
+        return lines.join("\n");
+    }
+    format!(
+        "{}\n... {} more lines (run `git log` for the rest)",
+        lines[..MAX_COMMIT_LINES].join("\n"),
+        lines.len() - MAX_COMMIT_LINES
+    )
+}
+
+/// Prompt for the docs-sync agent
+pub fn build_docs_sync_context(
+    plan: &SprintsYaml,
+    since: Option<DateTime<Utc>>,
+    analysis: &str,
+    commits: &str,
+    doc_contents: &[(String, String)],
+) -> String {
+    let completed: Vec<String> = plan
+        .sprints
+        .iter()
+        .filter(|s| s.is_done())
+        .filter(|s| match (since, s.completed_at) {
+            (Some(since), Some(done)) => done >= since,
+            _ => true,
+        })
+        .map(|s| {
+            let mut line = format!("- Sprint {}: {}", s.id, s.goal);
+            if !s.deliverables.is_empty() {
+                line.push_str(&format!(" (deliverables: {})", s.deliverables.join(", ")));
+            }
+            line
+        })
+        .collect();
+
+    let docs: String = doc_contents
+        .iter()
+        .map(|(file, toc)| format!("\n## {}\n\n{}", file, toc))
+        .collect();
+
+    format!(
+        r#"Synchronize the project documentation with the code that was built.
+
+# Completed Sprints
+
+{}
+
+# Recent Commits
+
+```
+{}
+```
+
+# Codebase Analysis
+
+{}
+
+# Documentation (tables of contents)
+
+Docs live in `.autoflow/docs/`. Read only the sections that relate to the changes above.
+{}
+"#,
+        if completed.is_empty() { "None".to_string() } else { completed.join("\n") },
+        commits,
+        analysis,
+        docs
+    )
+}
+
+/// Run the docs-sync agent if anything changed since the last sync
+///
+/// Returns None when there was nothing to sync.
+pub async fn run_docs_sync(project_path: &Path, plan: &SprintsYaml) -> Result<Option<AgentResult>> {
+    let since = docs_sync_since(project_path, plan);
+    let commits = recent_commit_summary(project_path, since);
+    if commits.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let analysis = CodebaseAnalyzer::new(project_path)
+        .analyze()
+        .map(|a| a.to_markdown())
+        .unwrap_or_default();
+
+    let docs_dir = project_path.join(autoflow_agents::doc_index::DOCS_DIR);
+    let mut doc_contents: Vec<(String, String)> = std::fs::read_dir(&docs_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+                .filter_map(|p| {
+                    let index = doc_index(&p)?;
+                    let name = p.file_name()?.to_string_lossy().to_string();
+                    Some((name, index.table_of_contents()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if doc_contents.is_empty() {
+        return Ok(None);
+    }
+    doc_contents.sort();
+
+    let context = build_docs_sync_context(plan, since, &analysis, &commits, &doc_contents);
+    let result = execute_agent("docs-sync", &context, 30, None)
+        .await
+        .map_err(|e| AutoFlowError::AgentExecutionFailed("docs-sync".to_string(), e.to_string()))?;
+
+    if result.success {
+        std::fs::write(project_path.join(DOCS_SYNC_MARKER), Utc::now().to_rfc3339())?;
+    }
+    Ok(Some(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planner::template_sprint;
+    use autoflow_data::{ProjectMetadata, SprintStatus};
+
+    #[test]
+    fn test_context_lists_sprints_completed_since_last_sync() {
+        let since = Utc::now() - chrono::Duration::hours(1);
+        let mut old = template_sprint(1, "Auth");
+        old.status = SprintStatus::Done;
+        old.completed_at = Some(since - chrono::Duration::days(1));
+        let mut new = template_sprint(2, "Billing");
+        new.status = SprintStatus::Done;
+        new.completed_at = Some(Utc::now());
+        let pending = template_sprint(3, "Search");
+
+        let plan = SprintsYaml {
+            project: ProjectMetadata {
+                name: "demo".to_string(),
+                version: "0.1.0".to_string(),
+                description: "Demo".to_string(),
+                total_sprints: 3,
+                current_sprint: None,
+                last_updated: Utc::now(),
+                repos: vec![],
+            },
+            sprints: vec![old, new, pending],
+        };
+
+        let docs = vec![("API_SPEC.md".to_string(), "- Billing\n".to_string())];
+        let context = build_docs_sync_context(&plan, Some(since), "", "abc123 Add billing", &docs);
+        assert!(context.contains("Sprint 2: Billing"));
+        assert!(!context.contains("Sprint 1: Auth"));
+        assert!(!context.contains("Sprint 3"));
+        assert!(context.contains("## API_SPEC.md"));
+    }
+}
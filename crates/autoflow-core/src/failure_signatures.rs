@@ -0,0 +1,135 @@
+// Failure signatures: recognise a fix loop that keeps hitting the same failure
+
+use autoflow_data::SprintStatus;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Failure report a validation phase leaves in `.autoflow/.failures`
+pub fn failure_report_name(status: SprintStatus, sprint_id: u32) -> Option<String> {
+    let kind = match status {
+        SprintStatus::CodeReview => "review",
+        SprintStatus::RunUnitTests => "unit-tests",
+        SprintStatus::RunE2eTests => "integration-tests",
+        _ => return None,
+    };
+    Some(format!("sprint-{}-{}.md", sprint_id, kind))
+}
+
+/// Signature of the failure report a validation phase wrote since `since`, if any
+///
+/// A report left over from an earlier attempt doesn't count: it says nothing
+/// about how this attempt failed.
+pub fn read_failure_signature(
+    project_path: &Path,
+    status: SprintStatus,
+    sprint_id: u32,
+    since: SystemTime,
+) -> Option<String> {
+    let path = project_path.join(".autoflow/.failures").join(failure_report_name(status, sprint_id)?);
+    if std::fs::metadata(&path).and_then(|m| m.modified()).ok()? < since {
+        return None;
+    }
+    failure_signature(&std::fs::read_to_string(path).ok()?)
+}
+
+/// Leave a note that sprint `sprint_id`'s fix loop stopped on `status`
+///
+/// Written next to the failure reports as `sprint-<id>-fix-loop.md`, so it is
+/// listed with them wherever a blocked sprint's reports are. Returns the paths,
+/// relative to `project_path`, of the note and the report that repeated, as the
+/// sprint's `failure_reports` keep them.
+pub fn write_fix_loop_note(project_path: &Path, status: SprintStatus, sprint_id: u32, attempts: u32) -> Option<Vec<String>> {
+    let report = format!(".autoflow/.failures/{}", failure_report_name(status, sprint_id)?);
+    let note = format!(".autoflow/.failures/sprint-{}-fix-loop.md", sprint_id);
+    let content = format!(
+        "# Fix loop stopped - Sprint {}\n\n\
+         {:?} failed the same way twice in a row (attempt {}), so AutoFlow stopped running the fixer \
+         and marked the sprint BLOCKED.\n\n\
+         The failure is in `{}`. The last fix didn't change it, so it likely needs a different approach.\n",
+        sprint_id, status, attempts, report
+    );
+    std::fs::write(project_path.join(&note), content).ok()?;
+    Some(vec![note, report])
+}
+
+/// Hash of a failure report with the parts that change between identical failures removed
+///
+/// Numbers (timings, line numbers, ports, ids, timestamps) and hex addresses
+/// become `#`, case and whitespace are ignored, and HTML comments such as the
+/// run marker are dropped. None for a report with nothing left to compare.
+pub fn failure_signature(report: &str) -> Option<String> {
+    let mut normalized = String::new();
+    for line in report.lines() {
+        let line = line.trim();
+        if line.is_empty() || (line.starts_with("<!--") && line.ends_with("-->")) {
+            continue;
+        }
+        let mut previous_was_number = false;
+        for word in line.split_whitespace() {
+            for c in word.to_lowercase().chars() {
+                let number = c.is_ascii_digit() || (previous_was_number && (c.is_ascii_hexdigit() || c == 'x'));
+                if number {
+                    if !previous_was_number {
+                        normalized.push('#');
+                    }
+                } else {
+                    normalized.push(c);
+                }
+                previous_was_number = number;
+            }
+            normalized.push(' ');
+            previous_was_number = false;
+        }
+        normalized.push('\n');
+    }
+    if normalized.trim().is_empty() {
+        return None;
+    }
+
+    // FNV-1a: stable across builds, unlike std's hasher
+    let hash = normalized
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3));
+    Some(format!("{:016x}", hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_signature() {
+        let first = "<!-- run: 01J9Z -->\n# Unit Test Failures\n\n\
+                     FAILED tests/test_cart.py::test_total - AssertionError: 41 != 42\n\
+                     Ran 120 tests in 3.21s at 0x7f3a2c (line 88)\n";
+        let second = "<!-- run: 01JA0 -->\n# Unit Test Failures\n\n\
+                      FAILED  tests/test_cart.py::test_total - AssertionError: 40 != 42\n\
+                      Ran 121 tests in 2.98s at 0x7f9bbb (line 90)\n";
+        let different = "# Unit Test Failures\n\nFAILED tests/test_cart.py::test_discount - KeyError: 'code'\n";
+
+        assert_eq!(failure_signature(first), failure_signature(second));
+        assert_ne!(failure_signature(first), failure_signature(different));
+        assert_eq!(failure_signature("<!-- run: 01J9Z -->\n\n"), None);
+
+        assert_eq!(
+            failure_report_name(SprintStatus::RunUnitTests, 3).as_deref(),
+            Some("sprint-3-unit-tests.md")
+        );
+        assert_eq!(failure_report_name(SprintStatus::WriteCode, 3), None);
+    }
+
+    #[test]
+    fn test_write_fix_loop_note() {
+        let project = std::env::temp_dir().join(format!("autoflow-fix-loop-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&project);
+        std::fs::create_dir_all(project.join(".autoflow/.failures")).unwrap();
+
+        let reports = write_fix_loop_note(&project, SprintStatus::RunUnitTests, 3, 2).unwrap();
+        assert_eq!(reports, [".autoflow/.failures/sprint-3-fix-loop.md", ".autoflow/.failures/sprint-3-unit-tests.md"]);
+        let note = std::fs::read_to_string(project.join(&reports[0])).unwrap();
+        assert!(note.contains("RunUnitTests failed the same way twice"));
+        assert!(note.contains("`.autoflow/.failures/sprint-3-unit-tests.md`"));
+        assert_eq!(write_fix_loop_note(&project, SprintStatus::WriteCode, 3, 2), None);
+        let _ = std::fs::remove_dir_all(project);
+    }
+}
@@ -0,0 +1,304 @@
+// Failure report summaries: keep a big test suite's report within a fixer's context budget,
+// and optionally add a model-drafted triage note
+
+use autoflow_agents::FAILURE_REPORTS_DIR;
+use autoflow_data::{FailureReportsConfig, Result};
+use std::path::Path;
+
+/// Where the full report goes once its summary takes its place, under [`FAILURE_REPORTS_DIR`]
+pub const FULL_REPORTS_DIR: &str = "full";
+
+/// Groups listed in the summary table; the rest are counted
+const MAX_GROUPS: usize = 30;
+
+/// Report lines before the first failure (title, totals) kept as they are
+const MAX_PREAMBLE_LINES: usize = 15;
+
+/// Text task that drafts a triage note for each failure report, when `[backends]` maps it to a provider
+pub const TRIAGE_TASK: &str = "failure-triage";
+
+/// Heading of the triage note at the end of a report
+const TRIAGE_HEADING: &str = "## Triage";
+
+/// Characters of the report the triage model is shown
+const TRIAGE_INPUT_CHARS: usize = 24_000;
+
+const TRIAGE_SYSTEM: &str = "You triage failure reports from automated tests and code review for the developer who will fix them. \
+     Answer in at most ten short lines of Markdown: the likely root cause, the files to look at first, and whether the test \
+     or the code looks wrong. Only use what the report shows.";
+
+/// Lines that start a failure in common runners' output and in agent-written markdown
+const FAILURE_STARTS: &[&str] = &[
+    "FAILED", "FAIL ", "ERROR ", "ERROR:", "Error:", "error:", "error[", "--- FAIL", "---- ", "not ok ", "●", "✕", "✗", "×", "## ", "### ",
+];
+
+/// Extensions recognised as the file a failure is in
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "py", "ts", "tsx", "js", "jsx", "mjs", "cjs", "go", "java", "kt", "rb", "php", "cs", "swift", "c", "cc", "cpp", "h", "vue",
+    "svelte", "ex", "exs", "scala", "dart",
+];
+
+/// One failure: the line that started it and the trace below it
+struct FailureBlock<'a> {
+    lines: Vec<&'a str>,
+    error: String,
+    file: Option<String>,
+}
+
+/// Failures with the same error type in the same file
+struct FailureGroup<'a> {
+    error: String,
+    file: Option<String>,
+    first: FailureBlock<'a>,
+    count: usize,
+}
+
+/// Summary of `report`, pointing at `full_path` for everything it leaves out
+pub fn summarize_failure_report(report: &str, full_path: &str, config: &FailureReportsConfig) -> String {
+    let mut marker = None;
+    let mut preamble = Vec::new();
+    let mut blocks: Vec<FailureBlock> = Vec::new();
+    for line in report.lines() {
+        let trimmed = line.trim();
+        if marker.is_none() && blocks.is_empty() && preamble.is_empty() && trimmed.starts_with("<!-- run:") {
+            marker = Some(trimmed);
+        } else if starts_failure(trimmed) {
+            blocks.push(FailureBlock { lines: vec![line], error: String::new(), file: None });
+        } else if let Some(block) = blocks.last_mut() {
+            block.lines.push(line);
+        } else if !trimmed.is_empty() {
+            preamble.push(line);
+        }
+    }
+
+    let mut groups: Vec<FailureGroup> = Vec::new();
+    for mut block in blocks {
+        while block.lines.last().is_some_and(|l| l.trim().is_empty()) {
+            block.lines.pop();
+        }
+        block.error = error_type(&block.lines);
+        block.file = block.lines.iter().flat_map(|l| l.split_whitespace()).find_map(source_file);
+        // Section headings with nothing under them aren't failures
+        if block.lines.len() == 1 && block.lines[0].trim_start().starts_with('#') {
+            continue;
+        }
+        match groups.iter_mut().find(|g| g.error == block.error && g.file == block.file) {
+            Some(group) => group.count += 1,
+            None => groups.push(FailureGroup { error: block.error.clone(), file: block.file.clone(), first: block, count: 1 }),
+        }
+    }
+    // Stable, so equal counts keep report order
+    groups.sort_by_key(|g| std::cmp::Reverse(g.count));
+
+    let total_lines = report.lines().count();
+    let failures: usize = groups.iter().map(|g| g.count).sum();
+    let mut summary = String::new();
+    if let Some(marker) = marker {
+        summary.push_str(marker);
+        summary.push('\n');
+    }
+    summary.push_str("# Failure Report Summary\n\n");
+    summary.push_str(&format!(
+        "The full report ({} lines) is at `{}`. This summary groups its {} failures by error type and file; \
+         read the full report only for a failure not shown here.\n",
+        total_lines, full_path, failures
+    ));
+    if !preamble.is_empty() {
+        summary.push_str("\n## Report Header\n\n");
+        for line in preamble.iter().take(MAX_PREAMBLE_LINES) {
+            summary.push_str(line);
+            summary.push('\n');
+        }
+    }
+
+    if groups.is_empty() {
+        // Nothing recognisable as a failure: keep the head of the report
+        let keep = config.traces.max(1) * config.trace_lines.max(1);
+        summary.push_str(&format!("\n## First {} Lines\n\n```\n", keep));
+        for line in report.lines().filter(|l| !l.trim().starts_with("<!-- run:")).take(keep) {
+            summary.push_str(line);
+            summary.push('\n');
+        }
+        summary.push_str("```\n");
+        return summary;
+    }
+
+    summary.push_str("\n## Failures by Type\n\n| Error | File | Failures |\n|-------|------|----------|\n");
+    for group in groups.iter().take(MAX_GROUPS) {
+        summary.push_str(&format!("| {} | {} | {} |\n", group.error, group.file.as_deref().unwrap_or("-"), group.count));
+    }
+    if groups.len() > MAX_GROUPS {
+        let rest = &groups[MAX_GROUPS..];
+        summary.push_str(&format!(
+            "\n{} more groups ({} failures) are only in the full report.\n",
+            rest.len(),
+            rest.iter().map(|g| g.count).sum::<usize>()
+        ));
+    }
+
+    summary.push_str("\n## Representative Traces\n");
+    for group in groups.iter().take(config.traces) {
+        let location = group.file.as_deref().map(|f| format!(" in {}", f)).unwrap_or_default();
+        summary.push_str(&format!("\n### {}{} ({} failures)\n\n```\n", group.error, location, group.count));
+        for line in group.first.lines.iter().take(config.trace_lines) {
+            // Keep the code fence intact around traces that contain fences of their own
+            summary.push_str(&line.replace("```", "'''"));
+            summary.push('\n');
+        }
+        if group.first.lines.len() > config.trace_lines {
+            summary.push_str(&format!("... {} more lines\n", group.first.lines.len() - config.trace_lines));
+        }
+        summary.push_str("```\n");
+    }
+    summary
+}
+
+/// Replace the report `name` in `workdir` with its summary when it's over the size threshold
+///
+/// The full report is moved to `.autoflow/.failures/full/<name>` first. True when
+/// the report was summarized.
+pub fn summarize_failure_report_file(workdir: &Path, name: &str, config: &FailureReportsConfig) -> Result<bool> {
+    let path = workdir.join(FAILURE_REPORTS_DIR).join(name);
+    let Ok(metadata) = std::fs::metadata(&path) else {
+        return Ok(false);
+    };
+    if config.summarize_over_bytes == 0 || metadata.len() <= config.summarize_over_bytes {
+        return Ok(false);
+    }
+    let report = String::from_utf8_lossy(&std::fs::read(&path)?).into_owned();
+    let full_path = format!("{}/{}/{}", FAILURE_REPORTS_DIR, FULL_REPORTS_DIR, name);
+    std::fs::create_dir_all(workdir.join(FAILURE_REPORTS_DIR).join(FULL_REPORTS_DIR))?;
+    std::fs::write(workdir.join(&full_path), &report)?;
+    std::fs::write(&path, summarize_failure_report(&report, &full_path, config))?;
+    Ok(true)
+}
+
+/// Append a model-drafted triage note to the report `name` in `workdir`
+///
+/// Runs only when a provider is mapped to `failure-triage`. The model gets no
+/// tools, so a local one can take it. Runs after the report is summarized, and
+/// a report is triaged once. True when a note was added.
+pub async fn triage_failure_report_file(workdir: &Path, name: &str, sprint_id: u32) -> Result<bool> {
+    let path = workdir.join(FAILURE_REPORTS_DIR).join(name);
+    let Ok(report) = std::fs::read_to_string(&path) else {
+        return Ok(false);
+    };
+    if report.contains(TRIAGE_HEADING) {
+        return Ok(false);
+    }
+    let excerpt: String = report.chars().take(TRIAGE_INPUT_CHARS).collect();
+    let note = autoflow_agents::complete_text(TRIAGE_TASK, Some(sprint_id), TRIAGE_SYSTEM, &excerpt)
+        .await
+        .map_err(|e| autoflow_data::AutoFlowError::AgentError(e.to_string()))?;
+    let Some(note) = note else {
+        return Ok(false);
+    };
+    std::fs::write(&path, with_triage_note(&report, &note))?;
+    Ok(true)
+}
+
+/// `report` with the triage note appended
+fn with_triage_note(report: &str, note: &str) -> String {
+    format!(
+        "{}\n\n{}\n\n_Drafted by a model from this report alone; check it against the code._\n\n{}\n",
+        report.trim_end(),
+        TRIAGE_HEADING,
+        note.trim()
+    )
+}
+
+fn starts_failure(line: &str) -> bool {
+    FAILURE_STARTS.iter().any(|start| line.starts_with(start))
+}
+
+/// The kind of failure a block shows: an exception or error class, a compiler error code, or a panic
+fn error_type(lines: &[&str]) -> String {
+    for line in lines {
+        for word in line.split_whitespace() {
+            let word = word.trim_matches(|c: char| !c.is_alphanumeric() && c != '_' && c != '.' && c != '[' && c != ']');
+            if let Some(code) = word.strip_prefix("error[").and_then(|w| w.split(']').next()) {
+                return format!("error[{}]", code);
+            }
+            let name = word.rsplit('.').next().unwrap_or(word);
+            if name.len() > 5
+                && name.starts_with(|c: char| c.is_ascii_uppercase())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && (name.ends_with("Error") || name.ends_with("Exception"))
+            {
+                return name.to_string();
+            }
+        }
+        if line.contains("panicked at") {
+            return "panic".to_string();
+        }
+    }
+    let lower = lines.join("\n").to_lowercase();
+    if lower.contains("timed out") || lower.contains("timeout") {
+        "timeout".to_string()
+    } else if lower.contains("assert") || lower.contains("expected") {
+        "assertion".to_string()
+    } else {
+        "unclassified".to_string()
+    }
+}
+
+/// The source file a word of a trace names, without line numbers or test paths
+fn source_file(word: &str) -> Option<String> {
+    let word = word.trim_matches(|c: char| matches!(c, '`' | '\'' | '"' | '(' | ')' | '[' | ']' | ',' | '<' | '>'));
+    if word.contains("://") {
+        return None;
+    }
+    let path = word.split("::").next()?.split(':').next()?.trim_start_matches("./");
+    let (stem, extension) = path.rsplit_once('.')?;
+    let plausible = !stem.is_empty() && path.chars().all(|c| c.is_alphanumeric() || "/._-@+".contains(c));
+    (plausible && SOURCE_EXTENSIONS.contains(&extension)).then(|| path.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_failure_report() {
+        let mut report = "<!-- run: 01J9Z -->\n# Unit Test Failures\n\nRan 1200 tests, 41 failed\n\n".to_string();
+        for i in 0..40 {
+            report.push_str(&format!(
+                "FAILED tests/test_cart.py::test_total_{} - AssertionError: {} != 42\n    at tests/test_cart.py:{}\n    assert total == 42\n\n",
+                i, i, 10 + i
+            ));
+        }
+        report.push_str("---- api::tests::login stdout ----\nthread 'api::tests::login' panicked at src/api.rs:88:5:\nexpected 200, got 500\n");
+
+        let config = FailureReportsConfig { summarize_over_bytes: 1000, traces: 2, trace_lines: 2 };
+        let summary = summarize_failure_report(&report, ".autoflow/.failures/full/sprint-3-unit-tests.md", &config);
+        assert!(summary.starts_with("<!-- run: 01J9Z -->\n# Failure Report Summary"));
+        assert!(summary.contains("`.autoflow/.failures/full/sprint-3-unit-tests.md`"));
+        assert!(summary.contains("Ran 1200 tests, 41 failed"));
+        assert!(summary.contains("| AssertionError | tests/test_cart.py | 40 |"));
+        assert!(summary.contains("| panic | src/api.rs | 1 |"));
+        assert!(summary.contains("### AssertionError in tests/test_cart.py (40 failures)"));
+        assert!(summary.contains("... 1 more lines"));
+        assert!(!summary.contains("test_total_1 "));
+        assert!(summary.len() < report.len() / 3);
+
+        let workdir = std::env::temp_dir().join(format!("autoflow-failure-summary-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&workdir);
+        std::fs::create_dir_all(workdir.join(FAILURE_REPORTS_DIR)).unwrap();
+        std::fs::write(workdir.join(FAILURE_REPORTS_DIR).join("sprint-3-unit-tests.md"), &report).unwrap();
+        std::fs::write(workdir.join(FAILURE_REPORTS_DIR).join("sprint-3-review.md"), "# Review\n\nMissing input validation\n").unwrap();
+        assert!(summarize_failure_report_file(&workdir, "sprint-3-unit-tests.md", &config).unwrap());
+        assert!(!summarize_failure_report_file(&workdir, "sprint-3-review.md", &config).unwrap());
+        assert!(!summarize_failure_report_file(&workdir, "sprint-3-e2e-tests.md", &config).unwrap());
+        let full = std::fs::read_to_string(workdir.join(".autoflow/.failures/full/sprint-3-unit-tests.md")).unwrap();
+        assert_eq!(full, report);
+        let _ = std::fs::remove_dir_all(&workdir);
+    }
+
+    #[test]
+    fn test_triage_note_goes_after_the_report() {
+        let report = "# Unit Test Failures\n\nFAILED tests/test_cart.py::test_total\n\n";
+        let triaged = with_triage_note(report, "Cart total ignores discounts; see src/cart.py.\n");
+        assert!(triaged.starts_with("# Unit Test Failures\n\nFAILED tests/test_cart.py::test_total\n\n## Triage\n"));
+        assert!(triaged.ends_with("check it against the code._\n\nCart total ignores discounts; see src/cart.py.\n"));
+    }
+}
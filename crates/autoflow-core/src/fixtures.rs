@@ -0,0 +1,153 @@
+// Seed data for E2E phases: load fixtures before tests, reset between attempts
+
+use autoflow_data::{AutoFlowError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Fixture data and the manifest live here
+pub const FIXTURES_DIR: &str = ".autoflow/fixtures";
+
+/// Manifest listing the commands that load and reset fixtures
+pub const FIXTURES_MANIFEST: &str = "fixtures.yml";
+
+/// Contents of `.autoflow/fixtures/fixtures.yml`
+///
+/// Commands run with `sh -c` from the project root, so they can target the
+/// dev environment (e.g. `docker compose exec -T db psql ...`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FixtureConfig {
+    /// Bring the environment back to a clean state
+    #[serde(default)]
+    pub reset: Vec<String>,
+
+    /// Load the fixture data
+    #[serde(default)]
+    pub load: Vec<String>,
+}
+
+/// Fixtures configured for a project
+#[derive(Debug, Clone)]
+pub struct FixtureSet {
+    pub dir: PathBuf,
+    pub config: FixtureConfig,
+}
+
+impl FixtureSet {
+    /// Read the manifest; None when the project has no fixtures
+    pub fn load(project_path: &Path) -> Result<Option<Self>> {
+        let dir = project_path.join(FIXTURES_DIR);
+        let manifest = dir.join(FIXTURES_MANIFEST);
+        if !manifest.exists() {
+            return Ok(None);
+        }
+        let config: FixtureConfig = serde_yaml::from_str(&std::fs::read_to_string(&manifest)?)?;
+        if config.load.is_empty() && config.reset.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self { dir, config }))
+    }
+
+    /// Data files agents can reference (everything but the manifest)
+    pub fn data_files(&self) -> Vec<String> {
+        let mut files: Vec<String> = walkdir::WalkDir::new(&self.dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && e.file_name() != FIXTURES_MANIFEST)
+            .filter_map(|e| {
+                e.path()
+                    .strip_prefix(&self.dir)
+                    .ok()
+                    .map(|p| format!("{}/{}", FIXTURES_DIR, p.display()))
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Reset the environment, then load fixtures
+    ///
+    /// Run before every E2E attempt so each run sees the same data.
+    pub fn prepare(&self, project_path: &Path) -> Result<()> {
+        run_commands(project_path, "reset", &self.config.reset)?;
+        run_commands(project_path, "load", &self.config.load)
+    }
+
+    /// Instructions for agents writing or fixing E2E tests
+    pub fn agent_instructions(&self) -> String {
+        let files = self.data_files();
+        let listing = if files.is_empty() {
+            "(no data files yet)".to_string()
+        } else {
+            files.iter().map(|f| format!("- `{}`", f)).collect::<Vec<_>>().join("\n")
+        };
+        format!(
+            r#"# Test Fixtures
+
+Deterministic seed data lives in `{dir}/`. AutoFlow resets the environment and
+loads it before every E2E run (commands in `{dir}/{manifest}`).
+
+Fixture files:
+{listing}
+
+- Use fixture records (known IDs, emails, passwords) in tests instead of creating ad-hoc data through the UI
+- Tests must not depend on data created by other tests
+- When a test needs new data, add it to the fixture files rather than to the test
+"#,
+            dir = FIXTURES_DIR,
+            manifest = FIXTURES_MANIFEST,
+            listing = listing
+        )
+    }
+}
+
+fn run_commands(project_path: &Path, step: &str, commands: &[String]) -> Result<()> {
+    for command in commands {
+        tracing::info!("Fixtures {}: {}", step, command);
+        let output = Command::new("sh")
+            .args(["-c", command])
+            .current_dir(project_path)
+            .output()?;
+        if !output.status.success() {
+            return Err(AutoFlowError::CommandFailed(format!(
+                "Fixture {} command failed: {}\n{}",
+                step,
+                command,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_prepare_resets_before_loading() {
+        let project = std::env::temp_dir().join(format!("autoflow-fixtures-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&project);
+        fs::create_dir_all(project.join(FIXTURES_DIR)).unwrap();
+        fs::write(project.join(FIXTURES_DIR).join("users.sql"), "insert into users values (1);").unwrap();
+        fs::write(
+            project.join(FIXTURES_DIR).join(FIXTURES_MANIFEST),
+            "reset:\n  - echo reset >> order.txt\nload:\n  - echo load >> order.txt\n",
+        )
+        .unwrap();
+
+        let fixtures = FixtureSet::load(&project).unwrap().unwrap();
+        assert_eq!(fixtures.data_files(), vec![format!("{}/users.sql", FIXTURES_DIR)]);
+
+        fixtures.prepare(&project).unwrap();
+        fixtures.prepare(&project).unwrap();
+        assert_eq!(fs::read_to_string(project.join("order.txt")).unwrap(), "reset\nload\nreset\nload\n");
+
+        fs::write(project.join(FIXTURES_DIR).join(FIXTURES_MANIFEST), "load:\n  - exit 3\n").unwrap();
+        let err = FixtureSet::load(&project).unwrap().unwrap().prepare(&project).unwrap_err();
+        assert!(err.to_string().contains("exit 3"));
+
+        let _ = fs::remove_dir_all(project);
+    }
+}
@@ -0,0 +1,256 @@
+// Token forecasting: estimate a run's token/cost envelope before it starts
+
+use crate::workflow::workflow_for;
+use autoflow_data::Sprint;
+use autoflow_utils::{RunEvent, INPUT_PRICE_PER_MTOK, OUTPUT_PRICE_PER_MTOK};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Output tokens per agent turn when an agent has no history yet
+const DEFAULT_TOKENS_PER_TURN: u64 = 1_500;
+
+/// Task count the per-phase averages are taken to represent
+const TYPICAL_TASKS: f64 = 4.0;
+
+/// Fix cycles expected per validation phase without history to go on
+const DEFAULT_FIX_RATE: f64 = 0.5;
+
+/// Rough bytes per token for English prose and code
+const BYTES_PER_TOKEN: u64 = 4;
+
+/// Estimated token envelope for the sprints a run will execute
+///
+/// Output tokens are the unit `[policy] max_run_tokens` is measured in.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunForecast {
+    pub sprints: usize,
+
+    /// Agent phases left to run, not counting fix cycles
+    pub agent_calls: usize,
+
+    /// Phases whose estimate comes from past runs rather than defaults
+    pub from_history: usize,
+
+    /// Output tokens if every validation phase passes first time
+    pub low_tokens: u64,
+
+    /// Output tokens with the historical fix-cycle rate
+    pub expected_tokens: u64,
+
+    /// Output tokens if every validation phase needs all its retries
+    pub high_tokens: u64,
+
+    /// Context read by the agents (docs and sprint plan), summed over calls
+    pub input_tokens: u64,
+}
+
+impl RunForecast {
+    /// Estimated cost (USD) of the expected and high envelopes
+    pub fn cost_usd(&self) -> (f64, f64) {
+        let input = self.input_tokens as f64 * INPUT_PRICE_PER_MTOK / 1_000_000.0;
+        let cost = |output: u64| input + output as f64 * OUTPUT_PRICE_PER_MTOK / 1_000_000.0;
+        (cost(self.expected_tokens), cost(self.high_tokens))
+    }
+
+    /// True when even the expected envelope won't fit in `budget` (0 = no limit)
+    pub fn exceeds(&self, budget: u64) -> bool {
+        budget > 0 && self.expected_tokens > budget
+    }
+}
+
+/// Per-agent output token averages and run counts from `.autoflow/runs.jsonl`
+#[derive(Debug, Clone, Default)]
+pub struct AgentUsage {
+    runs: HashMap<String, (u64, u64)>,
+}
+
+impl AgentUsage {
+    pub fn from_events(events: &[RunEvent]) -> Self {
+        let mut runs: HashMap<String, (u64, u64)> = HashMap::new();
+        for event in events.iter().filter(|e| e.event == "agent") {
+            if let (Some(agent), Some(tokens)) = (&event.agent, event.tokens) {
+                let entry = runs.entry(agent.clone()).or_default();
+                entry.0 += 1;
+                entry.1 += tokens;
+            }
+        }
+        Self { runs }
+    }
+
+    fn count(&self, agent: &str) -> u64 {
+        self.runs.get(agent).map(|(count, _)| *count).unwrap_or(0)
+    }
+
+    fn average(&self, agent: &str) -> Option<u64> {
+        self.runs
+            .get(agent)
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, tokens)| tokens / count)
+    }
+}
+
+/// Estimate the output tokens the given sprints will use from where each one stands
+///
+/// Each remaining phase costs its agent's historical average, or
+/// `max_turns` × a per-turn default without history, scaled by the sprint's
+/// task count. Validation phases add their fix phase at the historical
+/// fix-cycle rate. Every agent call also reads the docs and the sprint plan.
+pub fn forecast_run(sprints: &[&Sprint], docs_bytes: u64, usage: &AgentUsage) -> RunForecast {
+    let mut forecast = RunForecast::default();
+    let (mut low, mut expected, mut high) = (0.0, 0.0, 0.0);
+    let mut context_tokens = 0;
+
+    for sprint in sprints.iter().filter(|s| s.status.is_runnable()) {
+        forecast.sprints += 1;
+        let workflow = workflow_for(sprint);
+        let size = (sprint.tasks.len().max(1) as f64 / TYPICAL_TASKS).clamp(0.5, 3.0);
+        let plan_tokens = serde_yaml::to_string(sprint).map(|y| y.len() as u64).unwrap_or(0) / BYTES_PER_TOKEN;
+        let call_context = docs_bytes / BYTES_PER_TOKEN + plan_tokens;
+
+        // Blocked sprints restart from the top once the blocker is resolved
+        let start = workflow.phases.iter().position(|p| p.status == sprint.status).unwrap_or(0);
+        for phase in &workflow.phases[start..] {
+            if workflow.is_fix_phase(phase.status) {
+                continue;
+            }
+            if phase.agent != "none" {
+                forecast.agent_calls += 1;
+                if usage.average(phase.agent).is_some() {
+                    forecast.from_history += 1;
+                }
+                let tokens = phase_tokens(usage, phase.agent, phase.max_turns) as f64 * size;
+                low += tokens;
+                expected += tokens;
+                high += tokens;
+                context_tokens += call_context;
+            }
+
+            let Some(fix) = workflow.get_fix_phase(phase.status).filter(|f| f.agent != "none") else {
+                continue;
+            };
+            let fix_tokens = phase_tokens(usage, fix.agent, fix.max_turns) as f64 * size;
+            let validations = usage.count(phase.agent);
+            let fix_rate = if validations > 0 {
+                (usage.count(fix.agent) as f64 / validations as f64).min(phase.max_retries as f64)
+            } else {
+                DEFAULT_FIX_RATE
+            };
+            expected += fix_tokens * fix_rate;
+            high += fix_tokens * phase.max_retries as f64;
+            context_tokens += ((call_context as f64) * fix_rate) as u64;
+        }
+    }
+
+    forecast.low_tokens = low as u64;
+    forecast.expected_tokens = expected as u64;
+    forecast.high_tokens = high as u64;
+    forecast.input_tokens = context_tokens;
+    forecast
+}
+
+fn phase_tokens(usage: &AgentUsage, agent: &str, max_turns: u32) -> u64 {
+    usage
+        .average(agent)
+        .unwrap_or(DEFAULT_TOKENS_PER_TURN * max_turns.max(1) as u64)
+}
+
+/// Total size of the markdown under `.autoflow/docs`
+pub fn docs_size(project_path: &Path) -> u64 {
+    fn walk(dir: &Path) -> u64 {
+        std::fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| {
+                        let path = e.path();
+                        if path.is_dir() {
+                            walk(&path)
+                        } else if path.extension().is_some_and(|ext| ext == "md") {
+                            e.metadata().map(|m| m.len()).unwrap_or(0)
+                        } else {
+                            0
+                        }
+                    })
+                    .sum()
+            })
+            .unwrap_or(0)
+    }
+    walk(&project_path.join(".autoflow/docs"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use autoflow_data::{SprintStatus, WorkflowType};
+
+    fn sprint(id: u32, status: SprintStatus) -> Sprint {
+        Sprint {
+            id,
+            uid: None,
+            goal: format!("Sprint {}", id),
+            status,
+            workflow_type: WorkflowType::Documentation,
+            workflow: None,
+            duration: None,
+            total_effort: "1h".to_string(),
+            max_effort: "2h".to_string(),
+            started: None,
+            last_updated: chrono::Utc::now(),
+            completed_at: None,
+            deliverables: vec![],
+            tasks: vec![],
+            dependencies: vec![],
+            integration_points: None,
+            blocked_count: None,
+            must_complete_first: false,
+            priority: None,
+            value: None,
+            failure_reports: vec![],
+            repo: None,
+            uses_blocker_resolver: false,
+            issue_url: None,
+            skipped: None,
+            cancelled: None,
+            paused_from: None,
+        }
+    }
+
+    fn agent_event(agent: &str, tokens: u64) -> RunEvent {
+        RunEvent {
+            event: "agent".to_string(),
+            agent: Some(agent.to_string()),
+            tokens: Some(tokens),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_forecast_uses_history_and_skips_done_sprints() {
+        let done = sprint(1, SprintStatus::Done);
+        let pending = sprint(2, SprintStatus::Pending);
+        let sprints = [&done, &pending];
+
+        // doc-writer, doc-reviewer (+ doc-fixer at the default rate), health-check
+        let without_history = forecast_run(&sprints, 0, &AgentUsage::default());
+        assert_eq!(without_history.sprints, 1);
+        assert_eq!(without_history.agent_calls, 3);
+        assert_eq!(without_history.from_history, 0);
+        assert!(without_history.low_tokens < without_history.expected_tokens);
+        assert!(without_history.expected_tokens < without_history.high_tokens);
+
+        let events: Vec<RunEvent> = ["doc-writer", "doc-reviewer", "doc-reviewer", "doc-fixer", "health-check"]
+            .iter()
+            .map(|agent| agent_event(agent, 100))
+            .collect();
+        let with_history = forecast_run(&sprints, 4_000, &AgentUsage::from_events(&events));
+        assert_eq!(with_history.from_history, 3);
+        // 0.5 size factor (no tasks): 3 × 50, plus the fixer at half the reviews
+        assert_eq!(with_history.low_tokens, 150);
+        assert_eq!(with_history.expected_tokens, 175);
+        assert!(with_history.input_tokens >= 1_000 * with_history.agent_calls as u64);
+
+        assert!(with_history.exceeds(100));
+        assert!(!with_history.exceeds(200));
+        assert!(!with_history.exceeds(0));
+    }
+}
@@ -1,7 +1,209 @@
-// Git integration for automatic project commits
+// Version control integration for automatic project commits
 use std::path::Path;
 use std::process::Command;
-use autoflow_data::{Result, AutoFlowError, Sprint, SprintStatus};
+use autoflow_data::{CommitConfig, CommitSigning, IgnoreRules, Result, AutoFlowError, Sprint, SprintStatus, VcsBackend};
+use std::io::Write;
+use std::process::Stdio;
+use walkdir::WalkDir;
+
+/// Directories never searched for nested repositories
+const NESTED_REPO_SKIP_DIRS: &[&str] = &[".git", ".autoflow", "node_modules", "vendor", "target"];
+
+/// Version control that the orchestrator records phase results in
+pub trait Vcs: Send + Sync {
+    /// Backend name for logs
+    fn name(&self) -> &'static str;
+
+    /// Record the project's changes (minus AutoFlow state and ignored paths) as one commit
+    fn commit(
+        &self,
+        project_path: &Path,
+        sprint: &Sprint,
+        message: &str,
+        allow_submodule_bumps: bool,
+        policy: &CommitConfig,
+    ) -> Result<()>;
+
+    /// Fold the sprint's phase commits into one, for `[commit] squash_on_done`
+    fn squash_sprint(&self, _project_path: &Path, sprint: &Sprint, _policy: &CommitConfig) -> Result<()> {
+        tracing::debug!("{} can't squash commits, keeping sprint {}'s phase commits", self.name(), sprint.id);
+        Ok(())
+    }
+
+    /// Files with uncommitted changes, relative to the project root
+    fn changed_files(&self, project_path: &Path) -> Vec<String>;
+}
+
+/// Backend for the project in `project_path`
+///
+/// `VcsBackend::Auto` prefers Jujutsu when `.jj` exists (colocated repos have
+/// `.git` too), then git, and otherwise works on the plain directory.
+pub fn detect_vcs(project_path: &Path, backend: VcsBackend) -> Box<dyn Vcs> {
+    match backend {
+        VcsBackend::Git => Box::new(GitVcs),
+        VcsBackend::Jujutsu => Box::new(JujutsuVcs),
+        VcsBackend::None => Box::new(PlainDirectory),
+        VcsBackend::Auto if project_path.join(".jj").is_dir() => Box::new(JujutsuVcs),
+        VcsBackend::Auto if project_path.join(".git").exists() => Box::new(GitVcs),
+        VcsBackend::Auto => Box::new(PlainDirectory),
+    }
+}
+
+/// Git, through the `git` command
+pub struct GitVcs;
+
+impl Vcs for GitVcs {
+    fn name(&self) -> &'static str {
+        "git"
+    }
+
+    fn commit(
+        &self,
+        project_path: &Path,
+        sprint: &Sprint,
+        message: &str,
+        allow_submodule_bumps: bool,
+        policy: &CommitConfig,
+    ) -> Result<()> {
+        commit_project_changes(project_path, sprint, message, allow_submodule_bumps, policy)
+    }
+
+    fn squash_sprint(&self, project_path: &Path, sprint: &Sprint, policy: &CommitConfig) -> Result<()> {
+        let squashed = squash_sprint_commits(project_path, sprint, policy)?;
+        if squashed > 0 {
+            tracing::info!("✓ Squashed {} commits for sprint {}", squashed, sprint.id);
+        }
+        Ok(())
+    }
+
+    fn changed_files(&self, project_path: &Path) -> Vec<String> {
+        changed_files(project_path)
+    }
+}
+
+/// Jujutsu, through the `jj` command
+///
+/// The working-copy commit is split with `jj commit`, taking only the changed
+/// files that git would have staged. jj has no submodules, so
+/// `allow_submodule_bumps` does not apply.
+pub struct JujutsuVcs;
+
+impl Vcs for JujutsuVcs {
+    fn name(&self) -> &'static str {
+        "jujutsu"
+    }
+
+    fn commit(
+        &self,
+        project_path: &Path,
+        sprint: &Sprint,
+        message: &str,
+        _allow_submodule_bumps: bool,
+        policy: &CommitConfig,
+    ) -> Result<()> {
+        autoflow_data::ensure_writable("jj commit")?;
+        if !project_path.join(".jj").is_dir() {
+            tracing::debug!("No .jj directory found at {:?}, skipping commit", project_path);
+            return Ok(());
+        }
+
+        let nested = find_nested_repos(project_path, &[]);
+        let files: Vec<String> = self
+            .changed_files(project_path)
+            .into_iter()
+            .filter(|f| !f.starts_with(".autoflow/"))
+            .filter(|f| !nested.iter().any(|repo| f.starts_with(&format!("{}/", repo))))
+            .collect();
+        if files.is_empty() {
+            tracing::debug!("No changes to commit in {:?}", project_path);
+            return Ok(());
+        }
+
+        let result = Command::new("jj")
+            .current_dir(project_path)
+            .envs(identity_env(policy, "JJ_USER", "JJ_EMAIL"))
+            .args(jj_signing_args(policy))
+            .args(["commit", "-m", &format_commit_message(sprint, message), "--"])
+            .args(files.iter().map(|f| jj_file_pattern(f)))
+            .output()
+            .map_err(|e| AutoFlowError::GitCommandFailed(format!("Failed to run jj commit: {}", e)))?;
+        if result.status.success() {
+            tracing::info!("✓ Created jj commit: {}", message);
+        } else {
+            tracing::warn!(
+                "Failed to create jj commit: {} - Error: {}",
+                message,
+                String::from_utf8_lossy(&result.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+
+    fn changed_files(&self, project_path: &Path) -> Vec<String> {
+        let Ok(output) = Command::new("jj")
+            .current_dir(project_path)
+            .args(["diff", "--name-only", "--color", "never"])
+            .output()
+        else {
+            return Vec::new();
+        };
+        let files = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect();
+        IgnoreRules::load(project_path).filter(files)
+    }
+}
+
+/// Exact-path jj fileset for `path`, relative to the workspace root
+pub fn jj_file_pattern(path: &str) -> String {
+    format!("root-file:\"{}\"", path.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// A directory without version control: nothing is committed and no
+/// changes are tracked, for experiments where history doesn't matter
+pub struct PlainDirectory;
+
+impl Vcs for PlainDirectory {
+    fn name(&self) -> &'static str {
+        "none"
+    }
+
+    fn commit(
+        &self,
+        _project_path: &Path,
+        _sprint: &Sprint,
+        message: &str,
+        _allow_submodule_bumps: bool,
+        _policy: &CommitConfig,
+    ) -> Result<()> {
+        tracing::debug!("No version control, not committing: {}", message);
+        Ok(())
+    }
+
+    fn changed_files(&self, _project_path: &Path) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Commit message with the sprint's context and the run trailer
+fn format_commit_message(sprint: &Sprint, message: &str) -> String {
+    let mut commit_message = format!(
+        "{}\n\nSprint {}: {}\nWorkflow: {:?}\nStatus: {:?}\n\n🤖 Automated commit by AutoFlow",
+        message,
+        sprint.id,
+        sprint.goal,
+        sprint.workflow_type,
+        sprint.status
+    );
+
+    // Trailer links the commit back to the run's logs and events
+    if let Some(run_id) = autoflow_utils::current_run_id() {
+        commit_message.push_str(&format!("\n\n{}: {}", autoflow_utils::RUN_ID_TRAILER, run_id));
+    }
+    commit_message
+}
 
 /// Commit project changes with a formatted message
 ///
@@ -10,7 +212,19 @@ use autoflow_data::{Result, AutoFlowError, Sprint, SprintStatus};
 /// - No .git directory exists
 /// - There are no changes to commit
 /// - Git commands fail
-pub fn commit_project_changes(project_path: &Path, sprint: &Sprint, message: &str) -> Result<()> {
+///
+/// Nested git repositories and paths in `.autoflowignore` are never staged, and
+/// submodule pointers are only bumped when `allow_submodule_bumps` is set.
+/// `policy` sets the signing and the author identity.
+pub fn commit_project_changes(
+    project_path: &Path,
+    sprint: &Sprint,
+    message: &str,
+    allow_submodule_bumps: bool,
+    policy: &CommitConfig,
+) -> Result<()> {
+    autoflow_data::ensure_writable("git commit")?;
+
     // Check if .git directory exists
     let git_dir = project_path.join(".git");
     if !git_dir.exists() {
@@ -18,34 +232,34 @@ pub fn commit_project_changes(project_path: &Path, sprint: &Sprint, message: &st
         return Ok(());
     }
 
-    // Format commit message with sprint context
-    let commit_message = format!(
-        "{}\n\nSprint {}: {}\nWorkflow: {:?}\nStatus: {:?}\n\n🤖 Automated commit by AutoFlow",
-        message,
-        sprint.id,
-        sprint.goal,
-        sprint.workflow_type,
-        sprint.status
-    );
+    let commit_message = format_commit_message(sprint, message);
 
     // Check if there are changes to commit
     let status_output = Command::new("git")
         .current_dir(project_path)
         .args(["status", "--porcelain"])
         .output()
-        .map_err(|e| AutoFlowError::ValidationError(format!("Failed to check git status: {}", e)))?;
+        .map_err(|e| AutoFlowError::GitCommandFailed(format!("Failed to check git status: {}", e)))?;
 
     if status_output.stdout.is_empty() {
         tracing::debug!("No changes to commit in {:?}", project_path);
         return Ok(());
     }
 
-    // Stage all changes (excluding .autoflow/)
+    // Stage all changes (excluding .autoflow/, nested repos and possibly submodules)
+    let submodules = list_submodules(project_path);
+    let nested = find_nested_repos(project_path, &submodules);
+    if !nested.is_empty() {
+        tracing::debug!("Excluding nested git repositories from auto-commit: {:?}", nested);
+    }
+    let pathspecs = staging_pathspecs(&submodules, &nested, allow_submodule_bumps);
+
     let add_result = Command::new("git")
         .current_dir(project_path)
-        .args(["add", "-A", ":(exclude).autoflow/"])
+        .args(["add", "-A", "--"])
+        .args(&pathspecs)
         .output()
-        .map_err(|e| AutoFlowError::ValidationError(format!("Failed to stage changes: {}", e)))?;
+        .map_err(|e| AutoFlowError::GitCommandFailed(format!("Failed to stage changes: {}", e)))?;
 
     if !add_result.status.success() {
         let error = String::from_utf8_lossy(&add_result.stderr);
@@ -53,12 +267,14 @@ pub fn commit_project_changes(project_path: &Path, sprint: &Sprint, message: &st
         return Ok(()); // Don't fail the sprint, just skip committing
     }
 
+    unstage_ignored(project_path);
+
     // Check if there are staged changes to commit
     let diff_result = Command::new("git")
         .current_dir(project_path)
         .args(["diff", "--cached", "--quiet"])
         .status()
-        .map_err(|e| AutoFlowError::ValidationError(format!("Failed to check staged changes: {}", e)))?;
+        .map_err(|e| AutoFlowError::GitCommandFailed(format!("Failed to check staged changes: {}", e)))?;
 
     // git diff --cached --quiet exits with 0 if no changes, 1 if there are changes
     if diff_result.success() {
@@ -67,11 +283,7 @@ pub fn commit_project_changes(project_path: &Path, sprint: &Sprint, message: &st
     }
 
     // Create commit
-    let commit_result = Command::new("git")
-        .current_dir(project_path)
-        .args(["commit", "-m", &commit_message])
-        .output()
-        .map_err(|e| AutoFlowError::ValidationError(format!("Failed to create commit: {}", e)))?;
+    let commit_result = git_commit(project_path, policy, &commit_message)?;
 
     if commit_result.status.success() {
         let output = String::from_utf8_lossy(&commit_result.stdout);
@@ -84,6 +296,282 @@ pub fn commit_project_changes(project_path: &Path, sprint: &Sprint, message: &st
     Ok(())
 }
 
+/// Author and committer overrides from the policy, as `name_var`/`email_var` environment
+fn identity_env(policy: &CommitConfig, name_var: &'static str, email_var: &'static str) -> Vec<(&'static str, String)> {
+    let mut env = Vec::new();
+    if let Some(name) = &policy.author_name {
+        env.push((name_var, name.clone()));
+    }
+    if let Some(email) = &policy.author_email {
+        env.push((email_var, email.clone()));
+    }
+    env
+}
+
+/// `git` arguments committing the index with `message`, signed as the policy says
+pub fn git_commit_args(policy: &CommitConfig, message: &str) -> Vec<String> {
+    let mut args: Vec<String> = Vec::new();
+    let format = match policy.sign {
+        CommitSigning::Gpg => Some("openpgp"),
+        CommitSigning::Ssh => Some("ssh"),
+        CommitSigning::Default | CommitSigning::Off => None,
+    };
+    if let Some(format) = format {
+        args.extend(["-c".to_string(), format!("gpg.format={}", format)]);
+        if let Some(key) = &policy.signing_key {
+            args.extend(["-c".to_string(), format!("user.signingkey={}", key)]);
+        }
+    }
+    args.push("commit".to_string());
+    match policy.sign {
+        CommitSigning::Gpg | CommitSigning::Ssh => args.push("-S".to_string()),
+        CommitSigning::Off => args.push("--no-gpg-sign".to_string()),
+        CommitSigning::Default => {}
+    }
+    args.extend(["-m".to_string(), message.to_string()]);
+    args
+}
+
+/// `jj --config` settings for the policy's signing
+fn jj_signing_args(policy: &CommitConfig) -> Vec<String> {
+    let mut settings = match policy.sign {
+        CommitSigning::Gpg => vec!["signing.behavior=own", "signing.backend=gpg"],
+        CommitSigning::Ssh => vec!["signing.behavior=own", "signing.backend=ssh"],
+        CommitSigning::Off => vec!["signing.behavior=drop"],
+        CommitSigning::Default => vec![],
+    }
+    .into_iter()
+    .map(String::from)
+    .collect::<Vec<_>>();
+    if let Some(key) = policy.signing_key.as_ref().filter(|_| !settings.is_empty() && policy.sign != CommitSigning::Off) {
+        settings.push(format!("signing.key={}", key));
+    }
+    settings.into_iter().flat_map(|setting| ["--config".to_string(), setting]).collect()
+}
+
+fn git_commit(project_path: &Path, policy: &CommitConfig, message: &str) -> Result<std::process::Output> {
+    Command::new("git")
+        .current_dir(project_path)
+        .envs(identity_env(policy, "GIT_AUTHOR_NAME", "GIT_AUTHOR_EMAIL"))
+        .envs(identity_env(policy, "GIT_COMMITTER_NAME", "GIT_COMMITTER_EMAIL"))
+        .args(git_commit_args(policy, message))
+        .output()
+        .map_err(|e| AutoFlowError::GitCommandFailed(format!("Failed to create commit: {}", e)))
+}
+
+/// Squash the sprint's latest run of AutoFlow commits into one; returns how many were folded
+///
+/// Walks back from HEAD while commits carry the sprint's `Sprint N: goal` line,
+/// so a commit made by hand in between ends the run. Nothing changes with
+/// fewer than two such commits, or when the run reaches the root commit.
+pub fn squash_sprint_commits(project_path: &Path, sprint: &Sprint, policy: &CommitConfig) -> Result<usize> {
+    autoflow_data::ensure_writable("git commit squash")?;
+    if !project_path.join(".git").exists() {
+        return Ok(0);
+    }
+    let git = |args: &[&str]| -> Result<String> {
+        let output = Command::new("git")
+            .current_dir(project_path)
+            .args(args)
+            .output()
+            .map_err(|e| AutoFlowError::GitCommandFailed(format!("Failed to run git {}: {}", args[0], e)))?;
+        if !output.status.success() {
+            return Err(AutoFlowError::GitCommandFailed(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    };
+
+    let sprint_line = format!("Sprint {}: {}", sprint.id, sprint.goal);
+    let log = git(&["log", "--format=%H%n%B%x1e", "-n", "200"])?;
+    let run: Vec<(&str, &str)> = log
+        .split('\x1e')
+        .filter_map(|entry| entry.trim_start().split_once('\n'))
+        .take_while(|(_, body)| body.lines().any(|line| line == sprint_line))
+        .collect();
+    if run.len() < 2 {
+        return Ok(0);
+    }
+    let (oldest, _) = run[run.len() - 1];
+    let Ok(base) = git(&["rev-parse", "--verify", "-q", &format!("{}^", oldest)]) else {
+        tracing::debug!("Sprint {}'s commits start at the root commit, not squashing", sprint.id);
+        return Ok(0);
+    };
+
+    let phases: String = run.iter().rev().map(|(_, body)| format!("- {}\n", body.lines().next().unwrap_or(""))).collect();
+    let message = format_commit_message(sprint, &format!("{}\n\nSquashed phase commits:\n{}", sprint_line, phases.trim_end()));
+    let head = run[0].0;
+    git(&["reset", "--soft", base.trim()])?;
+    let result = git_commit(project_path, policy, &message)?;
+    if !result.status.success() {
+        // Put the phase commits back rather than leave them staged
+        git(&["reset", "--soft", head])?;
+        return Err(AutoFlowError::GitCommandFailed(format!(
+            "Failed to squash sprint {}: {}",
+            sprint.id,
+            String::from_utf8_lossy(&result.stderr).trim()
+        )));
+    }
+    Ok(run.len())
+}
+
+/// Whether the policy commits after `status` succeeds
+pub fn commits_after_phase(policy: &CommitConfig, status: SprintStatus) -> bool {
+    match &policy.phases {
+        Some(phases) => phases.contains(&status),
+        None => should_commit_after_phase(status),
+    }
+}
+
+/// Take paths matched by `.autoflowignore` back out of the index
+///
+/// Ignore rules are gitignore syntax, which pathspecs can't express (negation,
+/// unanchored names), so they're applied to the staged list instead.
+fn unstage_ignored(project_path: &Path) {
+    let ignore = IgnoreRules::load(project_path);
+    if ignore.is_empty() {
+        return;
+    }
+    let Ok(staged) = Command::new("git")
+        .current_dir(project_path)
+        .args(["diff", "--cached", "--name-only", "-z"])
+        .output()
+    else {
+        return;
+    };
+    let ignored: Vec<String> = String::from_utf8_lossy(&staged.stdout)
+        .split('\0')
+        .filter(|path| !path.is_empty() && ignore.is_ignored(path, false))
+        .map(String::from)
+        .collect();
+    if ignored.is_empty() {
+        return;
+    }
+    tracing::debug!("Leaving {} path(s) from .autoflowignore out of the commit", ignored.len());
+
+    // Before the first commit there's no HEAD to reset to, and everything staged is new
+    let has_head = Command::new("git")
+        .current_dir(project_path)
+        .args(["rev-parse", "--verify", "-q", "HEAD"])
+        .output()
+        .is_ok_and(|o| o.status.success());
+    let unstage: &[&str] = if has_head { &["reset", "-q"] } else { &["rm", "--cached", "-q"] };
+
+    let pathspecs: String = ignored.iter().map(|p| format!(":(literal){}\0", p)).collect();
+    let child = Command::new("git")
+        .current_dir(project_path)
+        .args(unstage)
+        .args(["--pathspec-from-file=-", "--pathspec-file-nul"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn();
+    let result = child.and_then(|mut child| {
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(pathspecs.as_bytes())?;
+        }
+        child.wait_with_output()
+    });
+    match result {
+        Ok(output) if !output.status.success() => {
+            tracing::warn!("Failed to unstage ignored paths: {}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Err(e) => tracing::warn!("Failed to unstage ignored paths: {}", e),
+        Ok(_) => {}
+    }
+}
+
+/// Pathspecs for `git add -A` that keep AutoFlow state and foreign repos out of commits
+pub fn staging_pathspecs(submodules: &[String], nested_repos: &[String], allow_submodule_bumps: bool) -> Vec<String> {
+    let mut specs = vec![".".to_string(), ":(exclude).autoflow".to_string()];
+    if !allow_submodule_bumps {
+        specs.extend(submodules.iter().map(|p| format!(":(exclude){}", p)));
+    }
+    specs.extend(nested_repos.iter().map(|p| format!(":(exclude){}", p)));
+    specs
+}
+
+/// Submodule paths declared in .gitmodules, relative to the project root
+pub fn list_submodules(project_path: &Path) -> Vec<String> {
+    if !project_path.join(".gitmodules").exists() {
+        return Vec::new();
+    }
+    Command::new("git")
+        .current_dir(project_path)
+        .args(["config", "--file", ".gitmodules", "--get-regexp", r"^submodule\..*\.path$"])
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| line.split_once(' ').map(|(_, path)| path.trim().to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Files with uncommitted changes (including untracked ones), relative to the project root
+///
+/// Paths in `.autoflowignore` are left out.
+pub fn changed_files(project_path: &Path) -> Vec<String> {
+    let Ok(output) = Command::new("git")
+        .current_dir(project_path)
+        .args(["status", "--porcelain", "-z", "--untracked-files=all"])
+        .output()
+    else {
+        return Vec::new();
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut entries = stdout.split('\0').filter(|e| !e.is_empty());
+    let mut files = Vec::new();
+    while let Some(entry) = entries.next() {
+        if entry.len() < 4 {
+            continue;
+        }
+        // Renames and copies are followed by their original path
+        if matches!(entry.as_bytes()[0], b'R' | b'C') {
+            entries.next();
+        }
+        let file = &entry[3..];
+        // AutoFlow state (a symlink in patch-mode checkouts) is never committed
+        if file != ".autoflow" && !file.starts_with(".autoflow/") {
+            files.push(file.to_string());
+        }
+    }
+    IgnoreRules::load(project_path).filter(files)
+}
+
+/// Directories below the project root that are git repositories but not submodules
+pub fn find_nested_repos(project_path: &Path, submodules: &[String]) -> Vec<String> {
+    let mut nested = Vec::new();
+    let mut walker = WalkDir::new(project_path).min_depth(1).into_iter();
+
+    while let Some(Ok(entry)) = walker.next() {
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy();
+        if NESTED_REPO_SKIP_DIRS.contains(&name.as_ref()) {
+            walker.skip_current_dir();
+            continue;
+        }
+        if entry.path().join(".git").exists() {
+            let relative = entry
+                .path()
+                .strip_prefix(project_path)
+                .unwrap_or(entry.path())
+                .to_string_lossy()
+                .replace('\\', "/");
+            if !submodules.contains(&relative) {
+                nested.push(relative);
+            }
+            // Whatever is inside belongs to that repository
+            walker.skip_current_dir();
+        }
+    }
+
+    nested
+}
+
 /// Determine if a commit should be created after this sprint status/phase
 pub fn should_commit_after_phase(status: SprintStatus) -> bool {
     match status {
@@ -108,9 +596,15 @@ pub fn should_commit_after_phase(status: SprintStatus) -> bool {
         SprintStatus::UnitFix => true,
         SprintStatus::E2eFix => true,
 
-        // Don't commit on pending/blocked
+        // Commit what the deploy agent changed; checks and rollbacks don't edit the tree
+        SprintStatus::Deploy => true,
+        SprintStatus::SmokeTest => false,
+        SprintStatus::Rollback => false,
+
+        // Don't commit on pending/blocked, or on a status a human set
         SprintStatus::Pending => false,
         SprintStatus::Blocked => false,
+        SprintStatus::Paused | SprintStatus::Skipped | SprintStatus::Cancelled => false,
     }
 }
 
@@ -126,6 +620,7 @@ pub fn get_commit_message_for_phase(status: SprintStatus) -> &'static str {
         SprintStatus::ReviewFix => "Fix code review issues",
         SprintStatus::UnitFix => "Fix unit test failures",
         SprintStatus::E2eFix => "Fix E2E test failures",
+        SprintStatus::Deploy => "Deploy",
         SprintStatus::Done => "Sprint completed",
         SprintStatus::Complete => "Sprint completed",
         _ => "Update code",
@@ -136,6 +631,145 @@ pub fn get_commit_message_for_phase(status: SprintStatus) -> &'static str {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_staging_pathspecs_exclude_nested_repos() {
+        let submodules = vec!["libs/shared".to_string()];
+        let nested = vec!["tools/scratch".to_string()];
+
+        let specs = staging_pathspecs(&submodules, &nested, false);
+        assert!(specs.contains(&":(exclude).autoflow".to_string()));
+        assert!(specs.contains(&":(exclude)libs/shared".to_string()));
+        assert!(specs.contains(&":(exclude)tools/scratch".to_string()));
+
+        let specs = staging_pathspecs(&submodules, &nested, true);
+        assert!(!specs.contains(&":(exclude)libs/shared".to_string()));
+        assert!(specs.contains(&":(exclude)tools/scratch".to_string()));
+    }
+
+    #[test]
+    fn test_find_nested_repos_skips_submodules() {
+        let root = std::env::temp_dir().join(format!("autoflow-nested-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("tools/scratch/.git")).unwrap();
+        std::fs::create_dir_all(root.join("tools/scratch/inner/.git")).unwrap();
+        std::fs::create_dir_all(root.join("libs/shared")).unwrap();
+        std::fs::write(root.join("libs/shared/.git"), "gitdir: ../../.git/modules/shared").unwrap();
+        std::fs::create_dir_all(root.join("node_modules/pkg/.git")).unwrap();
+
+        let nested = find_nested_repos(&root, &["libs/shared".to_string()]);
+        assert_eq!(nested, vec!["tools/scratch".to_string()]);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_commit_leaves_out_autoflowignore() {
+        let root = std::env::temp_dir().join(format!("autoflow-ignore-commit-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("fixtures")).unwrap();
+        let git = |args: &[&str]| {
+            let output = Command::new("git").current_dir(&root).args(args).output().unwrap();
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
+        git(&["init", "-q"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test"]);
+        std::fs::write(root.join(".autoflowignore"), "fixtures/\n*.min.js\n").unwrap();
+        std::fs::write(root.join("src/app.js"), "app").unwrap();
+        std::fs::write(root.join("src/app.min.js"), "min").unwrap();
+        std::fs::write(root.join("fixtures/dump.sql"), "dump").unwrap();
+
+        let sprint: Sprint = serde_yaml::from_str(
+            "id: 1\ngoal: App\nstatus: WRITE_CODE\ntotal_effort: 1h\nmax_effort: 2h\n\
+             last_updated: 2025-01-01T00:00:00Z\ndeliverables: []\ntasks: []\n",
+        )
+        .unwrap();
+        assert_eq!(changed_files(&root), vec![".autoflowignore".to_string(), "src/app.js".to_string()]);
+
+        // Before the first commit, then with a HEAD to reset to
+        commit_project_changes(&root, &sprint, "First", false, &CommitConfig::default()).unwrap();
+        std::fs::write(root.join("src/app.js"), "app v2").unwrap();
+        std::fs::write(root.join("src/app.min.js"), "min v2").unwrap();
+        commit_project_changes(&root, &sprint, "Second", false, &CommitConfig::default()).unwrap();
+
+        assert_eq!(git(&["ls-files"]), ".autoflowignore\nsrc/app.js\n");
+        assert_eq!(git(&["rev-list", "--count", "HEAD"]).trim(), "2");
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_commit_policy() {
+        let root = std::env::temp_dir().join(format!("autoflow-commit-policy-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        let git = |args: &[&str]| {
+            let output = Command::new("git").current_dir(&root).args(args).output().unwrap();
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
+        git(&["init", "-q"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test"]);
+        std::fs::write(root.join("README.md"), "readme").unwrap();
+        git(&["add", "-A"]);
+        git(&["commit", "-q", "-m", "Initial"]);
+
+        let policy = CommitConfig {
+            phases: Some(vec![SprintStatus::WriteCode]),
+            squash_on_done: true,
+            author_name: Some("AutoFlow Bot".to_string()),
+            author_email: Some("bot@example.com".to_string()),
+            ..Default::default()
+        };
+        assert!(commits_after_phase(&policy, SprintStatus::WriteCode));
+        assert!(!commits_after_phase(&policy, SprintStatus::UnitFix));
+        assert!(commits_after_phase(&CommitConfig::default(), SprintStatus::UnitFix));
+
+        let sprint: Sprint = serde_yaml::from_str(
+            "id: 2\ngoal: Search\nstatus: DONE\ntotal_effort: 1h\nmax_effort: 2h\n\
+             last_updated: 2025-01-01T00:00:00Z\ndeliverables: []\ntasks: []\n",
+        )
+        .unwrap();
+        for (file, message) in [("a.rs", "Implement code for sprint"), ("b.rs", "Add unit tests")] {
+            std::fs::write(root.join(file), file).unwrap();
+            commit_project_changes(&root, &sprint, message, false, &policy).unwrap();
+        }
+        assert_eq!(git(&["log", "-1", "--format=%an <%ae> / %cn"]).trim(), "AutoFlow Bot <bot@example.com> / AutoFlow Bot");
+
+        assert_eq!(squash_sprint_commits(&root, &sprint, &policy).unwrap(), 2);
+        assert_eq!(git(&["rev-list", "--count", "HEAD"]).trim(), "2");
+        let body = git(&["log", "-1", "--format=%B"]);
+        assert!(body.starts_with("Sprint 2: Search\n\nSquashed phase commits:\n- Implement code for sprint\n- Add unit tests\n"));
+        assert_eq!(git(&["ls-files"]), "README.md\na.rs\nb.rs\n");
+        // Already one commit: nothing left to squash
+        assert_eq!(squash_sprint_commits(&root, &sprint, &policy).unwrap(), 0);
+
+        let signed = CommitConfig { sign: CommitSigning::Ssh, signing_key: Some("~/.ssh/id.pub".to_string()), ..Default::default() };
+        assert_eq!(
+            git_commit_args(&signed, "msg"),
+            ["-c", "gpg.format=ssh", "-c", "user.signingkey=~/.ssh/id.pub", "commit", "-S", "-m", "msg"]
+        );
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_detect_vcs() {
+        let root = std::env::temp_dir().join(format!("autoflow-vcs-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        assert_eq!(detect_vcs(&root, VcsBackend::Auto).name(), "none");
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        assert_eq!(detect_vcs(&root, VcsBackend::Auto).name(), "git");
+        std::fs::create_dir_all(root.join(".jj")).unwrap();
+        assert_eq!(detect_vcs(&root, VcsBackend::Auto).name(), "jujutsu");
+        assert_eq!(detect_vcs(&root, VcsBackend::Git).name(), "git");
+        assert_eq!(detect_vcs(&root, VcsBackend::None).name(), "none");
+        assert!(PlainDirectory.changed_files(&root).is_empty());
+
+        assert_eq!(jj_file_pattern("src/app.rs"), r#"root-file:"src/app.rs""#);
+        assert_eq!(jj_file_pattern(r#"odd "name".txt"#), r#"root-file:"odd \"name\".txt""#);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
     #[test]
     fn test_should_commit_after_write_phases() {
         assert!(should_commit_after_phase(SprintStatus::WriteCode));
@@ -144,10 +778,16 @@ mod tests {
     }
 
     #[test]
-    fn test_should_not_commit_after_fix_phases() {
-        assert!(!should_commit_after_phase(SprintStatus::ReviewFix));
-        assert!(!should_commit_after_phase(SprintStatus::UnitFix));
-        assert!(!should_commit_after_phase(SprintStatus::E2eFix));
+    fn test_should_commit_after_fix_phases() {
+        assert!(should_commit_after_phase(SprintStatus::ReviewFix));
+        assert!(should_commit_after_phase(SprintStatus::UnitFix));
+        assert!(should_commit_after_phase(SprintStatus::E2eFix));
+    }
+
+    #[test]
+    fn test_should_not_commit_when_pending_or_blocked() {
+        assert!(!should_commit_after_phase(SprintStatus::Pending));
+        assert!(!should_commit_after_phase(SprintStatus::Blocked));
     }
 
     #[test]
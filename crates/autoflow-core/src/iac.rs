@@ -0,0 +1,256 @@
+// Infrastructure as code: detect Terraform/Pulumi, plan natively for review, apply after approval
+
+use autoflow_data::{AutoFlowError, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Saved plans and their output live here until applied
+pub const IAC_DIR: &str = ".autoflow/iac";
+
+/// Where IaC code is looked for, relative to the project root
+const IAC_DIRS: &[&str] = &[".", "terraform", "infra", "infrastructure"];
+
+/// Lines of plan output shown to the reviewer
+const MAX_PLAN_LINES: usize = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IacTool {
+    Terraform,
+    Pulumi,
+}
+
+impl IacTool {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Terraform => "Terraform",
+            Self::Pulumi => "Pulumi",
+        }
+    }
+}
+
+/// IaC code found in a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IacProject {
+    pub tool: IacTool,
+
+    /// Directory holding the code, relative to the project root
+    pub dir: PathBuf,
+}
+
+/// Result of a native `terraform plan` / `pulumi preview`
+#[derive(Debug, Clone)]
+pub struct IacPlan {
+    pub succeeded: bool,
+    pub has_changes: bool,
+    pub output: String,
+}
+
+impl IacProject {
+    /// First of the usual directories holding `*.tf` files or `Pulumi.yaml`
+    pub fn detect(project_path: &Path) -> Option<Self> {
+        IAC_DIRS.iter().find_map(|dir| {
+            let path = project_path.join(dir);
+            let tool = if path.join("Pulumi.yaml").exists() {
+                IacTool::Pulumi
+            } else if has_tf_files(&path) {
+                IacTool::Terraform
+            } else {
+                return None;
+            };
+            Some(Self { tool, dir: PathBuf::from(dir) })
+        })
+    }
+
+    /// Briefing for infrastructure agents: they edit code, AutoFlow plans and applies
+    pub fn agent_instructions(&self) -> String {
+        let (plan, apply) = match self.tool {
+            IacTool::Terraform => ("terraform validate / terraform plan", "terraform apply"),
+            IacTool::Pulumi => ("pulumi preview", "pulumi up"),
+        };
+        format!(
+            r#"# Infrastructure as Code
+
+This project manages infrastructure with {} in `{}/`.
+- Make infrastructure changes in that code, not by hand or through cloud CLIs
+- You may run `{}` to check your work
+- NEVER run `{}` or anything else that changes real infrastructure
+- AutoFlow runs the plan itself for code review and applies it only after a human approves
+"#,
+            self.tool.name(),
+            self.dir.display(),
+            plan,
+            apply
+        )
+    }
+
+    /// Plan the sprint's changes; a plan with changes is kept until applied
+    pub fn plan(&self, project_path: &Path, sprint_id: u32) -> Result<IacPlan> {
+        let iac_dir = project_path.join(IAC_DIR);
+        std::fs::create_dir_all(&iac_dir)?;
+        let plan_file = iac_dir.join(format!("sprint-{}.tfplan", sprint_id));
+
+        let plan = match self.tool {
+            IacTool::Terraform => {
+                let init = self.run(project_path, "terraform", &["init", "-input=false", "-no-color"])?;
+                if !init.status.success() {
+                    return Ok(IacPlan { succeeded: false, has_changes: false, output: combined_output(&init) });
+                }
+                let out = format!("-out={}", plan_file.display());
+                let output = self.run(
+                    project_path,
+                    "terraform",
+                    &["plan", "-input=false", "-no-color", "-detailed-exitcode", &out],
+                )?;
+                // -detailed-exitcode: 0 = no changes, 2 = changes, anything else = error
+                let code = output.status.code();
+                IacPlan {
+                    succeeded: matches!(code, Some(0 | 2)),
+                    has_changes: code == Some(2),
+                    output: combined_output(&output),
+                }
+            }
+            IacTool::Pulumi => {
+                let output = self.run(project_path, "pulumi", &["preview", "--non-interactive", "--diff"])?;
+                let text = combined_output(&output);
+                IacPlan {
+                    succeeded: output.status.success(),
+                    has_changes: pulumi_has_changes(&text),
+                    output: text,
+                }
+            }
+        };
+
+        let pending = pending_plan_path(project_path, sprint_id);
+        if plan.succeeded && plan.has_changes {
+            std::fs::write(&pending, &plan.output)?;
+        } else {
+            let _ = std::fs::remove_file(&pending);
+            let _ = std::fs::remove_file(&plan_file);
+        }
+        Ok(plan)
+    }
+
+    /// Reviewed plan waiting to be applied, if any
+    pub fn pending_plan(&self, project_path: &Path, sprint_id: u32) -> Option<PathBuf> {
+        let path = pending_plan_path(project_path, sprint_id);
+        path.exists().then_some(path)
+    }
+
+    /// Apply the reviewed plan (Terraform applies exactly the saved plan file)
+    pub fn apply(&self, project_path: &Path, sprint_id: u32) -> Result<String> {
+        let plan_file = project_path.join(IAC_DIR).join(format!("sprint-{}.tfplan", sprint_id));
+        let output = match self.tool {
+            IacTool::Terraform => {
+                let plan_arg = plan_file.display().to_string();
+                self.run(project_path, "terraform", &["apply", "-input=false", "-no-color", &plan_arg])?
+            }
+            IacTool::Pulumi => self.run(
+                project_path,
+                "pulumi",
+                &["up", "--yes", "--non-interactive", "--skip-preview"],
+            )?,
+        };
+        let text = combined_output(&output);
+        if !output.status.success() {
+            return Err(AutoFlowError::CommandFailed(format!(
+                "{} apply failed:\n{}",
+                self.tool.name(),
+                text.trim()
+            )));
+        }
+
+        let _ = std::fs::remove_file(pending_plan_path(project_path, sprint_id));
+        let _ = std::fs::remove_file(plan_file);
+        Ok(text)
+    }
+
+    fn run(&self, project_path: &Path, program: &str, args: &[&str]) -> Result<std::process::Output> {
+        tracing::info!("IaC: {} {}", program, args.join(" "));
+        Command::new(program)
+            .args(args)
+            .current_dir(project_path.join(&self.dir))
+            .output()
+            .map_err(|e| AutoFlowError::CommandFailed(format!("Failed to run {}: {}", program, e)))
+    }
+}
+
+impl IacPlan {
+    /// Section appended to the reviewer's context
+    pub fn review_context(&self, tool: IacTool) -> String {
+        let summary = if !self.succeeded {
+            "The plan FAILED. The infrastructure code must be fixed before it can be approved."
+        } else if self.has_changes {
+            "Review every resource change below. AutoFlow applies exactly this plan after a human approves it."
+        } else {
+            "The plan has no infrastructure changes."
+        };
+        let lines: Vec<&str> = self.output.lines().collect();
+        let shown = lines.len().min(MAX_PLAN_LINES);
+        let mut output = lines[..shown].join("\n");
+        if lines.len() > shown {
+            output.push_str(&format!("\n... ({} more lines)", lines.len() - shown));
+        }
+        format!("# {} Plan\n\n{}\n\n```\n{}\n```\n", tool.name(), summary, output)
+    }
+}
+
+fn pending_plan_path(project_path: &Path, sprint_id: u32) -> PathBuf {
+    project_path.join(IAC_DIR).join(format!("sprint-{}-plan.txt", sprint_id))
+}
+
+fn has_tf_files(dir: &Path) -> bool {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .any(|e| e.path().extension().is_some_and(|ext| ext == "tf"))
+        })
+        .unwrap_or(false)
+}
+
+/// Pulumi's summary lists "N to create/update/delete/replace" when anything changes
+fn pulumi_has_changes(output: &str) -> bool {
+    ["to create", "to update", "to delete", "to replace"]
+        .iter()
+        .any(|marker| output.contains(marker))
+}
+
+fn combined_output(output: &std::process::Output) -> String {
+    format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_detect_iac_projects() {
+        let project = std::env::temp_dir().join(format!("autoflow-iac-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&project);
+        fs::create_dir_all(project.join("infra")).unwrap();
+        assert_eq!(IacProject::detect(&project), None);
+
+        fs::write(project.join("infra/main.tf"), "terraform {}").unwrap();
+        let iac = IacProject::detect(&project).unwrap();
+        assert_eq!(iac.tool, IacTool::Terraform);
+        assert_eq!(iac.dir, PathBuf::from("infra"));
+        assert!(iac.agent_instructions().contains("NEVER run `terraform apply`"));
+
+        fs::write(project.join("Pulumi.yaml"), "name: app").unwrap();
+        assert_eq!(IacProject::detect(&project).unwrap().tool, IacTool::Pulumi);
+        assert!(iac.pending_plan(&project, 1).is_none());
+
+        let _ = fs::remove_dir_all(project);
+    }
+
+    #[test]
+    fn test_pulumi_change_detection() {
+        assert!(pulumi_has_changes("Resources:\n    + 2 to create\n    5 unchanged"));
+        assert!(!pulumi_has_changes("Resources:\n    7 unchanged"));
+    }
+}
@@ -4,8 +4,56 @@ pub mod orchestrator;
 pub mod analyzer;
 pub mod workflow;
 pub mod git;
+pub mod planner;
+pub mod docs_sync;
+pub mod api_codegen;
+pub mod fixtures;
+pub mod bug_registry;
+pub mod scheduler;
+pub mod deployment;
+pub mod iac;
+pub mod routing;
+pub mod forecast;
+pub mod repos;
+pub mod task_groups;
+pub mod phase_history;
+pub mod doc_sets;
+pub mod workflow_graph;
+pub mod failure_signatures;
+pub mod failure_summary;
+pub mod phase_hooks;
+pub mod snapshots;
+pub mod workflow_fit;
+pub mod maintenance;
+pub mod patches;
+pub mod checkpoint;
+pub mod pull_requests;
 
 pub use orchestrator::*;
 pub use analyzer::*;
 pub use workflow::*;
 pub use git::*;
+pub use planner::*;
+pub use docs_sync::*;
+pub use api_codegen::*;
+pub use fixtures::*;
+pub use bug_registry::*;
+pub use scheduler::*;
+pub use deployment::*;
+pub use iac::*;
+pub use routing::*;
+pub use forecast::*;
+pub use repos::*;
+pub use task_groups::*;
+pub use phase_history::*;
+pub use doc_sets::*;
+pub use workflow_graph::*;
+pub use failure_signatures::*;
+pub use failure_summary::*;
+pub use phase_hooks::*;
+pub use snapshots::*;
+pub use workflow_fit::*;
+pub use maintenance::*;
+pub use patches::*;
+pub use checkpoint::*;
+pub use pull_requests::*;
@@ -0,0 +1,465 @@
+// Maintenance sprints: dependency bumps, TODO/FIXME debt and flaky tests, found by native scans
+
+use crate::{template_sprint, TechStack};
+use autoflow_data::{
+    AutoFlowError, IgnoreRules, MaintenanceConfig, Priority, Result, Sprint, TaskType, WorkflowType,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use walkdir::WalkDir;
+
+/// Tests known to fail intermittently, kept by hand or by CI
+pub const FLAKY_TESTS_PATH: &str = ".autoflow/flaky-tests.yml";
+
+/// When `autoflow maintain` last generated sprints
+pub const MAINTENANCE_STATE_PATH: &str = ".autoflow/maintenance.json";
+
+/// Goal prefix that marks a sprint as generated maintenance
+pub const MAINTENANCE_PREFIX: &str = "Maintenance: ";
+
+const DEBT_MARKERS: &[&str] = &["TODO", "FIXME", "XXX", "HACK"];
+
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "ts", "tsx", "js", "jsx", "mjs", "vue", "svelte", "php", "py", "go", "rb", "java", "kt", "cs", "swift",
+];
+
+const SKIP_DIRS: &[&str] = &[
+    ".git", ".autoflow", "node_modules", "vendor", "target", "dist", "build", ".venv", "venv", "__pycache__",
+];
+
+/// Items spelled out in one task description; the rest are summarised
+const MAX_LISTED: usize = 15;
+
+/// A direct dependency with a newer release
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutdatedDependency {
+    pub name: String,
+    pub current: String,
+    pub latest: String,
+}
+
+/// The command listing outdated direct dependencies for a package manager
+pub fn outdated_command(package_manager: &str) -> Option<String> {
+    let command = match package_manager {
+        "npm" | "yarn" => "npm outdated --json",
+        "pnpm" => "pnpm outdated --format json",
+        "composer" => "composer outdated --direct --format=json",
+        "cargo" => "cargo outdated --root-deps-only --format json",
+        "pip" => "pip list --outdated --format=json",
+        "poetry" | "uv" => return Some(format!("{} run pip list --outdated --format=json", package_manager)),
+        "go" => "go list -u -m -json all",
+        _ => return None,
+    };
+    Some(command.to_string())
+}
+
+/// Outdated dependencies from `outdated_command`'s output
+pub fn parse_outdated(package_manager: &str, output: &str) -> Vec<OutdatedDependency> {
+    let dep = |name: &str, current: Option<&str>, latest: Option<&str>| {
+        let (current, latest) = (current?.to_string(), latest?.to_string());
+        (current != latest && latest != "---").then(|| OutdatedDependency { name: name.to_string(), current, latest })
+    };
+    let str_field = |v: &serde_json::Value, key: &str| v.get(key).and_then(|f| f.as_str()).map(String::from);
+
+    if package_manager == "go" {
+        // A stream of module objects; the main module and indirect ones are skipped
+        return serde_json::Deserializer::from_str(output)
+            .into_iter::<serde_json::Value>()
+            .flatten()
+            .filter(|m| !m["Main"].as_bool().unwrap_or(false) && !m["Indirect"].as_bool().unwrap_or(false))
+            .filter_map(|m| {
+                let latest = m["Update"]["Version"].as_str()?;
+                dep(m["Path"].as_str()?, m["Version"].as_str(), Some(latest))
+            })
+            .collect();
+    }
+
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(output) else {
+        return Vec::new();
+    };
+    let entries: Vec<(String, Option<String>, Option<String>)> = match package_manager {
+        "npm" | "yarn" | "pnpm" => json
+            .as_object()
+            .map(|deps| {
+                deps.iter()
+                    .map(|(name, v)| (name.clone(), str_field(v, "current"), str_field(v, "latest")))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        "composer" => json["installed"]
+            .as_array()
+            .map(|deps| {
+                deps.iter()
+                    .filter_map(|v| Some((str_field(v, "name")?, str_field(v, "version"), str_field(v, "latest"))))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        "cargo" => json["dependencies"]
+            .as_array()
+            .map(|deps| {
+                deps.iter()
+                    .filter_map(|v| Some((str_field(v, "name")?, str_field(v, "project"), str_field(v, "latest"))))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => json
+            .as_array()
+            .map(|deps| {
+                deps.iter()
+                    .filter_map(|v| {
+                        Some((str_field(v, "name")?, str_field(v, "version"), str_field(v, "latest_version")))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+    entries
+        .iter()
+        .filter_map(|(name, current, latest)| dep(name, current.as_deref(), latest.as_deref()))
+        .collect()
+}
+
+/// Run the ecosystem's outdated check in `project_path`
+///
+/// Errors when the stack has no check or its tool isn't installed. Most tools
+/// exit non-zero when something is outdated, so the output is parsed regardless.
+pub fn outdated_dependencies(project_path: &Path, stack: &TechStack) -> Result<Vec<OutdatedDependency>> {
+    let command = outdated_command(&stack.package_manager).ok_or_else(|| {
+        AutoFlowError::CommandFailed(format!("no outdated-dependency check for {}", stack.package_manager))
+    })?;
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .current_dir(project_path)
+        .output()
+        .map_err(|e| AutoFlowError::CommandFailed(format!("{}: {}", command, e)))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() && stdout.trim().is_empty() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().last().unwrap_or("failed").trim().to_string();
+        return Err(AutoFlowError::CommandFailed(format!("{}: {}", command, reason)));
+    }
+    Ok(parse_outdated(&stack.package_manager, &stdout))
+}
+
+/// TODO/FIXME markers across the project's source files
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DebtScan {
+    /// Files with markers and their counts, most first
+    pub files: Vec<(String, usize)>,
+    pub markers: usize,
+    pub lines: usize,
+}
+
+impl DebtScan {
+    /// Markers per thousand source lines
+    pub fn per_kloc(&self) -> f64 {
+        if self.lines == 0 {
+            return 0.0;
+        }
+        self.markers as f64 * 1000.0 / self.lines as f64
+    }
+}
+
+fn debt_markers(line: &str) -> usize {
+    DEBT_MARKERS
+        .iter()
+        .filter(|marker| {
+            line.match_indices(*marker).any(|(i, _)| {
+                let after = line[i + marker.len()..].chars().next();
+                !after.is_some_and(|c| c.is_alphanumeric() || c == '_')
+            })
+        })
+        .count()
+}
+
+/// Count TODO/FIXME/XXX/HACK markers in source files, honouring `.autoflowignore`
+pub fn scan_debt(project_path: &Path) -> DebtScan {
+    let ignore = IgnoreRules::load(project_path);
+    let mut scan = DebtScan::default();
+    let walker = WalkDir::new(project_path).into_iter().filter_entry(|entry| {
+        let is_dir = entry.file_type().is_dir();
+        let skipped = is_dir && SKIP_DIRS.contains(&entry.file_name().to_string_lossy().as_ref());
+        let relative = entry.path().strip_prefix(project_path).unwrap_or(entry.path());
+        !skipped && !ignore.is_ignored(&relative.to_string_lossy(), is_dir)
+    });
+    for entry in walker.flatten().filter(|e| e.file_type().is_file()) {
+        let is_source = entry.path().extension().and_then(|e| e.to_str()).is_some_and(|e| SOURCE_EXTENSIONS.contains(&e));
+        let Some(content) = is_source.then(|| fs::read_to_string(entry.path()).ok()).flatten() else {
+            continue;
+        };
+        let markers: usize = content.lines().map(debt_markers).sum();
+        scan.lines += content.lines().count();
+        scan.markers += markers;
+        if markers > 0 {
+            let relative = entry.path().strip_prefix(project_path).unwrap_or(entry.path());
+            scan.files.push((relative.to_string_lossy().to_string(), markers));
+        }
+    }
+    scan.files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    scan
+}
+
+/// One entry in `.autoflow/flaky-tests.yml`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FlakyTest {
+    pub name: String,
+
+    #[serde(default)]
+    pub file: Option<String>,
+
+    /// Intermittent failures seen so far
+    #[serde(default)]
+    pub failures: u32,
+
+    #[serde(default)]
+    pub last_failed: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FlakyTestRegistry {
+    #[serde(default)]
+    pub tests: Vec<FlakyTest>,
+}
+
+impl FlakyTestRegistry {
+    pub fn load(project_path: &Path) -> Result<Self> {
+        let path = project_path.join(FLAKY_TESTS_PATH);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_yaml::from_str(&autoflow_data::read_state_to_string(path)?)?)
+    }
+
+    /// Tests that failed often enough to be worth a sprint
+    pub fn failing(&self, min_failures: u32) -> Vec<FlakyTest> {
+        self.tests.iter().filter(|t| t.failures >= min_failures.max(1)).cloned().collect()
+    }
+}
+
+/// What one maintenance scan found
+#[derive(Debug, Clone, Default)]
+pub struct MaintenanceFindings {
+    pub outdated: Vec<OutdatedDependency>,
+    pub debt: DebtScan,
+    pub flaky: Vec<FlakyTest>,
+    /// Checks that couldn't run, and why
+    pub skipped: Vec<String>,
+}
+
+/// Run every maintenance scan on the project
+pub fn scan_maintenance(project_path: &Path, stack: &TechStack, config: &MaintenanceConfig) -> MaintenanceFindings {
+    let mut findings = MaintenanceFindings { debt: scan_debt(project_path), ..Default::default() };
+    if config.check_dependencies {
+        match outdated_dependencies(project_path, stack) {
+            Ok(outdated) => findings.outdated = outdated,
+            Err(e) => findings.skipped.push(format!("dependencies: {}", e)),
+        }
+    }
+    match FlakyTestRegistry::load(project_path) {
+        Ok(registry) => findings.flaky = registry.failing(config.flaky_min_failures),
+        Err(e) => findings.skipped.push(format!("flaky tests: {}", e)),
+    }
+    findings
+}
+
+fn listing<T>(items: &[T], line: impl Fn(&T) -> String) -> String {
+    let mut text: String = items.iter().take(MAX_LISTED).map(|item| format!("- {}\n", line(item))).collect();
+    if items.len() > MAX_LISTED {
+        text.push_str(&format!("- ...and {} more\n", items.len() - MAX_LISTED));
+    }
+    text
+}
+
+/// A one-task maintenance sprint
+fn maintenance_sprint(
+    id: u32,
+    goal: &str,
+    workflow_type: WorkflowType,
+    task_type: TaskType,
+    description: String,
+    acceptance_criteria: &[&str],
+    files: Vec<String>,
+) -> Sprint {
+    let goal = format!("{}{}", MAINTENANCE_PREFIX, goal);
+    let mut sprint = template_sprint(id, &goal);
+    sprint.workflow_type = workflow_type;
+    sprint.priority = Some(Priority::Medium);
+    sprint.deliverables = vec![goal.clone()];
+    let task = &mut sprint.tasks[0];
+    task.title = goal.clone();
+    task.description = Some(description);
+    task.r#type = task_type;
+    task.feature = "maintenance".to_string();
+    task.priority = Priority::Medium;
+    task.acceptance_criteria = acceptance_criteria.iter().map(|c| c.to_string()).collect();
+    task.test_specification = Some("The existing test suite passes unchanged".to_string());
+    task.files = files;
+    sprint
+}
+
+/// Sprints for what the scan found, numbered from the plan's next id
+///
+/// A finding gets no sprint while an earlier one for it is still unfinished,
+/// so a scheduled run doesn't pile up duplicates.
+pub fn maintenance_sprints(findings: &MaintenanceFindings, plan: &[Sprint], config: &MaintenanceConfig) -> Vec<Sprint> {
+    let open = |goal: &str| {
+        plan.iter().any(|s| s.goal == format!("{}{}", MAINTENANCE_PREFIX, goal) && !s.status.is_closed())
+    };
+    let mut next_id = plan.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+    let mut sprints = Vec::new();
+
+    let goal = "Update outdated dependencies";
+    if !findings.outdated.is_empty() && !open(goal) {
+        let description = format!(
+            "Bump these direct dependencies, reading each changelog for breaking changes \
+             and adapting the code where the API moved:\n\n{}",
+            listing(&findings.outdated, |d| format!("{} {} → {}", d.name, d.current, d.latest))
+        );
+        sprints.push(maintenance_sprint(
+            next_id,
+            goal,
+            WorkflowType::Infrastructure,
+            TaskType::Infrastructure,
+            description,
+            &["Listed dependencies are on their latest versions, or the reason one can't move is noted", "Lockfile is updated"],
+            Vec::new(),
+        ));
+        next_id += 1;
+    }
+
+    let goal = "Pay down TODO/FIXME debt";
+    if findings.debt.markers > 0 && findings.debt.per_kloc() > config.max_debt_per_kloc && !open(goal) {
+        let description = format!(
+            "{} TODO/FIXME markers ({:.1} per 1000 lines). Resolve them, or turn the ones \
+             that are real follow-up work into documented issues, starting with:\n\n{}",
+            findings.debt.markers,
+            findings.debt.per_kloc(),
+            listing(&findings.debt.files, |(file, count)| format!("{} ({})", file, count))
+        );
+        let files = findings.debt.files.iter().take(MAX_LISTED).map(|(file, _)| file.clone()).collect();
+        sprints.push(maintenance_sprint(
+            next_id,
+            goal,
+            WorkflowType::Refactor,
+            TaskType::Refactor,
+            description,
+            &["Markers in the listed files are resolved or replaced with a tracked issue", "Behaviour is unchanged"],
+            files,
+        ));
+        next_id += 1;
+    }
+
+    let goal = "Stabilise flaky tests";
+    if !findings.flaky.is_empty() && !open(goal) {
+        let description = format!(
+            "These tests fail intermittently. Find the cause (timing, ordering, shared state, \
+             network) and make them deterministic rather than retrying them:\n\n{}",
+            listing(&findings.flaky, |t| match &t.file {
+                Some(file) => format!("{} in {} ({} failures)", t.name, file, t.failures),
+                None => format!("{} ({} failures)", t.name, t.failures),
+            })
+        );
+        let mut files: Vec<String> = findings.flaky.iter().filter_map(|t| t.file.clone()).collect();
+        files.dedup();
+        sprints.push(maintenance_sprint(
+            next_id,
+            goal,
+            WorkflowType::Refactor,
+            TaskType::Refactor,
+            description,
+            &["Each listed test passes reliably on repeated runs", "No test is skipped or given retries to pass"],
+            files,
+        ));
+    }
+
+    sprints
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MaintenanceState {
+    last_run: DateTime<Utc>,
+}
+
+/// When maintenance sprints were last generated
+pub fn last_maintenance_run(project_path: &Path) -> Option<DateTime<Utc>> {
+    let content = autoflow_data::read_state_to_string(project_path.join(MAINTENANCE_STATE_PATH)).ok()?;
+    serde_json::from_str::<MaintenanceState>(&content).ok().map(|state| state.last_run)
+}
+
+/// Whether `interval_days` have passed since the last run (always true before the first)
+pub fn maintenance_due(project_path: &Path, interval_days: u64, now: DateTime<Utc>) -> bool {
+    last_maintenance_run(project_path)
+        .is_none_or(|last| now.signed_duration_since(last).num_days() >= interval_days as i64)
+}
+
+pub fn record_maintenance_run(project_path: &Path, now: DateTime<Utc>) -> Result<()> {
+    let state = MaintenanceState { last_run: now };
+    autoflow_data::write_state(project_path.join(MAINTENANCE_STATE_PATH), serde_json::to_string_pretty(&state)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maintenance_sprints_from_scans() {
+        let npm = r#"{"react": {"current": "18.2.0", "wanted": "18.3.1", "latest": "19.0.0"}, "zod": {"current": "3.23.8", "latest": "3.23.8"}}"#;
+        assert_eq!(
+            parse_outdated("npm", npm),
+            [OutdatedDependency { name: "react".into(), current: "18.2.0".into(), latest: "19.0.0".into() }]
+        );
+        let go = r#"{"Path": "example.com/app", "Main": true}
+{"Path": "github.com/lib/pq", "Version": "v1.10.0", "Update": {"Version": "v1.10.9"}}
+{"Path": "golang.org/x/sys", "Version": "v0.1.0", "Indirect": true, "Update": {"Version": "v0.5.0"}}"#;
+        assert_eq!(parse_outdated("go", go).len(), 1);
+        assert_eq!(parse_outdated("pip", r#"[{"name": "django", "version": "4.2", "latest_version": "5.1"}]"#)[0].latest, "5.1");
+        assert!(parse_outdated("cargo", "not json").is_empty());
+        assert_eq!(debt_markers("// TODO: x, FIXME later, TODOS don't count"), 2);
+
+        let project = std::env::temp_dir().join(format!("autoflow-maintenance-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&project);
+        fs::create_dir_all(project.join("src")).unwrap();
+        fs::create_dir_all(project.join("node_modules/dep")).unwrap();
+        fs::create_dir_all(project.join(".autoflow")).unwrap();
+        fs::write(project.join("src/a.ts"), "// TODO one\nconst a = 1;\n// FIXME two\n").unwrap();
+        fs::write(project.join("node_modules/dep/index.js"), "// TODO not ours\n").unwrap();
+        fs::write(
+            project.join(FLAKY_TESTS_PATH),
+            "tests:\n  - name: checkout applies coupon\n    file: tests/checkout.spec.ts\n    failures: 3\n  - name: once\n    failures: 1\n",
+        )
+        .unwrap();
+
+        let debt = scan_debt(&project);
+        assert_eq!(debt.files, [("src/a.ts".to_string(), 2)]);
+        assert_eq!(debt.lines, 3);
+
+        let config = MaintenanceConfig { check_dependencies: false, ..Default::default() };
+        let stack = crate::CodebaseAnalyzer::new(&project).analyze().unwrap().tech_stack;
+        let mut findings = scan_maintenance(&project, &stack, &config);
+        assert_eq!(findings.flaky.len(), 1);
+        findings.outdated = parse_outdated("npm", npm);
+
+        let mut plan = vec![template_sprint(4, "Auth")];
+        let sprints = maintenance_sprints(&findings, &plan, &config);
+        assert_eq!(sprints.iter().map(|s| s.id).collect::<Vec<_>>(), [5, 6, 7]);
+        assert_eq!(sprints[0].workflow_type, WorkflowType::Infrastructure);
+        assert_eq!(sprints[2].goal, "Maintenance: Stabilise flaky tests");
+        assert_eq!(sprints[2].tasks[0].files, ["tests/checkout.spec.ts"]);
+        assert!(crate::workflow_mismatches(&sprints).is_empty());
+
+        // Unfinished maintenance sprints aren't generated again
+        plan.extend(sprints);
+        assert!(maintenance_sprints(&findings, &plan, &config).is_empty());
+
+        let now = Utc::now();
+        assert!(maintenance_due(&project, 7, now));
+        record_maintenance_run(&project, now).unwrap();
+        assert!(!maintenance_due(&project, 7, now + chrono::Duration::days(6)));
+        assert!(maintenance_due(&project, 7, now + chrono::Duration::days(7)));
+        let _ = fs::remove_dir_all(project);
+    }
+}
@@ -1,14 +1,57 @@
-use autoflow_data::{AutoFlowError, Result, Sprint, SprintStatus};
-use crate::workflow::get_workflow_definition;
-use crate::git::{commit_project_changes, should_commit_after_phase, get_commit_message_for_phase};
+use autoflow_agents::{agent_protocol, Marker};
+use autoflow_data::{AutoFlowError, CommitConfig, HealthConfig, HookFailure, HooksConfig, Result, Sprint, SprintStatus, TaskStatus, VcsBackend, WorkflowType};
+use crate::workflow::{workflow_for, ArtifactContract, WorkflowDefinition};
+use crate::snapshots::PhaseSnapshot;
+use crate::checkpoint::{Checkpoint, SprintCheckpoint};
+use crate::scheduler::{DagState, SprintDag};
+use crate::pull_requests::{is_linked_worktree, publish_sprint_branch};
+use crate::git::{detect_vcs, commits_after_phase, get_commit_message_for_phase};
+use crate::api_codegen::{find_api_spec, generate_api_client};
+use crate::fixtures::{FixtureSet, FIXTURES_DIR, FIXTURES_MANIFEST};
+use crate::deployment::{head_ref, DeployConfig, DeployState};
+use crate::iac::IacProject;
+use crate::analyzer::{CodebaseAnalysis, CodebaseAnalyzer, ANALYSIS_PATH};
+use crate::patches::{capture_patch, patch_mode_instructions};
+use crate::routing::{ModelTier, RoutingStats};
+use crate::repos::repo_instructions;
+use crate::task_groups::{parallel_instructions, partition_tasks, reconcile_instructions, within_targets};
+use crate::failure_signatures::{read_failure_signature, write_fix_loop_note};
+use crate::failure_summary::{summarize_failure_report_file, triage_failure_report_file};
+use crate::phase_hooks::{hook_commands, run_hook, HookStage};
+use autoflow_quality::{
+    agent_check_instructions, touches_data_model, GateContext, HealthCheckGate, HealthFailure, HealthFailureClass, HealthReport,
+    IntegrationPointsGate, MigrationGate,
+    MigrationTool, QualityGate, QualityReport, TestResults,
+};
+use autoflow_utils::{cache_key, consume_hints, current_run_id, hints_section, pending_hints, record_run_event, EnvCache, register_sprint_uid, request_signoff, run_tokens_used, sanitize_output};
 use chrono::Utc;
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Lines of the health-check agent's output kept in the health report
+const MAX_HEALTH_AGENT_LINES: usize = 30;
+
+/// Callback invoked after each orchestrator iteration to persist sprint state
+pub type SaveCallback = Box<dyn Fn(&Sprint) -> Result<()> + Send + Sync>;
+
+/// Checked at each phase boundary; returns the id of a sprint that should take over
+pub type PreemptCheck = Box<dyn Fn(&Sprint) -> Option<u32> + Send + Sync>;
 
 pub struct Orchestrator {
     max_iterations: u32,
-    save_callback: Option<Box<dyn Fn(&Sprint) -> Result<()> + Send + Sync>>,
+    save_callback: Option<SaveCallback>,
+    preempt_check: Option<PreemptCheck>,
+    token_budget: u64,
+    fix_budget: u32,
     project_path: Option<PathBuf>,
+    repos: BTreeMap<String, PathBuf>,
     enable_auto_commit: bool,
+    allow_submodule_bumps: bool,
+    vcs_backend: VcsBackend,
+    commit_policy: CommitConfig,
+    patch_checkout: Option<PathBuf>,
+    task_parallelism: usize,
+    resume: bool,
 }
 
 impl Orchestrator {
@@ -16,8 +59,18 @@ impl Orchestrator {
         Self {
             max_iterations,
             save_callback: None,
+            preempt_check: None,
+            token_budget: 0,
+            fix_budget: 0,
             project_path: None,
+            repos: BTreeMap::new(),
             enable_auto_commit: false,
+            allow_submodule_bumps: false,
+            vcs_backend: VcsBackend::Auto,
+            commit_policy: CommitConfig::default(),
+            patch_checkout: None,
+            task_parallelism: 0,
+            resume: false,
         }
     }
 
@@ -30,22 +83,143 @@ impl Orchestrator {
         self
     }
 
+    /// Pause the sprint at the next phase boundary when `check` names another sprint
+    pub fn with_preempt_check<F>(mut self, check: F) -> Self
+    where
+        F: Fn(&Sprint) -> Option<u32> + Send + Sync + 'static,
+    {
+        self.preempt_check = Some(Box::new(check));
+        self
+    }
+
+    /// Stop before the next phase once the run has used `limit` output tokens (0 = no limit)
+    pub fn with_token_budget(mut self, limit: u64) -> Self {
+        self.token_budget = limit;
+        self
+    }
+
+    /// Block a sprint instead of starting its next fix phase once it has run
+    /// `limit` fix phases in total (0 = no limit)
+    pub fn with_fix_budget(mut self, limit: u32) -> Self {
+        self.fix_budget = limit;
+        self
+    }
+
     /// Set the project path for automatic git commits
     pub fn with_project_path(mut self, path: PathBuf) -> Self {
         self.project_path = Some(path);
         self
     }
 
+    /// Checkouts of a meta-project's repositories, by name; sprints with a
+    /// `repo` run their agents and commits there
+    pub fn with_repos(mut self, repos: BTreeMap<String, PathBuf>) -> Self {
+        self.repos = repos;
+        self
+    }
+
     /// Enable automatic git commits after successful phases
     pub fn with_auto_commit(mut self, enabled: bool) -> Self {
         self.enable_auto_commit = enabled;
         self
     }
 
+    /// Allow automatic commits to bump submodule pointers
+    pub fn with_submodule_bumps(mut self, allowed: bool) -> Self {
+        self.allow_submodule_bumps = allowed;
+        self
+    }
+
+    /// Version control that automatic commits go through (default: detected per checkout)
+    pub fn with_vcs(mut self, backend: VcsBackend) -> Self {
+        self.vcs_backend = backend;
+        self
+    }
+
+    /// Which phases commit, and how commits are signed, attributed and squashed
+    pub fn with_commit_policy(mut self, policy: CommitConfig) -> Self {
+        self.commit_policy = policy;
+        self
+    }
+
+    /// Patch mode: agents work in `shadow` (see [`crate::prepare_shadow`]) and each
+    /// phase's changes are queued as a bundle instead of committed
+    pub fn with_patch_checkout(mut self, shadow: Option<PathBuf>) -> Self {
+        self.patch_checkout = shadow;
+        self
+    }
+
+    /// Implement a sprint's independent tasks with up to `limit` agents at once
+    /// (0 or 1 = one agent for the whole sprint)
+    pub fn with_task_parallelism(mut self, limit: usize) -> Self {
+        self.task_parallelism = limit;
+        self
+    }
+
+    /// Pick each sprint's loop up from its checkpoint: iteration, retry counters,
+    /// fix attempts and failure signatures (see [`crate::CHECKPOINT_PATH`])
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
     /// Run a sprint through its TDD pipeline phases
     pub async fn run_sprint(&self, sprint: &mut Sprint) -> Result<()> {
+        let result = self.drive_sprint(sprint).await;
+        if sprint.status == SprintStatus::Blocked {
+            let reason = result.as_ref().err().map(|e| e.to_string()).unwrap_or_default();
+            record_run_event("sprint_blocked", Some(sprint.id), &reason);
+        }
+        result
+    }
+
+    async fn drive_sprint(&self, sprint: &mut Sprint) -> Result<()> {
         let mut iteration = 0;
         let mut retry_count: std::collections::HashMap<SprintStatus, u32> = Default::default();
+        // Fix phases entered so far, across all fix loops
+        let mut fix_attempts: Vec<SprintStatus> = Vec::new();
+        // Signature of each validation phase's last failure report
+        let mut last_failures: std::collections::HashMap<SprintStatus, String> = Default::default();
+
+        if self.resume {
+            let saved = self.project_path.as_deref().and_then(Checkpoint::load).and_then(|c| c.sprints.get(&sprint.id).cloned());
+            match saved {
+                Some(saved) if saved.status.is_none_or(|status| status == sprint.status) => {
+                    tracing::info!("Resuming sprint {} at iteration {} from its checkpoint", sprint.id, saved.iteration);
+                    record_run_event("resumed", Some(sprint.id), &format!("{:?} at iteration {}", sprint.status, saved.iteration));
+                    iteration = saved.iteration;
+                    retry_count = saved.retry_count;
+                    fix_attempts = saved.fix_attempts;
+                    last_failures = saved.last_failures;
+                }
+                Some(_) => tracing::warn!("Sprint {} status changed since its checkpoint, starting its counters afresh", sprint.id),
+                None => {}
+            }
+        }
+
+        // Held and closed sprints are a human's call
+        match sprint.status {
+            SprintStatus::Paused => {
+                return Err(AutoFlowError::ValidationError(format!(
+                    "Sprint {} is paused; run `autoflow sprints unpause {}` first",
+                    sprint.id, sprint.id
+                )));
+            }
+            SprintStatus::Skipped | SprintStatus::Cancelled => return Ok(()),
+            _ => {}
+        }
+
+        if let Some(ref repo) = sprint.repo {
+            if !self.repos.contains_key(repo) {
+                return Err(AutoFlowError::ValidationError(format!(
+                    "Sprint {} targets repo '{}', which is not declared in project.repos",
+                    sprint.id, repo
+                )));
+            }
+        }
+
+        let hooks = autoflow_data::Config::global().map(|c| c.hooks).unwrap_or_default();
+        register_sprint_uid(sprint.id, sprint.uid.as_deref());
 
         // Set started timestamp if not already set
         if sprint.started.is_none() {
@@ -53,6 +227,22 @@ impl Orchestrator {
         }
 
         while !sprint.is_done() && iteration < self.max_iterations {
+            // Between phases the sprint's state is saved, so it can resume later
+            if iteration > 0 {
+                if let Some(by) = self.preempt_check.as_ref().and_then(|check| check(sprint)) {
+                    tracing::info!("Sprint {} preempted by critical sprint {}", sprint.id, by);
+                    record_run_event("preempted", Some(sprint.id), &format!("by sprint {}", by));
+                    return Err(AutoFlowError::SprintPreempted { sprint: sprint.id, by });
+                }
+            }
+            if self.token_budget > 0 {
+                let used = run_tokens_used();
+                if used >= self.token_budget {
+                    tracing::warn!("Token budget exhausted ({} of {}), stopping sprint {}", used, self.token_budget, sprint.id);
+                    record_run_event("budget_exceeded", Some(sprint.id), &format!("{} of {} tokens", used, self.token_budget));
+                    return Err(AutoFlowError::BudgetExceeded { used, limit: self.token_budget });
+                }
+            }
             iteration += 1;
 
             tracing::info!(
@@ -79,37 +269,28 @@ impl Orchestrator {
 
                         // Create git commit for blocker-resolver fixes
                         if self.enable_auto_commit {
-                            if let Some(ref project_path) = self.project_path {
-                                tracing::debug!("Committing blocker-resolver fixes for sprint {}", sprint.id);
-                                let commit_msg = format!("Sprint {}: Fix blocked issues (blocker-resolver)", sprint.id);
-                                if let Err(e) = commit_project_changes(project_path, sprint, &commit_msg) {
-                                    tracing::warn!("Failed to create git commit for blocker-resolver: {}", e);
-                                }
-                            }
+                            tracing::debug!("Committing blocker-resolver fixes for sprint {}", sprint.id);
+                            let commit_msg = format!("Sprint {}: Fix blocked issues (blocker-resolver)", sprint.id);
+                            self.record_changes(sprint, SprintStatus::Blocked, &commit_msg);
                         }
 
-                        // Blocker-resolver may have fixed the issue, determine appropriate status to retry from
-                        // For infrastructure/docs: go to first test/validation phase
-                        // For implementation: go to RUN_UNIT_TESTS
-                        let workflow = get_workflow_definition(sprint.workflow_type);
-
-                        // Try to find a validation phase (has fix_status, requires_validation)
-                        // Otherwise default to WriteCode to restart the workflow
-                        let retry_status = workflow.phases.iter()
-                            .find(|p| p.requires_validation && p.fix_status.is_some())
-                            .map(|p| p.status)
-                            .unwrap_or(SprintStatus::WriteCode);
+                        // Blocker-resolver may have fixed the issue: verify it from the first validation phase
+                        let retry_status = workflow_for(sprint).unblock_status();
 
                         tracing::info!("Blocker-resolver completed, resetting sprint {} ({:?} workflow) to {:?} to verify fix",
                             sprint.id, sprint.workflow_type, retry_status);
                         sprint.status = retry_status;
                         sprint.blocked_count = Some(0); // Reset blocked count
+                        sprint.failure_reports.clear(); // Why it blocked; the next failure writes its own reports
+                        last_failures.clear();
+                        record_run_event("reset", Some(sprint.id), &format!("{:?}", retry_status));
                         sprint.last_updated = Utc::now();
 
                         // Save progress and continue loop to retry
                         if let Some(ref save_fn) = self.save_callback {
                             save_fn(sprint)?;
                         }
+                        self.save_checkpoint(sprint, iteration, &retry_count, &fix_attempts, &last_failures);
                         continue; // Continue to next iteration
                     }
                     Err(e) => {
@@ -123,8 +304,40 @@ impl Orchestrator {
                 }
             }
 
+            // Deploys and infrastructure applies stop for approval; the orchestrator
+            // (never an agent) performs them
+            let stop = match (sprint.workflow_type, sprint.status) {
+                (WorkflowType::Deployment, SprintStatus::Deploy) => self.begin_deployment(sprint).err(),
+                (WorkflowType::Deployment, SprintStatus::Rollback) => Some(self.roll_back_deployment(sprint)),
+                (WorkflowType::Infrastructure, SprintStatus::WriteE2eTests) => self.apply_reviewed_plan(sprint).err(),
+                _ => None,
+            };
+            if let Some(error) = stop {
+                // Waiting for approval isn't a failure; anything else needs a human
+                if !matches!(error, AutoFlowError::DeploymentNotApproved(_) | AutoFlowError::ApplyNotApproved(_)) {
+                    sprint.status = SprintStatus::Blocked;
+                    sprint.blocked_count = Some(1);
+                    sprint.last_updated = Utc::now();
+                    if let Some(ref save_fn) = self.save_callback {
+                        save_fn(sprint)?;
+                    }
+                }
+                return Err(error);
+            }
+
             // Execute the phase based on current status
+            sprint.advance_tasks(TaskStatus::InProgress, None);
+            self.snapshot_phase(sprint);
+            record_run_event("phase", Some(sprint.id), &format!("{:?}", sprint.status));
+            self.run_phase_hooks(&hooks, HookStage::PrePhase, sprint, None).await?;
+            let phase_started = std::time::SystemTime::now();
             let phase_result = self.execute_phase(sprint).await;
+            let outcome = match phase_result {
+                Ok(true) => "passed",
+                Ok(false) => "failed",
+                Err(_) => "error",
+            };
+            self.run_phase_hooks(&hooks, HookStage::PostPhase, sprint, Some(outcome)).await?;
 
             match phase_result {
                 Ok(should_advance) => {
@@ -135,7 +348,7 @@ impl Orchestrator {
                         // Reset retry count for this status
                         retry_count.insert(current_status, 0);
 
-                        let workflow = get_workflow_definition(sprint.workflow_type);
+                        let workflow = workflow_for(sprint);
 
                         // If this is a fix phase, loop back to the validation phase
                         // Otherwise, advance to the next phase (skipping fix phases)
@@ -169,13 +382,53 @@ impl Orchestrator {
                             sprint.status = status;
                             sprint.last_updated = Utc::now();
 
-                            // Create git commit after successful phase completion
-                            if self.enable_auto_commit && should_commit_after_phase(previous_status) {
+                            // Compare what WRITE_CODE touched with the declared integration points
+                            if previous_status == SprintStatus::WriteCode {
+                                self.check_integration_points(sprint);
+                            }
+
+                            // Infrastructure sprints regenerate the API client from the spec
+                            if previous_status == SprintStatus::WriteCode
+                                && sprint.workflow_type == WorkflowType::Infrastructure
+                            {
                                 if let Some(ref project_path) = self.project_path {
-                                    tracing::debug!("Attempting to commit after phase: {:?}", previous_status);
-                                    let commit_msg = get_commit_message_for_phase(previous_status);
-                                    if let Err(e) = commit_project_changes(project_path, sprint, commit_msg) {
-                                        tracing::warn!("Failed to create git commit for phase {:?}: {}", previous_status, e);
+                                    if find_api_spec(project_path).is_some() {
+                                        match generate_api_client(project_path, None, None) {
+                                            Ok(files) => tracing::info!("Generated API client: {} file(s)", files.len()),
+                                            Err(e) => tracing::warn!("API client generation skipped: {}", e),
+                                        }
+                                    }
+                                }
+                            }
+
+                            // Create git commit after successful phase completion
+                            let committed = self.enable_auto_commit && commits_after_phase(&self.commit_policy, previous_status);
+                            if committed {
+                                tracing::debug!("Attempting to commit after phase: {:?}", previous_status);
+                                self.record_changes(sprint, previous_status, get_commit_message_for_phase(previous_status));
+                            }
+
+                            // Tasks move with the phase they just passed, and are done with the sprint
+                            if let Some(task_status) = previous_status.task_status() {
+                                let commit = if committed { self.head_commit(sprint) } else { None };
+                                sprint.advance_tasks(task_status, commit.as_deref());
+                            }
+                            if sprint.is_done() {
+                                sprint.advance_tasks(TaskStatus::Done, None);
+                            }
+
+                            // One commit per sprint, when the policy asks for it; patch mode has nothing to squash
+                            if self.enable_auto_commit && self.commit_policy.squash_on_done && sprint.is_done() && self.patch_checkout.is_none() {
+                                if let Some(project_path) = self.commit_path(sprint) {
+                                    match detect_vcs(project_path, self.vcs_backend).squash_sprint(project_path, sprint, &self.commit_policy) {
+                                        // The phase commits are gone; point the tasks at the squashed one
+                                        Ok(()) => {
+                                            let head = self.head_commit(sprint);
+                                            for task in &mut sprint.tasks {
+                                                task.git_commit = head.clone().or(task.git_commit.take());
+                                            }
+                                        }
+                                        Err(e) => tracing::warn!("Failed to squash sprint {} commits: {}", sprint.id, e),
                                     }
                                 }
                             }
@@ -193,7 +446,7 @@ impl Orchestrator {
                         *count += 1;
 
                         // Get workflow to check max retries
-                        let workflow = get_workflow_definition(sprint.workflow_type);
+                        let workflow = workflow_for(sprint);
                         let max_retries = workflow
                             .get_phase(current_status)
                             .map(|p| p.max_retries)
@@ -207,8 +460,53 @@ impl Orchestrator {
                             max_retries
                         );
 
+                        // The same failure twice in a row means the fix loop isn't converging
+                        let signature = self
+                            .commit_path(sprint)
+                            .and_then(|p| read_failure_signature(p, current_status, sprint.id, phase_started));
+                        let repeated = signature.is_some() && last_failures.get(&current_status) == signature.as_ref();
+                        if let Some(signature) = signature {
+                            last_failures.insert(current_status, signature);
+                        }
+
                         // Check if we've exceeded max retries OR if this sprint uses blocker-resolver
-                        if *count >= max_retries {
+                        if current_status == SprintStatus::Complete {
+                            self.route_failed_health_checks(sprint, &workflow, *count, max_retries, &mut fix_attempts)?;
+                        } else if repeated {
+                            tracing::warn!(
+                                "Sprint {} failed {:?} twice with the same failure, leaving the fix loop",
+                                sprint.id,
+                                current_status
+                            );
+                            record_run_event(
+                                "loop_detected",
+                                Some(sprint.id),
+                                &format!("{:?}: {}", current_status, last_failures[&current_status]),
+                            );
+                            // Kept on the sprint, so status, resolve and the blocker resolver see why it stopped
+                            let reports = self
+                                .commit_path(sprint)
+                                .and_then(|p| write_fix_loop_note(p, current_status, sprint.id, *count));
+                            for report in reports.into_iter().flatten() {
+                                if !sprint.failure_reports.contains(&report) {
+                                    sprint.failure_reports.push(report);
+                                }
+                            }
+                            let resolver_tried = sprint.uses_blocker_resolver;
+                            sprint.status = SprintStatus::Blocked;
+                            sprint.blocked_count = Some(*count);
+                            sprint.last_updated = Utc::now();
+                            // The blocker resolver takes over next iteration, unless it already had its go
+                            if resolver_tried {
+                                if let Some(ref save_fn) = self.save_callback {
+                                    save_fn(sprint)?;
+                                }
+                                return Err(AutoFlowError::SprintBlocked(
+                                    sprint.id,
+                                    format!("{:?} keeps failing the same way after the blocker resolver", current_status),
+                                ));
+                            }
+                        } else if *count >= max_retries {
                             tracing::error!(
                                 "Sprint {} exceeded max retries for {:?}, marking as BLOCKED",
                                 sprint.id,
@@ -233,6 +531,10 @@ impl Orchestrator {
                         } else {
                             // Move to fix status if available (from workflow)
                             if let Some(fix_phase) = workflow.get_fix_phase(current_status) {
+                                if self.fix_budget > 0 && fix_attempts.len() as u32 >= self.fix_budget {
+                                    return self.exhaust_fix_budget(sprint, &fix_attempts);
+                                }
+                                fix_attempts.push(fix_phase.status);
                                 sprint.status = fix_phase.status;
                                 sprint.last_updated = Utc::now();
                                 tracing::info!(
@@ -244,8 +546,17 @@ impl Orchestrator {
                         }
                     }
                 }
+                // The network never came back, or an agent can't be trusted to report:
+                // stop where the sprint is, so `start` resumes it once that's fixed
+                Err(e @ (AutoFlowError::NetworkUnavailable { .. } | AutoFlowError::AgentProtocolMismatch { .. })) => {
+                    if let Some(ref save_fn) = self.save_callback {
+                        save_fn(sprint)?;
+                    }
+                    return Err(e);
+                }
                 Err(e) => {
                     tracing::error!("Sprint {} phase failed: {}", sprint.id, e);
+                    record_run_event("error", Some(sprint.id), &format!("{:?}: {}", sprint.status, e));
 
                     // Track retry
                     let current_status = sprint.status;
@@ -253,7 +564,7 @@ impl Orchestrator {
                     *count += 1;
 
                     // Get workflow to check max retries
-                    let workflow = get_workflow_definition(sprint.workflow_type);
+                    let workflow = workflow_for(sprint);
                     let max_retries = workflow
                         .get_phase(current_status)
                         .map(|p| p.max_retries)
@@ -271,6 +582,7 @@ impl Orchestrator {
             if let Some(ref save_fn) = self.save_callback {
                 save_fn(sprint)?;
             }
+            self.save_checkpoint(sprint, iteration, &retry_count, &fix_attempts, &last_failures);
         }
 
         // Check if we hit max iterations
@@ -278,21 +590,76 @@ impl Orchestrator {
             return Err(AutoFlowError::MaxIterationsExceeded(self.max_iterations));
         }
 
+        // A finished sprint has nothing to resume
+        if sprint.is_done() {
+            if let Some(ref project_path) = self.project_path {
+                if let Err(e) = Checkpoint::clear(project_path, sprint.id) {
+                    tracing::warn!("Failed to clear sprint {} checkpoint: {}", sprint.id, e);
+                }
+            }
+        }
+
         // Set completion timestamp if done
         if sprint.is_done() && sprint.completed_at.is_none() {
             sprint.completed_at = Some(Utc::now());
+            record_run_event("sprint_done", Some(sprint.id), &sprint.goal);
+            self.open_pull_request(sprint);
         }
 
         Ok(())
     }
 
+    /// With `[forge] open_pull_requests`, push a sprint worktree's branch and open a PR for it (best effort)
+    fn open_pull_request(&self, sprint: &Sprint) {
+        let forge = autoflow_data::Config::global().map(|c| c.forge).unwrap_or_default();
+        if !forge.open_pull_requests || (self.patch_checkout.is_some() && sprint.repo.is_none()) {
+            return;
+        }
+        let Some(checkout) = self.commit_path(sprint).filter(|p| is_linked_worktree(p)) else {
+            return;
+        };
+        let Some(branch) = autoflow_git::current_branch(checkout).filter(|b| autoflow_git::is_autoflow_branch(b)) else {
+            return;
+        };
+        match publish_sprint_branch(checkout, sprint, &branch, &forge) {
+            Ok(url) => {
+                tracing::info!("✓ Sprint {} pull request: {}", sprint.id, url);
+                record_run_event("pull_request", Some(sprint.id), &url);
+            }
+            Err(e) => tracing::warn!("Failed to open a pull request for sprint {}: {}", sprint.id, e),
+        }
+    }
+
+    /// Record where the sprint's loop stands, for `autoflow resume`
+    fn save_checkpoint(
+        &self,
+        sprint: &Sprint,
+        iteration: u32,
+        retry_count: &std::collections::HashMap<SprintStatus, u32>,
+        fix_attempts: &[SprintStatus],
+        last_failures: &std::collections::HashMap<SprintStatus, String>,
+    ) {
+        let Some(ref project_path) = self.project_path else {
+            return;
+        };
+        let checkpoint = SprintCheckpoint {
+            status: Some(sprint.status),
+            iteration,
+            retry_count: retry_count.clone(),
+            fix_attempts: fix_attempts.to_vec(),
+            last_failures: last_failures.clone(),
+            updated_at: Some(Utc::now()),
+        };
+        if let Err(e) = Checkpoint::update(project_path, current_run_id(), sprint.id, checkpoint) {
+            tracing::warn!("Failed to checkpoint sprint {}: {}", sprint.id, e);
+        }
+    }
+
     /// Execute a phase based on sprint status
     /// Returns Ok(true) if should advance, Ok(false) if should retry, Err if failed
-    async fn execute_phase(&self, sprint: &Sprint) -> Result<bool> {
-        use autoflow_agents::{build_agent_context, build_fixer_context, build_test_runner_context, execute_agent};
-
+    async fn execute_phase(&self, sprint: &mut Sprint) -> Result<bool> {
         // Get workflow definition for this sprint
-        let workflow = get_workflow_definition(sprint.workflow_type);
+        let workflow = workflow_for(sprint);
 
         // Get the current phase from workflow
         let phase = workflow.get_phase(sprint.status).ok_or_else(|| {
@@ -313,6 +680,23 @@ impl Orchestrator {
             return Ok(true);
         }
 
+        // Smoke checks run natively against the deployed URLs
+        if sprint.status == SprintStatus::SmokeTest {
+            return self.run_smoke_checks(sprint).await;
+        }
+
+        // Completion checks run natively; the agent only sees project-specific ones
+        let health = (sprint.status == SprintStatus::Complete)
+            .then(|| autoflow_data::Config::global().map(|c| c.health).unwrap_or_default());
+        if let Some(ref health) = health {
+            if !self.run_health_checks(sprint, health) {
+                return Ok(false);
+            }
+            if health.agent_checks.is_empty() {
+                return Ok(true);
+            }
+        }
+
         // Skip execution if agent is "none"
         if phase.agent == "none" {
             tracing::info!(
@@ -325,27 +709,50 @@ impl Orchestrator {
 
         let agent_name = phase.agent;
 
-        // Use lightweight context for different agent types to reduce token usage
-        let context = match sprint.status {
-            // Test runner agents - only need test specifications
-            SprintStatus::RunUnitTests
-            | SprintStatus::RunE2eTests
-            | SprintStatus::WriteE2eTests => {
-                tracing::debug!("Using lightweight test runner context for {:?}", sprint.status);
-                build_test_runner_context(sprint)
-            }
-            // Fixer agents - only need failure reports
-            SprintStatus::ReviewFix
-            | SprintStatus::UnitFix
-            | SprintStatus::E2eFix => {
-                tracing::debug!("Using lightweight fixer context for {:?}", sprint.status);
-                build_fixer_context(sprint)
+        let analysis = self.analysis();
+        let hint_count = self.operator_hints(sprint).len();
+        let mut context = self.agent_context(sprint, analysis.as_ref());
+        if let Some(health) = health {
+            context.push_str("\n\n");
+            context.push_str(&agent_check_instructions(&health.agent_checks));
+        }
+
+        // E2E writers and fixers work against the seeded fixture data
+        let fixtures = self.fixtures();
+        if matches!(sprint.status, SprintStatus::WriteE2eTests | SprintStatus::E2eFix) {
+            if let Some(ref fixtures) = fixtures {
+                context.push_str("\n\n");
+                context.push_str(&fixtures.agent_instructions());
             }
-            // All other agents - need full context
-            _ => {
-                build_agent_context(sprint)
+        }
+
+        // Migrations must apply to an empty database before tests run against them
+        if self.is_migration_checkpoint(sprint) && !self.verify_migrations(sprint) {
+            return Ok(false);
+        }
+
+        // E2E runs reuse the dependencies and images earlier checkouts built
+        if sprint.status == SprintStatus::RunE2eTests {
+            self.bootstrap_env_cache(sprint);
+        }
+
+        // Every E2E attempt starts from freshly loaded fixtures
+        if sprint.status == SprintStatus::RunE2eTests {
+            if let (Some(fixtures), Some(project_path)) = (fixtures, self.project_path.as_deref()) {
+                match fixtures.prepare(project_path) {
+                    Ok(()) => self.clear_failure_report(sprint, "fixtures"),
+                    Err(e) => {
+                        tracing::warn!("Sprint {} fixtures failed to load - moving to fix phase", sprint.id);
+                        self.write_failure_report(
+                            sprint,
+                            "fixtures",
+                            &format!("# Fixture Failure - Sprint {}\n\nFixtures could not be loaded before E2E tests.\n\n```\n{}\n```\n", sprint.id, e),
+                        );
+                        return Ok(false);
+                    }
+                }
             }
-        };
+        }
 
         let max_turns = phase.max_turns;
 
@@ -358,23 +765,48 @@ impl Orchestrator {
         );
 
         // Archive any existing failure reports before running agents that write to .failures
-        if let Some(ref project_path) = self.project_path {
-            archive_failure_reports_before_agent(project_path, sprint.id, agent_name);
+        if let Some(workdir) = self.commit_path(sprint) {
+            archive_failure_reports_before_agent(workdir, sprint.id, agent_name);
         }
 
-        // Execute agent
-        let result = execute_agent(agent_name, &context, max_turns, Some(sprint.id))
-            .await
-            .map_err(|e| AutoFlowError::AgentExecutionFailed(agent_name.to_string(), e.to_string()))?;
+        // What the checkout looked like, to tell which files the phase produced
+        let before = phase.artifacts.and_then(|_| self.change_snapshot(sprint));
+
+        // Independent tasks get an implementer each
+        if let Some(groups) = self.parallel_task_groups(sprint) {
+            let advanced = self.execute_task_groups(sprint, agent_name, max_turns, groups, analysis.as_ref()).await?;
+            self.consume_operator_hints(sprint, hint_count);
+            return match (advanced, phase.artifacts, before) {
+                (true, Some(contract), Some(before)) => self.check_artifacts(sprint, agent_name, &context, max_turns, &contract, &before).await,
+                _ => Ok(advanced),
+            };
+        }
+
+        // Execute agent (low-risk agents may try the cheap model first)
+        let result = self.execute_routed(agent_name, &context, max_turns, sprint).await?;
+        self.consume_operator_hints(sprint, hint_count);
 
         if result.success {
             tracing::info!("Agent '{}' completed successfully", agent_name);
+            self.check_protocol_violations(sprint, agent_name, &result.output);
+            self.summarize_failure_report(sprint, agent_name);
+            self.triage_failure_report(sprint, agent_name).await;
+
+            if let (Some(contract), Some(before)) = (phase.artifacts, before.as_ref()) {
+                if !self.check_artifacts(sprint, agent_name, &context, max_turns, &contract, before).await? {
+                    return Ok(false);
+                }
+            }
 
             // Determine if we should advance or retry based on status
             let should_advance = match sprint.status {
                 // Test phases - check if tests actually passed
                 SprintStatus::RunUnitTests | SprintStatus::RunE2eTests => {
-                    let passed = parse_test_results(&result.output);
+                    let mut passed = parse_verdict(Marker::TestResult, &result.output);
+                    if sprint.status == SprintStatus::RunUnitTests && !self.check_test_command(sprint, analysis.as_ref(), &result.commands) {
+                        passed = false;
+                    }
+                    self.record_test_result(sprint, passed);
                     if passed {
                         tracing::info!("Tests passed - advancing to next phase");
                     } else {
@@ -384,7 +816,12 @@ impl Orchestrator {
                 }
                 // Review phase - check if review actually passed
                 SprintStatus::CodeReview => {
-                    let passed = parse_review_results(&result.output);
+                    let passed = parse_verdict(Marker::ReviewStatus, &result.output);
+                    // Findings go onto their tasks, for the fixer and `sprints show`
+                    let findings = sprint.record_review_findings(&result.output);
+                    if findings > 0 {
+                        tracing::info!("Attached {} review finding(s) to sprint {} tasks", findings, sprint.id);
+                    }
                     if passed {
                         tracing::info!("Code review passed - advancing to next phase");
                     } else {
@@ -392,6 +829,18 @@ impl Orchestrator {
                     }
                     passed
                 }
+                // Project-specific health checks - the agent's verdict decides
+                SprintStatus::Complete => {
+                    let passed = Marker::HealthStatus.parse(&result.output) != Some(false);
+                    if !passed {
+                        tracing::warn!("Project-specific health checks failed");
+                        let lines: Vec<&str> = result.output.lines().filter(|l| !l.trim().is_empty()).collect();
+                        let tail = lines[lines.len().saturating_sub(MAX_HEALTH_AGENT_LINES)..].join("\n");
+                        let failure = HealthFailure::new(HealthFailureClass::Project, format!("Project-specific checks failed:\n{}", tail));
+                        self.report_health_failures(sprint, vec![failure]);
+                    }
+                    passed
+                }
                 // All other phases advance on success
                 _ => true,
             };
@@ -407,33 +856,1009 @@ impl Orchestrator {
         }
     }
 
-    /// Run multiple sprints in parallel
-    pub async fn run_parallel(&self, sprints: &mut [Sprint]) -> Result<Vec<Result<()>>> {
-        use futures::future::join_all;
+    /// Files with uncommitted changes and a hash of each, so a phase's own
+    /// changes can be told apart from what was already dirty; None without version control
+    fn change_snapshot(&self, sprint: &Sprint) -> Option<BTreeMap<String, String>> {
+        let checkout = self.commit_path(sprint)?;
+        let vcs = detect_vcs(checkout, self.vcs_backend);
+        if vcs.name() == "none" {
+            return None;
+        }
+        Some(vcs.changed_files(checkout).into_iter().map(|file| {
+            let hash = cache_key(&[checkout.join(&file)]);
+            (file, hash)
+        }).collect())
+    }
+
+    /// The phase's change manifest: files that changed since `before`
+    fn changes_since(&self, sprint: &Sprint, before: &BTreeMap<String, String>) -> Vec<String> {
+        self.change_snapshot(sprint)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(file, hash)| before.get(file) != Some(hash))
+            .map(|(file, _)| file)
+            .collect()
+    }
+
+    /// Hold the phase to its artifact contract, rerunning the agent once with
+    /// explicit feedback when it didn't produce what the phase promises
+    async fn check_artifacts(
+        &self,
+        sprint: &Sprint,
+        agent_name: &str,
+        context: &str,
+        max_turns: u32,
+        contract: &ArtifactContract,
+        before: &BTreeMap<String, String>,
+    ) -> Result<bool> {
+        let changed = self.changes_since(sprint, before);
+        if contract.is_met(&changed) {
+            self.clear_failure_report(sprint, "artifacts");
+            return Ok(true);
+        }
+
+        let feedback = contract.feedback(&changed);
+        tracing::warn!("Sprint {} {:?} produced no {} - retrying '{}' with feedback", sprint.id, sprint.status, contract.description, agent_name);
+        record_run_event("artifacts_missing", Some(sprint.id), &format!("{:?}: {}", sprint.status, contract.description));
+        let retry = self.execute_routed(agent_name, &format!("{}\n\n{}", feedback, context), max_turns, sprint).await?;
+
+        let changed = self.changes_since(sprint, before);
+        if retry.success && contract.is_met(&changed) {
+            self.clear_failure_report(sprint, "artifacts");
+            return Ok(true);
+        }
+        tracing::warn!("Sprint {} {:?} still has no {} after the retry", sprint.id, sprint.status, contract.description);
+        self.write_failure_report(
+            sprint,
+            "artifacts",
+            &format!("# Missing Artifacts - Sprint {} ({:?})\n\n{}", sprint.id, sprint.status, contract.feedback(&changed)),
+        );
+        Ok(false)
+    }
+
+    /// Context for the phase's agent: the sprint, plus whatever briefings the phase needs
+    fn agent_context(&self, sprint: &Sprint, analysis: Option<&CodebaseAnalysis>) -> String {
+        use autoflow_agents::{build_agent_context, build_fixer_context, build_test_runner_context};
+
+        // Use lightweight context for different agent types to reduce token usage
+        let mut context = match sprint.status {
+            // Test runner agents - only need test specifications
+            SprintStatus::RunUnitTests
+            | SprintStatus::RunE2eTests
+            | SprintStatus::WriteE2eTests => {
+                tracing::debug!("Using lightweight test runner context for {:?}", sprint.status);
+                build_test_runner_context(sprint)
+            }
+            // Fixer agents - only need failure reports
+            SprintStatus::ReviewFix
+            | SprintStatus::UnitFix
+            | SprintStatus::E2eFix => {
+                tracing::debug!("Using lightweight fixer context for {:?}", sprint.status);
+                build_fixer_context(sprint, self.workdir(sprint))
+            }
+            // All other agents - need full context
+            _ => {
+                let mut context = build_agent_context(sprint, self.workdir(sprint));
+                if let Some(tool) = self.migration_tool_for(sprint) {
+                    context.push_str("\n\n");
+                    context.push_str(&tool.agent_instructions());
+                }
+                if let Some(iac) = self.iac_for(sprint) {
+                    context.push_str("\n\n");
+                    context.push_str(&iac.agent_instructions());
+                    if sprint.status == SprintStatus::CodeReview {
+                        context.push_str("\n\n");
+                        context.push_str(&self.plan_for_review(&iac, sprint));
+                    }
+                }
+                if sprint.status == SprintStatus::CodeReview {
+                    if let Some(report) = self
+                        .failure_report_path(sprint, "integration-points")
+                        .and_then(|path| std::fs::read_to_string(path).ok())
+                    {
+                        context.push_str("\n\n");
+                        context.push_str(&report);
+                    }
+                }
+                context
+            }
+        };
+
+        // Meta-project sprints work in another checkout
+        if let Some(briefing) = self.repo_briefing(sprint) {
+            context.push_str("\n\n");
+            context.push_str(&briefing);
+        }
+        if let (Some(shadow), Some(project_path), None) = (self.patch_checkout.as_deref(), self.project_path.as_deref(), sprint.repo.as_ref()) {
+            context.push_str("\n\n");
+            context.push_str(&patch_mode_instructions(shadow, project_path));
+        }
+
+        // Every agent gets the detected stack so it doesn't guess tools
+        if let Some(facts) = analysis.and_then(|a| a.project_facts()) {
+            context.push_str("\n\n");
+            context.push_str(&facts);
+        }
+
+        // Operator hints go first, so they're read before anything else
+        let hints = self.operator_hints(sprint);
+        if !hints.is_empty() {
+            context = format!("{}\n\n{}", hints_section(&hints), context);
+        }
+
+        context
+    }
+
+    /// Hints queued for the sprint's next agent with `autoflow hint`
+    fn operator_hints(&self, sprint: &Sprint) -> Vec<String> {
+        self.project_path
+            .as_deref()
+            .map(|project_path| pending_hints(project_path, sprint.id))
+            .unwrap_or_default()
+    }
+
+    /// Drop the hints an agent just ran with
+    fn consume_operator_hints(&self, sprint: &Sprint, count: usize) {
+        let Some(project_path) = self.project_path.as_deref().filter(|_| count > 0) else {
+            return;
+        };
+        match consume_hints(project_path, sprint.id, count) {
+            Ok(()) => record_run_event("hints_consumed", Some(sprint.id), &count.to_string()),
+            Err(e) => tracing::warn!("Failed to clear sprint {} hints: {}", sprint.id, e),
+        }
+    }
+
+    /// Task groups to implement side by side, when this phase and sprint allow it
+    fn parallel_task_groups(&self, sprint: &Sprint) -> Option<Vec<Vec<usize>>> {
+        if self.task_parallelism < 2 || sprint.status != SprintStatus::WriteCode {
+            return None;
+        }
+        partition_tasks(&sprint.tasks)
+    }
+
+    /// Run one implementer per task group in the same checkout, then check
+    /// that nobody strayed outside the declared files
+    ///
+    /// The groups' targets are disjoint, so when every agent kept to its own
+    /// files the changes need no merging and the sprint moves on to review.
+    /// If any file outside the targets changed, the groups may have collided,
+    /// so a single implementer goes over the whole sprint to reconcile the
+    /// combined changes, and the phase passes only if that run succeeds.
+    async fn execute_task_groups(
+        &self,
+        sprint: &Sprint,
+        agent_name: &str,
+        max_turns: u32,
+        groups: Vec<Vec<usize>>,
+        analysis: Option<&CodebaseAnalysis>,
+    ) -> Result<bool> {
+        use futures::stream::{self, StreamExt};
+
+        let group_sprints: Vec<Sprint> = groups
+            .iter()
+            .map(|group| {
+                let mut group_sprint = sprint.clone();
+                group_sprint.tasks = group.iter().map(|&i| sprint.tasks[i].clone()).collect();
+                group_sprint
+            })
+            .collect();
+        tracing::info!(
+            "Sprint {}: implementing {} independent task groups, {} at a time",
+            sprint.id,
+            groups.len(),
+            self.task_parallelism
+        );
+        record_run_event(
+            "task_groups",
+            Some(sprint.id),
+            &group_sprints
+                .iter()
+                .map(|s| s.tasks.iter().map(|t| t.id.as_str()).collect::<Vec<_>>().join("+"))
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+
+        let checkout = self.commit_path(sprint);
+        let vcs = checkout.map(|path| detect_vcs(path, self.vcs_backend));
+        let dirty_before = match (checkout, &vcs) {
+            (Some(path), Some(vcs)) => vcs.changed_files(path),
+            _ => Vec::new(),
+        };
+
+        let group_count = group_sprints.len();
+        let results: Vec<_> = stream::iter(group_sprints.iter().map(|group_sprint| async move {
+            let mut context = self.agent_context(group_sprint, analysis);
+            context.push_str("\n\n");
+            context.push_str(&parallel_instructions(&group_sprint.tasks, group_count));
+            self.execute_routed(agent_name, &context, max_turns, group_sprint).await
+        }))
+        .buffer_unordered(self.task_parallelism)
+        .collect()
+        .await;
+
+        let mut all_succeeded = true;
+        for result in results {
+            let result = result?;
+            if !result.success {
+                tracing::warn!("Agent '{}' failed on a task group: {:?}", agent_name, result.error);
+                all_succeeded = false;
+            }
+        }
+
+        if let (Some(checkout), Some(vcs)) = (checkout, &vcs) {
+            let targets: Vec<String> = group_sprints
+                .iter()
+                .flat_map(|s| s.tasks.iter().flat_map(|t| t.files.iter().cloned()))
+                .collect();
+            let stray: Vec<String> = vcs
+                .changed_files(checkout)
+                .into_iter()
+                .filter(|f| !f.starts_with(".autoflow/") && !dirty_before.contains(f) && !within_targets(f, &targets))
+                .collect();
+            if !stray.is_empty() {
+                tracing::warn!(
+                    "Sprint {}: parallel implementers changed files outside the tasks' targets: {}",
+                    sprint.id,
+                    stray.join(", ")
+                );
+                record_run_event("task_groups_stray", Some(sprint.id), &stray.join(", "));
+                if all_succeeded {
+                    return self.reconcile_task_groups(sprint, agent_name, max_turns, &stray, analysis).await;
+                }
+            }
+        }
+
+        Ok(all_succeeded)
+    }
+
+    /// One implementer over the whole sprint, after parallel groups strayed into `stray`
+    async fn reconcile_task_groups(
+        &self,
+        sprint: &Sprint,
+        agent_name: &str,
+        max_turns: u32,
+        stray: &[String],
+        analysis: Option<&CodebaseAnalysis>,
+    ) -> Result<bool> {
+        let mut context = self.agent_context(sprint, analysis);
+        context.push_str("\n\n");
+        context.push_str(&reconcile_instructions(stray));
+        let result = self.execute_routed(agent_name, &context, max_turns, sprint).await?;
+        record_run_event("task_groups_reconciled", Some(sprint.id), if result.success { "passed" } else { "failed" });
+        if !result.success {
+            tracing::warn!("Agent '{}' failed to reconcile the task groups: {:?}", agent_name, result.error);
+        }
+        Ok(result.success)
+    }
+
+    /// Migration tool to brief agents about, for implementation/infrastructure
+    /// sprints that change the data model
+    fn migration_tool_for(&self, sprint: &Sprint) -> Option<MigrationTool> {
+        if !matches!(sprint.workflow_type, WorkflowType::Implementation | WorkflowType::Infrastructure)
+            || !touches_data_model(sprint)
+        {
+            return None;
+        }
+        MigrationTool::detect(self.project_path.as_deref()?)
+    }
+
+    /// Run the agent, on the cheap model first when routing applies
+    ///
+    /// The cheap attempt counts only if the agent succeeds and its output
+    /// validates for the phase; otherwise the agent's own model reruns it.
+    async fn execute_routed(
+        &self,
+        agent_name: &str,
+        context: &str,
+        max_turns: u32,
+        sprint: &Sprint,
+    ) -> Result<autoflow_agents::AgentResult> {
+        use autoflow_agents::execute_agent_with_model;
+
+        let run = |model: Option<String>| async move {
+            execute_agent_with_model(agent_name, context, max_turns, Some(sprint.id), model.as_deref(), self.agent_dir(sprint))
+                .await
+                .map_err(|e| match e.downcast::<AutoFlowError>() {
+                    Ok(e @ (AutoFlowError::NetworkUnavailable { .. } | AutoFlowError::AgentProtocolMismatch { .. })) => e,
+                    Ok(e) => AutoFlowError::AgentExecutionFailed(agent_name.to_string(), e.to_string()),
+                    Err(e) => AutoFlowError::AgentExecutionFailed(agent_name.to_string(), e.to_string()),
+                })
+        };
+
+        let routing = autoflow_data::Config::global().map(|c| c.routing).unwrap_or_default();
+        let project_path = self.project_path.as_deref();
+        let mut stats = project_path.map(RoutingStats::load).unwrap_or_default();
+        let Some(cheap_model) = stats.cheap_model_for(&routing, agent_name).map(String::from) else {
+            return run(None).await;
+        };
+
+        tracing::info!("Trying agent '{}' on {} first", agent_name, cheap_model);
+        let cheap = run(Some(cheap_model.clone())).await;
+        let cheap_ok = matches!(cheap, Ok(ref result) if result.success && output_validates(sprint.status, &result.output));
+
+        stats.record(agent_name, cheap_ok);
+        if let Some(project_path) = project_path {
+            if let Err(e) = stats.save(project_path) {
+                tracing::debug!("Failed to save routing stats: {}", e);
+            }
+        }
+
+        let tier = if cheap_ok { ModelTier::Cheap } else { ModelTier::Escalated };
+        record_run_event("model_tier", Some(sprint.id), &format!("{}: {} ({})", agent_name, tier.as_str(), cheap_model));
+        if cheap_ok {
+            return cheap;
+        }
+        tracing::warn!("Agent '{}' failed on {} - escalating to its own model", agent_name, cheap_model);
+        run(None).await
+    }
+
+    /// Checkout a meta-project sprint runs in; None for the coordinator itself
+    fn repo_dir(&self, sprint: &Sprint) -> Option<&Path> {
+        self.repos.get(sprint.repo.as_deref()?).map(PathBuf::as_path)
+    }
+
+    fn repo_briefing(&self, sprint: &Sprint) -> Option<String> {
+        let project_path = self.project_path.as_deref()?;
+        Some(repo_instructions(sprint.repo.as_deref()?, self.repo_dir(sprint)?, project_path))
+    }
+
+    /// Checkout the sprint's agents run in when it isn't the current directory:
+    /// its repo, or the patch-mode shadow
+    fn agent_dir(&self, sprint: &Sprint) -> Option<&Path> {
+        self.repo_dir(sprint).or(self.patch_checkout.as_deref())
+    }
+
+    /// Where a sprint's work is committed: its repo, the patch-mode shadow, or the project itself
+    fn commit_path(&self, sprint: &Sprint) -> Option<&Path> {
+        self.agent_dir(sprint).or(self.project_path.as_deref())
+    }
+
+    /// Commit the sprint's work was last committed as; none in patch mode, where nothing is committed to the project
+    fn head_commit(&self, sprint: &Sprint) -> Option<String> {
+        if self.patch_checkout.is_some() && sprint.repo.is_none() {
+            return None;
+        }
+        self.commit_path(sprint).and_then(head_ref)
+    }
+
+    /// Commit what `phase` changed, or in patch mode queue it as a bundle for review
+    fn record_changes(&self, sprint: &Sprint, phase: SprintStatus, message: &str) {
+        let Some(checkout) = self.commit_path(sprint) else {
+            return;
+        };
+        let shadow = self.patch_checkout.as_deref().filter(|_| sprint.repo.is_none());
+        if let (Some(shadow), Some(project_path)) = (shadow, self.project_path.as_deref()) {
+            match capture_patch(project_path, shadow, sprint, phase, message, self.allow_submodule_bumps, &self.commit_policy) {
+                Ok(Some(bundle)) => {
+                    tracing::info!("✓ Queued patch bundle {}", bundle.file);
+                    record_run_event("patch_captured", Some(sprint.id), &bundle.file);
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Failed to capture patch for phase {:?}: {}", phase, e),
+            }
+            return;
+        }
+        if let Err(e) = detect_vcs(checkout, self.vcs_backend).commit(checkout, sprint, message, self.allow_submodule_bumps, &self.commit_policy) {
+            tracing::warn!("Failed to create git commit for phase {:?}: {}", phase, e);
+        }
+    }
+
+    /// Record the code and sprint state the phase starts from, so
+    /// `autoflow debug at` can replay it (best effort)
+    fn snapshot_phase(&self, sprint: &Sprint) {
+        let (Some(run_id), Some(project_path), Some(checkout)) =
+            (current_run_id(), self.project_path.as_deref(), self.commit_path(sprint))
+        else {
+            return;
+        };
+        if autoflow_data::is_read_only() {
+            return;
+        }
+        let seq = PhaseSnapshot::count(project_path, &run_id, sprint.id) + 1;
+        if let Err(e) = PhaseSnapshot::capture(checkout, &run_id, sprint, seq).save(project_path, seq) {
+            tracing::debug!("Failed to snapshot sprint {} at {:?}: {}", sprint.id, sprint.status, e);
+        }
+    }
+
+    /// Directory the sprint's agents run in; the current one without a project path
+    fn workdir(&self, sprint: &Sprint) -> &Path {
+        self.commit_path(sprint).unwrap_or(Path::new("."))
+    }
+
+    /// Run the `[hooks]` for this stage of the sprint's current phase
+    ///
+    /// A failing `fatal` hook blocks the sprint; a `warn` one is logged and
+    /// recorded as a `hook_failed` event.
+    async fn run_phase_hooks(
+        &self,
+        hooks: &HooksConfig,
+        stage: HookStage,
+        sprint: &mut Sprint,
+        outcome: Option<&str>,
+    ) -> Result<()> {
+        if sprint.status == SprintStatus::Pending {
+            return Ok(());
+        }
+        for (command, on_failure) in hook_commands(hooks, stage, sprint.workflow_type, sprint.status) {
+            tracing::info!("Sprint {} {} hook: {}", sprint.id, stage.as_str(), command);
+            let workdir = self.workdir(sprint).to_path_buf();
+            let Some(failure) = run_hook(&command, stage, sprint, &workdir, outcome, hooks.timeout_secs).await else {
+                continue;
+            };
+            let detail = format!("{} hook `{}` failed for {:?}: {}", stage.as_str(), command, sprint.status, failure);
+            record_run_event("hook_failed", Some(sprint.id), &detail);
+            if on_failure == HookFailure::Warn {
+                tracing::warn!("Sprint {}: {}", sprint.id, detail);
+                continue;
+            }
+            sprint.status = SprintStatus::Blocked;
+            sprint.blocked_count = Some(1);
+            sprint.last_updated = Utc::now();
+            if let Some(ref save_fn) = self.save_callback {
+                save_fn(sprint)?;
+            }
+            return Err(AutoFlowError::SprintBlocked(sprint.id, detail));
+        }
+        Ok(())
+    }
+
+    /// Saved analysis.json, or a fresh analysis when `autoflow analyze` hasn't run
+    fn analysis(&self) -> Option<CodebaseAnalysis> {
+        let project_path = self.project_path.as_deref()?;
+        CodebaseAnalysis::load_json(project_path.join(ANALYSIS_PATH))
+            .or_else(|| CodebaseAnalyzer::new(project_path).analyze().ok())
+    }
+
+    /// Log and record where an agent's output breaks its completion protocol
+    fn check_protocol_violations(&self, sprint: &Sprint, agent_name: &str, output: &str) {
+        let (Some(protocol), Some(workdir)) = (agent_protocol(agent_name), self.commit_path(sprint)) else {
+            return;
+        };
+        for violation in protocol.violations(output, workdir, Some(sprint.id)) {
+            tracing::warn!("Agent '{}' broke the completion protocol: {}", agent_name, violation);
+            record_run_event("protocol_violation", Some(sprint.id), &format!("{}: {}", agent_name, violation));
+        }
+    }
+
+    /// Swap an oversized report the agent wrote for its summary, so the fixer's context stays small
+    fn summarize_failure_report(&self, sprint: &Sprint, agent_name: &str) {
+        let (Some(report), Some(workdir)) = (agent_protocol(agent_name).and_then(|p| p.failure_report_name(sprint.id)), self.commit_path(sprint)) else {
+            return;
+        };
+        let config = autoflow_data::Config::global().map(|c| c.failure_reports).unwrap_or_default();
+        match summarize_failure_report_file(workdir, &report, &config) {
+            Ok(true) => {
+                tracing::info!("Summarized oversized failure report {}", report);
+                record_run_event("failure_report_summarized", Some(sprint.id), &report);
+            }
+            Ok(false) => {}
+            Err(e) => tracing::warn!("Failed to summarize failure report {}: {}", report, e),
+        }
+    }
+
+    /// Have the `failure-triage` provider, if one is configured, add a triage note to the report the agent wrote
+    async fn triage_failure_report(&self, sprint: &Sprint, agent_name: &str) {
+        let (Some(report), Some(workdir)) = (agent_protocol(agent_name).and_then(|p| p.failure_report_name(sprint.id)), self.commit_path(sprint)) else {
+            return;
+        };
+        match triage_failure_report_file(workdir, &report, sprint.id).await {
+            Ok(true) => {
+                tracing::info!("Added a triage note to failure report {}", report);
+                record_run_event("failure_report_triaged", Some(sprint.id), &report);
+            }
+            Ok(false) => {}
+            Err(e) => tracing::warn!("Failed to triage failure report {}: {}", report, e),
+        }
+    }
+
+    /// A unit test run only counts if it used the project's test command
+    fn check_test_command(&self, sprint: &Sprint, analysis: Option<&CodebaseAnalysis>, commands: &[String]) -> bool {
+        let Some(stack) = analysis.map(|a| &a.tech_stack) else {
+            return true;
+        };
+        if stack.ran_test_command(commands) {
+            self.clear_failure_report(sprint, "test-command");
+            return true;
+        }
+
+        let expected = stack.test_command.as_deref().unwrap_or_default();
+        tracing::warn!("Sprint {} test run never used `{}` - not trusting its result", sprint.id, expected);
+        record_run_event("test_command_mismatch", Some(sprint.id), expected);
+        self.write_failure_report(
+            sprint,
+            "test-command",
+            &format!(
+                "# Wrong Test Command - Sprint {}\n\nThe test runner did not run the project's test command `{}`.\nCommands it ran:\n\n{}\n\nRun the tests with `{}`.\n",
+                sprint.id,
+                expected,
+                commands.iter().map(|c| format!("- `{}`", c)).collect::<Vec<_>>().join("\n"),
+                expected
+            ),
+        );
+        false
+    }
+
+    /// IaC project to plan and apply, for infrastructure sprints
+    fn iac_for(&self, sprint: &Sprint) -> Option<IacProject> {
+        if sprint.workflow_type != WorkflowType::Infrastructure {
+            return None;
+        }
+        IacProject::detect(self.project_path.as_deref()?)
+    }
+
+    /// Run the plan natively and render it for the reviewer
+    fn plan_for_review(&self, iac: &IacProject, sprint: &Sprint) -> String {
+        let Some(ref project_path) = self.project_path else {
+            return String::new();
+        };
+        match iac.plan(project_path, sprint.id) {
+            Ok(plan) => {
+                tracing::info!(
+                    "Sprint {} {} plan: {}",
+                    sprint.id,
+                    iac.tool.name(),
+                    if !plan.succeeded { "failed" } else if plan.has_changes { "changes" } else { "no changes" }
+                );
+                plan.review_context(iac.tool)
+            }
+            Err(e) => {
+                tracing::warn!("Sprint {} {} plan could not run: {}", sprint.id, iac.tool.name(), e);
+                format!("# {} Plan\n\nThe plan could not run: {}\n", iac.tool.name(), e)
+            }
+        }
+    }
+
+    /// Apply the plan approved in code review, after a human confirms
+    fn apply_reviewed_plan(&self, sprint: &Sprint) -> Result<()> {
+        let (Some(iac), Some(project_path)) = (self.iac_for(sprint), self.project_path.as_deref()) else {
+            return Ok(());
+        };
+        let Some(plan_path) = iac.pending_plan(project_path, sprint.id) else {
+            return Ok(());
+        };
+
+        request_signoff(
+            &format!("Apply the reviewed {} plan for sprint {} ({})?", iac.tool.name(), sprint.id, plan_path.display()),
+            project_path,
+            "iac-apply",
+            Some(sprint.id),
+            iac.tool.name(),
+            std::slice::from_ref(&plan_path),
+        )
+        .map_err(|_| AutoFlowError::ApplyNotApproved(sprint.id))?;
+
+        match iac.apply(project_path, sprint.id) {
+            Ok(_) => {
+                record_run_event("iac_apply", Some(sprint.id), iac.tool.name());
+                self.clear_failure_report(sprint, "iac-apply");
+                Ok(())
+            }
+            Err(e) => {
+                self.write_failure_report(
+                    sprint,
+                    "iac-apply",
+                    &format!("# {} Apply Failure - Sprint {}\n\n```\n{}\n```\n", iac.tool.name(), sprint.id, e),
+                );
+                Err(e)
+            }
+        }
+    }
+
+    /// First test phase of the workflow, where migrations are verified
+    fn is_migration_checkpoint(&self, sprint: &Sprint) -> bool {
+        let checkpoint = match sprint.workflow_type {
+            WorkflowType::Implementation => SprintStatus::RunUnitTests,
+            WorkflowType::Infrastructure => SprintStatus::RunE2eTests,
+            _ => return false,
+        };
+        sprint.status == checkpoint && self.migration_tool_for(sprint).is_some()
+    }
+
+    /// Run the migration gate; on failure leave a report for the fix phase
+    fn verify_migrations(&self, sprint: &Sprint) -> bool {
+        let Some(ref project_path) = self.project_path else {
+            return true;
+        };
+
+        let context = GateContext::new(String::new(), project_path.display().to_string());
+        let result = match MigrationGate.run(&context) {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::warn!("Migration gate could not run: {}", e);
+                return true;
+            }
+        };
+        for warning in &result.warnings {
+            tracing::warn!("{}", warning);
+        }
+
+        if result.passed {
+            self.clear_failure_report(sprint, "migrations");
+            return true;
+        }
+
+        tracing::warn!("Sprint {} migrations failed to apply - moving to fix phase", sprint.id);
+        self.write_failure_report(
+            sprint,
+            "migrations",
+            &format!(
+                "# Migration Failure - Sprint {}\n\nMigrations did not apply cleanly to an empty database.\n\n```\n{}\n```\n",
+                sprint.id,
+                result.errors.join("\n")
+            ),
+        );
+        false
+    }
+
+    /// Native completion checks; on failure leave a report for the fix phase they route to
+    fn run_health_checks(&self, sprint: &Sprint, health: &HealthConfig) -> bool {
+        let Some(workdir) = self.commit_path(sprint) else {
+            return true;
+        };
+
+        let (messages, failures) = HealthCheckGate::for_sprint(health.clone(), sprint).check(workdir);
+        for message in &messages {
+            tracing::info!("Sprint {} health: {}", sprint.id, message);
+        }
+
+        if failures.is_empty() {
+            self.clear_failure_report(sprint, "health");
+            HealthReport::clear(workdir, sprint.id);
+            return true;
+        }
+
+        tracing::warn!("Sprint {} failed {} health check(s)", sprint.id, failures.len());
+        self.report_health_failures(sprint, failures);
+        false
+    }
+
+    /// Write the health report: JSON for routing, markdown for the fixer it routes to
+    fn report_health_failures(&self, sprint: &Sprint, failures: Vec<HealthFailure>) {
+        let Some(workdir) = self.commit_path(sprint) else {
+            return;
+        };
+        let report = HealthReport { sprint: sprint.id, failures };
+        let route = match workflow_for(sprint).health_fix_status(&report.failures) {
+            SprintStatus::Blocked => "These can't be fixed by a fix phase, so the sprint is BLOCKED.".to_string(),
+            fix => format!(
+                "The sprint goes back to {} to fix these, then through the phases after it to COMPLETE again.",
+                crate::status_name(fix)
+            ),
+        };
+        if let Err(e) = report.save(workdir) {
+            tracing::warn!("Failed to save sprint {} health report: {}", sprint.id, e);
+        }
+        self.write_failure_report(sprint, "health", &report.to_markdown(&route));
+    }
+
+    /// Send a sprint that failed COMPLETE to the fix phase its health report calls for
+    ///
+    /// The sprint never stays at COMPLETE: it blocks instead when the failure
+    /// can't be fixed by a fix phase, when the blocker resolver has had the
+    /// sprint, or after `max_retries` rounds.
+    fn route_failed_health_checks(
+        &self,
+        sprint: &mut Sprint,
+        workflow: &WorkflowDefinition,
+        count: u32,
+        max_retries: u32,
+        fix_attempts: &mut Vec<SprintStatus>,
+    ) -> Result<()> {
+        let failures = self
+            .commit_path(sprint)
+            .and_then(|p| HealthReport::load(p, sprint.id))
+            .map(|r| r.failures)
+            .unwrap_or_default();
+        let route = workflow.health_fix_status(&failures);
+        record_run_event("health_failed", Some(sprint.id), &format!("{} failure(s), routed to {:?}", failures.len(), route));
+
+        if route == SprintStatus::Blocked || count > max_retries || sprint.uses_blocker_resolver {
+            tracing::error!("Sprint {} failed its health checks, marking as BLOCKED", sprint.id);
+            sprint.status = SprintStatus::Blocked;
+            sprint.blocked_count = Some(count);
+        } else {
+            if self.fix_budget > 0 && fix_attempts.len() as u32 >= self.fix_budget {
+                return self.exhaust_fix_budget(sprint, fix_attempts);
+            }
+            tracing::info!("Sprint {} failed its health checks, moving to fix phase {:?}", sprint.id, route);
+            fix_attempts.push(route);
+            sprint.status = route;
+        }
+        sprint.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Link or build the shared dependency and container caches (best effort)
+    fn bootstrap_env_cache(&self, sprint: &Sprint) {
+        let Some(checkout) = self.commit_path(sprint) else {
+            return;
+        };
+        let Some(cache) = EnvCache::load(checkout) else {
+            return;
+        };
+        let steps = cache.bootstrap(checkout);
+        let summary = steps
+            .iter()
+            .map(|step| format!("{} {} ({:.1}s)", step.name, step.outcome, step.secs))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if summary.is_empty() {
+            return;
+        }
+        tracing::info!("Sprint {} environment cache: {}", sprint.id, summary);
+        record_run_event("env_cache", Some(sprint.id), &summary);
+    }
+
+    /// Remember the test phase's outcome for the completion checks
+    fn record_test_result(&self, sprint: &Sprint, passed: bool) {
+        let Some(workdir) = self.commit_path(sprint) else {
+            return;
+        };
+        if let Err(e) = TestResults::record(workdir, sprint.id, &crate::status_name(sprint.status), passed) {
+            tracing::warn!("Failed to record test results: {}", e);
+        }
+    }
+
+    /// Flag files changed outside the sprint's integration_points for the reviewer
+    fn check_integration_points(&self, sprint: &Sprint) {
+        let Some(ref project_path) = self.project_path else {
+            return;
+        };
+
+        let gate = IntegrationPointsGate { points: sprint.integration_points.clone() };
+        let delta = match gate.check(project_path) {
+            Ok(Some(delta)) => delta,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!("Integration points check could not run: {}", e);
+                return;
+            }
+        };
+
+        let mut report = QualityReport::new();
+        report.add_result(gate.result(Some(&delta)));
+        tracing::info!("Sprint {} quality report:{}", sprint.id, report);
+
+        if delta.is_clean() {
+            self.clear_failure_report(sprint, "integration-points");
+            return;
+        }
+
+        tracing::warn!(
+            "Sprint {} changed {} file(s) outside its integration points",
+            sprint.id,
+            delta.unexpected.len()
+        );
+        record_run_event("integration_delta", Some(sprint.id), &delta.unexpected.join(", "));
+        self.write_failure_report(sprint, "integration-points", &delta.to_markdown(sprint.id));
+    }
+
+    /// Fixture set for the project, if one is configured
+    fn fixtures(&self) -> Option<FixtureSet> {
+        let project_path = self.project_path.as_deref()?;
+        match FixtureSet::load(project_path) {
+            Ok(fixtures) => fixtures,
+            Err(e) => {
+                tracing::warn!("Ignoring invalid {}/{}: {}", FIXTURES_DIR, FIXTURES_MANIFEST, e);
+                None
+            }
+        }
+    }
+
+    /// Approval gate before the deploy agent; records what is being deployed
+    fn begin_deployment(&self, sprint: &Sprint) -> Result<()> {
+        let project_path = self.project_path.as_deref().ok_or_else(|| {
+            AutoFlowError::ValidationError("Deployment sprints need a project path".to_string())
+        })?;
+        let config = DeployConfig::load(project_path)?;
+        let git_ref = head_ref(project_path).unwrap_or_default();
+        if config.require_approval {
+            request_signoff(&format!("Deploy sprint {} ({})?", sprint.id, sprint.goal), project_path, "deploy", Some(sprint.id), &git_ref, &[])
+                .map_err(|_| AutoFlowError::DeploymentNotApproved(sprint.id))?;
+        }
+
+        let mut state = DeployState::load(project_path);
+        record_run_event("deploy", Some(sprint.id), &git_ref);
+        state.begin(sprint.id, git_ref);
+        state.save(project_path)
+    }
+
+    /// Native smoke checks; on failure leave a report and let the workflow roll back
+    async fn run_smoke_checks(&self, sprint: &Sprint) -> Result<bool> {
+        let project_path = self.project_path.as_deref().ok_or_else(|| {
+            AutoFlowError::ValidationError("Deployment sprints need a project path".to_string())
+        })?;
+        let config = DeployConfig::load(project_path)?;
+
+        tracing::info!("Sprint {} running {} smoke check(s)", sprint.id, config.smoke_checks.len());
+        let failures = config.run_smoke_checks().await;
+        if failures.is_empty() {
+            let mut state = DeployState::load(project_path);
+            state.mark_good();
+            state.save(project_path)?;
+            self.clear_failure_report(sprint, "smoke");
+            return Ok(true);
+        }
+
+        tracing::warn!("Sprint {} smoke checks failed - rolling back", sprint.id);
+        self.write_failure_report(
+            sprint,
+            "smoke",
+            &format!(
+                "# Smoke Check Failure - Sprint {}\n\n{}\n",
+                sprint.id,
+                failures.iter().map(|f| format!("- {}", f)).collect::<Vec<_>>().join("\n")
+            ),
+        );
+        Ok(false)
+    }
+
+    /// Restore the last good deployment; the error explains how the sprint ended
+    fn roll_back_deployment(&self, sprint: &Sprint) -> AutoFlowError {
+        let Some(project_path) = self.project_path.as_deref() else {
+            return AutoFlowError::ValidationError("Deployment sprints need a project path".to_string());
+        };
+        let config = match DeployConfig::load(project_path) {
+            Ok(config) => config,
+            Err(e) => return e,
+        };
+
+        let mut state = DeployState::load(project_path);
+        match config.rollback(project_path, &state) {
+            Ok(target) => {
+                record_run_event("rollback", Some(sprint.id), target.as_deref().unwrap_or("previous release"));
+                state.abandon();
+                if let Err(e) = state.save(project_path) {
+                    tracing::warn!("Failed to save deploy state: {}", e);
+                }
+                AutoFlowError::DeploymentRolledBack { sprint: sprint.id, to: target }
+            }
+            Err(e) => {
+                tracing::error!("Sprint {} rollback failed: {}", sprint.id, e);
+                record_run_event("error", Some(sprint.id), &format!("Rollback: {}", e));
+                e
+            }
+        }
+    }
+
+    /// Block a sprint whose fix loops used up the fix budget, leaving a triage report
+    fn exhaust_fix_budget(&self, sprint: &mut Sprint, attempts: &[SprintStatus]) -> Result<()> {
+        tracing::error!(
+            "Sprint {} used its fix budget ({} fix phases), marking as BLOCKED",
+            sprint.id,
+            attempts.len()
+        );
+        record_run_event("fix_budget_exceeded", Some(sprint.id), &format!("{} fix phases", attempts.len()));
 
-        let futures = sprints.iter_mut().map(|sprint| self.run_sprint(sprint));
+        let report = fix_budget_report(sprint.id, sprint.status, attempts, self.fix_budget);
+        self.write_failure_report(sprint, "fix-budget", &report);
 
-        let results = join_all(futures).await;
+        sprint.status = SprintStatus::Blocked;
+        sprint.blocked_count = Some(attempts.len() as u32);
+        sprint.last_updated = Utc::now();
+        if let Some(ref save_fn) = self.save_callback {
+            save_fn(sprint)?;
+        }
+        Err(AutoFlowError::SprintBlocked(
+            sprint.id,
+            format!(
+                "Fix budget exhausted after {} fix phases; see .autoflow/.failures/sprint-{}-fix-budget.md",
+                attempts.len(),
+                sprint.id
+            ),
+        ))
+    }
 
-        Ok(results)
+    /// Reports live in the sprint's checkout, where its agents read and write them
+    fn failure_report_path(&self, sprint: &Sprint, kind: &str) -> Option<PathBuf> {
+        self.commit_path(sprint).map(|p| {
+            p.join(".autoflow/.failures")
+                .join(format!("sprint-{}-{}.md", sprint.id, kind))
+        })
+    }
+
+    /// Write `.autoflow/.failures/sprint-<id>-<kind>.md` for the fix phase
+    fn write_failure_report(&self, sprint: &Sprint, kind: &str, report: &str) {
+        let Some(path) = self.failure_report_path(sprint, kind) else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        let report = sanitize_output(report);
+        let report = match current_run_id() {
+            Some(run_id) => format!("<!-- run: {} -->\n{}", run_id, report),
+            None => report,
+        };
+        if let Err(e) = std::fs::write(&path, report) {
+            tracing::warn!("Failed to write {} failure report: {}", kind, e);
+        }
+    }
+
+    fn clear_failure_report(&self, sprint: &Sprint, kind: &str) {
+        if let Some(path) = self.failure_report_path(sprint, kind) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    /// Run sprints concurrently in dependency order, at most `jobs` at a time (0 = no limit)
+    ///
+    /// A sprint starts as soon as its prerequisites reach DONE. `plan` is every
+    /// sprint in SPRINTS.yml, for dependencies outside `sprints`. Sprints that
+    /// never start because a prerequisite didn't finish get a MissingDependency error.
+    pub async fn run_parallel(&self, sprints: &mut [Sprint], plan: &[Sprint], jobs: usize) -> Result<Vec<Result<()>>> {
+        use futures::stream::{FuturesUnordered, StreamExt};
+
+        let mut dag = SprintDag::new(sprints, plan)?;
+        let limit = if jobs == 0 { sprints.len().max(1) } else { jobs };
+        let ids: Vec<u32> = sprints.iter().map(|s| s.id).collect();
+        let mut results: Vec<Option<Result<()>>> = sprints.iter().map(|_| None).collect();
+        let mut slots: Vec<Option<&mut Sprint>> = sprints.iter_mut().map(Some).collect();
+
+        let mut running = FuturesUnordered::new();
+        loop {
+            while running.len() < limit {
+                let Some(idx) = dag.next_ready() else {
+                    break;
+                };
+                let Some(sprint) = slots[idx].take() else {
+                    continue;
+                };
+                tracing::info!("Starting sprint {} ({} running)", sprint.id, running.len() + 1);
+                running.push(async move {
+                    let result = self.run_sprint(sprint).await;
+                    (idx, result, sprint.status)
+                });
+            }
+            let Some((idx, result, status)) = running.next().await else {
+                break;
+            };
+            dag.finish(idx, result.is_ok() && status.satisfies_dependents());
+            results[idx] = Some(result);
+        }
+
+        Ok(results
+            .into_iter()
+            .enumerate()
+            .map(|(idx, result)| {
+                result.unwrap_or_else(|| {
+                    let missing = match dag.state(idx) {
+                        DagState::Skipped(dep) => dep,
+                        _ => ids[idx],
+                    };
+                    tracing::info!("Sprint {} skipped: sprint {} didn't finish", ids[idx], missing);
+                    Err(AutoFlowError::MissingDependency { sprint: ids[idx], missing })
+                })
+            })
+            .collect())
     }
 
     /// Run blocker-resolver agent to diagnose blocked sprint
     async fn run_blocker_resolver(&self, sprint: &Sprint) -> Result<String> {
-        use autoflow_agents::{build_fixer_context, execute_agent};
+        use autoflow_agents::{build_fixer_context, execute_agent_with_model};
 
         // Use lightweight context - blocker-resolver only needs failure reports, not full task details
-        let context = build_fixer_context(sprint);
+        let mut context = build_fixer_context(sprint, self.workdir(sprint));
+        if let Some(briefing) = self.repo_briefing(sprint) {
+            context.push_str("\n\n");
+            context.push_str(&briefing);
+        }
         let max_turns = 10; // Give resolver plenty of turns to investigate
 
         tracing::info!("Executing blocker-resolver agent for sprint {}", sprint.id);
 
-        let result = execute_agent("blocker-resolver", &context, max_turns, Some(sprint.id))
+        let result = execute_agent_with_model("blocker-resolver", &context, max_turns, Some(sprint.id), None, self.agent_dir(sprint))
             .await
             .map_err(|e| AutoFlowError::AgentExecutionFailed("blocker-resolver".to_string(), e.to_string()))?;
 
         if result.success {
             tracing::info!("Blocker-resolver analysis complete");
+            // Kept for the issue filed if the sprint stays blocked
+            self.write_failure_report(
+                sprint,
+                "blocker-analysis",
+                &format!("# Blocker Analysis - Sprint {}\n\n{}\n", sprint.id, result.output.trim()),
+            );
             Ok(result.output)
         } else {
             Err(AutoFlowError::AgentExecutionFailed(
@@ -444,70 +1869,74 @@ impl Orchestrator {
     }
 }
 
-/// Parse test results from agent output
-/// Returns true if tests passed, false if they failed
-///
-/// Looks for standardized output marker: "TEST_RESULT: PASSED" or "TEST_RESULT: FAILED"
-/// This format is enforced in all test-runner agent prompts for reliable parsing.
-fn parse_test_results(output: &str) -> bool {
-    // Look for the standardized marker first
-    if output.contains("TEST_RESULT: PASSED") {
-        return true;
+/// Failure report for a sprint stopped at `stopped_at` after `attempts` fix phases hit the budget
+fn fix_budget_report(sprint_id: u32, stopped_at: SprintStatus, attempts: &[SprintStatus], limit: u32) -> String {
+    let mut report = format!(
+        "# Sprint {} Fix Budget Exhausted\n\n\
+         The sprint ran {} fix phases without passing validation and was stopped at {:?} \
+         instead of starting another (limit: `[defaults] max_fix_attempts = {}`).\n\n\
+         ## Fix Phases\n\n",
+        sprint_id,
+        attempts.len(),
+        stopped_at,
+        limit
+    );
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for status in attempts {
+        *counts.entry(format!("{:?}", status)).or_default() += 1;
     }
-
-    if output.contains("TEST_RESULT: FAILED") {
-        return false;
+    for (status, count) in &counts {
+        report.push_str(&format!("- {}: {}\n", status, count));
     }
-
-    // Fallback: if no standardized marker found, log warning and assume passed
-    // This allows backward compatibility but we should fix agents to use the marker
-    tracing::warn!(
-        "Test output missing standardized marker 'TEST_RESULT: PASSED/FAILED'. \
-         Defaulting to PASSED. Please ensure test-runner agents output the required marker."
+    report.push_str(
+        "\nThe other reports for this sprint in `.autoflow/.failures` show what kept failing. \
+         Fix the cause, then resume with `autoflow start --sprint <id>`.\n",
     );
-    true
+    report
 }
 
-/// Parse code review results from agent output
-/// Returns true if review passed, false if it failed
+/// The verdict an agent's completion marker gives
 ///
-/// Looks for standardized output marker: "REVIEW_STATUS: PASSED" or "REVIEW_STATUS: FAILED"
-/// This format is enforced in the reviewer agent prompt for reliable parsing.
-fn parse_review_results(output: &str) -> bool {
-    // Look for the standardized marker first
-    if output.contains("REVIEW_STATUS: PASSED") {
-        return true;
-    }
+/// A missing marker is logged and treated as passed, for agents written before
+/// the protocol required one.
+fn parse_verdict(marker: Marker, output: &str) -> bool {
+    marker.parse(output).unwrap_or_else(|| {
+        tracing::warn!(
+            "Output missing standardized marker '{}'. Defaulting to PASSED. \
+             Please ensure the agent outputs the required marker.",
+            marker.line(true).replace("PASSED", "PASSED/FAILED")
+        );
+        true
+    })
+}
 
-    if output.contains("REVIEW_STATUS: FAILED") {
-        return false;
+/// Strict check used to accept a cheap-model attempt: the phase's marker must say it passed
+fn output_validates(status: SprintStatus, output: &str) -> bool {
+    match status {
+        SprintStatus::RunUnitTests | SprintStatus::RunE2eTests => Marker::TestResult.parse(output) == Some(true),
+        SprintStatus::CodeReview => Marker::ReviewStatus.parse(output) == Some(true),
+        _ => !output.trim().is_empty(),
     }
-
-    // Fallback: if no standardized marker found, log warning and assume passed
-    // This allows backward compatibility but we should fix the reviewer agent to use the marker
-    tracing::warn!(
-        "Review output missing standardized marker 'REVIEW_STATUS: PASSED/FAILED'. \
-         Defaulting to PASSED. Please ensure reviewer agent outputs the required marker."
-    );
-    true
 }
 
 /// Archive existing failure reports before running agents that write to .failures
 /// This preserves iteration history for debugging infinite loops
-fn archive_failure_reports_before_agent(project_path: &PathBuf, sprint_id: u32, agent_name: &str) {
+///
+/// `workdir` is where the agent runs: the project, or the sprint's repo checkout.
+fn archive_failure_reports_before_agent(workdir: &Path, sprint_id: u32, agent_name: &str) {
     use chrono::Local;
     use std::fs;
 
-    // Map agent names to their failure file patterns
+    // The report the agent's completion protocol has it write
     let failure_file = match agent_name {
-        "reviewer" => format!("sprint-{}-review.md", sprint_id),
-        "unit-test-runner" => format!("sprint-{}-unit-tests.md", sprint_id),
-        "e2e-test-runner" => format!("sprint-{}-integration-tests.md", sprint_id),
         "blocker-resolver" => format!("blocker-analysis-sprint-{}.md", sprint_id),
-        _ => return, // Agent doesn't write to .failures
+        _ => match agent_protocol(agent_name).and_then(|p| p.failure_report_name(sprint_id)) {
+            Some(report) => report,
+            None => return, // Agent doesn't write to .failures
+        },
     };
 
-    let failures_dir = project_path.join(".autoflow").join(".failures");
+    let failures_dir = workdir.join(".autoflow").join(".failures");
     let archive_dir = failures_dir.join("archive");
     let failure_path = failures_dir.join(&failure_file);
 
@@ -534,3 +1963,25 @@ fn archive_failure_reports_before_agent(project_path: &PathBuf, sprint_id: u32,
         tracing::info!("Archived {} to archive/{}", failure_file, archive_filename);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fix_budget_report() {
+        let attempts = [SprintStatus::UnitFix, SprintStatus::ReviewFix, SprintStatus::UnitFix];
+        let report = fix_budget_report(3, SprintStatus::RunUnitTests, &attempts, 3);
+        assert_eq!(
+            report,
+            "# Sprint 3 Fix Budget Exhausted\n\n\
+             The sprint ran 3 fix phases without passing validation and was stopped at RunUnitTests \
+             instead of starting another (limit: `[defaults] max_fix_attempts = 3`).\n\n\
+             ## Fix Phases\n\n\
+             - ReviewFix: 1\n\
+             - UnitFix: 2\n\n\
+             The other reports for this sprint in `.autoflow/.failures` show what kept failing. \
+             Fix the cause, then resume with `autoflow start --sprint <id>`.\n"
+        );
+    }
+}
@@ -0,0 +1,297 @@
+// Patch mode: agents work in a shadow checkout and each phase's changes become a reviewable patch bundle
+
+use crate::{commit_project_changes, status_name};
+use autoflow_data::{AutoFlowError, CommitConfig, Result, Sprint, SprintStatus};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Patch bundles waiting for review, relative to the project root
+pub const PATCHES_DIR: &str = ".autoflow/patches";
+
+/// Order and state of the bundles in [`PATCHES_DIR`]
+const PATCH_INDEX: &str = "index.json";
+
+/// One phase's changes, as `git format-patch` output
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PatchBundle {
+    /// File name under [`PATCHES_DIR`], e.g. `sprint-3-write_code.patch`
+    pub file: String,
+    pub sprint: u32,
+    pub phase: SprintStatus,
+    pub created_at: DateTime<Utc>,
+
+    /// Set while the bundle is applied to the working tree
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub applied_at: Option<DateTime<Utc>>,
+}
+
+/// Every bundle captured so far, oldest first; each builds on the ones before it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PatchQueue {
+    #[serde(default)]
+    pub bundles: Vec<PatchBundle>,
+}
+
+impl PatchQueue {
+    pub fn load(project_path: &Path) -> Result<Self> {
+        let path = project_path.join(PATCHES_DIR).join(PATCH_INDEX);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_str(&autoflow_data::read_state_to_string(path)?)?)
+    }
+
+    pub fn save(&self, project_path: &Path) -> Result<()> {
+        let dir = project_path.join(PATCHES_DIR);
+        fs::create_dir_all(&dir)?;
+        autoflow_data::write_state(dir.join(PATCH_INDEX), serde_json::to_string_pretty(self)?)
+    }
+
+    /// Bundles not yet applied, oldest first, optionally for one sprint
+    pub fn pending(&self, sprint: Option<u32>) -> Vec<&PatchBundle> {
+        self.bundles.iter().filter(|b| b.applied_at.is_none() && sprint.is_none_or(|id| b.sprint == id)).collect()
+    }
+
+    /// Applied bundles, newest first (the order to undo them in), optionally for one sprint
+    pub fn applied(&self, sprint: Option<u32>) -> Vec<&PatchBundle> {
+        self.bundles.iter().rev().filter(|b| b.applied_at.is_some() && sprint.is_none_or(|id| b.sprint == id)).collect()
+    }
+
+    /// File name for the next bundle of `sprint`'s `phase`; repeats get `-2`, `-3`...
+    fn next_file(&self, sprint: u32, phase: SprintStatus) -> String {
+        let stem = format!("sprint-{}-{}", sprint, status_name(phase).to_lowercase());
+        let taken = self.bundles.iter().filter(|b| b.sprint == sprint && b.phase == phase).count();
+        match taken {
+            0 => format!("{}.patch", stem),
+            n => format!("{}-{}.patch", stem, n + 1),
+        }
+    }
+
+    fn mark(&mut self, file: &str, applied_at: Option<DateTime<Utc>>) {
+        if let Some(bundle) = self.bundles.iter_mut().find(|b| b.file == file) {
+            bundle.applied_at = applied_at;
+        }
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .map_err(|e| AutoFlowError::GitCommandFailed(format!("Failed to run git {}: {}", args[0], e)))?;
+    if !output.status.success() {
+        return Err(AutoFlowError::GitCommandFailed(format!(
+            "git {}: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// The shadow checkout agents work in: a detached worktree beside the project
+pub fn shadow_path(project_path: &Path) -> PathBuf {
+    let name = project_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "project".to_string());
+    project_path.parent().unwrap_or(project_path).join(format!("{}-autoflow-patches", name))
+}
+
+/// Create the shadow checkout at the project's HEAD, or reuse it with the
+/// work captured so far
+///
+/// Its `.autoflow` links to the project's, so docs, failure reports and state
+/// are shared with the agents working there.
+pub fn prepare_shadow(project_path: &Path) -> Result<PathBuf> {
+    let project_path = project_path.canonicalize()?;
+    let shadow = shadow_path(&project_path);
+    if !shadow.join(".git").exists() {
+        autoflow_data::ensure_writable("creating the patch checkout")?;
+        git(&project_path, &["rev-parse", "--verify", "-q", "HEAD"]).map_err(|_| {
+            AutoFlowError::GitCommandFailed("Patch mode needs a git repository with at least one commit".to_string())
+        })?;
+        git(&project_path, &["worktree", "add", "--detach", &shadow.to_string_lossy(), "HEAD"])?;
+    }
+    #[cfg(unix)]
+    if !shadow.join(".autoflow").exists() {
+        std::os::unix::fs::symlink(project_path.join(".autoflow"), shadow.join(".autoflow"))?;
+    }
+    Ok(shadow)
+}
+
+/// Remove the shadow checkout, so the next run starts from the project's HEAD
+pub fn remove_shadow(project_path: &Path) -> Result<()> {
+    let shadow = shadow_path(&project_path.canonicalize()?);
+    if shadow.exists() {
+        git(project_path, &["worktree", "remove", "--force", &shadow.to_string_lossy()])?;
+    }
+    Ok(())
+}
+
+/// Commit the phase's changes in the shadow checkout and queue them as a bundle
+///
+/// None when the phase changed nothing.
+pub fn capture_patch(
+    project_path: &Path,
+    shadow: &Path,
+    sprint: &Sprint,
+    phase: SprintStatus,
+    message: &str,
+    allow_submodule_bumps: bool,
+    policy: &CommitConfig,
+) -> Result<Option<PatchBundle>> {
+    let before = git(shadow, &["rev-parse", "HEAD"])?;
+    commit_project_changes(shadow, sprint, message, allow_submodule_bumps, policy)?;
+    if git(shadow, &["rev-parse", "HEAD"])? == before {
+        return Ok(None);
+    }
+
+    let mut queue = PatchQueue::load(project_path)?;
+    let bundle = PatchBundle {
+        file: queue.next_file(sprint.id, phase),
+        sprint: sprint.id,
+        phase,
+        created_at: Utc::now(),
+        applied_at: None,
+    };
+    let patch = git(shadow, &["format-patch", "-1", "--binary", "--stdout", "HEAD"])?;
+    fs::create_dir_all(project_path.join(PATCHES_DIR))?;
+    fs::write(project_path.join(PATCHES_DIR).join(&bundle.file), patch)?;
+    queue.bundles.push(bundle.clone());
+    queue.save(project_path)?;
+    Ok(Some(bundle))
+}
+
+/// Apply bundles to the project's working tree and index, in order
+///
+/// Stops at the first bundle that doesn't apply; the ones before it stay applied.
+pub fn apply_patches(project_path: &Path, files: &[String]) -> Result<()> {
+    autoflow_data::ensure_writable("applying patches")?;
+    let mut queue = PatchQueue::load(project_path)?;
+    for file in files {
+        let path = project_path.join(PATCHES_DIR).join(file);
+        let result = git(project_path, &["apply", "--index", &path.to_string_lossy()]);
+        if let Err(e) = result {
+            queue.save(project_path)?;
+            return Err(e);
+        }
+        queue.mark(file, Some(Utc::now()));
+    }
+    queue.save(project_path)
+}
+
+/// Reverse applied bundles, given newest first
+pub fn undo_patches(project_path: &Path, files: &[String]) -> Result<()> {
+    autoflow_data::ensure_writable("undoing patches")?;
+    let mut queue = PatchQueue::load(project_path)?;
+    for file in files {
+        let path = project_path.join(PATCHES_DIR).join(file);
+        if let Err(e) = git(project_path, &["apply", "-R", "--index", &path.to_string_lossy()]) {
+            queue.save(project_path)?;
+            return Err(e);
+        }
+        queue.mark(file, None);
+    }
+    queue.save(project_path)
+}
+
+/// Drop every pending bundle and the shadow checkout holding their work
+///
+/// Returns how many bundles were dropped. Applied bundles are kept so they can
+/// still be undone.
+pub fn discard_patches(project_path: &Path) -> Result<usize> {
+    autoflow_data::ensure_writable("discarding patches")?;
+    let mut queue = PatchQueue::load(project_path)?;
+    let (pending, kept): (Vec<PatchBundle>, Vec<PatchBundle>) = queue.bundles.drain(..).partition(|b| b.applied_at.is_none());
+    for bundle in &pending {
+        let _ = fs::remove_file(project_path.join(PATCHES_DIR).join(&bundle.file));
+    }
+    queue.bundles = kept;
+    queue.save(project_path)?;
+    remove_shadow(project_path)?;
+    Ok(pending.len())
+}
+
+/// `git apply --stat` summary of a bundle
+pub fn patch_stat(project_path: &Path, file: &str) -> Result<String> {
+    let path = project_path.join(PATCHES_DIR).join(file);
+    git(project_path, &["apply", "--stat", &path.to_string_lossy()])
+}
+
+/// Briefing for agents working in the shadow checkout
+pub fn patch_mode_instructions(shadow: &Path, project_path: &Path) -> String {
+    format!(
+        r#"# Patch Mode
+
+Your working directory is a scratch checkout of the project: {}
+Your changes are captured as a patch that a human reviews before it reaches the real checkout ({}).
+Work only in your working directory; `.autoflow/` there is the project's own.
+"#,
+        shadow.display(),
+        project_path.display()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patch_bundles_round_trip() {
+        let base = std::env::temp_dir().join(format!("autoflow-patches-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        let project = base.join("app");
+        fs::create_dir_all(project.join(".autoflow")).unwrap();
+        let run = |dir: &Path, args: &[&str]| git(dir, args).unwrap();
+        run(&project, &["init", "-q"]);
+        run(&project, &["config", "user.email", "test@example.com"]);
+        run(&project, &["config", "user.name", "Test"]);
+        fs::write(project.join("app.js"), "v1\n").unwrap();
+        run(&project, &["add", "app.js"]);
+        run(&project, &["commit", "-q", "-m", "Initial"]);
+
+        let shadow = prepare_shadow(&project).unwrap();
+        assert_eq!(shadow, base.canonicalize().unwrap().join("app-autoflow-patches"));
+        assert!(shadow.join(".autoflow").exists());
+
+        let sprint: Sprint = serde_yaml::from_str(
+            "id: 3\ngoal: Search\nstatus: WRITE_CODE\ntotal_effort: 1h\nmax_effort: 2h\n\
+             last_updated: 2025-01-01T00:00:00Z\ndeliverables: []\ntasks: []\n",
+        )
+        .unwrap();
+        let policy = CommitConfig::default();
+        fs::write(shadow.join("app.js"), "v2\n").unwrap();
+        fs::write(shadow.join("search.js"), "search\n").unwrap();
+        let first =
+            capture_patch(&project, &shadow, &sprint, SprintStatus::WriteCode, "Implement", false, &policy).unwrap().unwrap();
+        assert_eq!(first.file, "sprint-3-write_code.patch");
+        assert!(capture_patch(&project, &shadow, &sprint, SprintStatus::WriteCode, "Again", false, &policy).unwrap().is_none());
+        fs::write(shadow.join("search.js"), "search v2\n").unwrap();
+        let second =
+            capture_patch(&project, &shadow, &sprint, SprintStatus::WriteCode, "Again", false, &policy).unwrap().unwrap();
+        assert_eq!(second.file, "sprint-3-write_code-2.patch");
+
+        // The project's checkout is untouched until the bundles are applied
+        assert_eq!(fs::read_to_string(project.join("app.js")).unwrap(), "v1\n");
+        assert!(patch_stat(&project, &first.file).unwrap().contains("search.js"));
+        let queue = PatchQueue::load(&project).unwrap();
+        let pending: Vec<String> = queue.pending(Some(3)).iter().map(|b| b.file.clone()).collect();
+        apply_patches(&project, &pending).unwrap();
+        assert_eq!(fs::read_to_string(project.join("search.js")).unwrap(), "search v2\n");
+
+        let queue = PatchQueue::load(&project).unwrap();
+        assert!(queue.pending(None).is_empty());
+        let applied: Vec<String> = queue.applied(None).iter().map(|b| b.file.clone()).collect();
+        assert_eq!(applied, [second.file.clone(), first.file.clone()]);
+        undo_patches(&project, &applied).unwrap();
+        assert_eq!(fs::read_to_string(project.join("app.js")).unwrap(), "v1\n");
+        assert!(!project.join("search.js").exists());
+
+        assert_eq!(discard_patches(&project).unwrap(), 2);
+        assert!(!shadow.exists());
+        assert!(PatchQueue::load(&project).unwrap().bundles.is_empty());
+        let _ = fs::remove_dir_all(base);
+    }
+}
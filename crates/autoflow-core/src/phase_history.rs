@@ -0,0 +1,166 @@
+// Phase history: which of a sprint's phases have already run to completion, from the run log
+
+use crate::workflow::{workflow_for, WorkflowDefinition};
+use autoflow_data::{Sprint, SprintStatus};
+use autoflow_utils::RunEvent;
+
+/// Position of a status in the workflow; fix phases share their validation phase's
+fn position(workflow: &WorkflowDefinition, status: SprintStatus) -> Option<usize> {
+    let status = workflow.get_validation_phase_for_fix(status).map(|p| p.status).unwrap_or(status);
+    workflow.phases.iter().position(|p| p.status == status)
+}
+
+/// Workflow phase a `phase` or `reset` event names
+fn phase_named(workflow: &WorkflowDefinition, detail: &str) -> Option<SprintStatus> {
+    workflow.phases.iter().map(|p| p.status).find(|s| format!("{:?}", s) == detail)
+}
+
+/// Phases of the sprint that finished since it started, in workflow order
+///
+/// A phase counts as finished once the sprint moved on to a later one (or
+/// completed). `reset` events, recorded when the orchestrator or the user
+/// deliberately sends a sprint back, forget everything from the reset phase on.
+/// Sprints that haven't started have no history, so a regenerated plan starts clean.
+pub fn completed_phases(sprint: &Sprint, events: &[RunEvent]) -> Vec<SprintStatus> {
+    let Some(started) = sprint.started else {
+        return Vec::new();
+    };
+    let workflow = workflow_for(sprint);
+    let mut completed: Vec<SprintStatus> = Vec::new();
+    let mut last: Option<SprintStatus> = None;
+
+    for event in events.iter().filter(|e| e.is_for(sprint) && e.timestamp >= started) {
+        match event.event.as_str() {
+            "phase" => {
+                let Some(status) = phase_named(&workflow, &event.detail) else {
+                    continue;
+                };
+                if let Some(previous) = last {
+                    if position(&workflow, status) > position(&workflow, previous) && !completed.contains(&previous) {
+                        completed.push(previous);
+                    }
+                }
+                last = Some(status);
+            }
+            "sprint_done" => {
+                if let Some(previous) = last.filter(|p| !completed.contains(p)) {
+                    completed.push(previous);
+                }
+            }
+            "reset" => {
+                match phase_named(&workflow, &event.detail).and_then(|s| position(&workflow, s)) {
+                    Some(from) => completed.retain(|&s| position(&workflow, s).is_some_and(|p| p < from)),
+                    None => completed.clear(),
+                }
+                last = None;
+            }
+            _ => {}
+        }
+    }
+
+    completed.sort_by_key(|&s| position(&workflow, s));
+    completed
+}
+
+/// Finished phases the sprint would run again from its current status
+///
+/// Non-empty when SPRINTS.yml was sent back to a phase that already completed,
+/// typically by hand; running on would redo that work and commit it twice.
+pub fn phases_to_redo(sprint: &Sprint, events: &[RunEvent]) -> Vec<SprintStatus> {
+    let completed = completed_phases(sprint, events);
+    if !completed.contains(&sprint.status) {
+        return Vec::new();
+    }
+    let workflow = workflow_for(sprint);
+    let from = position(&workflow, sprint.status);
+    completed.into_iter().filter(|&s| position(&workflow, s) >= from).collect()
+}
+
+/// The workflow phase the sprint ran last, e.g. where it was when it blocked
+///
+/// A fix phase counts as the validation phase it loops back to, since that's
+/// what has to pass. None without a recorded phase since the sprint started.
+pub fn last_phase(sprint: &Sprint, events: &[RunEvent]) -> Option<SprintStatus> {
+    let started = sprint.started?;
+    let workflow = workflow_for(sprint);
+    let status = events
+        .iter()
+        .rev()
+        .filter(|e| e.is_for(sprint) && e.timestamp >= started && e.event == "phase")
+        .find_map(|e| phase_named(&workflow, &e.detail))?;
+    Some(workflow.get_validation_phase_for_fix(status).map(|p| p.status).unwrap_or(status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn sprint(status: &str) -> Sprint {
+        let yaml = format!(
+            "id: 4\ngoal: Checkout\nstatus: {}\ntotal_effort: 2h\nmax_effort: 4h\n\
+             last_updated: 2025-01-01T00:00:00Z\ndeliverables: []\ntasks: []\n",
+            status
+        );
+        let mut sprint: Sprint = serde_yaml::from_str(&yaml).unwrap();
+        sprint.started = Some(Utc::now() - Duration::hours(1));
+        sprint
+    }
+
+    fn events(list: &[(&str, &str)]) -> Vec<RunEvent> {
+        list.iter()
+            .map(|(event, detail)| RunEvent {
+                timestamp: Utc::now(),
+                event: event.to_string(),
+                sprint: Some(4),
+                detail: detail.to_string(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_phase_regressions() {
+        let history = events(&[
+            ("phase", "WriteUnitTests"),
+            ("phase", "WriteCode"),
+            ("phase", "CodeReview"),
+            ("phase", "ReviewFix"),
+            ("phase", "CodeReview"),
+            ("phase", "RunUnitTests"),
+        ]);
+
+        assert_eq!(
+            completed_phases(&sprint("RUN_UNIT_TESTS"), &history),
+            vec![SprintStatus::WriteUnitTests, SprintStatus::WriteCode, SprintStatus::CodeReview]
+        );
+        // Resuming where it stopped, or in a fix loop, isn't a regression
+        assert!(phases_to_redo(&sprint("RUN_UNIT_TESTS"), &history).is_empty());
+        assert!(phases_to_redo(&sprint("UNIT_FIX"), &history).is_empty());
+        // Hand-edited back to WRITE_CODE
+        assert_eq!(
+            phases_to_redo(&sprint("WRITE_CODE"), &history),
+            vec![SprintStatus::WriteCode, SprintStatus::CodeReview]
+        );
+
+        // A reset forgets the phases from where it sent the sprint back
+        let mut reset = history.clone();
+        reset.extend(events(&[("reset", "WriteCode")]));
+        assert!(phases_to_redo(&sprint("WRITE_CODE"), &reset).is_empty());
+        assert_eq!(completed_phases(&sprint("WRITE_CODE"), &reset), vec![SprintStatus::WriteUnitTests]);
+
+        // A sprint blocked in a fix loop resumes at the check it was fixing
+        assert_eq!(last_phase(&sprint("BLOCKED"), &history), Some(SprintStatus::RunUnitTests));
+        let mut fixing = history.clone();
+        fixing.extend(events(&[("phase", "UnitFix"), ("phase", "Blocked")]));
+        assert_eq!(last_phase(&sprint("BLOCKED"), &fixing), Some(SprintStatus::RunUnitTests));
+
+        // Events from before the sprint (re)started don't count
+        let mut fresh = sprint("WRITE_CODE");
+        fresh.started = Some(Utc::now() + Duration::hours(1));
+        assert!(phases_to_redo(&fresh, &history).is_empty());
+        assert_eq!(last_phase(&fresh, &history), None);
+        fresh.started = None;
+        assert!(completed_phases(&fresh, &history).is_empty());
+    }
+}
@@ -0,0 +1,148 @@
+// Phase hooks: user-defined shell commands run around sprint phases
+
+use autoflow_data::{HookFailure, HooksConfig, PhaseHooks, Sprint, SprintStatus, WorkflowType};
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+/// When a hook runs relative to its phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookStage {
+    PrePhase,
+    PostPhase,
+}
+
+impl HookStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PrePhase => "pre_phase",
+            Self::PostPhase => "post_phase",
+        }
+    }
+}
+
+/// Phase name as written in SPRINTS.yml and `[hooks.phase.<STATUS>]`
+pub fn phase_name(status: SprintStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_else(|| format!("{:?}", status))
+}
+
+/// Commands to run for one stage of a phase, each with its failure policy
+///
+/// Top-level hooks come first, then the workflow's, then the phase's.
+pub fn hook_commands(
+    config: &HooksConfig,
+    stage: HookStage,
+    workflow: WorkflowType,
+    status: SprintStatus,
+) -> Vec<(String, HookFailure)> {
+    let pick = |hooks: &PhaseHooks| match stage {
+        HookStage::PrePhase => hooks.pre_phase.clone(),
+        HookStage::PostPhase => hooks.post_phase.clone(),
+    };
+    let top = PhaseHooks {
+        pre_phase: config.pre_phase.clone(),
+        post_phase: config.post_phase.clone(),
+        on_failure: Some(config.on_failure),
+    };
+    let workflow_name = workflow.to_string();
+    let phase = phase_name(status);
+    let scoped = |hooks: &std::collections::BTreeMap<String, PhaseHooks>, name: &str| {
+        hooks.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, h)| h.clone())
+    };
+
+    [Some(top), scoped(&config.workflows, &workflow_name), scoped(&config.phases, &phase)]
+        .into_iter()
+        .flatten()
+        .flat_map(|hooks| {
+            let on_failure = hooks.on_failure.unwrap_or(config.on_failure);
+            pick(&hooks).into_iter().map(move |command| (command, on_failure))
+        })
+        .collect()
+}
+
+/// Run one hook in `workdir` with the sprint and phase in its environment
+///
+/// `outcome` (`passed`, `failed` or `error`) is set for post-phase hooks.
+/// Returns why the hook failed, if it did.
+pub async fn run_hook(
+    command: &str,
+    stage: HookStage,
+    sprint: &Sprint,
+    workdir: &Path,
+    outcome: Option<&str>,
+    timeout_secs: u64,
+) -> Option<String> {
+    let mut process = tokio::process::Command::new("sh");
+    process
+        .args(["-c", command])
+        .current_dir(workdir)
+        .env("AUTOFLOW_HOOK", stage.as_str())
+        .env("AUTOFLOW_SPRINT_ID", sprint.id.to_string())
+        .env("AUTOFLOW_SPRINT_GOAL", &sprint.goal)
+        .env("AUTOFLOW_PHASE", phase_name(sprint.status))
+        .env("AUTOFLOW_WORKFLOW", sprint.workflow_type.to_string())
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+    if let Some(outcome) = outcome {
+        process.env("AUTOFLOW_PHASE_RESULT", outcome);
+    }
+
+    let run = tokio::time::timeout(Duration::from_secs(timeout_secs.max(1)), process.output());
+    match run.await {
+        Err(_) => Some(format!("timed out after {}s", timeout_secs)),
+        Ok(Err(e)) => Some(format!("could not start: {}", e)),
+        Ok(Ok(output)) if !output.status.success() => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let tail: Vec<&str> = stderr.trim().lines().rev().take(5).collect();
+            let code = output.status.code().map(|c| c.to_string()).unwrap_or_else(|| "signal".to_string());
+            Some(if tail.is_empty() {
+                format!("exit {}", code)
+            } else {
+                format!("exit {}: {}", code, tail.into_iter().rev().collect::<Vec<_>>().join(" | "))
+            })
+        }
+        Ok(Ok(_)) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_commands_layering() {
+        let config: HooksConfig = serde_json::from_value(serde_json::json!({
+            "pre_phase": ["make warm"],
+            "on_failure": "warn",
+            "workflow": { "implementation": { "pre_phase": ["npm run lint:types"] } },
+            "phase": {
+                "RUN_UNIT_TESTS": {
+                    "pre_phase": ["npm run gen:graphql"],
+                    "post_phase": ["npm run coverage-upload"],
+                    "on_failure": "fatal"
+                }
+            }
+        }))
+        .unwrap();
+
+        let commands = hook_commands(&config, HookStage::PrePhase, WorkflowType::Implementation, SprintStatus::RunUnitTests);
+        assert_eq!(
+            commands,
+            vec![
+                ("make warm".to_string(), HookFailure::Warn),
+                ("npm run lint:types".to_string(), HookFailure::Warn),
+                ("npm run gen:graphql".to_string(), HookFailure::Fatal),
+            ]
+        );
+
+        let commands = hook_commands(&config, HookStage::PrePhase, WorkflowType::Refactor, SprintStatus::WriteCode);
+        assert_eq!(commands, vec![("make warm".to_string(), HookFailure::Warn)]);
+
+        let commands = hook_commands(&config, HookStage::PostPhase, WorkflowType::Test, SprintStatus::RunUnitTests);
+        assert_eq!(commands, vec![("npm run coverage-upload".to_string(), HookFailure::Fatal)]);
+        assert_eq!(phase_name(SprintStatus::RunE2eTests), "RUN_E2E_TESTS");
+    }
+}
@@ -0,0 +1,389 @@
+// Sprint planning helpers shared by commands that patch an existing SPRINTS.yml
+
+use crate::analyzer::IntegrationPoint;
+use autoflow_agents::execute_agent;
+use autoflow_data::{
+    AutoFlowError, IntegrationPoints, Priority, Result, Sprint, SprintStatus, SprintsYaml, Task,
+    TaskStatus, TaskType, TestRequirement, TestingRequirements, WorkflowType,
+};
+use chrono::Utc;
+use std::collections::BTreeSet;
+
+/// Ask make-sprints for a sprint and only accept it once the patched plan validates
+///
+/// Schema errors from a rejected attempt are fed back into the next one. Falls back
+/// to a template sprint (which is itself validated) when every attempt fails, so the
+/// caller always gets a sprint that keeps SPRINTS.yml schema-valid. The returned
+/// flag is true when the sprint came from the agent rather than the template.
+pub async fn generate_validated_sprint(
+    existing: &SprintsYaml,
+    context: &str,
+    next_id: u32,
+    description: &str,
+    max_attempts: u32,
+) -> Result<(Sprint, bool)> {
+    let mut last_error = String::new();
+
+    for attempt in 1..=max_attempts {
+        let prompt = if last_error.is_empty() {
+            context.to_string()
+        } else {
+            format!(
+                "{}\n\nPREVIOUS ATTEMPT WAS REJECTED:\n{}\n\nFix these problems and output the sprint again.",
+                context, last_error
+            )
+        };
+
+        let result = match execute_agent("make-sprints", &prompt, 10, None).await {
+            Ok(result) if result.success => result,
+            Ok(result) => {
+                last_error = result.error.unwrap_or_else(|| "Agent execution failed".to_string());
+                tracing::warn!("make-sprints attempt {}/{} failed: {}", attempt, max_attempts, last_error);
+                continue;
+            }
+            Err(e) => {
+                last_error = e.to_string();
+                tracing::warn!("make-sprints attempt {}/{} failed: {}", attempt, max_attempts, last_error);
+                continue;
+            }
+        };
+
+        let candidate = match parse_sprint_from_output(&result.output) {
+            Ok(mut sprint) => {
+                normalize_new_sprint(&mut sprint, next_id);
+                sprint
+            }
+            Err(e) => {
+                last_error = e.to_string();
+                continue;
+            }
+        };
+
+        match validate_with_sprint(existing, &candidate) {
+            Ok(()) => return Ok((candidate, true)),
+            Err(errors) => last_error = errors,
+        }
+    }
+
+    tracing::warn!("Falling back to template sprint: {}", last_error);
+    let template = template_sprint(next_id, description);
+    validate_with_sprint(existing, &template).map_err(AutoFlowError::ValidationError)?;
+    Ok((template, false))
+}
+
+/// Validate the plan as it would look after appending `sprint`
+pub fn validate_with_sprint(existing: &SprintsYaml, sprint: &Sprint) -> std::result::Result<(), String> {
+    let mut patched = existing.clone();
+    patched.sprints.push(sprint.clone());
+    patched.project.total_sprints = patched.sprints.len() as u32;
+    patched.validate_errors()
+}
+
+/// Extract the first sprint from agent output
+///
+/// Accepts a full SPRINTS.yml document, a bare list of sprints, or a single sprint,
+/// optionally wrapped in a ```yaml fence.
+pub fn parse_sprint_from_output(output: &str) -> Result<Sprint> {
+    let yaml_content = if output.contains("```yaml") {
+        output
+            .split("```yaml")
+            .nth(1)
+            .and_then(|s| s.split("```").next())
+            .unwrap_or(output)
+            .trim()
+    } else {
+        output.trim()
+    };
+
+    if let Ok(doc) = SprintsYaml::validate_and_fix(yaml_content) {
+        return doc.sprints.into_iter().next().ok_or_else(|| {
+            AutoFlowError::ValidationError("No sprints found in agent output".to_string())
+        });
+    }
+    if let Ok(mut list) = serde_yaml::from_str::<Vec<Sprint>>(yaml_content) {
+        if !list.is_empty() {
+            return Ok(list.remove(0));
+        }
+    }
+    serde_yaml::from_str::<Sprint>(yaml_content).map_err(|e| {
+        AutoFlowError::ValidationError(format!("Failed to parse sprint YAML: {}", e))
+    })
+}
+
+/// Reset lifecycle fields so a generated sprint starts fresh at `id`
+fn normalize_new_sprint(sprint: &mut Sprint, id: u32) {
+    sprint.id = id;
+    sprint.status = SprintStatus::Pending;
+    sprint.started = None;
+    sprint.completed_at = None;
+    sprint.last_updated = Utc::now();
+    sprint.blocked_count = None;
+    sprint.failure_reports.clear();
+    for task in &mut sprint.tasks {
+        task.status = TaskStatus::Pending;
+        task.committed_at = None;
+        task.reviewed_at = None;
+        task.tested_at = None;
+        task.done_at = None;
+        task.git_commit = None;
+    }
+}
+
+/// Infer which existing sprints `sprint` depends on from overlapping integration points
+///
+/// A sprint depends on every unfinished sprint that creates or modifies a file it
+/// touches. Analysis integration points mentioned by name in the sprint's goal or
+/// tasks contribute their files to the touched set.
+pub fn infer_dependencies(
+    sprint: &Sprint,
+    existing: &[Sprint],
+    analysis_points: &[IntegrationPoint],
+) -> Vec<String> {
+    let mut touched = touched_paths(sprint);
+
+    let text = sprint_text(sprint);
+    for point in analysis_points {
+        if !point.name.is_empty() && text.contains(&point.name.to_lowercase()) {
+            touched.extend(point.files.iter().cloned());
+        }
+    }
+
+    if touched.is_empty() {
+        return Vec::new();
+    }
+
+    existing
+        .iter()
+        .filter(|other| other.id != sprint.id && !other.status.is_closed())
+        .filter(|other| {
+            let theirs = touched_paths(other);
+            touched.iter().any(|a| theirs.iter().any(|b| paths_overlap(a, b)))
+        })
+        .map(|other| other.id.to_string())
+        .collect()
+}
+
+/// Mark a plan for an existing codebase as integration work
+///
+/// Sprints that don't declare integration_points get them from the analysis
+/// points their goal or tasks mention by name: those files as `modifies`, with
+/// the points' patterns. Returns the ids of sprints that still declare none.
+pub fn mark_integration_work(sprints: &mut [Sprint], analysis_points: &[IntegrationPoint]) -> Vec<u32> {
+    let mut undeclared = Vec::new();
+    for sprint in sprints.iter_mut().filter(|s| s.integration_points.is_none()) {
+        let text = sprint_text(sprint);
+        let mentioned: Vec<&IntegrationPoint> = analysis_points
+            .iter()
+            .filter(|p| !p.name.is_empty() && text.contains(&p.name.to_lowercase()))
+            .collect();
+        if mentioned.is_empty() {
+            undeclared.push(sprint.id);
+            continue;
+        }
+        let mut modifies: Vec<String> = Vec::new();
+        let mut patterns: Vec<String> = Vec::new();
+        for point in mentioned {
+            for file in &point.files {
+                if !modifies.contains(file) {
+                    modifies.push(file.clone());
+                }
+            }
+            for pattern in &point.patterns {
+                if !patterns.contains(pattern) {
+                    patterns.push(pattern.clone());
+                }
+            }
+        }
+        sprint.integration_points = Some(IntegrationPoints { modifies, creates: vec![], tests_existing: vec![], patterns });
+    }
+    undeclared
+}
+
+/// Files and directories a sprint declares it creates or modifies
+fn touched_paths(sprint: &Sprint) -> BTreeSet<String> {
+    sprint
+        .integration_points
+        .as_ref()
+        .map(|ip| ip.modifies.iter().chain(ip.creates.iter()).cloned().collect())
+        .unwrap_or_default()
+}
+
+fn sprint_text(sprint: &Sprint) -> String {
+    let mut text = sprint.goal.clone();
+    for task in &sprint.tasks {
+        text.push(' ');
+        text.push_str(&task.title);
+        if let Some(desc) = &task.description {
+            text.push(' ');
+            text.push_str(desc);
+        }
+    }
+    text.to_lowercase()
+}
+
+/// Equal paths, or one is a directory containing the other
+fn paths_overlap(a: &str, b: &str) -> bool {
+    let a = a.trim_start_matches("./").trim_end_matches('/');
+    let b = b.trim_start_matches("./").trim_end_matches('/');
+    a == b || a.starts_with(&format!("{}/", b)) || b.starts_with(&format!("{}/", a))
+}
+
+/// Minimal schema-valid sprint used when the agent cannot produce one
+pub fn template_sprint(id: u32, description: &str) -> Sprint {
+    Sprint {
+        id,
+        uid: None,
+        goal: description.to_string(),
+        status: SprintStatus::Pending,
+        workflow_type: WorkflowType::Implementation,
+        workflow: None,
+        duration: Some("Week 1".to_string()),
+        total_effort: "8h".to_string(),
+        max_effort: "15h".to_string(),
+        started: None,
+        last_updated: Utc::now(),
+        completed_at: None,
+        deliverables: vec![
+            format!("{} implementation", description),
+            "Unit tests".to_string(),
+            "E2E tests".to_string(),
+        ],
+        tasks: vec![Task {
+            id: format!("task-{:03}", id),
+            uid: None,
+            title: format!("Implement {}", description),
+            description: Some(format!("Implement {} feature", description)),
+            r#type: TaskType::Implementation,
+            doc_reference: None,
+            acceptance_criteria: vec!["Feature works as specified".to_string()],
+            test_specification: Some("Unit tests pass".to_string()),
+            effort: "6h".to_string(),
+            priority: Priority::High,
+            feature: description.to_string(),
+            docs: vec![],
+            business_rules: vec![
+                "Follow existing code patterns".to_string(),
+                "Maintain backward compatibility".to_string(),
+            ],
+            integration_notes: None,
+            files: vec![],
+            testing: TestingRequirements {
+                unit_tests: Some(TestRequirement {
+                    required: true,
+                    reason: "Core functionality validation".to_string(),
+                }),
+                integration_tests: None,
+                e2e_tests: None,
+            },
+            status: TaskStatus::Pending,
+            committed_at: None,
+            reviewed_at: None,
+            tested_at: None,
+            done_at: None,
+            git_commit: None,
+            review_findings: vec![],
+        }],
+        dependencies: vec![],
+        integration_points: None,
+        blocked_count: None,
+        must_complete_first: false,
+        priority: None,
+        value: None,
+        failure_reports: vec![],
+        repo: None,
+        uses_blocker_resolver: false,
+        issue_url: None,
+        skipped: None,
+        cancelled: None,
+        paused_from: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn integration_points(modifies: &[&str], creates: &[&str]) -> IntegrationPoints {
+        IntegrationPoints {
+            modifies: modifies.iter().map(|s| s.to_string()).collect(),
+            creates: creates.iter().map(|s| s.to_string()).collect(),
+            tests_existing: vec![],
+            patterns: vec![],
+        }
+    }
+
+    #[test]
+    fn test_infer_dependencies_from_overlapping_files() {
+        let mut first = template_sprint(1, "Auth");
+        first.integration_points = Some(integration_points(&[], &["src/auth/"]));
+        let mut second = template_sprint(2, "Billing");
+        second.integration_points = Some(integration_points(&[], &["src/billing.rs"]));
+        let mut done = template_sprint(3, "Auth setup");
+        done.status = SprintStatus::Done;
+        done.integration_points = Some(integration_points(&["src/auth/mod.rs"], &[]));
+
+        let mut new = template_sprint(4, "Password reset");
+        new.integration_points = Some(integration_points(&["src/auth/login.rs"], &[]));
+
+        let deps = infer_dependencies(&new, &[first, second, done], &[]);
+        assert_eq!(deps, vec!["1".to_string()]);
+    }
+
+    #[test]
+    fn test_infer_dependencies_from_analysis_points() {
+        let mut existing = template_sprint(1, "Users API");
+        existing.integration_points = Some(integration_points(&["app/Models/User.php"], &[]));
+        let point = IntegrationPoint {
+            name: "User".to_string(),
+            point_type: "model".to_string(),
+            files: vec!["app/Models/User.php".to_string()],
+            patterns: vec![],
+        };
+
+        let new = template_sprint(2, "Add avatar to user profile");
+        assert_eq!(infer_dependencies(&new, &[existing], &[point]), vec!["1".to_string()]);
+    }
+
+    #[test]
+    fn test_mark_integration_work() {
+        let models = IntegrationPoint {
+            name: "Models".to_string(),
+            point_type: "model".to_string(),
+            files: vec!["app/Models/User.php".to_string()],
+            patterns: vec!["extends Model".to_string()],
+        };
+        let mut declared = template_sprint(1, "Checkout");
+        declared.integration_points = Some(integration_points(&["app/Http/Cart.php"], &[]));
+        let mut sprints = vec![declared, template_sprint(2, "Add avatars to user models"), template_sprint(3, "Dark mode")];
+
+        assert_eq!(mark_integration_work(&mut sprints, &[models]), vec![3]);
+        assert_eq!(sprints[0].integration_points.as_ref().unwrap().modifies, vec!["app/Http/Cart.php"]);
+        let marked = sprints[1].integration_points.as_ref().unwrap();
+        assert_eq!(marked.modifies, vec!["app/Models/User.php"]);
+        assert_eq!(marked.patterns, vec!["extends Model"]);
+        assert!(sprints[2].integration_points.is_none());
+    }
+
+    #[test]
+    fn test_template_sprint_is_schema_valid() {
+        let plan = SprintsYaml {
+            project: autoflow_data::ProjectMetadata {
+                name: "demo".to_string(),
+                version: "0.1.0".to_string(),
+                description: "Demo".to_string(),
+                total_sprints: 0,
+                current_sprint: None,
+                last_updated: Utc::now(),
+                repos: vec![],
+            },
+            sprints: vec![],
+        };
+        assert!(validate_with_sprint(&plan, &template_sprint(1, "Search")).is_ok());
+    }
+
+    #[test]
+    fn test_paths_overlap() {
+        assert!(paths_overlap("src/api", "./src/api/users.ts"));
+        assert!(!paths_overlap("src/api", "src/apiv2/users.ts"));
+    }
+}
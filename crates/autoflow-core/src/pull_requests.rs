@@ -0,0 +1,41 @@
+// Pull requests: publish a sprint's branch once the sprint is done
+
+use autoflow_data::{AutoFlowError, Config, ForgeConfig, Result, Sprint};
+use autoflow_git::{push_branch, remote_provider, sprint_pull_request};
+use autoflow_quality::TestResults;
+use std::path::Path;
+
+/// Remote sprint branches are pushed to
+pub const PULL_REQUEST_REMOTE: &str = "origin";
+
+/// Whether `checkout` is a linked worktree rather than the main checkout
+pub fn is_linked_worktree(checkout: &Path) -> bool {
+    checkout.join(".git").is_file()
+}
+
+/// Push `branch` from `checkout` and open a pull request for the sprint
+///
+/// The body carries the sprint's goal, its tasks and the test results
+/// recorded in the checkout. A pull request already open for the branch is
+/// returned instead of opening another. Unfinished sprints get a draft.
+pub fn publish_sprint_branch(checkout: &Path, sprint: &Sprint, branch: &str, forge: &ForgeConfig) -> Result<String> {
+    let provider = autoflow_utils::detect_forge(forge)
+        .ok_or_else(|| AutoFlowError::ValidationError("No forge detected for origin; set [forge] provider".to_string()))?;
+    let remote = remote_provider(provider, forge.repo.clone()).ok_or_else(|| {
+        AutoFlowError::ValidationError(format!("Pull requests aren't supported on {:?} yet", provider))
+    })?;
+
+    push_branch(checkout, PULL_REQUEST_REMOTE, branch)?;
+    if let Some(url) = remote.find_pull_request(checkout, branch)? {
+        tracing::info!("Pull request for {} already open: {}", branch, url);
+        return Ok(url);
+    }
+
+    let base = Config::global().ok().and_then(|c| c.defaults.worktree_base_branch);
+    let tests = TestResults::load(checkout, sprint.id);
+    let draft = forge.draft_pull_requests || !sprint.is_done();
+    let pr = sprint_pull_request(sprint, &tests.phases, branch, base, draft);
+    let url = remote.open_pull_request(checkout, &pr)?;
+    tracing::info!("Opened {} pull request for {}: {}", remote.name(), branch, url);
+    Ok(url)
+}
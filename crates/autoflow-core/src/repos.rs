@@ -0,0 +1,129 @@
+// Meta-projects: one plan whose sprints run in several repositories
+
+use autoflow_data::{AutoFlowError, RepoSpec, Result, SprintStatus, SprintsYaml};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Open every declared repository, cloning the ones that aren't checked out yet
+///
+/// Returns each repo's absolute checkout path by name.
+pub fn open_repos(coordinator: &Path, repos: &[RepoSpec]) -> Result<BTreeMap<String, PathBuf>> {
+    let mut opened = BTreeMap::new();
+    for repo in repos {
+        let dir = coordinator.join(&repo.path);
+        if !dir.join(".git").exists() {
+            let Some(ref url) = repo.url else {
+                return Err(AutoFlowError::ValidationError(format!(
+                    "Repo '{}' is not checked out at {} and has no url to clone",
+                    repo.name,
+                    dir.display()
+                )));
+            };
+            tracing::info!("Cloning repo '{}' from {}", repo.name, url);
+            let output = Command::new("git")
+                .arg("clone")
+                .arg(url)
+                .arg(&dir)
+                .current_dir(coordinator)
+                .output()?;
+            if !output.status.success() {
+                return Err(AutoFlowError::GitCommandFailed(format!(
+                    "Failed to clone repo '{}': {}",
+                    repo.name,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+        }
+        opened.insert(repo.name.clone(), dir.canonicalize()?);
+    }
+    Ok(opened)
+}
+
+/// Briefing for agents working in a repo other than the coordinator
+pub fn repo_instructions(name: &str, dir: &Path, coordinator: &Path) -> String {
+    format!(
+        r#"# Repository
+
+This sprint targets the `{}` repository. Your working directory is its checkout: {}
+The plan, docs and failure reports live in the coordinator directory: {}
+Paths under `.autoflow/` in these instructions are relative to the coordinator, not to this repository.
+Only change code in this repository.
+"#,
+        name,
+        dir.display(),
+        coordinator.display()
+    )
+}
+
+/// Sprint counts for one repository of a meta-project
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepoProgress {
+    /// None for sprints that run in the coordinator itself
+    pub repo: Option<String>,
+    pub total: usize,
+    pub done: usize,
+    pub blocked: usize,
+    pub in_progress: usize,
+}
+
+/// Progress per repository, in declaration order; coordinator sprints come last
+pub fn repo_progress(sprints_data: &SprintsYaml) -> Vec<RepoProgress> {
+    let mut names: Vec<Option<String>> = sprints_data.project.repos.iter().map(|r| Some(r.name.clone())).collect();
+    names.push(None);
+
+    names
+        .into_iter()
+        .map(|repo| {
+            let mut progress = RepoProgress { repo, ..Default::default() };
+            for sprint in sprints_data.sprints.iter().filter(|s| s.repo == progress.repo) {
+                progress.total += 1;
+                match sprint.status {
+                    SprintStatus::Done => progress.done += 1,
+                    SprintStatus::Blocked => progress.blocked += 1,
+                    SprintStatus::Pending | SprintStatus::Paused | SprintStatus::Skipped | SprintStatus::Cancelled => {}
+                    _ => progress.in_progress += 1,
+                }
+            }
+            progress
+        })
+        .filter(|p| p.repo.is_some() || p.total > 0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_open_repos_clones_missing_checkouts() {
+        let root = std::env::temp_dir().join(format!("autoflow-repos-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let origin = root.join("origin");
+        fs::create_dir_all(&origin).unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git").args(args).current_dir(&origin).output().unwrap().status;
+            assert!(status.success(), "git {:?}", args);
+        };
+        git(&["init", "-q"]);
+        git(&["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "--allow-empty", "-m", "init"]);
+
+        let coordinator = root.join("meta");
+        fs::create_dir_all(&coordinator).unwrap();
+        let repos = vec![RepoSpec {
+            name: "backend".to_string(),
+            path: "repos/backend".to_string(),
+            url: Some(origin.display().to_string()),
+        }];
+        let opened = open_repos(&coordinator, &repos).unwrap();
+        assert!(opened["backend"].join(".git").exists());
+        // Already cloned: opened in place
+        assert_eq!(open_repos(&coordinator, &repos).unwrap(), opened);
+
+        let missing = vec![RepoSpec { name: "web".to_string(), path: "web".to_string(), url: None }];
+        assert!(open_repos(&coordinator, &missing).is_err());
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,116 @@
+// Model routing: which agents try the cheap model first, learned from past outcomes
+
+use autoflow_data::{Result, RoutingConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Per-agent cheap-model outcomes, kept across runs
+pub const ROUTING_STATS_PATH: &str = ".autoflow/routing.json";
+
+/// Which model tier handled a phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelTier {
+    /// Cheap model succeeded
+    Cheap,
+    /// Cheap model failed; the agent's own model took over
+    Escalated,
+}
+
+impl ModelTier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cheap => "cheap",
+            Self::Escalated => "escalated",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TierStats {
+    pub cheap_succeeded: u32,
+    pub cheap_failed: u32,
+}
+
+impl TierStats {
+    pub fn attempts(&self) -> u32 {
+        self.cheap_succeeded + self.cheap_failed
+    }
+
+    pub fn success_rate(&self) -> f64 {
+        if self.attempts() == 0 {
+            return 1.0;
+        }
+        self.cheap_succeeded as f64 / self.attempts() as f64
+    }
+}
+
+/// Contents of `.autoflow/routing.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoutingStats {
+    #[serde(default)]
+    pub agents: BTreeMap<String, TierStats>,
+}
+
+impl RoutingStats {
+    pub fn load(project_path: &Path) -> Self {
+        autoflow_data::read_state_to_string(project_path.join(ROUTING_STATS_PATH))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, project_path: &Path) -> Result<()> {
+        autoflow_data::write_state(project_path.join(ROUTING_STATS_PATH), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Cheap model to try first for `agent`, if routing applies to it
+    ///
+    /// Agents whose cheap attempts keep failing go straight to their own model
+    /// once there are enough samples to judge.
+    pub fn cheap_model_for<'a>(&self, config: &'a RoutingConfig, agent: &str) -> Option<&'a str> {
+        if !config.enabled || !config.agents.iter().any(|a| a == agent) {
+            return None;
+        }
+        let stats = self.agents.get(agent).copied().unwrap_or_default();
+        if stats.attempts() >= config.min_samples && stats.success_rate() < config.min_success_rate {
+            return None;
+        }
+        Some(config.cheap_model.as_str())
+    }
+
+    pub fn record(&mut self, agent: &str, cheap_succeeded: bool) {
+        let stats = self.agents.entry(agent.to_string()).or_default();
+        if cheap_succeeded {
+            stats.cheap_succeeded += 1;
+        } else {
+            stats.cheap_failed += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unreliable_agents_stop_using_cheap_model() {
+        let config = RoutingConfig { enabled: true, ..RoutingConfig::default() };
+        let mut stats = RoutingStats::default();
+
+        assert_eq!(stats.cheap_model_for(&config, "unit-test-runner"), Some("claude-haiku-4-5"));
+        assert_eq!(stats.cheap_model_for(&config, "code-implementer"), None);
+
+        // 1 of 4 succeeded: below the 50% floor
+        stats.record("unit-test-runner", true);
+        for _ in 0..3 {
+            stats.record("unit-test-runner", false);
+        }
+        assert_eq!(stats.cheap_model_for(&config, "unit-test-runner"), None);
+        assert_eq!(stats.cheap_model_for(&config, "e2e-test-runner"), Some("claude-haiku-4-5"));
+
+        let disabled = RoutingConfig::default();
+        assert_eq!(RoutingStats::default().cheap_model_for(&disabled, "unit-test-runner"), None);
+    }
+}
@@ -0,0 +1,383 @@
+// Sprint selection for continuous mode: runnable filter, weighted priority and preemption,
+// and the dependency graph parallel runs start sprints from
+
+use autoflow_data::{AutoFlowError, Priority, Result, SchedulingPolicy, Sprint, SprintStatus};
+use chrono::{DateTime, Utc};
+
+/// Feature value assumed when a sprint doesn't declare one (1-10 scale)
+const DEFAULT_VALUE: u32 = 5;
+
+/// Age stops adding to the score after this many days
+const MAX_AGE_DAYS: i64 = 10;
+
+/// Bonus that keeps an in-progress sprint ahead of equal-priority fresh work
+const IN_PROGRESS_BONUS: i64 = 25;
+
+/// Indices of sprints that can run now
+///
+/// Not closed or paused, dependencies done or skipped, and - while any
+/// must_complete_first sprint is still open - only the must_complete_first sprints.
+pub fn runnable_sprints(sprints: &[Sprint]) -> Vec<usize> {
+    let has_incomplete_critical = sprints
+        .iter()
+        .any(|s| s.must_complete_first && !s.status.is_closed());
+
+    sprints
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.status.is_runnable())
+        .filter(|(_, s)| !has_incomplete_critical || s.must_complete_first)
+        .filter(|(_, s)| dependencies_satisfied(s, sprints))
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+fn dependencies_satisfied(sprint: &Sprint, sprints: &[Sprint]) -> bool {
+    sprint.dependencies.iter().all(|dep_id| {
+        sprints
+            .iter()
+            .find(|other| other.id.to_string() == *dep_id)
+            .map(|dep| dep.status.satisfies_dependents())
+            .unwrap_or(true)
+    })
+}
+
+fn is_in_progress(sprint: &Sprint) -> bool {
+    sprint.status != SprintStatus::Pending && sprint.status.is_runnable()
+}
+
+/// Sprint priority, falling back to its highest task priority, then Medium
+pub fn effective_priority(sprint: &Sprint) -> Priority {
+    sprint.priority.unwrap_or_else(|| {
+        sprint
+            .tasks
+            .iter()
+            .map(|t| t.priority)
+            .max_by_key(|p| priority_weight(*p))
+            .unwrap_or(Priority::Medium)
+    })
+}
+
+fn priority_weight(priority: Priority) -> i64 {
+    match priority {
+        Priority::Critical => 4,
+        Priority::High => 3,
+        Priority::Medium => 2,
+        Priority::Low => 1,
+    }
+}
+
+/// Weighted score: priority dominates, value and waiting time break ties
+pub fn priority_score(sprint: &Sprint, now: DateTime<Utc>) -> i64 {
+    let value = sprint.value.unwrap_or(DEFAULT_VALUE).clamp(1, 10) as i64;
+    let age_days = (now - sprint.last_updated).num_days().clamp(0, MAX_AGE_DAYS);
+    let in_progress = if is_in_progress(sprint) { IN_PROGRESS_BONUS } else { 0 };
+
+    priority_weight(effective_priority(sprint)) * 10 + value * 3 + age_days * 2 + in_progress
+}
+
+/// Index of the sprint continuous mode should run next
+pub fn next_sprint(sprints: &[Sprint], policy: SchedulingPolicy, now: DateTime<Utc>) -> Option<usize> {
+    let runnable = runnable_sprints(sprints);
+    match policy {
+        // (not in_progress, not critical, id): lower is selected first
+        SchedulingPolicy::Sequential => runnable.into_iter().min_by_key(|&i| {
+            let sprint = &sprints[i];
+            (!is_in_progress(sprint), !sprint.must_complete_first, sprint.id)
+        }),
+        SchedulingPolicy::Weighted => runnable.into_iter().min_by_key(|&i| {
+            let sprint = &sprints[i];
+            (!sprint.must_complete_first, -priority_score(sprint, now), sprint.id)
+        }),
+    }
+}
+
+/// Critical sprint that should take over from `current`, if any
+///
+/// `sprints` is the latest plan (it may have changed since `current` started).
+/// A must_complete_first sprint never gets preempted.
+pub fn preempting_sprint(current: &Sprint, sprints: &[Sprint]) -> Option<u32> {
+    if current.must_complete_first {
+        return None;
+    }
+    runnable_sprints(sprints)
+        .into_iter()
+        .map(|i| &sprints[i])
+        .filter(|s| s.must_complete_first && s.id != current.id)
+        .map(|s| s.id)
+        .min()
+}
+
+/// Where a sprint stands in a parallel run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DagState {
+    /// Prerequisites still running or waiting
+    Waiting,
+    Running,
+    /// Reached DONE (or SKIPPED): dependents may start
+    Done,
+    /// Finished without reaching DONE
+    Failed,
+    /// Never started: this prerequisite didn't reach DONE
+    Skipped(u32),
+}
+
+/// Dependency graph of the sprints in a parallel run
+///
+/// A sprint starts once every prerequisite in the run is done, and - while any
+/// must_complete_first sprint in the run is open - only after those. When a
+/// prerequisite fails, everything depending on it is skipped.
+#[derive(Debug, Clone)]
+pub struct SprintDag {
+    ids: Vec<u32>,
+    /// Indices each sprint waits for
+    prerequisites: Vec<Vec<usize>>,
+    /// Topological order, ties broken like sequential mode: in progress, critical, lowest id
+    order: Vec<usize>,
+    states: Vec<DagState>,
+}
+
+impl SprintDag {
+    /// Graph of `batch`, the sprints to run
+    ///
+    /// Dependencies outside the batch are looked up in `plan` (the whole of
+    /// SPRINTS.yml): one that isn't done or skipped there can't finish during
+    /// the run, so its dependents are skipped. Unknown ids are ignored, as in
+    /// continuous mode. Fails when sprints in the batch depend on each other in a cycle.
+    pub fn new(batch: &[Sprint], plan: &[Sprint]) -> Result<Self> {
+        let has_critical = batch.iter().any(|s| s.must_complete_first);
+        let mut states = vec![DagState::Waiting; batch.len()];
+        let mut prerequisites: Vec<Vec<usize>> = vec![Vec::new(); batch.len()];
+        for (i, sprint) in batch.iter().enumerate() {
+            for dep_id in &sprint.dependencies {
+                if let Some(j) = batch.iter().position(|other| other.id.to_string() == *dep_id) {
+                    prerequisites[i].push(j);
+                } else if let Some(dep) = plan.iter().find(|other| other.id.to_string() == *dep_id) {
+                    if !dep.status.satisfies_dependents() && states[i] == DagState::Waiting {
+                        states[i] = DagState::Skipped(dep.id);
+                    }
+                }
+            }
+            if has_critical && !sprint.must_complete_first {
+                prerequisites[i].extend((0..batch.len()).filter(|&j| batch[j].must_complete_first));
+            }
+            prerequisites[i].sort_unstable();
+            prerequisites[i].dedup();
+        }
+
+        // Kahn's algorithm, always taking the first ready sprint by sequential-mode priority
+        let key = |i: usize| (!is_in_progress(&batch[i]), !batch[i].must_complete_first, batch[i].id);
+        let mut pending: Vec<usize> = prerequisites.iter().map(Vec::len).collect();
+        let mut ready: Vec<usize> = (0..batch.len()).filter(|&i| pending[i] == 0).collect();
+        let mut order = Vec::with_capacity(batch.len());
+        while let Some(pos) = (0..ready.len()).min_by_key(|&p| key(ready[p])) {
+            let i = ready.swap_remove(pos);
+            order.push(i);
+            for j in 0..batch.len() {
+                if prerequisites[j].contains(&i) {
+                    pending[j] -= 1;
+                    if pending[j] == 0 {
+                        ready.push(j);
+                    }
+                }
+            }
+        }
+        if order.len() < batch.len() {
+            let mut cycle: Vec<String> = (0..batch.len())
+                .filter(|i| !order.contains(i))
+                .map(|i| batch[i].id.to_string())
+                .collect();
+            cycle.sort();
+            return Err(AutoFlowError::InvalidDependency(format!(
+                "Sprints {} depend on each other in a cycle",
+                cycle.join(", ")
+            )));
+        }
+
+        let mut dag = Self { ids: batch.iter().map(|s| s.id).collect(), prerequisites, order, states };
+        for i in dag.order.clone() {
+            if let DagState::Skipped(dep) = dag.states[i] {
+                dag.skip_dependents(i, dep);
+            }
+        }
+        Ok(dag)
+    }
+
+    /// Topological order of the batch, by index
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    pub fn state(&self, idx: usize) -> DagState {
+        self.states[idx]
+    }
+
+    /// Next sprint whose prerequisites are all done, marked running
+    pub fn next_ready(&mut self) -> Option<usize> {
+        let idx = self.order.iter().copied().find(|&i| {
+            self.states[i] == DagState::Waiting && self.prerequisites[i].iter().all(|&j| self.states[j] == DagState::Done)
+        })?;
+        self.states[idx] = DagState::Running;
+        Some(idx)
+    }
+
+    /// Record that a running sprint finished; when it didn't reach DONE, skip its dependents
+    pub fn finish(&mut self, idx: usize, done: bool) {
+        if done {
+            self.states[idx] = DagState::Done;
+        } else {
+            self.states[idx] = DagState::Failed;
+            self.skip_dependents(idx, self.ids[idx]);
+        }
+    }
+
+    /// Skip the waiting sprints that depend on `idx`, directly or not, because of sprint `cause`
+    fn skip_dependents(&mut self, idx: usize, cause: u32) {
+        let mut stack = vec![idx];
+        while let Some(i) = stack.pop() {
+            for j in 0..self.ids.len() {
+                if self.states[j] == DagState::Waiting && self.prerequisites[j].contains(&i) {
+                    self.states[j] = DagState::Skipped(cause);
+                    stack.push(j);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use autoflow_data::WorkflowType;
+    use chrono::Duration;
+
+    fn sprint(id: u32, status: SprintStatus) -> Sprint {
+        Sprint {
+            id,
+            uid: None,
+            goal: format!("Sprint {}", id),
+            status,
+            workflow_type: WorkflowType::Implementation,
+            workflow: None,
+            duration: None,
+            total_effort: "1h".to_string(),
+            max_effort: "2h".to_string(),
+            started: None,
+            last_updated: Utc::now(),
+            completed_at: None,
+            deliverables: vec![],
+            tasks: vec![],
+            dependencies: vec![],
+            integration_points: None,
+            blocked_count: None,
+            must_complete_first: false,
+            priority: None,
+            value: None,
+            failure_reports: vec![],
+            repo: None,
+            uses_blocker_resolver: false,
+            issue_url: None,
+            skipped: None,
+            cancelled: None,
+            paused_from: None,
+        }
+    }
+
+    #[test]
+    fn test_weighted_prefers_priority_value_and_age() {
+        let now = Utc::now();
+        let mut low = sprint(1, SprintStatus::Pending);
+        low.priority = Some(Priority::Low);
+        let mut high = sprint(2, SprintStatus::Pending);
+        high.priority = Some(Priority::High);
+        let mut valuable = sprint(3, SprintStatus::Pending);
+        valuable.priority = Some(Priority::High);
+        valuable.value = Some(9);
+
+        let sprints = vec![low.clone(), high.clone(), valuable];
+        assert_eq!(next_sprint(&sprints, SchedulingPolicy::Sequential, now), Some(0));
+        assert_eq!(next_sprint(&sprints, SchedulingPolicy::Weighted, now), Some(2));
+
+        // Ten days of waiting lifts a low-priority sprint over a fresh high one
+        low.last_updated = now - Duration::days(30);
+        low.value = Some(10);
+        assert!(priority_score(&low, now) > priority_score(&high, now));
+    }
+
+    #[test]
+    fn test_critical_sprint_preempts_lower_priority_work() {
+        let now = Utc::now();
+        let mut current = sprint(1, SprintStatus::WriteCode);
+        current.priority = Some(Priority::High);
+        let mut critical = sprint(5, SprintStatus::Pending);
+        critical.must_complete_first = true;
+        critical.dependencies = vec!["4".to_string()];
+        let dependency = sprint(4, SprintStatus::Pending);
+
+        // Not runnable until its dependency is done
+        let mut sprints = vec![current.clone(), dependency, critical];
+        assert_eq!(preempting_sprint(&current, &sprints), None);
+
+        sprints[1].status = SprintStatus::Done;
+        assert_eq!(preempting_sprint(&current, &sprints), Some(5));
+        assert_eq!(next_sprint(&sprints, SchedulingPolicy::Weighted, now), Some(2));
+
+        current.must_complete_first = true;
+        assert_eq!(preempting_sprint(&current, &sprints), None);
+    }
+
+    #[test]
+    fn test_held_and_closed_sprints_are_not_runnable() {
+        let mut sprints = vec![
+            sprint(1, SprintStatus::Skipped),
+            sprint(2, SprintStatus::Cancelled),
+            sprint(3, SprintStatus::Paused),
+            sprint(4, SprintStatus::Pending),
+            sprint(5, SprintStatus::Pending),
+        ];
+        sprints[3].dependencies = vec!["1".to_string()];
+        sprints[4].dependencies = vec!["2".to_string()];
+
+        // A skipped dependency lets its dependents run; a cancelled one doesn't
+        assert_eq!(runnable_sprints(&sprints), [3]);
+        sprints[1].must_complete_first = true;
+        assert_eq!(runnable_sprints(&sprints), [3]);
+    }
+
+    #[test]
+    fn test_dag_starts_dependents_when_prerequisites_finish() {
+        // 1 <- 2 <- 4, 3 independent, 5 waits on a paused sprint outside the run
+        let plan = vec![
+            sprint(1, SprintStatus::Pending),
+            sprint(2, SprintStatus::Pending),
+            sprint(3, SprintStatus::Pending),
+            sprint(4, SprintStatus::Pending),
+            sprint(5, SprintStatus::Pending),
+            sprint(6, SprintStatus::Paused),
+        ];
+        let mut batch = plan[..5].to_vec();
+        batch[1].dependencies = vec!["1".to_string()];
+        batch[3].dependencies = vec!["2".to_string(), "3".to_string()];
+        batch[4].dependencies = vec!["6".to_string()];
+
+        let mut dag = SprintDag::new(&batch, &plan).unwrap();
+        assert_eq!(dag.order(), [0, 1, 2, 3, 4]);
+        assert_eq!(dag.state(4), DagState::Skipped(6));
+        assert_eq!((dag.next_ready(), dag.next_ready(), dag.next_ready()), (Some(0), Some(2), None));
+
+        dag.finish(0, true);
+        assert_eq!((dag.next_ready(), dag.next_ready()), (Some(1), None));
+        dag.finish(2, true);
+        dag.finish(1, false);
+        assert_eq!(dag.state(3), DagState::Skipped(2));
+        assert_eq!(dag.next_ready(), None);
+
+        // A critical sprint goes first; a cycle is refused
+        batch[4].dependencies.clear();
+        batch[4].must_complete_first = true;
+        let mut dag = SprintDag::new(&batch, &plan).unwrap();
+        assert_eq!((dag.next_ready(), dag.next_ready()), (Some(4), None));
+        batch[0].dependencies = vec!["4".to_string()];
+        assert!(SprintDag::new(&batch, &plan).is_err());
+    }
+}
@@ -0,0 +1,244 @@
+// Phase snapshots: the code and sprint state as each phase started, for replaying it later
+
+use crate::workflow_graph::status_name;
+use autoflow_data::{AutoFlowError, Result, Sprint, SprintStatus};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Snapshot directory inside `.autoflow/runs/<run-id>/`
+pub const SNAPSHOTS_DIR: &str = "snapshots";
+
+/// Git refs that keep snapshot commits from being garbage collected
+pub const SNAPSHOT_REF_PREFIX: &str = "refs/autoflow/snapshots";
+
+/// `.autoflow/runs/<run-id>/snapshots/sprint-<id>-<seq>-<PHASE>.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseSnapshot {
+    pub run_id: String,
+    pub sprint_id: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sprint_uid: Option<String>,
+    pub phase: SprintStatus,
+    pub taken_at: DateTime<Utc>,
+    /// HEAD of the sprint's checkout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+    /// Commit whose tree is the checkout's tracked files as they were: HEAD,
+    /// or a `git stash create` commit when there were uncommitted edits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tree_commit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    /// Untracked files at the time, which the tree commit doesn't hold
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub untracked: Vec<String>,
+    /// The sprint as it was when the phase started
+    pub sprint: Sprint,
+}
+
+impl PhaseSnapshot {
+    /// Capture `checkout` and `sprint` as the sprint's current phase starts
+    ///
+    /// Without git only the sprint state is kept. The working tree is left
+    /// untouched: uncommitted edits go into a dangling stash commit, which a
+    /// ref under [`SNAPSHOT_REF_PREFIX`] keeps alive.
+    pub fn capture(checkout: &Path, run_id: &str, sprint: &Sprint, seq: usize) -> Self {
+        let head = git(checkout, &["rev-parse", "--verify", "-q", "HEAD"]);
+        let tree_commit = git(checkout, &["stash", "create"])
+            .filter(|commit| !commit.is_empty())
+            .or_else(|| head.clone());
+        let git_ref = tree_commit.as_ref().and_then(|commit| {
+            let name = format!("{}/{}/sprint-{}-{:03}", SNAPSHOT_REF_PREFIX, run_id, sprint.id, seq);
+            git(checkout, &["update-ref", &name, commit]).map(|_| name)
+        });
+        let untracked = git(checkout, &["ls-files", "--others", "--exclude-standard"])
+            .map(|out| {
+                out.lines()
+                    .filter(|f| !f.starts_with(".autoflow/"))
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            run_id: run_id.to_string(),
+            sprint_id: sprint.id,
+            sprint_uid: sprint.uid.clone(),
+            phase: sprint.status,
+            taken_at: Utc::now(),
+            head,
+            tree_commit,
+            git_ref,
+            untracked,
+            sprint: sprint.clone(),
+        }
+    }
+
+    pub fn dir(project_path: &Path, run_id: &str) -> PathBuf {
+        project_path.join(autoflow_utils::RUNS_DIR).join(run_id).join(SNAPSHOTS_DIR)
+    }
+
+    /// Snapshots of `sprint_id` already taken in the run
+    pub fn count(project_path: &Path, run_id: &str, sprint_id: u32) -> usize {
+        let prefix = format!("sprint-{}-", sprint_id);
+        fs::read_dir(Self::dir(project_path, run_id))
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.file_name().to_string_lossy().starts_with(&prefix))
+                    .count()
+            })
+            .unwrap_or(0)
+    }
+
+    pub fn save(&self, project_path: &Path, seq: usize) -> Result<PathBuf> {
+        let dir = Self::dir(project_path, &self.run_id);
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("sprint-{}-{:03}-{}.json", self.sprint_id, seq, status_name(self.phase)));
+        autoflow_data::write_state(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+
+    /// Every snapshot in the project's runs, oldest first
+    pub fn load_all(project_path: &Path) -> Result<Vec<Self>> {
+        let runs = project_path.join(autoflow_utils::RUNS_DIR);
+        let mut snapshots = Vec::new();
+        let Ok(run_dirs) = fs::read_dir(&runs) else {
+            return Ok(snapshots);
+        };
+        for run_dir in run_dirs.filter_map(|e| e.ok()) {
+            let Ok(files) = fs::read_dir(run_dir.path().join(SNAPSHOTS_DIR)) else {
+                continue;
+            };
+            for file in files.filter_map(|e| e.ok()) {
+                let content = autoflow_data::read_state_to_string(file.path())?;
+                match serde_json::from_str::<Self>(&content) {
+                    Ok(snapshot) => snapshots.push(snapshot),
+                    Err(e) => tracing::debug!("Skipping unreadable snapshot {:?}: {}", file.path(), e),
+                }
+            }
+        }
+        snapshots.sort_by_key(|s| s.taken_at);
+        Ok(snapshots)
+    }
+
+    /// The `attempt`-th (1-based) time the phase ran for the sprint, or the
+    /// latest; optionally only within runs whose id starts with `run`
+    pub fn find<'a>(
+        snapshots: &'a [Self],
+        sprint_id: u32,
+        phase: SprintStatus,
+        run: Option<&str>,
+        attempt: Option<usize>,
+    ) -> Option<&'a Self> {
+        let run = run.map(|r| r.to_uppercase());
+        let matching: Vec<&Self> = snapshots
+            .iter()
+            .filter(|s| s.sprint_id == sprint_id && s.phase == phase)
+            .filter(|s| run.as_deref().is_none_or(|r| s.run_id.starts_with(r)))
+            .collect();
+        match attempt {
+            Some(n) => matching.get(n.checked_sub(1)?).copied(),
+            None => matching.last().copied(),
+        }
+    }
+
+    /// Check the snapshot's code out, detached, in a new worktree at `dest`
+    pub fn checkout(&self, repo: &Path, dest: &Path) -> Result<()> {
+        let commit = self.tree_commit.as_deref().ok_or_else(|| {
+            AutoFlowError::GitCommandFailed("the snapshot has no commit (the checkout wasn't a git repository)".to_string())
+        })?;
+        let output = Command::new("git")
+            .current_dir(repo)
+            .args(["worktree", "add", "--detach"])
+            .arg(dest)
+            .arg(commit)
+            .output()
+            .map_err(|e| AutoFlowError::GitCommandFailed(format!("Failed to run git worktree add: {}", e)))?;
+        if !output.status.success() {
+            return Err(AutoFlowError::GitCommandFailed(format!(
+                "git worktree add failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A phase given as `RUN_UNIT_TESTS` or `RunUnitTests`
+pub fn parse_phase(name: &str) -> Option<SprintStatus> {
+    let name = name.trim();
+    let camel = !name.contains('_') && name.chars().any(|c| c.is_ascii_lowercase());
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if i > 0 && camel && c.is_ascii_uppercase() {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_uppercase());
+    }
+    serde_json::from_value(serde_json::Value::String(snake)).ok()
+}
+
+/// Trimmed stdout of a successful git command
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git").current_dir(dir).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase_snapshot_round_trip() {
+        assert_eq!(parse_phase("RUN_UNIT_TESTS"), Some(SprintStatus::RunUnitTests));
+        assert_eq!(parse_phase("RunE2eTests"), Some(SprintStatus::RunE2eTests));
+        assert_eq!(parse_phase("e2e_fix"), Some(SprintStatus::E2eFix));
+        assert_eq!(parse_phase("WRITECODE"), None);
+        assert_eq!(parse_phase("Testing"), None);
+
+        let root = std::env::temp_dir().join(format!("autoflow-snapshot-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let run = |args: &[&str]| Command::new("git").current_dir(&root).args(args).output().unwrap();
+        run(&["init", "-q"]);
+        run(&["config", "user.email", "test@example.com"]);
+        run(&["config", "user.name", "Test"]);
+        fs::write(root.join("app.js"), "v1").unwrap();
+        run(&["add", "app.js"]);
+        run(&["commit", "-q", "-m", "First"]);
+        fs::write(root.join("app.js"), "v2 (uncommitted)").unwrap();
+        fs::write(root.join("notes.txt"), "new").unwrap();
+
+        let mut sprint: Sprint = serde_yaml::from_str(
+            "id: 5\ngoal: App\nstatus: RUN_UNIT_TESTS\ntotal_effort: 1h\nmax_effort: 2h\n\
+             last_updated: 2025-01-01T00:00:00Z\ndeliverables: []\ntasks: []\n",
+        )
+        .unwrap();
+        let snapshot = PhaseSnapshot::capture(&root, "01SNAP", &sprint, 1);
+        assert_ne!(snapshot.tree_commit, snapshot.head);
+        assert_eq!(snapshot.untracked, ["notes.txt"]);
+        snapshot.save(&root, 1).unwrap();
+        sprint.status = SprintStatus::UnitFix;
+        PhaseSnapshot::capture(&root, "01SNAP", &sprint, 2).save(&root, 2).unwrap();
+        assert_eq!(PhaseSnapshot::count(&root, "01SNAP", 5), 2);
+
+        let all = PhaseSnapshot::load_all(&root).unwrap();
+        let found = PhaseSnapshot::find(&all, 5, SprintStatus::RunUnitTests, Some("01s"), None).unwrap();
+        assert_eq!(found.sprint.status, SprintStatus::RunUnitTests);
+        assert!(PhaseSnapshot::find(&all, 5, SprintStatus::RunUnitTests, None, Some(2)).is_none());
+
+        // The worktree holds the uncommitted edit, and the original is untouched
+        let dest = root.join("replay");
+        found.checkout(&root, &dest).unwrap();
+        assert_eq!(fs::read_to_string(dest.join("app.js")).unwrap(), "v2 (uncommitted)");
+        assert_eq!(fs::read_to_string(root.join("app.js")).unwrap(), "v2 (uncommitted)");
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,162 @@
+// Task groups: split a sprint's tasks into sets that can be implemented in parallel
+
+use autoflow_data::{Task, TaskStatus};
+
+/// Indices of the sprint's unfinished tasks, grouped so no two groups share a file target
+///
+/// Tasks whose targets overlap (the same file, or one inside the other's
+/// directory) end up in the same group. Returns None when any unfinished task
+/// declares no `files`, since nothing can be said about what it touches, or
+/// when everything lands in a single group.
+pub fn partition_tasks(tasks: &[Task]) -> Option<Vec<Vec<usize>>> {
+    let pending: Vec<usize> = (0..tasks.len()).filter(|&i| tasks[i].status != TaskStatus::Done).collect();
+    if pending.iter().any(|&i| tasks[i].files.is_empty()) {
+        return None;
+    }
+
+    // Union-find over the pending tasks
+    let mut parent: Vec<usize> = (0..pending.len()).collect();
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        parent[i] = root;
+        root
+    }
+    for a in 0..pending.len() {
+        for b in a + 1..pending.len() {
+            let overlap = tasks[pending[a]]
+                .files
+                .iter()
+                .any(|x| tasks[pending[b]].files.iter().any(|y| targets_overlap(x, y)));
+            if overlap {
+                let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+                parent[rb] = ra;
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut roots: Vec<usize> = Vec::new();
+    for (i, &task) in pending.iter().enumerate() {
+        let root = find(&mut parent, i);
+        match roots.iter().position(|&r| r == root) {
+            Some(g) => groups[g].push(task),
+            None => {
+                roots.push(root);
+                groups.push(vec![task]);
+            }
+        }
+    }
+    (groups.len() > 1).then_some(groups)
+}
+
+/// Path a target covers: globs are cut back to the directory they start in
+fn target_root(target: &str) -> &str {
+    let target = target.trim().trim_start_matches("./");
+    let target = match target.find(['*', '?', '[']) {
+        Some(i) => target[..i].rsplit_once('/').map(|(dir, _)| dir).unwrap_or(""),
+        None => target,
+    };
+    target.trim_end_matches('/')
+}
+
+/// True when `path` is `root` or lies under it
+fn covers(root: &str, path: &str) -> bool {
+    root.is_empty() || path == root || path.strip_prefix(root).is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// True when two file targets could touch the same file
+pub fn targets_overlap(a: &str, b: &str) -> bool {
+    let (a, b) = (target_root(a), target_root(b));
+    covers(a, b) || covers(b, a)
+}
+
+/// True when `path` falls under any of `targets`
+pub fn within_targets(path: &str, targets: &[String]) -> bool {
+    let path = path.trim_start_matches("./");
+    targets.iter().any(|t| covers(target_root(t), path))
+}
+
+/// Briefing for one of several implementers working side by side in the same checkout
+pub fn parallel_instructions(tasks: &[Task], groups: usize) -> String {
+    let mut text = format!(
+        "# Parallel Implementation\n\nYou are one of {} implementers working on this sprint at the same time, \
+         in the same checkout. Implement only the tasks above. Create and change only these files:\n\n",
+        groups
+    );
+    for file in tasks.iter().flat_map(|t| t.files.iter()) {
+        text.push_str(&format!("- {}\n", file));
+    }
+    text.push_str(
+        "\nDo not edit anything else (shared config, lockfiles, other tasks' code) and do not commit; \
+         the changes are reviewed together when every implementer has finished.\n",
+    );
+    text
+}
+
+/// Briefing for the implementer that finishes a sprint after parallel implementers
+/// changed `stray` files, outside every task's declared targets
+pub fn reconcile_instructions(stray: &[String]) -> String {
+    let mut text = String::from(
+        "# Reconcile Parallel Work\n\nSeveral implementers just worked on this sprint's tasks side by side in this \
+         checkout. Some of them changed files outside the tasks' declared targets, so their changes may conflict:\n\n",
+    );
+    for file in stray {
+        text.push_str(&format!("- {}\n", file));
+    }
+    text.push_str(
+        "\nReview the uncommitted changes as a whole. Make every task complete and the code consistent, keep \
+         the changes the tasks need, and undo edits to the files above that no task calls for. Do not commit.\n",
+    );
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, files: &[&str]) -> Task {
+        let yaml = format!("id: {}\ntitle: {}\n", id, id);
+        let mut task: Task = serde_yaml::from_str(&yaml).unwrap();
+        task.files = files.iter().map(|f| f.to_string()).collect();
+        task
+    }
+
+    #[test]
+    fn test_reconcile_instructions_list_stray_files() {
+        let text = reconcile_instructions(&["Cargo.lock".to_string(), "src/lib.rs".to_string()]);
+        assert!(text.contains("- Cargo.lock\n- src/lib.rs\n"));
+        assert!(text.contains("Do not commit"));
+    }
+
+    #[test]
+    fn test_partition_tasks() {
+        let tasks = vec![
+            task("orders", &["src/api/orders.rs", "tests/orders.rs"]),
+            task("users", &["src/api/users/**"]),
+            task("user-model", &["src/api/users/model.rs"]),
+            task("billing", &["./src/billing/"]),
+        ];
+        assert_eq!(partition_tasks(&tasks), Some(vec![vec![0], vec![1, 2], vec![3]]));
+
+        // Finished tasks don't count
+        let mut done = tasks.clone();
+        done[1].status = TaskStatus::Done;
+        done[3].status = TaskStatus::Done;
+        assert_eq!(partition_tasks(&done), Some(vec![vec![0], vec![2]]));
+
+        // A task without targets could touch anything
+        let mut unknown = tasks.clone();
+        unknown.push(task("misc", &[]));
+        assert_eq!(partition_tasks(&unknown), None);
+
+        assert_eq!(partition_tasks(&tasks[1..3]), None);
+
+        assert!(!targets_overlap("src/api/user.rs", "src/api/users"));
+        assert!(targets_overlap("src/*.rs", "src/main.rs"));
+        assert!(within_targets("src/billing/invoice.rs", &["src/billing/".to_string()]));
+        assert!(!within_targets("Cargo.toml", &["src/billing/".to_string()]));
+    }
+}
@@ -1,5 +1,9 @@
-use autoflow_data::{SprintStatus, WorkflowType};
-use std::collections::HashMap;
+use crate::status_name;
+use autoflow_data::{AutoFlowError, IgnoreRules, Result, Sprint, SprintStatus, WorkflowType};
+use autoflow_quality::{HealthFailure, HealthFailureClass};
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::OnceLock;
 
 /// Defines a phase in a workflow
 #[derive(Debug, Clone)]
@@ -21,12 +25,93 @@ pub struct WorkflowPhase {
 
     /// Whether this phase requires validation (e.g., tests must pass)
     pub requires_validation: bool,
+
+    /// Files the phase must leave behind, checked against what it changed
+    pub artifacts: Option<ArtifactContract>,
+}
+
+/// What a phase promises to produce, e.g. WRITE_UNIT_TESTS adds test files
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArtifactContract {
+    /// Changed files that count, in `.autoflowignore` (gitignore) syntax; `!` excludes
+    pub patterns: &'static [&'static str],
+
+    /// How many changed files must match
+    pub min_files: usize,
+
+    /// What the phase promised, for the agent's feedback
+    pub description: &'static str,
+}
+
+impl ArtifactContract {
+    /// The changed files that satisfy the contract
+    pub fn matching<'a>(&self, changed: &'a [String]) -> Vec<&'a String> {
+        let rules = IgnoreRules::parse(&self.patterns.join("\n"));
+        changed.iter().filter(|file| rules.is_ignored(file, false)).collect()
+    }
+
+    pub fn is_met(&self, changed: &[String]) -> bool {
+        self.matching(changed).len() >= self.min_files
+    }
+
+    /// Why the contract isn't met, for the agent's retry
+    pub fn feedback(&self, changed: &[String]) -> String {
+        let found = if changed.is_empty() {
+            "Your previous attempt changed no files at all.".to_string()
+        } else {
+            format!(
+                "Your previous attempt changed {} file(s), of which {} count:\n{}",
+                changed.len(),
+                self.matching(changed).len(),
+                changed.iter().map(|f| format!("- {}", f)).collect::<Vec<_>>().join("\n")
+            )
+        };
+        format!(
+            "# Missing Artifacts\n\n\
+             This phase must produce {}: at least {} changed file(s) matching {}.\n\n{}\n\n\
+             Write the files now. Describing them or only planning them does not count.\n",
+            self.description,
+            self.min_files,
+            self.patterns.iter().map(|p| format!("`{}`", p)).collect::<Vec<_>>().join(", "),
+            found
+        )
+    }
 }
 
+/// Test files: anything named or placed like a test
+pub const TEST_FILES: ArtifactContract = ArtifactContract {
+    patterns: &["*test*", "*spec*", "tests/", "test/", "__tests__/"],
+    min_files: 1,
+    description: "test files",
+};
+
+/// E2E tests, wherever the project's runner keeps them
+pub const E2E_TESTS: ArtifactContract = ArtifactContract {
+    patterns: &["*e2e*", "*spec*", "*test*", "tests/", "playwright/", "cypress/"],
+    min_files: 1,
+    description: "E2E test files",
+};
+
+/// Any change that isn't only Markdown
+pub const CODE_CHANGES: ArtifactContract = ArtifactContract {
+    patterns: &["*", "!*.md"],
+    min_files: 1,
+    description: "code changes",
+};
+
+pub const DOC_FILES: ArtifactContract = ArtifactContract {
+    patterns: &["*.md", "*.mdx", "*.rst", "*.adoc", "*.txt", "docs/"],
+    min_files: 1,
+    description: "documentation",
+};
+
 /// Defines a complete workflow for a specific workflow type
 #[derive(Debug, Clone)]
 pub struct WorkflowDefinition {
-    /// The workflow type this definition is for
+    /// The workflow type's name, or a custom workflow's (`hotfix`)
+    pub name: String,
+
+    /// The workflow type this definition is for; a custom workflow's base type
     pub workflow_type: WorkflowType,
 
     /// The sequence of phases in this workflow
@@ -85,22 +170,218 @@ impl WorkflowDefinition {
     pub fn is_fix_phase(&self, status: SprintStatus) -> bool {
         self.phases.iter().any(|p| p.fix_status == Some(status))
     }
+
+    /// Where a blocked sprint picks up once the blocker is dealt with
+    ///
+    /// The first validation phase verifies the fix; a workflow without one
+    /// restarts at WRITE_CODE. Deployments were rolled back, so they redeploy.
+    pub fn unblock_status(&self) -> SprintStatus {
+        if self.workflow_type == WorkflowType::Deployment {
+            return SprintStatus::Deploy;
+        }
+        self.phases
+            .iter()
+            .find(|p| p.requires_validation && p.fix_status.is_some())
+            .map(|p| p.status)
+            .unwrap_or(SprintStatus::WriteCode)
+    }
+
+    /// Where a sprint goes when its completion checks fail
+    ///
+    /// A failed test phase goes to its own fix phase, a broken build or project
+    /// check to the unit fix, and missing docs to the review fix. With several
+    /// failures the earliest of those loops wins: the phases after it run again
+    /// on the way back to COMPLETE. An unreachable environment, or a failure the
+    /// workflow has no fix phase for, blocks the sprint.
+    pub fn health_fix_status(&self, failures: &[HealthFailure]) -> SprintStatus {
+        if failures.is_empty() || failures.iter().any(|f| f.class == HealthFailureClass::Environment) {
+            return SprintStatus::Blocked;
+        }
+        let mut earliest: Option<usize> = None;
+        for failure in failures {
+            let failed_phase = failure.phase.as_deref().and_then(|p| serde_yaml::from_str::<SprintStatus>(p).ok());
+            let candidates = match failure.class {
+                HealthFailureClass::Tests => vec![failed_phase, Some(SprintStatus::RunUnitTests)],
+                HealthFailureClass::Build | HealthFailureClass::Project => {
+                    vec![Some(SprintStatus::RunUnitTests), Some(SprintStatus::CodeReview)]
+                }
+                HealthFailureClass::Docs => vec![Some(SprintStatus::CodeReview), Some(SprintStatus::RunUnitTests)],
+                HealthFailureClass::Environment => unreachable!("environment failures block the sprint"),
+            };
+            let Some(index) = candidates
+                .into_iter()
+                .flatten()
+                .find_map(|status| self.phases.iter().position(|p| p.status == status && p.fix_status.is_some()))
+            else {
+                return SprintStatus::Blocked;
+            };
+            earliest = Some(earliest.map_or(index, |e| e.min(index)));
+        }
+        earliest.and_then(|i| self.phases[i].fix_status).unwrap_or(SprintStatus::Blocked)
+    }
+}
+
+/// Custom workflow files, relative to the project root
+pub const WORKFLOWS_DIR: &str = ".autoflow/workflows";
+
+/// `.autoflow/workflows/<name>.yml`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WorkflowFile {
+    name: String,
+    /// Workflow type the rest of AutoFlow treats the sprint as (agent context, hooks);
+    /// defaults to `name` when that is a workflow type
+    base: Option<WorkflowType>,
+    phases: Vec<PhaseFile>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PhaseFile {
+    status: SprintStatus,
+    /// Omitted for phases that auto-advance (PENDING, DONE)
+    agent: Option<String>,
+    #[serde(default = "default_max_turns")]
+    max_turns: u32,
+    /// Fix phase to run when this one fails
+    fix: Option<SprintStatus>,
+    #[serde(default = "default_max_retries")]
+    max_retries: u32,
+    /// Whether the agent's verdict decides if the phase passed; defaults to having a `fix`
+    validate: Option<bool>,
+    /// `tests`, `e2e`, `code` or `docs`
+    artifacts: Option<String>,
+}
+
+fn default_max_turns() -> u32 {
+    8
 }
 
-/// Get the workflow definition for a given workflow type
+fn default_max_retries() -> u32 {
+    1
+}
+
+/// Parse and check one workflow file
+pub fn parse_workflow(content: &str) -> Result<WorkflowDefinition> {
+    let file: WorkflowFile = serde_yaml::from_str(content)?;
+    let invalid = |reason: String| AutoFlowError::ValidationError(format!("Workflow '{}': {}", file.name, reason));
+    let workflow_type = match file.base {
+        Some(base) => base,
+        None => file.name.parse().map_err(|_| invalid("`base` must name a built-in workflow type".to_string()))?,
+    };
+    if file.phases.last().map(|p| p.status) != Some(SprintStatus::Done) {
+        return Err(invalid("the last phase must be DONE".to_string()));
+    }
+
+    let mut phases = Vec::new();
+    for phase in &file.phases {
+        if matches!(phase.status, SprintStatus::Paused | SprintStatus::Skipped | SprintStatus::Cancelled) {
+            return Err(invalid(format!("{} is set by hand, not a phase", status_name(phase.status))));
+        }
+        if phases.iter().any(|p: &WorkflowPhase| p.status == phase.status) {
+            return Err(invalid(format!("{} appears twice", status_name(phase.status))));
+        }
+        if let Some(fix) = phase.fix.filter(|fix| !file.phases.iter().any(|p| p.status == *fix)) {
+            return Err(invalid(format!("fix phase {} isn't in the workflow", status_name(fix))));
+        }
+        let artifacts = match phase.artifacts.as_deref() {
+            None => None,
+            Some("tests") => Some(TEST_FILES),
+            Some("e2e") => Some(E2E_TESTS),
+            Some("code") => Some(CODE_CHANGES),
+            Some("docs") => Some(DOC_FILES),
+            Some(other) => return Err(invalid(format!("unknown artifacts '{}' (tests, e2e, code or docs)", other))),
+        };
+        let agent = phase.agent.clone().unwrap_or_else(|| "none".to_string());
+        phases.push(WorkflowPhase {
+            status: phase.status,
+            // Loaded once per process and kept for its lifetime, like the built-ins
+            agent: Box::leak(agent.into_boxed_str()),
+            max_turns: if phase.agent.is_some() { phase.max_turns } else { 0 },
+            fix_status: phase.fix,
+            max_retries: phase.max_retries,
+            requires_validation: phase.validate.unwrap_or(phase.fix.is_some()),
+            artifacts,
+        });
+    }
+    Ok(WorkflowDefinition { name: file.name, workflow_type, phases })
+}
+
+/// Every workflow in `dir`, in file name order; files that don't parse are skipped with a warning
+pub fn load_workflows(dir: &Path) -> Vec<WorkflowDefinition> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<_> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| matches!(p.extension().and_then(|e| e.to_str()), Some("yml" | "yaml")))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter_map(|path| match std::fs::read_to_string(&path).map_err(AutoFlowError::from).and_then(|c| parse_workflow(&c)) {
+            Ok(workflow) => Some(workflow),
+            Err(e) => {
+                tracing::warn!("Ignoring workflow {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// The project's custom workflows, read once per process
+pub fn custom_workflows() -> &'static [WorkflowDefinition] {
+    static WORKFLOWS: OnceLock<Vec<WorkflowDefinition>> = OnceLock::new();
+    WORKFLOWS.get_or_init(|| load_workflows(Path::new(WORKFLOWS_DIR)))
+}
+
+/// A workflow by name: custom first, so a file can replace a built-in
+pub fn find_workflow(name: &str) -> Option<WorkflowDefinition> {
+    if let Some(custom) = custom_workflows().iter().find(|w| w.name.eq_ignore_ascii_case(name)) {
+        return Some(custom.clone());
+    }
+    name.parse().ok().map(builtin_workflow)
+}
+
+/// The workflow a sprint runs: its custom `workflow` when set, else its type's
+pub fn workflow_for(sprint: &Sprint) -> WorkflowDefinition {
+    if let Some(ref name) = sprint.workflow {
+        match custom_workflows().iter().find(|w| w.name.eq_ignore_ascii_case(name)) {
+            Some(custom) => return custom.clone(),
+            None => tracing::warn!("Sprint {} names unknown workflow '{}', using {}", sprint.id, name, sprint.workflow_type),
+        }
+    }
+    get_workflow_definition(sprint.workflow_type)
+}
+
+/// Get the workflow definition for a given workflow type, as overridden by
+/// `.autoflow/workflows/<type>.yml` when there is one
 pub fn get_workflow_definition(workflow_type: WorkflowType) -> WorkflowDefinition {
+    let name = workflow_type.to_string();
+    match custom_workflows().iter().find(|w| w.name == name) {
+        Some(custom) => custom.clone(),
+        None => builtin_workflow(workflow_type),
+    }
+}
+
+/// The workflow compiled into AutoFlow for `workflow_type`
+pub fn builtin_workflow(workflow_type: WorkflowType) -> WorkflowDefinition {
     match workflow_type {
         WorkflowType::Implementation => implementation_workflow(),
         WorkflowType::Documentation => documentation_workflow(),
         WorkflowType::Test => test_workflow(),
         WorkflowType::Infrastructure => infrastructure_workflow(),
         WorkflowType::Refactor => refactor_workflow(),
+        WorkflowType::Quick => quick_workflow(),
+        WorkflowType::Deployment => deployment_workflow(),
     }
 }
 
 /// Full TDD implementation workflow
 fn implementation_workflow() -> WorkflowDefinition {
     WorkflowDefinition {
+        name: WorkflowType::Implementation.to_string(),
         workflow_type: WorkflowType::Implementation,
         phases: vec![
             WorkflowPhase {
@@ -110,6 +391,7 @@ fn implementation_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::WriteUnitTests,
@@ -118,6 +400,7 @@ fn implementation_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: Some(TEST_FILES),
             },
             WorkflowPhase {
                 status: SprintStatus::WriteCode,
@@ -126,6 +409,7 @@ fn implementation_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: Some(CODE_CHANGES),
             },
             WorkflowPhase {
                 status: SprintStatus::CodeReview,
@@ -134,6 +418,7 @@ fn implementation_workflow() -> WorkflowDefinition {
                 fix_status: Some(SprintStatus::ReviewFix),
                 max_retries: 5,
                 requires_validation: true,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::ReviewFix,
@@ -142,6 +427,7 @@ fn implementation_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 5,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::RunUnitTests,
@@ -150,6 +436,7 @@ fn implementation_workflow() -> WorkflowDefinition {
                 fix_status: Some(SprintStatus::UnitFix),
                 max_retries: 3,
                 requires_validation: true,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::UnitFix,
@@ -158,6 +445,7 @@ fn implementation_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 3,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::WriteE2eTests,
@@ -166,6 +454,7 @@ fn implementation_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: Some(E2E_TESTS),
             },
             WorkflowPhase {
                 status: SprintStatus::RunE2eTests,
@@ -174,6 +463,7 @@ fn implementation_workflow() -> WorkflowDefinition {
                 fix_status: Some(SprintStatus::E2eFix),
                 max_retries: 3,
                 requires_validation: true,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::E2eFix,
@@ -182,6 +472,7 @@ fn implementation_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 3,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::Complete,
@@ -190,6 +481,7 @@ fn implementation_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::Done,
@@ -198,6 +490,7 @@ fn implementation_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 0,
                 requires_validation: false,
+                artifacts: None,
             },
         ],
     }
@@ -206,6 +499,7 @@ fn implementation_workflow() -> WorkflowDefinition {
 /// Simplified documentation workflow
 fn documentation_workflow() -> WorkflowDefinition {
     WorkflowDefinition {
+        name: WorkflowType::Documentation.to_string(),
         workflow_type: WorkflowType::Documentation,
         phases: vec![
             WorkflowPhase {
@@ -215,6 +509,7 @@ fn documentation_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::WriteCode, // Reuse WriteCode status for writing docs
@@ -223,6 +518,7 @@ fn documentation_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: Some(DOC_FILES),
             },
             WorkflowPhase {
                 status: SprintStatus::CodeReview, // Reuse CodeReview for doc review
@@ -231,6 +527,7 @@ fn documentation_workflow() -> WorkflowDefinition {
                 fix_status: Some(SprintStatus::ReviewFix),
                 max_retries: 3,
                 requires_validation: true,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::ReviewFix,
@@ -239,6 +536,7 @@ fn documentation_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 3,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::Complete,
@@ -247,6 +545,7 @@ fn documentation_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::Done,
@@ -255,6 +554,7 @@ fn documentation_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 0,
                 requires_validation: false,
+                artifacts: None,
             },
         ],
     }
@@ -263,6 +563,7 @@ fn documentation_workflow() -> WorkflowDefinition {
 /// Test-specific workflow
 fn test_workflow() -> WorkflowDefinition {
     WorkflowDefinition {
+        name: WorkflowType::Test.to_string(),
         workflow_type: WorkflowType::Test,
         phases: vec![
             WorkflowPhase {
@@ -272,6 +573,7 @@ fn test_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::WriteCode, // Reuse WriteCode for writing tests
@@ -280,6 +582,7 @@ fn test_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: Some(TEST_FILES),
             },
             WorkflowPhase {
                 status: SprintStatus::CodeReview,
@@ -288,6 +591,7 @@ fn test_workflow() -> WorkflowDefinition {
                 fix_status: Some(SprintStatus::ReviewFix),
                 max_retries: 3,
                 requires_validation: true,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::ReviewFix,
@@ -296,6 +600,7 @@ fn test_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 3,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::RunUnitTests, // Run the tests we just wrote
@@ -304,6 +609,7 @@ fn test_workflow() -> WorkflowDefinition {
                 fix_status: Some(SprintStatus::UnitFix),
                 max_retries: 3,
                 requires_validation: true,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::UnitFix,
@@ -312,6 +618,7 @@ fn test_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 3,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::Complete,
@@ -320,6 +627,7 @@ fn test_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::Done,
@@ -328,6 +636,7 @@ fn test_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 0,
                 requires_validation: false,
+                artifacts: None,
             },
         ],
     }
@@ -336,6 +645,7 @@ fn test_workflow() -> WorkflowDefinition {
 /// Infrastructure workflow with integration tests
 fn infrastructure_workflow() -> WorkflowDefinition {
     WorkflowDefinition {
+        name: WorkflowType::Infrastructure.to_string(),
         workflow_type: WorkflowType::Infrastructure,
         phases: vec![
             WorkflowPhase {
@@ -345,6 +655,7 @@ fn infrastructure_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::WriteCode,
@@ -353,6 +664,7 @@ fn infrastructure_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: Some(CODE_CHANGES),
             },
             WorkflowPhase {
                 status: SprintStatus::CodeReview,
@@ -361,6 +673,7 @@ fn infrastructure_workflow() -> WorkflowDefinition {
                 fix_status: Some(SprintStatus::ReviewFix),
                 max_retries: 5,
                 requires_validation: true,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::ReviewFix,
@@ -369,6 +682,7 @@ fn infrastructure_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 5,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::WriteE2eTests, // Use E2E for integration tests
@@ -377,6 +691,7 @@ fn infrastructure_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: Some(TEST_FILES),
             },
             WorkflowPhase {
                 status: SprintStatus::RunE2eTests, // Run integration tests
@@ -385,6 +700,7 @@ fn infrastructure_workflow() -> WorkflowDefinition {
                 fix_status: Some(SprintStatus::E2eFix),
                 max_retries: 3,
                 requires_validation: true,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::E2eFix,
@@ -393,6 +709,7 @@ fn infrastructure_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 3,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::Complete,
@@ -401,6 +718,7 @@ fn infrastructure_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::Done,
@@ -409,6 +727,7 @@ fn infrastructure_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 0,
                 requires_validation: false,
+                artifacts: None,
             },
         ],
     }
@@ -417,6 +736,7 @@ fn infrastructure_workflow() -> WorkflowDefinition {
 /// Refactoring workflow - verify tests exist, refactor, re-run tests
 fn refactor_workflow() -> WorkflowDefinition {
     WorkflowDefinition {
+        name: WorkflowType::Refactor.to_string(),
         workflow_type: WorkflowType::Refactor,
         phases: vec![
             WorkflowPhase {
@@ -426,6 +746,7 @@ fn refactor_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::WriteUnitTests, // Verify tests exist first
@@ -434,6 +755,7 @@ fn refactor_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::WriteCode, // Perform refactoring
@@ -442,6 +764,7 @@ fn refactor_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: Some(CODE_CHANGES),
             },
             WorkflowPhase {
                 status: SprintStatus::CodeReview,
@@ -450,6 +773,7 @@ fn refactor_workflow() -> WorkflowDefinition {
                 fix_status: Some(SprintStatus::ReviewFix),
                 max_retries: 5,
                 requires_validation: true,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::ReviewFix,
@@ -458,6 +782,7 @@ fn refactor_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 5,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::RunUnitTests, // Verify tests still pass
@@ -466,6 +791,7 @@ fn refactor_workflow() -> WorkflowDefinition {
                 fix_status: Some(SprintStatus::UnitFix),
                 max_retries: 3,
                 requires_validation: true,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::UnitFix,
@@ -474,6 +800,7 @@ fn refactor_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 3,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::Complete,
@@ -482,6 +809,7 @@ fn refactor_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 1,
                 requires_validation: false,
+                artifacts: None,
             },
             WorkflowPhase {
                 status: SprintStatus::Done,
@@ -490,6 +818,135 @@ fn refactor_workflow() -> WorkflowDefinition {
                 fix_status: None,
                 max_retries: 0,
                 requires_validation: false,
+                artifacts: None,
+            },
+        ],
+    }
+}
+
+/// Reduced workflow for `autoflow quick` - implement, test, review
+fn quick_workflow() -> WorkflowDefinition {
+    WorkflowDefinition {
+        name: WorkflowType::Quick.to_string(),
+        workflow_type: WorkflowType::Quick,
+        phases: vec![
+            WorkflowPhase {
+                status: SprintStatus::Pending,
+                agent: "none", // Auto-advance
+                max_turns: 0,
+                fix_status: None,
+                max_retries: 1,
+                requires_validation: false,
+                artifacts: None,
+            },
+            WorkflowPhase {
+                status: SprintStatus::WriteCode, // Change and its tests together
+                agent: "code-implementer",
+                max_turns: 8,
+                fix_status: None,
+                max_retries: 1,
+                requires_validation: false,
+                artifacts: Some(CODE_CHANGES),
+            },
+            WorkflowPhase {
+                status: SprintStatus::RunUnitTests,
+                agent: "unit-test-runner",
+                max_turns: 5,
+                fix_status: Some(SprintStatus::UnitFix),
+                max_retries: 2,
+                requires_validation: true,
+                artifacts: None,
+            },
+            WorkflowPhase {
+                status: SprintStatus::UnitFix,
+                agent: "unit-fixer",
+                max_turns: 8,
+                fix_status: None,
+                max_retries: 2,
+                requires_validation: false,
+                artifacts: None,
+            },
+            WorkflowPhase {
+                status: SprintStatus::CodeReview,
+                agent: "reviewer",
+                max_turns: 5,
+                fix_status: Some(SprintStatus::ReviewFix),
+                max_retries: 2,
+                requires_validation: true,
+                artifacts: None,
+            },
+            WorkflowPhase {
+                status: SprintStatus::ReviewFix,
+                agent: "review-fixer",
+                max_turns: 8,
+                fix_status: None,
+                max_retries: 2,
+                requires_validation: false,
+                artifacts: None,
+            },
+            WorkflowPhase {
+                status: SprintStatus::Done,
+                agent: "none",
+                max_turns: 0,
+                fix_status: None,
+                max_retries: 0,
+                requires_validation: false,
+                artifacts: None,
+            },
+        ],
+    }
+}
+
+/// Deployment workflow: deploy (after approval), native smoke checks, rollback on failure
+fn deployment_workflow() -> WorkflowDefinition {
+    WorkflowDefinition {
+        name: WorkflowType::Deployment.to_string(),
+        workflow_type: WorkflowType::Deployment,
+        phases: vec![
+            WorkflowPhase {
+                status: SprintStatus::Pending,
+                agent: "none", // Auto-advance
+                max_turns: 0,
+                fix_status: None,
+                max_retries: 1,
+                requires_validation: false,
+                artifacts: None,
+            },
+            WorkflowPhase {
+                status: SprintStatus::Deploy, // Approval gate runs first
+                agent: "deployer",
+                max_turns: 10,
+                fix_status: None,
+                max_retries: 1,
+                requires_validation: false,
+                artifacts: None,
+            },
+            WorkflowPhase {
+                status: SprintStatus::SmokeTest,
+                agent: "none", // Native HTTP checks from .autoflow/deploy/deploy.yml
+                max_turns: 0,
+                fix_status: Some(SprintStatus::Rollback),
+                max_retries: 2, // First failure goes straight to rollback
+                requires_validation: true,
+                artifacts: None,
+            },
+            WorkflowPhase {
+                status: SprintStatus::Rollback,
+                agent: "none", // Native: restore the last good deployment, then block
+                max_turns: 0,
+                fix_status: None,
+                max_retries: 1,
+                requires_validation: false,
+                artifacts: None,
+            },
+            WorkflowPhase {
+                status: SprintStatus::Done,
+                agent: "none",
+                max_turns: 0,
+                fix_status: None,
+                max_retries: 0,
+                requires_validation: false,
+                artifacts: None,
             },
         ],
     }
@@ -526,6 +983,88 @@ mod tests {
         assert!(unit_tests.is_none());
     }
 
+    #[test]
+    fn test_artifact_contracts() {
+        let workflow = get_workflow_definition(WorkflowType::Implementation);
+        let tests = workflow.get_phase(SprintStatus::WriteUnitTests).unwrap().artifacts.unwrap();
+        let changed = |files: &[&str]| files.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+
+        assert!(tests.is_met(&changed(&["src/cart.ts", "src/cart.test.ts"])));
+        assert!(tests.is_met(&changed(&["tests/test_cart.py"])));
+        assert!(tests.is_met(&changed(&["app/__tests__/Cart.tsx"])));
+        assert!(!tests.is_met(&changed(&["src/cart.ts"])));
+        assert!(tests.feedback(&[]).contains("changed no files"));
+
+        let code = workflow.get_phase(SprintStatus::WriteCode).unwrap().artifacts.unwrap();
+        assert!(!code.is_met(&changed(&["README.md"])));
+        assert_eq!(code.matching(&changed(&["README.md", "src/cart.ts"])), ["src/cart.ts"]);
+
+        // Validation and fix phases promise nothing
+        assert!(workflow.get_phase(SprintStatus::RunUnitTests).unwrap().artifacts.is_none());
+        assert!(workflow.get_phase(SprintStatus::UnitFix).unwrap().artifacts.is_none());
+    }
+
+    #[test]
+    fn test_workflow_from_yaml() {
+        let hotfix = parse_workflow(
+            "name: hotfix\nbase: IMPLEMENTATION\nphases:\n  - status: PENDING\n  - status: WRITE_CODE\n    agent: code-implementer\n    max_turns: 6\n    artifacts: code\n  - status: RUN_UNIT_TESTS\n    agent: unit-test-runner\n    fix: UNIT_FIX\n    max_retries: 2\n  - status: UNIT_FIX\n    agent: unit-fixer\n  - status: DONE\n",
+        )
+        .unwrap();
+        assert_eq!((hotfix.name.as_str(), hotfix.workflow_type), ("hotfix", WorkflowType::Implementation));
+        assert_eq!(hotfix.next_phase_skip_fix(SprintStatus::WriteCode).unwrap().status, SprintStatus::RunUnitTests);
+        assert_eq!(hotfix.next_phase_skip_fix(SprintStatus::RunUnitTests).unwrap().status, SprintStatus::Done);
+        let tests = hotfix.get_phase(SprintStatus::RunUnitTests).unwrap();
+        assert_eq!((tests.fix_status, tests.max_retries, tests.requires_validation), (Some(SprintStatus::UnitFix), 2, true));
+        assert!(hotfix.get_phase(SprintStatus::WriteCode).unwrap().artifacts.is_some());
+        assert_eq!(hotfix.get_phase(SprintStatus::Pending).unwrap().max_turns, 0);
+
+        // A custom name needs a base type; the graph must end at DONE and fix phases must exist
+        assert!(parse_workflow("name: hotfix\nphases:\n  - status: DONE\n").is_err());
+        assert!(parse_workflow("name: quick\nphases:\n  - status: WRITE_CODE\n    agent: code-implementer\n").is_err());
+        assert!(parse_workflow("name: quick\nphases:\n  - status: RUN_UNIT_TESTS\n    agent: unit-test-runner\n    fix: UNIT_FIX\n  - status: DONE\n").is_err());
+        assert!(parse_workflow("name: quick\nphases:\n  - status: DONE\n    retries: 3\n").is_err());
+
+        let dir = std::env::temp_dir().join(format!("autoflow-workflows-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("hotfix.yml"), "name: hotfix\nbase: QUICK\nphases:\n  - status: WRITE_CODE\n    agent: code-implementer\n  - status: DONE\n").unwrap();
+        std::fs::write(dir.join("broken.yaml"), "name: broken\n").unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a workflow").unwrap();
+        let loaded = load_workflows(&dir);
+        assert_eq!(loaded.iter().map(|w| w.name.as_str()).collect::<Vec<_>>(), ["hotfix"]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_quick_workflow() {
+        let workflow = get_workflow_definition(WorkflowType::Quick);
+
+        // Implement -> test -> review -> done, no E2E or health check
+        let next = workflow.next_phase_skip_fix(SprintStatus::WriteCode);
+        assert_eq!(next.unwrap().status, SprintStatus::RunUnitTests);
+        let next = workflow.next_phase_skip_fix(SprintStatus::RunUnitTests);
+        assert_eq!(next.unwrap().status, SprintStatus::CodeReview);
+        let next = workflow.next_phase_skip_fix(SprintStatus::CodeReview);
+        assert_eq!(next.unwrap().status, SprintStatus::Done);
+        assert!(workflow.get_phase(SprintStatus::RunE2eTests).is_none());
+    }
+
+    #[test]
+    fn test_deployment_workflow() {
+        let workflow = get_workflow_definition(WorkflowType::Deployment);
+
+        let next = workflow.next_phase_skip_fix(SprintStatus::Pending);
+        assert_eq!(next.unwrap().status, SprintStatus::Deploy);
+        let next = workflow.next_phase_skip_fix(SprintStatus::Deploy);
+        assert_eq!(next.unwrap().status, SprintStatus::SmokeTest);
+        let next = workflow.next_phase_skip_fix(SprintStatus::SmokeTest);
+        assert_eq!(next.unwrap().status, SprintStatus::Done);
+
+        // Failed smoke checks roll back instead of retrying the deploy
+        let fix = workflow.get_fix_phase(SprintStatus::SmokeTest);
+        assert_eq!(fix.unwrap().status, SprintStatus::Rollback);
+    }
+
     #[test]
     fn test_next_phase() {
         let workflow = get_workflow_definition(WorkflowType::Implementation);
@@ -543,4 +1082,24 @@ mod tests {
         assert!(fix.is_some());
         assert_eq!(fix.unwrap().status, SprintStatus::ReviewFix);
     }
+
+    #[test]
+    fn test_health_fix_status() {
+        let workflow = get_workflow_definition(WorkflowType::Implementation);
+        let failure = |class, phase: Option<&str>| HealthFailure { phase: phase.map(String::from), ..HealthFailure::new(class, "") };
+
+        let e2e = failure(HealthFailureClass::Tests, Some("RUN_E2E_TESTS"));
+        assert_eq!(workflow.health_fix_status(std::slice::from_ref(&e2e)), SprintStatus::E2eFix);
+        assert_eq!(workflow.health_fix_status(&[failure(HealthFailureClass::Build, None)]), SprintStatus::UnitFix);
+        assert_eq!(workflow.health_fix_status(&[failure(HealthFailureClass::Docs, None)]), SprintStatus::ReviewFix);
+
+        // The earliest loop covers the later ones; a down environment or an empty report blocks
+        assert_eq!(workflow.health_fix_status(&[e2e.clone(), failure(HealthFailureClass::Project, None)]), SprintStatus::UnitFix);
+        assert_eq!(workflow.health_fix_status(&[e2e, failure(HealthFailureClass::Environment, None)]), SprintStatus::Blocked);
+        assert_eq!(workflow.health_fix_status(&[]), SprintStatus::Blocked);
+
+        // Documentation sprints have only the review loop
+        let docs = get_workflow_definition(WorkflowType::Documentation);
+        assert_eq!(docs.health_fix_status(&[failure(HealthFailureClass::Build, None)]), SprintStatus::ReviewFix);
+    }
 }
@@ -0,0 +1,190 @@
+// Workflow fit: the workflow_type a sprint's content calls for, from its task types and files
+
+use autoflow_data::{Sprint, TaskType, WorkflowType};
+
+/// Files the sprint's tasks and integration_points name
+fn planned_files(sprint: &Sprint) -> Vec<&str> {
+    let declared = sprint
+        .integration_points
+        .iter()
+        .flat_map(|ip| ip.modifies.iter().chain(ip.creates.iter()));
+    sprint
+        .tasks
+        .iter()
+        .flat_map(|t| t.files.iter())
+        .chain(declared)
+        .map(|f| f.trim_start_matches("./"))
+        .collect()
+}
+
+fn is_doc_file(path: &str) -> bool {
+    let lower = path.to_ascii_lowercase();
+    lower.starts_with("docs/")
+        || [".md", ".mdx", ".rst", ".adoc", ".txt"].iter().any(|ext| lower.ends_with(ext))
+}
+
+fn is_config_file(path: &str) -> bool {
+    let lower = path.to_ascii_lowercase();
+    let name = lower.rsplit('/').next().unwrap_or(&lower);
+    name.starts_with("dockerfile")
+        || name.starts_with(".env")
+        || name == "makefile"
+        || lower.starts_with(".github/")
+        || lower.starts_with("k8s/")
+        || lower.starts_with("helm/")
+        || lower.starts_with("terraform/")
+        || [".yml", ".yaml", ".toml", ".ini", ".conf", ".tf", ".tfvars", ".hcl"]
+            .iter()
+            .any(|ext| name.ends_with(ext))
+}
+
+fn is_test_file(path: &str) -> bool {
+    let lower = path.to_ascii_lowercase();
+    let name = lower.rsplit('/').next().unwrap_or(&lower);
+    lower.starts_with("tests/")
+        || lower.starts_with("test/")
+        || lower.contains("/tests/")
+        || lower.contains("/__tests__/")
+        || lower.starts_with("e2e/")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+        || name.contains("_test.")
+        || name.starts_with("test_")
+}
+
+/// The workflow the sprint's content points to, with why; None when it's
+/// ordinary implementation work or too mixed to call
+///
+/// Every task sharing one type decides it. Otherwise the planned files do,
+/// when all of them are docs, all tests, or all configuration.
+pub fn recommend_workflow(sprint: &Sprint) -> Option<(WorkflowType, String)> {
+    if let Some(first) = sprint.tasks.first() {
+        if sprint.tasks.iter().all(|t| t.r#type == first.r#type) {
+            let workflow = match first.r#type {
+                TaskType::Documentation => Some(WorkflowType::Documentation),
+                TaskType::Test => Some(WorkflowType::Test),
+                TaskType::Infrastructure => Some(WorkflowType::Infrastructure),
+                TaskType::Refactor => Some(WorkflowType::Refactor),
+                TaskType::Implementation | TaskType::Bugfix => None,
+            };
+            if let Some(workflow) = workflow {
+                return Some((workflow, format!("every task is of type {:?}", first.r#type).to_lowercase()));
+            }
+        }
+    }
+
+    let files = planned_files(sprint);
+    if files.is_empty() {
+        return None;
+    }
+    let all = |check: fn(&str) -> bool| files.iter().all(|f| check(f));
+    if all(is_doc_file) {
+        Some((WorkflowType::Documentation, "every planned file is documentation".to_string()))
+    } else if all(is_test_file) {
+        Some((WorkflowType::Test, "every planned file is a test".to_string()))
+    } else if all(is_config_file) {
+        Some((WorkflowType::Infrastructure, "every planned file is configuration".to_string()))
+    } else {
+        None
+    }
+}
+
+/// A sprint whose content contradicts its workflow_type
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkflowMismatch {
+    pub sprint: u32,
+    pub declared: WorkflowType,
+    pub recommended: WorkflowType,
+    pub reason: String,
+}
+
+impl std::fmt::Display for WorkflowMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Sprint {} is workflow_type {} but {}: {} fits better",
+            self.sprint, self.declared, self.reason, self.recommended
+        )
+    }
+}
+
+/// Sprints whose workflow_type their content contradicts
+///
+/// Quick and deployment sprints are chosen deliberately and never flagged.
+pub fn workflow_mismatches(sprints: &[Sprint]) -> Vec<WorkflowMismatch> {
+    sprints
+        .iter()
+        .filter(|s| !matches!(s.workflow_type, WorkflowType::Quick | WorkflowType::Deployment))
+        .filter_map(|sprint| {
+            let (recommended, reason) = recommend_workflow(sprint)?;
+            (recommended != sprint.workflow_type).then_some(WorkflowMismatch {
+                sprint: sprint.id,
+                declared: sprint.workflow_type,
+                recommended,
+                reason,
+            })
+        })
+        .collect()
+}
+
+/// At generation time, move sprints left on the default implementation
+/// workflow to the one their content calls for
+///
+/// Returns the changes made. A sprint given any other workflow keeps it;
+/// [`workflow_mismatches`] still reports it if it contradicts its content.
+pub fn select_workflows(sprints: &mut [Sprint]) -> Vec<WorkflowMismatch> {
+    let mut changed = Vec::new();
+    for mismatch in workflow_mismatches(sprints) {
+        if mismatch.declared != WorkflowType::Implementation {
+            continue;
+        }
+        if let Some(sprint) = sprints.iter_mut().find(|s| s.id == mismatch.sprint) {
+            sprint.workflow_type = mismatch.recommended;
+            changed.push(mismatch);
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template_sprint;
+
+    #[test]
+    fn test_recommend_workflow() {
+        let mut docs = template_sprint(1, "API guide");
+        docs.tasks[0].files = vec!["docs/api.md".to_string(), "README.md".to_string()];
+        let mut config = template_sprint(2, "CI");
+        config.tasks[0].files = vec![".github/workflows/ci.yml".to_string(), "Dockerfile".to_string()];
+        let mut tests = template_sprint(3, "Coverage");
+        tests.tasks[0].r#type = TaskType::Test;
+        let mut mixed = template_sprint(4, "Search");
+        mixed.tasks[0].files = vec!["src/search.ts".to_string(), "docs/search.md".to_string()];
+        let mut refactor = template_sprint(5, "Tidy");
+        refactor.workflow_type = WorkflowType::Refactor;
+        refactor.tasks[0].files = vec!["docs/notes.md".to_string()];
+        let mut quick = template_sprint(6, "Typo");
+        quick.workflow_type = WorkflowType::Quick;
+        quick.tasks[0].files = vec!["README.md".to_string()];
+
+        assert_eq!(recommend_workflow(&config).map(|(w, _)| w), Some(WorkflowType::Infrastructure));
+        assert_eq!(recommend_workflow(&tests).unwrap().1, "every task is of type test");
+        assert_eq!(recommend_workflow(&mixed), None);
+
+        let mut sprints = vec![docs, config, tests, mixed, refactor, quick];
+        let mismatches = workflow_mismatches(&sprints);
+        assert_eq!(mismatches.iter().map(|m| m.sprint).collect::<Vec<_>>(), [1, 2, 3, 5]);
+        assert_eq!(
+            mismatches[0].to_string(),
+            "Sprint 1 is workflow_type implementation but every planned file is documentation: documentation fits better"
+        );
+
+        // Only the sprints on the default workflow are switched
+        let changed = select_workflows(&mut sprints);
+        assert_eq!(changed.len(), 3);
+        assert_eq!(sprints[0].workflow_type, WorkflowType::Documentation);
+        assert_eq!(sprints[4].workflow_type, WorkflowType::Refactor);
+        assert_eq!(workflow_mismatches(&sprints).len(), 1);
+    }
+}
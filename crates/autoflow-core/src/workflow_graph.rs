@@ -0,0 +1,132 @@
+// Workflow graph: where each phase of a workflow leads, for `autoflow workflows show`
+
+use crate::workflow::{WorkflowDefinition, WorkflowPhase};
+use autoflow_data::SprintStatus;
+
+/// Status as written in SPRINTS.yml, e.g. `WRITE_CODE`
+pub fn status_name(status: SprintStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_else(|| format!("{:?}", status))
+}
+
+/// Where a sprint goes when the phase succeeds
+///
+/// Fix phases loop back to the phase they fix. A deployment's rollback never
+/// succeeds in that sense: it restores the last release and blocks the sprint.
+pub fn on_success(workflow: &WorkflowDefinition, phase: &WorkflowPhase) -> Option<SprintStatus> {
+    if phase.status == SprintStatus::Rollback {
+        return Some(SprintStatus::Blocked);
+    }
+    if let Some(validation) = workflow.get_validation_phase_for_fix(phase.status) {
+        return Some(validation.status);
+    }
+    workflow.next_phase_skip_fix(phase.status).map(|p| p.status)
+}
+
+/// Where a sprint goes when the phase fails, before its retries run out
+///
+/// Validation phases hand over to their fix phase; other phases, fix phases
+/// included, run again. Either way the sprint is BLOCKED after `max_retries`
+/// failures. None for phases that can't fail, and for rollback, which blocks regardless.
+pub fn on_failure(phase: &WorkflowPhase) -> Option<SprintStatus> {
+    match phase.status {
+        SprintStatus::Pending | SprintStatus::Done | SprintStatus::Rollback => None,
+        SprintStatus::Paused | SprintStatus::Skipped | SprintStatus::Cancelled => None,
+        _ => Some(phase.fix_status.unwrap_or(phase.status)),
+    }
+}
+
+/// Mermaid flowchart of a workflow
+pub fn workflow_mermaid(workflow: &WorkflowDefinition) -> String {
+    let mut out = String::from("flowchart TD\n");
+    let mut blocked = false;
+
+    for phase in &workflow.phases {
+        let name = status_name(phase.status);
+        let label = match phase.agent {
+            "none" => name.clone(),
+            agent => format!("{}<br/>{} · {} turns", name, agent, phase.max_turns),
+        };
+        out.push_str(&format!("    {}[\"{}\"]\n", name, label));
+    }
+
+    for phase in &workflow.phases {
+        let name = status_name(phase.status);
+        let is_fix = workflow.is_fix_phase(phase.status);
+        match on_success(workflow, phase) {
+            Some(SprintStatus::Blocked) => {
+                blocked = true;
+                out.push_str(&format!("    {} --> BLOCKED\n", name));
+            }
+            Some(next) if is_fix => out.push_str(&format!("    {} -->|fixed| {}\n", name, status_name(next))),
+            Some(next) => out.push_str(&format!("    {} --> {}\n", name, status_name(next))),
+            None => {}
+        }
+        match on_failure(phase) {
+            Some(fix) if fix != phase.status => {
+                out.push_str(&format!("    {} -->|fail| {}\n", name, status_name(fix)));
+            }
+            Some(_) if phase.max_retries > 1 => {
+                out.push_str(&format!("    {} -->|retry| {}\n", name, name));
+            }
+            _ => {}
+        }
+        if on_failure(phase).is_some() {
+            blocked = true;
+            out.push_str(&format!(
+                "    {} -.->|after {}| BLOCKED\n",
+                name,
+                plural(phase.max_retries, "failure")
+            ));
+        }
+    }
+
+    if blocked {
+        out.push_str("    BLOCKED[\"BLOCKED\"]\n");
+    }
+    out
+}
+
+fn plural(n: u32, word: &str) -> String {
+    if n == 1 {
+        format!("1 {}", word)
+    } else {
+        format!("{} {}s", n, word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::get_workflow_definition;
+    use autoflow_data::WorkflowType;
+
+    #[test]
+    fn test_workflow_graph() {
+        let workflow = get_workflow_definition(WorkflowType::Implementation);
+        let review = workflow.get_phase(SprintStatus::CodeReview).unwrap();
+        let fix = workflow.get_phase(SprintStatus::ReviewFix).unwrap();
+        assert_eq!(on_success(&workflow, review), Some(SprintStatus::RunUnitTests));
+        assert_eq!(on_failure(review), Some(SprintStatus::ReviewFix));
+        assert_eq!(on_success(&workflow, fix), Some(SprintStatus::CodeReview));
+
+        let mermaid = workflow_mermaid(&workflow);
+        assert!(mermaid.starts_with("flowchart TD\n"));
+        assert!(mermaid.contains("    WRITE_CODE[\"WRITE_CODE<br/>code-implementer · 10 turns\"]\n"));
+        assert!(mermaid.contains("    WRITE_CODE --> CODE_REVIEW\n"));
+        assert!(mermaid.contains("    CODE_REVIEW -->|fail| REVIEW_FIX\n"));
+        assert!(mermaid.contains("    REVIEW_FIX -->|fixed| CODE_REVIEW\n"));
+        assert!(mermaid.contains("    CODE_REVIEW -.->|after 5 failures| BLOCKED\n"));
+        assert!(!mermaid.contains("DONE -->"));
+
+        let deployment = get_workflow_definition(WorkflowType::Deployment);
+        let mermaid = workflow_mermaid(&deployment);
+        assert!(mermaid.contains("    SMOKE_TEST -->|fail| ROLLBACK\n"));
+        assert!(mermaid.contains("    ROLLBACK --> BLOCKED\n"));
+
+        assert_eq!("Deployment".parse(), Ok(WorkflowType::Deployment));
+        assert!("nightly".parse::<WorkflowType>().is_err());
+    }
+}
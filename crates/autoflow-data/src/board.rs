@@ -0,0 +1,139 @@
+// Markdown kanban board rendering of sprint state
+
+use crate::signoffs::{read_signoffs, SignOff, SIGNOFFS_FILE};
+use crate::sprints::{Sprint, SprintStatus, SprintsYaml};
+use crate::tasks::TaskStatus;
+use std::fs;
+use std::path::Path;
+
+/// Default location of the generated board
+pub const BOARD_PATH: &str = ".autoflow/BOARD.md";
+
+/// Board columns in workflow order
+const COLUMNS: &[(SprintStatus, &str)] = &[
+    (SprintStatus::Pending, "📋 Pending"),
+    (SprintStatus::WriteUnitTests, "🧪 Write Unit Tests"),
+    (SprintStatus::WriteCode, "💻 Write Code"),
+    (SprintStatus::CodeReview, "🔍 Code Review"),
+    (SprintStatus::ReviewFix, "🔧 Review Fix"),
+    (SprintStatus::RunUnitTests, "▶️ Run Unit Tests"),
+    (SprintStatus::UnitFix, "🔧 Unit Fix"),
+    (SprintStatus::WriteE2eTests, "🧪 Write E2E Tests"),
+    (SprintStatus::RunE2eTests, "▶️ Run E2E Tests"),
+    (SprintStatus::E2eFix, "🔧 E2E Fix"),
+    (SprintStatus::Deploy, "🚀 Deploy"),
+    (SprintStatus::SmokeTest, "🩺 Smoke Test"),
+    (SprintStatus::Rollback, "⏪ Rollback"),
+    (SprintStatus::Complete, "🏁 Complete"),
+    (SprintStatus::Blocked, "🚫 Blocked"),
+    (SprintStatus::Done, "✅ Done"),
+    (SprintStatus::Paused, "⏸️ Paused"),
+    (SprintStatus::Skipped, "⏭️ Skipped"),
+    (SprintStatus::Cancelled, "✖️ Cancelled"),
+];
+
+/// Render the plan as a markdown kanban board, one column per sprint status
+///
+/// Empty columns are listed in the overview table but omitted from the body.
+pub fn render_board(plan: &SprintsYaml) -> String {
+    let mut md = String::new();
+    md.push_str(&format!("# {} - Board\n\n", plan.project.name));
+    md.push_str(&format!(
+        "_Generated by AutoFlow at {}. Do not edit; regenerate with `autoflow board`._\n\n",
+        plan.project.last_updated.format("%Y-%m-%d %H:%M UTC")
+    ));
+
+    // Overview: one table column per status
+    let counts: Vec<usize> = COLUMNS
+        .iter()
+        .map(|(status, _)| plan.sprints.iter().filter(|s| s.status == *status).count())
+        .collect();
+    let visible: Vec<usize> = (0..COLUMNS.len())
+        .filter(|&i| counts[i] > 0 || matches!(COLUMNS[i].0, SprintStatus::Pending | SprintStatus::Done))
+        .collect();
+
+    md.push('|');
+    for &i in &visible {
+        md.push_str(&format!(" {} |", COLUMNS[i].1));
+    }
+    md.push_str("\n|");
+    for _ in &visible {
+        md.push_str("---|");
+    }
+    md.push_str("\n|");
+    for &i in &visible {
+        md.push_str(&format!(" {} |", counts[i]));
+    }
+    md.push_str("\n\n");
+
+    for (i, (status, title)) in COLUMNS.iter().enumerate() {
+        if counts[i] == 0 {
+            continue;
+        }
+        md.push_str(&format!("## {} ({})\n\n", title, counts[i]));
+        for sprint in plan.sprints.iter().filter(|s| s.status == *status) {
+            render_card(&mut md, sprint);
+        }
+        md.push('\n');
+    }
+
+    md
+}
+
+fn render_card(md: &mut String, sprint: &Sprint) {
+    let done = sprint.tasks.iter().filter(|t| t.status == TaskStatus::Done).count();
+    md.push_str(&format!(
+        "- **Sprint {}**: {} ({}/{} tasks",
+        sprint.id,
+        sprint.goal.replace('\n', " "),
+        done,
+        sprint.tasks.len()
+    ));
+    if !sprint.dependencies.is_empty() {
+        md.push_str(&format!(", after {}", sprint.dependencies.join(", ")));
+    }
+    if let Some(count) = sprint.blocked_count.filter(|c| *c > 0) {
+        md.push_str(&format!(", blocked {}x", count));
+    }
+    md.push_str(")\n");
+
+    for task in &sprint.tasks {
+        let check = if task.status == TaskStatus::Done { "x" } else { " " };
+        md.push_str(&format!("  - [{}] {} {}\n", check, task.id, task.title.replace('\n', " ")));
+    }
+}
+
+/// Sign-off section: every approval recorded at a gate, newest first
+pub fn render_signoffs(signoffs: &[SignOff]) -> String {
+    if signoffs.is_empty() {
+        return String::new();
+    }
+    let mut md = String::from("## ✍️ Sign-off
+
+| Approved | Gate | Sprint | Approver | Artifacts |
+|---|---|---|---|---|
+");
+    for signoff in signoffs.iter().rev() {
+        let artifacts: Vec<String> =
+            signoff.artifacts.iter().map(|a| format!("`{}` ({})", a.path, &a.sha256[..a.sha256.len().min(12)])).collect();
+        md.push_str(&format!(
+            "| {} | {} | {} | {} (via {}) | {} |\n",
+            signoff.approved_at.format("%Y-%m-%d %H:%M UTC"),
+            signoff.gate,
+            signoff.sprint.map(|id| id.to_string()).unwrap_or_else(|| "-".to_string()),
+            signoff.approver,
+            signoff.via,
+            if artifacts.is_empty() { signoff.detail.clone() } else { artifacts.join(", ") }
+        ));
+    }
+    md.push('\n');
+    md
+}
+
+/// Render and write the board to `path`, with the sign-offs logged beside it
+pub fn write_board<P: AsRef<Path>>(plan: &SprintsYaml, path: P) -> crate::Result<()> {
+    let path = path.as_ref();
+    let signoffs = read_signoffs(path.with_file_name(SIGNOFFS_FILE));
+    fs::write(path, render_board(plan) + &render_signoffs(&signoffs))?;
+    Ok(())
+}
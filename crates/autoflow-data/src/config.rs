@@ -1,28 +1,258 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use crate::Result;
+use crate::{Result, SprintStatus};
+
+/// Overrides the home directory AutoFlow reads its config and agents from (`--home`)
+pub const HOME_ENV: &str = "AUTOFLOW_HOME";
+
+/// Home directory: `--home`/`AUTOFLOW_HOME`, then `HOME`
+pub fn home_dir() -> Option<PathBuf> {
+    [HOME_ENV, "HOME"]
+        .iter()
+        .filter_map(std::env::var_os)
+        .find(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// `~/.autoflow`, or `$XDG_CONFIG_HOME/autoflow` when there's no home directory
+pub fn autoflow_dir() -> Option<PathBuf> {
+    home_dir().map(|h| h.join(".autoflow")).or_else(|| {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|v| !v.is_empty())
+            .map(|v| PathBuf::from(v).join("autoflow"))
+    })
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub autoflow: AutoFlowConfig,
     pub paths: PathsConfig,
     pub defaults: DefaultsConfig,
+
+    /// Stalled-agent detection
+    #[serde(default)]
+    pub watchdog: WatchdogConfig,
+
+    /// Preflight resource thresholds
+    #[serde(default)]
+    pub resources: ResourceConfig,
+
+    /// Cheaper model first for low-risk phases
+    #[serde(default)]
+    pub routing: RoutingConfig,
+
+    /// Run guardrails: approval, token budget, tool restrictions
+    #[serde(default)]
+    pub policy: PolicyConfig,
+
+    /// Object store that run summaries and reports are uploaded to
+    #[serde(default)]
+    pub artifacts: ArtifactsConfig,
+
+    /// Who hears about finished runs, and how
+    #[serde(default)]
+    pub notify: NotifyConfig,
+
+    /// Issue tracker that persistently blocked sprints are filed on
+    #[serde(default)]
+    pub forge: ForgeConfig,
+
+    /// Size cap and compression for live stream logs
+    #[serde(default)]
+    pub live_logs: LiveLogConfig,
+
+    /// Embedding model used to find doc sections when a reference misses
+    #[serde(default)]
+    pub embeddings: EmbeddingsConfig,
+
+    /// `.autoflow` files kept encrypted at rest
+    #[serde(default)]
+    pub encryption: EncryptionConfig,
+
+    /// Shell commands run before and after sprint phases
+    #[serde(default)]
+    pub hooks: HooksConfig,
+
+    /// Version control that phase results are committed to
+    #[serde(default)]
+    pub vcs: VcsConfig,
+
+    /// Which phases commit automatically, and how those commits are made
+    #[serde(default)]
+    pub commit: CommitConfig,
+
+    /// Native checks a sprint must pass before it is marked done
+    #[serde(default)]
+    pub health: HealthConfig,
+
+    /// Summarizing oversized failure reports before fixers read them
+    #[serde(default)]
+    pub failure_reports: FailureReportsConfig,
+
+    /// Commands behind `autoflow env start/stop/restart/logs/health`
+    #[serde(default)]
+    pub env: EnvConfig,
+
+    /// Dependency and container build caches shared across worktrees
+    #[serde(default)]
+    pub env_cache: EnvCacheConfig,
+
+    /// Pausing the run while the network is down
+    #[serde(default)]
+    pub network: NetworkConfig,
+
+    /// Maintenance sprints generated by `autoflow maintain`
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+
+    /// Agents run through another vendor's API instead of the claude CLI
+    #[serde(default)]
+    pub backends: BackendsConfig,
+
+    /// Temperature and other sampling parameters per agent
+    #[serde(default)]
+    pub sampling: SamplingConfig,
+
+    /// `[profile.<name>]` policy overrides per environment (dev, staging, prod...)
+    #[serde(default, rename = "profile", skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+
+    /// `[remote."<project>"]` hosts that run a project's agents over SSH
+    #[serde(default, rename = "remote", skip_serializing_if = "BTreeMap::is_empty")]
+    pub remotes: BTreeMap<String, RemoteConfig>,
 }
 
 impl Config {
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
         let content = fs::read_to_string(path)?;
         let config: Config = toml::from_str(&content)
-            .map_err(|e| crate::AutoFlowError::ValidationError(e.to_string()))?;
+            .map_err(|e| crate::AutoFlowError::ConfigError(e.to_string()))?;
         Ok(config)
     }
 
     pub fn global() -> Result<Self> {
-        let home = std::env::var("HOME").expect("HOME environment variable not set");
-        let config_path = PathBuf::from(home).join(".autoflow/config.toml");
-        Self::load(config_path)
+        Self::load(Self::global_path()?)
+    }
+
+    /// Path of the global config file (~/.autoflow/config.toml)
+    pub fn global_path() -> Result<PathBuf> {
+        autoflow_dir()
+            .map(|dir| dir.join("config.toml"))
+            .ok_or(crate::AutoFlowError::HomeNotFound)
+    }
+
+    /// Build a fresh config rooted at the given home directory
+    pub fn new_for_home<P: AsRef<Path>>(home: P) -> Self {
+        let home = home.as_ref();
+        Self {
+            autoflow: AutoFlowConfig {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                install_date: chrono::Utc::now().to_rfc3339(),
+            },
+            paths: PathsConfig {
+                agents_dir: home.join(".claude/agents"),
+                skills_dir: home.join(".claude/skills"),
+                reference_dir: home.join(".autoflow/reference"),
+                schemas_dir: home.join(".autoflow/schemas"),
+            },
+            defaults: DefaultsConfig::default(),
+            watchdog: WatchdogConfig::default(),
+            resources: ResourceConfig::default(),
+            routing: RoutingConfig::default(),
+            policy: PolicyConfig::default(),
+            artifacts: ArtifactsConfig::default(),
+            notify: NotifyConfig::default(),
+            forge: ForgeConfig::default(),
+            live_logs: LiveLogConfig::default(),
+            embeddings: EmbeddingsConfig::default(),
+            encryption: EncryptionConfig::default(),
+            hooks: HooksConfig::default(),
+            vcs: VcsConfig::default(),
+            commit: CommitConfig::default(),
+            health: HealthConfig::default(),
+            failure_reports: FailureReportsConfig::default(),
+            env: EnvConfig::default(),
+            env_cache: EnvCacheConfig::default(),
+            network: NetworkConfig::default(),
+            maintenance: MaintenanceConfig::default(),
+            backends: BackendsConfig::default(),
+            sampling: SamplingConfig::default(),
+            profiles: BTreeMap::new(),
+            remotes: BTreeMap::new(),
+        }
+    }
+
+    /// Profile to apply: the requested one, else the first whose `branches` match
+    ///
+    /// Requesting a profile that isn't defined is an error rather than a silent
+    /// fall back to the base policy.
+    pub fn resolve_profile(&self, requested: Option<&str>, branch: Option<&str>) -> Result<Option<String>> {
+        if let Some(name) = requested.filter(|n| !n.is_empty()) {
+            if !self.profiles.contains_key(name) {
+                let known = self.profiles.keys().cloned().collect::<Vec<_>>().join(", ");
+                return Err(crate::AutoFlowError::ConfigError(format!(
+                    "Unknown profile '{}' (defined: {})",
+                    name,
+                    if known.is_empty() { "none".to_string() } else { known }
+                )));
+            }
+            return Ok(Some(name.to_string()));
+        }
+
+        let Some(branch) = branch else {
+            return Ok(None);
+        };
+        Ok(self
+            .profiles
+            .iter()
+            .find(|(_, profile)| profile.branches.iter().any(|pattern| branch_matches(pattern, branch)))
+            .map(|(name, _)| name.clone()))
+    }
+
+    /// `[policy]` with the named profile's overrides applied
+    pub fn policy_for(&self, profile: Option<&str>) -> PolicyConfig {
+        let mut policy = self.policy.clone();
+        if let Some(overrides) = profile.and_then(|name| self.profiles.get(name)) {
+            if let Some(require_approval) = overrides.require_approval {
+                policy.require_approval = require_approval;
+            }
+            if let Some(max_run_tokens) = overrides.max_run_tokens {
+                policy.max_run_tokens = max_run_tokens;
+            }
+            if let Some(ref tools) = overrides.disallowed_tools {
+                policy.disallowed_tools = tools.clone();
+            }
+            if let Some(read_only) = overrides.read_only {
+                policy.read_only = read_only;
+            }
+            if let Some(patches) = overrides.patches {
+                policy.patches = patches;
+            }
+        }
+        policy
+    }
+
+    /// Remote host for the project in `project_dir`, keyed by its absolute path or directory name
+    pub fn remote_for(&self, project_dir: &Path) -> Option<&RemoteConfig> {
+        let full = project_dir.display().to_string();
+        let name = project_dir.file_name().map(|n| n.to_string_lossy().to_string());
+        self.remotes
+            .get(&full)
+            .or_else(|| name.and_then(|name| self.remotes.get(&name)))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| crate::AutoFlowError::ConfigError(e.to_string()))?;
+        fs::write(path, content)?;
+        Ok(())
     }
 }
 
@@ -47,29 +277,1261 @@ pub struct DefaultsConfig {
     pub parallel_sprints: bool,
     pub auto_commit: bool,
 
+    /// Implementers to run at once on a sprint's independent tasks (0 or 1 = one at a time)
+    #[serde(default)]
+    pub parallel_tasks: usize,
+
+    /// Let automatic commits stage submodule pointer changes
+    #[serde(default)]
+    pub allow_submodule_bumps: bool,
+
+    /// Branch new worktrees start from (main when unset)
+    #[serde(default)]
+    pub worktree_base_branch: Option<String>,
+
+    /// Remove merged AutoFlow branches and their worktrees this many days
+    /// after their last commit, when `start` runs (0 = keep)
+    #[serde(default)]
+    pub worktree_retention_days: u32,
+
+    /// Update .autoflow/docs from the built code at the end of each run
+    #[serde(default)]
+    pub docs_sync: bool,
+
+    /// How continuous mode picks the next sprint
+    #[serde(default)]
+    pub scheduling: SchedulingPolicy,
+
+    /// Doc sets `autoflow create` generates (empty = decide from IDEA.md)
+    #[serde(default)]
+    pub create_docs: Vec<DocSet>,
+
+    /// Fix phases (review, unit and E2E fixes together) a sprint may run per
+    /// run before it is blocked, whatever each phase's own retries allow (0 = no limit)
+    #[serde(default = "default_max_fix_attempts")]
+    pub max_fix_attempts: u32,
+
     /// Per-agent model overrides
     /// Example: {"reviewer": "claude-opus-4", "unit-fixer": "claude-sonnet-4"}
     #[serde(default)]
     pub model_overrides: std::collections::HashMap<String, String>,
 }
 
-impl DefaultsConfig {
+fn default_max_fix_attempts() -> u32 {
+    10
+}
+
+impl Default for DefaultsConfig {
     fn default() -> Self {
         Self {
             model: "claude-sonnet-4-5-20250929".to_string(),
             max_iterations: 50,
             parallel_sprints: false,
             auto_commit: true,
+            parallel_tasks: 0,
+            allow_submodule_bumps: false,
+            worktree_base_branch: None,
+            worktree_retention_days: 0,
+            docs_sync: false,
+            scheduling: SchedulingPolicy::default(),
+            create_docs: vec![],
+            max_fix_attempts: default_max_fix_attempts(),
             model_overrides: std::collections::HashMap::new(),
         }
     }
+}
+
+/// Sprint selection order for continuous mode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SchedulingPolicy {
+    /// In-progress first, then must_complete_first, then lowest ID
+    #[default]
+    Sequential,
+    /// Highest weighted score (priority, value, age); critical sprints preempt
+    Weighted,
+}
+
+impl std::str::FromStr for SchedulingPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sequential" => Ok(Self::Sequential),
+            "weighted" => Ok(Self::Weighted),
+            other => Err(format!("Unknown schedule '{}' (expected sequential or weighted)", other)),
+        }
+    }
+}
+
+/// Project docs `autoflow create` can generate, one doc agent each
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DocSet {
+    /// BUILD_SPEC.md and ARCHITECTURE.md (make-docs-foundation)
+    Build,
+    /// API_SPEC.md and DATA_MODEL.md (make-docs-api)
+    Api,
+    /// UI_SPEC.md and TESTING_STRATEGY.md (make-docs-ui)
+    Ui,
+}
+
+impl DocSet {
+    pub const ALL: [DocSet; 3] = [DocSet::Build, DocSet::Api, DocSet::Ui];
+
+    /// Parse a comma-separated list such as `build,api`
+    pub fn parse_list(list: &str) -> std::result::Result<Vec<DocSet>, String> {
+        let mut sets: Vec<DocSet> = Vec::new();
+        for name in list.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let set = name.parse()?;
+            if !sets.contains(&set) {
+                sets.push(set);
+            }
+        }
+        sets.sort();
+        Ok(sets)
+    }
+}
+
+impl std::fmt::Display for DocSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DocSet::Build => "build",
+            DocSet::Api => "api",
+            DocSet::Ui => "ui",
+        })
+    }
+}
+
+impl std::str::FromStr for DocSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "build" | "foundation" => Ok(Self::Build),
+            "api" => Ok(Self::Api),
+            "ui" => Ok(Self::Ui),
+            other => Err(format!("Unknown doc set '{}' (expected build, api or ui)", other)),
+        }
+    }
+}
+
+/// `[watchdog]`: how long an agent may go without a stream event
+///
+/// Separate from any overall time limit: a long but active agent is fine,
+/// a live process that has stopped emitting events is not.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    /// Warn after this many seconds of silence
+    pub warn_after_secs: u64,
+
+    /// Kill and retry the agent after this many seconds of silence (0 disables)
+    pub kill_after_secs: u64,
+
+    /// Retries after a stalled agent is killed
+    pub max_stall_retries: u32,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            warn_after_secs: 5 * 60,
+            kill_after_secs: 20 * 60,
+            max_stall_retries: 1,
+        }
+    }
+}
+
+/// `[network]`: waiting out lost connectivity instead of failing agents
+///
+/// When an agent fails and `probe` can't be reached, the run pauses and
+/// retries with backoff until it can, then reruns the agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// `host:port` whose reachability means the network is up
+    pub probe: String,
+
+    /// Longest pause between connectivity checks
+    pub max_backoff_secs: u64,
+
+    /// Give up after waiting this long (0 waits indefinitely)
+    pub max_wait_secs: u64,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            probe: "api.anthropic.com:443".to_string(),
+            max_backoff_secs: 60,
+            max_wait_secs: 0,
+        }
+    }
+}
+
+/// `[maintenance]`: when `autoflow maintain` turns its scans into sprints
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct MaintenanceConfig {
+    /// Days between runs for `autoflow maintain --if-due`
+    pub interval_days: u64,
+
+    /// Run the package manager's outdated check
+    pub check_dependencies: bool,
+
+    /// TODO/FIXME markers per 1000 source lines above which a debt sprint is added
+    pub max_debt_per_kloc: f64,
+
+    /// Intermittent failures before a test in `.autoflow/flaky-tests.yml` gets a sprint
+    pub flaky_min_failures: u32,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            interval_days: 7,
+            check_dependencies: true,
+            max_debt_per_kloc: 5.0,
+            flaky_min_failures: 2,
+        }
+    }
+}
+
+/// `[backends]`: which agents run on another vendor's chat API
+///
+/// Agents not mapped to a provider run on the claude CLI as usual. The
+/// provider's model is used whatever model the agent or routing asks for.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct BackendsConfig {
+    /// Provider for every agent not listed in `agents`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+
+    /// Agent or text task name to provider name, e.g. `reviewer = "openai"` or
+    /// `failure-triage = "ollama"`
+    pub agents: BTreeMap<String, String>,
+
+    /// `[backends.providers.<name>]`
+    pub providers: BTreeMap<String, ProviderConfig>,
+}
+
+impl BackendsConfig {
+    /// Name and settings of the provider `agent` runs on; None for the claude CLI
+    ///
+    /// A provider name that isn't defined is an error rather than a silent
+    /// fall back to the claude CLI.
+    pub fn provider_for(&self, agent: &str) -> Result<Option<(&str, &ProviderConfig)>> {
+        let Some(name) = self.agents.get(agent).or(self.default.as_ref()) else {
+            return Ok(None);
+        };
+        if name == "claude" {
+            return Ok(None);
+        }
+        self.providers
+            .get_key_value(name)
+            .map(|(name, provider)| Some((name.as_str(), provider)))
+            .ok_or_else(|| crate::AutoFlowError::ConfigError(format!("Agent '{}' uses provider '{}', which [backends.providers] doesn't define", agent, name)))
+    }
+
+    /// Provider for a text task such as `failure-triage`; only an explicit entry in `agents` turns one on
+    pub fn provider_for_task(&self, task: &str) -> Result<Option<(&str, &ProviderConfig)>> {
+        if !self.agents.contains_key(task) {
+            return Ok(None);
+        }
+        self.provider_for(task)
+    }
+}
+
+/// One chat API agents can run on
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProviderConfig {
+    pub kind: ProviderKind,
+
+    pub model: String,
+
+    /// API base URL; defaults to the vendor's public endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// Environment variable holding the API key; OPENAI_API_KEY or GEMINI_API_KEY when
+    /// unset (local servers are called without a key unless this is set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+
+    /// Whether the model can call tools; on by default except for local models
+    ///
+    /// Agents that need tools never run on a provider without them. Such a
+    /// provider only takes text tasks like `failure-triage`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<bool>,
+
+    /// Output token limit per model turn
+    #[serde(default = "default_provider_max_tokens")]
+    pub max_tokens: u32,
+
+    /// USD per million input and output tokens, for `autoflow cost` (0 = not priced)
+    #[serde(default)]
+    pub input_price_per_mtok: f64,
+    #[serde(default)]
+    pub output_price_per_mtok: f64,
+}
+
+impl ProviderConfig {
+    pub fn can_use_tools(&self) -> bool {
+        self.tools.unwrap_or(self.kind != ProviderKind::Local)
+    }
+}
+
+fn default_provider_max_tokens() -> u32 {
+    8192
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    /// `/chat/completions` as served by OpenAI and compatible servers (Azure, vLLM, OpenRouter...)
+    Openai,
+    /// Google's Gemini `generateContent` API
+    Gemini,
+    /// A model served on this machine or network through an OpenAI-compatible API:
+    /// Ollama by default, or llama.cpp's `llama-server` with `url`
+    Local,
+}
+
+/// `[sampling]`: how deterministic each agent's model output is
+///
+/// Each phase runs one agent, so keys are agent names (or text tasks such as
+/// `failure-triage`). An agent's entry overrides `default` field by field.
+/// Chat API providers apply them; the claude CLI takes no sampling options,
+/// so agents on it run with the model's defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SamplingConfig {
+    pub default: SamplingParams,
+    pub agents: BTreeMap<String, SamplingParams>,
+}
+
+impl SamplingConfig {
+    /// Parameters `agent` runs with
+    pub fn for_agent(&self, agent: &str) -> SamplingParams {
+        let Some(own) = self.agents.get(agent) else {
+            return self.default.clone();
+        };
+        SamplingParams {
+            temperature: own.temperature.or(self.default.temperature),
+            top_p: own.top_p.or(self.default.top_p),
+            top_k: own.top_k.or(self.default.top_k),
+            seed: own.seed.or(self.default.seed),
+        }
+    }
+}
+
+/// Sampling parameters; unset ones are left to the provider
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SamplingParams {
+    /// 0 gives the most repeatable output; OpenAI and Gemini accept 0-2
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+
+    /// Nucleus sampling: only tokens within this probability mass (0-1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+
+    /// Only the k likeliest tokens (Gemini and local models; OpenAI ignores it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_k: Option<u32>,
+
+    /// Same seed and input give the same output where the provider supports it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+impl SamplingParams {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// e.g. `temperature=0 seed=7`, as recorded in the run events; empty when nothing is set
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(t) = self.temperature {
+            parts.push(format!("temperature={}", t));
+        }
+        if let Some(p) = self.top_p {
+            parts.push(format!("top_p={}", p));
+        }
+        if let Some(k) = self.top_k {
+            parts.push(format!("top_k={}", k));
+        }
+        if let Some(seed) = self.seed {
+            parts.push(format!("seed={}", seed));
+        }
+        parts.join(" ")
+    }
+}
+
+/// `[live_logs]`: keep `.autoflow/.debug/live` logs from growing without bound
+///
+/// Each agent execution writes one log. When it reaches the cap it is rolled
+/// into a numbered segment and a fresh file takes its place, so the log being
+/// written (and followed) is always the uncompressed tail.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct LiveLogConfig {
+    /// Roll a log into a segment at this size (0 = never)
+    pub max_file_mb: u64,
+
+    /// Compress finished segments with the `zstd` CLI, when it's installed
+    pub compress: bool,
+}
+
+impl Default for LiveLogConfig {
+    fn default() -> Self {
+        Self { max_file_mb: 64, compress: false }
+    }
+}
+
+/// `[embeddings]`: resolve doc references whose heading has drifted by meaning
+///
+/// When a task's `FILE.md#Section` matches no heading, the sections closest
+/// to the task text are inlined instead of a table of contents. Off unless a
+/// provider is set.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EmbeddingsConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<EmbeddingProvider>,
+
+    /// Endpoint base URL; defaults to the provider's usual local or public one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// Embedding model; defaults to `nomic-embed-text` (ollama) or `text-embedding-3-small` (openai)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// Environment variable holding the API key, sent as a bearer token when set
+    pub api_key_env: String,
+
+    /// Sections inlined for one reference
+    pub max_sections: usize,
+
+    /// Cosine similarity below which a section isn't considered relevant
+    pub min_similarity: f32,
+}
+
+impl Default for EmbeddingsConfig {
+    fn default() -> Self {
+        Self {
+            provider: None,
+            url: None,
+            model: None,
+            api_key_env: "OPENAI_API_KEY".to_string(),
+            max_sections: 2,
+            min_similarity: 0.3,
+        }
+    }
+}
+
+/// Wire format of the embedding endpoint
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingProvider {
+    /// Ollama's `/api/embed`, for a local model
+    Ollama,
+    /// `/embeddings` as served by OpenAI and compatible servers (vLLM, LM Studio, llama.cpp)
+    Openai,
+}
+
+/// `[encryption]`: `.autoflow` state files kept encrypted at rest
+///
+/// Designated files are sealed with ChaCha20-Poly1305 on write and decrypted
+/// when AutoFlow loads them. Files written before encryption was turned on stay
+/// readable and are encrypted the next time they're saved.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EncryptionConfig {
+    /// Paths relative to `.autoflow/`, globs allowed, e.g. `["runs.jsonl", "runs/*/summary.json"]`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+
+    /// Environment variable holding the key: 64 hex characters (32 bytes)
+    pub key_env: String,
+
+    /// OS keyring service the key is read from when the variable is unset
+    /// (`secret-tool` on Linux, `security` on macOS; account `encryption-key`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keyring_service: Option<String>,
+}
+
+impl Default for EncryptionConfig {
+    fn default() -> Self {
+        Self {
+            files: Vec::new(),
+            key_env: "AUTOFLOW_ENCRYPTION_KEY".to_string(),
+            keyring_service: None,
+        }
+    }
+}
+
+/// `[hooks]`: shell commands run around each sprint phase
+///
+/// Top-level hooks run for every phase; `[hooks.workflow.<type>]` and
+/// `[hooks.phase.<STATUS>]` add hooks for one workflow type or one phase,
+/// e.g. regenerating GraphQL types before `RUN_UNIT_TESTS`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// Commands run before every phase
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pre_phase: Vec<String>,
+
+    /// Commands run after every phase, whatever its outcome
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub post_phase: Vec<String>,
+
+    /// What a failing hook does, unless its workflow or phase says otherwise
+    pub on_failure: HookFailure,
+
+    /// A hook still running after this long is killed and counts as failed
+    pub timeout_secs: u64,
+
+    /// Hooks for one workflow type (`implementation`, `infrastructure`, ...)
+    #[serde(rename = "workflow", skip_serializing_if = "BTreeMap::is_empty")]
+    pub workflows: BTreeMap<String, PhaseHooks>,
+
+    /// Hooks for one phase (`WRITE_CODE`, `RUN_E2E_TESTS`, ...)
+    #[serde(rename = "phase", skip_serializing_if = "BTreeMap::is_empty")]
+    pub phases: BTreeMap<String, PhaseHooks>,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            pre_phase: Vec::new(),
+            post_phase: Vec::new(),
+            on_failure: HookFailure::Warn,
+            timeout_secs: 600,
+            workflows: BTreeMap::new(),
+            phases: BTreeMap::new(),
+        }
+    }
+}
+
+/// `[hooks.workflow.<type>]` / `[hooks.phase.<STATUS>]`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PhaseHooks {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pre_phase: Vec<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub post_phase: Vec<String>,
+
+    /// Overrides `[hooks] on_failure` for these commands
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_failure: Option<HookFailure>,
+}
+
+/// How a failing hook affects the sprint
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HookFailure {
+    /// Log it and carry on with the phase
+    #[default]
+    Warn,
+    /// Block the sprint
+    Fatal,
+}
+
+/// `[vcs]`: which version control backend automatic commits go through
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct VcsConfig {
+    pub backend: VcsBackend,
+}
+
+/// Version control backend
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VcsBackend {
+    /// Jujutsu when the project has `.jj`, else git when it has `.git`, else none
+    #[default]
+    Auto,
+    Git,
+    #[serde(alias = "jj")]
+    Jujutsu,
+    /// Plain directory: nothing is committed
+    None,
+}
+
+/// `[commit]`: policy for the commits AutoFlow makes after phases
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CommitConfig {
+    /// Phases that commit when they succeed, e.g. `["WRITE_CODE", "UNIT_FIX"]`;
+    /// unset commits after every phase that changes code, empty never commits
+    pub phases: Option<Vec<SprintStatus>>,
+
+    /// Squash a sprint's phase commits into one when it reaches DONE (git only)
+    pub squash_on_done: bool,
 
+    /// Sign automatic commits
+    pub sign: CommitSigning,
+
+    /// Key to sign with: a GPG key id or an SSH key path (git's `user.signingkey` when unset)
+    pub signing_key: Option<String>,
+
+    /// Author and committer name for automatic commits, e.g. "AutoFlow Bot"
+    pub author_name: Option<String>,
+
+    /// Author and committer email for automatic commits
+    pub author_email: Option<String>,
+}
+
+/// How automatic commits are signed
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitSigning {
+    /// Whatever the repository's git config says
+    #[default]
+    Default,
+    Gpg,
+    Ssh,
+    /// Never sign, even if `commit.gpgsign` is set
+    Off,
+}
+
+/// `[health]`: what the Complete phase checks before a sprint is done
+///
+/// The build, cached test results, endpoints and doc deliverables are checked
+/// natively. The health-check agent only runs when `agent_checks` lists
+/// project-specific checks that need judgement.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct HealthConfig {
+    /// Command that must build the project; detected from its manifest when unset
+    pub build_command: Option<String>,
+
+    /// URLs that must answer with a success status
+    pub endpoints: Vec<String>,
+
+    /// Seconds the build may take
+    pub build_timeout_secs: u64,
+
+    /// Project-specific checks, in prose, handed to the health-check agent
+    pub agent_checks: Vec<String>,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            build_command: None,
+            endpoints: Vec::new(),
+            build_timeout_secs: 600,
+            agent_checks: Vec::new(),
+        }
+    }
+}
+
+/// `[failure_reports]`: keeping fixer contexts small
+///
+/// A report over `summarize_over_bytes` is moved to `.autoflow/.failures/full/`
+/// and replaced by a summary: failures grouped by error type and file, with a
+/// few representative traces.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct FailureReportsConfig {
+    /// Reports larger than this are summarized; 0 never summarizes
+    pub summarize_over_bytes: u64,
+
+    /// Representative traces kept, one per failure group
+    pub traces: usize,
+
+    /// Lines kept of each trace
+    pub trace_lines: usize,
+}
+
+impl Default for FailureReportsConfig {
+    fn default() -> Self {
+        Self {
+            summarize_over_bytes: 24_000,
+            traces: 5,
+            trace_lines: 25,
+        }
+    }
+}
+
+/// `[env]`: what `autoflow env` runs for each action
+///
+/// Unset actions use the project's task runner when it has a matching target
+/// (`just dev`, `make up`, `npm run start`, ...), then docker compose.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EnvConfig {
+    /// Look for justfile, Makefile and package.json targets
+    pub detect_runners: bool,
+
+    /// Shell command for `env start`, e.g. "just dev"
+    pub start: Option<String>,
+
+    pub stop: Option<String>,
+
+    /// Stop then start when unset and the runner has no restart target
+    pub restart: Option<String>,
+
+    pub logs: Option<String>,
+
+    pub health: Option<String>,
+}
+
+impl Default for EnvConfig {
+    fn default() -> Self {
+        Self {
+            detect_runners: true,
+            start: None,
+            stop: None,
+            restart: None,
+            logs: None,
+            health: None,
+        }
+    }
+}
+
+/// `[env_cache]`: caches that let a checkout skip reinstalling and rebuilding
+///
+/// Caches live under `dir/<project>`, so every worktree of a project shares
+/// them while other projects don't.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EnvCacheConfig {
+    pub enabled: bool,
+
+    /// Cache root; `~/.autoflow/cache` when unset
+    pub dir: Option<PathBuf>,
+
+    /// Share `node_modules` per lockfile, and the pnpm, npm and yarn stores
+    pub node_modules: bool,
+
+    /// Point `CARGO_TARGET_DIR` at one target directory for all worktrees
+    pub cargo_target: bool,
+
+    /// Build compose services with a BuildKit cache keyed by their inputs
+    pub docker: bool,
+
+    /// Dependency install command; detected from the lockfile when unset
+    pub install_command: Option<String>,
+}
+
+impl Default for EnvCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: None,
+            node_modules: true,
+            cargo_target: true,
+            docker: true,
+            install_command: None,
+        }
+    }
+}
+
+/// `[resources]`: minimum free resources before and during a run (0 disables a check)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceConfig {
+    /// Free space on the project's filesystem
+    pub min_free_disk_mb: u64,
+
+    /// Available memory
+    pub min_available_memory_mb: u64,
+
+    /// Soft limit on open file descriptors (`ulimit -n`)
+    pub min_open_files: u64,
+}
+
+impl Default for ResourceConfig {
+    fn default() -> Self {
+        Self {
+            min_free_disk_mb: 2048,
+            min_available_memory_mb: 1024,
+            min_open_files: 1024,
+        }
+    }
+}
+
+/// `[routing]`: try low-risk agents on a cheaper model, escalate when they fail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RoutingConfig {
+    pub enabled: bool,
+
+    /// Model tried first for the agents below
+    pub cheap_model: String,
+
+    /// Agents routed to the cheap model first
+    pub agents: Vec<String>,
+
+    /// Stop trying the cheap model for an agent once its success rate drops below this (0-1)
+    pub min_success_rate: f64,
+
+    /// Cheap attempts recorded before the success rate is trusted
+    pub min_samples: u32,
+}
+
+impl Default for RoutingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cheap_model: "claude-haiku-4-5".to_string(),
+            agents: vec![
+                "unit-test-runner".to_string(),
+                "e2e-test-runner".to_string(),
+                "integration-test-runner".to_string(),
+                "doc-writer".to_string(),
+            ],
+            min_success_rate: 0.5,
+            min_samples: 4,
+        }
+    }
+}
+
+/// `[policy]`: guardrails applied to every run unless a profile overrides them
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PolicyConfig {
+    /// Ask for confirmation before start, fix, pivot or quick change anything
+    pub require_approval: bool,
+
+    /// Stop a run once its agents have produced this many output tokens (0 = no limit)
+    pub max_run_tokens: u64,
+
+    /// Tools agents may not use, in `--disallowedTools` syntax (e.g. "Bash(git push:*)")
+    pub disallowed_tools: Vec<String>,
+
+    /// Forbid file writes, commits and writing agent tools, as `--read-only` does
+    pub read_only: bool,
+
+    /// Patch mode: `start` runs agents in a shadow checkout and queues each
+    /// phase's changes in `.autoflow/patches` for `autoflow patch apply`
+    pub patches: bool,
+
+    /// Identity recorded on sign-offs, e.g. "Jane Doe <jane@example.com>"; the OS user when unset
+    pub approver: Option<String>,
+}
+
+/// `[artifacts]`: upload each run's summary, events and reports for the team
+///
+/// Uploads go through the `aws` or `gcloud` CLI, which take credentials from
+/// the environment as usual (`AWS_*`, `GOOGLE_APPLICATION_CREDENTIALS`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ArtifactsConfig {
+    /// `s3://bucket/prefix` or `gs://bucket/prefix`; unset disables uploads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+
+    /// Base URL for links when the bucket is served elsewhere (CDN, proxy)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_url: Option<String>,
+
+    /// Report directories uploaded when present, relative to the project root
+    pub paths: Vec<String>,
+}
+
+impl Default for ArtifactsConfig {
+    fn default() -> Self {
+        Self {
+            destination: None,
+            public_url: None,
+            paths: vec!["playwright-report".to_string(), "coverage".to_string()],
+        }
+    }
+}
+
+/// `[forge]`: where issues for blocked sprints are filed and sprint pull requests opened
+///
+/// Issues and pull requests are created with the forge's own CLI (`gh` or
+/// `glab`), so it must be installed and logged in.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ForgeConfig {
+    /// File an issue for a sprint still blocked after the blocker resolver ran
+    pub file_blocked_issues: bool,
+
+    /// Push a sprint worktree's branch and open a pull request when the sprint is DONE
+    pub open_pull_requests: bool,
+
+    /// Open those pull requests as drafts
+    pub draft_pull_requests: bool,
+
+    /// Detected from the `origin` remote when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<ForgeProvider>,
+
+    /// `owner/name` (GitHub) or `group/project` (GitLab); the checkout's own when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+
+    /// Labels for filed issues; they must already exist on the forge
+    pub labels: Vec<String>,
+}
+
+impl Default for ForgeConfig {
+    fn default() -> Self {
+        Self {
+            file_blocked_issues: false,
+            open_pull_requests: false,
+            draft_pull_requests: false,
+            provider: None,
+            repo: None,
+            labels: vec!["autoflow".to_string(), "blocked".to_string()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeProvider {
+    Github,
+    Gitlab,
+}
+
+/// `[notify]`: run completion notifications
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NotifyConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<EmailConfig>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<WebhookConfig>,
+}
+
+/// `[notify.webhook]`: signed JSON posted on run and sprint lifecycle events
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookConfig {
+    pub url: String,
+
+    /// Environment variable holding the HMAC signing secret
+    #[serde(default = "default_webhook_secret_env")]
+    pub secret_env: String,
+
+    /// Events to send, e.g. `["run.finished", "sprint.blocked"]`; empty sends all
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<String>,
+
+    /// Deliveries tried before giving up, with exponential backoff between them
+    #[serde(default = "default_webhook_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Per-attempt request timeout
+    #[serde(default = "default_webhook_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_webhook_secret_env() -> String {
+    "AUTOFLOW_WEBHOOK_SECRET".to_string()
+}
+
+fn default_webhook_max_attempts() -> u32 {
+    5
+}
+
+fn default_webhook_timeout_secs() -> u64 {
+    10
+}
+
+/// `[notify.email]`: digest email sent over SMTP when a run finishes
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmailConfig {
+    /// `smtps://host:465`, or `smtp://host:587` upgraded with STARTTLS
+    pub smtp_url: String,
+
+    pub from: String,
+
+    pub to: Vec<String>,
+
+    /// SMTP login; the password is read from `password_env`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+
+    #[serde(default = "default_password_env")]
+    pub password_env: String,
+
+    /// Refuse to send without TLS; turn off only for a local relay
+    #[serde(default = "default_require_tls")]
+    pub require_tls: bool,
+
+    /// Skip runs shorter than this, so quick commands don't send mail
+    #[serde(default)]
+    pub min_duration_mins: u64,
+}
+
+fn default_password_env() -> String {
+    "AUTOFLOW_SMTP_PASSWORD".to_string()
+}
+
+fn default_require_tls() -> bool {
+    true
+}
+
+/// `[profile.<name>]`: overrides for `[policy]`; unset fields keep the base value
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ProfileConfig {
+    /// Apply automatically on matching branches (`*` matches any run of characters)
+    pub branches: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_approval: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_run_tokens: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disallowed_tools: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub patches: Option<bool>,
+}
+
+/// `[remote."<project>"]`: run the project's agents on another machine over SSH
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RemoteConfig {
+    /// SSH destination, e.g. "dev@buildbox"
+    pub host: String,
+
+    /// Working copy on the remote host
+    pub dir: String,
+
+    /// How the working copy is kept in step: rsync (default) or git
+    #[serde(default)]
+    pub sync: RemoteSync,
+
+    /// Extra `ssh` arguments, e.g. ["-p", "2222"]
+    #[serde(default)]
+    pub ssh_args: Vec<String>,
+
+    /// claude binary on the remote host
+    #[serde(default = "default_remote_claude")]
+    pub claude: String,
+
+    /// Paths rsync leaves alone in both directions (build output, dependencies)
+    #[serde(default = "default_remote_exclude")]
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteSync {
+    /// Mirror the working tree, uncommitted changes included
+    #[default]
+    Rsync,
+    /// Push a snapshot commit of the working tree, uncommitted changes included
+    Git,
+}
+
+fn default_remote_claude() -> String {
+    "claude".to_string()
+}
+
+fn default_remote_exclude() -> Vec<String> {
+    vec!["target/".to_string(), "node_modules/".to_string()]
+}
+
+/// Glob match where `*` stands for any (possibly empty) run of characters
+fn branch_matches(pattern: &str, branch: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == branch,
+        Some((prefix, rest)) => {
+            let Some(tail) = branch.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=tail.len())
+                .filter(|&i| tail.is_char_boundary(i))
+                .any(|i| branch_matches(rest, &tail[i..]))
+        }
+    }
+}
+
+/// Project settings file, relative to the project root
+pub const PROJECT_CONFIG_FILE: &str = ".autoflow/config.yml";
+
+/// `.autoflow/config.yml`: one project's defaults, each overridden by its environment variable
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// Iterations a sprint gets per run (`AUTOFLOW_MAX_ITERATIONS`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_iterations: Option<u32>,
+
+    /// Commit each phase's work (`AUTOFLOW_AUTO_COMMIT`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_commit: Option<bool>,
+
+    /// Model for every agent, over agent definitions and routing (`AUTOFLOW_MODEL`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// Look for a newer AutoFlow at startup (`AUTOFLOW_AUTO_UPDATE`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_check: Option<bool>,
+
+    /// Stream agent output to .autoflow/.debug/live (`AUTOFLOW_LIVE_LOGGING`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub live_logging: Option<bool>,
+}
+
+impl ProjectConfig {
+    /// Settings keys, with the environment variable that takes precedence over each
+    pub const KEYS: [(&'static str, &'static str); 5] = [
+        ("max_iterations", "AUTOFLOW_MAX_ITERATIONS"),
+        ("auto_commit", "AUTOFLOW_AUTO_COMMIT"),
+        ("model", "AUTOFLOW_MODEL"),
+        ("update_check", "AUTOFLOW_AUTO_UPDATE"),
+        ("live_logging", "AUTOFLOW_LIVE_LOGGING"),
+    ];
+
+    /// Settings of the project at `root`; defaults when it has no config.yml
+    pub fn load<P: AsRef<Path>>(root: P) -> Result<Self> {
+        let path = root.as_ref().join(PROJECT_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)?;
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        serde_yaml::from_str(&content)
+            .map_err(|e| crate::AutoFlowError::ConfigError(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Settings of the project in the current directory; defaults when they can't be read
+    pub fn current() -> Self {
+        std::env::current_dir().ok().and_then(|dir| Self::load(dir).ok()).unwrap_or_default()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, root: P) -> Result<()> {
+        crate::ensure_writable("write .autoflow/config.yml")?;
+        let path = root.as_ref().join(PROJECT_CONFIG_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_yaml::to_string(self).map_err(|e| crate::AutoFlowError::ConfigError(e.to_string()))?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Environment variable that overrides `key`
+    pub fn env_var(key: &str) -> Result<&'static str> {
+        Self::KEYS
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, env)| *env)
+            .ok_or_else(|| {
+                let keys: Vec<&str> = Self::KEYS.iter().map(|(k, _)| *k).collect();
+                crate::AutoFlowError::ConfigError(format!("Unknown setting '{}' (expected one of: {})", key, keys.join(", ")))
+            })
+    }
+
+    /// `key`'s value in config.yml, as text
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        Self::env_var(key)?;
+        Ok(match key {
+            "max_iterations" => self.max_iterations.map(|v| v.to_string()),
+            "auto_commit" => self.auto_commit.map(|v| v.to_string()),
+            "model" => self.model.clone(),
+            "update_check" => self.update_check.map(|v| v.to_string()),
+            _ => self.live_logging.map(|v| v.to_string()),
+        })
+    }
+
+    /// Set `key` from text; None clears it back to the default
+    pub fn set(&mut self, key: &str, value: Option<&str>) -> Result<()> {
+        let env = Self::env_var(key)?;
+        let invalid = |expected: &str| {
+            crate::AutoFlowError::ConfigError(format!("Invalid {} '{}' (expected {})", key, value.unwrap_or_default(), expected))
+        };
+        match key {
+            "max_iterations" => {
+                self.max_iterations = value
+                    .map(|v| v.trim().parse::<u32>().ok().filter(|&n| n > 0).ok_or_else(|| invalid("a positive number")))
+                    .transpose()?
+            }
+            "model" => self.model = value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty()),
+            _ => {
+                let flag = value.map(|v| parse_flag(v).ok_or_else(|| invalid("true or false"))).transpose()?;
+                match env {
+                    "AUTOFLOW_AUTO_COMMIT" => self.auto_commit = flag,
+                    "AUTOFLOW_AUTO_UPDATE" => self.update_check = flag,
+                    _ => self.live_logging = flag,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `key`'s effective value and where it came from: the environment variable, else config.yml
+    pub fn resolve(&self, key: &str) -> Result<Option<(String, &'static str)>> {
+        let env = Self::env_var(key)?;
+        if let Some(value) = std::env::var(env).ok().filter(|v| !v.is_empty()) {
+            let mut parsed = Self::default();
+            // An unparseable variable is ignored, like an unset one
+            if parsed.set(key, Some(&value)).is_ok() {
+                return Ok(parsed.get(key)?.map(|v| (v, env)));
+            }
+        }
+        Ok(self.get(key)?.map(|v| (v, PROJECT_CONFIG_FILE)))
+    }
+
+    pub fn max_iterations(&self) -> Option<u32> {
+        self.resolved("max_iterations").and_then(|v| v.parse().ok())
+    }
+
+    pub fn auto_commit(&self) -> Option<bool> {
+        self.resolved("auto_commit").and_then(|v| parse_flag(&v))
+    }
+
+    pub fn model(&self) -> Option<String> {
+        self.resolved("model")
+    }
+
+    pub fn update_check(&self) -> Option<bool> {
+        self.resolved("update_check").and_then(|v| parse_flag(&v))
+    }
+
+    pub fn live_logging(&self) -> Option<bool> {
+        self.resolved("live_logging").and_then(|v| parse_flag(&v))
+    }
+
+    fn resolved(&self, key: &str) -> Option<String> {
+        self.resolve(key).ok().flatten().map(|(value, _)| value)
+    }
+}
+
+/// true/false, also accepting 1/0, yes/no and on/off
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+impl DefaultsConfig {
     /// Get the model to use for a specific agent
-    /// Priority: agent override > global default > env var
+    /// Priority: AUTOFLOW_MODEL > project config.yml > agent override > global default
     pub fn get_model_for_agent(&self, agent_name: &str) -> String {
-        // Check environment variable first
-        if let Ok(env_model) = std::env::var("AUTOFLOW_MODEL") {
-            return env_model;
+        // Check environment variable and project settings first
+        if let Some(model) = ProjectConfig::current().model() {
+            return model;
         }
 
         // Check per-agent override
@@ -81,3 +1543,169 @@ impl DefaultsConfig {
         self.model.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+[autoflow]
+version = "0.1.18"
+install_date = "2025-01-01T00:00:00Z"
+
+[paths]
+agents_dir = "/home/dev/.claude/agents"
+skills_dir = "/home/dev/.claude/skills"
+reference_dir = "/home/dev/.autoflow/reference"
+schemas_dir = "/home/dev/.autoflow/schemas"
+
+[defaults]
+model = "claude-sonnet-4-5-20250929"
+max_iterations = 50
+parallel_sprints = false
+auto_commit = true
+
+[policy]
+max_run_tokens = 500000
+
+[profile.prod]
+branches = ["prod", "release/*"]
+require_approval = true
+disallowed_tools = ["Bash(git push:*)"]
+
+[profile.dev]
+max_run_tokens = 0
+
+[remote."/home/dev/shop"]
+host = "dev@buildbox"
+dir = "~/work/shop"
+ssh_args = ["-p", "2222"]
+
+[remote.blog]
+host = "dev@buildbox"
+dir = "~/work/blog"
+sync = "git"
+
+[backends]
+agents = { reviewer = "openai", "doc-writer" = "gemini", "code-implementer" = "claude", planner = "mistral", "failure-triage" = "ollama" }
+
+[backends.providers.openai]
+kind = "openai"
+model = "gpt-4.1"
+
+[backends.providers.gemini]
+kind = "gemini"
+model = "gemini-2.5-pro"
+api_key_env = "GOOGLE_API_KEY"
+
+[backends.providers.ollama]
+kind = "local"
+model = "qwen2.5-coder:7b"
+
+[sampling]
+default = { temperature = 0.7, top_p = 0.9 }
+agents = { reviewer = { temperature = 0, seed = 7 } }
+"#;
+
+    #[test]
+    fn test_profiles_override_base_policy() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+
+        assert_eq!(config.resolve_profile(None, Some("release/2.1")).unwrap().as_deref(), Some("prod"));
+        assert_eq!(config.resolve_profile(None, Some("main")).unwrap(), None);
+        assert_eq!(config.resolve_profile(Some("dev"), Some("prod")).unwrap().as_deref(), Some("dev"));
+        assert!(config.resolve_profile(Some("staging"), None).is_err());
+
+        let prod = config.policy_for(Some("prod"));
+        assert!(prod.require_approval);
+        assert_eq!(prod.max_run_tokens, 500_000);
+        assert_eq!(prod.disallowed_tools, vec!["Bash(git push:*)"]);
+
+        let dev = config.policy_for(Some("dev"));
+        assert!(!dev.require_approval);
+        assert_eq!(dev.max_run_tokens, 0);
+        assert_eq!(config.policy_for(None), config.policy);
+    }
+
+    #[test]
+    fn test_remote_matches_project_path_or_name() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+
+        let shop = config.remote_for(Path::new("/home/dev/shop")).unwrap();
+        assert_eq!(shop.sync, RemoteSync::Rsync);
+        assert_eq!(shop.claude, "claude");
+        assert_eq!(shop.exclude, vec!["target/", "node_modules/"]);
+
+        let blog = config.remote_for(Path::new("/srv/projects/blog")).unwrap();
+        assert_eq!(blog.sync, RemoteSync::Git);
+        assert!(config.remote_for(Path::new("/home/dev/other")).is_none());
+    }
+
+    #[test]
+    fn test_backends_pick_provider_per_agent() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        let backends = &config.backends;
+
+        let (name, openai) = backends.provider_for("reviewer").unwrap().unwrap();
+        assert_eq!((name, openai.kind, openai.max_tokens), ("openai", ProviderKind::Openai, 8192));
+        let (_, gemini) = backends.provider_for("doc-writer").unwrap().unwrap();
+        assert_eq!(gemini.api_key_env.as_deref(), Some("GOOGLE_API_KEY"));
+        assert!(backends.provider_for("code-implementer").unwrap().is_none());
+        assert!(backends.provider_for("unit-fixer").unwrap().is_none());
+        assert!(backends.provider_for("planner").is_err());
+
+        let all_gemini = BackendsConfig { default: Some("gemini".to_string()), ..backends.clone() };
+        assert_eq!(all_gemini.provider_for("unit-fixer").unwrap().unwrap().0, "gemini");
+        assert!(all_gemini.provider_for("code-implementer").unwrap().is_none());
+
+        // Text tasks need an explicit entry; local models can't call tools unless told they can
+        let (_, ollama) = all_gemini.provider_for_task("failure-triage").unwrap().unwrap();
+        assert!(all_gemini.provider_for_task("summarize").unwrap().is_none());
+        assert!(openai.can_use_tools() && !ollama.can_use_tools());
+        assert!(ProviderConfig { tools: Some(true), ..ollama.clone() }.can_use_tools());
+    }
+
+    #[test]
+    fn test_sampling_agent_overrides_default() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+
+        let reviewer = config.sampling.for_agent("reviewer");
+        assert_eq!(reviewer, SamplingParams { temperature: Some(0.0), top_p: Some(0.9), top_k: None, seed: Some(7) });
+        assert_eq!(reviewer.describe(), "temperature=0 top_p=0.9 seed=7");
+        assert_eq!(config.sampling.for_agent("doc-writer").describe(), "temperature=0.7 top_p=0.9");
+        assert!(SamplingConfig::default().for_agent("reviewer").is_empty());
+        assert!(toml::from_str::<SamplingParams>("temprature = 0").is_err());
+    }
+
+    #[test]
+    fn test_project_config_set_get_and_env_precedence() {
+        let root = std::env::temp_dir().join(format!("autoflow-project-config-test-{}", std::process::id()));
+        assert_eq!(ProjectConfig::load(&root).unwrap(), ProjectConfig::default());
+
+        let mut settings = ProjectConfig::default();
+        settings.set("max_iterations", Some("80")).unwrap();
+        settings.set("auto_commit", Some("off")).unwrap();
+        settings.set("model", Some("claude-opus-4")).unwrap();
+        assert!(settings.set("max_iterations", Some("0")).is_err());
+        assert!(settings.set("live_logging", Some("maybe")).is_err());
+        assert!(settings.set("colour", Some("blue")).is_err());
+        settings.save(&root).unwrap();
+
+        let mut loaded = ProjectConfig::load(&root).unwrap();
+        assert_eq!(loaded, settings);
+        assert_eq!(loaded.get("auto_commit").unwrap().as_deref(), Some("false"));
+        assert_eq!(loaded.get("update_check").unwrap(), None);
+        loaded.set("model", None).unwrap();
+        assert_eq!(loaded.model, None);
+
+        // The environment variable wins; an unparseable one is ignored
+        assert_eq!(settings.resolve("max_iterations").unwrap(), Some(("80".to_string(), PROJECT_CONFIG_FILE)));
+        std::env::set_var("AUTOFLOW_MAX_ITERATIONS", "120");
+        assert_eq!(settings.max_iterations(), Some(120));
+        std::env::set_var("AUTOFLOW_MAX_ITERATIONS", "lots");
+        assert_eq!(settings.max_iterations(), Some(80));
+        std::env::remove_var("AUTOFLOW_MAX_ITERATIONS");
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
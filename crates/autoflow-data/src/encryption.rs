@@ -0,0 +1,360 @@
+// Encryption at rest: designated .autoflow files sealed with ChaCha20-Poly1305
+
+use crate::{AutoFlowError, Config, EncryptionConfig, Result};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Component, Path};
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// First bytes of every encrypted file; also bound into the tag as associated data
+pub const ENCRYPTED_MAGIC: &[u8] = b"AUTOFLOW-ENC1\n";
+
+/// First bytes of each record of an encrypted append-only file; see [`append_state`]
+///
+/// A record is this magic, the big-endian u32 length of what follows, a
+/// random nonce, and the ciphertext and tag of one appended line.
+pub const ENCRYPTED_RECORD_MAGIC: &[u8] = b"AUTOFLOW-ENCR1\n";
+
+/// Keyring account the key is stored under
+pub const KEYRING_ACCOUNT: &str = "encryption-key";
+
+/// True when `data` was written by [`encrypt`] or [`encrypt_record`]
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(ENCRYPTED_MAGIC) || data.starts_with(ENCRYPTED_RECORD_MAGIC)
+}
+
+/// Seal `plaintext` under `key`: magic, random nonce, ciphertext and tag
+pub fn encrypt(key: &[u8; 32], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let (nonce, sealed) = seal(key, ENCRYPTED_MAGIC, plaintext)?;
+    let mut out = Vec::with_capacity(ENCRYPTED_MAGIC.len() + NONCE_LEN + sealed.len());
+    out.extend_from_slice(ENCRYPTED_MAGIC);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+/// Seal `plaintext` as one record of an append-only file
+///
+/// Records are self-delimiting, so a file of them grows by plain appends.
+pub fn encrypt_record(key: &[u8; 32], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let (nonce, sealed) = seal(key, ENCRYPTED_RECORD_MAGIC, plaintext)?;
+    let len = u32::try_from(NONCE_LEN + sealed.len())
+        .map_err(|_| AutoFlowError::EncryptionError("record too large".to_string()))?;
+    let mut out = Vec::with_capacity(ENCRYPTED_RECORD_MAGIC.len() + 4 + NONCE_LEN + sealed.len());
+    out.extend_from_slice(ENCRYPTED_RECORD_MAGIC);
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+fn seal(key: &[u8; 32], magic: &[u8], plaintext: &[u8]) -> Result<([u8; NONCE_LEN], Vec<u8>)> {
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| AutoFlowError::EncryptionError("no randomness for a nonce".to_string()))?;
+    let mut sealed = plaintext.to_vec();
+    aead_key(key)
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(magic), &mut sealed)
+        .map_err(|_| AutoFlowError::EncryptionError("encryption failed".to_string()))?;
+    Ok((nonce, sealed))
+}
+
+/// Open data written by [`encrypt`], or a run of [`encrypt_record`] records
+///
+/// Fails on a wrong key, tampered data or a torn record.
+pub fn decrypt(key: &[u8; 32], data: &[u8]) -> Result<Vec<u8>> {
+    if let Some(body) = data.strip_prefix(ENCRYPTED_MAGIC) {
+        return open(key, ENCRYPTED_MAGIC, body);
+    }
+    if !data.starts_with(ENCRYPTED_RECORD_MAGIC) {
+        return Err(AutoFlowError::EncryptionError("not an AutoFlow encrypted file".to_string()));
+    }
+    let torn = || AutoFlowError::EncryptionError("truncated encrypted record".to_string());
+    let mut plaintext = Vec::new();
+    let mut rest = data;
+    while !rest.is_empty() {
+        let header = rest.strip_prefix(ENCRYPTED_RECORD_MAGIC).ok_or_else(torn)?;
+        let len = header.get(..4).ok_or_else(torn)?;
+        let len = u32::from_be_bytes(len.try_into().expect("4 bytes")) as usize;
+        let body = header.get(4..4 + len).ok_or_else(torn)?;
+        plaintext.extend_from_slice(&open(key, ENCRYPTED_RECORD_MAGIC, body)?);
+        rest = &header[4 + len..];
+    }
+    Ok(plaintext)
+}
+
+/// Open a nonce followed by ciphertext and tag, sealed with `magic` as associated data
+fn open(key: &[u8; 32], magic: &[u8], body: &[u8]) -> Result<Vec<u8>> {
+    if body.len() < NONCE_LEN {
+        return Err(AutoFlowError::EncryptionError("not an AutoFlow encrypted file".to_string()));
+    }
+    let (nonce, sealed) = body.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce)
+        .map_err(|_| AutoFlowError::EncryptionError("bad nonce".to_string()))?;
+    let mut opened = sealed.to_vec();
+    let plaintext = aead_key(key)
+        .open_in_place(nonce, Aad::from(magic), &mut opened)
+        .map_err(|_| AutoFlowError::EncryptionError("wrong key or corrupted file".to_string()))?;
+    Ok(plaintext.to_vec())
+}
+
+fn aead_key(key: &[u8; 32]) -> LessSafeKey {
+    LessSafeKey::new(UnboundKey::new(&CHACHA20_POLY1305, key).expect("ChaCha20 keys are 32 bytes"))
+}
+
+/// Key from its hex form (64 characters; surrounding whitespace ignored)
+pub fn parse_key(text: &str) -> Result<[u8; 32]> {
+    let text = text.trim();
+    let invalid = || AutoFlowError::EncryptionError("the key must be 64 hex characters (32 bytes)".to_string());
+    if text.len() != 64 || !text.is_ascii() {
+        return Err(invalid());
+    }
+    let mut key = [0u8; 32];
+    for (i, byte) in key.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&text[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+    }
+    Ok(key)
+}
+
+/// Whether `path` is designated for encryption by `config.files`
+///
+/// Patterns are matched against the path below its `.autoflow` directory, so
+/// the same pattern covers the project and its worktrees.
+pub fn should_encrypt(config: &EncryptionConfig, path: &Path) -> bool {
+    let components: Vec<Component> = path.components().collect();
+    let Some(start) = components.iter().rposition(|c| c.as_os_str() == ".autoflow") else {
+        return false;
+    };
+    let relative: Vec<char> = components[start + 1..]
+        .iter()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+        .chars()
+        .collect();
+    config.files.iter().any(|pattern| {
+        let pattern: Vec<char> = pattern.trim_start_matches("./").chars().collect();
+        crate::ignore_rules::glob_match(&pattern, &relative)
+    })
+}
+
+/// `[encryption]` from the global config, loaded once per process
+fn active_config() -> &'static EncryptionConfig {
+    static CONFIG: OnceLock<EncryptionConfig> = OnceLock::new();
+    CONFIG.get_or_init(|| Config::global().map(|c| c.encryption).unwrap_or_default())
+}
+
+/// The key: `key_env` if set, else the OS keyring; looked up once per process
+pub fn encryption_key(config: &EncryptionConfig) -> Result<[u8; 32]> {
+    static KEY: OnceLock<[u8; 32]> = OnceLock::new();
+    if let Some(key) = KEY.get() {
+        return Ok(*key);
+    }
+    let key = match std::env::var(&config.key_env).ok().filter(|v| !v.trim().is_empty()) {
+        Some(value) => parse_key(&value)?,
+        None => match &config.keyring_service {
+            Some(service) => parse_key(&keyring_lookup(service)?)?,
+            None => {
+                return Err(AutoFlowError::EncryptionError(format!(
+                    "no key: {} is not set and no keyring_service is configured",
+                    config.key_env
+                )))
+            }
+        },
+    };
+    Ok(*KEY.get_or_init(|| key))
+}
+
+/// Read the key from the OS keyring through its command-line tool
+fn keyring_lookup(service: &str) -> Result<String> {
+    let output = if cfg!(target_os = "macos") {
+        Command::new("security")
+            .args(["find-generic-password", "-s", service, "-a", KEYRING_ACCOUNT, "-w"])
+            .output()
+    } else {
+        Command::new("secret-tool")
+            .args(["lookup", "service", service, "account", KEYRING_ACCOUNT])
+            .output()
+    }
+    .map_err(|e| AutoFlowError::EncryptionError(format!("keyring unavailable: {}", e)))?;
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(AutoFlowError::EncryptionError(format!(
+            "no key for service '{}' in the keyring",
+            service
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Read a state file, decrypting it when it's encrypted
+pub fn read_state<P: AsRef<Path>>(path: P) -> Result<Vec<u8>> {
+    let data = fs::read(path)?;
+    if !is_encrypted(&data) {
+        return Ok(data);
+    }
+    decrypt(&encryption_key(active_config())?, &data)
+}
+
+pub fn read_state_to_string<P: AsRef<Path>>(path: P) -> Result<String> {
+    String::from_utf8(read_state(path)?)
+        .map_err(|_| AutoFlowError::EncryptionError("decrypted file is not UTF-8".to_string()))
+}
+
+/// Write a state file, encrypted when `[encryption] files` designates it
+///
+/// Without a key a designated file is not written at all rather than left
+/// in plaintext.
+pub fn write_state<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> Result<()> {
+    let path = path.as_ref();
+    let config = active_config();
+    if !should_encrypt(config, path) {
+        fs::write(path, contents)?;
+        return Ok(());
+    }
+    let sealed = encrypt(&encryption_key(config)?, contents.as_ref())?;
+    // Written aside and renamed so a crash never leaves a half-sealed file
+    let temp = path.with_extension("enc-tmp");
+    fs::write(&temp, sealed)?;
+    fs::rename(&temp, path)?;
+    Ok(())
+}
+
+/// Append `line` and a newline to a state file
+///
+/// The file is locked for the append, so concurrent appenders (parallel
+/// sprints, or a run log and its state) never lose lines. Encrypted or
+/// designated files get the line as one more sealed record, so an append
+/// stays an append. A file sealed as a whole, or a designated one still in
+/// plaintext, is converted to records first and replaced atomically.
+pub fn append_state<P: AsRef<Path>>(path: P, line: &str) -> Result<()> {
+    let path = path.as_ref();
+    let config = active_config();
+    let mut record = line.as_bytes().to_vec();
+    record.push(b'\n');
+
+    // Twice at most: a concurrent conversion may replace the file while we wait for its lock
+    for _ in 0..2 {
+        let mut file = OpenOptions::new().read(true).append(true).create(true).open(path)?;
+        file.lock()?;
+        if !is_same_file(&file, path) {
+            continue;
+        }
+
+        let mut head = Vec::with_capacity(ENCRYPTED_RECORD_MAGIC.len());
+        (&mut file).take(ENCRYPTED_RECORD_MAGIC.len() as u64).read_to_end(&mut head)?;
+        let records = head.starts_with(ENCRYPTED_RECORD_MAGIC);
+        if !records && !head.starts_with(ENCRYPTED_MAGIC) && !should_encrypt(config, path) {
+            file.write_all(&record)?;
+            return Ok(());
+        }
+
+        let key = encryption_key(config)?;
+        if records || head.is_empty() {
+            file.write_all(&encrypt_record(&key, &record)?)?;
+            return Ok(());
+        }
+
+        // Sealed as a whole or still plaintext: rewrite as records, keeping the lock until the rename
+        let mut existing = Vec::new();
+        File::open(path)?.read_to_end(&mut existing)?;
+        let mut contents = if is_encrypted(&existing) { decrypt(&key, &existing)? } else { existing };
+        contents.extend_from_slice(&record);
+        let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let temp = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+        fs::write(&temp, encrypt_record(&key, &contents)?)?;
+        fs::rename(&temp, path)?;
+        return Ok(());
+    }
+    Err(AutoFlowError::EncryptionError(format!("{} kept changing while appending", path.display())))
+}
+
+/// True when `path` still names the file `file` has open
+#[cfg(unix)]
+fn is_same_file(file: &File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (file.metadata(), fs::metadata(path)) {
+        (Ok(open), Ok(named)) => open.dev() == named.dev() && open.ino() == named.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_same_file(_file: &File, _path: &Path) -> bool {
+    // A file open here can't be renamed over on Windows, so it is still the one at the path
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_round_trip() {
+        let key = parse_key(&"0f".repeat(32)).unwrap();
+        let sealed = encrypt(&key, b"{\"run_id\":\"01J9Z\"}\n").unwrap();
+        assert!(is_encrypted(&sealed));
+        assert!(!sealed.windows(6).any(|w| w == b"run_id"));
+        assert_ne!(sealed, encrypt(&key, b"{\"run_id\":\"01J9Z\"}\n").unwrap());
+        assert_eq!(decrypt(&key, &sealed).unwrap(), b"{\"run_id\":\"01J9Z\"}\n");
+
+        let other = parse_key(&"a1".repeat(32)).unwrap();
+        assert!(decrypt(&other, &sealed).is_err());
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(decrypt(&key, &tampered).is_err());
+        assert!(parse_key("abc").is_err());
+        assert!(parse_key(&"zz".repeat(32)).is_err());
+
+        let config = EncryptionConfig {
+            files: vec!["runs.jsonl".to_string(), "runs/*/summary.json".to_string()],
+            ..Default::default()
+        };
+        assert!(should_encrypt(&config, Path::new(".autoflow/runs.jsonl")));
+        assert!(should_encrypt(&config, Path::new("/work/app/.autoflow/runs/01J9Z/summary.json")));
+        assert!(!should_encrypt(&config, Path::new(".autoflow/routing.json")));
+        assert!(!should_encrypt(&config, Path::new("runs.jsonl")));
+    }
+
+    #[test]
+    fn test_encrypted_appends_are_records_and_keep_every_line() {
+        let key = parse_key(&"3c".repeat(32)).unwrap();
+        std::env::set_var("AUTOFLOW_ENCRYPTION_KEY", "3c".repeat(32));
+
+        let mut records = encrypt_record(&key, b"one\n").unwrap();
+        records.extend(encrypt_record(&key, b"two\n").unwrap());
+        assert_eq!(decrypt(&key, &records).unwrap(), b"one\ntwo\n");
+        assert!(decrypt(&key, &records[..records.len() - 1]).is_err());
+
+        let dir = std::env::temp_dir().join(format!("autoflow-append-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        // A file sealed as a whole is converted to records on the first append
+        let path = dir.join("runs.jsonl");
+        fs::write(&path, encrypt(&key, b"first\n").unwrap()).unwrap();
+        append_state(&path, "second").unwrap();
+        assert!(fs::read(&path).unwrap().starts_with(ENCRYPTED_RECORD_MAGIC));
+
+        // Concurrent appenders all land, each as a single record
+        std::thread::scope(|scope| {
+            for t in 0..8 {
+                let path = &path;
+                scope.spawn(move || {
+                    for i in 0..25 {
+                        append_state(path, &format!("{}-{}", t, i)).unwrap();
+                    }
+                });
+            }
+        });
+        let text = read_state_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 202);
+        assert_eq!(&lines[..2], ["first", "second"]);
+        assert!(lines.contains(&"7-24"));
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -1,3 +1,4 @@
+use serde::Serialize;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, AutoFlowError>;
@@ -16,15 +17,57 @@ pub enum AutoFlowError {
     #[error("Git operation failed: {0}")]
     GitError(#[from] git2::Error),
 
+    #[error("{0}")]
+    GitCommandFailed(String),
+
+    #[error("Invalid configuration: {0}")]
+    ConfigError(String),
+
+    #[error("{0}")]
+    ClaudeCliUnavailable(String),
+
+    #[error("Agent '{0}' not found")]
+    AgentNotFound(String),
+
+    #[error("{0}")]
+    CommandFailed(String),
+
     #[error("Agent execution failed: {0}")]
     AgentError(String),
 
     #[error("Agent '{0}' execution failed: {1}")]
     AgentExecutionFailed(String, String),
 
+    #[error("Network unavailable: gave up waiting after {waited_secs}s")]
+    NetworkUnavailable { waited_secs: u64 },
+
+    #[error("Agent '{agent}' speaks completion protocol v{declared}, this AutoFlow supports v{supported}")]
+    AgentProtocolMismatch { agent: String, declared: u32, supported: u32 },
+
+    #[error("Encrypted state: {0}")]
+    EncryptionError(String),
+
     #[error("Sprint {0} is blocked: {1}")]
     SprintBlocked(u32, String),
 
+    #[error("Sprint {sprint} paused: preempted by critical sprint {by}")]
+    SprintPreempted { sprint: u32, by: u32 },
+
+    #[error("Token budget exhausted: {used} of {limit} output tokens used this run")]
+    BudgetExceeded { used: u64, limit: u64 },
+
+    #[error("Deployment of sprint {0} needs approval: run it interactively or set AUTOFLOW_APPROVE=1")]
+    DeploymentNotApproved(u32),
+
+    #[error("Infrastructure changes for sprint {0} need approval before apply: run it interactively or set AUTOFLOW_APPROVE=1")]
+    ApplyNotApproved(u32),
+
+    #[error("Read-only mode: {0} is not allowed")]
+    ReadOnly(String),
+
+    #[error("Sprint {sprint} deployment failed smoke checks and was rolled back{}", to.as_ref().map(|r| format!(" to {}", r)).unwrap_or_default())]
+    DeploymentRolledBack { sprint: u32, to: Option<String> },
+
     #[error("Maximum iterations ({0}) reached")]
     MaxIterationsExceeded(u32),
 
@@ -34,6 +77,12 @@ pub enum AutoFlowError {
     #[error("Project not initialized. Run 'autoflow init' first.")]
     NotInitialized,
 
+    #[error("No home directory: HOME is not set")]
+    HomeNotFound,
+
+    #[error("Project directory not found: {0}")]
+    ProjectNotFound(String),
+
     #[error("Invalid dependency: {0}")]
     InvalidDependency(String),
 
@@ -49,6 +98,9 @@ pub enum AutoFlowError {
         failing_tests: Vec<String>,
     },
 
+    #[error("Sprint {sprint} was sent back to {phase}, which already completed; running it again would repeat finished work")]
+    PhaseAlreadyCompleted { sprint: u32, phase: String },
+
     #[error("Infrastructure not ready: {issues:?}")]
     InfrastructureNotReady { issues: Vec<String> },
 
@@ -85,3 +137,194 @@ pub enum AutoFlowError {
     #[error("Invalid component: {0}")]
     InvalidComponent(String),
 }
+
+/// Broad class of an error; decides the CLI's exit code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorCategory {
+    /// AutoFlow, the Claude CLI or the project isn't set up
+    Setup,
+    /// `~/.autoflow/config.toml`, a command-line value or the encryption key is wrong
+    Config,
+    /// SPRINTS.yml, templates or imports don't parse or validate
+    Plan,
+    /// An agent couldn't run or failed
+    Agent,
+    Git,
+    /// A sprint stopped: blocked, preempted or out of iterations
+    Workflow,
+    /// A guardrail stopped the run: budget, approval or read-only mode
+    Policy,
+    /// Docker, services, deployment or an external tool
+    Infrastructure,
+    Io,
+    /// Anything not raised as an AutoFlowError
+    Internal,
+}
+
+impl ErrorCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Setup => "setup",
+            Self::Config => "config",
+            Self::Plan => "plan",
+            Self::Agent => "agent",
+            Self::Git => "git",
+            Self::Workflow => "workflow",
+            Self::Policy => "policy",
+            Self::Infrastructure => "infrastructure",
+            Self::Io => "io",
+            Self::Internal => "internal",
+        }
+    }
+
+    /// Process exit code for a command failing with this category
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Internal => 1,
+            Self::Setup => 3,
+            Self::Config => 4,
+            Self::Plan => 5,
+            Self::Agent => 6,
+            Self::Git => 7,
+            Self::Workflow => 8,
+            Self::Policy => 9,
+            Self::Infrastructure => 10,
+            Self::Io => 11,
+        }
+    }
+}
+
+impl AutoFlowError {
+    /// Stable code (`AF` + category digit + number), documented in TROUBLESHOOTING.md
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::ClaudeCliUnavailable(_) => "AF101",
+            Self::NotInitialized => "AF102",
+            Self::NoBashInstallation => "AF103",
+            Self::HomeNotFound => "AF104",
+            Self::ProjectNotFound(_) => "AF105",
+            Self::ConfigError(_) => "AF201",
+            Self::InvalidComponent(_) => "AF202",
+            Self::EncryptionError(_) => "AF203",
+            Self::SprintsParseError(_) => "AF301",
+            Self::JsonParseError(_) => "AF302",
+            Self::ValidationError(_) => "AF303",
+            Self::InvalidDependency(_) => "AF304",
+            Self::MissingDependency { .. } => "AF305",
+            Self::AgentError(_) => "AF401",
+            Self::AgentExecutionFailed(..) => "AF402",
+            Self::AgentNotFound(_) => "AF403",
+            Self::NetworkUnavailable { .. } => "AF404",
+            Self::AgentProtocolMismatch { .. } => "AF405",
+            Self::GitError(_) => "AF501",
+            Self::GitCommandFailed(_) => "AF502",
+            Self::MergeConflict { .. } => "AF503",
+            Self::SprintBlocked(..) => "AF601",
+            Self::SprintPreempted { .. } => "AF602",
+            Self::MaxIterationsExceeded(_) => "AF603",
+            Self::BugNotFixed { .. } => "AF604",
+            Self::RegressionDetected { .. } => "AF605",
+            Self::PhaseAlreadyCompleted { .. } => "AF606",
+            Self::BudgetExceeded { .. } => "AF701",
+            Self::DeploymentNotApproved(_) => "AF702",
+            Self::ApplyNotApproved(_) => "AF703",
+            Self::ReadOnly(_) => "AF704",
+            Self::DeploymentRolledBack { .. } => "AF801",
+            Self::InfrastructureNotReady { .. } => "AF802",
+            Self::DockerBuildFailed => "AF803",
+            Self::DockerStartFailed => "AF804",
+            Self::ServiceStartTimeout => "AF805",
+            Self::DatabaseConnectionFailed => "AF806",
+            Self::RedisConnectionFailed => "AF807",
+            Self::AppHealthCheckFailed => "AF808",
+            Self::MigrationFailed => "AF809",
+            Self::NoBackupsFound => "AF810",
+            Self::CommandFailed(_) => "AF811",
+            Self::IoError(_) => "AF901",
+        }
+    }
+
+    pub fn category(&self) -> ErrorCategory {
+        match self.code().as_bytes()[2] {
+            b'1' => ErrorCategory::Setup,
+            b'2' => ErrorCategory::Config,
+            b'3' => ErrorCategory::Plan,
+            b'4' => ErrorCategory::Agent,
+            b'5' => ErrorCategory::Git,
+            b'6' => ErrorCategory::Workflow,
+            b'7' => ErrorCategory::Policy,
+            b'8' => ErrorCategory::Infrastructure,
+            _ => ErrorCategory::Io,
+        }
+    }
+
+    /// What the user can do about it, when there's something specific
+    pub fn hint(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::ClaudeCliUnavailable(_) => "Run `autoflow doctor` to check prerequisites",
+            Self::NotInitialized => "Run `autoflow init` in the project directory",
+            Self::HomeNotFound => "Set HOME, or pass --home <dir> (AUTOFLOW_HOME)",
+            Self::ProjectNotFound(_) => "Pass --project an existing directory, or omit it to use the current one",
+            Self::ConfigError(_) => "Check ~/.autoflow/config.toml",
+            Self::EncryptionError(_) => "Set the key from [encryption] key_env, or store it in the OS keyring",
+            Self::SprintsParseError(_) | Self::ValidationError(_) => "Run `autoflow validate --fix`",
+            Self::InvalidDependency(_) | Self::MissingDependency { .. } => {
+                "Check the sprint's dependencies in .autoflow/SPRINTS.yml"
+            }
+            Self::AgentError(_) | Self::AgentExecutionFailed(..) => "See the agent's output with `autoflow logs`",
+            Self::AgentNotFound(_) => "Run `autoflow install --force` to reinstall the agents",
+            Self::NetworkUnavailable { .. } => "Reconnect and resume with `autoflow start`, or raise [network] max_wait_secs",
+            Self::AgentProtocolMismatch { .. } => {
+                "Run `autoflow install --force` to reinstall matching agents, or update the template's `protocol:` line"
+            }
+            Self::MergeConflict { .. } => "Resolve the conflict in the worktree, commit, then retry",
+            Self::SprintBlocked(..) => "Work through it with `autoflow resolve <id>`, or read .autoflow/.failures and `autoflow rollback --sprint <id>`",
+            Self::PhaseAlreadyCompleted { .. } => {
+                "Restore the sprint's status, start it over with `autoflow rollback --sprint <id>`, or pass `--force-redo`"
+            }
+            Self::SprintPreempted { .. } => "Run `autoflow start` again once the critical sprint is done",
+            Self::BudgetExceeded { .. } => "Raise [policy] max_run_tokens or resume with `autoflow start`",
+            Self::DeploymentNotApproved(_) | Self::ApplyNotApproved(_) => {
+                "Run interactively to approve, or set AUTOFLOW_APPROVE=1"
+            }
+            Self::ReadOnly(_) => "Only analyze, status, validate and start --dry-run run in read-only mode",
+            Self::InfrastructureNotReady { .. }
+            | Self::DockerBuildFailed
+            | Self::DockerStartFailed
+            | Self::ServiceStartTimeout
+            | Self::DatabaseConnectionFailed
+            | Self::RedisConnectionFailed
+            | Self::AppHealthCheckFailed => "Check the environment with `autoflow env health`",
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_match_categories() {
+        let errors = [
+            AutoFlowError::NotInitialized,
+            AutoFlowError::ConfigError("x".to_string()),
+            AutoFlowError::ValidationError("x".to_string()),
+            AutoFlowError::AgentNotFound("x".to_string()),
+            AutoFlowError::GitCommandFailed("x".to_string()),
+            AutoFlowError::SprintBlocked(1, "x".to_string()),
+            AutoFlowError::BudgetExceeded { used: 2, limit: 1 },
+            AutoFlowError::DockerBuildFailed,
+            AutoFlowError::IoError(std::io::Error::other("x")),
+        ];
+        let categories: Vec<&str> = errors.iter().map(|e| e.category().as_str()).collect();
+        assert_eq!(
+            categories,
+            vec!["setup", "config", "plan", "agent", "git", "workflow", "policy", "infrastructure", "io"]
+        );
+        assert_eq!(AutoFlowError::BudgetExceeded { used: 2, limit: 1 }.category().exit_code(), 9);
+        assert_eq!(AutoFlowError::ReadOnly("git commit".to_string()).category().exit_code(), 9);
+        assert!(AutoFlowError::NotInitialized.hint().unwrap().contains("autoflow init"));
+    }
+}
@@ -0,0 +1,193 @@
+// .autoflowignore: paths AutoFlow leaves alone when analyzing, diffing and committing
+
+use std::path::Path;
+
+/// Ignore file at the project root, in gitignore syntax
+pub const AUTOFLOWIGNORE: &str = ".autoflowignore";
+
+/// One line of an ignore file
+#[derive(Debug, Clone, PartialEq)]
+struct IgnoreRule {
+    pattern: Vec<char>,
+    /// `!pattern`: re-include what an earlier rule ignored
+    negated: bool,
+    /// `pattern/`: only matches directories
+    dir_only: bool,
+    /// Contains a `/`: matched against the whole path rather than any name in it
+    anchored: bool,
+}
+
+/// Rules from `.autoflowignore`
+///
+/// Supports the gitignore syntax people actually use: comments, `!` negation,
+/// trailing `/` for directories, leading or inner `/` to anchor at the root,
+/// and `*`, `?`, `[...]` and `**` globs. As in git, nothing inside an ignored
+/// directory can be re-included.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IgnoreRules {
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreRules {
+    /// Rules for the project at `root`; empty when it has no `.autoflowignore`
+    pub fn load<P: AsRef<Path>>(root: P) -> Self {
+        std::fs::read_to_string(root.as_ref().join(AUTOFLOWIGNORE))
+            .map(|content| Self::parse(&content))
+            .unwrap_or_default()
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let (negated, line) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, line.strip_prefix('\\').unwrap_or(line)),
+                };
+                let (dir_only, line) = match line.strip_suffix('/') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let anchored = line.contains('/');
+                let line = line.trim_start_matches('/');
+                (!line.is_empty()).then(|| IgnoreRule { pattern: line.chars().collect(), negated, dir_only, anchored })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// True when `path` (relative to the project root) or a directory above it is ignored
+    pub fn is_ignored(&self, path: &str, is_dir: bool) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+        let path = path.trim_start_matches("./").trim_end_matches('/');
+        let parts: Vec<&str> = path.split('/').collect();
+        (1..parts.len()).any(|n| self.matches(&parts[..n].join("/"), true)) || self.matches(path, is_dir)
+    }
+
+    /// Drop ignored files from a list of paths
+    pub fn filter(&self, paths: Vec<String>) -> Vec<String> {
+        if self.rules.is_empty() {
+            return paths;
+        }
+        paths.into_iter().filter(|p| !self.is_ignored(p, false)).collect()
+    }
+
+    /// The last rule matching this exact path decides
+    fn matches(&self, path: &str, is_dir: bool) -> bool {
+        let full: Vec<char> = path.chars().collect();
+        let name: Vec<char> = path.rsplit('/').next().unwrap_or(path).chars().collect();
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+            let subject = if rule.anchored { &full } else { &name };
+            if glob_match(&rule.pattern, subject) {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+}
+
+/// Match a gitignore-style glob; `*` and `?` stop at `/`, `**` doesn't
+pub fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        ['*', '*', rest @ ..] => {
+            let rest = match rest {
+                ['/', after @ ..] => after,
+                _ => rest,
+            };
+            if rest.is_empty() {
+                return true;
+            }
+            // Zero or more leading directories
+            glob_match(rest, text)
+                || text.iter().enumerate().any(|(i, &c)| c == '/' && glob_match(rest, &text[i + 1..]))
+        }
+        ['*', rest @ ..] => {
+            let segment = text.iter().position(|&c| c == '/').unwrap_or(text.len());
+            (0..=segment).any(|i| glob_match(rest, &text[i..]))
+        }
+        ['?', rest @ ..] => matches!(text, [c, ..] if *c != '/') && glob_match(rest, &text[1..]),
+        ['[', rest @ ..] => match (text.first(), rest.iter().position(|&c| c == ']')) {
+            (Some(&c), Some(end)) if c != '/' => class_matches(&rest[..end], c) && glob_match(&rest[end + 1..], &text[1..]),
+            (Some(&c), None) => c == '[' && glob_match(rest, &text[1..]),
+            _ => false,
+        },
+        [p, rest @ ..] => matches!(text, [c, ..] if c == p) && glob_match(rest, &text[1..]),
+    }
+}
+
+/// `[abc]`, `[a-z]`, `[!abc]` / `[^abc]`
+fn class_matches(class: &[char], c: char) -> bool {
+    let (negated, class) = match class {
+        ['!' | '^', rest @ ..] => (true, rest),
+        _ => (false, class),
+    };
+    let mut found = false;
+    let mut i = 0;
+    while i < class.len() {
+        if i + 2 < class.len() && class[i + 1] == '-' {
+            found |= (class[i]..=class[i + 2]).contains(&c);
+            i += 3;
+        } else {
+            found |= class[i] == c;
+            i += 1;
+        }
+    }
+    found != negated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignore_rules() {
+        let rules = IgnoreRules::parse(
+            "# Vendored and generated code\n\
+             node_modules/\n\
+             /fixtures\n\
+             src/generated/**\n\
+             *.min.js\n\
+             !keep.min.js\n\
+             docs/**/*.pdf\n\
+             report-[0-9].txt\n",
+        );
+
+        assert!(rules.is_ignored("node_modules", true));
+        assert!(rules.is_ignored("web/node_modules/react/index.js", false));
+        assert!(!rules.is_ignored("node_modules", false));
+        assert!(rules.is_ignored("fixtures/users.json", false));
+        assert!(!rules.is_ignored("tests/fixtures/users.json", false));
+        assert!(rules.is_ignored("src/generated/api/client.ts", false));
+        assert!(!rules.is_ignored("src/generated.ts", false));
+        assert!(rules.is_ignored("public/app.min.js", false));
+        assert!(!rules.is_ignored("public/keep.min.js", false));
+        assert!(rules.is_ignored("docs/manual.pdf", false));
+        assert!(rules.is_ignored("docs/a/b/manual.pdf", false));
+        assert!(rules.is_ignored("report-7.txt", false));
+        assert!(!rules.is_ignored("report-x.txt", false));
+        assert!(!rules.is_ignored("src/main.rs", false));
+
+        // Nothing inside an ignored directory comes back
+        let reinclude = IgnoreRules::parse("vendor/\n!vendor/patched.rb\n");
+        assert!(reinclude.is_ignored("vendor/patched.rb", false));
+
+        assert_eq!(
+            rules.filter(vec!["src/main.rs".to_string(), "fixtures/big.sql".to_string()]),
+            vec!["src/main.rs".to_string()]
+        );
+        assert!(IgnoreRules::parse("# nothing\n\n").is_empty());
+    }
+}
@@ -0,0 +1,546 @@
+// Import/export of sprint plans in formats that can be edited outside AutoFlow
+
+use crate::sprints::{ProjectMetadata, Sprint, SprintStatus, SprintsYaml, WorkflowType};
+use crate::tasks::{Priority, Task, TaskStatus, TaskType, TestingRequirements};
+use crate::{AutoFlowError, Result};
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Supported interchange formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanFormat {
+    Json,
+    Csv,
+    Markdown,
+    Yaml,
+}
+
+impl PlanFormat {
+    /// Guess the format from a file extension
+    pub fn from_path(path: &str) -> Option<Self> {
+        let ext = path.rsplit('.').next()?.to_lowercase();
+        match ext.as_str() {
+            "json" => Some(Self::Json),
+            "csv" => Some(Self::Csv),
+            "md" | "markdown" => Some(Self::Markdown),
+            "yml" | "yaml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for PlanFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            "md" | "markdown" => Ok(Self::Markdown),
+            "yml" | "yaml" => Ok(Self::Yaml),
+            other => Err(format!("Unknown format '{}' (expected json, csv, md or yaml)", other)),
+        }
+    }
+}
+
+/// How to handle imported sprints whose ID already exists in the plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictStrategy {
+    /// Abort the import
+    Fail,
+    /// Overwrite the existing sprint (fields missing from CSV are kept)
+    Replace,
+    /// Keep the existing sprint and drop the imported one
+    Skip,
+    /// Give the imported sprint a fresh ID and remap dependencies on it
+    Renumber,
+}
+
+impl std::str::FromStr for ConflictStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "fail" => Ok(Self::Fail),
+            "replace" => Ok(Self::Replace),
+            "skip" => Ok(Self::Skip),
+            "renumber" => Ok(Self::Renumber),
+            other => Err(format!(
+                "Unknown conflict strategy '{}' (expected fail, replace, skip or renumber)",
+                other
+            )),
+        }
+    }
+}
+
+/// What an import did, for reporting
+#[derive(Debug, Default, Clone)]
+pub struct ImportSummary {
+    pub added: Vec<u32>,
+    pub replaced: Vec<u32>,
+    pub skipped: Vec<u32>,
+    /// (imported ID, assigned ID)
+    pub renumbered: Vec<(u32, u32)>,
+}
+
+/// One spreadsheet row: a task plus the sprint columns it belongs to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CsvRow {
+    sprint_id: u32,
+    sprint_goal: String,
+    sprint_status: String,
+    workflow_type: String,
+    total_effort: String,
+    max_effort: String,
+    dependencies: String,
+    must_complete_first: bool,
+    task_id: String,
+    task_title: String,
+    task_type: String,
+    task_status: String,
+    priority: String,
+    effort: String,
+    feature: String,
+    description: String,
+    acceptance_criteria: String,
+}
+
+/// Separator used for list values inside a single CSV cell
+const LIST_SEPARATOR: &str = "; ";
+
+/// Serialize the whole plan as pretty JSON (lossless)
+pub fn export_json(plan: &SprintsYaml) -> Result<String> {
+    Ok(serde_json::to_string_pretty(plan)?)
+}
+
+/// Serialize the plan as CSV, one row per task (sprints without tasks get one row)
+pub fn export_csv(plan: &SprintsYaml) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for sprint in &plan.sprints {
+        let base = CsvRow {
+            sprint_id: sprint.id,
+            sprint_goal: sprint.goal.clone(),
+            sprint_status: enum_name(&sprint.status),
+            workflow_type: enum_name(&sprint.workflow_type),
+            total_effort: sprint.total_effort.clone(),
+            max_effort: sprint.max_effort.clone(),
+            dependencies: sprint.dependencies.join(LIST_SEPARATOR),
+            must_complete_first: sprint.must_complete_first,
+            task_id: String::new(),
+            task_title: String::new(),
+            task_type: String::new(),
+            task_status: String::new(),
+            priority: String::new(),
+            effort: String::new(),
+            feature: String::new(),
+            description: String::new(),
+            acceptance_criteria: String::new(),
+        };
+
+        if sprint.tasks.is_empty() {
+            writer.serialize(&base).map_err(csv_error)?;
+        }
+        for task in &sprint.tasks {
+            let row = CsvRow {
+                task_id: task.id.clone(),
+                task_title: task.title.clone(),
+                task_type: enum_name(&task.r#type),
+                task_status: enum_name(&task.status),
+                priority: enum_name(&task.priority),
+                effort: task.effort.clone(),
+                feature: task.feature.clone(),
+                description: task.description.clone().unwrap_or_default(),
+                acceptance_criteria: task.acceptance_criteria.join(LIST_SEPARATOR),
+                ..base.clone()
+            };
+            writer.serialize(&row).map_err(csv_error)?;
+        }
+    }
+
+    let bytes = writer.into_inner().map_err(|e| AutoFlowError::ValidationError(e.to_string()))?;
+    String::from_utf8(bytes).map_err(|e| AutoFlowError::ValidationError(e.to_string()))
+}
+
+/// Render a read-only markdown summary of the plan
+pub fn export_markdown(plan: &SprintsYaml) -> String {
+    let mut md = String::new();
+    md.push_str(&format!("# {} - Sprint Plan\n\n", plan.project.name));
+    md.push_str(&format!("{}\n\n", plan.project.description));
+    md.push_str("| ID | Goal | Status | Workflow | Effort | Depends On | Tasks |\n");
+    md.push_str("|----|------|--------|----------|--------|------------|-------|\n");
+    for sprint in &plan.sprints {
+        md.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} |\n",
+            sprint.id,
+            escape_md(&sprint.goal),
+            enum_name(&sprint.status),
+            enum_name(&sprint.workflow_type),
+            sprint.total_effort,
+            if sprint.dependencies.is_empty() { "-".to_string() } else { sprint.dependencies.join(", ") },
+            sprint.tasks.len()
+        ));
+    }
+
+    for sprint in &plan.sprints {
+        md.push_str(&format!("\n## Sprint {}: {}\n\n", sprint.id, sprint.goal));
+        for task in &sprint.tasks {
+            let check = if task.status == TaskStatus::Done { "x" } else { " " };
+            md.push_str(&format!(
+                "- [{}] **{}** {} ({}, {})\n",
+                check,
+                task.id,
+                task.title,
+                enum_name(&task.priority),
+                task.effort
+            ));
+        }
+    }
+    md
+}
+
+/// Parse a plan from JSON or YAML; accepts a full document or a bare list of sprints
+pub fn parse_structured(content: &str, format: PlanFormat) -> Result<Vec<Sprint>> {
+    let value: serde_json::Value = match format {
+        PlanFormat::Json => serde_json::from_str(content)?,
+        PlanFormat::Yaml => serde_yaml::from_str(content)?,
+        _ => {
+            return Err(AutoFlowError::ValidationError(
+                "parse_structured only handles json and yaml".to_string(),
+            ))
+        }
+    };
+
+    let sprints = if value.get("sprints").is_some() {
+        value["sprints"].clone()
+    } else {
+        value
+    };
+    Ok(serde_json::from_value(sprints)?)
+}
+
+/// Parse CSV rows back into sprints
+///
+/// Sprints that already exist in `existing` keep the fields CSV does not carry
+/// (deliverables, integration points, timestamps, task testing requirements...),
+/// so an export → spreadsheet → import round trip only changes what was edited.
+pub fn parse_csv(content: &str, existing: &[Sprint]) -> Result<Vec<Sprint>> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let mut grouped: BTreeMap<u32, Vec<CsvRow>> = BTreeMap::new();
+    let mut order: Vec<u32> = Vec::new();
+    for (line, row) in reader.deserialize::<CsvRow>().enumerate() {
+        let row = row.map_err(|e| {
+            AutoFlowError::ValidationError(format!("CSV row {}: {}", line + 2, e))
+        })?;
+        if !grouped.contains_key(&row.sprint_id) {
+            order.push(row.sprint_id);
+        }
+        grouped.entry(row.sprint_id).or_default().push(row);
+    }
+
+    let mut sprints = Vec::new();
+    for id in order {
+        let rows = &grouped[&id];
+        let first = &rows[0];
+        let previous = existing.iter().find(|s| s.id == id);
+
+        let mut sprint = previous.cloned().unwrap_or_else(|| blank_sprint(id));
+        sprint.goal = first.sprint_goal.clone();
+        sprint.status = parse_enum(&first.sprint_status, "sprint_status")?;
+        sprint.workflow_type = parse_enum_or_default::<WorkflowType>(&first.workflow_type, "workflow_type")?;
+        sprint.total_effort = first.total_effort.clone();
+        sprint.max_effort = first.max_effort.clone();
+        sprint.dependencies = split_list(&first.dependencies);
+        sprint.must_complete_first = first.must_complete_first;
+        sprint.last_updated = Utc::now();
+
+        let mut tasks = Vec::new();
+        for row in rows.iter().filter(|r| !r.task_id.is_empty() || !r.task_title.is_empty()) {
+            let mut task = previous
+                .and_then(|p| p.tasks.iter().find(|t| t.id == row.task_id))
+                .cloned()
+                .unwrap_or_else(|| blank_task(&row.task_id));
+            task.title = row.task_title.clone();
+            task.r#type = parse_enum_or_default::<TaskType>(&row.task_type, "task_type")?;
+            task.status = parse_enum_or_default::<TaskStatus>(&row.task_status, "task_status")?;
+            if !row.priority.is_empty() {
+                task.priority = parse_enum::<Priority>(&row.priority, "priority")?;
+            }
+            if !row.effort.is_empty() {
+                task.effort = row.effort.clone();
+            }
+            if !row.feature.is_empty() {
+                task.feature = row.feature.clone();
+            }
+            task.description = Some(row.description.clone()).filter(|d| !d.is_empty());
+            task.acceptance_criteria = split_list(&row.acceptance_criteria);
+            tasks.push(task);
+        }
+        sprint.tasks = tasks;
+        sprints.push(sprint);
+    }
+
+    Ok(sprints)
+}
+
+/// Merge imported sprints into `plan`, resolving ID conflicts with `strategy`
+///
+/// Does not validate; callers should run `SprintsYaml::validate_errors` on the
+/// result before saving.
+pub fn merge_import(
+    plan: &mut SprintsYaml,
+    imported: Vec<Sprint>,
+    strategy: ConflictStrategy,
+) -> Result<ImportSummary> {
+    let mut summary = ImportSummary::default();
+
+    let mut seen = HashSet::new();
+    for sprint in &imported {
+        if !seen.insert(sprint.id) {
+            return Err(AutoFlowError::ValidationError(format!(
+                "Import contains sprint {} more than once",
+                sprint.id
+            )));
+        }
+    }
+
+    let existing_ids: HashSet<u32> = plan.sprints.iter().map(|s| s.id).collect();
+    let conflicts: Vec<u32> = imported.iter().map(|s| s.id).filter(|id| existing_ids.contains(id)).collect();
+    if strategy == ConflictStrategy::Fail && !conflicts.is_empty() {
+        return Err(AutoFlowError::ValidationError(format!(
+            "Sprint ID(s) already exist: {}. Use --on-conflict replace|skip|renumber",
+            conflicts.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+        )));
+    }
+
+    // Work out final IDs first so dependencies inside the import can be remapped
+    let mut next_id = existing_ids
+        .iter()
+        .chain(imported.iter().map(|s| &s.id))
+        .max()
+        .copied()
+        .unwrap_or(0)
+        + 1;
+    let mut remap: HashMap<String, String> = HashMap::new();
+    if strategy == ConflictStrategy::Renumber {
+        for id in &conflicts {
+            remap.insert(id.to_string(), next_id.to_string());
+            summary.renumbered.push((*id, next_id));
+            next_id += 1;
+        }
+    }
+
+    for mut sprint in imported {
+        let conflict = existing_ids.contains(&sprint.id);
+        match (conflict, strategy) {
+            (true, ConflictStrategy::Skip) => {
+                summary.skipped.push(sprint.id);
+            }
+            (true, ConflictStrategy::Replace) => {
+                let slot = plan.sprints.iter_mut().find(|s| s.id == sprint.id).expect("conflict exists");
+                *slot = sprint;
+                summary.replaced.push(slot.id);
+            }
+            (true, ConflictStrategy::Renumber) => {
+                let new_id = remap[&sprint.id.to_string()].parse().expect("numeric id");
+                sprint.id = new_id;
+                remap_dependencies(&mut sprint, &remap);
+                plan.sprints.push(sprint);
+            }
+            _ => {
+                remap_dependencies(&mut sprint, &remap);
+                summary.added.push(sprint.id);
+                plan.sprints.push(sprint);
+            }
+        }
+    }
+
+    plan.sprints.sort_by_key(|s| s.id);
+    plan.project.total_sprints = plan.sprints.len() as u32;
+    plan.project.last_updated = Utc::now();
+    Ok(summary)
+}
+
+/// Create an empty plan to import into when no SPRINTS.yml exists yet
+pub fn empty_plan(name: &str) -> SprintsYaml {
+    SprintsYaml {
+        project: ProjectMetadata {
+            name: name.to_string(),
+            version: "0.1.0".to_string(),
+            description: "AutoFlow Project".to_string(),
+            total_sprints: 0,
+            current_sprint: None,
+            last_updated: Utc::now(),
+            repos: vec![],
+        },
+        sprints: vec![],
+    }
+}
+
+fn remap_dependencies(sprint: &mut Sprint, remap: &HashMap<String, String>) {
+    for dep in &mut sprint.dependencies {
+        if let Some(new_id) = remap.get(dep) {
+            *dep = new_id.clone();
+        }
+    }
+}
+
+fn blank_sprint(id: u32) -> Sprint {
+    Sprint {
+        id,
+        uid: None,
+        goal: String::new(),
+        status: SprintStatus::Pending,
+        workflow_type: WorkflowType::default(),
+        workflow: None,
+        duration: None,
+        total_effort: String::new(),
+        max_effort: String::new(),
+        started: None,
+        last_updated: Utc::now(),
+        completed_at: None,
+        deliverables: vec![],
+        tasks: vec![],
+        dependencies: vec![],
+        integration_points: None,
+        blocked_count: None,
+        must_complete_first: false,
+        priority: None,
+        value: None,
+        failure_reports: vec![],
+        repo: None,
+        uses_blocker_resolver: false,
+        issue_url: None,
+        skipped: None,
+        cancelled: None,
+        paused_from: None,
+    }
+}
+
+fn blank_task(id: &str) -> Task {
+    Task {
+        id: id.to_string(),
+        uid: None,
+        title: String::new(),
+        description: None,
+        r#type: TaskType::default(),
+        doc_reference: None,
+        acceptance_criteria: vec![],
+        test_specification: None,
+        effort: "4h".to_string(),
+        priority: Priority::Medium,
+        feature: "core".to_string(),
+        docs: vec![],
+        business_rules: vec![],
+        integration_notes: None,
+        files: vec![],
+        testing: TestingRequirements {
+            unit_tests: None,
+            integration_tests: None,
+            e2e_tests: None,
+        },
+        status: TaskStatus::default(),
+        committed_at: None,
+        reviewed_at: None,
+        tested_at: None,
+        done_at: None,
+        git_commit: None,
+        review_findings: vec![],
+    }
+}
+
+/// Serialized (SCREAMING_SNAKE_CASE) name of a unit enum variant
+fn enum_name<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn parse_enum<T: DeserializeOwned>(value: &str, column: &str) -> Result<T> {
+    let normalized = value.trim().to_uppercase().replace([' ', '-'], "_");
+    serde_json::from_value(serde_json::Value::String(normalized)).map_err(|_| {
+        AutoFlowError::ValidationError(format!("Invalid {} value '{}'", column, value))
+    })
+}
+
+fn parse_enum_or_default<T: DeserializeOwned + Default>(value: &str, column: &str) -> Result<T> {
+    if value.trim().is_empty() {
+        Ok(T::default())
+    } else {
+        parse_enum(value, column)
+    }
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn escape_md(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+fn csv_error(e: csv::Error) -> AutoFlowError {
+    AutoFlowError::ValidationError(format!("CSV error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_plan() -> SprintsYaml {
+        let mut plan = empty_plan("demo");
+        let mut sprint = blank_sprint(1);
+        sprint.goal = "Auth, login; logout".to_string();
+        sprint.total_effort = "8h".to_string();
+        sprint.max_effort = "12h".to_string();
+        sprint.deliverables = vec!["Login page".to_string()];
+        let mut task = blank_task("task-001");
+        task.title = "Login form".to_string();
+        task.acceptance_criteria = vec!["Shows errors".to_string(), "Redirects".to_string()];
+        sprint.tasks.push(task);
+        plan.sprints.push(sprint);
+        plan
+    }
+
+    #[test]
+    fn test_csv_round_trip_keeps_non_csv_fields() {
+        let plan = sample_plan();
+        let csv = export_csv(&plan).unwrap();
+        let edited = csv.replace("Login form", "Login form v2");
+
+        let sprints = parse_csv(&edited, &plan.sprints).unwrap();
+        assert_eq!(sprints.len(), 1);
+        assert_eq!(sprints[0].goal, "Auth, login; logout");
+        assert_eq!(sprints[0].tasks[0].title, "Login form v2");
+        assert_eq!(sprints[0].tasks[0].acceptance_criteria.len(), 2);
+        assert_eq!(sprints[0].deliverables, vec!["Login page".to_string()]);
+    }
+
+    #[test]
+    fn test_merge_renumbers_conflicts_and_remaps_dependencies() {
+        let mut plan = sample_plan();
+        let mut a = blank_sprint(1);
+        a.goal = "Imported".to_string();
+        let mut b = blank_sprint(2);
+        b.dependencies = vec!["1".to_string()];
+
+        let summary = merge_import(&mut plan, vec![a, b], ConflictStrategy::Renumber).unwrap();
+        assert_eq!(summary.renumbered, vec![(1, 3)]);
+        assert_eq!(plan.sprints.len(), 3);
+        let b = plan.sprints.iter().find(|s| s.id == 2).unwrap();
+        assert_eq!(b.dependencies, vec!["3".to_string()]);
+    }
+
+    #[test]
+    fn test_merge_fails_on_conflict_by_default() {
+        let mut plan = sample_plan();
+        assert!(merge_import(&mut plan, vec![blank_sprint(1)], ConflictStrategy::Fail).is_err());
+    }
+}
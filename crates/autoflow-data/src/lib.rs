@@ -1,9 +1,25 @@
+pub mod board;
 pub mod config;
+pub mod encryption;
 pub mod error;
+pub mod ignore_rules;
+pub mod interchange;
+pub mod read_only;
+pub mod signoffs;
+pub mod sprint_templates;
 pub mod sprints;
 pub mod tasks;
+pub mod ulid;
 
+pub use board::*;
 pub use config::*;
+pub use encryption::*;
 pub use error::*;
+pub use ignore_rules::*;
+pub use interchange::*;
+pub use read_only::*;
+pub use signoffs::*;
+pub use sprint_templates::*;
 pub use sprints::*;
 pub use tasks::*;
+pub use ulid::*;
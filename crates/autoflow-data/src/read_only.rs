@@ -0,0 +1,22 @@
+// Read-only mode: inspect an untrusted repository without changing it
+
+use crate::{AutoFlowError, Result};
+
+/// Set to 1 (by `--read-only` or `[policy] read_only`) to forbid changes
+pub const READ_ONLY_ENV: &str = "AUTOFLOW_READ_ONLY";
+
+/// Agent tools that can change files; Bash is included since any command can write
+pub const WRITE_TOOLS: [&str; 5] = ["Write", "Edit", "MultiEdit", "NotebookEdit", "Bash"];
+
+/// True when this process runs in read-only mode
+pub fn is_read_only() -> bool {
+    std::env::var(READ_ONLY_ENV).is_ok_and(|v| v == "1")
+}
+
+/// Refuse `action` (e.g. "git commit") in read-only mode
+pub fn ensure_writable(action: &str) -> Result<()> {
+    if is_read_only() {
+        return Err(AutoFlowError::ReadOnly(action.to_string()));
+    }
+    Ok(())
+}
@@ -0,0 +1,108 @@
+// Sign-off records: who approved what at an approval gate, and the exact artifacts they approved
+
+use crate::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Append-only sign-off log, beside SPRINTS.yml and the board
+pub const SIGNOFFS_FILE: &str = "signoffs.jsonl";
+
+/// [`SIGNOFFS_FILE`] relative to the project root
+pub const SIGNOFFS_PATH: &str = ".autoflow/signoffs.jsonl";
+
+/// One approval, as a line of [`SIGNOFFS_PATH`]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SignOff {
+    /// What was approved: `run`, `deploy`, `iac-apply`, `patch-apply`
+    pub gate: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sprint: Option<u32>,
+    /// `[policy] approver`, or the OS user
+    pub approver: String,
+    /// `prompt`, or `AUTOFLOW_APPROVE` when approved non-interactively
+    pub via: String,
+    pub approved_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    /// e.g. the git ref being deployed
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub detail: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<ArtifactHash>,
+}
+
+/// SHA-256 of a file as it was when approved
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArtifactHash {
+    pub path: String,
+    pub sha256: String,
+}
+
+impl ArtifactHash {
+    /// Hash `path`, recorded relative to the project when it is inside it
+    pub fn of(project_path: &Path, path: &Path) -> Result<Self> {
+        let full = project_path.join(path);
+        let digest = ring::digest::digest(&ring::digest::SHA256, &fs::read(&full)?);
+        Ok(Self {
+            path: full.strip_prefix(project_path).unwrap_or(&full).display().to_string(),
+            sha256: digest.as_ref().iter().map(|b| format!("{:02x}", b)).collect(),
+        })
+    }
+}
+
+/// Append `signoff` to the project's log
+pub fn append_signoff(project_path: &Path, signoff: &SignOff) -> Result<()> {
+    let path = project_path.join(SIGNOFFS_PATH);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    crate::append_state(path, &serde_json::to_string(signoff)?)
+}
+
+/// Every sign-off in the log at `path`, oldest first; unparseable lines are skipped
+pub fn read_signoffs<P: AsRef<Path>>(path: P) -> Vec<SignOff> {
+    crate::read_state_to_string(path)
+        .map(|content| content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signoffs_log_round_trip() {
+        let project = std::env::temp_dir().join(format!("autoflow-signoffs-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&project);
+        fs::create_dir_all(project.join(".autoflow/iac")).unwrap();
+        fs::write(project.join(".autoflow/iac/sprint-2-plan.txt"), "abc").unwrap();
+
+        let artifact = ArtifactHash::of(&project, &project.join(".autoflow/iac/sprint-2-plan.txt")).unwrap();
+        assert_eq!(artifact.path, ".autoflow/iac/sprint-2-plan.txt");
+        assert_eq!(artifact.sha256, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+
+        let signoff = SignOff {
+            gate: "iac-apply".to_string(),
+            sprint: Some(2),
+            approver: "Jane Doe <jane@example.com>".to_string(),
+            via: "prompt".to_string(),
+            approved_at: Utc::now(),
+            run_id: None,
+            detail: "terraform".to_string(),
+            artifacts: vec![artifact],
+        };
+        append_signoff(&project, &signoff).unwrap();
+        append_signoff(&project, &SignOff { gate: "deploy".to_string(), artifacts: Vec::new(), ..signoff.clone() }).unwrap();
+        let logged = read_signoffs(project.join(SIGNOFFS_PATH));
+        assert_eq!(logged.len(), 2);
+        assert_eq!(logged[0], signoff);
+
+        let section = crate::render_signoffs(&logged);
+        assert!(section.starts_with("## ✍️ Sign-off"));
+        assert!(section.contains("| iac-apply | 2 | Jane Doe <jane@example.com> (via prompt) | `.autoflow/iac/sprint-2-plan.txt` (ba7816bf8f01) |"));
+        assert!(section.contains("| deploy | 2 | Jane Doe <jane@example.com> (via prompt) | terraform |"));
+        let _ = fs::remove_dir_all(project);
+    }
+}
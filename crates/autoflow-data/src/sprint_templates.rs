@@ -0,0 +1,289 @@
+use crate::{AutoFlowError, Result, Sprint, SprintStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Reusable sprint definition with `{{param}}` placeholders
+///
+/// Lives in `~/.autoflow/sprint-templates/<name>.yml`. Placeholders can take
+/// filters: `{{resource|snake|plural}}` renders `Invoice` as `invoices`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SprintTemplate {
+    pub name: String,
+
+    #[serde(default)]
+    pub description: String,
+
+    #[serde(default)]
+    pub params: Vec<TemplateParam>,
+
+    /// Sprint fields; id, status and timestamps are filled in on render
+    pub sprint: serde_yaml::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateParam {
+    pub name: String,
+
+    #[serde(default)]
+    pub description: String,
+
+    /// Makes the parameter optional
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+/// Directory holding the sprint template library (~/.autoflow/sprint-templates)
+pub fn sprint_templates_dir() -> PathBuf {
+    crate::autoflow_dir()
+        .unwrap_or_else(|| PathBuf::from(".autoflow"))
+        .join("sprint-templates")
+}
+
+/// Every `*.yml`/`*.yaml` template in `dir`, sorted by name
+pub fn load_sprint_templates<P: AsRef<Path>>(dir: P) -> Result<Vec<SprintTemplate>> {
+    let dir = dir.as_ref();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut templates = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_yaml = matches!(path.extension().and_then(|e| e.to_str()), Some("yml" | "yaml"));
+        if is_yaml {
+            templates.push(SprintTemplate::load(&path)?);
+        }
+    }
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(templates)
+}
+
+impl SprintTemplate {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        serde_yaml::from_str(&content).map_err(|e| {
+            AutoFlowError::ValidationError(format!("Sprint template {}: {}", path.display(), e))
+        })
+    }
+
+    /// Render the template into a PENDING sprint with the given id
+    pub fn render(&self, id: u32, params: &HashMap<String, String>) -> Result<Sprint> {
+        let values = self.resolve_params(params)?;
+
+        let mut value = self.sprint.clone();
+        substitute(&mut value, &values)?;
+
+        let serde_yaml::Value::Mapping(ref mut fields) = value else {
+            return Err(AutoFlowError::ValidationError(format!(
+                "Sprint template '{}': `sprint` must be a mapping",
+                self.name
+            )));
+        };
+        let now = serde_yaml::to_value(chrono::Utc::now())?;
+        fields.insert("id".into(), id.into());
+        fields.insert("status".into(), serde_yaml::to_value(SprintStatus::Pending)?);
+        fields.insert("last_updated".into(), now);
+        for list in ["deliverables", "tasks"] {
+            if !fields.contains_key(list) {
+                fields.insert(list.into(), serde_yaml::Value::Sequence(Vec::new()));
+            }
+        }
+        // Task ids derived from the sprint id (7 -> task-701, task-702...) so they
+        // don't collide with ids generated for the rest of the plan
+        if let Some(serde_yaml::Value::Sequence(tasks)) = fields.get_mut("tasks") {
+            for (i, task) in tasks.iter_mut().enumerate() {
+                if let serde_yaml::Value::Mapping(task) = task {
+                    if !task.contains_key("id") {
+                        task.insert("id".into(), format!("task-{}{:02}", id, i + 1).into());
+                    }
+                }
+            }
+        }
+
+        serde_yaml::from_value(value).map_err(|e| {
+            AutoFlowError::ValidationError(format!("Sprint template '{}' renders an invalid sprint: {}", self.name, e))
+        })
+    }
+
+    /// Supplied values plus defaults; rejects unknown and missing parameters
+    fn resolve_params(&self, params: &HashMap<String, String>) -> Result<HashMap<String, String>> {
+        if let Some(unknown) = params.keys().find(|key| !self.params.iter().any(|p| &p.name == *key)) {
+            return Err(AutoFlowError::ValidationError(format!(
+                "Sprint template '{}' has no parameter '{}' (expected: {})",
+                self.name,
+                unknown,
+                self.param_names()
+            )));
+        }
+
+        let mut values = HashMap::new();
+        for param in &self.params {
+            let value = params.get(&param.name).or(param.default.as_ref()).ok_or_else(|| {
+                AutoFlowError::ValidationError(format!(
+                    "Sprint template '{}' needs --param {}=<value>{}",
+                    self.name,
+                    param.name,
+                    if param.description.is_empty() { String::new() } else { format!(" ({})", param.description) }
+                ))
+            })?;
+            values.insert(param.name.clone(), value.clone());
+        }
+        Ok(values)
+    }
+
+    fn param_names(&self) -> String {
+        self.params.iter().map(|p| p.name.as_str()).collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// Replace placeholders in every string (keys and values) of a YAML tree
+fn substitute(value: &mut serde_yaml::Value, params: &HashMap<String, String>) -> Result<()> {
+    match value {
+        serde_yaml::Value::String(s) => *s = render_string(s, params)?,
+        serde_yaml::Value::Sequence(items) => {
+            for item in items {
+                substitute(item, params)?;
+            }
+        }
+        serde_yaml::Value::Mapping(map) => {
+            let entries: Vec<_> = std::mem::take(map).into_iter().collect();
+            for (mut key, mut item) in entries {
+                substitute(&mut key, params)?;
+                substitute(&mut item, params)?;
+                map.insert(key, item);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn render_string(template: &str, params: &HashMap<String, String>) -> Result<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or_else(|| {
+            AutoFlowError::ValidationError(format!("Unclosed placeholder in sprint template: {}", template))
+        })?;
+
+        let mut parts = after[..end].split('|').map(str::trim);
+        let name = parts.next().unwrap_or_default();
+        let mut rendered = params
+            .get(name)
+            .ok_or_else(|| AutoFlowError::ValidationError(format!("Unknown placeholder '{{{{{}}}}}'", name)))?
+            .clone();
+        for filter in parts {
+            rendered = apply_filter(&rendered, filter)?;
+        }
+        out.push_str(&rendered);
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn apply_filter(value: &str, filter: &str) -> Result<String> {
+    Ok(match filter {
+        "lower" => value.to_lowercase(),
+        "upper" => value.to_uppercase(),
+        "snake" => split_words(value).join("_"),
+        "kebab" => split_words(value).join("-"),
+        "plural" => pluralize(value),
+        other => {
+            return Err(AutoFlowError::ValidationError(format!(
+                "Unknown placeholder filter '{}' (expected lower, upper, snake, kebab or plural)",
+                other
+            )))
+        }
+    })
+}
+
+/// Lowercase words from PascalCase, camelCase, snake_case, kebab-case or spaces
+fn split_words(value: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for c in value.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// English plural good enough for resource names
+fn pluralize(word: &str) -> String {
+    let lower = word.to_lowercase();
+    if lower.ends_with('s') || lower.ends_with('x') || lower.ends_with("ch") || lower.ends_with("sh") {
+        format!("{}es", word)
+    } else if lower.ends_with('y') && !lower.ends_with("ay") && !lower.ends_with("ey") && !lower.ends_with("oy") {
+        format!("{}ies", &word[..word.len() - 1])
+    } else {
+        format!("{}s", word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CRUD: &str = r#"
+name: crud
+params:
+  - name: resource
+  - name: effort
+    default: 6h
+sprint:
+  goal: "CRUD endpoints for {{resource}}"
+  total_effort: "{{effort}}"
+  max_effort: 10h
+  deliverables:
+    - "/api/{{resource|kebab|plural}} list, create, show, update, delete"
+  tasks:
+    - title: "{{resource}} model and migration for {{resource|snake|plural}}"
+"#;
+
+    fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_render_fills_placeholders_and_defaults() {
+        let template: SprintTemplate = serde_yaml::from_str(CRUD).unwrap();
+        let sprint = template.render(7, &params(&[("resource", "LineItem")])).unwrap();
+
+        assert_eq!(sprint.id, 7);
+        assert_eq!(sprint.status, SprintStatus::Pending);
+        assert_eq!(sprint.goal, "CRUD endpoints for LineItem");
+        assert_eq!(sprint.total_effort, "6h");
+        assert_eq!(sprint.deliverables[0], "/api/line-items list, create, show, update, delete");
+        assert_eq!(sprint.tasks[0].id, "task-701");
+        assert_eq!(sprint.tasks[0].title, "LineItem model and migration for line_items");
+    }
+
+    #[test]
+    fn test_render_rejects_bad_params() {
+        let template: SprintTemplate = serde_yaml::from_str(CRUD).unwrap();
+        assert!(template.render(1, &params(&[])).is_err());
+        assert!(template.render(1, &params(&[("resource", "Invoice"), ("resorce", "x")])).is_err());
+        assert_eq!(pluralize("Category"), "Categories");
+        assert_eq!(pluralize("Address"), "Addresses");
+    }
+}
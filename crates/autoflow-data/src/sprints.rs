@@ -1,21 +1,69 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
-use crate::tasks::Task;
+use crate::tasks::{ReviewFinding, Task, TaskStatus};
 use crate::Result;
 
+/// SPRINTS.yml
+///
+/// Change the plan through `add_sprint`, `update_status`, `set_dependency` and
+/// `renumber_ids` rather than editing `sprints` directly: they check the change
+/// and keep `project.total_sprints` and `last_updated` in step.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SprintsYaml {
     pub project: ProjectMetadata,
     pub sprints: Vec<Sprint>,
 }
 
+/// Advisory lock serializing writers of one plan file, released when dropped
+///
+/// Held on `<plan>.lock` beside the plan, as the plan itself is replaced on
+/// every save. Only AutoFlow takes it; editors and git don't.
+struct PlanLock {
+    file: fs::File,
+}
+
+impl PlanLock {
+    fn acquire(plan: &Path) -> Result<Self> {
+        crate::ensure_writable("saving SPRINTS.yml")?;
+        let mut name = plan.file_name().unwrap_or_default().to_os_string();
+        name.push(".lock");
+        let path: PathBuf = plan.with_file_name(name);
+        let file = fs::OpenOptions::new().create(true).truncate(false).write(true).open(&path)?;
+        file.lock()?;
+        Ok(Self { file })
+    }
+}
+
+impl Drop for PlanLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+/// The SPRINTS.yml schema compiled into AutoFlow
+pub const SPRINTS_SCHEMA: &str = include_str!("../schemas/sprints.schema.json");
+
+/// Whether `schema` accepts every sprint status `reference` does
+fn knows_all_statuses(schema: &str, reference: &str) -> bool {
+    let statuses = |content: &str| -> Option<Vec<serde_json::Value>> {
+        let schema: serde_json::Value = serde_json::from_str(content).ok()?;
+        schema.pointer("/properties/sprints/items/properties/status/enum")?.as_array().cloned()
+    };
+    match (statuses(schema), statuses(reference)) {
+        (Some(installed), Some(known)) => known.iter().all(|status| installed.contains(status)),
+        _ => true,
+    }
+}
+
 impl SprintsYaml {
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
         let content = fs::read_to_string(path)?;
-        let sprints = serde_yaml::from_str(&content)?;
+        let mut sprints: Self = serde_yaml::from_str(&content)?;
+        sprints.migrate_statuses();
         Ok(sprints)
     }
 
@@ -23,14 +71,142 @@ impl SprintsYaml {
     /// Used to check sprint status when resuming after interruption
     pub fn load_without_validation<P: AsRef<Path>>(path: P) -> Result<Self> {
         let content = fs::read_to_string(path)?;
-        let sprints = serde_yaml::from_str(&content)?;
+        let mut sprints: Self = serde_yaml::from_str(&content)?;
+        sprints.migrate_statuses();
         Ok(sprints)
     }
 
+    /// Move sprints skipped before SKIPPED existed (DONE with a `skipped` reason) to SKIPPED
+    ///
+    /// The change persists from the next save. Returns the ids moved.
+    pub fn migrate_statuses(&mut self) -> Vec<u32> {
+        let mut moved = Vec::new();
+        for sprint in &mut self.sprints {
+            if sprint.status == SprintStatus::Done && sprint.skipped.is_some() {
+                sprint.status = SprintStatus::Skipped;
+                moved.push(sprint.id);
+            }
+        }
+        moved
+    }
+
+    /// Give every sprint and task without a `uid` (or with a duplicate) a new one
+    ///
+    /// Loading leaves a plan's uids as written; saving fills in the missing
+    /// ones, and `autoflow start` migrates older plans before a run. Returns
+    /// whether anything changed.
+    pub fn assign_uids(&mut self) -> bool {
+        let mut seen = std::collections::HashSet::new();
+        let mut changed = false;
+        let mut fill = |uid: &mut Option<String>| {
+            if uid.as_ref().is_none_or(|u| !seen.insert(u.clone())) {
+                let fresh = crate::new_ulid();
+                seen.insert(fresh.clone());
+                *uid = Some(fresh);
+                changed = true;
+            }
+        };
+        for sprint in &mut self.sprints {
+            fill(&mut sprint.uid);
+            for task in &mut sprint.tasks {
+                fill(&mut task.uid);
+            }
+        }
+        changed
+    }
+
+    /// Write the plan, refusing one that fails schema validation
+    ///
+    /// Writers take the plan's lock, and the file is replaced by rename, so a
+    /// reader never sees a half-written plan.
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let _lock = PlanLock::acquire(path)?;
+        let mut plan = self.clone();
+        if let Ok(saved) = Self::load_without_validation(path) {
+            plan.inherit_uids(&saved);
+        }
+        plan.assign_uids();
+        plan.write(path)
+    }
+
+    /// Replace the sprint with `sprint`'s id in the plan on disk and save it
+    ///
+    /// The read and the write happen under the plan's lock. Sprints running
+    /// in parallel, or orchestrators sharing the file, each persist their own
+    /// sprint without overwriting the others' progress. Returns the saved
+    /// plan, or None (and writes nothing) when the plan has no such sprint.
+    pub fn update_sprint<P: AsRef<Path>>(path: P, sprint: &Sprint) -> Result<Option<Self>> {
+        let path = path.as_ref();
+        let _lock = PlanLock::acquire(path)?;
+        let mut plan = Self::load(path)?;
+        let Some(slot) = plan.sprints.iter_mut().find(|s| s.id == sprint.id) else {
+            return Ok(None);
+        };
+        let uid = slot.uid.take();
+        let tasks = std::mem::take(&mut slot.tasks);
+        *slot = sprint.clone();
+        slot.uid = slot.uid.take().or(uid);
+        for task in &mut slot.tasks {
+            if task.uid.is_none() {
+                task.uid = tasks.iter().find(|t| t.id == task.id).and_then(|t| t.uid.clone());
+            }
+        }
+        plan.assign_uids();
+        plan.project.last_updated = Utc::now();
+        plan.write(path)?;
+        Ok(Some(plan))
+    }
+
+    /// Give the plan on disk the uids it lacks and return it as saved
+    ///
+    /// Only the uids change, under the plan's lock, so edits made to the
+    /// file meanwhile are kept.
+    pub fn migrate_uids<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let _lock = PlanLock::acquire(path)?;
+        let mut plan = Self::load(path)?;
+        if plan.assign_uids() {
+            plan.write(path)?;
+        }
+        Ok(plan)
+    }
+
+    /// Take uids this plan lacks from `saved`, matching sprints and tasks by id
+    ///
+    /// A plan loaded before its uids were first written keeps the ones that
+    /// save minted, rather than minting new ones on every save.
+    fn inherit_uids(&mut self, saved: &Self) {
+        for sprint in &mut self.sprints {
+            let Some(old) = saved.sprint(sprint.id) else { continue };
+            if sprint.uid.is_none() {
+                sprint.uid = old.uid.clone();
+            }
+            for task in &mut sprint.tasks {
+                if task.uid.is_none() {
+                    task.uid = old.tasks.iter().find(|t| t.id == task.id).and_then(|t| t.uid.clone());
+                }
+            }
+        }
+    }
+
+    /// Validate and write through a temporary file; the caller holds the lock
+    fn write(&self, path: &Path) -> Result<()> {
+        self.validate_errors().map_err(crate::AutoFlowError::ValidationError)?;
         let content = serde_yaml::to_string(self)?;
-        fs::write(path, content)?;
-        Ok(())
+
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let temp = path.with_file_name(format!(".{}.{}.tmp", name, std::process::id()));
+        let written = (|| -> std::io::Result<()> {
+            let mut file = fs::File::create(&temp)?;
+            file.write_all(content.as_bytes())?;
+            file.sync_all()?;
+            fs::rename(&temp, path)
+        })();
+        if written.is_err() {
+            let _ = fs::remove_file(&temp);
+        }
+        Ok(written?)
     }
 
     /// Validate YAML content against the JSON schema and collect ALL errors
@@ -41,8 +217,20 @@ impl SprintsYaml {
             Err(e) => return Err(format!("Failed to read YAML file: {}", e)),
         };
 
+        Self::validate_yaml_str(&yaml_content)
+    }
+
+    /// Validate an in-memory plan against the JSON schema (same rules as the file on disk)
+    pub fn validate_errors(&self) -> std::result::Result<(), String> {
+        let yaml_content = serde_yaml::to_string(self)
+            .map_err(|e| format!("Failed to serialize sprints: {}", e))?;
+        Self::validate_yaml_str(&yaml_content)
+    }
+
+    /// Validate YAML text against the JSON schema and collect ALL errors
+    pub fn validate_yaml_str(yaml_content: &str) -> std::result::Result<(), String> {
         // Parse YAML to JSON Value
-        let yaml_value: serde_yaml::Value = match serde_yaml::from_str(&yaml_content) {
+        let yaml_value: serde_yaml::Value = match serde_yaml::from_str(yaml_content) {
             Ok(value) => value,
             Err(e) => return Err(format!("Failed to parse YAML: {}", e)),
         };
@@ -55,14 +243,17 @@ impl SprintsYaml {
 
         // Load schema - try global location first, then fall back to embedded
         let schema_content = {
-            let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-            let global_schema_path = format!("{}/.autoflow/schemas/sprints.schema.json", home);
+            let embedded = SPRINTS_SCHEMA;
+            let global_schema = crate::autoflow_dir()
+                .and_then(|dir| fs::read_to_string(dir.join("schemas/sprints.schema.json")).ok())
+                // An install from before a status was added would reject plans that use it
+                .filter(|content| knows_all_statuses(content, embedded));
 
-            if let Ok(content) = fs::read_to_string(&global_schema_path) {
+            if let Some(content) = global_schema {
                 content
             } else {
                 // Fall back to embedded schema (compiled into binary)
-                include_str!("../schemas/sprints.schema.json").to_string()
+                embedded.to_string()
             }
         };
 
@@ -118,28 +309,28 @@ impl SprintsYaml {
                 if let Some(project) = value.get_mut("project") {
                     if let Some(project_map) = project.as_mapping_mut() {
                         // Add last_updated if missing (required)
-                        if !project_map.contains_key(&serde_yaml::Value::String("last_updated".to_string())) {
+                        if !project_map.contains_key(serde_yaml::Value::String("last_updated".to_string())) {
                             project_map.insert(
                                 serde_yaml::Value::String("last_updated".to_string()),
                                 serde_yaml::Value::String(now.clone()),
                             );
                         }
                         // Add current_sprint if missing (optional, but explicit)
-                        if !project_map.contains_key(&serde_yaml::Value::String("current_sprint".to_string())) {
+                        if !project_map.contains_key(serde_yaml::Value::String("current_sprint".to_string())) {
                             project_map.insert(
                                 serde_yaml::Value::String("current_sprint".to_string()),
                                 serde_yaml::Value::Null,
                             );
                         }
                         // Add version if missing (for backwards compatibility)
-                        if !project_map.contains_key(&serde_yaml::Value::String("version".to_string())) {
+                        if !project_map.contains_key(serde_yaml::Value::String("version".to_string())) {
                             project_map.insert(
                                 serde_yaml::Value::String("version".to_string()),
                                 serde_yaml::Value::String("0.1.0".to_string()),
                             );
                         }
                         // Add description if missing (for backwards compatibility)
-                        if !project_map.contains_key(&serde_yaml::Value::String("description".to_string())) {
+                        if !project_map.contains_key(serde_yaml::Value::String("description".to_string())) {
                             project_map.insert(
                                 serde_yaml::Value::String("description".to_string()),
                                 serde_yaml::Value::String("AutoFlow Project".to_string()),
@@ -154,21 +345,21 @@ impl SprintsYaml {
                         for sprint in sprints_seq.iter_mut() {
                             if let Some(sprint_map) = sprint.as_mapping_mut() {
                                 // Add last_updated if missing (required)
-                                if !sprint_map.contains_key(&serde_yaml::Value::String("last_updated".to_string())) {
+                                if !sprint_map.contains_key(serde_yaml::Value::String("last_updated".to_string())) {
                                     sprint_map.insert(
                                         serde_yaml::Value::String("last_updated".to_string()),
                                         serde_yaml::Value::String(now.clone()),
                                     );
                                 }
                                 // Add started if missing (optional)
-                                if !sprint_map.contains_key(&serde_yaml::Value::String("started".to_string())) {
+                                if !sprint_map.contains_key(serde_yaml::Value::String("started".to_string())) {
                                     sprint_map.insert(
                                         serde_yaml::Value::String("started".to_string()),
                                         serde_yaml::Value::Null,
                                     );
                                 }
                                 // Add completed_at if missing (optional)
-                                if !sprint_map.contains_key(&serde_yaml::Value::String("completed_at".to_string())) {
+                                if !sprint_map.contains_key(serde_yaml::Value::String("completed_at".to_string())) {
                                     sprint_map.insert(
                                         serde_yaml::Value::String("completed_at".to_string()),
                                         serde_yaml::Value::Null,
@@ -188,6 +379,291 @@ impl SprintsYaml {
         }
     }
 
+    /// Repository a sprint targets, None when it runs in the plan's own directory
+    pub fn repo_for(&self, sprint: &Sprint) -> Option<&RepoSpec> {
+        let name = sprint.repo.as_deref()?;
+        self.project.repos.iter().find(|r| r.name == name)
+    }
+
+    /// Every sprint's `repo` must name a repository declared in `project.repos`
+    pub fn check_repos(&self) -> std::result::Result<(), String> {
+        let unknown: Vec<String> = self
+            .sprints
+            .iter()
+            .filter(|s| s.repo.is_some() && self.repo_for(s).is_none())
+            .map(|s| format!("sprint {} targets undeclared repo '{}'", s.id, s.repo.as_deref().unwrap_or_default()))
+            .collect();
+        if unknown.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("{} (declare it under project.repos)", unknown.join("; ")))
+        }
+    }
+
+    /// Id for a new sprint: one past the highest in use
+    pub fn next_id(&self) -> u32 {
+        self.sprints.iter().map(|s| s.id).max().unwrap_or(0) + 1
+    }
+
+    /// Add `sprint` after sprint `after`, or at the end; returns its id
+    ///
+    /// An id of 0 takes the next free one, and uids already in the plan (as
+    /// on a copied sprint) are dropped for the next save to replace. The plan
+    /// must still pass schema validation afterwards, otherwise it's left as
+    /// it was.
+    pub fn add_sprint(&mut self, mut sprint: Sprint, after: Option<u32>) -> Result<u32> {
+        if sprint.id == 0 {
+            sprint.id = self.next_id();
+        }
+        let in_use: std::collections::HashSet<&String> = self
+            .sprints
+            .iter()
+            .flat_map(|s| s.uid.iter().chain(s.tasks.iter().filter_map(|t| t.uid.as_ref())))
+            .collect();
+        if sprint.uid.as_ref().is_some_and(|uid| in_use.contains(uid)) {
+            sprint.uid = None;
+        }
+        for task in &mut sprint.tasks {
+            if task.uid.as_ref().is_some_and(|uid| in_use.contains(uid)) {
+                task.uid = None;
+            }
+        }
+        if self.sprint(sprint.id).is_some() {
+            return Err(crate::AutoFlowError::ValidationError(format!("Sprint {} already exists", sprint.id)));
+        }
+        let index = match after {
+            Some(after) => self.position(after)? + 1,
+            None => self.sprints.len(),
+        };
+        let id = sprint.id;
+        self.sprints.insert(index, sprint);
+        if let Err(errors) = self.validate_errors() {
+            self.sprints.remove(index);
+            return Err(crate::AutoFlowError::ValidationError(errors));
+        }
+        self.touch();
+        Ok(id)
+    }
+
+    /// Move a sprint to `status`, if `SprintStatus::can_transition_to` allows it
+    ///
+    /// Back to PENDING clears its start and completion times; DONE sets the
+    /// completion time.
+    pub fn update_status(&mut self, id: u32, status: SprintStatus) -> Result<()> {
+        let index = self.position(id)?;
+        let sprint = &mut self.sprints[index];
+        if !sprint.status.can_transition_to(status) {
+            return Err(crate::AutoFlowError::ValidationError(format!(
+                "Sprint {} can't move from {:?} to {:?}",
+                id, sprint.status, status
+            )));
+        }
+        if status == SprintStatus::Paused && sprint.status != SprintStatus::Paused {
+            sprint.paused_from = Some(sprint.status);
+        } else if status != SprintStatus::Paused {
+            sprint.paused_from = None;
+        }
+        sprint.status = status;
+        sprint.last_updated = Utc::now();
+        match status {
+            SprintStatus::Pending => {
+                sprint.started = None;
+                sprint.completed_at = None;
+                sprint.skipped = None;
+                sprint.cancelled = None;
+            }
+            SprintStatus::Done if sprint.completed_at.is_none() => sprint.completed_at = Some(Utc::now()),
+            _ => {}
+        }
+        self.touch();
+        Ok(())
+    }
+
+    /// Make sprint `id` depend on sprint `on`
+    ///
+    /// Both must exist, and the new edge may not close a dependency cycle.
+    pub fn set_dependency(&mut self, id: u32, on: u32) -> Result<()> {
+        let invalid = |reason: String| Err(crate::AutoFlowError::InvalidDependency(reason));
+        if id == on {
+            return invalid(format!("sprint {} can't depend on itself", id));
+        }
+        self.position(on)?;
+        let index = self.position(id)?;
+        if self.depends_on(on, id) {
+            return invalid(format!("sprint {} already depends on sprint {}, which would make a cycle", on, id));
+        }
+        let sprint = &mut self.sprints[index];
+        if !sprint.dependencies.contains(&on.to_string()) {
+            sprint.dependencies.push(on.to_string());
+            sprint.last_updated = Utc::now();
+            self.touch();
+        }
+        Ok(())
+    }
+
+    /// Number the sprints 1, 2, 3... in plan order; returns old id → new id
+    ///
+    /// Dependencies given as ids and `project.current_sprint` follow their sprint.
+    pub fn renumber_ids(&mut self) -> std::collections::BTreeMap<u32, u32> {
+        let mapping: std::collections::BTreeMap<u32, u32> =
+            self.sprints.iter().zip(1..).map(|(s, new)| (s.id, new)).collect();
+        let renamed = |dep: &String| {
+            dep.parse::<u32>()
+                .ok()
+                .and_then(|old| mapping.get(&old))
+                .map(|new| new.to_string())
+                .unwrap_or_else(|| dep.clone())
+        };
+        for sprint in &mut self.sprints {
+            sprint.id = mapping[&sprint.id];
+            sprint.dependencies = sprint.dependencies.iter().map(renamed).collect();
+        }
+        if let Some(current) = self.project.current_sprint {
+            self.project.current_sprint = mapping.get(&current).copied().or(Some(current));
+        }
+        self.touch();
+        mapping
+    }
+
+    pub fn sprint(&self, id: u32) -> Option<&Sprint> {
+        self.sprints.iter().find(|s| s.id == id)
+    }
+
+    #[deprecated(note = "edit sprints through the validated methods (update_status, skip_sprint, ...)")]
+    pub fn sprint_mut(&mut self, id: u32) -> Option<&mut Sprint> {
+        self.sprints.iter_mut().find(|s| s.id == id)
+    }
+
+    /// Skip sprint `id` with `reason`; see [`Sprint::skip`]. A closed sprint stays as it is
+    pub fn skip_sprint(&mut self, id: u32, reason: &str) -> Result<()> {
+        let sprint = self.edit(id)?;
+        if sprint.status.is_closed() {
+            return Err(crate::AutoFlowError::ValidationError(format!(
+                "Sprint {} is already {:?}",
+                id, sprint.status
+            )));
+        }
+        sprint.skip(reason);
+        Ok(())
+    }
+
+    /// Cancel sprint `id` with `reason`; see [`Sprint::cancel`]
+    pub fn cancel_sprint(&mut self, id: u32, reason: &str) -> Result<()> {
+        self.edit(id)?.cancel(reason)
+    }
+
+    /// Pause sprint `id`; returns the status it will resume at
+    pub fn pause_sprint(&mut self, id: u32) -> Result<SprintStatus> {
+        let sprint = self.edit(id)?;
+        sprint.pause()?;
+        Ok(sprint.paused_from.unwrap_or(SprintStatus::Pending))
+    }
+
+    /// Unpause sprint `id`; returns the status it is back at
+    pub fn unpause_sprint(&mut self, id: u32) -> Result<SprintStatus> {
+        self.edit(id)?.unpause()
+    }
+
+    /// Start counting sprint `id`'s fix attempts afresh, as after a human stepped in
+    ///
+    /// The reports recorded when it was blocked are dropped with the count.
+    pub fn reset_blocked_count(&mut self, id: u32) -> Result<()> {
+        let sprint = self.edit(id)?;
+        sprint.blocked_count = Some(0);
+        sprint.failure_reports.clear();
+        sprint.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Record that the blocker resolver ran on sprint `id`, so later failures block it
+    pub fn mark_blocker_resolver_used(&mut self, id: u32) -> Result<()> {
+        let sprint = self.edit(id)?;
+        sprint.uses_blocker_resolver = true;
+        sprint.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Put all of sprint `id`'s tasks back to PENDING, forgetting their progress
+    pub fn reset_tasks(&mut self, id: u32) -> Result<()> {
+        let sprint = self.edit(id)?;
+        for task in &mut sprint.tasks {
+            task.status = crate::TaskStatus::Pending;
+            task.committed_at = None;
+            task.reviewed_at = None;
+            task.tested_at = None;
+            task.done_at = None;
+            task.git_commit = None;
+        }
+        sprint.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Carry `old`'s progress over to sprint `id` of a replanned plan
+    ///
+    /// PENDING and BLOCKED sprints start over as PENDING; any other status, and
+    /// why a human held or closed the sprint, is kept. Uids of the sprint and of
+    /// tasks with the same id are kept too, so history and metrics stay linked.
+    pub fn carry_over(&mut self, id: u32, old: &Sprint) -> Result<()> {
+        let sprint = self.edit(id)?;
+        match old.status {
+            SprintStatus::Pending | SprintStatus::Blocked => sprint.status = SprintStatus::Pending,
+            _ => {
+                sprint.status = old.status;
+                sprint.paused_from = old.paused_from;
+                sprint.skipped = old.skipped.clone();
+                sprint.cancelled = old.cancelled.clone();
+            }
+        }
+        sprint.uid = old.uid.clone();
+        for task in &mut sprint.tasks {
+            if let Some(old_task) = old.tasks.iter().find(|t| t.id == task.id) {
+                task.uid = old_task.uid.clone();
+            }
+        }
+        sprint.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Sprint `id` for one of the validated edits above
+    fn edit(&mut self, id: u32) -> Result<&mut Sprint> {
+        let index = self.position(id)?;
+        self.touch();
+        Ok(&mut self.sprints[index])
+    }
+
+    fn position(&self, id: u32) -> Result<usize> {
+        self.sprints
+            .iter()
+            .position(|s| s.id == id)
+            .ok_or_else(|| crate::AutoFlowError::ValidationError(format!("No sprint {} in the plan", id)))
+    }
+
+    /// Whether sprint `id` depends on `on`, directly or through other sprints
+    fn depends_on(&self, id: u32, on: u32) -> bool {
+        let mut stack = vec![id];
+        let mut seen = std::collections::HashSet::new();
+        while let Some(current) = stack.pop() {
+            if !seen.insert(current) {
+                continue;
+            }
+            let Some(sprint) = self.sprint(current) else {
+                continue;
+            };
+            for dep in sprint.dependencies.iter().filter_map(|d| d.parse::<u32>().ok()) {
+                if dep == on {
+                    return true;
+                }
+                stack.push(dep);
+            }
+        }
+        false
+    }
+
+    fn touch(&mut self) {
+        self.project.total_sprints = self.sprints.len() as u32;
+        self.project.last_updated = Utc::now();
+    }
+
     pub fn filter_by_status(&self, status: SprintStatus) -> Vec<&Sprint> {
         self.sprints
             .iter()
@@ -206,6 +682,24 @@ pub struct ProjectMetadata {
     pub total_sprints: u32,
     pub current_sprint: Option<u32>,
     pub last_updated: DateTime<Utc>,
+
+    /// Repositories this plan's sprints run in (meta-project); empty when
+    /// the plan lives in the only repository
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub repos: Vec<RepoSpec>,
+}
+
+/// A repository a meta-project's sprints can target
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RepoSpec {
+    pub name: String,
+
+    /// Checkout location, relative to the directory holding `.autoflow/`
+    pub path: String,
+
+    /// Cloned into `path` when the checkout is missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
 }
 
 fn default_version() -> String {
@@ -216,31 +710,79 @@ fn default_description() -> String {
     "AutoFlow Project".to_string()
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum WorkflowType {
+    #[default]
     Implementation,
     Documentation,
     Test,
     Infrastructure,
     Refactor,
+    /// One-shot small change (`autoflow quick`): implement, test, review
+    Quick,
+    /// Release to an environment: approval, deploy, smoke checks, rollback on failure
+    Deployment,
+}
+
+impl WorkflowType {
+    pub const ALL: [WorkflowType; 7] = [
+        WorkflowType::Implementation,
+        WorkflowType::Documentation,
+        WorkflowType::Test,
+        WorkflowType::Infrastructure,
+        WorkflowType::Refactor,
+        WorkflowType::Quick,
+        WorkflowType::Deployment,
+    ];
 }
 
-impl Default for WorkflowType {
-    fn default() -> Self {
-        WorkflowType::Implementation
+impl std::fmt::Display for WorkflowType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            WorkflowType::Implementation => "implementation",
+            WorkflowType::Documentation => "documentation",
+            WorkflowType::Test => "test",
+            WorkflowType::Infrastructure => "infrastructure",
+            WorkflowType::Refactor => "refactor",
+            WorkflowType::Quick => "quick",
+            WorkflowType::Deployment => "deployment",
+        })
+    }
+}
+
+impl std::str::FromStr for WorkflowType {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase().replace('-', "_");
+        Self::ALL.into_iter().find(|w| w.to_string() == name).ok_or_else(|| {
+            let names: Vec<String> = Self::ALL.iter().map(|w| w.to_string()).collect();
+            format!("Unknown workflow '{}' (expected one of: {})", s, names.join(", "))
+        })
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Sprint {
     pub id: u32,
+
+    /// Stable identity (a ULID); unlike `id` it survives renumbering,
+    /// pivots and splits, so history and metrics link through it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+
     pub goal: String,
     pub status: SprintStatus,
 
     #[serde(default)]
     pub workflow_type: WorkflowType,
 
+    /// Custom workflow (`name` in `.autoflow/workflows/*.yml`) whose phases
+    /// replace the workflow type's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow: Option<String>,
+
     pub duration: Option<String>,
     pub total_effort: String,
     pub max_effort: String,
@@ -262,13 +804,41 @@ pub struct Sprint {
     #[serde(default)]
     pub must_complete_first: bool,
 
+    /// Scheduling priority; defaults to the highest task priority
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<crate::Priority>,
+
+    /// Business value of the sprint's feature, 1-10 (weighted scheduling)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<u32>,
+
     #[serde(default)]
     pub failure_reports: Vec<String>,
 
+    /// Repository (from `project.repos`) the sprint's phases run in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+
     /// Track if this sprint has been through blocker-resolver
     /// If true, failures will go back to BLOCKED instead of unit-fixer
     #[serde(default)]
     pub uses_blocker_resolver: bool,
+
+    /// Issue filed on the forge when the sprint stayed blocked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_url: Option<String>,
+
+    /// Why a human skipped the sprint without finishing it (`autoflow resolve`, `sprints skip`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<String>,
+
+    /// Why the sprint was cancelled (`sprints cancel`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancelled: Option<String>,
+
+    /// Status a PAUSED sprint returns to when it is resumed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paused_from: Option<SprintStatus>,
 }
 
 impl Sprint {
@@ -276,6 +846,99 @@ impl Sprint {
         matches!(self.status, SprintStatus::Done)
     }
 
+    /// Give up on the sprint: SKIPPED without its work, so dependents can run
+    ///
+    /// Bypasses the status rules on purpose; the reason stays on the sprint.
+    pub fn skip(&mut self, reason: &str) {
+        let now = Utc::now();
+        self.status = SprintStatus::Skipped;
+        self.skipped = Some(reason.to_string());
+        self.paused_from = None;
+        self.completed_at = Some(now);
+        self.last_updated = now;
+    }
+
+    /// Abandon the sprint; unlike a skipped sprint, its dependents keep waiting
+    pub fn cancel(&mut self, reason: &str) -> Result<()> {
+        self.set_status(SprintStatus::Cancelled)?;
+        self.cancelled = Some(reason.to_string());
+        self.paused_from = None;
+        Ok(())
+    }
+
+    /// Hold the sprint where it is until [`Sprint::unpause`]; runs pass it over
+    pub fn pause(&mut self) -> Result<()> {
+        let from = self.status;
+        self.set_status(SprintStatus::Paused)?;
+        if from != SprintStatus::Paused {
+            self.paused_from = Some(from);
+        }
+        Ok(())
+    }
+
+    /// Return a paused sprint to the status it was paused at
+    pub fn unpause(&mut self) -> Result<SprintStatus> {
+        if self.status != SprintStatus::Paused {
+            return Err(crate::AutoFlowError::ValidationError(format!("Sprint {} isn't paused", self.id)));
+        }
+        let status = self.paused_from.take().unwrap_or(SprintStatus::Pending);
+        self.set_status(status)?;
+        Ok(status)
+    }
+
+    fn set_status(&mut self, status: SprintStatus) -> Result<()> {
+        if !self.status.can_transition_to(status) {
+            return Err(crate::AutoFlowError::ValidationError(format!(
+                "Sprint {} can't move from {:?} to {:?}",
+                self.id, self.status, status
+            )));
+        }
+        self.status = status;
+        self.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Replace the tasks' review findings with those in a reviewer's output
+    ///
+    /// Findings for an unknown task id go to the only task when there is just
+    /// one, and are otherwise dropped (the failure report still has them).
+    /// Returns how many findings were attached.
+    pub fn record_review_findings(&mut self, output: &str) -> usize {
+        for task in &mut self.tasks {
+            task.review_findings.clear();
+        }
+
+        let mut attached = 0;
+        for (task_id, finding) in output.lines().filter_map(ReviewFinding::parse_line) {
+            let index = match self.tasks.iter().position(|t| t.id == task_id) {
+                Some(i) => Some(i),
+                None if self.tasks.len() == 1 => Some(0),
+                None => None,
+            };
+            if let Some(i) = index {
+                self.tasks[i].review_findings.push(finding);
+                attached += 1;
+            }
+        }
+        attached
+    }
+
+    /// Move every task forward to `status`; `commit` is recorded on tasks
+    /// reaching COMMITTED. Returns how many tasks moved.
+    pub fn advance_tasks(&mut self, status: TaskStatus, commit: Option<&str>) -> usize {
+        let now = Utc::now();
+        let mut moved = 0;
+        for task in &mut self.tasks {
+            if task.advance_to(status, now) {
+                moved += 1;
+                if status == TaskStatus::Committed && task.git_commit.is_none() {
+                    task.git_commit = commit.map(String::from);
+                }
+            }
+        }
+        moved
+    }
+
     pub fn advance(&mut self) -> Result<()> {
         if let Some(next_status) = self.status.next() {
             self.status = next_status;
@@ -300,9 +963,18 @@ pub enum SprintStatus {
     WriteE2eTests,
     RunE2eTests,
     E2eFix,
+    Deploy,
+    SmokeTest,
+    Rollback,
     Blocked,
     Complete,
     Done,
+    /// Held by a user; resumes at `paused_from`
+    Paused,
+    /// Deliberately not done; dependents still run
+    Skipped,
+    /// Abandoned; dependents wait
+    Cancelled,
 }
 
 impl SprintStatus {
@@ -319,9 +991,65 @@ impl SprintStatus {
             SprintStatus::WriteE2eTests => Some(SprintStatus::RunE2eTests),
             SprintStatus::RunE2eTests => Some(SprintStatus::Complete),
             SprintStatus::E2eFix => Some(SprintStatus::RunE2eTests),
+            SprintStatus::Deploy => Some(SprintStatus::SmokeTest),
+            SprintStatus::SmokeTest => Some(SprintStatus::Complete),
+            SprintStatus::Rollback => None,
             SprintStatus::Complete => Some(SprintStatus::Done),
             SprintStatus::Done => None,
             SprintStatus::Blocked => None,
+            SprintStatus::Paused | SprintStatus::Skipped | SprintStatus::Cancelled => None,
+        }
+    }
+
+    /// Whether a sprint may move from this status to `to`
+    ///
+    /// Phases move forward (a workflow skips the ones it doesn't use), a fix
+    /// phase returns to the check it fixes, any sprint can be blocked or reset
+    /// to PENDING, and a blocked sprint can resume anywhere short of DONE.
+    /// Any unfinished sprint can be paused, skipped or cancelled; a paused
+    /// sprint resumes like a blocked one, and a skipped or cancelled one only
+    /// reopens to PENDING.
+    pub fn can_transition_to(&self, to: SprintStatus) -> bool {
+        use SprintStatus::*;
+        match (*self, to) {
+            (from, to) if from == to => true,
+            (_, Pending) => true,
+            (Done | Skipped | Cancelled, _) => false,
+            (_, Paused | Skipped | Cancelled) => true,
+            (_, Blocked) => true,
+            (Blocked | Paused, to) => to != Done,
+            (ReviewFix, CodeReview) | (UnitFix, RunUnitTests) | (E2eFix, RunE2eTests) => true,
+            (from, to) => to as u8 > from as u8,
+        }
+    }
+
+    /// Nothing left to run: DONE, SKIPPED or CANCELLED
+    pub fn is_closed(&self) -> bool {
+        matches!(self, SprintStatus::Done | SprintStatus::Skipped | SprintStatus::Cancelled)
+    }
+
+    /// Whether sprints depending on one in this status may run
+    pub fn satisfies_dependents(&self) -> bool {
+        matches!(self, SprintStatus::Done | SprintStatus::Skipped)
+    }
+
+    /// Whether a run may pick the sprint up: not closed, and not held by a user
+    pub fn is_runnable(&self) -> bool {
+        !self.is_closed() && *self != SprintStatus::Paused
+    }
+
+    /// Status the sprint's tasks reach once this phase passes
+    ///
+    /// Writing tests starts the tasks; code is committed, reviewed and tested
+    /// in turn, and tasks are done with the sprint.
+    pub fn task_status(&self) -> Option<TaskStatus> {
+        match self {
+            SprintStatus::WriteUnitTests => Some(TaskStatus::InProgress),
+            SprintStatus::WriteCode => Some(TaskStatus::Committed),
+            SprintStatus::CodeReview => Some(TaskStatus::Reviewed),
+            SprintStatus::RunUnitTests | SprintStatus::RunE2eTests => Some(TaskStatus::Tested),
+            SprintStatus::Done => Some(TaskStatus::Done),
+            _ => None,
         }
     }
 
@@ -384,6 +1112,121 @@ mod tests {
         assert_eq!(SprintStatus::Blocked.next(), None);
     }
 
+    const PLAN: &str = r#"
+project:
+  name: Shop
+  total_sprints: 2
+  last_updated: 2025-01-01T00:00:00Z
+  current_sprint: 7
+sprints:
+  - id: 4
+    goal: Orders endpoint
+    status: PENDING
+    total_effort: 2h
+    max_effort: 4h
+    last_updated: 2025-01-01T00:00:00Z
+    deliverables: [API]
+    tasks:
+      - id: task-001
+        title: Build it
+        description: Build it
+        type: IMPLEMENTATION
+        status: PENDING
+        effort: 2h
+        priority: HIGH
+        feature: orders
+        docs: []
+        acceptance_criteria: [Works]
+        test_plan:
+          unit_tests: [it works]
+  - id: 7
+    goal: Orders page
+    status: PENDING
+    total_effort: 2h
+    max_effort: 4h
+    last_updated: 2025-01-01T00:00:00Z
+    deliverables: [API]
+    tasks:
+      - id: task-001
+        title: Build it
+        description: Build it
+        type: IMPLEMENTATION
+        status: PENDING
+        effort: 2h
+        priority: HIGH
+        feature: orders
+        docs: []
+        acceptance_criteria: [Works]
+        test_plan:
+          unit_tests: [it works]
+    dependencies: ["4"]
+"#;
+
+    #[test]
+    fn test_plan_mutations() {
+        let mut plan: SprintsYaml = serde_yaml::from_str(PLAN).unwrap();
+        let mut sprint = plan.sprints[0].clone();
+        sprint.id = 0;
+        sprint.goal = "Order emails".to_string();
+        assert_eq!(plan.add_sprint(sprint.clone(), Some(4)).unwrap(), 8);
+        assert_eq!(plan.sprints.iter().map(|s| s.id).collect::<Vec<_>>(), [4, 8, 7]);
+        assert_eq!(plan.project.total_sprints, 3);
+        sprint.id = 7;
+        assert!(plan.add_sprint(sprint, None).is_err());
+
+        plan.set_dependency(8, 7).unwrap();
+        assert!(matches!(plan.set_dependency(4, 8), Err(crate::AutoFlowError::InvalidDependency(_))));
+        assert!(plan.set_dependency(8, 8).is_err());
+
+        plan.update_status(4, SprintStatus::WriteCode).unwrap();
+        assert!(plan.update_status(4, SprintStatus::WriteUnitTests).is_err());
+        plan.update_status(4, SprintStatus::Blocked).unwrap();
+        plan.update_status(4, SprintStatus::RunUnitTests).unwrap();
+        plan.update_status(4, SprintStatus::UnitFix).unwrap();
+        plan.update_status(4, SprintStatus::RunUnitTests).unwrap();
+        plan.update_status(4, SprintStatus::Done).unwrap();
+        assert!(plan.sprint(4).unwrap().completed_at.is_some());
+        assert!(plan.update_status(4, SprintStatus::WriteCode).is_err());
+        assert!(plan.update_status(9, SprintStatus::WriteCode).is_err());
+
+        // A blocked sprint can't be declared DONE, only skipped
+        plan.update_status(7, SprintStatus::Blocked).unwrap();
+        assert!(plan.update_status(7, SprintStatus::Done).is_err());
+        plan.skip_sprint(7, "Replaced by sprint 8").unwrap();
+        assert_eq!(plan.sprint(7).unwrap().status, SprintStatus::Skipped);
+        assert!(plan.sprint(7).unwrap().status.satisfies_dependents());
+        assert_eq!(plan.sprint(7).unwrap().skipped.as_deref(), Some("Replaced by sprint 8"));
+        assert!(plan.update_status(7, SprintStatus::WriteCode).is_err());
+        assert!(plan.skip_sprint(7, "Again").is_err());
+
+        // Pausing remembers the phase to resume at; cancelling keeps dependents waiting
+        plan.update_status(8, SprintStatus::WriteCode).unwrap();
+        plan.update_status(8, SprintStatus::RunUnitTests).unwrap();
+        assert_eq!(plan.pause_sprint(8).unwrap(), SprintStatus::RunUnitTests);
+        assert!(!plan.sprint(8).unwrap().status.is_runnable());
+        assert_eq!(plan.unpause_sprint(8).unwrap(), SprintStatus::RunUnitTests);
+        assert!(plan.unpause_sprint(8).is_err());
+        plan.cancel_sprint(8, "Out of scope").unwrap();
+        let status = plan.sprint(8).unwrap().status;
+        assert!(status.is_closed() && !status.satisfies_dependents());
+        assert!(plan.pause_sprint(8).is_err());
+        plan.update_status(8, SprintStatus::Pending).unwrap();
+        plan.update_status(8, SprintStatus::Paused).unwrap();
+        assert_eq!(plan.sprint(8).unwrap().paused_from, Some(SprintStatus::Pending));
+
+        // Plans from before SKIPPED existed recorded skips as DONE
+        plan.sprints[2].status = SprintStatus::Done;
+        assert_eq!(plan.migrate_statuses(), [7]);
+        assert_eq!(plan.sprint(7).unwrap().status, SprintStatus::Skipped);
+
+        let mapping = plan.renumber_ids();
+        assert_eq!(mapping.into_iter().collect::<Vec<_>>(), [(4, 1), (7, 3), (8, 2)]);
+        assert_eq!(plan.sprints[1].dependencies, ["3"]);
+        assert_eq!(plan.sprints[2].dependencies, ["1"]);
+        assert_eq!(plan.project.current_sprint, Some(3));
+        assert!(plan.validate_errors().is_ok());
+    }
+
     #[test]
     fn test_retriable_statuses() {
         assert!(SprintStatus::ReviewFix.is_retriable());
@@ -399,4 +1242,188 @@ mod tests {
         assert_eq!(SprintStatus::E2eFix.max_retries(), 3);
         assert_eq!(SprintStatus::ReviewFix.max_retries(), 5);
     }
+
+    #[test]
+    fn test_sprint_repos_must_be_declared() {
+        let yaml = r#"
+project:
+  name: Shop
+  total_sprints: 2
+  last_updated: 2025-01-01T00:00:00Z
+  repos:
+    - name: api
+      path: ../shop-api
+      url: git@example.com:shop/api.git
+sprints:
+  - id: 1
+    goal: Orders endpoint
+    status: PENDING
+    total_effort: 2h
+    max_effort: 4h
+    last_updated: 2025-01-01T00:00:00Z
+    deliverables: []
+    tasks: []
+    repo: api
+  - id: 2
+    goal: Orders page
+    status: PENDING
+    total_effort: 2h
+    max_effort: 4h
+    last_updated: 2025-01-01T00:00:00Z
+    deliverables: []
+    tasks: []
+    repo: web
+"#;
+        let mut plan: SprintsYaml = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(plan.repo_for(&plan.sprints[0]).map(|r| r.path.as_str()), Some("../shop-api"));
+
+        let err = plan.check_repos().unwrap_err();
+        assert!(err.contains("sprint 2 targets undeclared repo 'web'"));
+
+        plan.sprints[1].repo = None;
+        assert!(plan.check_repos().is_ok());
+        assert!(plan.repo_for(&plan.sprints[1]).is_none());
+    }
+
+    #[test]
+    fn test_review_findings_attach_to_tasks() {
+        let yaml = r#"
+id: 4
+goal: Login
+status: CODE_REVIEW
+total_effort: 2h
+max_effort: 4h
+last_updated: 2025-01-01T00:00:00Z
+deliverables: []
+tasks:
+  - id: task-001
+    title: Login endpoint
+  - id: task-002
+    title: Password hashing
+"#;
+        let mut sprint: Sprint = serde_yaml::from_str(yaml).unwrap();
+        let output = "Found problems.\n\
+            REVIEW_FINDING: task-002 | high | Passwords are hashed with bcrypt | src/auth.rs:42 | Stored in plain text\n\
+            `REVIEW_FINDING: task-001 | LOW | - | - | No rate limiting`\n\
+            REVIEW_FINDING: task-009 | HIGH | - | - | Unknown task\n\
+            REVIEW_STATUS: FAILED\n";
+        assert_eq!(sprint.record_review_findings(output), 2);
+
+        let finding = &sprint.tasks[1].review_findings[0];
+        assert_eq!(finding.severity, crate::Priority::High);
+        assert_eq!(finding.criterion.as_deref(), Some("Passwords are hashed with bcrypt"));
+        assert_eq!(finding.file.as_deref(), Some("src/auth.rs:42"));
+        assert_eq!(sprint.tasks[0].review_findings[0].criterion, None);
+        assert_eq!(
+            finding.to_string(),
+            "[HIGH] Unmet: Passwords are hashed with bcrypt - Stored in plain text (src/auth.rs:42)"
+        );
+
+        // A passing review clears them
+        assert_eq!(sprint.record_review_findings("REVIEW_STATUS: PASSED"), 0);
+        assert!(sprint.tasks.iter().all(|t| t.review_findings.is_empty()));
+    }
+    #[test]
+    fn test_tasks_follow_phases() {
+        let yaml = "id: 4\ngoal: Login\nstatus: WRITE_CODE\ntotal_effort: 2h\nmax_effort: 4h\n\
+                    last_updated: 2025-01-01T00:00:00Z\ndeliverables: []\n\
+                    tasks:\n  - id: task-001\n    title: Login endpoint\n  - id: task-002\n    title: Hashing\n    status: TESTED\n";
+        let mut sprint: Sprint = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(sprint.advance_tasks(TaskStatus::InProgress, None), 1);
+
+        // Tasks already further along keep their place
+        let committed = SprintStatus::WriteCode.task_status().unwrap();
+        assert_eq!(sprint.advance_tasks(committed, Some("abc123")), 1);
+        assert_eq!(sprint.tasks[0].status, TaskStatus::Committed);
+        assert_eq!(sprint.tasks[0].git_commit.as_deref(), Some("abc123"));
+        assert!(sprint.tasks[0].committed_at.is_some());
+        assert_eq!(sprint.tasks[1].git_commit, None);
+
+        assert_eq!(sprint.advance_tasks(SprintStatus::RunUnitTests.task_status().unwrap(), None), 1);
+        assert!(sprint.tasks[0].tested_at.is_some() && sprint.tasks[0].reviewed_at.is_none());
+        assert_eq!(sprint.advance_tasks(SprintStatus::CodeReview.task_status().unwrap(), None), 0);
+        assert_eq!(SprintStatus::UnitFix.task_status(), None);
+
+        assert_eq!(sprint.advance_tasks(TaskStatus::Done, None), 2);
+        assert!(sprint.tasks.iter().all(|t| t.status == TaskStatus::Done && t.done_at.is_some()));
+    }
+
+    #[test]
+    fn test_parallel_sprint_updates_are_all_kept() {
+        let dir = std::env::temp_dir().join(format!("autoflow-plan-lock-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("SPRINTS.yml");
+        let plan: SprintsYaml = serde_yaml::from_str(PLAN).unwrap();
+        plan.save(&path).unwrap();
+
+        // Each thread saves its own sprint's progress from a stale copy of the plan
+        let handles: Vec<_> = plan
+            .sprints
+            .iter()
+            .cloned()
+            .map(|mut sprint| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    for status in [SprintStatus::WriteUnitTests, SprintStatus::WriteCode, SprintStatus::CodeReview] {
+                        sprint.status = status;
+                        SprintsYaml::update_sprint(&path, &sprint).unwrap().unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let saved = SprintsYaml::load(&path).unwrap();
+        assert!(saved.sprints.iter().all(|s| s.status == SprintStatus::CodeReview));
+        let mut unknown = plan.sprints[0].clone();
+        unknown.id = 99;
+        assert!(SprintsYaml::update_sprint(&path, &unknown).unwrap().is_none());
+        assert!(!fs::read_dir(&dir).unwrap().any(|e| e.unwrap().file_name().to_string_lossy().ends_with(".tmp")));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_uids_are_minted_on_save_only() {
+        let dir = std::env::temp_dir().join(format!("autoflow-plan-uid-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("SPRINTS.yml");
+        let plan: SprintsYaml = serde_yaml::from_str(PLAN).unwrap();
+        plan.save(&path).unwrap();
+
+        // Loading doesn't mint; what the first save wrote is what every load sees
+        assert!(plan.sprints.iter().all(|s| s.uid.is_none()));
+        let first = SprintsYaml::load(&path).unwrap();
+        let uids = |plan: &SprintsYaml| -> Vec<Option<String>> {
+            plan.sprints.iter().flat_map(|s| std::iter::once(s.uid.clone()).chain(s.tasks.iter().map(|t| t.uid.clone()))).collect()
+        };
+        assert!(uids(&first).iter().all(|uid| uid.as_ref().is_some_and(|u| u.len() == 26)));
+        assert_eq!(uids(&SprintsYaml::load(&path).unwrap()), uids(&first));
+
+        // Saving the uid-less copy again, or one sprint of it, keeps them
+        plan.save(&path).unwrap();
+        SprintsYaml::update_sprint(&path, &plan.sprints[1]).unwrap().unwrap();
+        assert_eq!(uids(&SprintsYaml::load(&path).unwrap()), uids(&first));
+
+        // A copied sprint doesn't take its original's uid
+        let mut copy = first.sprints[1].clone();
+        copy.id = 0;
+        let mut plan = first.clone();
+        let id = plan.add_sprint(copy, None).unwrap();
+        assert_eq!(plan.sprint(id).unwrap().uid, None);
+        assert!(plan.assign_uids());
+        assert!(!plan.assign_uids());
+        assert_eq!(uids(&plan)[..uids(&first).len()], uids(&first)[..]);
+
+        // Migrating an older plan writes its uids and nothing else
+        fs::write(&path, PLAN).unwrap();
+        let migrated = SprintsYaml::migrate_uids(&path).unwrap();
+        assert!(uids(&migrated).iter().all(Option::is_some));
+        assert_eq!(uids(&SprintsYaml::load(&path).unwrap()), uids(&migrated));
+        assert_eq!(uids(&SprintsYaml::migrate_uids(&path).unwrap()), uids(&migrated));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
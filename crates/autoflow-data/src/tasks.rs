@@ -1,9 +1,10 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TaskType {
+    #[default]
     Implementation,
     Documentation,
     Test,
@@ -12,16 +13,15 @@ pub enum TaskType {
     Bugfix,
 }
 
-impl Default for TaskType {
-    fn default() -> Self {
-        TaskType::Implementation
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
     #[serde(default = "generate_task_id")]
     pub id: String,
+
+    /// Stable identity (a ULID) that survives pivots and splits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+
     pub title: String,
 
     #[serde(default)]
@@ -57,6 +57,11 @@ pub struct Task {
     #[serde(default)]
     pub integration_notes: Option<String>,
 
+    /// Files and directories the task will create or change; tasks with
+    /// disjoint targets can be implemented in parallel
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+
     #[serde(default = "default_testing")]
     pub testing: TestingRequirements,
 
@@ -77,6 +82,32 @@ pub struct Task {
 
     #[serde(default)]
     pub git_commit: Option<String>,
+
+    /// Open findings from the last failed code review
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub review_findings: Vec<ReviewFinding>,
+}
+
+impl Task {
+    /// Move the task forward to `status`, stamping when it got there; a task
+    /// already past `status` is left alone. True when the task moved.
+    pub fn advance_to(&mut self, status: TaskStatus, at: DateTime<Utc>) -> bool {
+        if self.status >= status {
+            return false;
+        }
+        self.status = status;
+        let stamp = match status {
+            TaskStatus::Pending | TaskStatus::InProgress => None,
+            TaskStatus::Committed => Some(&mut self.committed_at),
+            TaskStatus::Reviewed => Some(&mut self.reviewed_at),
+            TaskStatus::Tested => Some(&mut self.tested_at),
+            TaskStatus::Done => Some(&mut self.done_at),
+        };
+        if let Some(stamp) = stamp {
+            stamp.get_or_insert(at);
+        }
+        true
+    }
 }
 
 fn generate_task_id() -> String {
@@ -105,9 +136,11 @@ fn default_testing() -> TestingRequirements {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+/// Task progress, in the order the pipeline moves tasks through it
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Default)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TaskStatus {
+    #[default]
     Pending,
     InProgress,
     Committed,
@@ -116,12 +149,6 @@ pub enum TaskStatus {
     Done,
 }
 
-impl Default for TaskStatus {
-    fn default() -> Self {
-        TaskStatus::Pending
-    }
-}
-
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Priority {
@@ -148,3 +175,69 @@ pub struct TestRequirement {
     pub required: bool,
     pub reason: String,
 }
+
+/// Marker the reviewer starts each structured finding line with
+pub const REVIEW_FINDING_MARKER: &str = "REVIEW_FINDING:";
+
+/// A reviewer finding against one task
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReviewFinding {
+    pub severity: Priority,
+
+    /// Acceptance criterion the finding shows is unmet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub criterion: Option<String>,
+
+    /// Where the problem is, e.g. `src/auth.rs:42`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+
+    pub issue: String,
+}
+
+/// `[HIGH] Unmet: <criterion> - <issue> (<file>)`
+impl std::fmt::Display for ReviewFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("[{:?}] ", self.severity).to_uppercase())?;
+        if let Some(ref criterion) = self.criterion {
+            write!(f, "Unmet: {} - ", criterion)?;
+        }
+        write!(f, "{}", self.issue)?;
+        if let Some(ref file) = self.file {
+            write!(f, " ({})", file)?;
+        }
+        Ok(())
+    }
+}
+
+impl ReviewFinding {
+    /// Parse `REVIEW_FINDING: <task id> | <severity> | <criterion> | <file> | <issue>`
+    ///
+    /// `-` leaves the criterion or file empty. Returns the task id with the finding.
+    pub fn parse_line(line: &str) -> Option<(String, Self)> {
+        let rest = line.trim().trim_matches('`').strip_prefix(REVIEW_FINDING_MARKER)?;
+        let fields: Vec<&str> = rest.splitn(5, '|').map(str::trim).collect();
+        let [task, severity, criterion, file, issue] = fields.as_slice() else {
+            return None;
+        };
+        if task.is_empty() || issue.is_empty() {
+            return None;
+        }
+        let optional = |value: &str| (!value.is_empty() && value != "-").then(|| value.to_string());
+        let severity = match severity.to_ascii_uppercase().as_str() {
+            "CRITICAL" => Priority::Critical,
+            "HIGH" => Priority::High,
+            "LOW" => Priority::Low,
+            _ => Priority::Medium,
+        };
+        Some((
+            task.to_string(),
+            Self {
+                severity,
+                criterion: optional(criterion),
+                file: optional(file),
+                issue: issue.to_string(),
+            },
+        ))
+    }
+}
@@ -0,0 +1,57 @@
+// ULIDs: time-sortable unique ids for runs, sprints and tasks
+
+use ring::rand::{SecureRandom, SystemRandom};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Alphabet of ULIDs (Crockford base32: no I, L, O or U)
+pub const CROCKFORD_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Generate a new ULID (26 Crockford base32 characters, sortable by time)
+pub fn new_ulid() -> String {
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 & 0xFFFF_FFFF_FFFF;
+
+    // 80 bits from the OS generator
+    let mut bytes = [0u8; 16];
+    SystemRandom::new().fill(&mut bytes[6..]).expect("system random generator failed");
+    let random = u128::from_be_bytes(bytes);
+
+    let mut id = String::with_capacity(26);
+    encode(millis as u128, 10, &mut id);
+    encode(random, 16, &mut id);
+    id
+}
+
+fn encode(value: u128, chars: u32, out: &mut String) {
+    for i in (0..chars).rev() {
+        out.push(CROCKFORD_ALPHABET[((value >> (i * 5)) & 0x1F) as usize] as char);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ulid_format() {
+        let id = new_ulid();
+        assert_eq!(id.len(), 26);
+        assert!(id.bytes().all(|b| CROCKFORD_ALPHABET.contains(&b)));
+        // 48 bits of milliseconds fit in 10 characters without overflowing the first
+        assert!(id.as_bytes()[0] <= b'7');
+    }
+
+    #[test]
+    fn test_ulids_sort_by_time() {
+        let earlier = new_ulid();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let later = new_ulid();
+        assert!(earlier < later);
+        assert!(earlier[..10] < later[..10]);
+    }
+
+    #[test]
+    fn test_ulids_are_unique() {
+        let ids: std::collections::HashSet<String> = (0..10_000).map(|_| new_ulid()).collect();
+        assert_eq!(ids.len(), 10_000);
+    }
+}
@@ -1,3 +1,8 @@
+pub mod remote;
 pub mod worktree;
 
-pub use worktree::{WorktreeManager, WorktreeInfo};
+pub use remote::{current_branch, push_branch, remote_provider, sprint_pull_request, GithubProvider, PullRequest, RemoteProvider};
+pub use worktree::{
+    bugfix_worktree_name, is_autoflow_branch, parse_age, StaleBranch, WorktreeInfo, WorktreeKind,
+    WorktreeManager, WorktreeOptions, BUGFIX_BASE_PORT, BUGFIX_PREFIX, SPRINT_BASE_PORT,
+};
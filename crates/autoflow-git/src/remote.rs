@@ -0,0 +1,196 @@
+use autoflow_data::{AutoFlowError, ForgeProvider, Result, Sprint, TaskStatus};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Pull request to open for a pushed branch
+#[derive(Debug, Clone, PartialEq)]
+pub struct PullRequest {
+    /// Branch with the changes
+    pub head: String,
+    /// Branch to merge into; the repository's default branch when unset
+    pub base: Option<String>,
+    pub title: String,
+    /// Markdown
+    pub body: String,
+    pub draft: bool,
+}
+
+/// Forge that hosts the repository's remote and takes pull requests
+pub trait RemoteProvider {
+    /// Provider name for logs
+    fn name(&self) -> &'static str;
+
+    /// URL of the open pull request for `head`, if there is one
+    fn find_pull_request(&self, checkout: &Path, head: &str) -> Result<Option<String>>;
+
+    /// Open `pr` and return its URL
+    fn open_pull_request(&self, checkout: &Path, pr: &PullRequest) -> Result<String>;
+}
+
+/// Provider for a forge; None when pull requests aren't supported there yet
+pub fn remote_provider(provider: ForgeProvider, repo: Option<String>) -> Option<Box<dyn RemoteProvider>> {
+    match provider {
+        ForgeProvider::Github => Some(Box::new(GithubProvider { repo })),
+        ForgeProvider::Gitlab => None,
+    }
+}
+
+/// GitHub, through the `gh` CLI (which must be installed and logged in)
+pub struct GithubProvider {
+    /// `owner/name`; the checkout's own repository when unset
+    pub repo: Option<String>,
+}
+
+impl GithubProvider {
+    fn gh(&self, checkout: &Path, args: &[&str]) -> Command {
+        let mut cmd = Command::new("gh");
+        cmd.args(args).current_dir(checkout);
+        if let Some(ref repo) = self.repo {
+            cmd.arg("--repo").arg(repo);
+        }
+        cmd
+    }
+}
+
+impl RemoteProvider for GithubProvider {
+    fn name(&self) -> &'static str {
+        "github"
+    }
+
+    fn find_pull_request(&self, checkout: &Path, head: &str) -> Result<Option<String>> {
+        let output = self
+            .gh(checkout, &["pr", "list", "--head", head, "--state", "open", "--json", "url", "--jq", ".[0].url"])
+            .output()
+            .map_err(|e| AutoFlowError::GitCommandFailed(format!("Failed to run gh (is it installed?): {}", e)))?;
+        if !output.status.success() {
+            return Err(AutoFlowError::GitCommandFailed(format!(
+                "gh pr list failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok((!url.is_empty()).then_some(url))
+    }
+
+    fn open_pull_request(&self, checkout: &Path, pr: &PullRequest) -> Result<String> {
+        // The body goes in on stdin; it can be longer than an argument may be
+        let mut cmd = self.gh(checkout, &["pr", "create", "--head", &pr.head, "--title", &pr.title, "--body-file", "-"]);
+        if let Some(ref base) = pr.base {
+            cmd.arg("--base").arg(base);
+        }
+        if pr.draft {
+            cmd.arg("--draft");
+        }
+        let mut child = cmd
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| AutoFlowError::GitCommandFailed(format!("Failed to run gh (is it installed?): {}", e)))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(pr.body.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(AutoFlowError::GitCommandFailed(format!(
+                "gh pr create failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .find(|line| line.starts_with("https://") || line.starts_with("http://"))
+            .map(String::from)
+            .ok_or_else(|| AutoFlowError::GitCommandFailed("gh didn't print the pull request's URL".to_string()))
+    }
+}
+
+/// Push `branch` from `checkout` to `remote`, tracking it there
+pub fn push_branch(checkout: &Path, remote: &str, branch: &str) -> Result<()> {
+    autoflow_data::ensure_writable("git push")?;
+    let output = Command::new("git")
+        .args(["push", "--set-upstream", remote, branch])
+        .current_dir(checkout)
+        .output()?;
+    if !output.status.success() {
+        return Err(AutoFlowError::GitCommandFailed(format!(
+            "git push {} {} failed: {}",
+            remote,
+            branch,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Branch checked out in `checkout`; None when HEAD is detached
+pub fn current_branch(checkout: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["symbolic-ref", "--quiet", "--short", "HEAD"])
+        .current_dir(checkout)
+        .output()
+        .ok()?;
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !branch.is_empty()).then_some(branch)
+}
+
+/// Pull request for a sprint's branch: its goal, tasks and test results
+///
+/// `tests` is the latest outcome of each test phase the sprint ran, keyed by phase name.
+pub fn sprint_pull_request(sprint: &Sprint, tests: &BTreeMap<String, bool>, head: &str, base: Option<String>, draft: bool) -> PullRequest {
+    let mut body = format!("**Sprint {}: {}**\n\n", sprint.id, sprint.goal);
+    body.push_str(&format!("Status: `{:?}` · Workflow: `{:?}`\n", sprint.status, sprint.workflow_type));
+
+    if !sprint.tasks.is_empty() {
+        body.push_str("\n## Tasks\n\n");
+        for task in &sprint.tasks {
+            let mark = if task.status == TaskStatus::Done { "x" } else { " " };
+            body.push_str(&format!("- [{}] **{}** {}\n", mark, task.id, task.title));
+        }
+    }
+
+    body.push_str("\n## Test Results\n\n");
+    if tests.is_empty() {
+        body.push_str("No test phases ran for this sprint.\n");
+    } else {
+        body.push_str("| Phase | Result |\n|---|---|\n");
+        for (phase, passed) in tests {
+            body.push_str(&format!("| {} | {} |\n", phase, if *passed { "✅ passed" } else { "❌ failed" }));
+        }
+    }
+
+    body.push_str("\n_Opened by AutoFlow._\n");
+    PullRequest {
+        head: head.to_string(),
+        base,
+        title: format!("Sprint {}: {}", sprint.id, sprint.goal),
+        body,
+        draft,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sprint_pull_request_body() {
+        let yaml = "id: 4\ngoal: Checkout flow\nstatus: DONE\ntotal_effort: 2h\nmax_effort: 4h\n\
+                    last_updated: 2025-01-01T00:00:00Z\ndeliverables: []\n\
+                    tasks:\n  - id: T1\n    title: Cart page\n    status: DONE\n  - id: T2\n    title: Payment form\n";
+        let sprint: Sprint = serde_yaml::from_str(yaml).unwrap();
+        let tests = BTreeMap::from([("RUN_E2E_TESTS".to_string(), false), ("RUN_UNIT_TESTS".to_string(), true)]);
+
+        let pr = sprint_pull_request(&sprint, &tests, "sprint-4", Some("main".to_string()), false);
+        assert_eq!(pr.title, "Sprint 4: Checkout flow");
+        assert_eq!((pr.head.as_str(), pr.base.as_deref()), ("sprint-4", Some("main")));
+        assert!(pr.body.contains("- [x] **T1** Cart page\n- [ ] **T2** Payment form\n"));
+        assert!(pr.body.contains("| RUN_E2E_TESTS | ❌ failed |\n| RUN_UNIT_TESTS | ✅ passed |\n"));
+
+        let pr = sprint_pull_request(&sprint, &BTreeMap::new(), "sprint-4", None, true);
+        assert!(pr.draft && pr.body.contains("No test phases ran"));
+    }
+}
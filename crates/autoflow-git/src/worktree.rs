@@ -3,27 +3,213 @@ use git2::{Repository, BranchType};
 use std::path::{Path, PathBuf};
 use std::fs;
 
+/// Sprint worktrees get port SPRINT_BASE_PORT + id * 10
+pub const SPRINT_BASE_PORT: u32 = 3000;
+
+/// Bugfix worktrees take the lowest free slot of BUGFIX_BASE_PORT + n * 10
+pub const BUGFIX_BASE_PORT: u32 = 4000;
+
+/// Prefix of bugfix worktree and branch names
+pub const BUGFIX_PREFIX: &str = "bugfix-";
+
+/// Kind of worktree, derived from its directory name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorktreeKind {
+    /// `sprint-<id>`
+    Sprint,
+    /// `bugfix-<slug>`
+    Bugfix,
+    /// Anything else (e.g. the main checkout)
+    Other,
+}
+
+impl WorktreeKind {
+    pub fn from_name(name: &str) -> Self {
+        if name.strip_prefix("sprint-").is_some_and(|id| id.parse::<u32>().is_ok()) {
+            WorktreeKind::Sprint
+        } else if name.starts_with(BUGFIX_PREFIX) {
+            WorktreeKind::Bugfix
+        } else {
+            WorktreeKind::Other
+        }
+    }
+}
+
+/// Worktree name for a bugfix slug (`login-fails` -> `bugfix-login-fails`)
+pub fn bugfix_worktree_name(slug: &str) -> String {
+    format!("{}{}", BUGFIX_PREFIX, slug.trim_start_matches(BUGFIX_PREFIX))
+}
+
+/// How new worktrees are branched
+#[derive(Debug, Clone)]
+pub struct WorktreeOptions {
+    /// Branch to start from. When the default `main` does not exist, `master`
+    /// and finally the current HEAD are used instead.
+    pub base_branch: String,
+    /// Remote to fetch from and track
+    pub remote: String,
+    /// Fetch the base branch before branching
+    pub fetch: bool,
+    /// Configure the new branch to push to a same-named branch on the remote
+    pub track: bool,
+}
+
+impl Default for WorktreeOptions {
+    fn default() -> Self {
+        Self {
+            base_branch: "main".to_string(),
+            remote: "origin".to_string(),
+            fetch: true,
+            track: true,
+        }
+    }
+}
+
 /// Git worktree manager
 pub struct WorktreeManager {
     repo: Repository,
+    /// Main working tree, or the git directory itself for bare repositories.
+    /// Git commands run here and sprint worktrees are created next to it.
+    root: PathBuf,
 }
 
 impl WorktreeManager {
     /// Create a new worktree manager for the current repository
+    ///
+    /// Works from the main checkout, from a linked worktree (sprint worktrees
+    /// are still created next to the main checkout), from a checkout with a
+    /// separate git dir, and from a bare repository.
     pub fn new<P: AsRef<Path>>(repo_path: P) -> Result<Self> {
         let repo = Repository::open(repo_path)?;
-        Ok(Self { repo })
+        let root = Self::resolve_root(&repo)?;
+        Ok(Self { repo, root })
     }
 
-    /// Create a new worktree for a sprint
+    /// Find the directory the repository is anchored at
+    fn resolve_root(repo: &Repository) -> Result<PathBuf> {
+        let common_dir = common_dir(repo);
+
+        // Bare repository, or a linked worktree of one: anchor at the git dir
+        let common_is_bare = repo.is_bare()
+            || Repository::open(&common_dir).map(|r| r.is_bare()).unwrap_or(false);
+        if common_is_bare {
+            return Ok(common_dir);
+        }
+
+        // Standard layout: the shared git dir is `<main checkout>/.git`
+        if common_dir.file_name().is_some_and(|n| n == ".git") {
+            if let Some(main) = common_dir.parent() {
+                return Ok(main.to_path_buf());
+            }
+        }
+
+        // Separate git dir (git init --separate-git-dir, GIT_DIR elsewhere)
+        repo.workdir().map(Path::to_path_buf).ok_or_else(|| {
+            AutoFlowError::ValidationError(format!(
+                "Unsupported git layout: {} has no working directory and is not a bare repository",
+                repo.path().display()
+            ))
+        })
+    }
+
+    /// Directory sprint worktrees are created next to
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Whether the repository has no working tree of its own
+    pub fn is_bare(&self) -> bool {
+        self.repo.is_bare()
+    }
+
+    /// Where a worktree with this name lives
+    pub fn worktree_path(&self, worktree_name: &str) -> PathBuf {
+        let parent = self.root.parent().unwrap_or(&self.root);
+        parent.join(worktree_name)
+    }
+
+    /// Create a new worktree for a sprint using the default options
     pub fn create_worktree(&self, sprint_id: u32, branch_name: &str) -> Result<WorktreeInfo> {
-        // Get repository path
-        let repo_path = self.repo.path().parent()
-            .ok_or_else(|| AutoFlowError::ValidationError("Invalid repository path".to_string()))?;
+        self.create_worktree_with(sprint_id, branch_name, &WorktreeOptions::default())
+    }
 
-        // Create worktree path
+    /// Create a new worktree for a sprint, branching from `options.base_branch`
+    pub fn create_worktree_with(
+        &self,
+        sprint_id: u32,
+        branch_name: &str,
+        options: &WorktreeOptions,
+    ) -> Result<WorktreeInfo> {
         let worktree_name = format!("sprint-{}", sprint_id);
-        let worktree_path = repo_path.join("..").join(&worktree_name);
+        let port = SPRINT_BASE_PORT + (sprint_id * 10);
+        self.add_worktree(worktree_name, branch_name, port, options)
+    }
+
+    /// Create an isolated worktree for a bug investigation
+    ///
+    /// The worktree and branch are both named `bugfix-<slug>`. Ports come from a
+    /// separate range so they never collide with sprint worktrees; the chosen
+    /// port is recorded in git config so `list_worktrees` reports it.
+    pub fn create_bugfix_worktree(&self, slug: &str, options: &WorktreeOptions) -> Result<WorktreeInfo> {
+        let worktree_name = bugfix_worktree_name(slug);
+        if worktree_name == BUGFIX_PREFIX {
+            return Err(AutoFlowError::ValidationError("Bugfix slug cannot be empty".to_string()));
+        }
+
+        let port = self.next_bugfix_port()?;
+        let branch_name = worktree_name.clone();
+        let info = self.add_worktree(worktree_name, &branch_name, port, options)?;
+        if let Err(e) = self.record_port(&info.name, port) {
+            tracing::warn!("Failed to record port for {}: {}", info.name, e);
+        }
+        Ok(info)
+    }
+
+    /// Find a worktree by directory name
+    pub fn find_worktree(&self, worktree_name: &str) -> Result<Option<WorktreeInfo>> {
+        Ok(self.list_worktrees()?.into_iter().find(|w| w.name == worktree_name))
+    }
+
+    /// Lowest bugfix port not used by an existing bugfix worktree
+    fn next_bugfix_port(&self) -> Result<u32> {
+        let used: Vec<u32> = self
+            .list_worktrees()?
+            .iter()
+            .filter(|w| WorktreeKind::from_name(&w.name) == WorktreeKind::Bugfix)
+            .map(|w| w.port)
+            .collect();
+        Ok((0..)
+            .map(|slot| BUGFIX_BASE_PORT + slot * 10)
+            .find(|port| !used.contains(port))
+            .unwrap_or(BUGFIX_BASE_PORT))
+    }
+
+    fn record_port(&self, worktree_name: &str, port: u32) -> Result<()> {
+        self.repo.config()?.set_i64(&format!("autoflow.{}.port", worktree_name), port as i64)?;
+        Ok(())
+    }
+
+    fn recorded_port(&self, worktree_name: &str) -> Option<u32> {
+        self.repo
+            .config()
+            .ok()?
+            .get_i64(&format!("autoflow.{}.port", worktree_name))
+            .ok()
+            .map(|p| p as u32)
+    }
+
+    /// Create the worktree directory and branch
+    fn add_worktree(
+        &self,
+        worktree_name: String,
+        branch_name: &str,
+        port: u32,
+        options: &WorktreeOptions,
+    ) -> Result<WorktreeInfo> {
+        autoflow_data::ensure_writable("creating a worktree")?;
+
+        // Create worktree path
+        let worktree_path = self.worktree_path(&worktree_name);
 
         // Check if worktree already exists
         if worktree_path.exists() {
@@ -32,29 +218,47 @@ impl WorktreeManager {
             ));
         }
 
-        // Get current branch to use as base
-        let head = self.repo.head()?;
-        let base_branch = head.shorthand()
-            .ok_or_else(|| AutoFlowError::ValidationError("Could not determine current branch".to_string()))?;
+        let has_remote = self.repo.find_remote(&options.remote).is_ok();
+        if has_remote && options.fetch {
+            self.fetch_base(options);
+        }
+        let start_point = self.resolve_start_point(options, has_remote);
 
-        tracing::info!("Creating worktree {} from branch {}", worktree_name, base_branch);
+        tracing::info!("Creating worktree {} from {}", worktree_name, start_point);
 
         // Create worktree with new branch using git command (git2 doesn't support worktree creation)
-        // The -b flag creates a new branch from HEAD
+        // --no-track stops git from making the base branch the upstream; see set_upstream
         let status = std::process::Command::new("git")
-            .args(&["worktree", "add", "-b", branch_name, worktree_path.to_str().unwrap(), "HEAD"])
-            .current_dir(repo_path)
+            .args(["worktree", "add", "--no-track", "-b", branch_name])
+            .arg(&worktree_path)
+            .arg(&start_point)
+            .current_dir(&self.root)
             .status()?;
 
         if !status.success() {
-            return Err(AutoFlowError::ValidationError(
+            return Err(AutoFlowError::GitCommandFailed(
                 "Failed to create git worktree".to_string()
             ));
         }
 
-        // Calculate unique port for this worktree
-        let base_port = 3000;
-        let port = base_port + (sprint_id * 10);
+        if has_remote && options.track {
+            if let Err(e) = self.set_upstream(branch_name, &options.remote) {
+                tracing::warn!("Failed to set upstream for {}: {}", branch_name, e);
+            }
+        }
+
+        // New worktrees start with empty submodule directories; check them out
+        // at the recorded commits so builds inside the worktree work
+        if self.has_submodules() {
+            tracing::info!("Initializing submodules in {}", worktree_path.display());
+            let status = std::process::Command::new("git")
+                .args(["submodule", "update", "--init", "--recursive"])
+                .current_dir(&worktree_path)
+                .status()?;
+            if !status.success() {
+                tracing::warn!("Failed to initialize submodules in {}", worktree_path.display());
+            }
+        }
 
         Ok(WorktreeInfo {
             name: worktree_name,
@@ -65,19 +269,79 @@ impl WorktreeManager {
         })
     }
 
+    /// Fetch the latest base branch; failures (offline, no such branch) only warn
+    fn fetch_base(&self, options: &WorktreeOptions) {
+        let status = std::process::Command::new("git")
+            .args(["fetch", "--quiet", &options.remote, &options.base_branch])
+            .current_dir(&self.root)
+            .status();
+        if !matches!(status, Ok(s) if s.success()) {
+            tracing::warn!("Could not fetch {}/{}; using local state", options.remote, options.base_branch);
+        }
+    }
+
+    /// Pick the commit-ish to branch from
+    ///
+    /// Prefers the remote-tracking branch unless the local branch has commits
+    /// the remote does not, so unpushed work is never silently dropped.
+    pub fn resolve_start_point(&self, options: &WorktreeOptions, has_remote: bool) -> String {
+        let mut candidates = vec![options.base_branch.as_str()];
+        if options.base_branch == "main" {
+            candidates.push("master");
+        }
+
+        for name in candidates {
+            let local = self.repo.find_branch(name, BranchType::Local)
+                .ok()
+                .and_then(|b| b.get().target());
+            let remote_name = format!("{}/{}", options.remote, name);
+            let remote = if has_remote {
+                self.repo.find_branch(&remote_name, BranchType::Remote)
+                    .ok()
+                    .and_then(|b| b.get().target())
+            } else {
+                None
+            };
+
+            match (local, remote) {
+                (Some(local), Some(remote)) => {
+                    let local_ahead = local != remote
+                        && !self.repo.graph_descendant_of(remote, local).unwrap_or(false);
+                    return if local_ahead { name.to_string() } else { remote_name };
+                }
+                (Some(_), None) => return name.to_string(),
+                (None, Some(_)) => return remote_name,
+                (None, None) => continue,
+            }
+        }
+
+        tracing::warn!("Base branch '{}' not found; branching from HEAD", options.base_branch);
+        "HEAD".to_string()
+    }
+
+    /// Make `git push` from the worktree publish to a same-named remote branch
+    fn set_upstream(&self, branch_name: &str, remote: &str) -> Result<()> {
+        let mut config = self.repo.config()?;
+        config.set_str(&format!("branch.{}.remote", branch_name), remote)?;
+        config.set_str(&format!("branch.{}.merge", branch_name), &format!("refs/heads/{}", branch_name))?;
+        Ok(())
+    }
+
+    /// Whether the repository declares any submodules
+    pub fn has_submodules(&self) -> bool {
+        self.repo.submodules().map(|s| !s.is_empty()).unwrap_or(false)
+    }
+
     /// List all worktrees
     pub fn list_worktrees(&self) -> Result<Vec<WorktreeInfo>> {
-        let repo_path = self.repo.path().parent()
-            .ok_or_else(|| AutoFlowError::ValidationError("Invalid repository path".to_string()))?;
-
         // Use git command to list worktrees
         let output = std::process::Command::new("git")
-            .args(&["worktree", "list", "--porcelain"])
-            .current_dir(repo_path)
+            .args(["worktree", "list", "--porcelain"])
+            .current_dir(&self.root)
             .output()?;
 
         if !output.status.success() {
-            return Err(AutoFlowError::ValidationError(
+            return Err(AutoFlowError::GitCommandFailed(
                 "Failed to list git worktrees".to_string()
             ));
         }
@@ -108,7 +372,9 @@ impl WorktreeManager {
                         None
                     };
 
-                    let port = sprint_id.map(|id| 3000 + (id * 10)).unwrap_or(3000);
+                    let port = self.recorded_port(&name)
+                        .or_else(|| sprint_id.map(|id| SPRINT_BASE_PORT + (id * 10)))
+                        .unwrap_or(SPRINT_BASE_PORT);
 
                     worktrees.push(WorktreeInfo {
                         name,
@@ -128,13 +394,11 @@ impl WorktreeManager {
 
     /// Delete a worktree
     pub fn delete_worktree(&self, worktree_name: &str) -> Result<()> {
-        let repo_path = self.repo.path().parent()
-            .ok_or_else(|| AutoFlowError::ValidationError("Invalid repository path".to_string()))?;
-
+        autoflow_data::ensure_writable("deleting a worktree")?;
         tracing::info!("Deleting worktree {}", worktree_name);
 
         // Get worktree path
-        let worktree_path = repo_path.join("..").join(worktree_name);
+        let worktree_path = self.worktree_path(worktree_name);
 
         if !worktree_path.exists() {
             return Err(AutoFlowError::ValidationError(
@@ -144,23 +408,37 @@ impl WorktreeManager {
 
         // Remove worktree using git command
         let status = std::process::Command::new("git")
-            .args(&["worktree", "remove", worktree_path.to_str().unwrap(), "--force"])
-            .current_dir(repo_path)
+            .args(["worktree", "remove"])
+            .arg(&worktree_path)
+            .arg("--force")
+            .current_dir(&self.root)
             .status()?;
 
         if !status.success() {
-            return Err(AutoFlowError::ValidationError(
+            return Err(AutoFlowError::GitCommandFailed(
                 "Failed to remove git worktree".to_string()
             ));
         }
 
+        // Forget the recorded port so the slot can be reused
+        if let Ok(mut config) = self.repo.config() {
+            let _ = config.remove(&format!("autoflow.{}.port", worktree_name));
+        }
+
         Ok(())
     }
 
     /// Merge a worktree branch back to main
     pub fn merge_worktree(&self, branch_name: &str) -> Result<()> {
+        autoflow_data::ensure_writable("git merge")?;
         tracing::info!("Merging branch {} to main", branch_name);
 
+        if self.repo.is_bare() {
+            return Err(AutoFlowError::ValidationError(
+                "Cannot merge in a bare repository; run the merge from a checkout of main".to_string()
+            ));
+        }
+
         // Checkout main branch
         let main_branch = self.repo.find_branch("main", BranchType::Local)
             .or_else(|_| self.repo.find_branch("master", BranchType::Local))?;
@@ -211,17 +489,15 @@ impl WorktreeManager {
 
     /// Prune merged worktrees
     pub fn prune_worktrees(&self) -> Result<Vec<String>> {
-        let repo_path = self.repo.path().parent()
-            .ok_or_else(|| AutoFlowError::ValidationError("Invalid repository path".to_string()))?;
-
+        autoflow_data::ensure_writable("pruning worktrees")?;
         // Prune using git command
         let status = std::process::Command::new("git")
-            .args(&["worktree", "prune"])
-            .current_dir(repo_path)
+            .args(["worktree", "prune"])
+            .current_dir(&self.root)
             .status()?;
 
         if !status.success() {
-            return Err(AutoFlowError::ValidationError(
+            return Err(AutoFlowError::GitCommandFailed(
                 "Failed to prune git worktrees".to_string()
             ));
         }
@@ -229,11 +505,115 @@ impl WorktreeManager {
         Ok(Vec::new()) // Return empty for now
     }
 
+    /// AutoFlow branches merged into `base_branch` whose last commit is older than `min_age`
+    ///
+    /// Only branches AutoFlow creates are considered (see `is_autoflow_branch`),
+    /// never the checked-out one. A branch counts as merged once it has
+    /// commits of its own and `base_branch` contains them; one that never
+    /// moved from where it was created is still in use, however old the
+    /// commit it started from. Each comes with its worktree, if it still has
+    /// one, the disk space that worktree takes and whether it has changes.
+    pub fn merged_branches(&self, base_branch: &str, min_age: chrono::Duration) -> Result<Vec<StaleBranch>> {
+        let base = self
+            .repo
+            .find_branch(base_branch, BranchType::Local)
+            .or_else(|e| match base_branch {
+                "main" => self.repo.find_branch("master", BranchType::Local),
+                _ => Err(e),
+            })?;
+        let base_name = base.name()?.unwrap_or(base_branch).to_string();
+        let base = base.get().peel_to_commit()?.id();
+        let head = self.repo.head().ok().and_then(|h| h.shorthand().map(str::to_string));
+        let worktrees = self.list_worktrees()?;
+        let cutoff = chrono::Utc::now() - min_age;
+
+        let mut stale = Vec::new();
+        for branch in self.repo.branches(Some(BranchType::Local))? {
+            let (branch, _) = branch?;
+            let Some(name) = branch.name()?.map(str::to_string) else {
+                continue;
+            };
+            if !is_autoflow_branch(&name) || name == base_name || head.as_deref() == Some(name.as_str()) {
+                continue;
+            }
+            let commit = branch.get().peel_to_commit()?;
+            if commit.id() != base && !self.repo.graph_descendant_of(base, commit.id())? {
+                continue;
+            }
+            if !self.has_own_commits(&name, commit.id()) {
+                continue;
+            }
+            let last_commit = chrono::DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or(cutoff);
+            if last_commit > cutoff {
+                continue;
+            }
+            let worktree = worktrees.iter().find(|w| w.branch == name && w.path != self.root);
+            stale.push(StaleBranch {
+                branch: name,
+                worktree: worktree.map(|w| w.path.clone()),
+                port: worktree.map(|w| w.port),
+                last_commit,
+                bytes: worktree.map(|w| dir_size(&w.path)).unwrap_or(0),
+                dirty: worktree.is_some_and(|w| is_dirty(&w.path)),
+            });
+        }
+        stale.sort_by_key(|b| b.last_commit);
+        Ok(stale)
+    }
+
+    /// Whether `name` moved on from the commit it was created at
+    ///
+    /// Read from the branch's reflog; without one there's no telling, so the
+    /// branch is taken to have nothing of its own yet.
+    fn has_own_commits(&self, name: &str, tip: git2::Oid) -> bool {
+        self.repo
+            .reflog(&format!("refs/heads/{}", name))
+            .ok()
+            .and_then(|log| log.iter().next_back().map(|created| created.id_new() != tip))
+            .unwrap_or(false)
+    }
+
+    /// Remove a stale branch: its worktree, the port recorded for it, then the branch
+    ///
+    /// Without `force`, a worktree with uncommitted changes is left alone and
+    /// the branch kept.
+    pub fn remove_stale_branch(&self, stale: &StaleBranch, force: bool) -> Result<()> {
+        autoflow_data::ensure_writable("deleting a branch")?;
+
+        if let Some(ref path) = stale.worktree {
+            let mut remove = std::process::Command::new("git");
+            remove.args(["worktree", "remove"]);
+            if force {
+                remove.arg("--force");
+            }
+            let status = remove.arg(path).current_dir(&self.root).status()?;
+            if !status.success() {
+                return Err(AutoFlowError::GitCommandFailed(format!(
+                    "Failed to remove worktree {}",
+                    path.display()
+                )));
+            }
+            if let (Some(name), Ok(mut config)) = (path.file_name().and_then(|n| n.to_str()), self.repo.config()) {
+                let _ = config.remove(&format!("autoflow.{}.port", name));
+            }
+        }
+
+        let status = std::process::Command::new("git")
+            .args(["branch", "--quiet", "-D", &stale.branch])
+            .current_dir(&self.root)
+            .status()?;
+        if !status.success() {
+            return Err(AutoFlowError::GitCommandFailed(format!("Failed to delete branch {}", stale.branch)));
+        }
+        Ok(())
+    }
+
     /// Setup environment for a worktree
     pub fn setup_worktree_env(&self, worktree_info: &WorktreeInfo) -> Result<()> {
-        // Copy docker-compose.yml if it exists
-        let repo_path = self.repo.path().parent()
-            .ok_or_else(|| AutoFlowError::ValidationError("Invalid repository path".to_string()))?;
+        // Copy docker-compose.yml if it exists (bare repositories have nothing to copy)
+        let Some(repo_path) = self.repo.workdir() else {
+            return Ok(());
+        };
 
         let docker_compose = repo_path.join("docker-compose.yml");
 
@@ -263,6 +643,81 @@ impl WorktreeManager {
     }
 }
 
+/// Git directory shared by all worktrees of `repo`
+///
+/// Linked worktrees have a private git dir whose `commondir` file points at the
+/// main one (git2 does not expose this, so read it the way git does).
+fn common_dir(repo: &Repository) -> PathBuf {
+    let git_dir = repo.path();
+    match fs::read_to_string(git_dir.join("commondir")) {
+        Ok(content) => {
+            let path = PathBuf::from(content.trim());
+            let path = if path.is_relative() { git_dir.join(path) } else { path };
+            path.canonicalize().unwrap_or(path)
+        }
+        Err(_) => git_dir.to_path_buf(),
+    }
+}
+
+/// Whether AutoFlow created the branch: `sprint-<id>`, `bugfix-*`, `quick/*` or `autoflow/*`
+pub fn is_autoflow_branch(name: &str) -> bool {
+    WorktreeKind::from_name(name) != WorktreeKind::Other
+        || name.starts_with("quick/")
+        || name.starts_with("autoflow/")
+}
+
+/// Age such as `14d`, `36h` or `2w`; a bare number means days
+pub fn parse_age(text: &str) -> Option<chrono::Duration> {
+    let text = text.trim();
+    let split = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    let amount: i64 = text[..split].parse().ok()?;
+    match &text[split..] {
+        "" | "d" => Some(chrono::Duration::days(amount)),
+        "h" => Some(chrono::Duration::hours(amount)),
+        "w" => Some(chrono::Duration::weeks(amount)),
+        _ => None,
+    }
+}
+
+/// Whether the checkout at `path` has changes removing it would lose
+fn is_dirty(path: &Path) -> bool {
+    let Ok(repo) = Repository::open(path) else {
+        return true;
+    };
+    let mut options = git2::StatusOptions::new();
+    options.include_untracked(true).include_ignored(false);
+    repo.statuses(Some(&mut options)).map(|s| !s.is_empty()).unwrap_or(true)
+}
+
+/// Bytes taken by the files under `path`; symlinks aren't followed
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) if meta.is_file() => meta.len(),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// A merged AutoFlow branch old enough to clean up
+#[derive(Debug, Clone)]
+pub struct StaleBranch {
+    pub branch: String,
+    /// Worktree the branch is checked out in, if any
+    pub worktree: Option<PathBuf>,
+    pub port: Option<u32>,
+    pub last_commit: chrono::DateTime<chrono::Utc>,
+    /// Disk space of the worktree
+    pub bytes: u64,
+    /// The worktree has uncommitted or untracked changes
+    pub dirty: bool,
+}
+
 /// Worktree information
 #[derive(Debug, Clone)]
 pub struct WorktreeInfo {
@@ -278,3 +733,171 @@ impl WorktreeInfo {
         self.path.display().to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("autoflow-wt-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    fn commit_initial(repo: &Repository) {
+        let signature = git2::Signature::now("AutoFlow", "autoflow@example.com").unwrap();
+        let tree_oid = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_oid).unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[]).unwrap();
+    }
+
+    #[test]
+    fn test_start_point_prefers_remote_unless_local_is_ahead() {
+        let dir = fixture_dir("start-point");
+        let repo = Repository::init(dir.join("app")).unwrap();
+        commit_initial(&repo);
+        let manager = WorktreeManager::new(dir.join("app")).unwrap();
+        let options = WorktreeOptions { base_branch: "main".to_string(), fetch: false, ..Default::default() };
+
+        // Freshly initialised repos may name the branch master; normalise to main
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("main", &head, true).unwrap();
+        assert_eq!(manager.resolve_start_point(&options, false), "main");
+
+        // Remote ahead of local: branch from the fetched state
+        let signature = git2::Signature::now("AutoFlow", "autoflow@example.com").unwrap();
+        let tree = head.tree().unwrap();
+        let newer = repo.commit(None, &signature, &signature, "remote", &tree, &[&head]).unwrap();
+        repo.remote("origin", "https://example.com/app.git").unwrap();
+        repo.reference("refs/remotes/origin/main", newer, true, "test").unwrap();
+        assert_eq!(manager.resolve_start_point(&options, true), "origin/main");
+
+        // Local ahead of remote: keep the unpushed commits
+        repo.reference("refs/remotes/origin/main", head.id(), true, "test").unwrap();
+        repo.reference("refs/heads/main", newer, true, "test").unwrap();
+        assert_eq!(manager.resolve_start_point(&options, true), "main");
+
+        let missing = WorktreeOptions { base_branch: "develop".to_string(), fetch: false, ..Default::default() };
+        assert_eq!(manager.resolve_start_point(&missing, true), "HEAD");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_worktree_kind_and_bugfix_names() {
+        assert_eq!(WorktreeKind::from_name("sprint-12"), WorktreeKind::Sprint);
+        assert_eq!(WorktreeKind::from_name("bugfix-login"), WorktreeKind::Bugfix);
+        assert_eq!(WorktreeKind::from_name("sprint-notes"), WorktreeKind::Other);
+        assert_eq!(bugfix_worktree_name("login-fails"), "bugfix-login-fails");
+        assert_eq!(bugfix_worktree_name("bugfix-login-fails"), "bugfix-login-fails");
+    }
+
+    #[test]
+    fn test_merged_branches_and_cleanup() {
+        let dir = fixture_dir("merged");
+        let main = dir.join("app");
+        let repo = Repository::init(&main).unwrap();
+        commit_initial(&repo);
+        let recent = repo.head().unwrap().peel_to_commit().unwrap();
+
+        let month_ago = chrono::Utc::now().timestamp() - 30 * 86400;
+        let old_signature = git2::Signature::new("AutoFlow", "autoflow@example.com", &git2::Time::new(month_ago, 0)).unwrap();
+        let tree = recent.tree().unwrap();
+        // sprint-2 gets a commit of its own, which main then takes in
+        repo.branch("sprint-2", &recent, false).unwrap();
+        let old = repo.commit(Some("refs/heads/sprint-2"), &old_signature, &old_signature, "old", &tree, &[&recent]).unwrap();
+        let old = repo.find_commit(old).unwrap();
+        repo.branch("quick/wip", &old, false).unwrap();
+        repo.commit(Some("refs/heads/quick/wip"), &old_signature, &old_signature, "wip", &tree, &[&old]).unwrap();
+
+        repo.branch("main", &old, true).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        // Created from main and its ancestors but not committed to: still in use
+        repo.branch("sprint-1", &recent, false).unwrap();
+        repo.branch("sprint-3", &old, false).unwrap();
+        repo.branch("feature", &old, false).unwrap();
+        let status = std::process::Command::new("git")
+            .args(["worktree", "add", "--quiet"])
+            .arg(dir.join("sprint-2"))
+            .arg("sprint-2")
+            .current_dir(&main)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        fs::write(dir.join("sprint-2/notes.txt"), "x".repeat(100)).unwrap();
+
+        let manager = WorktreeManager::new(&main).unwrap();
+        let all: Vec<String> = manager.merged_branches("main", chrono::Duration::zero()).unwrap().into_iter().map(|b| b.branch).collect();
+        assert_eq!(all, ["sprint-2"]);
+
+        let stale = manager.merged_branches("main", parse_age("14d").unwrap()).unwrap();
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].branch, "sprint-2");
+        assert_eq!(stale[0].worktree.as_deref(), Some(dir.join("sprint-2").as_path()));
+        assert!(stale[0].bytes >= 100);
+
+        // Uncommitted work keeps the worktree unless forced
+        assert!(stale[0].dirty);
+        assert!(manager.remove_stale_branch(&stale[0], false).is_err());
+        assert!(dir.join("sprint-2/notes.txt").exists());
+        assert!(repo.find_branch("sprint-2", BranchType::Local).is_ok());
+        manager.remove_stale_branch(&stale[0], true).unwrap();
+        assert!(!dir.join("sprint-2").exists());
+        assert!(repo.find_branch("sprint-2", BranchType::Local).is_err());
+        assert!(repo.find_branch("feature", BranchType::Local).is_ok());
+
+        assert_eq!(parse_age("36h"), Some(chrono::Duration::hours(36)));
+        assert_eq!(parse_age("2w"), Some(chrono::Duration::weeks(2)));
+        assert_eq!(parse_age("7"), Some(chrono::Duration::days(7)));
+        assert_eq!(parse_age("soon"), None);
+        assert!(is_autoflow_branch("bugfix-login") && is_autoflow_branch("autoflow/sync-docs"));
+        assert!(!is_autoflow_branch("sprint-notes"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_standard_checkout_root() {
+        let dir = fixture_dir("standard");
+        let main = dir.join("app");
+        Repository::init(&main).unwrap();
+
+        let manager = WorktreeManager::new(&main).unwrap();
+        assert_eq!(manager.root(), main.as_path());
+        assert_eq!(manager.worktree_path("sprint-1"), dir.join("sprint-1"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_bare_repo_root_and_merge_error() {
+        let dir = fixture_dir("bare");
+        let bare = dir.join("app.git");
+        Repository::init_bare(&bare).unwrap();
+
+        let manager = WorktreeManager::new(&bare).unwrap();
+        assert!(manager.is_bare());
+        assert_eq!(manager.root(), bare.as_path());
+        assert_eq!(manager.worktree_path("sprint-2"), dir.join("sprint-2"));
+
+        let err = manager.merge_worktree("sprint-2").unwrap_err().to_string();
+        assert!(err.contains("bare repository"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_linked_worktree_resolves_to_main_checkout() {
+        let dir = fixture_dir("linked");
+        let main = dir.join("app");
+        let repo = Repository::init(&main).unwrap();
+        commit_initial(&repo);
+
+        let linked = dir.join("feature");
+        repo.worktree("feature", &linked, None).unwrap();
+
+        // Opened from inside the linked worktree, sprint worktrees still sit next to main
+        let manager = WorktreeManager::new(&linked).unwrap();
+        assert_eq!(manager.root(), main.as_path());
+        assert_eq!(manager.worktree_path("sprint-3"), dir.join("sprint-3"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
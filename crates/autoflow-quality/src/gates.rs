@@ -13,11 +13,10 @@ impl QualityGate for SchemaValidationGate {
 
     fn run(&self, context: &GateContext) -> Result<GateResult> {
         // Try auto-fix BEFORE validation if enabled
-        if context.auto_fix {
-            if SchemaFixer::auto_fix(&context.sprints_path)? {
+        if context.auto_fix
+            && SchemaFixer::auto_fix(&context.sprints_path)? {
                 tracing::info!("Applied auto-fixes to SPRINTS.yml");
             }
-        }
 
         let validation = SchemaValidator::validate_sprints(&context.sprints_path)?;
 
@@ -75,13 +74,12 @@ impl QualityGate for OutputFormatGate {
 
         if !errors.is_empty() {
             // Try auto-fix if enabled
-            if context.auto_fix {
-                if SchemaFixer::auto_fix(&context.sprints_path)? {
+            if context.auto_fix
+                && SchemaFixer::auto_fix(&context.sprints_path)? {
                     return Ok(GateResult::pass(self.name().to_string())
                         .with_message("Output format issues auto-fixed".to_string())
                         .with_fixed());
                 }
-            }
 
             Ok(GateResult::fail(self.name().to_string(), errors))
         } else if !warnings.is_empty() {
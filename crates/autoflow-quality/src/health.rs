@@ -0,0 +1,476 @@
+use super::pipeline::{GateContext, GateResult, QualityGate};
+use autoflow_data::{HealthConfig, Result, Sprint};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Directory inside `.autoflow/` holding each sprint's latest test outcomes
+pub const TEST_RESULTS_DIR: &str = "test-results";
+
+/// Seconds an endpoint may take to answer
+const ENDPOINT_TIMEOUT_SECS: u64 = 10;
+
+/// Lines of build output kept in a failure report
+const MAX_OUTPUT_LINES: usize = 40;
+
+/// Latest outcome of each test phase a sprint ran, keyed by phase name
+///
+/// Written as the test phases finish, so completion never reruns the suite.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TestResults {
+    pub phases: BTreeMap<String, bool>,
+}
+
+impl TestResults {
+    pub fn path(workdir: &Path, sprint_id: u32) -> PathBuf {
+        workdir
+            .join(".autoflow")
+            .join(TEST_RESULTS_DIR)
+            .join(format!("sprint-{}.json", sprint_id))
+    }
+
+    /// Results recorded for the sprint; empty when it never ran tests
+    pub fn load(workdir: &Path, sprint_id: u32) -> Self {
+        let phases = autoflow_data::read_state_to_string(Self::path(workdir, sprint_id))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { phases }
+    }
+
+    /// Record the outcome of `phase`, replacing any earlier run of it
+    pub fn record(workdir: &Path, sprint_id: u32, phase: &str, passed: bool) -> Result<()> {
+        let mut results = Self::load(workdir, sprint_id);
+        results.phases.insert(phase.to_string(), passed);
+        let path = Self::path(workdir, sprint_id);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        autoflow_data::write_state(&path, serde_json::to_string_pretty(&results.phases)?)
+    }
+
+    pub fn failed(&self) -> Vec<&str> {
+        self.phases
+            .iter()
+            .filter(|(_, passed)| !**passed)
+            .map(|(phase, _)| phase.as_str())
+            .collect()
+    }
+}
+
+/// What a failed completion check points at, which decides where the sprint goes to fix it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthFailureClass {
+    /// The build command failed
+    Build,
+    /// A test phase's last run failed
+    Tests,
+    /// An endpoint didn't answer: the running environment, not the code
+    Environment,
+    /// A doc deliverable is missing or empty
+    Docs,
+    /// A project-specific check run by the health-check agent
+    Project,
+}
+
+/// One failed completion check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthFailure {
+    pub class: HealthFailureClass,
+    pub message: String,
+    /// The test phase that failed, for [`HealthFailureClass::Tests`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phase: Option<String>,
+}
+
+impl HealthFailure {
+    pub fn new(class: HealthFailureClass, message: impl Into<String>) -> Self {
+        Self { class, message: message.into(), phase: None }
+    }
+}
+
+/// A sprint's failed completion checks, kept as `.autoflow/.failures/sprint-<id>-health.json`
+/// beside the markdown report the fixers read
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub sprint: u32,
+    pub failures: Vec<HealthFailure>,
+}
+
+impl HealthReport {
+    pub fn path(workdir: &Path, sprint_id: u32) -> PathBuf {
+        workdir.join(".autoflow/.failures").join(format!("sprint-{}-health.json", sprint_id))
+    }
+
+    /// The sprint's last report; None once its checks pass
+    pub fn load(workdir: &Path, sprint_id: u32) -> Option<Self> {
+        autoflow_data::read_state_to_string(Self::path(workdir, sprint_id))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    pub fn save(&self, workdir: &Path) -> Result<()> {
+        let path = Self::path(workdir, self.sprint);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        autoflow_data::write_state(&path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn clear(workdir: &Path, sprint_id: u32) {
+        let _ = fs::remove_file(Self::path(workdir, sprint_id));
+    }
+
+    /// The markdown report, grouped by class; `route` says where the sprint went to fix it
+    pub fn to_markdown(&self, route: &str) -> String {
+        let mut md = format!("# Health Check Failure - Sprint {}
+
+{}
+", self.sprint, route);
+        for class in [
+            HealthFailureClass::Build,
+            HealthFailureClass::Tests,
+            HealthFailureClass::Docs,
+            HealthFailureClass::Project,
+            HealthFailureClass::Environment,
+        ] {
+            let failures: Vec<&HealthFailure> = self.failures.iter().filter(|f| f.class == class).collect();
+            if failures.is_empty() {
+                continue;
+            }
+            md.push_str(&format!("\n## {:?}\n\n", class));
+            for failure in failures {
+                md.push_str(&format!("- {}\n", failure.message.replace('\n', "\n  ")));
+            }
+        }
+        md
+    }
+}
+
+/// Build command implied by the project's manifest
+pub fn detect_build_command(project_root: &Path) -> Option<String> {
+    if project_root.join("Cargo.toml").exists() {
+        return Some("cargo build".to_string());
+    }
+    if project_root.join("go.mod").exists() {
+        return Some("go build ./...".to_string());
+    }
+    let package: serde_json::Value = fs::read_to_string(project_root.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())?;
+    package["scripts"]["build"].as_str()?;
+    let runner = if project_root.join("pnpm-lock.yaml").exists() {
+        "pnpm"
+    } else if project_root.join("yarn.lock").exists() {
+        "yarn"
+    } else {
+        "npm"
+    };
+    Some(format!("{} run build", runner))
+}
+
+/// True when `path` is documentation: Markdown or anything under `docs/`
+pub fn is_doc_path(path: &str) -> bool {
+    let path = path.trim_start_matches("./");
+    path.starts_with("docs/")
+        || [".md", ".mdx", ".rst", ".adoc"].iter().any(|ext| path.to_ascii_lowercase().ends_with(ext))
+}
+
+/// The Complete phase's checks: build, cached test results, endpoints and doc deliverables
+///
+/// Everything here is deterministic; project-specific checks listed in
+/// `[health] agent_checks` are left to the health-check agent.
+pub struct HealthCheckGate {
+    pub config: HealthConfig,
+    pub sprint_id: u32,
+    pub deliverables: Vec<String>,
+}
+
+impl HealthCheckGate {
+    pub fn for_sprint(config: HealthConfig, sprint: &Sprint) -> Self {
+        Self {
+            config,
+            sprint_id: sprint.id,
+            deliverables: sprint.deliverables.clone(),
+        }
+    }
+
+    /// Run every check: messages from the ones that passed, and the failures, classified
+    pub fn check(&self, root: &Path) -> (Vec<String>, Vec<HealthFailure>) {
+        let mut messages = Vec::new();
+        let mut failures = Vec::new();
+        self.check_build(root, &mut messages, &mut failures);
+        self.check_tests(root, &mut messages, &mut failures);
+        self.check_endpoints(&mut messages, &mut failures);
+        self.check_docs(root, &mut messages, &mut failures);
+        (messages, failures)
+    }
+
+    fn check_build(&self, root: &Path, messages: &mut Vec<String>, failures: &mut Vec<HealthFailure>) {
+        let Some(command) = self.config.build_command.clone().or_else(|| detect_build_command(root)) else {
+            messages.push("No build command configured or detected".to_string());
+            return;
+        };
+        match run_with_timeout(&command, root, Duration::from_secs(self.config.build_timeout_secs.max(1))) {
+            Ok(()) => messages.push(format!("`{}` succeeded", command)),
+            Err(reason) => failures.push(HealthFailure::new(HealthFailureClass::Build, format!("Build `{}` failed: {}", command, reason))),
+        }
+    }
+
+    fn check_tests(&self, root: &Path, messages: &mut Vec<String>, failures: &mut Vec<HealthFailure>) {
+        let tests = TestResults::load(root, self.sprint_id);
+        if tests.phases.is_empty() {
+            messages.push("No test results recorded for the sprint".to_string());
+            return;
+        }
+        let failed = tests.failed();
+        if failed.is_empty() {
+            messages.push(format!("Tests passed ({})", tests.phases.keys().cloned().collect::<Vec<_>>().join(", ")));
+        }
+        for phase in failed {
+            failures.push(HealthFailure {
+                phase: Some(phase.to_string()),
+                ..HealthFailure::new(HealthFailureClass::Tests, format!("Last test run failed: {}", phase))
+            });
+        }
+    }
+
+    fn check_endpoints(&self, messages: &mut Vec<String>, failures: &mut Vec<HealthFailure>) {
+        for url in &self.config.endpoints {
+            match probe_endpoint(url, Duration::from_secs(ENDPOINT_TIMEOUT_SECS)) {
+                Ok(()) => messages.push(format!("{} responded", url)),
+                Err(e) => failures.push(HealthFailure::new(
+                    HealthFailureClass::Environment,
+                    format!("{} did not respond: {}", url, e),
+                )),
+            }
+        }
+    }
+
+    fn check_docs(&self, root: &Path, messages: &mut Vec<String>, failures: &mut Vec<HealthFailure>) {
+        let docs: Vec<&String> = self.deliverables.iter().filter(|d| is_doc_path(d)).collect();
+        if docs.is_empty() {
+            return;
+        }
+        let missing: Vec<&str> = docs
+            .iter()
+            .filter(|doc| fs::metadata(root.join(doc.as_str())).map(|m| m.len() == 0).unwrap_or(true))
+            .map(|doc| doc.as_str())
+            .collect();
+        if missing.is_empty() {
+            messages.push(format!("{} doc deliverable(s) written", docs.len()));
+        } else {
+            failures.push(HealthFailure::new(
+                HealthFailureClass::Docs,
+                format!("Doc deliverables missing or empty: {}", missing.join(", ")),
+            ));
+        }
+    }
+}
+
+impl QualityGate for HealthCheckGate {
+    fn name(&self) -> &str {
+        "Health Check"
+    }
+
+    fn run(&self, context: &GateContext) -> Result<GateResult> {
+        let (messages, failures) = self.check(Path::new(&context.project_root));
+        let mut result = GateResult::pass(self.name().to_string());
+        result.messages = messages;
+        result.errors = failures.into_iter().map(|f| f.message).collect();
+        result.passed = result.errors.is_empty();
+        Ok(result)
+    }
+
+    fn is_critical(&self) -> bool {
+        true
+    }
+}
+
+/// GET `url`, following redirects; an error unless it answers 2xx within `timeout`
+///
+/// Runs on a thread of its own, as reqwest's blocking client can't be used on
+/// a thread that drives an async runtime.
+fn probe_endpoint(url: &str, timeout: Duration) -> std::result::Result<(), String> {
+    thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                let client = reqwest::blocking::Client::builder().timeout(timeout).build().map_err(|e| e.to_string())?;
+                let response = client.get(url).send().map_err(|e| e.to_string())?;
+                match response.status() {
+                    status if status.is_success() => Ok(()),
+                    status => Err(format!("HTTP {}", status)),
+                }
+            })
+            .join()
+            .unwrap_or_else(|_| Err("request panicked".to_string()))
+    })
+}
+
+/// Briefing for the health-check agent: only the project-specific checks
+pub fn agent_check_instructions(checks: &[String]) -> String {
+    let mut text = String::from(
+        "## Project-Specific Health Checks\n\n\
+         The build, tests, endpoints and doc deliverables have already passed. \
+         Verify only the following:\n\n",
+    );
+    for check in checks {
+        text.push_str(&format!("- {}\n", check));
+    }
+    text
+}
+
+/// Run `command` through the shell, killing it after `timeout`
+///
+/// Its output (stdout and stderr together) is read through a pipe; a background
+/// process still holding the pipe once the command ends only delays us a moment.
+fn run_with_timeout(command: &str, dir: &Path, timeout: Duration) -> std::result::Result<(), String> {
+    let mut child = Command::new("sh")
+        .args(["-c", &format!("exec 2>&1\n{}", command)])
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not start: {}", e))?;
+
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let reader = {
+        let output = Arc::clone(&output);
+        let mut pipe = child.stdout.take().expect("stdout is piped");
+        thread::spawn(move || {
+            let mut buf = [0u8; 8192];
+            while let Ok(n @ 1..) = pipe.read(&mut buf) {
+                output.lock().unwrap_or_else(|e| e.into_inner()).extend_from_slice(&buf[..n]);
+            }
+        })
+    };
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Ok(status),
+            Ok(None) if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                break Err(format!("timed out after {}s", timeout.as_secs()));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(200)),
+            Err(e) => break Err(e.to_string()),
+        }
+    };
+    let drained = Instant::now();
+    while !reader.is_finished() && drained.elapsed() < Duration::from_secs(1) {
+        thread::sleep(Duration::from_millis(20));
+    }
+    let output = String::from_utf8_lossy(&output.lock().unwrap_or_else(|e| e.into_inner())).into_owned();
+
+    let status = status?;
+    if status.success() {
+        return Ok(());
+    }
+    let lines: Vec<&str> = output.lines().collect();
+    let tail = lines[lines.len().saturating_sub(MAX_OUTPUT_LINES)..].join("\n");
+    let code = status.code().map(|c| c.to_string()).unwrap_or_else(|| "signal".to_string());
+    Err(format!("exit {}\n{}", code, tail))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("autoflow-health-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_health_check_gate() {
+        let root = fixture("gate");
+        fs::write(root.join("package.json"), r#"{"scripts": {"build": "tsc"}}"#).unwrap();
+        fs::write(root.join("yarn.lock"), "").unwrap();
+        assert_eq!(detect_build_command(&root).as_deref(), Some("yarn run build"));
+        fs::write(root.join("README.md"), "# App\n").unwrap();
+
+        let gate = HealthCheckGate {
+            config: HealthConfig {
+                build_command: Some("true".to_string()),
+                ..Default::default()
+            },
+            sprint_id: 3,
+            deliverables: vec!["src/app.ts".to_string(), "README.md".to_string()],
+        };
+        let context = GateContext::new(String::new(), root.display().to_string());
+        TestResults::record(&root, 3, "RUN_UNIT_TESTS", true).unwrap();
+        assert!(gate.run(&context).unwrap().passed);
+
+        // A failed test run, a failing build and a missing doc are all reported
+        TestResults::record(&root, 3, "RUN_E2E_TESTS", false).unwrap();
+        let gate = HealthCheckGate {
+            config: HealthConfig {
+                build_command: Some("echo broken >&2; exit 2".to_string()),
+                ..Default::default()
+            },
+            deliverables: vec!["docs/API.md".to_string()],
+            ..gate
+        };
+        let result = gate.run(&context).unwrap();
+        assert!(!result.passed);
+        assert_eq!(result.errors.len(), 3);
+        assert!(result.errors[0].contains("exit 2\nbroken"));
+        assert_eq!(result.errors[1], "Last test run failed: RUN_E2E_TESTS");
+        assert!(result.errors[2].contains("docs/API.md"));
+
+        // The same failures, classified for routing, survive a round trip through the JSON report
+        let (_, failures) = gate.check(&root);
+        let classes: Vec<HealthFailureClass> = failures.iter().map(|f| f.class).collect();
+        assert_eq!(classes, [HealthFailureClass::Build, HealthFailureClass::Tests, HealthFailureClass::Docs]);
+        assert_eq!(failures[1].phase.as_deref(), Some("RUN_E2E_TESTS"));
+        let report = HealthReport { sprint: 3, failures };
+        report.save(&root).unwrap();
+        assert_eq!(HealthReport::load(&root, 3), Some(report.clone()));
+        let md = report.to_markdown("Back to UNIT_FIX.");
+        assert!(md.contains("## Tests\n\n- Last test run failed: RUN_E2E_TESTS\n"));
+        HealthReport::clear(&root, 3);
+        assert!(HealthReport::load(&root, 3).is_none());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_probe_endpoint() {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        let serve = |status: &'static str| {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}/health", listener.local_addr().unwrap());
+            let server = thread::spawn(move || {
+                let (mut stream, _) = listener.accept().unwrap();
+                let _ = stream.read(&mut [0u8; 1024]);
+                write!(stream, "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status).unwrap();
+            });
+            (url, server)
+        };
+        let timeout = Duration::from_secs(5);
+
+        let (url, server) = serve("200 OK");
+        assert_eq!(probe_endpoint(&url, timeout), Ok(()));
+        server.join().unwrap();
+
+        let (url, server) = serve("503 Service Unavailable");
+        assert_eq!(probe_endpoint(&url, timeout), Err("HTTP 503 Service Unavailable".to_string()));
+        server.join().unwrap();
+
+        // Nothing listening
+        let closed = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        assert!(probe_endpoint(&format!("http://{}/health", closed), timeout).is_err());
+    }
+}
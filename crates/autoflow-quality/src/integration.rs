@@ -0,0 +1,212 @@
+use super::pipeline::{GateContext, GateResult, QualityGate};
+use autoflow_data::{IgnoreRules, IntegrationPoints, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Files that change as a side effect of dependency installs
+const LOCKFILES: &[&str] = &[
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "Cargo.lock",
+    "composer.lock",
+    "poetry.lock",
+    "Gemfile.lock",
+    "go.sum",
+];
+
+/// Difference between the files a sprint declared and the files it changed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IntegrationDelta {
+    /// Changed, but not covered by modifies/creates/tests_existing
+    pub unexpected: Vec<String>,
+    /// Declared in `creates` but not present
+    pub not_created: Vec<String>,
+    /// Declared in `modifies` but left unchanged
+    pub untouched: Vec<String>,
+    /// Every file the phase changed
+    pub changed: Vec<String>,
+}
+
+impl IntegrationDelta {
+    pub fn compute(points: &IntegrationPoints, changed: &[String], project_root: &Path) -> Self {
+        let declared: Vec<&String> = points
+            .modifies
+            .iter()
+            .chain(points.creates.iter())
+            .chain(points.tests_existing.iter())
+            .collect();
+
+        let unexpected = changed
+            .iter()
+            .filter(|file| !is_lockfile(file))
+            .filter(|file| !declared.iter().any(|d| covers(d, file)))
+            .cloned()
+            .collect();
+        let not_created = points
+            .creates
+            .iter()
+            .filter(|c| !project_root.join(normalize(c)).exists())
+            .cloned()
+            .collect();
+        let untouched = points
+            .modifies
+            .iter()
+            .filter(|m| !changed.iter().any(|file| covers(m, file)))
+            .cloned()
+            .collect();
+
+        Self { unexpected, not_created, untouched, changed: changed.to_vec() }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.unexpected.is_empty()
+    }
+
+    /// Report for the reviewer
+    pub fn to_markdown(&self, sprint_id: u32) -> String {
+        let list = |files: &[String]| files.iter().map(|f| format!("- `{}`", f)).collect::<Vec<_>>().join("\n");
+        let mut report = format!(
+            "# Integration Points Check - Sprint {}\n\nWRITE_CODE changed {} file(s) outside the sprint's declared integration_points.\n\n## Unexpected Modifications\n\n{}\n",
+            sprint_id,
+            self.unexpected.len(),
+            list(&self.unexpected)
+        );
+        if !self.not_created.is_empty() {
+            report.push_str(&format!("\n## Declared in `creates` but Missing\n\n{}\n", list(&self.not_created)));
+        }
+        if !self.untouched.is_empty() {
+            report.push_str(&format!("\n## Declared in `modifies` but Unchanged\n\n{}\n", list(&self.untouched)));
+        }
+        report.push_str(
+            "\n## Reviewer Action\n\nFor each unexpected file, decide whether the change is needed for the sprint goal.\nFail the review for changes that are unrelated or risky (they may collide with other sprints).\n",
+        );
+        report
+    }
+}
+
+/// Compares a phase's working-tree changes against a sprint's integration_points
+pub struct IntegrationPointsGate {
+    pub points: Option<IntegrationPoints>,
+}
+
+impl IntegrationPointsGate {
+    /// Delta for the current working tree; None when nothing was declared
+    pub fn check(&self, project_root: &Path) -> Result<Option<IntegrationDelta>> {
+        let Some(points) = &self.points else {
+            return Ok(None);
+        };
+        Ok(Some(IntegrationDelta::compute(points, &changed_files(project_root)?, project_root)))
+    }
+
+    /// Quality report entry for a computed delta (advisory: always passes)
+    pub fn result(&self, delta: Option<&IntegrationDelta>) -> GateResult {
+        let Some(delta) = delta else {
+            return GateResult::pass(self.name().to_string())
+                .with_message("Sprint declares no integration_points".to_string());
+        };
+
+        let mut result = GateResult::pass(self.name().to_string()).with_message(format!(
+            "{} changed file(s), {} outside integration_points",
+            delta.changed.len(),
+            delta.unexpected.len()
+        ));
+        for file in &delta.unexpected {
+            result = result.with_warning(format!("Unexpected modification: {}", file));
+        }
+        for file in &delta.not_created {
+            result = result.with_warning(format!("Declared but not created: {}", file));
+        }
+        result
+    }
+}
+
+impl QualityGate for IntegrationPointsGate {
+    fn name(&self) -> &str {
+        "Integration Points"
+    }
+
+    fn run(&self, context: &GateContext) -> Result<GateResult> {
+        let delta = self.check(Path::new(&context.project_root))?;
+        Ok(self.result(delta.as_ref()))
+    }
+
+    fn is_critical(&self) -> bool {
+        false
+    }
+}
+
+/// Uncommitted changes in the working tree, excluding AutoFlow's own state and `.autoflowignore`
+pub fn changed_files(project_root: &Path) -> Result<Vec<String>> {
+    let output = Command::new("git")
+        .args(["status", "--porcelain", "--untracked-files=all"])
+        .current_dir(project_root)
+        .output()?;
+    if !output.status.success() {
+        return Ok(Vec::new());
+    }
+    let mut files: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| line.len() > 3)
+        .map(|line| {
+            let path = &line[3..];
+            // Renames are reported as "old -> new"
+            path.rsplit(" -> ").next().unwrap_or(path).trim_matches('"').to_string()
+        })
+        .filter(|path| !path.starts_with(".autoflow/"))
+        .collect();
+    files.sort();
+    files.dedup();
+    Ok(IgnoreRules::load(project_root).filter(files))
+}
+
+fn normalize(path: &str) -> &str {
+    path.trim_start_matches("./")
+        .trim_end_matches("/**")
+        .trim_end_matches("/*")
+        .trim_end_matches('/')
+}
+
+/// Declared path equals the file or is a directory containing it
+fn covers(declared: &str, file: &str) -> bool {
+    let declared = normalize(declared);
+    let file = normalize(file);
+    !declared.is_empty() && (file == declared || file.starts_with(&format!("{}/", declared)))
+}
+
+fn is_lockfile(file: &str) -> bool {
+    let name = file.rsplit('/').next().unwrap_or(file);
+    LOCKFILES.contains(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delta_flags_undeclared_changes() {
+        let points = IntegrationPoints {
+            modifies: vec!["src/routes.ts".to_string(), "src/config.ts".to_string()],
+            creates: vec!["src/auth/".to_string()],
+            tests_existing: vec!["tests/routes.test.ts".to_string()],
+            patterns: vec![],
+        };
+        let changed: Vec<String> = [
+            "package-lock.json",
+            "src/auth/login.ts",
+            "src/billing/invoice.ts",
+            "src/routes.ts",
+            "tests/routes.test.ts",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let delta = IntegrationDelta::compute(&points, &changed, &std::env::temp_dir().join("autoflow-no-such-project"));
+        assert_eq!(delta.unexpected, vec!["src/billing/invoice.ts"]);
+        assert_eq!(delta.untouched, vec!["src/config.ts"]);
+        assert_eq!(delta.not_created, vec!["src/auth/"]);
+        assert!(!delta.is_clean());
+        assert!(delta.to_markdown(4).contains("`src/billing/invoice.ts`"));
+    }
+}
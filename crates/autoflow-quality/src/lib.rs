@@ -1,7 +1,13 @@
 pub mod schema_validator;
 pub mod pipeline;
 pub mod gates;
+pub mod migrations;
+pub mod integration;
+pub mod health;
 
 pub use schema_validator::{SchemaValidator, SchemaFixer, ValidationResult, ValidationError};
 pub use pipeline::{QualityGate, QualityPipeline, GateContext, GateResult, QualityReport};
 pub use gates::create_default_pipeline;
+pub use migrations::{touches_data_model, MigrationGate, MigrationTool, MIGRATION_DB_ENV};
+pub use integration::{changed_files, IntegrationDelta, IntegrationPointsGate};
+pub use health::{agent_check_instructions, detect_build_command, HealthCheckGate, HealthFailure, HealthFailureClass, HealthReport, TestResults};
@@ -0,0 +1,287 @@
+use super::pipeline::{GateContext, GateResult, QualityGate};
+use autoflow_data::{Result, Sprint};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Throwaway database URL for tools that can't use a temporary SQLite file
+pub const MIGRATION_DB_ENV: &str = "AUTOFLOW_MIGRATION_DATABASE_URL";
+
+/// Lines of tool output kept in a failure report
+const MAX_OUTPUT_LINES: usize = 60;
+
+/// Database migration tools AutoFlow knows how to drive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationTool {
+    /// Laravel `php artisan migrate`
+    Artisan,
+    Prisma,
+    Diesel,
+    Alembic,
+}
+
+impl MigrationTool {
+    /// Detect the project's migration tool from its marker files
+    pub fn detect(project_root: &Path) -> Option<Self> {
+        if project_root.join("artisan").exists() && project_root.join("database/migrations").is_dir() {
+            Some(Self::Artisan)
+        } else if project_root.join("prisma/schema.prisma").exists() {
+            Some(Self::Prisma)
+        } else if project_root.join("diesel.toml").exists() {
+            Some(Self::Diesel)
+        } else if project_root.join("alembic.ini").exists() {
+            Some(Self::Alembic)
+        } else {
+            None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Artisan => "Laravel (artisan)",
+            Self::Prisma => "Prisma",
+            Self::Diesel => "Diesel",
+            Self::Alembic => "Alembic",
+        }
+    }
+
+    /// Where migration files live, relative to the project root
+    pub fn migrations_dir(&self) -> &'static str {
+        match self {
+            Self::Artisan => "database/migrations",
+            Self::Prisma => "prisma/migrations",
+            Self::Diesel => "migrations",
+            Self::Alembic => "alembic/versions",
+        }
+    }
+
+    /// How agents should create and apply migrations with this tool
+    pub fn agent_instructions(&self) -> String {
+        let (create, apply) = match self {
+            Self::Artisan => ("php artisan make:migration <name>", "php artisan migrate"),
+            Self::Prisma => ("edit prisma/schema.prisma, then npx prisma migrate dev --name <name>", "npx prisma migrate deploy"),
+            Self::Diesel => ("diesel migration generate <name> (write up.sql and down.sql)", "diesel migration run"),
+            Self::Alembic => ("alembic revision --autogenerate -m \"<name>\"", "alembic upgrade head"),
+        };
+        format!(
+            r#"# Database Migrations
+
+This project uses {} migrations in `{}/`.
+- Every data model change needs a NEW migration: {}
+- Never edit a migration that has already been committed; add another one
+- Apply migrations locally with: {}
+- Migrations must apply cleanly to an empty database - AutoFlow verifies this before tests run
+"#,
+            self.name(),
+            self.migrations_dir(),
+            create,
+            apply
+        )
+    }
+
+    /// Whether the project's database can be swapped for a temporary SQLite file
+    fn supports_sqlite(&self, project_root: &Path) -> bool {
+        match self {
+            Self::Artisan | Self::Alembic => true,
+            Self::Prisma => fs::read_to_string(project_root.join("prisma/schema.prisma"))
+                .map(|s| s.contains("\"sqlite\""))
+                .unwrap_or(false),
+            Self::Diesel => fs::read_to_string(project_root.join("Cargo.toml"))
+                .map(|s| s.contains("sqlite"))
+                .unwrap_or(false),
+        }
+    }
+
+    /// Command that applies every migration to the database at `url`
+    fn apply_command(&self, url: &str, sqlite_path: Option<&Path>) -> Command {
+        let mut cmd = match self {
+            Self::Artisan => {
+                let mut cmd = Command::new("php");
+                cmd.args(["artisan", "migrate", "--force", "--no-interaction"]);
+                match sqlite_path {
+                    Some(path) => cmd.env("DB_CONNECTION", "sqlite").env("DB_DATABASE", path),
+                    None => cmd.env("DB_URL", url).env("DATABASE_URL", url),
+                };
+                cmd
+            }
+            Self::Prisma => {
+                let mut cmd = Command::new("npx");
+                cmd.args(["prisma", "migrate", "deploy"]);
+                cmd
+            }
+            Self::Diesel => {
+                let mut cmd = Command::new("diesel");
+                cmd.args(["migration", "run", "--database-url", url]);
+                cmd
+            }
+            Self::Alembic => {
+                let mut cmd = Command::new("alembic");
+                cmd.args(["-x", &format!("sqlalchemy.url={}", url), "upgrade", "head"]);
+                cmd
+            }
+        };
+        cmd.env("DATABASE_URL", url);
+        cmd
+    }
+}
+
+/// Whether a sprint is likely to change the database schema
+pub fn touches_data_model(sprint: &Sprint) -> bool {
+    const KEYWORDS: &[&str] = &["migration", "schema", "database", "data model", "table", "column"];
+    let mentions = |text: &str| {
+        let text = text.to_lowercase();
+        KEYWORDS.iter().any(|k| text.contains(k))
+    };
+
+    mentions(&sprint.goal)
+        || sprint.deliverables.iter().any(|d| mentions(d))
+        || sprint.tasks.iter().any(|task| {
+            task.docs.iter().any(|d| d.starts_with("DATA_MODEL.md"))
+                || mentions(&task.title)
+                || task.description.as_deref().is_some_and(mentions)
+        })
+}
+
+/// Database that exists only for one verification run
+struct DisposableDatabase {
+    url: String,
+    sqlite_path: Option<PathBuf>,
+}
+
+impl DisposableDatabase {
+    fn for_tool(tool: MigrationTool, project_root: &Path) -> Option<Self> {
+        if let Ok(url) = std::env::var(MIGRATION_DB_ENV) {
+            if !url.trim().is_empty() {
+                return Some(Self { url, sqlite_path: None });
+            }
+        }
+        if !tool.supports_sqlite(project_root) {
+            return None;
+        }
+        let path = std::env::temp_dir().join(format!(
+            "autoflow-migrations-{}-{}.sqlite",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or_default()
+        ));
+        fs::write(&path, b"").ok()?;
+        let url = match tool {
+            MigrationTool::Prisma => format!("file:{}", path.display()),
+            MigrationTool::Alembic => format!("sqlite:///{}", path.display()),
+            _ => path.display().to_string(),
+        };
+        Some(Self { url, sqlite_path: Some(path) })
+    }
+}
+
+impl Drop for DisposableDatabase {
+    fn drop(&mut self) {
+        if let Some(path) = &self.sqlite_path {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Verifies migrations apply cleanly against a disposable database
+pub struct MigrationGate;
+
+impl QualityGate for MigrationGate {
+    fn name(&self) -> &str {
+        "Database Migrations"
+    }
+
+    fn run(&self, context: &GateContext) -> Result<GateResult> {
+        let root = Path::new(&context.project_root);
+        let Some(tool) = MigrationTool::detect(root) else {
+            return Ok(GateResult::pass(self.name().to_string())
+                .with_message("No migration tool detected".to_string()));
+        };
+
+        let Some(db) = DisposableDatabase::for_tool(tool, root) else {
+            return Ok(GateResult::pass(self.name().to_string()).with_warning(format!(
+                "{} migrations not verified: set {} to a throwaway database URL",
+                tool.name(),
+                MIGRATION_DB_ENV
+            )));
+        };
+
+        let output = match tool
+            .apply_command(&db.url, db.sqlite_path.as_deref())
+            .current_dir(root)
+            .output()
+        {
+            Ok(output) => output,
+            Err(e) => {
+                return Ok(GateResult::pass(self.name().to_string())
+                    .with_warning(format!("{} migrations not verified: {}", tool.name(), e)));
+            }
+        };
+
+        if output.status.success() {
+            Ok(GateResult::pass(self.name().to_string()).with_message(format!(
+                "{} migrations apply cleanly to an empty database",
+                tool.name()
+            )))
+        } else {
+            let combined = format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            let lines: Vec<&str> = combined.lines().collect();
+            let tail = lines[lines.len().saturating_sub(MAX_OUTPUT_LINES)..].join("\n");
+            Ok(GateResult::fail(
+                self.name().to_string(),
+                vec![format!("{} migrations failed to apply:\n{}", tool.name(), tail)],
+            ))
+        }
+    }
+
+    fn is_critical(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("autoflow-migrations-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_detects_migration_tools() {
+        let laravel = fixture("laravel");
+        fs::write(laravel.join("artisan"), "").unwrap();
+        fs::create_dir_all(laravel.join("database/migrations")).unwrap();
+        assert_eq!(MigrationTool::detect(&laravel), Some(MigrationTool::Artisan));
+
+        let prisma = fixture("prisma");
+        fs::create_dir_all(prisma.join("prisma")).unwrap();
+        fs::write(prisma.join("prisma/schema.prisma"), "provider = \"postgresql\"").unwrap();
+        assert_eq!(MigrationTool::detect(&prisma), Some(MigrationTool::Prisma));
+        assert!(!MigrationTool::Prisma.supports_sqlite(&prisma));
+
+        let none = fixture("none");
+        assert_eq!(MigrationTool::detect(&none), None);
+
+        for dir in [laravel, prisma, none] {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+
+    #[test]
+    fn test_gate_passes_without_migration_tool() {
+        let dir = fixture("gate");
+        let context = GateContext::new(String::new(), dir.display().to_string());
+        let result = MigrationGate.run(&context).unwrap();
+        assert!(result.passed);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -118,6 +118,12 @@ pub struct QualityReport {
     pub passed: bool,
 }
 
+impl Default for QualityReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl QualityReport {
     pub fn new() -> Self {
         Self {
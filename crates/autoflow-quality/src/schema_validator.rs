@@ -17,6 +17,12 @@ pub struct ValidationError {
     pub message: String,
 }
 
+impl Default for ValidationResult {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ValidationResult {
     pub fn new() -> Self {
         Self {
@@ -60,8 +66,8 @@ impl SchemaValidator {
     /// Load default SPRINTS.yml schema
     pub fn sprints_schema() -> Result<Self> {
         // Try to load from ~/.autoflow/schemas/ first, then from crate
-        let home_schema = dirs::home_dir()
-            .map(|h| h.join(".autoflow/schemas/sprints.schema.json"));
+        let home_schema = autoflow_data::autoflow_dir()
+            .map(|d| d.join("schemas/sprints.schema.json"));
 
         if let Some(path) = home_schema {
             if path.exists() {
@@ -200,6 +206,9 @@ impl SchemaFixer {
             .replace("status: UnitFix", "status: UNIT_FIX")
             .replace("status: RunE2eTests", "status: RUN_E2E_TESTS")
             .replace("status: E2eFix", "status: E2E_FIX")
+            .replace("status: Paused", "status: PAUSED")
+            .replace("status: Skipped", "status: SKIPPED")
+            .replace("status: Cancelled", "status: CANCELLED")
     }
 }
 
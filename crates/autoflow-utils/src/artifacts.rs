@@ -0,0 +1,233 @@
+/// Run artifacts: upload a run's summary, events and reports to S3 or GCS
+///
+/// With `[artifacts] destination` set, everything lands under
+/// `<destination>/<run-id>/`: the run summary, the run's events, the board,
+/// failure reports and any report directories (Playwright, coverage) that
+/// exist. Copies go through the `aws` / `gcloud` CLI, so credentials come from
+/// the environment exactly as those tools expect.
+use crate::{RunEvent, RunSummary};
+use anyhow::{bail, Context, Result};
+use autoflow_data::ArtifactsConfig;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Failure reports uploaded with each run
+const FAILURES_DIR: &str = ".autoflow/.failures";
+
+/// Sprint board uploaded with each run
+const BOARD_FILE: &str = ".autoflow/BOARD.md";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtifactStore {
+    S3,
+    Gcs,
+}
+
+impl ArtifactStore {
+    /// Store and `bucket/prefix` for a destination URL
+    pub fn parse(destination: &str) -> Result<(Self, &str)> {
+        if let Some(rest) = destination.strip_prefix("s3://") {
+            Ok((Self::S3, rest.trim_end_matches('/')))
+        } else if let Some(rest) = destination.strip_prefix("gs://") {
+            Ok((Self::Gcs, rest.trim_end_matches('/')))
+        } else {
+            bail!("Artifact destination must start with s3:// or gs:// (got {})", destination)
+        }
+    }
+
+    /// CLI that does the copying
+    pub fn program(&self) -> &'static str {
+        match self {
+            Self::S3 => "aws",
+            Self::Gcs => "gcloud",
+        }
+    }
+}
+
+/// A local file or directory and where it goes under the run's prefix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Artifact {
+    pub local: PathBuf,
+    pub name: String,
+}
+
+impl Artifact {
+    /// What a link should open: the file, or a report directory's index.html
+    fn link_name(&self) -> String {
+        if self.local.is_dir() {
+            if self.local.join("index.html").exists() {
+                format!("{}/index.html", self.name)
+            } else {
+                format!("{}/", self.name)
+            }
+        } else {
+            self.name.clone()
+        }
+    }
+}
+
+/// Everything worth uploading for `run_id` that exists in `project_path`
+///
+/// The summary goes last so a reader who sees it can rely on the rest being there.
+pub fn collect_artifacts(project_path: &Path, run_id: &str, config: &ArtifactsConfig) -> Vec<Artifact> {
+    let mut artifacts = Vec::new();
+    let mut add = |local: PathBuf, name: String| {
+        if local.exists() {
+            artifacts.push(Artifact { local, name });
+        }
+    };
+
+    let run_dir = project_path.join(crate::RUNS_DIR).join(run_id);
+    add(run_dir.join("events.jsonl"), "events.jsonl".to_string());
+    add(project_path.join(BOARD_FILE), "BOARD.md".to_string());
+    if let Ok(entries) = std::fs::read_dir(project_path.join(FAILURES_DIR)) {
+        let mut reports: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+            .collect();
+        reports.sort();
+        for report in reports {
+            let name = format!("failures/{}", report.file_name().unwrap_or_default().to_string_lossy());
+            add(report, name);
+        }
+    }
+    for dir in &config.paths {
+        add(project_path.join(dir), dir.trim_end_matches('/').to_string());
+    }
+    add(run_dir.join("summary.json"), "summary.json".to_string());
+    artifacts
+}
+
+/// Public link for an uploaded artifact
+pub fn artifact_url(config: &ArtifactsConfig, run_id: &str, name: &str) -> Result<String> {
+    let destination = config.destination.as_deref().context("No artifact destination configured")?;
+    let (store, bucket_path) = ArtifactStore::parse(destination)?;
+    if let Some(ref base) = config.public_url {
+        return Ok(format!("{}/{}/{}", base.trim_end_matches('/'), run_id, name));
+    }
+    let (bucket, prefix) = bucket_path.split_once('/').unwrap_or((bucket_path, ""));
+    let key = if prefix.is_empty() {
+        format!("{}/{}", run_id, name)
+    } else {
+        format!("{}/{}/{}", prefix, run_id, name)
+    };
+    Ok(match store {
+        ArtifactStore::S3 => format!("https://{}.s3.amazonaws.com/{}", bucket, key),
+        ArtifactStore::Gcs => format!("https://storage.googleapis.com/{}/{}", bucket, key),
+    })
+}
+
+/// Upload a finished run's artifacts and record their links in its summary
+///
+/// The links are saved into the summary before anything is uploaded, so the
+/// uploaded copy carries them too; if an upload fails they are removed again.
+pub fn publish_run_artifacts(
+    project_path: &Path,
+    summary: &mut RunSummary,
+    events: &[RunEvent],
+    config: &ArtifactsConfig,
+) -> Result<()> {
+    let run_dir = project_path.join(crate::RUNS_DIR).join(&summary.run_id);
+    std::fs::create_dir_all(&run_dir)?;
+    let lines: Vec<String> = events.iter().filter_map(|e| serde_json::to_string(e).ok()).collect();
+    std::fs::write(run_dir.join("events.jsonl"), lines.join("\n") + "\n")?;
+    summary.save(project_path)?;
+
+    let artifacts = collect_artifacts(project_path, &summary.run_id, config);
+    summary.artifacts = artifacts
+        .iter()
+        .map(|a| artifact_url(config, &summary.run_id, &a.link_name()))
+        .collect::<Result<_>>()?;
+    summary.save(project_path)?;
+
+    if let Err(e) = upload_artifacts(&summary.run_id, &artifacts, config) {
+        summary.artifacts.clear();
+        summary.save(project_path)?;
+        return Err(e);
+    }
+    Ok(())
+}
+
+fn upload_artifacts(run_id: &str, artifacts: &[Artifact], config: &ArtifactsConfig) -> Result<()> {
+    let destination = config.destination.as_deref().context("No artifact destination configured")?;
+    let (store, _) = ArtifactStore::parse(destination)?;
+    let base = format!("{}/{}", destination.trim_end_matches('/'), run_id);
+
+    for artifact in artifacts {
+        let mut cmd = Command::new(store.program());
+        match store {
+            ArtifactStore::S3 => cmd.args(["s3", "cp", "--only-show-errors"]),
+            ArtifactStore::Gcs => cmd.args(["storage", "cp", "--quiet"]),
+        };
+        if artifact.local.is_dir() {
+            cmd.arg("--recursive");
+        }
+        let output = cmd
+            .arg(&artifact.local)
+            .arg(format!("{}/{}", base, artifact.name))
+            .output()
+            .with_context(|| format!("Failed to run the {} CLI", store.program()))?;
+        if !output.status.success() {
+            bail!(
+                "Uploading {} failed: {}",
+                artifact.local.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn config(destination: &str) -> ArtifactsConfig {
+        ArtifactsConfig { destination: Some(destination.to_string()), ..ArtifactsConfig::default() }
+    }
+
+    #[test]
+    fn test_artifact_urls() {
+        assert_eq!(
+            artifact_url(&config("s3://team-runs/autoflow/"), "01RUN", "summary.json").unwrap(),
+            "https://team-runs.s3.amazonaws.com/autoflow/01RUN/summary.json"
+        );
+        assert_eq!(
+            artifact_url(&config("gs://team-runs"), "01RUN", "BOARD.md").unwrap(),
+            "https://storage.googleapis.com/team-runs/01RUN/BOARD.md"
+        );
+        let mut cdn = config("s3://team-runs/autoflow");
+        cdn.public_url = Some("https://runs.example.com/".to_string());
+        assert_eq!(
+            artifact_url(&cdn, "01RUN", "coverage/index.html").unwrap(),
+            "https://runs.example.com/01RUN/coverage/index.html"
+        );
+        assert!(artifact_url(&config("ftp://nope"), "01RUN", "x").is_err());
+    }
+
+    #[test]
+    fn test_collect_artifacts_summary_last() {
+        let project = std::env::temp_dir().join(format!("autoflow-artifacts-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&project);
+        let run_dir = project.join(crate::RUNS_DIR).join("01RUN");
+        fs::create_dir_all(&run_dir).unwrap();
+        fs::create_dir_all(project.join(FAILURES_DIR)).unwrap();
+        fs::create_dir_all(project.join("playwright-report")).unwrap();
+        fs::write(run_dir.join("summary.json"), "{}").unwrap();
+        fs::write(run_dir.join("events.jsonl"), "").unwrap();
+        fs::write(project.join(FAILURES_DIR).join("sprint-2-unit-tests.md"), "# fail").unwrap();
+        fs::write(project.join("playwright-report/index.html"), "<html>").unwrap();
+
+        let artifacts = collect_artifacts(&project, "01RUN", &config("s3://b"));
+        let names: Vec<&str> = artifacts.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["events.jsonl", "failures/sprint-2-unit-tests.md", "playwright-report", "summary.json"]
+        );
+        assert_eq!(artifacts[2].link_name(), "playwright-report/index.html");
+
+        let _ = fs::remove_dir_all(project);
+    }
+}
@@ -94,7 +94,7 @@ pub async fn check_binary_update() -> Result<Option<BinaryUpdate>> {
 pub async fn install_binary_update(update: &BinaryUpdate) -> Result<()> {
     println!("{}", format!("📥 Downloading {} ...", update.asset_name).bright_cyan());
 
-    let home = dirs::home_dir().context("Could not find home directory")?;
+    let home = crate::require_home()?;
     let bin_dir = home.join(".autoflow/bin");
     let bin_path = bin_dir.join("autoflow");
     let tmp_archive = bin_dir.join(format!("autoflow-{}.tar.gz", update.version));
@@ -116,6 +116,7 @@ mod tests {
     fn test_sprint_context() {
         let sprint = Sprint {
             id: 1,
+            uid: None,
             goal: "Test sprint".to_string(),
             status: SprintStatus::Pending,
             duration: None,
@@ -130,6 +131,17 @@ mod tests {
             integration_points: None,
             blocked_count: None,
             must_complete_first: false,
+            priority: None,
+            value: None,
+            failure_reports: vec![],
+            repo: None,
+            uses_blocker_resolver: false,
+            issue_url: None,
+            skipped: None,
+            cancelled: None,
+            paused_from: None,
+            workflow_type: Default::default(),
+            workflow: None,
         };
 
         let context = build_sprint_context(&sprint, Some("Please implement this"));
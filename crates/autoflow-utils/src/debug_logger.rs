@@ -19,7 +19,10 @@ impl DebugLogger {
         // Add to gitignore
         Self::ensure_gitignored()?;
         
-        let session_id = Utc::now().format("%Y%m%d_%H%M%S").to_string();
+        let session_id = match crate::current_run_id() {
+            Some(run_id) => format!("{}_{}", Utc::now().format("%Y%m%d_%H%M%S"), run_id),
+            None => Utc::now().format("%Y%m%d_%H%M%S").to_string(),
+        };
         
         Ok(Self {
             debug_dir,
@@ -36,6 +39,7 @@ impl DebugLogger {
             let mut file = OpenOptions::new()
                 .create(true)
                 .write(true)
+                .truncate(true)
                 .open(gitignore_path)?;
             writeln!(file, ".debug/")?;
         } else {
@@ -121,6 +125,28 @@ impl DebugLogger {
         Ok(())
     }
     
+    /// Keep an agent's unsanitized stdout and stderr, escape codes and all
+    ///
+    /// Everything else AutoFlow stores is passed through `sanitize_output`;
+    /// this file is where the original bytes survive.
+    pub fn log_raw_output(&self, agent_name: &str, stdout: &str, stderr: &str) -> Result<()> {
+        let log_file = self.debug_dir.join(format!("{}_{}.raw.log", self.session_id, agent_name));
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_file)?;
+
+        writeln!(file, "[{}] STDOUT", Utc::now().format("%H:%M:%S"))?;
+        file.write_all(stdout.as_bytes())?;
+        if !stderr.is_empty() {
+            writeln!(file, "\n[{}] STDERR", Utc::now().format("%H:%M:%S"))?;
+            file.write_all(stderr.as_bytes())?;
+        }
+        writeln!(file)?;
+
+        Ok(())
+    }
+
     /// Log sprint execution
     pub fn log_sprint(&self, sprint_id: u32, status: &str, details: &str) -> Result<()> {
         let log_file = self.debug_dir.join(format!("{}_sprint_execution.log", self.session_id));
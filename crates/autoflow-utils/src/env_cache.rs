@@ -0,0 +1,385 @@
+/// Environment caches shared by every worktree of a project
+///
+/// Without them each sprint's E2E phase reinstalls `node_modules` and
+/// rebuilds its containers from scratch. `node_modules` is installed once per
+/// lockfile and symlinked into each checkout, package manager stores and the
+/// cargo target directory are shared through environment variables, and
+/// compose services build against a BuildKit cache keyed by their inputs.
+use autoflow_data::{EnvCacheConfig, Result};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Instant;
+
+/// Compose files looked for, in order
+pub const COMPOSE_FILES: &[&str] = &["docker-compose.yml", "docker-compose.yaml", "compose.yaml", "compose.yml"];
+
+/// Node lockfiles, each with the install command that honours it
+const NODE_LOCKFILES: &[(&str, &str)] = &[
+    ("pnpm-lock.yaml", "pnpm install --frozen-lockfile"),
+    ("yarn.lock", "yarn install --frozen-lockfile"),
+    ("package-lock.json", "npm ci"),
+    ("bun.lockb", "bun install --frozen-lockfile"),
+];
+
+/// What happened to one cache during a bootstrap
+#[derive(Debug, Clone, PartialEq)]
+pub enum CacheOutcome {
+    /// Reused what an earlier checkout left
+    Hit,
+    /// Built now, and kept for the next checkout
+    Miss,
+    /// Not applicable to this checkout
+    Skipped(String),
+    Failed(String),
+}
+
+impl fmt::Display for CacheOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hit => write!(f, "hit"),
+            Self::Miss => write!(f, "miss"),
+            Self::Skipped(reason) => write!(f, "skipped ({})", reason),
+            Self::Failed(reason) => write!(f, "failed ({})", reason),
+        }
+    }
+}
+
+/// One cache prepared by [`EnvCache::bootstrap`]
+#[derive(Debug, Clone)]
+pub struct BootstrapStep {
+    pub name: &'static str,
+    pub outcome: CacheOutcome,
+    pub secs: f64,
+}
+
+/// A project's caches, under `<dir>/<project>`
+#[derive(Debug, Clone)]
+pub struct EnvCache {
+    pub root: PathBuf,
+    pub config: EnvCacheConfig,
+}
+
+impl EnvCache {
+    pub fn at(root: PathBuf, config: EnvCacheConfig) -> Self {
+        Self { root, config }
+    }
+
+    /// Caches for the project `checkout` belongs to; None when disabled
+    pub fn for_checkout(checkout: &Path, config: &EnvCacheConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let base = match &config.dir {
+            Some(dir) => dir.clone(),
+            None => autoflow_data::autoflow_dir()?.join("cache"),
+        };
+        Some(Self::at(base.join(project_key(checkout)), config.clone()))
+    }
+
+    /// Caches for `checkout` as `[env_cache]` in the global config describes them
+    pub fn load(checkout: &Path) -> Option<Self> {
+        let config = autoflow_data::Config::global().map(|c| c.env_cache).unwrap_or_default();
+        Self::for_checkout(checkout, &config)
+    }
+
+    /// Variables that point package managers and cargo at the shared caches
+    pub fn shared_env(&self) -> Vec<(&'static str, PathBuf)> {
+        let mut env = Vec::new();
+        if self.config.node_modules {
+            env.push(("npm_config_store_dir", self.root.join("pnpm-store")));
+            env.push(("npm_config_cache", self.root.join("npm-cache")));
+            env.push(("YARN_CACHE_FOLDER", self.root.join("yarn-cache")));
+        }
+        if self.config.cargo_target {
+            env.push(("CARGO_TARGET_DIR", self.root.join("cargo-target")));
+        }
+        env
+    }
+
+    /// Prepare every enabled cache for `checkout`
+    pub fn bootstrap(&self, checkout: &Path) -> Vec<BootstrapStep> {
+        let timed = |name: &'static str, step: &dyn Fn() -> CacheOutcome| {
+            let started = Instant::now();
+            let outcome = step();
+            BootstrapStep {
+                name,
+                outcome,
+                secs: started.elapsed().as_secs_f64(),
+            }
+        };
+        let mut steps = Vec::new();
+        if self.config.node_modules {
+            steps.push(timed("node_modules", &|| self.link_node_modules(checkout)));
+        }
+        if self.config.docker {
+            steps.push(timed("docker", &|| self.build_containers(checkout)));
+        }
+        steps
+    }
+
+    /// Symlink the `node_modules` installed for the checkout's lockfile,
+    /// installing it into the cache first when no checkout has yet
+    ///
+    /// The cached copy sits at `node/<key>/node_modules` so that Node, which
+    /// resolves the symlink, still finds sibling packages.
+    pub fn link_node_modules(&self, checkout: &Path) -> CacheOutcome {
+        if !checkout.join("package.json").exists() {
+            return CacheOutcome::Skipped("no package.json".to_string());
+        }
+        let lockfile = NODE_LOCKFILES.iter().map(|(name, _)| checkout.join(name)).find(|p| p.exists());
+        let key = cache_key(&[Some(checkout.join("package.json")), lockfile].into_iter().flatten().collect::<Vec<_>>());
+        let cached = self.root.join("node").join(&key).join("node_modules");
+        let link = checkout.join("node_modules");
+
+        match fs::symlink_metadata(&link) {
+            Ok(meta) if meta.file_type().is_symlink() => {
+                if fs::read_link(&link).is_ok_and(|target| target == cached) && cached.is_dir() {
+                    return CacheOutcome::Hit;
+                }
+                // Linked for an older lockfile
+                if let Err(e) = fs::remove_file(&link) {
+                    return CacheOutcome::Failed(format!("could not replace the node_modules link: {}", e));
+                }
+            }
+            Ok(_) => return CacheOutcome::Skipped("the checkout has its own node_modules".to_string()),
+            Err(_) => {}
+        }
+
+        if cached.is_dir() {
+            return match symlink_dir(&cached, &link) {
+                Ok(()) => CacheOutcome::Hit,
+                Err(e) => CacheOutcome::Failed(format!("could not link node_modules: {}", e)),
+            };
+        }
+
+        let Some(command) = self.config.install_command.clone().or_else(|| detect_install_command(checkout)) else {
+            return CacheOutcome::Skipped("no install command".to_string());
+        };
+        let output = Command::new("sh")
+            .args(["-c", &command])
+            .current_dir(checkout)
+            .envs(self.shared_env())
+            .stdin(Stdio::null())
+            .output();
+        match output {
+            Ok(output) if output.status.success() => {}
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let tail: Vec<&str> = stderr.trim().lines().rev().take(3).collect();
+                return CacheOutcome::Failed(format!(
+                    "`{}` failed: {}",
+                    command,
+                    tail.into_iter().rev().collect::<Vec<_>>().join(" | ")
+                ));
+            }
+            Err(e) => return CacheOutcome::Failed(format!("`{}` could not start: {}", command, e)),
+        }
+        if !link.is_dir() {
+            return CacheOutcome::Failed(format!("`{}` did not create node_modules", command));
+        }
+
+        // Move the install into the cache; if another checkout got there first,
+        // or the cache is on another filesystem, this one stays as it is
+        if let Some(parent) = cached.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if fs::rename(&link, &cached).is_err() {
+            if !cached.is_dir() {
+                return CacheOutcome::Failed("could not move node_modules into the cache".to_string());
+            }
+            let _ = fs::remove_dir_all(&link);
+        }
+        match symlink_dir(&cached, &link) {
+            Ok(()) => CacheOutcome::Miss,
+            Err(e) => CacheOutcome::Failed(format!("could not link node_modules: {}", e)),
+        }
+    }
+
+    /// Build the compose services against the BuildKit cache for their inputs
+    pub fn build_containers(&self, checkout: &Path) -> CacheOutcome {
+        let Some(compose) = COMPOSE_FILES.iter().map(|name| checkout.join(name)).find(|p| p.exists()) else {
+            return CacheOutcome::Skipped("no compose file".to_string());
+        };
+        if !fs::read_to_string(&compose).is_ok_and(|content| content.contains("build:")) {
+            return CacheOutcome::Skipped("no service is built".to_string());
+        }
+
+        let mut inputs = vec![compose.clone()];
+        if let Ok(entries) = fs::read_dir(checkout) {
+            let mut dockerfiles: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.file_name().is_some_and(|n| n.to_string_lossy().starts_with("Dockerfile")))
+                .collect();
+            dockerfiles.sort();
+            inputs.extend(dockerfiles);
+        }
+        inputs.extend(
+            NODE_LOCKFILES
+                .iter()
+                .map(|(name, _)| *name)
+                .chain(["Cargo.lock", "go.sum", "requirements.txt", "composer.lock"])
+                .map(|name| checkout.join(name))
+                .filter(|p| p.exists()),
+        );
+        let dir = self.root.join("docker").join(cache_key(&inputs));
+        let hit = dir.is_dir();
+
+        let mut command = Command::new("docker");
+        command
+            .args(["buildx", "bake", "--load", "-f"])
+            .arg(&compose)
+            .current_dir(checkout)
+            .stdin(Stdio::null());
+        if hit {
+            command.arg("--set").arg(format!("*.cache-from=type=local,src={}", dir.display()));
+        } else {
+            command.arg("--set").arg(format!("*.cache-to=type=local,dest={},mode=max", dir.display()));
+        }
+        match command.output() {
+            Ok(output) if output.status.success() => {
+                if hit {
+                    CacheOutcome::Hit
+                } else {
+                    CacheOutcome::Miss
+                }
+            }
+            Ok(output) => CacheOutcome::Failed(format!(
+                "docker buildx bake failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim().lines().last().unwrap_or_default()
+            )),
+            Err(e) => CacheOutcome::Failed(format!("docker could not start: {}", e)),
+        }
+    }
+
+    /// Bytes taken by the caches
+    pub fn size(&self) -> u64 {
+        dir_size(&self.root)
+    }
+
+    pub fn clear(&self) -> Result<()> {
+        if self.root.exists() {
+            fs::remove_dir_all(&self.root)?;
+        }
+        Ok(())
+    }
+}
+
+/// Install command implied by the checkout's lockfile
+pub fn detect_install_command(checkout: &Path) -> Option<String> {
+    if !checkout.join("package.json").exists() {
+        return None;
+    }
+    let command = NODE_LOCKFILES
+        .iter()
+        .find(|(name, _)| checkout.join(name).exists())
+        .map(|(_, command)| *command)
+        .unwrap_or("npm install");
+    Some(command.to_string())
+}
+
+/// Stable key for the contents of `files` (FNV-1a, as hex); missing files count as empty
+pub fn cache_key(files: &[PathBuf]) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for file in files {
+        let name = file.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        for byte in name.bytes().chain([0]).chain(fs::read(file).unwrap_or_default()).chain([0]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+/// `<main checkout name>-<hash of its path>`, the same from every worktree
+fn project_key(checkout: &Path) -> String {
+    let common = Command::new("git")
+        .args(["rev-parse", "--path-format=absolute", "--git-common-dir"])
+        .current_dir(checkout)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| PathBuf::from(String::from_utf8_lossy(&o.stdout).trim()));
+    let main = match common {
+        Some(dir) if dir.file_name().is_some_and(|n| n == ".git") => dir.parent().map(Path::to_path_buf).unwrap_or(dir),
+        Some(dir) => dir,
+        None => checkout.canonicalize().unwrap_or_else(|_| checkout.to_path_buf()),
+    };
+    let name = main.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_else(|| "project".to_string());
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in main.to_string_lossy().bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{}-{:08x}", name, hash as u32)
+}
+
+#[cfg(unix)]
+fn symlink_dir(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink_dir(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_dir(target, link)
+}
+
+/// Bytes taken by the files under `path`; symlinks aren't followed
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) if meta.is_file() => meta.len(),
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_modules_shared_across_checkouts() {
+        let base = std::env::temp_dir().join(format!("autoflow-env-cache-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        let checkouts = [base.join("sprint-1"), base.join("sprint-2")];
+        for checkout in &checkouts {
+            fs::create_dir_all(checkout).unwrap();
+            fs::write(checkout.join("package.json"), r#"{"name": "app"}"#).unwrap();
+            fs::write(checkout.join("pnpm-lock.yaml"), "lockfileVersion: 9\n").unwrap();
+        }
+        assert_eq!(detect_install_command(&checkouts[0]).as_deref(), Some("pnpm install --frozen-lockfile"));
+
+        let cache = EnvCache::at(
+            base.join("cache"),
+            EnvCacheConfig {
+                install_command: Some("mkdir -p node_modules/left-pad && echo \"$npm_config_store_dir\" > node_modules/left-pad/store".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(cache.link_node_modules(&checkouts[0]), CacheOutcome::Miss);
+        assert_eq!(cache.link_node_modules(&checkouts[1]), CacheOutcome::Hit);
+        assert_eq!(cache.link_node_modules(&checkouts[0]), CacheOutcome::Hit);
+        let store = fs::read_to_string(checkouts[1].join("node_modules/left-pad/store")).unwrap();
+        assert_eq!(store.trim(), base.join("cache/pnpm-store").display().to_string());
+
+        // A changed lockfile gets its own install
+        fs::write(checkouts[1].join("pnpm-lock.yaml"), "lockfileVersion: 9\nleft-pad: 2\n").unwrap();
+        assert_eq!(cache.link_node_modules(&checkouts[1]), CacheOutcome::Miss);
+        assert_ne!(fs::read_link(checkouts[0].join("node_modules")).unwrap(), fs::read_link(checkouts[1].join("node_modules")).unwrap());
+
+        assert_eq!(
+            cache.build_containers(&checkouts[0]),
+            CacheOutcome::Skipped("no compose file".to_string())
+        );
+        assert!(cache.shared_env().iter().any(|(name, path)| *name == "CARGO_TARGET_DIR" && path.ends_with("cargo-target")));
+        let _ = fs::remove_dir_all(&base);
+    }
+}
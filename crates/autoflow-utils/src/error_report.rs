@@ -0,0 +1,106 @@
+/// Error reports: how a failed command's error reaches the user
+///
+/// Every error is reported with a stable code, a category, its cause chain
+/// and, where there is one, a hint. Codes and categories come from the first
+/// `AutoFlowError` in the chain, so errors wrapped with anyhow context keep
+/// theirs. The CLI prints reports as text or, with `--error-format json`, as
+/// one JSON object on stderr, and exits with the category's exit code.
+use autoflow_data::{AutoFlowError, ErrorCategory};
+use serde::Serialize;
+
+/// Code for errors that don't come from an `AutoFlowError`
+pub const INTERNAL_ERROR_CODE: &str = "AF000";
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ErrorReport {
+    pub code: String,
+    pub category: ErrorCategory,
+    pub message: String,
+    /// Underlying errors, outermost first, minus any the message already shows
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub causes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl ErrorReport {
+    pub fn from_error(error: &anyhow::Error) -> Self {
+        let message = error.to_string();
+        let mut causes: Vec<String> = Vec::new();
+        for cause in error.chain().skip(1).map(|c| c.to_string()) {
+            if !message.contains(&cause) && !causes.iter().any(|c| c.contains(&cause)) {
+                causes.push(cause);
+            }
+        }
+
+        let (code, category, hint) = match error.chain().find_map(|e| e.downcast_ref::<AutoFlowError>()) {
+            Some(e) => (e.code(), e.category(), e.hint()),
+            None if error.chain().any(|e| e.is::<std::io::Error>()) => {
+                let io = AutoFlowError::IoError(std::io::Error::other(""));
+                (io.code(), io.category(), None)
+            }
+            None => (INTERNAL_ERROR_CODE, ErrorCategory::Internal, None),
+        };
+
+        Self {
+            code: code.to_string(),
+            category,
+            message,
+            causes,
+            hint: hint.map(String::from),
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        self.category.exit_code()
+    }
+
+    /// `error[AF303]: ...` followed by indented causes and the hint
+    pub fn render_text(&self) -> String {
+        let mut text = format!("error[{}]: {}", self.code, self.message);
+        for cause in &self.causes {
+            text.push_str(&format!("\n  caused by: {}", cause));
+        }
+        if let Some(ref hint) = self.hint {
+            text.push_str(&format!("\n  hint: {}", hint));
+        }
+        text
+    }
+
+    pub fn render_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_report_finds_wrapped_autoflow_error() {
+        let error = Err::<(), _>(AutoFlowError::NotInitialized)
+            .context("Failed to load the sprint plan")
+            .unwrap_err();
+        let report = ErrorReport::from_error(&error);
+        assert_eq!(report.code, "AF102");
+        assert_eq!(report.category, ErrorCategory::Setup);
+        assert_eq!(report.exit_code(), 3);
+        assert_eq!(
+            report.render_text(),
+            "error[AF102]: Failed to load the sprint plan\n  \
+             caused by: Project not initialized. Run 'autoflow init' first.\n  \
+             hint: Run `autoflow init` in the project directory"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&report.render_json()).unwrap();
+        assert_eq!(json["code"], "AF102");
+        assert_eq!(json["category"], "setup");
+
+        let other = ErrorReport::from_error(&anyhow::anyhow!("boom"));
+        assert_eq!(other.code, INTERNAL_ERROR_CODE);
+        assert_eq!(other.exit_code(), 1);
+        assert!(other.render_json().contains("\"category\":\"internal\""));
+        assert!(!other.render_json().contains("hint"));
+    }
+}
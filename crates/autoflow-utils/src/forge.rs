@@ -0,0 +1,281 @@
+/// Forge issues: hand persistently blocked sprints to humans
+///
+/// With `[forge] file_blocked_issues = true`, `finish_run` files an issue for
+/// every sprint the run left BLOCKED after the blocker resolver had a go. The
+/// issue carries a triage report (where the sprint stopped, its errors, open
+/// review findings, failure reports and artifact links) and the configured
+/// labels. Its URL is saved as the sprint's `issue_url`, so the next run
+/// doesn't file it again.
+use crate::{RunEvent, RunSummary};
+use anyhow::{bail, Context, Result};
+use autoflow_data::{ForgeConfig, ForgeProvider, Sprint, SprintStatus};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Failure reports included in the issue body
+const FAILURES_DIR: &str = ".autoflow/.failures";
+
+/// Characters of each failure report quoted in an issue
+const MAX_REPORT_CHARS: usize = 4_000;
+
+/// Forge the project is hosted on: the configured one, else guessed from `origin`
+pub fn detect_forge(config: &ForgeConfig) -> Option<ForgeProvider> {
+    if config.provider.is_some() {
+        return config.provider;
+    }
+    let output = Command::new("git").args(["remote", "get-url", "origin"]).output().ok()?;
+    provider_for_remote(String::from_utf8_lossy(&output.stdout).trim())
+}
+
+fn provider_for_remote(url: &str) -> Option<ForgeProvider> {
+    if url.contains("github") {
+        Some(ForgeProvider::Github)
+    } else if url.contains("gitlab") {
+        Some(ForgeProvider::Gitlab)
+    } else {
+        None
+    }
+}
+
+/// Sprints to file issues for: blocked at the end of the run after the
+/// resolver ran (or after using up their fix budget), and without an issue yet
+pub fn sprints_needing_issues<'a>(sprints: &'a [Sprint], events: &[RunEvent]) -> Vec<&'a Sprint> {
+    sprints
+        .iter()
+        .filter(|s| s.status == SprintStatus::Blocked && s.issue_url.is_none())
+        .filter(|s| {
+            s.uses_blocker_resolver
+                || events.iter().any(|e| {
+                    e.is_for(s)
+                        && (e.agent.as_deref() == Some("blocker-resolver") || e.event == "fix_budget_exceeded")
+                })
+        })
+        .collect()
+}
+
+pub fn blocked_issue_title(sprint: &Sprint) -> String {
+    format!("[autoflow] Sprint {} blocked: {}", sprint.id, sprint.goal)
+}
+
+/// Markdown triage report for a blocked sprint
+pub fn blocked_issue_body(sprint: &Sprint, summary: &RunSummary, project_path: &Path) -> String {
+    let mut body = String::new();
+    let run = summary.sprints.iter().find(|s| s.id == sprint.id);
+
+    body.push_str(&format!(
+        "AutoFlow could not finish **Sprint {}: {}**, and the blocker resolver didn't unblock it.\n\n",
+        sprint.id, sprint.goal
+    ));
+    body.push_str("| | |\n|---|---|\n");
+    body.push_str(&format!("| Workflow | {:?} |\n", sprint.workflow_type));
+    if let Some(phase) = run.and_then(|r| r.phases.last()) {
+        body.push_str(&format!("| Last phase | {} |\n", phase.status));
+    }
+    body.push_str(&format!("| Failed attempts | {} |\n", sprint.blocked_count.unwrap_or(0)));
+    body.push_str(&format!("| Run | `{}` ({}) |\n", summary.run_id, summary.command));
+
+    let errors = run.map(|r| r.errors.as_slice()).unwrap_or_default();
+    if !errors.is_empty() {
+        body.push_str("\n## Errors\n\n");
+        for error in errors {
+            body.push_str(&format!("- {}\n", error));
+        }
+    }
+
+    let findings: Vec<_> = sprint
+        .tasks
+        .iter()
+        .flat_map(|t| t.review_findings.iter().map(move |f| (t, f)))
+        .collect();
+    if !findings.is_empty() {
+        body.push_str("\n## Open Review Findings\n\n");
+        for (task, finding) in findings {
+            body.push_str(&format!("- **{}**: {}\n", task.id, finding));
+        }
+    }
+
+    for (name, report) in failure_reports(project_path, sprint.id) {
+        body.push_str(&format!("\n<details><summary>{}</summary>\n\n", name));
+        body.push_str(&report);
+        body.push_str("\n\n</details>\n");
+    }
+
+    if !summary.artifacts.is_empty() {
+        body.push_str("\n## Artifacts\n\n");
+        for url in &summary.artifacts {
+            body.push_str(&format!("- {}\n", url));
+        }
+    }
+
+    body.push_str(&format!(
+        "\nOnce fixed, run `autoflow start --sprint {}` to resume.\n",
+        sprint.id
+    ));
+    body
+}
+
+/// The sprint's failure reports, trimmed to a readable length
+fn failure_reports(project_path: &Path, sprint_id: u32) -> Vec<(String, String)> {
+    let prefix = format!("sprint-{}-", sprint_id);
+    let Ok(entries) = std::fs::read_dir(project_path.join(FAILURES_DIR)) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<(String, String)> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            name.starts_with(&prefix) && name.ends_with(".md")
+        })
+        .filter_map(|e| {
+            let content = std::fs::read_to_string(e.path()).ok()?;
+            let mut content: String = content.chars().take(MAX_REPORT_CHARS).collect();
+            if content.len() < std::fs::metadata(e.path()).map(|m| m.len() as usize).unwrap_or(0) {
+                content.push_str("\n\n_(truncated)_");
+            }
+            Some((e.file_name().to_string_lossy().to_string(), content))
+        })
+        .collect();
+    reports.sort();
+    reports
+}
+
+/// Create an issue with `gh` or `glab`; returns its URL
+pub fn file_issue(config: &ForgeConfig, provider: ForgeProvider, title: &str, body: &str) -> Result<String> {
+    let program = match provider {
+        ForgeProvider::Github => "gh",
+        ForgeProvider::Gitlab => "glab",
+    };
+    let mut cmd = Command::new(program);
+    match provider {
+        // The body goes in on stdin; it can be longer than an argument may be
+        ForgeProvider::Github => {
+            cmd.args(["issue", "create", "--title", title, "--body-file", "-"]);
+            for label in &config.labels {
+                cmd.arg("--label").arg(label);
+            }
+        }
+        ForgeProvider::Gitlab => {
+            cmd.args(["issue", "create", "--yes", "--title", title, "--description", body]);
+            if !config.labels.is_empty() {
+                cmd.arg("--label").arg(config.labels.join(","));
+            }
+        }
+    }
+    if let Some(ref repo) = config.repo {
+        cmd.arg("--repo").arg(repo);
+    }
+
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {} (is it installed?)", program))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        if provider == ForgeProvider::Github {
+            stdin.write_all(body.as_bytes())?;
+        }
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!("{} issue create failed: {}", program, String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("https://") || line.starts_with("http://"))
+        .map(String::from)
+        .with_context(|| format!("{} didn't print the new issue's URL", program))
+}
+
+/// Save filed issue URLs on their sprints in SPRINTS.yml
+pub fn record_issue_urls(sprints_path: &Path, filed: &[(u32, String)]) -> Result<()> {
+    let mut plan = autoflow_data::SprintsYaml::load_without_validation(sprints_path)?;
+    for (id, url) in filed {
+        if let Some(sprint) = plan.sprints.iter_mut().find(|s| s.id == *id) {
+            sprint.issue_url = Some(url.clone());
+        }
+    }
+    plan.save(sprints_path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SprintRunSummary;
+    use chrono::Utc;
+
+    fn blocked_sprint(id: u32) -> Sprint {
+        let yaml = format!(
+            "id: {}\ngoal: Checkout\nstatus: BLOCKED\nblocked_count: 3\ntotal_effort: 2h\nmax_effort: 4h\n\
+             last_updated: 2025-01-01T00:00:00Z\ndeliverables: []\ntasks: []\n",
+            id
+        );
+        serde_yaml::from_str(&yaml).unwrap()
+    }
+
+    #[test]
+    fn test_blocked_sprint_issue() {
+        // Resolver ran this run; never reached the resolver; already filed; fix budget used up
+        let mut sprints = [blocked_sprint(1), blocked_sprint(2), blocked_sprint(3), blocked_sprint(4)];
+        sprints[2].uses_blocker_resolver = true;
+        sprints[2].issue_url = Some("https://github.com/acme/shop/issues/7".to_string());
+        let events = vec![
+            RunEvent {
+                event: "agent".to_string(),
+                sprint: Some(1),
+                agent: Some("blocker-resolver".to_string()),
+                ..Default::default()
+            },
+            RunEvent { event: "fix_budget_exceeded".to_string(), sprint: Some(4), ..Default::default() },
+        ];
+        let needing: Vec<u32> = sprints_needing_issues(&sprints, &events).iter().map(|s| s.id).collect();
+        assert_eq!(needing, vec![1, 4]);
+
+        let dir = std::env::temp_dir().join(format!("autoflow-forge-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join(FAILURES_DIR)).unwrap();
+        std::fs::write(dir.join(FAILURES_DIR).join("sprint-1-unit-tests.md"), "3 tests failed").unwrap();
+        std::fs::write(dir.join(FAILURES_DIR).join("sprint-11-review.md"), "not this one").unwrap();
+
+        let summary = RunSummary {
+            run_id: "01RUN".to_string(),
+            command: "start".to_string(),
+            outcome: "blocked".to_string(),
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+            duration_secs: 0,
+            sprints: vec![SprintRunSummary {
+                id: 1,
+                uid: None,
+                goal: "Checkout".to_string(),
+                final_status: "Blocked".to_string(),
+                phases: vec![],
+                duration_secs: 0,
+                tokens: 0,
+                errors: vec!["RunUnitTests: tests failed".to_string()],
+            }],
+            phases_executed: 0,
+            agents_run: 0,
+            tokens: 0,
+            commits: vec![],
+            errors: vec![],
+            artifacts: vec!["https://artifacts.example.com/01RUN/summary.json".to_string()],
+        };
+        let body = blocked_issue_body(&sprints[0], &summary, &dir);
+        assert!(body.contains("| Failed attempts | 3 |"));
+        assert!(body.contains("- RunUnitTests: tests failed"));
+        assert!(body.contains("<summary>sprint-1-unit-tests.md</summary>\n\n3 tests failed"));
+        assert!(!body.contains("not this one"));
+        assert!(body.contains("- https://artifacts.example.com/01RUN/summary.json"));
+        assert_eq!(blocked_issue_title(&sprints[0]), "[autoflow] Sprint 1 blocked: Checkout");
+
+        assert_eq!(provider_for_remote("git@github.com:acme/shop.git"), Some(ForgeProvider::Github));
+        assert_eq!(provider_for_remote("https://gitlab.example.com/acme/shop"), Some(ForgeProvider::Gitlab));
+        assert_eq!(provider_for_remote("ssh://git.example.com/shop"), None);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
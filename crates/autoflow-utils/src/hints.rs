@@ -0,0 +1,101 @@
+/// Operator hints: guidance for the next agent run on a sprint
+///
+/// `autoflow hint <sprint> "..."` queues a hint. The orchestrator puts every
+/// queued hint at the top of the next agent context for that sprint and drops
+/// them once that agent has run. They're kept per sprint beside SPRINTS.yml
+/// rather than inside it: a running `start` rewrites the whole sprint when it
+/// saves progress, which would lose a hint added mid-run.
+use autoflow_data::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Queued hints, one JSON file per sprint
+pub const HINTS_DIR: &str = ".autoflow/hints";
+
+pub fn hints_path(project_path: &Path, sprint_id: u32) -> PathBuf {
+    project_path.join(HINTS_DIR).join(format!("sprint-{}.json", sprint_id))
+}
+
+/// Hints waiting for the sprint's next agent, oldest first
+pub fn pending_hints(project_path: &Path, sprint_id: u32) -> Vec<String> {
+    autoflow_data::read_state_to_string(hints_path(project_path, sprint_id))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_hints(project_path: &Path, sprint_id: u32, hints: &[String]) -> Result<()> {
+    let path = hints_path(project_path, sprint_id);
+    if hints.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    autoflow_data::write_state(&path, serde_json::to_string_pretty(hints)?)
+}
+
+pub fn add_hint(project_path: &Path, sprint_id: u32, hint: &str) -> Result<()> {
+    autoflow_data::ensure_writable("adding a hint")?;
+    let mut hints = pending_hints(project_path, sprint_id);
+    hints.push(hint.trim().to_string());
+    save_hints(project_path, sprint_id, &hints)
+}
+
+/// Drop the `count` oldest hints, the ones an agent just ran with
+///
+/// Hints queued while that agent was running stay for the next one.
+pub fn consume_hints(project_path: &Path, sprint_id: u32, count: usize) -> Result<()> {
+    let hints = pending_hints(project_path, sprint_id);
+    save_hints(project_path, sprint_id, &hints[count.min(hints.len())..])
+}
+
+pub fn clear_hints(project_path: &Path, sprint_id: u32) -> Result<()> {
+    autoflow_data::ensure_writable("clearing hints")?;
+    save_hints(project_path, sprint_id, &[])
+}
+
+/// Context section carrying the hints; placed first so the agent reads it before anything else
+pub fn hints_section(hints: &[String]) -> String {
+    let mut section = String::from(
+        "# Operator Hints\n\n\
+         The developer running AutoFlow gave this guidance for the sprint. \
+         Follow it; where it conflicts with anything below, it wins.\n\n",
+    );
+    for hint in hints {
+        section.push_str(&format!("- {}\n", hint));
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hints_are_consumed_in_order() {
+        let project = std::env::temp_dir().join(format!("autoflow-hints-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&project);
+
+        assert!(pending_hints(&project, 3).is_empty());
+        add_hint(&project, 3, "use the existing db pool from src/db.ts ").unwrap();
+        add_hint(&project, 3, "don't touch the migrations").unwrap();
+        assert_eq!(pending_hints(&project, 3), ["use the existing db pool from src/db.ts", "don't touch the migrations"]);
+        assert!(pending_hints(&project, 4).is_empty());
+        assert!(hints_section(&pending_hints(&project, 3)).contains("- don't touch the migrations\n"));
+
+        // One hint was queued while the agent ran with the first; only that one is dropped
+        consume_hints(&project, 3, 1).unwrap();
+        assert_eq!(pending_hints(&project, 3), ["don't touch the migrations"]);
+        consume_hints(&project, 3, 5).unwrap();
+        assert!(!hints_path(&project, 3).exists());
+
+        add_hint(&project, 3, "x").unwrap();
+        clear_hints(&project, 3).unwrap();
+        assert!(pending_hints(&project, 3).is_empty());
+        let _ = fs::remove_dir_all(project);
+    }
+}
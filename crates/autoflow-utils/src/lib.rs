@@ -8,6 +8,24 @@ pub mod context;
 pub mod update;
 pub mod debug_logger;
 pub mod binary_update;
+pub mod telemetry;
+pub mod run_lock;
+pub mod run_id;
+pub mod run_summary;
+pub mod run_compare;
+pub mod resources;
+pub mod policy;
+pub mod artifacts;
+pub mod notify;
+pub mod error_report;
+pub mod runtime_status;
+pub mod forge;
+pub mod webhook;
+pub mod env_cache;
+pub mod network;
+pub mod hints;
+pub mod task_runner;
+pub mod support_bundle;
 
 pub use logging::*;
 pub use paths::*;
@@ -17,3 +35,21 @@ pub use context::*;
 pub use update::*;
 pub use debug_logger::*;
 pub use binary_update::*;
+pub use telemetry::*;
+pub use run_lock::*;
+pub use run_id::*;
+pub use run_summary::*;
+pub use run_compare::*;
+pub use resources::*;
+pub use policy::*;
+pub use artifacts::*;
+pub use notify::*;
+pub use error_report::*;
+pub use runtime_status::*;
+pub use forge::*;
+pub use webhook::*;
+pub use env_cache::*;
+pub use network::*;
+pub use hints::*;
+pub use task_runner::*;
+pub use support_bundle::*;
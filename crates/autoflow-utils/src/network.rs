@@ -0,0 +1,117 @@
+/// Connectivity loss mid-run: told apart from model errors and waited out
+///
+/// An agent that fails while the network is down would otherwise count as a
+/// failed phase, and a long enough outage blocks sprints that did nothing
+/// wrong. When an agent fails and the `[network] probe` can't be reached,
+/// the run records `network_lost` (which `autoflow status` shows as waiting
+/// for network), checks again with backoff, and records `network_restored`
+/// once it's back so the agent can be rerun.
+use crate::record_run_event;
+use autoflow_data::{AutoFlowError, NetworkConfig, Result};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+/// How long one connectivity check may take
+const PROBE_TIMEOUT_SECS: u64 = 5;
+
+/// First pause between checks; each pause doubles up to `max_backoff_secs`
+const FIRST_BACKOFF_SECS: u64 = 5;
+
+/// Error text that points at the connection rather than the model
+const NETWORK_ERROR_MARKERS: &[&str] = &[
+    "enotfound",
+    "econnrefused",
+    "econnreset",
+    "etimedout",
+    "eai_again",
+    "enetunreach",
+    "getaddrinfo",
+    "network is unreachable",
+    "could not resolve host",
+    "connection refused",
+    "connection reset",
+    "connection error",
+    "fetch failed",
+    "socket hang up",
+    "unable to connect",
+];
+
+/// Whether an agent's error output reads like lost connectivity
+pub fn looks_like_network_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    NETWORK_ERROR_MARKERS.iter().any(|marker| message.contains(marker))
+}
+
+/// Whether `probe` (`host:port`) accepts a TCP connection
+pub fn is_online(probe: &str) -> bool {
+    let Ok(addrs) = probe.to_socket_addrs() else {
+        return false;
+    };
+    addrs
+        .into_iter()
+        .any(|addr| TcpStream::connect_timeout(&addr, Duration::from_secs(PROBE_TIMEOUT_SECS)).is_ok())
+}
+
+/// Pauses between connectivity checks: 5s, 10s, 20s... capped at `max_secs`
+pub fn backoff_delays(max_secs: u64) -> impl Iterator<Item = u64> {
+    let max_secs = max_secs.max(1);
+    std::iter::successors(Some(FIRST_BACKOFF_SECS.min(max_secs)), move |delay| Some((delay * 2).min(max_secs)))
+}
+
+/// True when the network is down, checked off the async runtime
+pub async fn network_lost(config: &NetworkConfig) -> bool {
+    let probe = config.probe.clone();
+    !tokio::task::spawn_blocking(move || is_online(&probe)).await.unwrap_or(true)
+}
+
+/// Pause until the probe is reachable again; returns the seconds waited
+///
+/// Errors once `max_wait_secs` (when set) passes without connectivity.
+pub async fn wait_for_network(config: &NetworkConfig, agent: &str, sprint: Option<u32>) -> Result<u64> {
+    let started = Instant::now();
+    tracing::warn!("Network unavailable while running '{}' - waiting for it to return", agent);
+    println!("  📡 Network unavailable - run paused until {} is reachable", config.probe);
+    record_run_event("network_lost", sprint, agent);
+
+    for delay in backoff_delays(config.max_backoff_secs) {
+        let waited = started.elapsed().as_secs();
+        if config.max_wait_secs > 0 && waited >= config.max_wait_secs {
+            record_run_event("network_gave_up", sprint, &format!("after {}s", waited));
+            return Err(AutoFlowError::NetworkUnavailable { waited_secs: waited });
+        }
+        tokio::time::sleep(Duration::from_secs(delay)).await;
+        if !network_lost(config).await {
+            let waited = started.elapsed().as_secs();
+            tracing::info!("Network restored after {}s - resuming '{}'", waited, agent);
+            println!("  📡 Network restored after {}s - resuming", waited);
+            record_run_event("network_restored", sprint, &format!("after {}s", waited));
+            return Ok(waited);
+        }
+    }
+    unreachable!("backoff delays never run out")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_error_detection() {
+        assert!(looks_like_network_error("API Error: Connection error. (getaddrinfo ENOTFOUND api.anthropic.com)"));
+        assert!(looks_like_network_error("TypeError: fetch failed"));
+        assert!(!looks_like_network_error("API Error: 529 Overloaded"));
+        assert!(!looks_like_network_error("Error: rate_limit_error"));
+
+        let delays: Vec<u64> = backoff_delays(60).take(6).collect();
+        assert_eq!(delays, [5, 10, 20, 40, 60, 60]);
+        assert_eq!(backoff_delays(3).take(2).collect::<Vec<_>>(), [3, 3]);
+
+        // Nothing listens on a port that was just released
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let probe = listener.local_addr().unwrap().to_string();
+        assert!(is_online(&probe));
+        drop(listener);
+        assert!(!is_online(&probe));
+        assert!(!is_online("not a host"));
+    }
+}
@@ -0,0 +1,315 @@
+/// Run notifications: a digest email when a run finishes
+///
+/// With `[notify.email]` configured, `finish_run` mails a plain-text digest of
+/// the run (sprints done and blocked, tokens and estimated cost, commits,
+/// errors and artifact links) to the configured recipients. Mail goes out
+/// through `curl`'s SMTP support, so no mail client is needed on the machine.
+use crate::{RunSummary, RUNS_DIR};
+use anyhow::{anyhow, bail, Context, Result};
+use autoflow_data::EmailConfig;
+use chrono::Utc;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Errors listed in a digest before the rest are counted
+const MAX_DIGEST_ERRORS: usize = 5;
+
+/// Subject line for a run's digest
+pub fn digest_subject(project: &str, summary: &RunSummary) -> String {
+    let done = summary.sprints.iter().filter(|s| s.final_status == "Done").count();
+    let blocked = summary.sprints.iter().filter(|s| s.final_status == "Blocked").count();
+    let mut subject = format!("[autoflow] {}: run {} — {} done", project, summary.outcome, done);
+    if blocked > 0 {
+        subject.push_str(&format!(", {} blocked", blocked));
+    }
+    subject
+}
+
+/// Plain-text body of a run's digest
+pub fn digest_body(project: &str, summary: &RunSummary) -> String {
+    let mut body = String::new();
+    let mut line = |text: String| {
+        body.push_str(&text);
+        body.push('\n');
+    };
+
+    line(format!("Project:  {}", project));
+    line(format!("Run:      {} ({})", summary.run_id, summary.command));
+    line(format!("Outcome:  {}", summary.outcome));
+    line(format!(
+        "Duration: {}m {}s",
+        summary.duration_secs / 60,
+        summary.duration_secs % 60
+    ));
+    line(format!(
+        "Tokens:   {} output (~${:.2})",
+        summary.tokens,
+        summary.output_cost_usd()
+    ));
+    line(String::new());
+
+    let done: Vec<_> = summary.sprints.iter().filter(|s| s.final_status == "Done").collect();
+    let blocked: Vec<_> = summary.sprints.iter().filter(|s| s.final_status == "Blocked").collect();
+    let other: Vec<_> = summary
+        .sprints
+        .iter()
+        .filter(|s| s.final_status != "Done" && s.final_status != "Blocked")
+        .collect();
+    line(format!(
+        "Sprints: {} done, {} blocked, {} in progress",
+        done.len(),
+        blocked.len(),
+        other.len()
+    ));
+    for sprint in done {
+        line(format!("  ✓ Sprint {}: {}", sprint.id, sprint.goal));
+    }
+    for sprint in blocked {
+        line(format!("  ✗ Sprint {}: {}", sprint.id, sprint.goal));
+    }
+    for sprint in other {
+        line(format!("  … Sprint {}: {} ({})", sprint.id, sprint.goal, sprint.final_status));
+    }
+
+    if !summary.commits.is_empty() {
+        line(String::new());
+        line(format!("Commits ({}):", summary.commits.len()));
+        for commit in &summary.commits {
+            line(format!("  {} {}", &commit.sha[..commit.sha.len().min(8)], commit.subject));
+        }
+    }
+
+    if !summary.errors.is_empty() {
+        line(String::new());
+        line(format!("Errors ({}):", summary.errors.len()));
+        for error in summary.errors.iter().take(MAX_DIGEST_ERRORS) {
+            line(format!("  - {}", error));
+        }
+        if summary.errors.len() > MAX_DIGEST_ERRORS {
+            line(format!("  ... and {} more", summary.errors.len() - MAX_DIGEST_ERRORS));
+        }
+    }
+
+    line(String::new());
+    if summary.artifacts.is_empty() {
+        line(format!("Summary: {}/{}/summary.json", RUNS_DIR, summary.run_id));
+    } else {
+        line("Reports:".to_string());
+        for url in &summary.artifacts {
+            line(format!("  {}", url));
+        }
+    }
+    body
+}
+
+/// RFC 5322 message carrying `body`
+pub fn email_message(config: &EmailConfig, subject: &str, body: &str) -> String {
+    let mut message = String::new();
+    message.push_str(&format!("From: {}\r\n", config.from));
+    message.push_str(&format!("To: {}\r\n", config.to.join(", ")));
+    message.push_str(&format!("Subject: {}\r\n", encode_header(subject)));
+    message.push_str(&format!("Date: {}\r\n", Utc::now().to_rfc2822()));
+    message.push_str("MIME-Version: 1.0\r\n");
+    message.push_str("Content-Type: text/plain; charset=utf-8\r\n");
+    message.push_str("Content-Transfer-Encoding: 8bit\r\n");
+    message.push_str("\r\n");
+    for line in body.lines() {
+        // SMTP ends the data at a lone "." line
+        if line.starts_with('.') {
+            message.push('.');
+        }
+        message.push_str(line);
+        message.push_str("\r\n");
+    }
+    message
+}
+
+/// RFC 2047 Q-encoded word for headers that aren't plain ASCII
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        return value.to_string();
+    }
+    let mut encoded = String::from("=?utf-8?Q?");
+    for byte in value.bytes() {
+        match byte {
+            b' ' => encoded.push('_'),
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'.' | b',' | b'-' | b':' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("={:02X}", byte)),
+        }
+    }
+    encoded.push_str("?=");
+    encoded
+}
+
+/// Send a message through the configured SMTP server with `curl`
+///
+/// The login goes to curl on stdin as a config file, so the password never
+/// appears in the process list. The message is uploaded from a
+/// [`private_message_file`], removed once curl is done.
+pub fn send_email(config: &EmailConfig, message: &str) -> Result<()> {
+    if config.to.is_empty() {
+        bail!("[notify.email] has no recipients");
+    }
+    let secure = config.smtp_url.starts_with("smtps://");
+    if !secure && !config.smtp_url.starts_with("smtp://") {
+        bail!("smtp_url must start with smtp:// or smtps:// (got {})", config.smtp_url);
+    }
+
+    let message_file = private_message_file(message)?;
+
+    let mut cmd = Command::new("curl");
+    cmd.args(["--silent", "--show-error", "--url", &config.smtp_url])
+        .arg("--mail-from")
+        .arg(&config.from);
+    for to in &config.to {
+        cmd.arg("--mail-rcpt").arg(to);
+    }
+    if config.require_tls && !secure {
+        cmd.arg("--ssl-reqd");
+    }
+    cmd.arg("--upload-file").arg(&message_file).args(["--config", "-"]);
+
+    let mut curl_config = String::new();
+    if let Some(ref username) = config.username {
+        let password = std::env::var(&config.password_env)
+            .with_context(|| format!("SMTP password not set (export {})", config.password_env))?;
+        curl_config.push_str(&format!("user = \"{}\"\n", curl_escape(&format!("{}:{}", username, password))));
+    }
+
+    let result = (|| {
+        let mut child = cmd
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to run curl")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(curl_config.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            bail!("SMTP delivery failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(())
+    })();
+    let _ = std::fs::remove_file(&message_file);
+    result
+}
+
+/// Write `message` to a new temp file under a random name that only the current user can read
+///
+/// `create_new` refuses a file or symlink already at the path, so another
+/// user can't plant one there to read the digest.
+fn private_message_file(message: &str) -> Result<PathBuf> {
+    let mut nonce = [0u8; 12];
+    SystemRandom::new().fill(&mut nonce).map_err(|_| anyhow!("System randomness unavailable"))?;
+    let name: String = nonce.iter().map(|b| format!("{:02x}", b)).collect();
+    let path = std::env::temp_dir().join(format!("autoflow-digest-{}.eml", name));
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&path).with_context(|| format!("Failed to create {}", path.display()))?;
+    if let Err(e) = file.write_all(message.as_bytes()) {
+        let _ = std::fs::remove_file(&path);
+        return Err(e.into());
+    }
+    Ok(path)
+}
+
+/// Escape a value for a double-quoted curl config string
+fn curl_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RunCommit, SprintRunSummary};
+
+    fn sprint(id: u32, status: &str) -> SprintRunSummary {
+        SprintRunSummary {
+            id,
+            uid: None,
+            goal: format!("Goal {}", id),
+            final_status: status.to_string(),
+            phases: vec![],
+            duration_secs: 0,
+            tokens: 0,
+            errors: vec![],
+        }
+    }
+
+    fn summary() -> RunSummary {
+        RunSummary {
+            run_id: "01RUN".to_string(),
+            command: "start".to_string(),
+            outcome: "completed".to_string(),
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+            duration_secs: 3_725,
+            sprints: vec![sprint(1, "Done"), sprint(2, "Blocked"), sprint(3, "WriteCode")],
+            phases_executed: 9,
+            agents_run: 7,
+            tokens: 200_000,
+            commits: vec![RunCommit { sha: "0123456789abcdef".to_string(), subject: "feat: login".to_string() }],
+            errors: vec!["tests failed".to_string()],
+            artifacts: vec![],
+        }
+    }
+
+    #[test]
+    fn test_digest() {
+        let summary = summary();
+        assert_eq!(digest_subject("shop", &summary), "[autoflow] shop: run completed — 1 done, 1 blocked");
+
+        let body = digest_body("shop", &summary);
+        assert!(body.contains("Duration: 62m 5s"));
+        assert!(body.contains("Tokens:   200000 output (~$3.00)"));
+        assert!(body.contains("Sprints: 1 done, 1 blocked, 1 in progress"));
+        assert!(body.contains("  ✓ Sprint 1: Goal 1"));
+        assert!(body.contains("  ✗ Sprint 2: Goal 2"));
+        assert!(body.contains("  01234567 feat: login"));
+        assert!(body.contains("  - tests failed"));
+        assert!(body.contains("Summary: .autoflow/runs/01RUN/summary.json"));
+    }
+
+    #[test]
+    fn test_email_message() {
+        let config = EmailConfig {
+            smtp_url: "smtps://smtp.example.com:465".to_string(),
+            from: "autoflow@example.com".to_string(),
+            to: vec!["a@example.com".to_string(), "b@example.com".to_string()],
+            username: None,
+            password_env: "AUTOFLOW_SMTP_PASSWORD".to_string(),
+            require_tls: true,
+            min_duration_mins: 0,
+        };
+        let message = email_message(&config, "run — done", "line one\n.hidden\n");
+        assert!(message.starts_with("From: autoflow@example.com\r\nTo: a@example.com, b@example.com\r\n"));
+        assert!(message.contains("Subject: =?utf-8?Q?run_=E2=80=94_done?=\r\n"));
+        assert!(message.ends_with("\r\n\r\nline one\r\n..hidden\r\n"));
+    }
+
+    #[test]
+    fn test_private_message_file() {
+        let first = private_message_file("Subject: digest\r\n\r\nbody").unwrap();
+        let second = private_message_file("").unwrap();
+        assert_ne!(first, second);
+        assert_eq!(std::fs::read_to_string(&first).unwrap(), "Subject: digest\r\n\r\nbody");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&first).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+        let _ = std::fs::remove_file(first);
+        let _ = std::fs::remove_file(second);
+    }
+}
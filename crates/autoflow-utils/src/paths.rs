@@ -1,7 +1,10 @@
 // Centralized path constants and utilities
 
+use autoflow_data::AutoFlowError;
 use std::path::{Path, PathBuf};
 
+pub use autoflow_data::{autoflow_dir, home_dir, HOME_ENV};
+
 /// AutoFlow directory structure constants
 pub struct Paths;
 
@@ -45,14 +48,62 @@ impl Paths {
     pub const CLAUDE_SKILLS_DIR: &'static str = ".claude/skills";
 }
 
+/// A project's root directory, the one holding `.autoflow/`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectRoot {
+    path: PathBuf,
+}
+
+impl ProjectRoot {
+    /// An explicit root (`--project`); must be an existing directory
+    pub fn new<P: AsRef<Path>>(path: P) -> autoflow_data::Result<Self> {
+        let path = path.as_ref();
+        if !path.is_dir() {
+            return Err(AutoFlowError::ProjectNotFound(path.display().to_string()));
+        }
+        Ok(Self { path: path.canonicalize()? })
+    }
+
+    /// Walk up from `start` to the nearest directory with `.autoflow/`, or
+    /// failing that the nearest with `.git`
+    ///
+    /// `.autoflow/` wins even when a `.git` is closer, so a meta-project's
+    /// repository checkouts still resolve to the coordinator.
+    pub fn discover(start: &Path) -> Option<Self> {
+        let start = start.canonicalize().ok()?;
+        let find = |marker: &str| start.ancestors().find(|dir| dir.join(marker).exists()).map(Path::to_path_buf);
+        find(Paths::AUTOFLOW_DIR)
+            .or_else(|| find(".git"))
+            .map(|path| Self { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn join<P: AsRef<Path>>(&self, relative: P) -> PathBuf {
+        self.path.join(relative)
+    }
+
+    pub fn sprints_yml(&self) -> PathBuf {
+        self.join(Paths::SPRINTS_YML)
+    }
+
+    /// Make the root the working directory, so `.autoflow/...` paths, git and
+    /// agents all resolve against it
+    pub fn enter(&self) -> std::io::Result<()> {
+        std::env::set_current_dir(&self.path)
+    }
+}
+
 /// Check if project is initialized
 pub fn is_initialized() -> bool {
     Path::new(Paths::AUTOFLOW_DIR).exists()
 }
 
-/// Get home directory path
-pub fn home_dir() -> Option<PathBuf> {
-    std::env::var("HOME").ok().map(PathBuf::from)
+/// Home directory, or an error telling the user how to set one
+pub fn require_home() -> autoflow_data::Result<PathBuf> {
+    home_dir().ok_or(AutoFlowError::HomeNotFound)
 }
 
 /// Get Claude agents directory (global)
@@ -84,6 +135,28 @@ pub fn ensure_dir<P: AsRef<Path>>(path: P) -> std::io::Result<()> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_project_root_discovery() {
+        let root = std::env::temp_dir().join(format!("autoflow-paths-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let nested = root.join("repos/backend/src");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir_all(root.join("repos/backend/.git")).unwrap();
+
+        // Only a .git on the way up
+        let found = ProjectRoot::discover(&nested).unwrap();
+        assert_eq!(found.path(), root.join("repos/backend").canonicalize().unwrap());
+
+        // .autoflow further up beats the nearer .git
+        std::fs::create_dir_all(root.join(Paths::AUTOFLOW_DIR)).unwrap();
+        let found = ProjectRoot::discover(&nested).unwrap();
+        assert_eq!(found.path(), root.canonicalize().unwrap());
+        assert_eq!(found.sprints_yml(), found.path().join(".autoflow/SPRINTS.yml"));
+
+        assert!(ProjectRoot::new(root.join("missing")).is_err());
+        let _ = std::fs::remove_dir_all(root);
+    }
+
     #[test]
     fn test_paths_constants() {
         assert_eq!(Paths::AUTOFLOW_DIR, ".autoflow");
@@ -0,0 +1,146 @@
+/// Environment profiles: which guardrails apply to the current run
+///
+/// `[policy]` in `~/.autoflow/config.toml` sets the base approval, token
+/// budget and tool rules; `[profile.<name>]` overrides them. A profile is
+/// chosen with `--profile` (exported as `AUTOFLOW_PROFILE`) or, failing that,
+/// by matching the checked-out branch against each profile's `branches`.
+use crate::{current_run_id, read_run_events, record_run_event, RUN_EVENTS_PATH};
+use anyhow::{bail, Result};
+use autoflow_data::{append_signoff, ArtifactHash, Config, PolicyConfig, SignOff};
+use colored::*;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Environment variable naming the profile to apply
+pub const PROFILE_ENV: &str = "AUTOFLOW_PROFILE";
+
+/// Set to 1 to approve runs non-interactively (CI) when a profile requires approval
+pub const APPROVE_ENV: &str = "AUTOFLOW_APPROVE";
+
+/// Policy in effect and the profile it came from
+#[derive(Debug, Clone, Default)]
+pub struct ActivePolicy {
+    pub profile: Option<String>,
+    pub policy: PolicyConfig,
+}
+
+/// Resolve the policy for this process from the global config and current branch
+///
+/// Without a config file the base defaults apply, unless a profile was
+/// explicitly requested, which is an error.
+pub fn active_policy() -> Result<ActivePolicy> {
+    let requested = std::env::var(PROFILE_ENV).ok().filter(|p| !p.trim().is_empty());
+    let config = match Config::global() {
+        Ok(config) => config,
+        Err(_) if requested.is_none() => return Ok(ActivePolicy::default()),
+        Err(e) => bail!("Profile '{}' requested but the config could not be read: {}", requested.unwrap_or_default(), e),
+    };
+
+    let profile = config.resolve_profile(requested.as_deref(), current_branch().as_deref())?;
+    let policy = config.policy_for(profile.as_deref());
+    Ok(ActivePolicy { profile, policy })
+}
+
+fn current_branch() -> Option<String> {
+    let output = Command::new("git").args(["rev-parse", "--abbrev-ref", "HEAD"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!branch.is_empty() && branch != "HEAD").then_some(branch)
+}
+
+/// Show the active profile and, when it requires approval, ask before `command` runs
+pub fn ensure_approved(command: &str) -> Result<ActivePolicy> {
+    let active = active_policy()?;
+    if let Some(ref profile) = active.profile {
+        println!("{} {}", "🛡  Profile:".bright_black(), profile.bright_yellow());
+    }
+    if !active.policy.require_approval {
+        return Ok(active);
+    }
+
+    let profile = active.profile.as_deref().unwrap_or("default");
+    request_signoff(
+        &format!("Profile {} requires approval. Run `autoflow {}`?", profile, command),
+        Path::new("."),
+        "run",
+        None,
+        &format!("autoflow {}", command),
+        &[PathBuf::from(crate::Paths::SPRINTS_YML)],
+    )?;
+    Ok(active)
+}
+
+/// Ask a human to confirm `question`
+///
+/// `AUTOFLOW_APPROVE=1` approves without asking; without a terminal to ask
+/// on, the request is refused.
+pub fn request_approval(question: &str) -> Result<()> {
+    if std::env::var(APPROVE_ENV).is_ok_and(|v| v == "1") {
+        println!("  {} Approved via {}", "✓".green(), APPROVE_ENV);
+        return Ok(());
+    }
+    if !io::stdin().is_terminal() {
+        bail!("{} Set {}=1 to approve non-interactively.", question, APPROVE_ENV);
+    }
+
+    print!("  {} [y/N] ", question);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+        bail!("Not approved");
+    }
+    Ok(())
+}
+
+/// Who is approving: `[policy] approver`, else the OS user
+pub fn approver_identity() -> String {
+    let configured = active_policy().ok().and_then(|active| active.policy.approver);
+    configured
+        .or_else(|| std::env::var("USER").ok())
+        .or_else(|| std::env::var("USERNAME").ok())
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Ask for approval like [`request_approval`], then record the sign-off
+pub fn request_signoff(question: &str, project_path: &Path, gate: &str, sprint: Option<u32>, detail: &str, artifacts: &[PathBuf]) -> Result<SignOff> {
+    request_approval(question)?;
+    record_signoff(project_path, gate, sprint, detail, artifacts)
+}
+
+/// Record an approval already given: in `.autoflow/signoffs.jsonl` and as a
+/// `signoff` event in the sprint's history
+///
+/// Artifacts are hashed as they are now; missing ones are left out.
+pub fn record_signoff(project_path: &Path, gate: &str, sprint: Option<u32>, detail: &str, artifacts: &[PathBuf]) -> Result<SignOff> {
+    let via = if std::env::var(APPROVE_ENV).is_ok_and(|v| v == "1") { APPROVE_ENV } else { "prompt" };
+    let signoff = SignOff {
+        gate: gate.to_string(),
+        sprint,
+        approver: approver_identity(),
+        via: via.to_string(),
+        approved_at: chrono::Utc::now(),
+        run_id: current_run_id(),
+        detail: detail.to_string(),
+        artifacts: artifacts.iter().filter_map(|path| ArtifactHash::of(project_path, path).ok()).collect(),
+    };
+    if !autoflow_data::is_read_only() {
+        append_signoff(project_path, &signoff)?;
+    }
+    record_run_event("signoff", sprint, &format!("{} approved by {} via {}", gate, signoff.approver, via));
+    Ok(signoff)
+}
+
+/// Output tokens the current run's agents have used so far
+pub fn run_tokens_used() -> u64 {
+    let Some(run_id) = current_run_id() else {
+        return 0;
+    };
+    read_run_events(RUN_EVENTS_PATH, Some(&run_id))
+        .map(|events| events.iter().filter(|e| e.run_id == run_id).filter_map(|e| e.tokens).sum())
+        .unwrap_or(0)
+}
@@ -0,0 +1,162 @@
+/// Disk, memory and file-descriptor checks before and during runs
+///
+/// Worktrees, per-worktree `node_modules` and logs can fill a disk halfway
+/// through a sprint and leave SPRINTS.yml or git state half-written. `start`
+/// and `create` check resources up front, and `start` re-checks between
+/// sprints so it can stop at a clean boundary instead of mid-phase.
+use autoflow_data::ResourceConfig;
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+
+/// Resources observed at one point in time; None when a value can't be read
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResourceSnapshot {
+    pub free_disk_mb: Option<u64>,
+    pub available_memory_mb: Option<u64>,
+    pub open_files_limit: Option<u64>,
+}
+
+/// A resource below its configured minimum
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceShortage {
+    pub resource: &'static str,
+    pub available: u64,
+    pub required: u64,
+    pub unit: &'static str,
+}
+
+impl fmt::Display for ResourceShortage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} {} available, {} {} required",
+            self.resource, self.available, self.unit, self.required, self.unit
+        )
+    }
+}
+
+impl ResourceSnapshot {
+    /// Read current resources for the filesystem holding `path`
+    pub fn collect(path: &Path) -> Self {
+        Self {
+            free_disk_mb: free_disk_mb(path),
+            available_memory_mb: available_memory_mb(),
+            open_files_limit: open_files_limit(),
+        }
+    }
+
+    /// Resources below their thresholds; unreadable values never count as short
+    pub fn shortages(&self, config: &ResourceConfig) -> Vec<ResourceShortage> {
+        let checks = [
+            ("Free disk", self.free_disk_mb, config.min_free_disk_mb, "MB"),
+            ("Available memory", self.available_memory_mb, config.min_available_memory_mb, "MB"),
+            ("Open file limit", self.open_files_limit, config.min_open_files, "files"),
+        ];
+        checks
+            .into_iter()
+            .filter_map(|(resource, available, required, unit)| match available {
+                Some(available) if required > 0 && available < required => {
+                    Some(ResourceShortage { resource, available, required, unit })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Shortages for `path` using the global `[resources]` config (or defaults)
+pub fn check_resources(path: &Path) -> Vec<ResourceShortage> {
+    let config = autoflow_data::Config::global().map(|c| c.resources).unwrap_or_default();
+    ResourceSnapshot::collect(path).shortages(&config)
+}
+
+/// Fail with a readable report when any resource is below its threshold
+pub fn ensure_resources(path: &Path) -> anyhow::Result<()> {
+    let shortages = check_resources(path);
+    if shortages.is_empty() {
+        return Ok(());
+    }
+    let list = shortages.iter().map(|s| format!("  - {}", s)).collect::<Vec<_>>().join("\n");
+    anyhow::bail!(
+        "Not enough resources to start safely:\n{}\n\nFree some space (old worktrees, node_modules, .autoflow/.debug logs) or lower the thresholds under [resources] in ~/.autoflow/config.toml.",
+        list
+    )
+}
+
+/// Available KB from `df -Pk` (POSIX output format)
+fn free_disk_mb(path: &Path) -> Option<u64> {
+    let output = Command::new("df").arg("-Pk").arg(path).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_df_available_kb(&String::from_utf8_lossy(&output.stdout)).map(|kb| kb / 1024)
+}
+
+fn parse_df_available_kb(output: &str) -> Option<u64> {
+    output.lines().nth(1)?.split_whitespace().nth(3)?.parse().ok()
+}
+
+#[cfg(target_os = "linux")]
+fn available_memory_mb() -> Option<u64> {
+    parse_meminfo_available_kb(&std::fs::read_to_string("/proc/meminfo").ok()?).map(|kb| kb / 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn available_memory_mb() -> Option<u64> {
+    // No cheap, reliable "available" figure outside /proc; skip the check
+    None
+}
+
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+fn parse_meminfo_available_kb(meminfo: &str) -> Option<u64> {
+    meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemAvailable:"))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+#[cfg(unix)]
+fn open_files_limit() -> Option<u64> {
+    let output = Command::new("sh").args(["-c", "ulimit -n"]).output().ok()?;
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "unlimited" => Some(u64::MAX),
+        limit => limit.parse().ok(),
+    }
+}
+
+#[cfg(not(unix))]
+fn open_files_limit() -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_df_and_meminfo() {
+        let df = "Filesystem     1024-blocks      Used Available Capacity Mounted on\n/dev/sda1        102400000  40000000  62400000      40% /\n";
+        assert_eq!(parse_df_available_kb(df), Some(62_400_000));
+
+        let meminfo = "MemTotal:       16384000 kB\nMemFree:         1000000 kB\nMemAvailable:    8192000 kB\n";
+        assert_eq!(parse_meminfo_available_kb(meminfo), Some(8_192_000));
+    }
+
+    #[test]
+    fn test_shortages_respect_thresholds() {
+        let snapshot = ResourceSnapshot {
+            free_disk_mb: Some(500),
+            available_memory_mb: None,
+            open_files_limit: Some(256),
+        };
+        let config = ResourceConfig { min_free_disk_mb: 2048, min_available_memory_mb: 1024, min_open_files: 0 };
+
+        let shortages = snapshot.shortages(&config);
+        assert_eq!(shortages.len(), 1);
+        assert_eq!(shortages[0].to_string(), "Free disk: 500 MB available, 2048 MB required");
+    }
+}
@@ -0,0 +1,212 @@
+/// Side-by-side metrics of two runs, for A/B testing prompt and model changes
+///
+/// Everything comes from the runs' `summary.json`. A phase execution passed
+/// when the sprint moved on to anything but a fix phase or BLOCKED; one still
+/// in progress when the run ended isn't counted either way. Repeats of a
+/// phase within a sprint count as retries.
+use crate::RunSummary;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// How one phase fared across a run's sprints
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct PhaseStats {
+    pub executions: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub retries: usize,
+    pub duration_secs: i64,
+}
+
+impl PhaseStats {
+    /// Share of finished executions that passed, None when none finished
+    pub fn success_rate(&self) -> Option<f64> {
+        let finished = self.passed + self.failed;
+        (finished > 0).then(|| self.passed as f64 / finished as f64)
+    }
+
+    /// Mean wall time of one execution
+    pub fn mean_secs(&self) -> Option<i64> {
+        (self.executions > 0).then(|| self.duration_secs / self.executions as i64)
+    }
+}
+
+/// The numbers compared for one run
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RunMetrics {
+    pub run_id: String,
+    pub outcome: String,
+    pub duration_secs: i64,
+    pub tokens: u64,
+    pub agents_run: usize,
+    pub sprints: usize,
+    pub sprints_done: usize,
+    pub retries: usize,
+    pub errors: usize,
+    /// Keyed by phase name (`WriteCode`, `RunUnitTests`, ...)
+    pub phases: BTreeMap<String, PhaseStats>,
+}
+
+impl RunMetrics {
+    pub fn from_summary(summary: &RunSummary) -> Self {
+        let mut phases: BTreeMap<String, PhaseStats> = BTreeMap::new();
+        for sprint in &summary.sprints {
+            let mut seen: Vec<&str> = Vec::new();
+            for (i, phase) in sprint.phases.iter().enumerate() {
+                let stats = phases.entry(phase.status.clone()).or_default();
+                stats.executions += 1;
+                stats.duration_secs += phase.duration_secs;
+                if seen.contains(&phase.status.as_str()) {
+                    stats.retries += 1;
+                }
+                seen.push(&phase.status);
+
+                let next = match sprint.phases.get(i + 1) {
+                    Some(next) => Some(next.status.as_str()),
+                    None => Some(sprint.final_status.as_str()).filter(|s| *s != phase.status),
+                };
+                match next {
+                    Some(next) if next == "Blocked" || next.ends_with("Fix") => stats.failed += 1,
+                    Some(_) => stats.passed += 1,
+                    None => {}
+                }
+            }
+        }
+
+        Self {
+            run_id: summary.run_id.clone(),
+            outcome: summary.outcome.clone(),
+            duration_secs: summary.duration_secs,
+            tokens: summary.tokens,
+            agents_run: summary.agents_run,
+            sprints: summary.sprints.len(),
+            sprints_done: summary
+                .sprints
+                .iter()
+                .filter(|s| matches!(s.final_status.as_str(), "Done" | "Complete"))
+                .count(),
+            retries: phases.values().map(|p| p.retries).sum(),
+            errors: summary.errors.len(),
+            phases,
+        }
+    }
+}
+
+/// One row of the comparison: a metric's value in each run and the change
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ComparisonRow {
+    pub metric: String,
+    pub a: String,
+    pub b: String,
+    pub change: String,
+}
+
+/// Rows comparing run `a` with run `b`, run-level metrics first, then each phase
+pub fn compare_runs(a: &RunMetrics, b: &RunMetrics) -> Vec<ComparisonRow> {
+    let mut rows = vec![
+        row("Outcome", a.outcome.clone(), b.outcome.clone(), String::new()),
+        count_row("Sprints done", a.sprints_done as f64, b.sprints_done as f64, |v| {
+            format!("{}", v as usize)
+        }),
+        count_row("Duration", a.duration_secs as f64, b.duration_secs as f64, |v| format_secs(v as i64)),
+        count_row("Output tokens", a.tokens as f64, b.tokens as f64, |v| format!("{}", v as u64)),
+        count_row("Agent runs", a.agents_run as f64, b.agents_run as f64, |v| format!("{}", v as usize)),
+        count_row("Retries", a.retries as f64, b.retries as f64, |v| format!("{}", v as usize)),
+        count_row("Errors", a.errors as f64, b.errors as f64, |v| format!("{}", v as usize)),
+    ];
+
+    let names: std::collections::BTreeSet<&String> = a.phases.keys().chain(b.phases.keys()).collect();
+    let empty = PhaseStats::default();
+    for name in names {
+        let pa = a.phases.get(name).unwrap_or(&empty);
+        let pb = b.phases.get(name).unwrap_or(&empty);
+        let rate = |p: &PhaseStats| p.success_rate().map(|r| format!("{:.0}%", r * 100.0)).unwrap_or("-".to_string());
+        let change = match (pa.success_rate(), pb.success_rate()) {
+            (Some(ra), Some(rb)) if ra == rb => "=".to_string(),
+            (Some(ra), Some(rb)) => format!("{:+.0} pts", (rb - ra) * 100.0),
+            _ => String::new(),
+        };
+        rows.push(row(&format!("{} success", name), rate(pa), rate(pb), change));
+        rows.push(count_row(&format!("{} retries", name), pa.retries as f64, pb.retries as f64, |v| {
+            format!("{}", v as usize)
+        }));
+        let mean = |p: &PhaseStats| p.mean_secs().map(format_secs).unwrap_or("-".to_string());
+        let change = match (pa.mean_secs(), pb.mean_secs()) {
+            (Some(ma), Some(mb)) => percent_change(ma as f64, mb as f64),
+            _ => String::new(),
+        };
+        rows.push(row(&format!("{} mean time", name), mean(pa), mean(pb), change));
+    }
+    rows
+}
+
+fn row(metric: &str, a: String, b: String, change: String) -> ComparisonRow {
+    ComparisonRow { metric: metric.to_string(), a, b, change }
+}
+
+fn count_row(metric: &str, a: f64, b: f64, format: impl Fn(f64) -> String) -> ComparisonRow {
+    row(metric, format(a), format(b), percent_change(a, b))
+}
+
+/// `+12%` style change from `a` to `b`; empty when `a` is 0
+fn percent_change(a: f64, b: f64) -> String {
+    if a == b {
+        "=".to_string()
+    } else if a == 0.0 {
+        String::new()
+    } else {
+        format!("{:+.0}%", (b - a) / a * 100.0)
+    }
+}
+
+fn format_secs(secs: i64) -> String {
+    if secs >= 60 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_runs() {
+        let summary: RunSummary = serde_json::from_value(serde_json::json!({
+            "run_id": "01AAAA", "command": "start", "outcome": "completed",
+            "started_at": "2025-01-01T00:00:00Z", "finished_at": "2025-01-01T00:10:00Z",
+            "duration_secs": 600, "phases_executed": 6, "agents_run": 6, "tokens": 1000,
+            "commits": [], "errors": [],
+            "sprints": [{
+                "id": 1, "goal": "Checkout", "final_status": "Done", "duration_secs": 600,
+                "tokens": 1000, "errors": [],
+                "phases": [
+                    {"status": "WriteCode", "started_at": "2025-01-01T00:00:00Z", "duration_secs": 100},
+                    {"status": "RunUnitTests", "started_at": "2025-01-01T00:00:00Z", "duration_secs": 20},
+                    {"status": "UnitFix", "started_at": "2025-01-01T00:00:00Z", "duration_secs": 60},
+                    {"status": "RunUnitTests", "started_at": "2025-01-01T00:00:00Z", "duration_secs": 40},
+                    {"status": "CodeReview", "started_at": "2025-01-01T00:00:00Z", "duration_secs": 50}
+                ]
+            }]
+        }))
+        .unwrap();
+        let a = RunMetrics::from_summary(&summary);
+        let tests = &a.phases["RunUnitTests"];
+        assert_eq!((tests.executions, tests.passed, tests.failed, tests.retries), (2, 1, 1, 1));
+        assert_eq!(tests.success_rate(), Some(0.5));
+        assert_eq!(tests.mean_secs(), Some(30));
+        assert_eq!(a.phases["CodeReview"].passed, 1);
+        assert_eq!((a.sprints_done, a.retries), (1, 1));
+
+        let mut b = a.clone();
+        b.tokens = 1500;
+        b.phases.get_mut("RunUnitTests").unwrap().failed = 0;
+        let rows = compare_runs(&a, &b);
+        let find = |metric: &str| rows.iter().find(|r| r.metric == metric).unwrap();
+        assert_eq!(find("Output tokens").change, "+50%");
+        assert_eq!(find("Duration").change, "=");
+        let tests = find("RunUnitTests success");
+        assert_eq!((tests.a.as_str(), tests.b.as_str(), tests.change.as_str()), ("50%", "100%", "+50 pts"));
+    }
+}
@@ -0,0 +1,206 @@
+/// Run identifiers tying together everything one AutoFlow invocation produces
+///
+/// Orchestrated commands (`start`, `fix`, `pivot`, `create`) generate a ULID
+/// when they begin. It is exported as `AUTOFLOW_RUN_ID` so every component
+/// (and every agent subprocess) can stamp it on live log filenames, debug
+/// logs, commit trailers and reports, and it keys the run event stream in
+/// `.autoflow/runs.jsonl`.
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+/// Environment variable holding the current run id
+pub const RUN_ID_ENV: &str = "AUTOFLOW_RUN_ID";
+
+/// Run event stream, relative to the project root
+pub const RUN_EVENTS_PATH: &str = ".autoflow/runs.jsonl";
+
+/// Git trailer carrying the run id on automated commits
+pub const RUN_ID_TRAILER: &str = "AutoFlow-Run";
+
+/// One line of `.autoflow/runs.jsonl`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RunEvent {
+    pub run_id: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// e.g. `started`, `phase`, `agent`, `error`, `finished`
+    pub event: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sprint: Option<u32>,
+    /// Stable uid of `sprint`, which still identifies it after renumbering
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sprint_uid: Option<String>,
+    #[serde(default)]
+    pub detail: String,
+    /// Set on `agent` events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<u64>,
+    /// Sampling parameters the agent's model ran with, e.g. `temperature=0 seed=7`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<String>,
+}
+
+impl RunEvent {
+    /// Whether the event belongs to `sprint`: by uid when both carry one,
+    /// otherwise (events from before uids) by numeric id
+    pub fn is_for(&self, sprint: &autoflow_data::Sprint) -> bool {
+        match (&self.sprint_uid, &sprint.uid) {
+            (Some(event_uid), Some(uid)) => event_uid == uid,
+            _ => self.sprint == Some(sprint.id),
+        }
+    }
+}
+
+/// Generate a new run id (a ULID)
+pub fn new_run_id() -> String {
+    autoflow_data::new_ulid()
+}
+
+/// Start a run for `command`: generate its id, export it and record the start
+///
+/// An id already in the environment (e.g. set by a wrapper script) is kept so
+/// nested invocations share it.
+pub fn start_run(command: &str) -> String {
+    let run_id = current_run_id().unwrap_or_else(new_run_id);
+    std::env::set_var(RUN_ID_ENV, &run_id);
+    record_run_event("started", None, command);
+    run_id
+}
+
+/// Id of the run this process belongs to, if any
+pub fn current_run_id() -> Option<String> {
+    std::env::var(RUN_ID_ENV).ok().filter(|id| !id.trim().is_empty())
+}
+
+/// Append an event for the current run to the event stream (best effort)
+pub fn record_run_event(event: &str, sprint: Option<u32>, detail: &str) {
+    record(RunEvent {
+        event: event.to_string(),
+        sprint,
+        detail: detail.to_string(),
+        ..Default::default()
+    });
+}
+
+/// Record one finished agent execution with its token usage and duration
+///
+/// `sampling` describes the sampling parameters applied, when there were any.
+pub fn record_agent_run(agent: &str, sprint: Option<u32>, success: bool, tokens: u64, duration_secs: u64, sampling: Option<&str>) {
+    record(RunEvent {
+        event: "agent".to_string(),
+        sprint,
+        detail: if success { "ok" } else { "failed" }.to_string(),
+        agent: Some(agent.to_string()),
+        tokens: Some(tokens),
+        duration_secs: Some(duration_secs),
+        sampling: sampling.map(String::from),
+        ..Default::default()
+    });
+}
+
+fn sprint_uids() -> &'static Mutex<HashMap<u32, String>> {
+    static UIDS: OnceLock<Mutex<HashMap<u32, String>>> = OnceLock::new();
+    UIDS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Note sprint `id`'s uid so this process's events about it carry it
+pub fn register_sprint_uid(id: u32, uid: Option<&str>) {
+    if let (Some(uid), Ok(mut uids)) = (uid, sprint_uids().lock()) {
+        uids.insert(id, uid.to_string());
+    }
+}
+
+fn record(mut event: RunEvent) {
+    let Some(run_id) = current_run_id() else {
+        return;
+    };
+    if !Path::new(".autoflow").exists() || autoflow_data::is_read_only() {
+        return;
+    }
+    event.run_id = run_id;
+    event.timestamp = chrono::Utc::now();
+    event.detail = crate::strings::sanitize_output(&event.detail);
+    if event.sprint_uid.is_none() {
+        event.sprint_uid = event
+            .sprint
+            .and_then(|id| sprint_uids().lock().ok().and_then(|uids| uids.get(&id).cloned()));
+    }
+    if let Err(e) = append_run_event(RUN_EVENTS_PATH, &event) {
+        tracing::debug!("Failed to record run event: {}", e);
+    }
+    crate::runtime_status::update_runtime_status(&event);
+    crate::webhook::dispatch_run_event(&event);
+}
+
+/// Append `event` to the event stream at `path`
+pub fn append_run_event<P: AsRef<Path>>(path: P, event: &RunEvent) -> Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    autoflow_data::append_state(path, &serde_json::to_string(event)?)?;
+    Ok(())
+}
+
+/// Read the event stream at `path`, optionally only one run's events
+///
+/// `run_id` may be a prefix of the full id. Unparseable lines are skipped.
+pub fn read_run_events<P: AsRef<Path>>(path: P, run_id: Option<&str>) -> Result<Vec<RunEvent>> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let wanted = run_id.map(|id| id.to_uppercase());
+    let events = autoflow_data::read_state_to_string(path)?
+        .lines()
+        .filter_map(|line| serde_json::from_str::<RunEvent>(line).ok())
+        .filter(|event| wanted.as_deref().is_none_or(|id| event.run_id.starts_with(id)))
+        .collect();
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_ids_are_ulids_sorted_by_time() {
+        let first = new_run_id();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let second = new_run_id();
+
+        assert_eq!(first.len(), 26);
+        assert!(first.bytes().all(|b| autoflow_data::CROCKFORD_ALPHABET.contains(&b)));
+        assert_ne!(first, second);
+        assert!(first[..10] < second[..10]);
+    }
+
+    #[test]
+    fn test_read_run_events_filters_by_run() {
+        let path = std::env::temp_dir().join(format!("autoflow-run-events-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        for (run_id, event) in [("01AAAA", "started"), ("01BBBB", "started"), ("01AAAA", "finished")] {
+            let event = RunEvent {
+                run_id: run_id.to_string(),
+                timestamp: chrono::Utc::now(),
+                event: event.to_string(),
+                ..Default::default()
+            };
+            append_run_event(&path, &event).unwrap();
+        }
+
+        assert_eq!(read_run_events(&path, None).unwrap().len(), 3);
+        let events = read_run_events(&path, Some("01aa")).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].event, "finished");
+
+        let _ = fs::remove_file(path);
+    }
+}
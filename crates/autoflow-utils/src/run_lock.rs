@@ -0,0 +1,280 @@
+//! Run lock guarding a checkout against concurrent AutoFlow runs
+//!
+//! `start`, `fix` and `pivot` take the lock before launching agents so two
+//! people (or a person and a daemon) never drive the same working tree at
+//! once. The lock file records who holds it, and the holder keeps an
+//! exclusive advisory lock on it for the whole run. The OS drops that lock
+//! when the process exits, so a lock left behind by a crash on this host is
+//! simply taken over.
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Lock file location, relative to the project root
+pub const RUN_LOCK_PATH: &str = ".autoflow/run.lock";
+
+/// Contents of the lock file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LockInfo {
+    pub pid: u32,
+    pub host: String,
+    pub command: String,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+}
+
+impl LockInfo {
+    fn current(command: &str) -> Self {
+        Self {
+            pid: std::process::id(),
+            host: hostname(),
+            command: command.to_string(),
+            started_at: chrono::Utc::now(),
+            run_id: crate::current_run_id(),
+        }
+    }
+
+    /// True when the holder is known to be gone
+    ///
+    /// Only locks from this host can be checked; a lock taken on another
+    /// machine (shared checkout) is never considered stale.
+    pub fn is_stale(&self) -> bool {
+        self.host == hostname() && !process_alive(self.pid)
+    }
+}
+
+/// Held run lock; released when dropped
+#[derive(Debug)]
+pub struct RunLock {
+    path: PathBuf,
+    /// Open for as long as the lock is held, carrying the advisory lock
+    file: File,
+}
+
+impl RunLock {
+    /// Acquire the lock at the default location for `command`
+    pub fn acquire(command: &str, steal: bool) -> Result<Self> {
+        Self::acquire_at(RUN_LOCK_PATH, command, steal)
+    }
+
+    /// Acquire the lock at `path`, taking over a stale lock or, with `steal`, a live one
+    ///
+    /// The lock file is only written once its advisory lock is held, so a
+    /// concurrent acquirer never sees a half-written record. A stolen lock
+    /// is unlinked and recreated; its old holder keeps a lock on a file no
+    /// longer at `path`.
+    pub fn acquire_at<P: AsRef<Path>>(path: P, command: &str, steal: bool) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Two attempts: the second follows removal of a stolen lock or a race with a releasing holder
+        for _ in 0..2 {
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&path)
+                .with_context(|| format!("Failed to open lock {}", path.display()))?;
+            match file.try_lock() {
+                Ok(()) => {}
+                Err(TryLockError::WouldBlock) => {
+                    let holder = read_lock(&path);
+                    if !steal {
+                        bail!(held_message(&path, holder.as_ref()));
+                    }
+                    if let Some(holder) = holder {
+                        tracing::warn!("Replacing held lock from pid {} on {} ({})", holder.pid, holder.host, holder.command);
+                    }
+                    fs::remove_file(&path).with_context(|| format!("Failed to remove lock {}", path.display()))?;
+                    continue;
+                }
+                Err(TryLockError::Error(e)) => {
+                    return Err(e).with_context(|| format!("Failed to lock {}", path.display()))
+                }
+            }
+
+            // The holder may have released and unlinked the file between our open and lock
+            if !is_same_file(&file, &path) {
+                continue;
+            }
+
+            // Advisory locks don't reach every shared filesystem, so a record
+            // from another machine still counts until it is stolen
+            if let Some(holder) = read_lock(&path).filter(|h| h.host != hostname()) {
+                if !steal {
+                    bail!(held_message(&path, Some(&holder)));
+                }
+                tracing::warn!("Replacing held lock from pid {} on {} ({})", holder.pid, holder.host, holder.command);
+            }
+
+            let mut lock = Self { path, file };
+            lock.write_record(command)?;
+            return Ok(lock);
+        }
+
+        bail!("Could not acquire {} (another run raced us)", path.display())
+    }
+
+    fn write_record(&mut self, command: &str) -> Result<()> {
+        let info = serde_json::to_string_pretty(&LockInfo::current(command))?;
+        self.file.set_len(0)?;
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.write_all(info.as_bytes())?;
+        self.file.sync_all()?;
+        Ok(())
+    }
+}
+
+impl Drop for RunLock {
+    fn drop(&mut self) {
+        // Only remove the lock file if it is still ours (it may have been stolen)
+        if is_same_file(&self.file, &self.path) {
+            let _ = fs::remove_file(&self.path);
+        }
+        let _ = self.file.unlock();
+    }
+}
+
+fn held_message(path: &Path, holder: Option<&LockInfo>) -> String {
+    match holder {
+        Some(holder) => format!(
+            "Another AutoFlow run holds the lock ({}):\n  command: autoflow {}\n  pid: {} on {}\n  started: {}\n  run: {}\n\nWait for it to finish, or pass --steal-lock if you are sure it is gone.",
+            path.display(),
+            holder.command,
+            holder.pid,
+            holder.host,
+            holder.started_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            holder.run_id.as_deref().unwrap_or("unknown")
+        ),
+        None => format!(
+            "Another AutoFlow run holds the lock ({}).\n\nWait for it to finish, or pass --steal-lock if you are sure it is gone.",
+            path.display()
+        ),
+    }
+}
+
+/// True when `path` still names the file `file` has open
+#[cfg(unix)]
+fn is_same_file(file: &File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (file.metadata(), fs::metadata(path)) {
+        (Ok(open), Ok(named)) => open.dev() == named.dev() && open.ino() == named.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_same_file(_file: &File, path: &Path) -> bool {
+    // An open file can't be unlinked on Windows, so the path can't have moved on
+    path.exists()
+}
+
+/// Read the current lock holder, if any
+pub fn read_lock<P: AsRef<Path>>(path: P) -> Option<LockInfo> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+}
+
+pub(crate) fn hostname() -> String {
+    std::env::var("HOSTNAME")
+        .ok()
+        .filter(|h| !h.is_empty())
+        .or_else(|| fs::read_to_string("/etc/hostname").ok().map(|h| h.trim().to_string()))
+        .filter(|h| !h.is_empty())
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// True unless `pid` is known not to exist
+///
+/// EPERM means the process exists but belongs to another user, so it counts as alive.
+#[cfg(unix)]
+pub(crate) fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: signal 0 only checks that the process exists and may be signalled
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return true;
+    }
+    std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn process_alive(_pid: u32) -> bool {
+    // Cannot check cheaply; assume alive so we never steal by mistake
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_lock(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("autoflow-lock-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("run.lock")
+    }
+
+    #[test]
+    fn test_lock_is_exclusive_and_released_on_drop() {
+        let path = temp_lock("exclusive");
+        let lock = RunLock::acquire_at(&path, "start", false).unwrap();
+        assert_eq!(read_lock(&path).unwrap().command, "start");
+        assert!(RunLock::acquire_at(&path, "fix", false).is_err());
+
+        drop(lock);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_stale_and_stolen_locks_are_replaced() {
+        let path = temp_lock("stale");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        // A pid that cannot exist on this host
+        let mut dead = LockInfo::current("start");
+        dead.pid = u32::MAX - 1;
+        fs::write(&path, serde_json::to_string(&dead).unwrap()).unwrap();
+        let lock = RunLock::acquire_at(&path, "pivot", false).unwrap();
+        assert_eq!(read_lock(&path).unwrap().command, "pivot");
+        drop(lock);
+
+        // A live lock from another host needs --steal-lock
+        let mut remote = LockInfo::current("start");
+        remote.host = "other-machine".to_string();
+        fs::write(&path, serde_json::to_string(&remote).unwrap()).unwrap();
+        assert!(RunLock::acquire_at(&path, "fix", false).is_err());
+        let _lock = RunLock::acquire_at(&path, "fix", true).unwrap();
+        assert_eq!(read_lock(&path).unwrap().host, hostname());
+    }
+
+    #[test]
+    fn test_stealing_a_live_lock_leaves_the_new_one_in_place() {
+        let path = temp_lock("steal");
+        let held = RunLock::acquire_at(&path, "start", false).unwrap();
+        assert!(RunLock::acquire_at(&path, "fix", false).unwrap_err().to_string().contains("command: autoflow start"));
+
+        let stolen = RunLock::acquire_at(&path, "fix", true).unwrap();
+        drop(held);
+        assert_eq!(read_lock(&path).unwrap().command, "fix");
+        drop(stolen);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_process_alive() {
+        assert!(process_alive(std::process::id()));
+        // Another user's process can't be signalled but is still alive
+        assert!(process_alive(1));
+        assert!(!process_alive(i32::MAX as u32));
+        assert!(!process_alive(u32::MAX - 1));
+    }
+}
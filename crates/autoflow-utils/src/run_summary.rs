@@ -0,0 +1,380 @@
+/// Machine-readable outcome of one run
+///
+/// Written to `.autoflow/runs/<run-id>/summary.json` when an orchestrated
+/// command finishes, from the run's events in `.autoflow/runs.jsonl`, the
+/// final sprint states and the commits carrying the run's trailer. CI and
+/// dashboards read this instead of scraping console output.
+use crate::{
+    blocked_issue_body, blocked_issue_title, current_run_id, deliver_webhook, detect_forge, digest_body, digest_subject, email_message, file_issue, flush_webhooks, project_name, publish_run_artifacts, read_run_events, record_issue_urls, record_run_event, send_email, sprints_needing_issues, webhook_for, Paths, RunEvent, WebhookPayload, RUN_EVENTS_PATH, RUN_ID_TRAILER,
+};
+use anyhow::{Context, Result};
+use autoflow_data::{Config, Sprint};
+use colored::*;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Per-run output directory, relative to the project root
+pub const RUNS_DIR: &str = ".autoflow/runs";
+
+/// List price per million tokens (USD), Sonnet-class models
+pub const INPUT_PRICE_PER_MTOK: f64 = 3.0;
+pub const OUTPUT_PRICE_PER_MTOK: f64 = 15.0;
+
+/// `.autoflow/runs/<run-id>/summary.json`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RunSummary {
+    pub run_id: String,
+    pub command: String,
+    /// `completed`, `paused`, `failed`, ...
+    pub outcome: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub duration_secs: i64,
+    pub sprints: Vec<SprintRunSummary>,
+    pub phases_executed: usize,
+    pub agents_run: usize,
+    pub tokens: u64,
+    pub commits: Vec<RunCommit>,
+    pub errors: Vec<String>,
+    /// Links to the run's uploaded artifacts (`[artifacts]` in config)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<String>,
+}
+
+/// What one sprint went through during the run
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SprintRunSummary {
+    pub id: u32,
+    /// The sprint's stable uid, when its events carried one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+    pub goal: String,
+    pub final_status: String,
+    pub phases: Vec<PhaseRun>,
+    pub duration_secs: i64,
+    pub tokens: u64,
+    pub errors: Vec<String>,
+}
+
+/// One phase execution; duration runs until the sprint's next phase (or the run's end)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PhaseRun {
+    pub status: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_secs: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RunCommit {
+    pub sha: String,
+    pub subject: String,
+}
+
+impl RunSummary {
+    /// Build a summary from a run's events and the sprints as they ended
+    pub fn from_events(
+        run_id: &str,
+        events: &[RunEvent],
+        sprints: &[Sprint],
+        outcome: &str,
+        commits: Vec<RunCommit>,
+    ) -> Self {
+        let now = Utc::now();
+        let started_at = events.first().map(|e| e.timestamp).unwrap_or(now);
+        let finished_at = events.last().map(|e| e.timestamp).unwrap_or(now).max(started_at);
+        let command = events
+            .iter()
+            .find(|e| e.event == "started")
+            .map(|e| e.detail.clone())
+            .unwrap_or_default();
+
+        let mut sprint_ids: Vec<u32> = events.iter().filter_map(|e| e.sprint).collect();
+        sprint_ids.sort_unstable();
+        sprint_ids.dedup();
+
+        let sprint_summaries = sprint_ids
+            .into_iter()
+            .map(|id| {
+                let own: Vec<&RunEvent> = events.iter().filter(|e| e.sprint == Some(id)).collect();
+                let phase_events: Vec<&&RunEvent> = own.iter().filter(|e| e.event == "phase").collect();
+                let phases = phase_events
+                    .iter()
+                    .enumerate()
+                    .map(|(i, e)| {
+                        let end = phase_events.get(i + 1).map(|next| next.timestamp).unwrap_or(finished_at);
+                        PhaseRun {
+                            status: e.detail.clone(),
+                            started_at: e.timestamp,
+                            duration_secs: (end - e.timestamp).num_seconds(),
+                        }
+                    })
+                    .collect();
+                let uid = own.iter().find_map(|e| e.sprint_uid.clone());
+                let sprint = sprints
+                    .iter()
+                    .find(|s| uid.is_some() && s.uid == uid)
+                    .or_else(|| sprints.iter().find(|s| s.id == id));
+                SprintRunSummary {
+                    id,
+                    uid,
+                    goal: sprint.map(|s| s.goal.clone()).unwrap_or_default(),
+                    final_status: sprint.map(|s| format!("{:?}", s.status)).unwrap_or_default(),
+                    phases,
+                    duration_secs: match (own.first(), own.last()) {
+                        (Some(first), Some(last)) => (last.timestamp - first.timestamp).num_seconds(),
+                        _ => 0,
+                    },
+                    tokens: own.iter().filter_map(|e| e.tokens).sum(),
+                    errors: own.iter().filter(|e| is_error(e)).map(|e| e.detail.clone()).collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            run_id: run_id.to_string(),
+            command,
+            outcome: outcome.to_string(),
+            started_at,
+            finished_at,
+            duration_secs: (finished_at - started_at).num_seconds(),
+            sprints: sprint_summaries,
+            phases_executed: events.iter().filter(|e| e.event == "phase").count(),
+            agents_run: events.iter().filter(|e| e.event == "agent").count(),
+            tokens: events.iter().filter_map(|e| e.tokens).sum(),
+            commits,
+            errors: events.iter().filter(|e| is_error(e)).map(|e| e.detail.clone()).collect(),
+            artifacts: Vec::new(),
+        }
+    }
+
+    /// Rough cost of the run's output tokens (input isn't recorded)
+    pub fn output_cost_usd(&self) -> f64 {
+        self.tokens as f64 * OUTPUT_PRICE_PER_MTOK / 1_000_000.0
+    }
+
+    pub fn path(project_path: &Path, run_id: &str) -> PathBuf {
+        project_path.join(RUNS_DIR).join(run_id).join("summary.json")
+    }
+
+    pub fn save(&self, project_path: &Path) -> Result<PathBuf> {
+        let path = Self::path(project_path, &self.run_id);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        autoflow_data::write_state(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = autoflow_data::read_state_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Most recent summary (run ids sort by time)
+    pub fn latest(project_path: &Path) -> Result<Option<Self>> {
+        match Self::run_ids(project_path)?.last() {
+            Some(run_id) => Ok(Some(Self::load(&Self::path(project_path, run_id))?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Summary of the one run whose id starts with `prefix` (any case)
+    pub fn find(project_path: &Path, prefix: &str) -> Result<Self> {
+        let wanted = prefix.trim().to_uppercase();
+        let matches: Vec<String> = Self::run_ids(project_path)?
+            .into_iter()
+            .filter(|id| !wanted.is_empty() && id.starts_with(&wanted))
+            .collect();
+        match matches.as_slice() {
+            [run_id] => Self::load(&Self::path(project_path, run_id)),
+            [] => anyhow::bail!("No run summary matches '{}' in {}", prefix, RUNS_DIR),
+            _ => anyhow::bail!("'{}' matches {} runs: {}", prefix, matches.len(), matches.join(", ")),
+        }
+    }
+
+    /// Ids of the runs that have a summary, oldest first
+    fn run_ids(project_path: &Path) -> Result<Vec<String>> {
+        let dir = project_path.join(RUNS_DIR);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut run_ids: Vec<String> = fs::read_dir(&dir)?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().join("summary.json").exists())
+            .filter_map(|e| e.file_name().to_str().map(String::from))
+            .collect();
+        run_ids.sort();
+        Ok(run_ids)
+    }
+}
+
+fn is_error(event: &RunEvent) -> bool {
+    matches!(event.event.as_str(), "error" | "agent_stalled" | "paused")
+        || (event.event == "agent" && event.detail == "failed")
+}
+
+/// Record the end of the current run and write its summary (best effort)
+///
+/// Returns the summary path, or None outside a run or without `.autoflow/`.
+pub fn finish_run(outcome: &str, sprints: &[Sprint]) -> Option<PathBuf> {
+    let run_id = current_run_id()?;
+    record_run_event("finished", None, outcome);
+
+    let events = read_run_events(RUN_EVENTS_PATH, Some(&run_id)).ok()?;
+    let events: Vec<RunEvent> = events.into_iter().filter(|e| e.run_id == run_id).collect();
+    if events.is_empty() {
+        return None;
+    }
+    let mut summary = RunSummary::from_events(&run_id, &events, sprints, outcome, run_commits(&run_id));
+    let path = match summary.save(Path::new(".")) {
+        Ok(path) => path,
+        Err(e) => {
+            tracing::warn!("Failed to write run summary: {}", e);
+            return None;
+        }
+    };
+
+    let artifacts = Config::global().map(|c| c.artifacts).unwrap_or_default();
+    if artifacts.destination.is_some() {
+        match publish_run_artifacts(Path::new("."), &mut summary, &events, &artifacts) {
+            Ok(()) => {
+                println!("{}", "☁  Run artifacts:".bright_black());
+                for url in &summary.artifacts {
+                    println!("   {}", url);
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Artifact upload failed: {}", e);
+                println!("{} Artifact upload failed: {}", "⚠".yellow(), e);
+            }
+        }
+    }
+
+    let forge = Config::global().map(|c| c.forge).unwrap_or_default();
+    if forge.file_blocked_issues {
+        file_blocked_issues(&forge, sprints, &summary, &events);
+    }
+
+    let project = project_name();
+    flush_webhooks();
+    if let Some(webhook) = webhook_for("run.finished") {
+        match deliver_webhook(&webhook, &WebhookPayload::run_finished(&summary, &project)) {
+            Ok(()) => println!("{} Run webhook delivered to {}", "↗".bright_black(), webhook.url),
+            Err(e) => {
+                tracing::warn!("Run webhook failed: {}", e);
+                println!("{} Run webhook failed: {}", "⚠".yellow(), e);
+            }
+        }
+    }
+
+    let email = Config::global().ok().and_then(|c| c.notify.email);
+    if let Some(email) = email.filter(|e| summary.duration_secs >= (e.min_duration_mins * 60) as i64) {
+        let message = email_message(&email, &digest_subject(&project, &summary), &digest_body(&project, &summary));
+        match send_email(&email, &message) {
+            Ok(()) => println!("{} Run digest sent to {}", "✉".bright_black(), email.to.join(", ")),
+            Err(e) => {
+                tracing::warn!("Run digest email failed: {}", e);
+                println!("{} Run digest email failed: {}", "⚠".yellow(), e);
+            }
+        }
+    }
+    Some(path)
+}
+
+/// File an issue for each sprint the resolver couldn't unblock and note it in SPRINTS.yml
+fn file_blocked_issues(forge: &autoflow_data::ForgeConfig, sprints: &[Sprint], summary: &RunSummary, events: &[RunEvent]) {
+    let blocked = sprints_needing_issues(sprints, events);
+    if blocked.is_empty() {
+        return;
+    }
+    let Some(provider) = detect_forge(forge) else {
+        println!("{} Not filing issues for blocked sprints: set [forge] provider", "⚠".yellow());
+        return;
+    };
+
+    let mut filed = Vec::new();
+    for sprint in blocked {
+        let body = blocked_issue_body(sprint, summary, Path::new("."));
+        match file_issue(forge, provider, &blocked_issue_title(sprint), &body) {
+            Ok(url) => {
+                println!("{} Filed issue for blocked Sprint {}: {}", "✓".green(), sprint.id, url);
+                record_run_event("issue_filed", Some(sprint.id), &url);
+                filed.push((sprint.id, url));
+            }
+            Err(e) => {
+                tracing::warn!("Failed to file issue for sprint {}: {}", sprint.id, e);
+                println!("{} Could not file issue for blocked Sprint {}: {}", "⚠".yellow(), sprint.id, e);
+            }
+        }
+    }
+    if !filed.is_empty() {
+        if let Err(e) = record_issue_urls(Path::new(Paths::SPRINTS_YML), &filed) {
+            tracing::warn!("Failed to record issue URLs in SPRINTS.yml: {}", e);
+        }
+    }
+}
+
+/// Commits made by the run, found by their trailer
+fn run_commits(run_id: &str) -> Vec<RunCommit> {
+    let output = Command::new("git")
+        .args(["log", "--all", "--fixed-strings", "--format=%H%x09%s"])
+        .arg(format!("--grep={}: {}", RUN_ID_TRAILER, run_id))
+        .output();
+    match output {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(sha, subject)| RunCommit { sha: sha.to_string(), subject: subject.to_string() })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn event(at: DateTime<Utc>, name: &str, sprint: Option<u32>, detail: &str) -> RunEvent {
+        RunEvent {
+            run_id: "01RUN".to_string(),
+            timestamp: at,
+            event: name.to_string(),
+            sprint,
+            detail: detail.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_summary_from_events() {
+        let t0 = Utc::now();
+        let mut agent = event(t0 + Duration::seconds(5), "agent", Some(1), "ok");
+        agent.tokens = Some(1200);
+        let events = vec![
+            event(t0, "started", None, "start"),
+            event(t0 + Duration::seconds(1), "phase", Some(1), "WriteCode"),
+            agent,
+            event(t0 + Duration::seconds(30), "phase", Some(1), "RunUnitTests"),
+            event(t0 + Duration::seconds(40), "error", Some(1), "tests failed"),
+            event(t0 + Duration::seconds(60), "finished", None, "completed"),
+        ];
+
+        let summary = RunSummary::from_events("01RUN", &events, &[], "completed", Vec::new());
+        assert_eq!(summary.command, "start");
+        assert_eq!(summary.duration_secs, 60);
+        assert_eq!(summary.phases_executed, 2);
+        assert_eq!(summary.tokens, 1200);
+        assert_eq!(summary.errors, vec!["tests failed"]);
+
+        let sprint = &summary.sprints[0];
+        assert_eq!(sprint.id, 1);
+        assert_eq!(sprint.phases[0].status, "WriteCode");
+        assert_eq!(sprint.phases[0].duration_secs, 29);
+        assert_eq!(sprint.phases[1].duration_secs, 30);
+        assert_eq!(sprint.duration_secs, 39);
+    }
+}
@@ -0,0 +1,231 @@
+/// Live progress of the run in progress, for `autoflow status` in another terminal
+///
+/// SPRINTS.yml is only written between phases, so while an agent works it
+/// shows where the sprint was when the phase began. The running process keeps
+/// `.autoflow/runtime-status.json` current instead: every run event and every
+/// agent start rewrites it, and `finish_run` removes it. Readers trust it only
+/// while the process that wrote it is still alive.
+use crate::run_lock::{hostname, process_alive};
+use crate::RunEvent;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Live run status, relative to the project root
+pub const RUNTIME_STATUS_PATH: &str = ".autoflow/runtime-status.json";
+
+/// How long a status written on another host counts as live
+const REMOTE_FRESH_SECS: i64 = 15 * 60;
+
+/// `.autoflow/runtime-status.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RuntimeStatus {
+    pub run_id: String,
+    pub command: String,
+    pub pid: u32,
+    pub host: String,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Latest phase of each sprint this run has touched
+    pub sprints: BTreeMap<u32, String>,
+    /// Agents running right now (several with `start --parallel`)
+    pub agents: Vec<ActiveAgent>,
+    /// Output tokens used so far
+    pub tokens: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_event: Option<String>,
+    /// Since when the run has been paused waiting for the network
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waiting_for_network: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActiveAgent {
+    pub agent: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sprint: Option<u32>,
+    pub started_at: DateTime<Utc>,
+}
+
+impl RuntimeStatus {
+    pub fn load<P: AsRef<Path>>(path: P) -> Option<Self> {
+        fs::read_to_string(path).ok().and_then(|c| serde_json::from_str(&c).ok())
+    }
+
+    /// True while the writing process is alive (or, from another host, recently updated)
+    pub fn is_fresh(&self) -> bool {
+        if self.host == hostname() {
+            process_alive(self.pid)
+        } else {
+            (Utc::now() - self.updated_at).num_seconds() < REMOTE_FRESH_SECS
+        }
+    }
+
+    /// Fold one run event into the status
+    pub fn apply(&mut self, event: &RunEvent) {
+        self.updated_at = event.timestamp;
+        match event.event.as_str() {
+            "started" => {
+                self.command = event.detail.clone();
+                self.started_at = event.timestamp;
+            }
+            "phase" => {
+                if let Some(sprint) = event.sprint {
+                    self.sprints.insert(sprint, event.detail.clone());
+                }
+            }
+            "network_lost" => {
+                self.waiting_for_network.get_or_insert(event.timestamp);
+            }
+            "network_restored" | "network_gave_up" => self.waiting_for_network = None,
+            "sprint_done" => {
+                if let Some(sprint) = event.sprint {
+                    self.sprints.insert(sprint, "Done".to_string());
+                }
+            }
+            "agent" => {
+                self.tokens += event.tokens.unwrap_or(0);
+                if let Some(i) = self
+                    .agents
+                    .iter()
+                    .position(|a| Some(&a.agent) == event.agent.as_ref() && a.sprint == event.sprint)
+                {
+                    self.agents.remove(i);
+                }
+            }
+            _ => {}
+        }
+
+        let mut last = event.event.clone();
+        if let Some(ref agent) = event.agent {
+            last.push_str(&format!(" {}", agent));
+        }
+        if !event.detail.is_empty() {
+            last.push_str(&format!(": {}", event.detail));
+        }
+        self.last_event = Some(last);
+    }
+
+    pub fn agent_started(&mut self, agent: &str, sprint: Option<u32>) {
+        let now = Utc::now();
+        self.updated_at = now;
+        self.agents.push(ActiveAgent { agent: agent.to_string(), sprint, started_at: now });
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        // Write then rename, so readers never see half a file
+        let tmp = format!("{}.tmp", RUNTIME_STATUS_PATH);
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(tmp, RUNTIME_STATUS_PATH)
+    }
+}
+
+/// This process's view of the run; parallel sprints share it
+static LIVE: Mutex<Option<RuntimeStatus>> = Mutex::new(None);
+
+fn update(change: impl FnOnce(&mut RuntimeStatus)) {
+    let Some(run_id) = crate::current_run_id() else {
+        return;
+    };
+    if !Path::new(".autoflow").exists() {
+        return;
+    }
+    let Ok(mut live) = LIVE.lock() else {
+        return;
+    };
+    let status = live.get_or_insert_with(|| RuntimeStatus {
+        run_id: run_id.clone(),
+        pid: std::process::id(),
+        host: hostname(),
+        started_at: Utc::now(),
+        ..Default::default()
+    });
+    if status.run_id != run_id {
+        *status = RuntimeStatus {
+            run_id,
+            pid: std::process::id(),
+            host: hostname(),
+            started_at: Utc::now(),
+            ..Default::default()
+        };
+    }
+    change(status);
+    if let Err(e) = status.save() {
+        tracing::debug!("Failed to write runtime status: {}", e);
+    }
+}
+
+/// Fold a just-recorded run event into the live status file
+pub(crate) fn update_runtime_status(event: &RunEvent) {
+    if event.event == "finished" {
+        clear_runtime_status();
+        return;
+    }
+    update(|status| status.apply(event));
+}
+
+/// Note that an agent has just been launched
+pub fn record_agent_started(agent: &str, sprint: Option<u32>) {
+    update(|status| status.agent_started(agent, sprint));
+}
+
+/// Remove the live status at the end of a run
+pub fn clear_runtime_status() {
+    if let Ok(mut live) = LIVE.lock() {
+        *live = None;
+    }
+    let _ = fs::remove_file(RUNTIME_STATUS_PATH);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(name: &str, sprint: Option<u32>, detail: &str) -> RunEvent {
+        RunEvent {
+            run_id: "01RUN".to_string(),
+            timestamp: Utc::now(),
+            event: name.to_string(),
+            sprint,
+            detail: detail.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_apply_tracks_phases_and_agents() {
+        let mut status = RuntimeStatus { pid: std::process::id(), host: hostname(), ..Default::default() };
+        status.apply(&event("started", None, "start"));
+        status.apply(&event("phase", Some(2), "WriteCode"));
+        status.agent_started("code-implementer", Some(2));
+        status.agent_started("code-implementer", Some(3));
+        assert_eq!(status.agents.len(), 2);
+
+        let mut done = event("agent", Some(2), "ok");
+        done.agent = Some("code-implementer".to_string());
+        done.tokens = Some(900);
+        status.apply(&done);
+
+        assert_eq!(status.command, "start");
+        assert_eq!(status.sprints[&2], "WriteCode");
+        assert_eq!(status.agents.len(), 1);
+        assert_eq!(status.agents[0].sprint, Some(3));
+        assert_eq!(status.tokens, 900);
+        assert_eq!(status.last_event.as_deref(), Some("agent code-implementer: ok"));
+
+        status.apply(&event("network_lost", Some(2), "code-implementer"));
+        assert!(status.waiting_for_network.is_some());
+        status.apply(&event("network_restored", Some(2), "after 40s"));
+        assert!(status.waiting_for_network.is_none());
+
+        assert!(status.is_fresh());
+        // A process that has exited
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        child.wait().unwrap();
+        status.pid = child.id();
+        assert!(!status.is_fresh());
+    }
+}
@@ -44,6 +44,57 @@ pub fn format_duration(seconds: u64) -> String {
     }
 }
 
+/// Text fit for logs, reports and JSON: ANSI escapes and control characters removed
+///
+/// CSI (colours, cursor movement) and OSC (titles, hyperlinks) sequences are
+/// dropped. A carriage return that isn't part of `\r\n` rewinds the line the
+/// way a terminal would, so progress bars leave only their final state. Tabs
+/// and newlines are kept.
+pub fn sanitize_output(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    // Start of the current line in `out`, where a bare \r rewinds to
+    let mut line_start = 0;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => match chars.next() {
+                // CSI: parameters and intermediates, then one final byte in @..~
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: up to BEL or ST (ESC \)
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\u{7}' {
+                            break;
+                        }
+                        if c == '\u{1b}' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                // Two-character escapes (ESC 7, ESC =, ...)
+                _ => {}
+            },
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' => out.truncate(line_start),
+            '\n' => {
+                out.push('\n');
+                line_start = out.len();
+            }
+            '\t' => out.push('\t'),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,6 +124,18 @@ mod tests {
         assert_eq!(pluralize("sprint", 5), "sprints");
     }
 
+    #[test]
+    fn test_sanitize_output() {
+        assert_eq!(sanitize_output("\u{1b}[31mFAILED\u{1b}[0m test_total"), "FAILED test_total");
+        assert_eq!(sanitize_output("line one\r\nline two\r\n"), "line one\nline two\n");
+        assert_eq!(sanitize_output("Progress 10%\rProgress 50%\rProgress 100%\ndone"), "Progress 100%\ndone");
+        assert_eq!(
+            sanitize_output("see \u{1b}]8;;https://x.dev\u{7}docs\u{1b}]8;;\u{1b}\\ now\u{8}\u{0}"),
+            "see docs now"
+        );
+        assert_eq!(sanitize_output("\tindented ✓"), "\tindented ✓");
+    }
+
     #[test]
     fn test_format_duration() {
         assert_eq!(format_duration(45), "45s");
@@ -0,0 +1,246 @@
+/// Task runners behind `autoflow env`
+///
+/// Most projects already say how to bring their stack up: `just dev`,
+/// `make up`, `npm run start`. Each env action maps to the first of these that
+/// has a matching target, unless `[env]` names a command, and falls back to
+/// docker compose.
+use crate::COMPOSE_FILES;
+use autoflow_data::EnvConfig;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Node lockfiles, each with the package manager that runs the project's scripts
+const NODE_RUNNERS: &[(&str, &str)] = &[
+    ("pnpm-lock.yaml", "pnpm"),
+    ("yarn.lock", "yarn"),
+    ("bun.lockb", "bun"),
+    ("package-lock.json", "npm"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvAction {
+    Start,
+    Stop,
+    Restart,
+    Logs,
+    Health,
+}
+
+impl EnvAction {
+    pub const ALL: [EnvAction; 5] = [Self::Start, Self::Stop, Self::Restart, Self::Logs, Self::Health];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Stop => "stop",
+            Self::Restart => "restart",
+            Self::Logs => "logs",
+            Self::Health => "health",
+        }
+    }
+
+    /// Target names that mean this action, most specific first
+    pub fn targets(self) -> &'static [&'static str] {
+        match self {
+            Self::Start => &["dev", "up", "start", "serve"],
+            Self::Stop => &["down", "stop"],
+            Self::Restart => &["restart"],
+            Self::Logs => &["logs"],
+            Self::Health => &["health", "healthcheck", "status"],
+        }
+    }
+
+    fn configured(self, config: &EnvConfig) -> Option<&String> {
+        match self {
+            Self::Start => config.start.as_ref(),
+            Self::Stop => config.stop.as_ref(),
+            Self::Restart => config.restart.as_ref(),
+            Self::Logs => config.logs.as_ref(),
+            Self::Health => config.health.as_ref(),
+        }
+    }
+
+    fn compose_args(self) -> &'static str {
+        match self {
+            Self::Start => "up -d",
+            Self::Stop => "down",
+            Self::Restart => "restart",
+            Self::Logs => "logs",
+            Self::Health => "ps",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskRunner {
+    Just,
+    Make,
+    /// package.json scripts, run with this package manager
+    Node(&'static str),
+}
+
+impl TaskRunner {
+    pub fn command(self, target: &str) -> String {
+        match self {
+            Self::Just => format!("just {}", target),
+            Self::Make => format!("make {}", target),
+            Self::Node(manager) => format!("{} run {}", manager, target),
+        }
+    }
+}
+
+impl fmt::Display for TaskRunner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Just => write!(f, "justfile"),
+            Self::Make => write!(f, "Makefile"),
+            Self::Node(_) => write!(f, "package.json"),
+        }
+    }
+}
+
+/// A task runner found in the checkout, with its targets in file order
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunnerTargets {
+    pub runner: TaskRunner,
+    pub targets: Vec<String>,
+}
+
+/// Task runners the checkout has, in the order their targets are preferred
+pub fn detect_runners(checkout: &Path) -> Vec<RunnerTargets> {
+    let mut runners = Vec::new();
+    if let Some(content) = ["justfile", "Justfile", ".justfile"].iter().find_map(|name| fs::read_to_string(checkout.join(name)).ok()) {
+        runners.push(RunnerTargets { runner: TaskRunner::Just, targets: just_recipes(&content) });
+    }
+    if let Some(content) = ["GNUmakefile", "makefile", "Makefile"].iter().find_map(|name| fs::read_to_string(checkout.join(name)).ok()) {
+        runners.push(RunnerTargets { runner: TaskRunner::Make, targets: make_targets(&content) });
+    }
+    let scripts = fs::read_to_string(checkout.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|package| package.get("scripts")?.as_object().map(|scripts| scripts.keys().cloned().collect::<Vec<_>>()));
+    if let Some(targets) = scripts {
+        let manager = NODE_RUNNERS.iter().find(|(lockfile, _)| checkout.join(lockfile).exists()).map(|(_, manager)| *manager).unwrap_or("npm");
+        runners.push(RunnerTargets { runner: TaskRunner::Node(manager), targets });
+    }
+    runners
+}
+
+/// Recipe names in a justfile
+fn just_recipes(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter(|line| !line.starts_with([' ', '\t', '#', '[']))
+        .filter(|line| !["alias ", "set ", "export ", "import ", "mod "].iter().any(|keyword| line.starts_with(keyword)))
+        .filter_map(|line| {
+            let (head, rest) = line.split_once(':')?;
+            if rest.starts_with('=') || head.contains(":=") {
+                return None;
+            }
+            let name = head.trim_start_matches('@').split_whitespace().next()?;
+            name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_').then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Explicit target names in a Makefile (no special or pattern targets)
+fn make_targets(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter(|line| !line.starts_with([' ', '\t', '#', '.']))
+        .filter_map(|line| {
+            let (head, rest) = line.split_once(':')?;
+            if rest.starts_with('=') || head.contains('=') || head.contains('%') || head.contains('$') {
+                return None;
+            }
+            Some(head.split_whitespace().map(str::to_string).collect::<Vec<_>>())
+        })
+        .flatten()
+        .collect()
+}
+
+/// Where an env action's command came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvSource {
+    /// `[env]` in the config
+    Config,
+    Runner(TaskRunner),
+    Compose,
+}
+
+impl fmt::Display for EnvSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Config => write!(f, "[env] config"),
+            Self::Runner(runner) => write!(f, "{}", runner),
+            Self::Compose => write!(f, "docker compose"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvCommand {
+    pub command: String,
+    pub source: EnvSource,
+}
+
+/// The command `autoflow env <action>` runs in `checkout`; None when nothing provides it
+pub fn resolve_env_command(checkout: &Path, config: &EnvConfig, action: EnvAction) -> Option<EnvCommand> {
+    if let Some(command) = action.configured(config) {
+        return Some(EnvCommand { command: command.clone(), source: EnvSource::Config });
+    }
+    if config.detect_runners {
+        for found in detect_runners(checkout) {
+            if let Some(target) = action.targets().iter().find(|target| found.targets.iter().any(|t| t == *target)) {
+                return Some(EnvCommand { command: found.runner.command(target), source: EnvSource::Runner(found.runner) });
+            }
+        }
+    }
+    COMPOSE_FILES.iter().any(|name| checkout.join(name).exists()).then(|| EnvCommand {
+        command: format!("docker-compose {}", action.compose_args()),
+        source: EnvSource::Compose,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_actions_map_to_runner_targets() {
+        let checkout = std::env::temp_dir().join(format!("autoflow-task-runner-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&checkout);
+        fs::create_dir_all(&checkout).unwrap();
+        fs::write(
+            checkout.join("justfile"),
+            "set dotenv-load\nport := \"3000\"\nalias d := dev\n\n# Run the stack\n@dev *args:\n    docker compose up -d\n\nlogs service='api': build\n    docker compose logs {{service}}\n",
+        )
+        .unwrap();
+        fs::write(checkout.join("Makefile"), ".PHONY: up down\nCC := gcc\nup:\n\tdocker compose up -d\ndown stop:\n\tdocker compose down\n%.o: %.c\n").unwrap();
+        fs::write(checkout.join("package.json"), r#"{"scripts": {"start": "node server.js", "health": "curl -f localhost:3000"}}"#).unwrap();
+        fs::write(checkout.join("yarn.lock"), "").unwrap();
+
+        let runners = detect_runners(&checkout);
+        assert_eq!(runners[0].targets, ["dev", "logs"]);
+        assert_eq!(runners[1].targets, ["up", "down", "stop"]);
+        assert_eq!(runners[2].runner, TaskRunner::Node("yarn"));
+
+        let config = EnvConfig::default();
+        let resolve = |action| resolve_env_command(&checkout, &config, action).map(|c| c.command);
+        assert_eq!(resolve(EnvAction::Start).as_deref(), Some("just dev"));
+        assert_eq!(resolve(EnvAction::Stop).as_deref(), Some("make down"));
+        assert_eq!(resolve(EnvAction::Logs).as_deref(), Some("just logs"));
+        assert_eq!(resolve(EnvAction::Health).as_deref(), Some("yarn run health"));
+        assert_eq!(resolve(EnvAction::Restart), None);
+
+        // Compose fills the gaps, and config overrides everything
+        fs::write(checkout.join("compose.yaml"), "services: {}\n").unwrap();
+        assert_eq!(resolve(EnvAction::Restart).as_deref(), Some("docker-compose restart"));
+        let config = EnvConfig { start: Some("./bin/up".to_string()), detect_runners: false, ..Default::default() };
+        let start = resolve_env_command(&checkout, &config, EnvAction::Start).unwrap();
+        assert_eq!((start.command.as_str(), start.source), ("./bin/up", EnvSource::Config));
+        assert_eq!(resolve_env_command(&checkout, &config, EnvAction::Stop).unwrap().source, EnvSource::Compose);
+        let _ = fs::remove_dir_all(&checkout);
+    }
+}
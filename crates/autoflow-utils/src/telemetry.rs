@@ -0,0 +1,271 @@
+//! Opt-in anonymous usage telemetry
+//!
+//! Disabled unless the user runs `autoflow telemetry on`. Only command names,
+//! sprint outcomes and error classes are recorded - never code, prompts,
+//! file paths or project names. See docs/TELEMETRY.md for the payload.
+//!
+//! Events are appended to a local queue and sent in one batch when the
+//! command finishes, so telemetry never slows down or breaks a run.
+use anyhow::{Context, Result};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const SETTINGS_FILE: &str = "telemetry.json";
+const QUEUE_FILE: &str = "telemetry-queue.jsonl";
+
+/// Queued events beyond this are dropped rather than growing without bound
+const MAX_QUEUED_EVENTS: usize = 500;
+
+/// Persisted telemetry preferences (~/.autoflow/telemetry.json)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TelemetrySettings {
+    pub enabled: bool,
+
+    /// Random per-install identifier, not derived from any user data
+    #[serde(default)]
+    pub install_id: String,
+
+    /// Collector URL events are POSTed to
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+impl TelemetrySettings {
+    pub fn load() -> Self {
+        settings_path()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = settings_path().context("Could not find home directory")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Endpoint after applying the AUTOFLOW_TELEMETRY_ENDPOINT override
+    pub fn effective_endpoint(&self) -> Option<String> {
+        std::env::var("AUTOFLOW_TELEMETRY_ENDPOINT")
+            .ok()
+            .filter(|e| !e.is_empty())
+            .or_else(|| self.endpoint.clone())
+    }
+}
+
+/// What happened - the only data that leaves the machine besides the envelope
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TelemetryEvent {
+    /// A CLI command was run, e.g. "start" or "worktree create"
+    Command { name: String },
+    /// A sprint finished or stopped
+    SprintOutcome { workflow_type: String, status: String },
+    /// A command failed; `class` is an error variant name, never the message
+    Error { class: String },
+}
+
+/// Envelope sent for every event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryPayload {
+    pub install_id: String,
+    pub version: String,
+    pub os: String,
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub event: TelemetryEvent,
+}
+
+/// Whether telemetry is on (AUTOFLOW_TELEMETRY=0 always wins)
+pub fn telemetry_enabled() -> bool {
+    // Read-only runs write nothing, the event queue included
+    if autoflow_data::is_read_only() {
+        return false;
+    }
+    if let Ok(val) = std::env::var("AUTOFLOW_TELEMETRY") {
+        if val == "0" || val.eq_ignore_ascii_case("false") || val.eq_ignore_ascii_case("off") {
+            return false;
+        }
+    }
+    TelemetrySettings::load().enabled
+}
+
+/// Turn telemetry on, generating an install id on first use
+pub fn enable_telemetry(endpoint: Option<String>) -> Result<TelemetrySettings> {
+    let mut settings = TelemetrySettings::load();
+    settings.enabled = true;
+    if settings.install_id.is_empty() {
+        settings.install_id = generate_install_id();
+    }
+    if endpoint.is_some() {
+        settings.endpoint = endpoint;
+    }
+    settings.save()?;
+    Ok(settings)
+}
+
+/// Turn telemetry off and discard anything still queued
+pub fn disable_telemetry() -> Result<TelemetrySettings> {
+    let mut settings = TelemetrySettings::load();
+    settings.enabled = false;
+    settings.save()?;
+    if let Some(queue) = queue_path() {
+        let _ = fs::remove_file(queue);
+    }
+    Ok(settings)
+}
+
+/// Record an event if telemetry is enabled; failures are silently ignored
+pub fn record_event(event: TelemetryEvent) {
+    if !telemetry_enabled() {
+        return;
+    }
+    let _ = append_to_queue(&event);
+}
+
+/// Number of events waiting to be sent
+pub fn queued_event_count() -> usize {
+    queue_path()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .map(|c| c.lines().filter(|l| !l.trim().is_empty()).count())
+        .unwrap_or(0)
+}
+
+/// Send queued events to the configured endpoint
+///
+/// Keeps the queue if there is no endpoint or the request fails, so events
+/// are retried on the next run.
+pub async fn flush_telemetry() -> Result<usize> {
+    if !telemetry_enabled() {
+        return Ok(0);
+    }
+    let settings = TelemetrySettings::load();
+    let endpoint = match settings.effective_endpoint() {
+        Some(e) => e,
+        None => return Ok(0),
+    };
+
+    let queue = queue_path().context("Could not find home directory")?;
+    let content = match fs::read_to_string(&queue) {
+        Ok(c) => c,
+        Err(_) => return Ok(0),
+    };
+    let events: Vec<TelemetryPayload> = content
+        .lines()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect();
+    if events.is_empty() {
+        let _ = fs::remove_file(&queue);
+        return Ok(0);
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(3))
+        .build()?;
+    let response = client
+        .post(&endpoint)
+        .json(&serde_json::json!({ "events": events }))
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        anyhow::bail!("Telemetry endpoint returned {}", response.status());
+    }
+
+    fs::remove_file(&queue)?;
+    Ok(events.len())
+}
+
+/// Map an error to a coarse class name that carries no user data
+pub fn error_class(error: &anyhow::Error) -> String {
+    if let Some(e) = error.downcast_ref::<autoflow_data::AutoFlowError>() {
+        let debug = format!("{:?}", e);
+        return debug
+            .split(['(', ' ', '{'])
+            .next()
+            .unwrap_or("AutoFlowError")
+            .to_string();
+    }
+    if error.downcast_ref::<std::io::Error>().is_some() {
+        return "IoError".to_string();
+    }
+    "Other".to_string()
+}
+
+fn append_to_queue(event: &TelemetryEvent) -> Result<()> {
+    let path = queue_path().context("Could not find home directory")?;
+    if queued_event_count() >= MAX_QUEUED_EVENTS {
+        return Ok(());
+    }
+
+    let settings = TelemetrySettings::load();
+    let payload = TelemetryPayload {
+        install_id: settings.install_id,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        event: event.clone(),
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(&payload)?)?;
+    Ok(())
+}
+
+fn generate_install_id() -> String {
+    let mut bytes = [0u8; 16];
+    SystemRandom::new().fill(&mut bytes).expect("system random generator failed");
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn settings_path() -> Option<PathBuf> {
+    crate::autoflow_dir().map(|d| d.join(SETTINGS_FILE))
+}
+
+fn queue_path() -> Option<PathBuf> {
+    crate::autoflow_dir().map(|d| d.join(QUEUE_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_shape() {
+        let payload = TelemetryPayload {
+            install_id: "abc".to_string(),
+            version: "0.1.0".to_string(),
+            os: "linux".to_string(),
+            timestamp: "2025-01-01T00:00:00Z".to_string(),
+            event: TelemetryEvent::Command { name: "start".to_string() },
+        };
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["kind"], "command");
+        assert_eq!(json["name"], "start");
+        assert_eq!(json["install_id"], "abc");
+    }
+
+    #[test]
+    fn test_install_id_is_random() {
+        let id = generate_install_id();
+        assert_eq!(id.len(), 32);
+        assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(id, generate_install_id());
+    }
+
+    #[test]
+    fn test_error_class_uses_variant_name() {
+        let err = anyhow::Error::new(autoflow_data::AutoFlowError::SprintBlocked(3, "x".into()));
+        assert_eq!(error_class(&err), "SprintBlocked");
+        assert_eq!(error_class(&anyhow::anyhow!("secret path /home/me")), "Other");
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use colored::*;
 use std::collections::HashMap;
 use std::fs;
@@ -33,7 +33,7 @@ impl UpdateInfo {
 
 /// Check if updates are available by comparing template files with installed files
 pub fn check_for_updates() -> Result<UpdateInfo> {
-    let home = dirs::home_dir().context("Could not find home directory")?;
+    let home = crate::require_home()?;
     let template_agents_dir = home.join(".autoflow/agents");
     let template_skills_dir = home.join(".autoflow/skills");
     let installed_agents_dir = home.join(".claude/agents");
@@ -55,7 +55,7 @@ pub fn check_for_updates() -> Result<UpdateInfo> {
             let installed_name = format!("{}.agent", name);
             if let Some(installed_path) = installed_agents.get(&installed_name) {
                 // Check if template is newer
-                if is_newer(&template_path, installed_path)? {
+                if is_newer(template_path, installed_path)? {
                     info.updated_agents.push(installed_name.clone());
                 }
             } else {
@@ -73,7 +73,7 @@ pub fn check_for_updates() -> Result<UpdateInfo> {
         for (name, template_path) in &template_skills {
             if let Some(installed_path) = installed_skills.get(name) {
                 // Check if template is newer
-                if is_skill_newer(&template_path, installed_path)? {
+                if is_skill_newer(template_path, installed_path)? {
                     info.updated_skills.push(name.clone());
                 }
             } else {
@@ -88,7 +88,12 @@ pub fn check_for_updates() -> Result<UpdateInfo> {
 
 /// Check if we should run update check based on last check time and config
 pub fn should_check_for_updates() -> Result<bool> {
-    let home = dirs::home_dir().context("Could not find home directory")?;
+    // Turned off by AUTOFLOW_AUTO_UPDATE or the project's config.yml
+    if autoflow_data::ProjectConfig::current().update_check() == Some(false) {
+        return Ok(false);
+    }
+
+    let home = crate::require_home()?;
     let check_file = home.join(".autoflow/.last_update_check");
 
     // If file doesn't exist, we should check
@@ -107,10 +112,12 @@ pub fn should_check_for_updates() -> Result<bool> {
 
 /// Update the last check timestamp
 pub fn update_check_timestamp() -> Result<()> {
-    let home = dirs::home_dir().context("Could not find home directory")?;
+    let home = crate::require_home()?;
     let check_file = home.join(".autoflow/.last_update_check");
 
-    fs::create_dir_all(check_file.parent().unwrap())?;
+    if let Some(parent) = check_file.parent() {
+        fs::create_dir_all(parent)?;
+    }
     fs::write(&check_file, "")?;
 
     Ok(())
@@ -193,7 +200,7 @@ pub fn prompt_and_update(info: &UpdateInfo) -> Result<bool> {
 
 /// Run the update script
 fn run_update_script() -> Result<()> {
-    let home = dirs::home_dir().context("Could not find home directory")?;
+    let home = crate::require_home()?;
     let update_script = home.join(".autoflow/update.sh");
 
     // Check if script exists
@@ -241,7 +248,7 @@ fn list_markdown_files(dir: &Path) -> Result<HashMap<String, PathBuf>> {
         let entry = entry?;
         let path = entry.path();
 
-        if path.is_file() && path.extension().map_or(false, |e| e == "md") {
+        if path.is_file() && path.extension().is_some_and(|e| e == "md") {
             if let Some(stem) = path.file_stem() {
                 if let Some(name) = stem.to_str() {
                     files.insert(name.to_string(), path);
@@ -0,0 +1,337 @@
+//! Lifecycle webhooks: a versioned, signed contract for platforms embedding AutoFlow
+//!
+//! With `[notify.webhook]` configured, run and sprint lifecycle events are
+//! POSTed as JSON matching `schemas/webhook.v1.schema.json` (also installed to
+//! `~/.autoflow/schemas/`). Each body is signed with HMAC-SHA256 using the
+//! secret in `secret_env`, and failed deliveries are retried with exponential
+//! backoff. Sprint events go out in order from a background thread;
+//! `run.finished` waits for them and is sent last, carrying the run summary.
+use crate::{new_run_id, RunEvent, RunSummary};
+use anyhow::{bail, Context, Result};
+use autoflow_data::{Config, WebhookConfig};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{mpsc, Mutex, OnceLock};
+use std::time::Duration;
+
+/// Version of the payload contract; fields are only added within a version
+pub const WEBHOOK_SCHEMA_VERSION: u32 = 1;
+
+/// JSON Schema of the payload
+pub const WEBHOOK_SCHEMA: &str = include_str!("../../../schemas/webhook.v1.schema.json");
+
+/// Events a webhook can subscribe to
+pub const WEBHOOK_EVENTS: [&str; 5] = ["run.started", "run.finished", "sprint.phase", "sprint.done", "sprint.blocked"];
+
+/// Header carrying `sha256=<hex HMAC of the body>`
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-AutoFlow-Signature";
+
+/// Longest wait between delivery attempts
+const MAX_BACKOFF_SECS: u64 = 60;
+
+/// Body of one delivery
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookPayload {
+    pub schema_version: u32,
+    /// One of `WEBHOOK_EVENTS`
+    pub event: String,
+    /// Unchanged across retries, so receivers can drop duplicates
+    pub delivery_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub run_id: String,
+    pub project: String,
+    /// Set on `run.started`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Set on `sprint.*` events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sprint: Option<WebhookSprint>,
+    /// Set on `run.finished`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run: Option<RunSummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookSprint {
+    pub id: u32,
+    /// Phase entered (`sprint.phase`), or `Done` / `Blocked`
+    pub status: String,
+    /// Sprint goal, or why it was blocked
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub detail: String,
+}
+
+impl WebhookPayload {
+    fn new(event: &str, run_id: &str, timestamp: DateTime<Utc>, project: &str) -> Self {
+        Self {
+            schema_version: WEBHOOK_SCHEMA_VERSION,
+            event: event.to_string(),
+            delivery_id: new_run_id(),
+            timestamp,
+            run_id: run_id.to_string(),
+            project: project.to_string(),
+            command: None,
+            sprint: None,
+            run: None,
+        }
+    }
+
+    /// Payload for a recorded run event; None for events outside the contract
+    ///
+    /// `finished` is left to `run_finished`, which has the summary.
+    pub fn from_run_event(event: &RunEvent, project: &str) -> Option<Self> {
+        let (name, status) = match event.event.as_str() {
+            "started" => ("run.started", None),
+            "phase" => ("sprint.phase", Some(event.detail.clone())),
+            "sprint_done" => ("sprint.done", Some("Done".to_string())),
+            "sprint_blocked" => ("sprint.blocked", Some("Blocked".to_string())),
+            _ => return None,
+        };
+        let mut payload = Self::new(name, &event.run_id, event.timestamp, project);
+        match status {
+            None => payload.command = Some(event.detail.clone()),
+            Some(status) => {
+                let detail = if name == "sprint.phase" { String::new() } else { event.detail.clone() };
+                payload.sprint = Some(WebhookSprint { id: event.sprint?, status, detail });
+            }
+        }
+        Some(payload)
+    }
+
+    /// `run.finished` payload carrying the run's summary
+    pub fn run_finished(summary: &RunSummary, project: &str) -> Self {
+        let mut payload = Self::new("run.finished", &summary.run_id, summary.finished_at, project);
+        payload.run = Some(summary.clone());
+        payload
+    }
+}
+
+/// `sha256=<hex>` HMAC-SHA256 of `body`, as sent in `X-AutoFlow-Signature`
+///
+/// Receivers recompute it over the raw request body with the shared secret
+/// and compare in constant time.
+pub fn webhook_signature(secret: &[u8], body: &[u8]) -> String {
+    let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, secret);
+    let tag = ring::hmac::sign(&key, body);
+    let hex: String = tag.as_ref().iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+/// The configured webhook, if it subscribes to `event`
+pub fn webhook_for(event: &str) -> Option<WebhookConfig> {
+    Config::global()
+        .ok()
+        .and_then(|c| c.notify.webhook)
+        .filter(|w| w.events.is_empty() || w.events.iter().any(|e| e == event))
+}
+
+/// Post `payload`, retrying transient failures with exponential backoff
+///
+/// Timeouts, connection errors, 408, 429 and 5xx responses are retried up to
+/// `max_attempts` in total; any other non-2xx response fails at once.
+pub fn deliver_webhook(config: &WebhookConfig, payload: &WebhookPayload) -> Result<()> {
+    let secret = std::env::var(&config.secret_env)
+        .ok()
+        .filter(|s| !s.is_empty())
+        .with_context(|| format!("Webhook secret not set (export {})", config.secret_env))?;
+    let body = serde_json::to_vec(payload)?;
+    let signature = webhook_signature(secret.as_bytes(), &body);
+
+    let attempts = config.max_attempts.max(1);
+    let mut last_error = String::new();
+    for attempt in 1..=attempts {
+        if attempt > 1 {
+            std::thread::sleep(Duration::from_secs((1u64 << (attempt - 2).min(6)).min(MAX_BACKOFF_SECS)));
+        }
+        match post(config, payload, &body, &signature) {
+            Ok(status) if (200..300).contains(&status) => return Ok(()),
+            Ok(status) if status == 408 || status == 429 || status >= 500 => {
+                last_error = format!("HTTP {}", status);
+            }
+            Ok(status) => bail!("{} rejected {} with HTTP {}", config.url, payload.event, status),
+            Err(e) => last_error = e.to_string(),
+        }
+        tracing::debug!("Webhook {} attempt {}/{} failed: {}", payload.event, attempt, attempts, last_error);
+    }
+    bail!("{} not delivered after {} attempts: {}", payload.event, attempts, last_error)
+}
+
+/// One POST; returns the HTTP status
+///
+/// The request runs on a thread of its own: reqwest's blocking client can't
+/// be used from a thread that drives an async runtime, and `finish_run` may
+/// be called from one.
+fn post(config: &WebhookConfig, payload: &WebhookPayload, body: &[u8], signature: &str) -> Result<u16> {
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                let client = reqwest::blocking::Client::builder()
+                    .timeout(Duration::from_secs(config.timeout_secs))
+                    .user_agent(format!("autoflow/{}", env!("CARGO_PKG_VERSION")))
+                    .build()
+                    .context("Failed to build HTTP client")?;
+                let response = client
+                    .post(&config.url)
+                    .header("Content-Type", "application/json")
+                    .header("X-AutoFlow-Event", &payload.event)
+                    .header("X-AutoFlow-Delivery", &payload.delivery_id)
+                    .header(WEBHOOK_SIGNATURE_HEADER, signature)
+                    .body(body.to_vec())
+                    .send()?;
+                Ok(response.status().as_u16())
+            })
+            .join()
+            .map_err(|_| anyhow::anyhow!("Webhook request panicked"))?
+    })
+}
+
+enum Job {
+    Deliver(WebhookConfig, Box<WebhookPayload>),
+    Flush(mpsc::Sender<()>),
+}
+
+/// Background sender; one thread keeps deliveries in event order
+fn queue() -> &'static Mutex<mpsc::Sender<Job>> {
+    static QUEUE: OnceLock<Mutex<mpsc::Sender<Job>>> = OnceLock::new();
+    QUEUE.get_or_init(|| {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for job in rx {
+                match job {
+                    Job::Deliver(config, payload) => {
+                        if let Err(e) = deliver_webhook(&config, &payload) {
+                            tracing::warn!("Webhook delivery failed: {}", e);
+                        }
+                    }
+                    Job::Flush(done) => {
+                        let _ = done.send(());
+                    }
+                }
+            }
+        });
+        Mutex::new(tx)
+    })
+}
+
+/// Queue the webhook for a recorded run event, if one is configured for it
+pub fn dispatch_run_event(event: &RunEvent) {
+    let Some(payload) = WebhookPayload::from_run_event(event, &project_name()) else {
+        return;
+    };
+    let Some(config) = webhook_for(&payload.event) else {
+        return;
+    };
+    if let Ok(queue) = queue().lock() {
+        let _ = queue.send(Job::Deliver(config, Box::new(payload)));
+    }
+}
+
+/// Wait until every queued delivery has succeeded or given up
+pub fn flush_webhooks() {
+    let (tx, rx) = mpsc::channel();
+    let sent = queue().lock().map(|queue| queue.send(Job::Flush(tx)).is_ok()).unwrap_or(false);
+    if sent {
+        let _ = rx.recv();
+    }
+}
+
+/// Project directory name, as reported in payloads
+pub(crate) fn project_name() -> String {
+    std::env::current_dir()
+        .ok()
+        .and_then(|d| d.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook_payload() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            webhook_signature(b"Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+
+        let event = |name: &str, sprint: Option<u32>, detail: &str| RunEvent {
+            run_id: "01J9ZRUN".to_string(),
+            event: name.to_string(),
+            sprint,
+            detail: detail.to_string(),
+            ..Default::default()
+        };
+
+        let started = WebhookPayload::from_run_event(&event("started", None, "start"), "shop").unwrap();
+        assert_eq!(started.event, "run.started");
+        assert_eq!(started.command.as_deref(), Some("start"));
+        assert_eq!(started.delivery_id.len(), 26);
+
+        let blocked =
+            WebhookPayload::from_run_event(&event("sprint_blocked", Some(4), "Fix budget exhausted"), "shop").unwrap();
+        let json = serde_json::to_value(&blocked).unwrap();
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["event"], "sprint.blocked");
+        assert_eq!(json["run_id"], "01J9ZRUN");
+        assert_eq!(json["project"], "shop");
+        assert_eq!(json["sprint"], serde_json::json!({"id": 4, "status": "Blocked", "detail": "Fix budget exhausted"}));
+        assert!(json.get("run").is_none() && json.get("command").is_none());
+
+        let phase = WebhookPayload::from_run_event(&event("phase", Some(4), "CodeReview"), "shop").unwrap();
+        assert_eq!(phase.sprint.unwrap().status, "CodeReview");
+        assert!(WebhookPayload::from_run_event(&event("agent", Some(4), "ok"), "shop").is_none());
+        assert!(WebhookPayload::from_run_event(&event("finished", None, "completed"), "shop").is_none());
+
+        // The published schema and the code agree on the contract
+        let schema: serde_json::Value = serde_json::from_str(WEBHOOK_SCHEMA).unwrap();
+        assert_eq!(schema["properties"]["schema_version"]["const"], WEBHOOK_SCHEMA_VERSION);
+        assert_eq!(schema["properties"]["event"]["enum"], serde_json::json!(WEBHOOK_EVENTS));
+    }
+
+    #[test]
+    fn test_deliver_webhook_signs_and_retries() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for status in ["503 Service Unavailable", "204 No Content"] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                while !String::from_utf8_lossy(&request).contains(r#""event":"run.finished""#) {
+                    let n = stream.read(&mut buf).unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                write!(stream, "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status).unwrap();
+                requests.push(String::from_utf8(request).unwrap());
+            }
+            requests
+        });
+
+        std::env::set_var("AUTOFLOW_TEST_WEBHOOK_SECRET", "s3cret");
+        let config = WebhookConfig {
+            url,
+            secret_env: "AUTOFLOW_TEST_WEBHOOK_SECRET".to_string(),
+            events: Vec::new(),
+            max_attempts: 2,
+            timeout_secs: 10,
+        };
+        let payload = WebhookPayload::new("run.finished", "01J9ZRUN", Utc::now(), "shop");
+        deliver_webhook(&config, &payload).unwrap();
+
+        let requests = server.join().unwrap();
+        let body = serde_json::to_vec(&payload).unwrap();
+        let signature = format!("{}: {}", WEBHOOK_SIGNATURE_HEADER, webhook_signature(b"s3cret", &body)).to_ascii_lowercase();
+        assert_eq!(requests.len(), 2);
+        for request in requests.iter().map(|r| r.to_ascii_lowercase()) {
+            assert!(request.contains(&signature), "{}", request);
+            assert!(request.contains(&format!("x-autoflow-delivery: {}", payload.delivery_id.to_ascii_lowercase())));
+        }
+    }
+}